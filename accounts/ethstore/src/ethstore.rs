@@ -24,10 +24,10 @@ use random::Random;
 use crypto::publickey::{Signature, Address, Message, Secret, Public, KeyPair, ExtendedKeyPair};
 use ethkey::Password;
 use accounts_dir::{KeyDirectory, VaultKeyDirectory, VaultKey, SetKeyError};
-use account::SafeAccount;
+use account::{SafeAccount, Kdf, Cipher, Version};
 use presale::PresaleWallet;
 use json::{self, Uuid, OpaqueKeyFile};
-use {import, Error, SimpleSecretStore, SecretStore, SecretVaultRef, StoreAccountRef, Derivation, OpaqueSecret};
+use {import, Error, SimpleSecretStore, SecretStore, SecretVaultRef, StoreAccountRef, Derivation, OpaqueSecret, KeyFormat};
 
 /// Accounts store.
 pub struct EthStore {
@@ -211,6 +211,26 @@ impl SecretStore for EthStore {
 		Ok(account.meta.clone())
 	}
 
+	fn key_format(&self, account: &StoreAccountRef) -> Result<KeyFormat, Error> {
+		let account = self.get(account)?;
+		let version = match account.version {
+			Version::V3 => 3,
+		};
+		let (kdf, iterations) = match account.crypto.kdf {
+			Kdf::Pbkdf2(ref params) => ("pbkdf2", params.c),
+			Kdf::Scrypt(ref params) => ("scrypt", params.n),
+		};
+		let cipher = match account.crypto.cipher {
+			Cipher::Aes128Ctr(_) => "aes-128-ctr",
+		};
+		Ok(KeyFormat {
+			version,
+			kdf: kdf.into(),
+			iterations,
+			cipher: cipher.into(),
+		})
+	}
+
 	fn set_name(&self, account_ref: &StoreAccountRef, name: String) -> Result<(), Error> {
 		let old = self.get(account_ref)?;
 		let mut safe_account = old.clone();
@@ -691,7 +711,8 @@ mod tests {
 
 	use accounts_dir::{KeyDirectory, MemoryDirectory, RootDiskDirectory};
 	use crypto::publickey::{Random, Generator, KeyPair};
-	use secret_store::{SimpleSecretStore, SecretStore, SecretVaultRef, StoreAccountRef, Derivation};
+	use secret_store::{SimpleSecretStore, SecretStore, SecretVaultRef, StoreAccountRef, Derivation, KeyFormat};
+	use crypto::KEY_ITERATIONS;
 	use super::{EthStore, EthMultiStore};
 	use self::tempfile::TempDir;
 	use ethereum_types::H256;
@@ -761,6 +782,25 @@ mod tests {
 		assert_eq!(store.accounts().unwrap().len(), 1);
 	}
 
+	#[test]
+	fn should_report_key_format() {
+		// given
+		let store = store();
+		let keypair = keypair();
+		let passwd = "test".into();
+
+		// when
+		let address = store.insert_account(SecretVaultRef::Root, keypair.secret().clone(), &passwd).unwrap();
+
+		// then
+		assert_eq!(store.key_format(&address).unwrap(), KeyFormat {
+			version: 3,
+			kdf: "pbkdf2".into(),
+			iterations: KEY_ITERATIONS as u32,
+			cipher: "aes-128-ctr".into(),
+		});
+	}
+
 	#[test]
 	fn should_remove_account() {
 		// given
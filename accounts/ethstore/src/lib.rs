@@ -64,7 +64,7 @@ pub use self::json::OpaqueKeyFile as KeyFile;
 pub use self::presale::PresaleWallet;
 pub use self::secret_store::{
 	SecretVaultRef, StoreAccountRef, SimpleSecretStore, SecretStore,
-	Derivation, IndexDerivation,
+	Derivation, IndexDerivation, KeyFormat,
 };
 pub use self::random::random_string;
 pub use self::parity_wordlist::random_phrase;
@@ -54,6 +54,19 @@ impl ::std::borrow::Borrow<Address> for StoreAccountRef {
 	}
 }
 
+/// Key derivation and encryption parameters of a stored key file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct KeyFormat {
+	/// Key file version.
+	pub version: u32,
+	/// Key derivation function (`pbkdf2` or `scrypt`).
+	pub kdf: String,
+	/// KDF work factor: round count for `pbkdf2`, CPU/memory cost `n` for `scrypt`.
+	pub iterations: u32,
+	/// Symmetric cipher used to encrypt the secret.
+	pub cipher: String,
+}
+
 /// Simple Secret Store API
 pub trait SimpleSecretStore: Send + Sync {
 	/// Inserts new accounts to the store (or vault) with given password.
@@ -132,6 +145,8 @@ pub trait SecretStore: SimpleSecretStore {
 	fn name(&self, account: &StoreAccountRef) -> Result<String, Error>;
 	/// Returns account's metadata.
 	fn meta(&self, account: &StoreAccountRef) -> Result<String, Error>;
+	/// Returns key derivation and encryption parameters of account's key file.
+	fn key_format(&self, account: &StoreAccountRef) -> Result<KeyFormat, Error>;
 
 	/// Modifies account metadata.
 	fn set_name(&self, account: &StoreAccountRef, name: String) -> Result<(), Error>;
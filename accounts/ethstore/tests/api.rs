@@ -21,7 +21,7 @@ extern crate parity_crypto;
 
 mod util;
 
-use ethstore::{EthStore, SimpleSecretStore, SecretVaultRef, StoreAccountRef};
+use ethstore::{EthStore, SimpleSecretStore, SecretStore, SecretVaultRef, StoreAccountRef, KeyFormat};
 use parity_crypto::publickey::{Random, Generator, Secret, KeyPair, verify_address};
 use ethstore::accounts_dir::RootDiskDirectory;
 use util::TransientDir;
@@ -126,6 +126,19 @@ fn secret_store_laod_geth_files() {
 	]);
 }
 
+#[test]
+fn secret_store_reports_geth_key_format() {
+	let dir = RootDiskDirectory::at(test_path());
+	let store = EthStore::open(Box::new(dir)).unwrap();
+	let account = StoreAccountRef::root(Address::from_str("3f49624084b67849c7b4e805c5988c21a430f9d9").unwrap());
+	assert_eq!(store.key_format(&account).unwrap(), KeyFormat {
+		version: 3,
+		kdf: "scrypt".into(),
+		iterations: 262144,
+		cipher: "aes-128-ctr".into(),
+	});
+}
+
 #[test]
 fn secret_store_load_pat_files() {
 	let dir = RootDiskDirectory::at(pat_path());
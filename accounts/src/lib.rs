@@ -38,7 +38,7 @@ use ethstore::{
 use log::warn;
 use parking_lot::RwLock;
 
-pub use ethstore::{Derivation, IndexDerivation, KeyFile, Error};
+pub use ethstore::{Derivation, IndexDerivation, KeyFile, KeyFormat, Error};
 
 pub use self::account_data::AccountMeta;
 pub use self::error::SignError;
@@ -72,6 +72,8 @@ pub struct AccountProvider {
 	unlock_keep_secret: bool,
 	/// Disallowed accounts.
 	blacklisted_accounts: Vec<Address>,
+	/// Source of the current time, used for timed unlocks.
+	clock: Box<dyn Fn() -> Instant + Send + Sync>,
 }
 
 fn transient_sstore() -> EthMultiStore {
@@ -102,6 +104,7 @@ impl AccountProvider {
 			transient_sstore: transient_sstore(),
 			unlock_keep_secret: settings.unlock_keep_secret,
 			blacklisted_accounts: settings.blacklisted_accounts,
+			clock: Box::new(Instant::now),
 		}
 	}
 
@@ -115,9 +118,18 @@ impl AccountProvider {
 			transient_sstore: transient_sstore(),
 			unlock_keep_secret: false,
 			blacklisted_accounts: vec![],
+			clock: Box::new(Instant::now),
 		}
 	}
 
+	/// Use the given source of the current time for timed unlocks instead of the system clock.
+	pub fn with_clock<F>(mut self, clock: F) -> Self where
+		F: Fn() -> Instant + Send + Sync + 'static,
+	{
+		self.clock = Box::new(clock);
+		self
+	}
+
 	/// Creates new random account.
 	pub fn new_account(&self, password: &Password) -> Result<Address, Error> {
 		self.new_account_and_public(password).map(|d| d.0)
@@ -234,6 +246,11 @@ impl AccountProvider {
 		})
 	}
 
+	/// Returns key derivation and encryption parameters of the account's key file.
+	pub fn account_key_format(&self, address: Address) -> Result<KeyFormat, Error> {
+		self.sstore.key_format(&self.sstore.account_ref(&address)?)
+	}
+
 	/// Returns account public key.
 	pub fn account_public(&self, address: Address, password: &Password) -> Result<Public, Error> {
 		self.sstore.public(&self.sstore.account_ref(&address)?, password)
@@ -309,7 +326,7 @@ impl AccountProvider {
 			unlocked.remove(account).expect("data exists: so key must exist: qed");
 		}
 		if let Unlock::Timed(ref end) = data.unlock {
-			if Instant::now() > *end {
+			if (self.clock)() > *end {
 				unlocked.remove(account).expect("data exists: so key must exist: qed");
 				return Err(SignError::NotUnlocked);
 			}
@@ -329,15 +346,21 @@ impl AccountProvider {
 
 	/// Unlocks account temporarily with a timeout.
 	pub fn unlock_account_timed(&self, account: Address, password: Password, duration: Duration) -> Result<(), Error> {
-		self.unlock_account(account, password, Unlock::Timed(Instant::now() + duration))
+		let end = (self.clock)().checked_add(duration)
+			.ok_or_else(|| Error::Custom(format!("Unlock duration of {:?} is too long", duration)))?;
+		self.unlock_account(account, password, Unlock::Timed(end))
 	}
 
-	/// Checks if given account is unlocked
+	/// Checks if given account is unlocked. Expired timed unlocks are considered locked.
 	pub fn is_unlocked(&self, address: &Address) -> bool {
 		let unlocked = self.unlocked.read();
 		let unlocked_secrets = self.unlocked_secrets.read();
+		let is_valid = |data: &AccountData| match data.unlock {
+			Unlock::Timed(ref end) => (self.clock)() <= *end,
+			_ => true,
+		};
 		self.sstore.account_ref(address)
-			.map(|r| unlocked.get(&r).is_some() || unlocked_secrets.get(&r).is_some())
+			.map(|r| unlocked.get(&r).map_or(false, is_valid) || unlocked_secrets.get(&r).is_some())
 			.unwrap_or(false)
 	}
 
@@ -618,6 +641,26 @@ mod tests {
 		assert!(ap.sign(kp.address(), None, dummy_msg).is_err());
 	}
 
+	#[test]
+	fn timed_unlock_rejects_overflowing_duration() {
+		let kp = Random.generate();
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.insert_account(kp.secret().clone(), &"test".into()).is_ok());
+		assert!(ap.unlock_account_timed(kp.address(), "test".into(), Duration::from_secs(u64::max_value())).is_err());
+		assert!(!ap.is_unlocked(&kp.address()));
+	}
+
+	#[test]
+	fn timed_unlock_expires() {
+		let kp = Random.generate();
+		let ap = AccountProvider::transient_provider();
+		assert!(ap.insert_account(kp.secret().clone(), &"test".into()).is_ok());
+		assert!(ap.unlock_account_timed(kp.address(), "test".into(), Duration::from_secs(60)).is_ok());
+		assert!(ap.is_unlocked(&kp.address()));
+		ap.unlocked.write().get_mut(&StoreAccountRef::root(kp.address())).unwrap().unlock = Unlock::Timed(Instant::now() - Duration::from_secs(1));
+		assert!(!ap.is_unlocked(&kp.address()));
+	}
+
 	#[test]
 	fn should_sign_and_return_token() {
 		// given
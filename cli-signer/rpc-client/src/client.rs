@@ -50,8 +50,8 @@ use serde_json::{
 use futures::{Canceled, Complete, Future, oneshot, done};
 
 use jsonrpc_core::{Id, Version, Params, Error as JsonRpcError};
-use jsonrpc_core::request::MethodCall;
-use jsonrpc_core::response::{Output, Success, Failure};
+use jsonrpc_core::request::{Call, MethodCall, Request as JsonRpcRequest};
+use jsonrpc_core::response::{Output, Response, Success, Failure};
 
 use BoxFuture;
 
@@ -79,6 +79,40 @@ impl RpcHandler {
 			complete: Some(complete),
 		}
 	}
+
+	fn complete_output(&mut self, output: Output) {
+		let ret: Result<JsonValue, JsonRpcError>;
+		let response_id;
+		match output {
+			Output::Success(Success { result, id: Id::Num(id), .. }) => {
+				ret = Ok(result);
+				response_id = id as usize;
+			}
+			Output::Failure(Failure { error, id: Id::Num(id), .. }) => {
+				ret = Err(error);
+				response_id = id as usize;
+			}
+			output => {
+				warn!(
+					target: "rpc-client",
+					"recieved invalid response: {:?}",
+					output
+				);
+				return
+			}
+		}
+
+		match self.pending.remove(response_id) {
+			Some(c) => if let Err(_) = c.send(ret.map_err(|err| RpcError::JsonRpc(err))) {
+				warn!(target: "rpc-client", "Unable to send response.")
+			},
+			None => warn!(
+				target: "rpc-client",
+				"warning: unexpected id: {}",
+				response_id
+			),
+		}
+	}
 }
 
 impl Handler for RpcHandler {
@@ -127,46 +161,19 @@ impl Handler for RpcHandler {
 		}
 	}
 	fn on_message(&mut self, msg: Message) -> WsResult<()> {
-		let ret: Result<JsonValue, JsonRpcError>;
-		let response_id;
 		let string = &msg.to_string();
-		match json::from_str::<Output>(&string) {
-			Ok(Output::Success(Success { result, id: Id::Num(id), .. })) =>
-			{
-				ret = Ok(result);
-				response_id = id as usize;
-			}
-			Ok(Output::Failure(Failure { error, id: Id::Num(id), .. })) => {
-				ret = Err(error);
-				response_id = id as usize;
-			}
-			Err(e) => {
-				warn!(
-					target: "rpc-client",
-					"recieved invalid message: {}\n {:?}",
-					string,
-					e
-				);
-				return Ok(())
+		match json::from_str::<Response>(&string) {
+			Ok(Response::Single(output)) => self.complete_output(output),
+			// responses to a batch may come in any order, each one
+			// is matched up with its own pending request by id
+			Ok(Response::Batch(outputs)) => for output in outputs {
+				self.complete_output(output);
 			},
-			_ => {
-				warn!(
-					target: "rpc-client",
-					"recieved invalid message: {}",
-					string
-				);
-				return Ok(())
-			}
-		}
-
-		match self.pending.remove(response_id) {
-			Some(c) => if let Err(_) = c.send(ret.map_err(|err| RpcError::JsonRpc(err))) {
-				warn!(target: "rpc-client", "Unable to send response.")
-			},
-			None => warn!(
+			Err(e) => warn!(
 				target: "rpc-client",
-				"warning: unexpected id: {}",
-				response_id
+				"recieved invalid message: {}\n {:?}",
+				string,
+				e
 			),
 		}
 		Ok(())
@@ -266,23 +273,44 @@ impl Rpc {
 	) -> BoxFuture<Result<T, RpcError>, Canceled>
 		where T: DeserializeOwned + Send + Sized {
 
+		let (request, response) = self.register(method, params);
+
+		let serialized = json::to_string(&request)
+			.expect("request is serializable");
+		let _ = self.out.send(serialized);
+
+		response
+	}
+
+	/// Returns a builder collecting several calls to be sent
+	/// as a single JSON-RPC batch
+	pub fn batch(&mut self) -> Batch {
+		Batch {
+			rpc: self,
+			calls: Vec::new(),
+		}
+	}
+
+	/// Allocates an id for the call and registers it as pending,
+	/// returns the call and a future of its typed response
+	fn register<T>(
+		&mut self, method: &'static str, params: Vec<JsonValue>
+	) -> (MethodCall, BoxFuture<Result<T, RpcError>, Canceled>)
+		where T: DeserializeOwned + Send + Sized {
+
 		let (c, p) = oneshot::<Result<JsonValue, RpcError>>();
 
 		let id = self.counter.fetch_add(1, Ordering::Relaxed);
 		self.pending.insert(id, c);
 
-		let request = MethodCall {
+		let call = MethodCall {
 			jsonrpc: Some(Version::V2),
 			method: method.to_owned(),
 			params: Params::Array(params),
 			id: Id::Num(id as u64),
 		};
 
-		let serialized = json::to_string(&request)
-			.expect("request is serializable");
-		let _ = self.out.send(serialized);
-
-		Box::new(p.map(|result| {
+		let response = Box::new(p.map(|result| {
 			match result {
 				Ok(json) => {
 					let t: T = json::from_value(json)?;
@@ -290,7 +318,64 @@ impl Rpc {
 				},
 				Err(err) => Err(err)
 			}
-		}))
+		}));
+
+		(call, response)
+	}
+}
+
+/// A set of calls to be sent in a single JSON-RPC batch.
+///
+/// Each call gets its own future, resolved independently of the others
+/// once the batch is sent and the matching response arrives. Futures of
+/// a batch dropped without being sent are canceled.
+pub struct Batch<'a> {
+	rpc: &'a mut Rpc,
+	calls: Vec<MethodCall>,
+}
+
+impl<'a> Batch<'a> {
+	/// Adds a call to the batch, returns a future of its response
+	pub fn request<T>(
+		&mut self, method: &'static str, params: Vec<JsonValue>
+	) -> BoxFuture<Result<T, RpcError>, Canceled>
+		where T: DeserializeOwned + Send + Sized {
+
+		let (call, response) = self.rpc.register(method, params);
+		self.calls.push(call);
+		response
+	}
+
+	/// Number of calls in the batch
+	pub fn len(&self) -> usize {
+		self.calls.len()
+	}
+
+	/// Whether the batch contains no calls
+	pub fn is_empty(&self) -> bool {
+		self.calls.is_empty()
+	}
+
+	/// Sends all the collected calls, an empty batch is not sent at all
+	pub fn send(mut self) {
+		if self.calls.is_empty() {
+			return;
+		}
+
+		let calls = self.calls.drain(..).map(Call::MethodCall).collect();
+		let serialized = json::to_string(&JsonRpcRequest::Batch(calls))
+			.expect("request is serializable");
+		let _ = self.rpc.out.send(serialized);
+	}
+}
+
+impl<'a> Drop for Batch<'a> {
+	fn drop(&mut self) {
+		for call in self.calls.drain(..) {
+			if let Id::Num(id) = call.id {
+				self.rpc.pending.remove(id as usize);
+			}
+		}
 	}
 }
 
@@ -43,8 +43,32 @@ mod tests {
 
 	use futures::Future;
 	use std::path::PathBuf;
+	use ethereum_types::U256;
+	use jsonrpc_core::{MetaIoHandler, Params, Value, Error as JsonRpcError};
 	use client::{Rpc, RpcError};
+	use signer_client::SignerRpc;
 	use rpc;
+	use rpc::signer::TransactionModification;
+
+	fn batch_io() -> MetaIoHandler<rpc::Metadata> {
+		let mut io = MetaIoHandler::default();
+		io.add_method("test_echo", |params: Params| {
+			params.parse::<(Value,)>().map(|(value,)| value)
+		});
+		io.add_method("test_fail", |_| Err::<Value, _>(JsonRpcError::internal_error()));
+		io.add_method("signer_confirmRequest", |params: Params| {
+			let (id, _, _) = params.parse::<(String, Value, String)>()?;
+			match id.as_str() {
+				"0x2" => Err(JsonRpcError::invalid_params("Unknown request id")),
+				_ => Ok(Value::String(id)),
+			}
+		});
+		io
+	}
+
+	fn modification() -> TransactionModification {
+		TransactionModification { sender: None, gas_price: None, gas: None, condition: None }
+	}
 
 	#[test]
 	fn test_connection_refused() {
@@ -88,4 +112,70 @@ mod tests {
 		}).wait();
 	}
 
+	#[test]
+	fn test_batch_demultiplexes_responses() {
+		let (_srv, port, mut authcodes) = rpc::tests::ws::serve_with_io(batch_io());
+
+		let _ = authcodes.generate_new();
+		authcodes.to_file(&authcodes.path).unwrap();
+
+		let mut conn = Rpc::new(&format!("ws://127.0.0.1:{}", port),
+								&authcodes.path).unwrap();
+
+		let mut batch = conn.batch();
+		let first = batch.request::<String>("test_echo", vec!["first".into()]);
+		let failed = batch.request::<String>("test_fail", vec![]);
+		let second = batch.request::<u64>("test_echo", vec![5.into()]);
+		assert_eq!(batch.len(), 3);
+		batch.send();
+
+		assert_eq!(second.wait().unwrap().unwrap(), 5);
+		assert!(matches!(failed.wait().unwrap(), Err(RpcError::JsonRpc(_))));
+		assert_eq!(first.wait().unwrap().unwrap(), "first");
+
+		// the connection is still usable for single requests
+		let single = conn.request::<String>("test_echo", vec!["single".into()]);
+		assert_eq!(single.wait().unwrap().unwrap(), "single");
+	}
+
+	#[test]
+	fn test_batch_dropped_without_sending_is_canceled() {
+		let (_srv, port, mut authcodes) = rpc::tests::ws::serve_with_io(batch_io());
+
+		let _ = authcodes.generate_new();
+		authcodes.to_file(&authcodes.path).unwrap();
+
+		let mut conn = Rpc::new(&format!("ws://127.0.0.1:{}", port),
+								&authcodes.path).unwrap();
+
+		let response = {
+			let mut batch = conn.batch();
+			batch.request::<String>("test_echo", vec!["dropped".into()])
+		};
+
+		assert!(response.wait().is_err());
+	}
+
+	#[test]
+	fn test_confirm_requests_partial_failure() {
+		let (_srv, port, mut authcodes) = rpc::tests::ws::serve_with_io(batch_io());
+
+		let _ = authcodes.generate_new();
+		authcodes.to_file(&authcodes.path).unwrap();
+
+		let mut signer = SignerRpc::new(&format!("ws://127.0.0.1:{}", port),
+										&authcodes.path).unwrap();
+
+		let mut responses = signer.confirm_requests(vec![
+			(U256::from(1), modification()),
+			(U256::from(2), modification()),
+			(U256::from(3), modification()),
+		], "password").into_iter();
+
+		assert_eq!(responses.next().unwrap().wait().unwrap().unwrap(), U256::from(1));
+		assert!(matches!(responses.next().unwrap().wait().unwrap(), Err(RpcError::JsonRpc(_))));
+		assert_eq!(responses.next().unwrap().wait().unwrap().unwrap(), U256::from(3));
+		assert!(responses.next().is_none());
+	}
+
 }
@@ -51,6 +51,25 @@ impl SignerRpc {
 		])
 	}
 
+	/// Confirms several requests with a single batch round trip,
+	/// each confirmation succeeds or fails on its own
+	pub fn confirm_requests(
+		&mut self,
+		requests: Vec<(U256, TransactionModification)>,
+		pwd: &str
+	) -> Vec<BoxFuture<Result<U256, RpcError>, Canceled>> {
+		let mut batch = self.rpc.batch();
+		let responses = requests.into_iter().map(|(id, modification)| {
+			batch.request("signer_confirmRequest", vec![
+				Self::to_value(&format!("{:#x}", id)),
+				Self::to_value(&modification),
+				Self::to_value(&pwd),
+			])
+		}).collect();
+		batch.send();
+		responses
+	}
+
 	pub fn reject_request(&mut self, id: U256) -> BoxFuture<Result<bool, RpcError>, Canceled> {
 		self.rpc.request("signer_rejectRequest", vec![
 			JsonValue::String(format!("{:#x}", id))
@@ -14,39 +14,136 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+extern crate common_types as types;
 extern crate ethereum_types;
+extern crate ethkey;
+extern crate ethstore;
 extern crate futures;
+extern crate rlp;
 extern crate rpassword;
+extern crate rustc_hex;
+extern crate serde;
+extern crate serde_json;
+extern crate toml;
+
+#[macro_use]
+extern crate serde_derive;
+
+#[cfg(test)]
+extern crate jsonrpc_core;
 
 extern crate parity_rpc as rpc;
 extern crate parity_rpc_client as client;
 
-use ethereum_types::U256;
-use rpc::signer::ConfirmationRequest;
+mod policy;
+
+pub use policy::{Policy, PolicyFile};
+
+use ethereum_types::{Address, H256, U256};
+use ethkey::Password;
+use rpc::signer::{ConfirmationPayload, ConfirmationRequest, format_ether};
+use rustc_hex::ToHex;
 use client::signer_client::SignerRpc;
-use std::io::{Write, BufRead, BufReader, stdout, stdin};
+use std::collections::HashSet;
+use std::io::{Read, Write, BufRead, BufReader, stdout, stdin};
+use std::fmt::Debug;
 use std::path::PathBuf;
 use std::fs::File;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use types::transaction::{Action, Transaction};
+
+use futures::{Async, Future};
+use futures::executor::{self, Notify};
+
+/// Default time to wait for a response from the signer RPC.
+pub const DEFAULT_RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Wakes up the thread waiting for a future.
+struct ThreadNotify(thread::Thread);
+
+impl Notify for ThreadNotify {
+	fn notify(&self, _id: usize) {
+		self.0.unpark();
+	}
+}
+
+/// Wait for an RPC response, giving up after `timeout`.
+fn wait_for<F>(future: F, timeout: Duration) -> Result<F::Item, String> where
+	F: Future,
+	F::Error: Debug,
+{
+	let deadline = Instant::now().checked_add(timeout)
+		.ok_or_else(|| format!("signer RPC timeout of {:?} is too large", timeout))?;
+	let notify = Arc::new(ThreadNotify(thread::current()));
+	let mut future = executor::spawn(future);
+	loop {
+		match future.poll_future_notify(&notify, 0) {
+			Ok(Async::Ready(item)) => return Ok(item),
+			Ok(Async::NotReady) => (),
+			Err(err) => return Err(format!("{:?}", err)),
+		}
+		let now = Instant::now();
+		if now >= deadline {
+			return Err(format!("signer RPC timed out after {:?}", timeout));
+		}
+		thread::park_timeout(deadline - now);
+	}
+}
+
+/// Gas limit above which a transaction is flagged before signing.
+const HIGH_GAS: u64 = 1_000_000;
+/// Value above which a transaction is flagged before signing: 10 ether.
+const HIGH_VALUE: u64 = 10_000_000_000_000_000_000;
+
+/// Fee estimate and warnings shown before signing a transaction request.
+fn cost_summary(request: &ConfirmationRequest) -> Option<String> {
+	let tx = match request.payload {
+		ConfirmationPayload::SendTransaction(ref tx) | ConfirmationPayload::SignTransaction(ref tx) => tx,
+		_ => return None,
+	};
+	let value = tx.value.unwrap_or_default();
 
-use futures::Future;
+	let mut lines = Vec::new();
+	match (tx.gas, tx.gas_price) {
+		(Some(gas), Some(gas_price)) => {
+			let fee = gas.saturating_mul(gas_price);
+			lines.push(format!(
+				"Estimated fee: {} ETH ({} gas at {} wei), total cost: {} ETH",
+				format_ether(fee), gas, gas_price, format_ether(fee.saturating_add(value)),
+			));
+		}
+		_ => lines.push("Estimated fee: unknown, gas or gas price not set".to_owned()),
+	}
+	if value > HIGH_VALUE.into() {
+		lines.push(format!("Warning: value of {} ETH is unusually high", format_ether(value)));
+	}
+	if let Some(gas) = tx.gas.filter(|gas| *gas > HIGH_GAS.into()) {
+		lines.push(format!("Warning: gas limit of {} is unusually high", gas));
+	}
+	Some(lines.join("\n"))
+}
 
 fn sign_interactive(
 	signer: &mut SignerRpc,
 	password: &str,
-	request: ConfirmationRequest
+	request: ConfirmationRequest,
+	timeout: Duration,
 ) {
-	print!("\n{}\nSign this transaction? (y)es/(N)o/(r)eject: ", request);
+	let summary = cost_summary(&request).map_or_else(String::new, |s| format!("{}\n", s));
+	print!("\n{}\n{}Sign this transaction? (y)es/(N)o/(r)eject: ", request, summary);
 	let _ = stdout().flush();
 	match BufReader::new(stdin()).lines().next() {
 		Some(Ok(line)) => {
 			match line.to_lowercase().chars().nth(0) {
 				Some('y') => {
-					match sign_transaction(signer, request.id, password) {
+					match sign_transaction(signer, request.id, password, timeout) {
 						Ok(s) | Err(s) => println!("{}", s),
 					}
 				}
 				Some('r') => {
-					match reject_transaction(signer, request.id) {
+					match reject_transaction(signer, request.id, timeout) {
 						Ok(s) | Err(s) => println!("{}", s),
 					}
 				}
@@ -59,16 +156,17 @@ fn sign_interactive(
 
 fn sign_transactions(
 	signer: &mut SignerRpc,
-	password: String
+	password: String,
+	timeout: Duration,
 ) -> Result<String, String> {
-	signer.requests_to_confirm().map(|reqs| {
+	wait_for(signer.requests_to_confirm().map(|reqs| {
 		match reqs {
 			Ok(ref reqs) if reqs.is_empty() => {
 				Ok("No transactions in signing queue".to_owned())
 			}
 			Ok(reqs) => {
 				for r in reqs {
-					sign_interactive(signer, &password, r)
+					sign_interactive(signer, &password, r, timeout)
 				}
 				Ok("".to_owned())
 			}
@@ -76,13 +174,11 @@ fn sign_transactions(
 				Err(format!("error: {:?}", err))
 			}
 		}
-	}).map_err(|err| {
-		format!("{:?}", err)
-	}).wait()?
+	}), timeout)?
 }
 
-fn list_transactions(signer: &mut SignerRpc) -> Result<String, String> {
-	signer.requests_to_confirm().map(|reqs| {
+fn list_transactions(signer: &mut SignerRpc, timeout: Duration) -> Result<String, String> {
+	wait_for(signer.requests_to_confirm().map(|reqs| {
 		match reqs {
 			Ok(ref reqs) if reqs.is_empty() => {
 				Ok("No transactions in signing queue".to_owned())
@@ -98,88 +194,262 @@ fn list_transactions(signer: &mut SignerRpc) -> Result<String, String> {
 				Err(format!("error: {:?}", err))
 			}
 		}
-	}).map_err(|err| {
-		format!("{:?}", err)
-	}).wait()?
+	}), timeout)?
+}
+
+fn request_details(request: &ConfirmationRequest) -> String {
+	fn field<T: ToString>(name: &str, value: Option<T>) -> String {
+		format!("  {}: {}", name, value.map_or_else(|| "(not set)".to_owned(), |v| v.to_string()))
+	}
+
+	let mut details = vec![format!("{}", request)];
+	match request.payload {
+		ConfirmationPayload::SendTransaction(ref tx) | ConfirmationPayload::SignTransaction(ref tx) => {
+			details.push(field("from", tx.from.map(|a| format!("{:#x}", a))));
+			details.push(field("to", Some(tx.to.map_or_else(|| "(contract creation)".to_owned(), |a| format!("{:#x}", a)))));
+			details.push(field("value (wei)", tx.value));
+			details.push(field("gas", tx.gas));
+			details.push(field("gas price (wei)", tx.gas_price));
+			details.push(field("nonce", tx.nonce));
+			details.push(field("data", tx.data.as_ref().map(|d| format!("0x{}", d.0.to_hex::<String>()))));
+			details.push(field("condition", tx.condition.as_ref().map(|c| format!("{:?}", c))));
+		}
+		ConfirmationPayload::EthSignMessage(ref sign) => {
+			details.push(field("address", Some(format!("{:#x}", sign.address))));
+			details.push(field("data", Some(format!("0x{}", sign.data.0.to_hex::<String>()))));
+		}
+		ConfirmationPayload::EIP191SignMessage(ref sign) => {
+			details.push(field("address", Some(format!("{:#x}", sign.address))));
+			details.push(field("data", Some(format!("{:#x}", sign.data))));
+		}
+		ConfirmationPayload::Decrypt(ref decrypt) => {
+			details.push(field("address", Some(format!("{:#x}", decrypt.address))));
+			details.push(field("message", Some(format!("0x{}", decrypt.msg.0.to_hex::<String>()))));
+		}
+	}
+	details.join("\n")
+}
+
+fn find_request(requests: &[ConfirmationRequest], id: U256) -> Result<String, String> {
+	requests.iter()
+		.find(|r| r.id == id)
+		.map(request_details)
+		.ok_or_else(|| format!("No request #{} in signing queue", id))
+}
+
+fn show_transaction(signer: &mut SignerRpc, id: U256, timeout: Duration) -> Result<String, String> {
+	wait_for(signer.requests_to_confirm().map(|reqs| {
+		match reqs {
+			Ok(ref reqs) => find_request(reqs, id),
+			Err(err) => Err(format!("error: {:?}", err)),
+		}
+	}), timeout)?
 }
 
 fn sign_transaction(
-	signer: &mut SignerRpc, id: U256, password: &str
+	signer: &mut SignerRpc, id: U256, password: &str, timeout: Duration
 ) -> Result<String, String> {
-	signer.confirm_request(id, None, None, None, password).map(|res| {
+	wait_for(signer.confirm_request(id, None, None, None, password).map(|res| {
 		match res {
 			Ok(u) => Ok(format!("Signed transaction id: {:#x}", u)),
 			Err(e) => Err(format!("{:?}", e)),
 		}
-	}).map_err(|err| {
-		format!("{:?}", err)
-	}).wait()?
+	}), timeout)?
 }
 
 fn reject_transaction(
-	signer: &mut SignerRpc, id: U256) -> Result<String, String>
+	signer: &mut SignerRpc, id: U256, timeout: Duration) -> Result<String, String>
 {
-	signer.reject_request(id).map(|res| {
+	wait_for(signer.reject_request(id).map(|res| {
 		match res {
 			Ok(true) => Ok(format!("Rejected transaction id {:#x}", id)),
 			Ok(false) => Err(format!("No such request")),
 			Err(e) => Err(format!("{:?}", e)),
 		}
-	}).map_err(|err| {
-		format!("{:?}", err)
-	}).wait()?
+	}), timeout)?
+}
+
+/// Interval between polls of the signing queue in watch mode.
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+fn watch_transactions(
+	signer: &mut SignerRpc,
+	mut policy: PolicyFile,
+	timeout: Duration,
+) -> Result<String, String> {
+	// requests already printed for manual approval.
+	let mut awaiting = HashSet::new();
+	loop {
+		match policy.reload_if_changed() {
+			Ok(true) => println!("Policy reloaded"),
+			Ok(false) => (),
+			Err(e) => println!("{}, keeping the previous policy", e),
+		}
+
+		let reqs = wait_for(signer.requests_to_confirm(), timeout)?
+			.map_err(|err| format!("error: {:?}", err))?;
+
+		awaiting.retain(|id| reqs.iter().any(|r| &r.id == id));
+		for r in reqs {
+			match policy.policy().check(&r) {
+				Err(reason) => {
+					println!("\n{}\nViolates the policy: {}", r, reason);
+					awaiting.remove(&r.id);
+					match reject_transaction(signer, r.id, timeout) {
+						Ok(s) | Err(s) => println!("{}", s),
+					}
+				}
+				Ok(()) if awaiting.insert(r.id) => println!("\n{}\nAwaiting manual approval", r),
+				Ok(()) => (),
+			}
+		}
+
+		thread::sleep(WATCH_INTERVAL);
+	}
+}
+
+fn read_password(pwfile: Option<PathBuf>) -> Result<String, String> {
+	match pwfile {
+		Some(pwfile) => {
+			match File::open(pwfile) {
+				Ok(fd) => {
+					match BufReader::new(fd).lines().next() {
+						Some(Ok(line)) => Ok(line),
+						_ => Err(format!("No password in file"))
+					}
+				},
+				Err(e) =>
+					Err(format!("Could not open password file: {}", e))
+			}
+		}
+		None => {
+			rpassword::prompt_password_stdout("Password: ").map_err(|e| format!("{}", e))
+		}
+	}
+}
+
+/// Transaction fields used for offline signing.
+///
+/// Everything a node would normally fill in (nonce, gas, gas price and chain id)
+/// has to be given explicitly since no network access takes place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OfflineTransaction {
+	/// Transaction nonce.
+	pub nonce: U256,
+	/// Gas price.
+	pub gas_price: U256,
+	/// Gas limit.
+	pub gas: U256,
+	/// Recipient; `None` creates a contract.
+	pub to: Option<Address>,
+	/// Transferred value.
+	pub value: U256,
+	/// Transaction data.
+	pub data: Vec<u8>,
+	/// Chain id used for replay protection (EIP-155).
+	pub chain_id: u64,
+}
+
+fn sign_offline(keyfile: &[u8], password: &Password, tx: OfflineTransaction) -> Result<(Vec<u8>, H256), String> {
+	let json: serde_json::Value = serde_json::from_slice(keyfile)
+		.map_err(|e| format!("Invalid key file: {}", e))?;
+	let crypto = json.get("crypto").or_else(|| json.get("Crypto"))
+		.ok_or_else(|| format!("Invalid key file: missing crypto section"))?;
+	let crypto: ethstore::Crypto = crypto.to_string().parse()
+		.map_err(|e| format!("Invalid key file: {}", e))?;
+	let secret = crypto.secret(password).map_err(|e| format!("{}", e))?;
+
+	let signed = Transaction {
+		nonce: tx.nonce,
+		gas_price: tx.gas_price,
+		gas: tx.gas,
+		action: tx.to.map_or(Action::Create, Action::Call),
+		value: tx.value,
+		data: tx.data,
+	}.sign(&secret, Some(tx.chain_id));
+
+	Ok((rlp::encode(&signed), signed.hash()))
 }
 
 // cmds
 
+/// Sign a transaction with a key exported with `parity_exportAccount`
+/// without contacting the node.
+pub fn signer_sign_offline(
+	keyfile: PathBuf,
+	pwfile: Option<PathBuf>,
+	tx: OfflineTransaction,
+) -> Result<String, String> {
+	let mut json = Vec::new();
+	File::open(&keyfile)
+		.and_then(|mut fd| fd.read_to_end(&mut json))
+		.map_err(|e| format!("Could not read key file: {}", e))?;
+	let password = read_password(pwfile)?;
+
+	let (raw, hash) = sign_offline(&json, &password.into(), tx)?;
+	Ok(format!("Raw transaction: 0x{}\nTransaction hash: {:#x}", raw.to_hex::<String>(), hash))
+}
+
 pub fn signer_list(
-	signerport: u16, authfile: PathBuf
+	signerport: u16, authfile: PathBuf, timeout: Duration
 ) -> Result<String, String> {
 	let addr = &format!("ws://127.0.0.1:{}", signerport);
 	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
 		format!("{:?}", err)
 	})?;
-	list_transactions(&mut signer)
+	list_transactions(&mut signer, timeout)
 }
 
 pub fn signer_reject(
-	id: Option<usize>, signerport: u16, authfile: PathBuf
+	id: Option<usize>, signerport: u16, authfile: PathBuf, timeout: Duration
 ) -> Result<String, String> {
 	let id = id.ok_or(format!("id required for signer reject"))?;
 	let addr = &format!("ws://127.0.0.1:{}", signerport);
 	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
 		format!("{:?}", err)
 	})?;
-	reject_transaction(&mut signer, U256::from(id))
+	reject_transaction(&mut signer, U256::from(id), timeout)
+}
+
+/// Show the full details of a single request in the signing queue.
+pub fn signer_show(
+	id: Option<usize>, signerport: u16, authfile: PathBuf, timeout: Duration
+) -> Result<String, String> {
+	let id = id.ok_or(format!("id required for signer show"))?;
+	let addr = &format!("ws://127.0.0.1:{}", signerport);
+	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
+		format!("{:?}", err)
+	})?;
+	show_transaction(&mut signer, U256::from(id), timeout)
+}
+
+/// Watch the signing queue, rejecting requests which violate the policy and
+/// printing the rest for manual approval.
+///
+/// The policy is reloaded whenever the policy file changes.
+pub fn signer_watch(
+	policy_file: PathBuf,
+	signerport: u16,
+	authfile: PathBuf,
+	timeout: Duration,
+) -> Result<String, String> {
+	let policy = PolicyFile::load(policy_file)?;
+
+	let addr = &format!("ws://127.0.0.1:{}", signerport);
+	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
+		format!("{:?}", err)
+	})?;
+	watch_transactions(&mut signer, policy, timeout)
 }
 
 pub fn signer_sign(
 	id: Option<usize>,
 	pwfile: Option<PathBuf>,
 	signerport: u16,
-	authfile: PathBuf
+	authfile: PathBuf,
+	timeout: Duration,
 ) -> Result<String, String> {
-	let password;
-	match pwfile {
-		Some(pwfile) => {
-			match File::open(pwfile) {
-				Ok(fd) => {
-					match BufReader::new(fd).lines().next() {
-						Some(Ok(line)) => password = line,
-						_ => return Err(format!("No password in file"))
-					}
-				},
-				Err(e) =>
-					return Err(format!("Could not open password file: {}", e))
-			}
-		}
-		None => {
-			password = match rpassword::prompt_password_stdout("Password: ") {
-				Ok(p) => p,
-				Err(e) => return Err(format!("{}", e)),
-			}
-		}
-	}
+	let password = read_password(pwfile)?;
 
 	let addr = &format!("ws://127.0.0.1:{}", signerport);
 	let mut signer = SignerRpc::new(addr, &authfile).map_err(|err| {
@@ -188,10 +458,156 @@ pub fn signer_sign(
 
 	match id {
 		Some(id) => {
-			sign_transaction(&mut signer, U256::from(id), &password)
+			sign_transaction(&mut signer, U256::from(id), &password, timeout)
 		},
 		None => {
-			sign_transactions(&mut signer, password)
+			sign_transactions(&mut signer, password, timeout)
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpc_core::{MetaIoHandler, Value, Error as JsonRpcError};
+	use jsonrpc_core::futures::future;
+	use rpc::Origin;
+	use rpc::signer::TransactionRequest;
+	use rustc_hex::FromHex;
+	use serde_json;
+
+	#[test]
+	fn should_sign_offline_with_exported_keyfile() {
+		// EIP-155 example transaction signed with secret 0x4646..46.
+		let keyfile = include_bytes!("../res/offline_keyfile.json");
+		let tx = OfflineTransaction {
+			nonce: 9.into(),
+			gas_price: 20_000_000_000u64.into(),
+			gas: 21_000.into(),
+			to: Some(Address::repeat_byte(0x35)),
+			value: 1_000_000_000_000_000_000u64.into(),
+			data: vec![],
+			chain_id: 1,
+		};
+
+		let (raw, hash) = sign_offline(&keyfile[..], &"offline-signing".into(), tx).unwrap();
+
+		assert_eq!(raw, "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83".from_hex::<Vec<u8>>().unwrap());
+		assert_eq!(hash, "33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788".parse().unwrap());
+	}
+
+	#[test]
+	fn should_not_sign_offline_with_invalid_password() {
+		let keyfile = include_bytes!("../res/offline_keyfile.json");
+		let tx = OfflineTransaction {
+			nonce: 0.into(),
+			gas_price: 1.into(),
+			gas: 21_000.into(),
+			to: None,
+			value: 0.into(),
+			data: vec![],
+			chain_id: 1,
+		};
+
+		assert!(sign_offline(&keyfile[..], &"wrong".into(), tx).is_err());
+	}
+
+	#[test]
+	fn should_show_details_of_matching_request() {
+		let tx = TransactionRequest {
+			to: Some(Address::repeat_byte(0x35)),
+			gas: Some(21_000.into()),
+			data: Some(vec![0xde, 0xad].into()),
+			..Default::default()
+		};
+		let requests = vec![
+			ConfirmationRequest { id: 1.into(), payload: ConfirmationPayload::SendTransaction(Default::default()), origin: Origin::Unknown },
+			ConfirmationRequest { id: 2.into(), payload: ConfirmationPayload::SignTransaction(tx), origin: Origin::Unknown },
+		];
+
+		let details = find_request(&requests, 2.into()).unwrap();
+
+		assert!(details.starts_with(&format!("{}", requests[1])));
+		assert!(details.contains("to: 0x3535353535353535353535353535353535353535"));
+		assert!(details.contains("gas: 21000"));
+		assert!(details.contains("data: 0xdead"));
+		assert!(details.contains("nonce: (not set)"));
+	}
+
+	#[test]
+	fn should_not_show_missing_request() {
+		let requests = vec![
+			ConfirmationRequest { id: 1.into(), payload: ConfirmationPayload::SendTransaction(Default::default()), origin: Origin::Unknown },
+		];
+
+		assert_eq!(find_request(&requests, 3.into()), Err("No request #3 in signing queue".to_owned()));
+	}
+
+	#[test]
+	fn should_estimate_transaction_cost() {
+		let tx = TransactionRequest {
+			to: Some(Address::repeat_byte(0x35)),
+			value: Some(1_000_000_000_000_000_000u64.into()),
+			gas: Some(21_000.into()),
+			gas_price: Some(20_000_000_000u64.into()),
+			..Default::default()
+		};
+		let request = ConfirmationRequest { id: 1.into(), payload: ConfirmationPayload::SendTransaction(tx), origin: Origin::Unknown };
+
+		assert_eq!(
+			cost_summary(&request),
+			Some("Estimated fee: 0.00042 ETH (21000 gas at 20000000000 wei), total cost: 1.00042 ETH".to_owned()),
+		);
+	}
+
+	#[test]
+	fn should_warn_about_high_value_and_gas() {
+		let tx = TransactionRequest {
+			value: Some(U256::from(11) * U256::from(1_000_000_000_000_000_000u64)),
+			gas: Some(2_000_000.into()),
+			..Default::default()
+		};
+		let request = ConfirmationRequest { id: 1.into(), payload: ConfirmationPayload::SignTransaction(tx), origin: Origin::Unknown };
+
+		let summary = cost_summary(&request).unwrap();
+		let lines: Vec<_> = summary.lines().collect();
+		assert_eq!(lines, vec![
+			"Estimated fee: unknown, gas or gas price not set",
+			"Warning: value of 11 ETH is unusually high",
+			"Warning: gas limit of 2000000 is unusually high",
+		]);
+	}
+
+	#[test]
+	fn should_not_estimate_cost_of_sign_requests() {
+		let payload = r#"{"sign": {"address": "0x3535353535353535353535353535353535353535", "data": "0x01"}}"#;
+		let request = ConfirmationRequest { id: 1.into(), payload: serde_json::from_str(payload).unwrap(), origin: Origin::Unknown };
+
+		assert_eq!(cost_summary(&request), None);
+	}
+
+	#[test]
+	fn should_time_out_if_signer_does_not_respond() {
+		let mut io = MetaIoHandler::default();
+		io.add_method("signer_requestsToConfirm", |_| future::empty::<Value, JsonRpcError>());
+		let (_srv, port, mut authcodes) = rpc::tests::ws::serve_with_io(io);
+		let _ = authcodes.generate_new();
+		authcodes.to_file(&authcodes.path).unwrap();
+
+		let mut signer = SignerRpc::new(&format!("ws://127.0.0.1:{}", port), &authcodes.path).unwrap();
+
+		assert_eq!(
+			list_transactions(&mut signer, Duration::from_millis(200)),
+			Err("signer RPC timed out after 200ms".to_owned()),
+		);
+	}
+
+	#[test]
+	fn should_reject_too_large_timeout() {
+		let timeout = Duration::from_secs(u64::max_value());
+		assert_eq!(
+			wait_for(future::empty::<(), ()>(), timeout),
+			Err(format!("signer RPC timeout of {:?} is too large", timeout)),
+		);
+	}
+}
@@ -0,0 +1,290 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signing policy used to reject requests automatically.
+//!
+//! Policies are read from TOML files, or JSON files if the extension is `.json`:
+//!
+//! ```toml
+//! max_value = "0xde0b6b3a7640000"
+//! max_gas_price = "0x4a817c800"
+//! allowlist = ["0x3535353535353535353535353535353535353535"]
+//! denylist = []
+//! allow_data = false
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use ethereum_types::{Address, U256};
+use rpc::signer::{ConfirmationPayload, ConfirmationRequest};
+use serde_json;
+use toml;
+
+/// Limits a transaction has to stay within not to be rejected.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+	/// Maximal transferred value in wei.
+	pub max_value: Option<U256>,
+	/// Maximal gas price in wei.
+	pub max_gas_price: Option<U256>,
+	/// If given, only these recipients are allowed.
+	pub allowlist: Option<Vec<Address>>,
+	/// Recipients which are never allowed.
+	#[serde(default)]
+	pub denylist: Vec<Address>,
+	/// Whether transactions may carry data or create contracts.
+	#[serde(default = "allow_data_default")]
+	pub allow_data: bool,
+}
+
+fn allow_data_default() -> bool {
+	true
+}
+
+impl Default for Policy {
+	fn default() -> Self {
+		Policy {
+			max_value: None,
+			max_gas_price: None,
+			allowlist: None,
+			denylist: Vec::new(),
+			allow_data: allow_data_default(),
+		}
+	}
+}
+
+impl Policy {
+	/// Parse a policy, as JSON if the path has a `.json` extension and as TOML otherwise.
+	pub fn parse(path: &Path, contents: &str) -> Result<Policy, String> {
+		let result = match path.extension().and_then(|ext| ext.to_str()) {
+			Some("json") => serde_json::from_str(contents).map_err(|e| e.to_string()),
+			_ => toml::from_str(contents).map_err(|e| e.to_string()),
+		};
+
+		result.map_err(|e| format!("Invalid policy file {}: {}", path.display(), e))
+	}
+
+	/// Check a request against the policy, returning the reason if it's violated.
+	///
+	/// Only transactions are subject to the policy, a gas price or value left
+	/// for the node to fill in is not checked.
+	pub fn check(&self, request: &ConfirmationRequest) -> Result<(), String> {
+		let tx = match request.payload {
+			ConfirmationPayload::SendTransaction(ref tx) | ConfirmationPayload::SignTransaction(ref tx) => tx,
+			_ => return Ok(()),
+		};
+
+		if let (Some(max_value), Some(value)) = (self.max_value, tx.value) {
+			if value > max_value {
+				return Err(format!("value {} is above the cap of {}", value, max_value));
+			}
+		}
+
+		if let (Some(max_gas_price), Some(gas_price)) = (self.max_gas_price, tx.gas_price) {
+			if gas_price > max_gas_price {
+				return Err(format!("gas price {} is above the cap of {}", gas_price, max_gas_price));
+			}
+		}
+
+		match tx.to {
+			Some(ref to) if self.denylist.contains(to) => {
+				return Err(format!("recipient {:?} is denied", to));
+			}
+			Some(ref to) if self.allowlist.as_ref().map_or(false, |allowlist| !allowlist.contains(to)) => {
+				return Err(format!("recipient {:?} is not allowed", to));
+			}
+			None if !self.allow_data => {
+				return Err("contract creation is not allowed".into());
+			}
+			None if self.allowlist.is_some() => {
+				return Err("contract creation has no allowed recipient".into());
+			}
+			_ => {}
+		}
+
+		if !self.allow_data && tx.data.as_ref().map_or(false, |data| !data.0.is_empty()) {
+			return Err("transaction data is not allowed".into());
+		}
+
+		Ok(())
+	}
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+	fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Policy loaded from a file, reloaded when the file changes.
+pub struct PolicyFile {
+	path: PathBuf,
+	modified: Option<SystemTime>,
+	policy: Policy,
+}
+
+impl PolicyFile {
+	/// Load the policy from the given file.
+	pub fn load(path: PathBuf) -> Result<PolicyFile, String> {
+		let modified = modified(&path);
+		let contents = fs::read_to_string(&path)
+			.map_err(|e| format!("Could not read policy file {}: {}", path.display(), e))?;
+		let policy = Policy::parse(&path, &contents)?;
+
+		Ok(PolicyFile { path, modified, policy })
+	}
+
+	/// The policy in effect.
+	pub fn policy(&self) -> &Policy {
+		&self.policy
+	}
+
+	/// Reload the policy if the file was modified since it was last loaded.
+	///
+	/// Returns whether the policy was reloaded; on error the previous policy stays in effect.
+	pub fn reload_if_changed(&mut self) -> Result<bool, String> {
+		let modified = modified(&self.path);
+		if modified == self.modified {
+			return Ok(false);
+		}
+
+		self.modified = modified;
+		let reloaded = PolicyFile::load(self.path.clone())?;
+		self.policy = reloaded.policy;
+		Ok(true)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+
+	use ethereum_types::{Address, U256};
+	use rpc::Origin;
+	use rpc::signer::{ConfirmationPayload, ConfirmationRequest, TransactionRequest};
+	use serde_json;
+
+	use super::Policy;
+
+	fn request(tx: TransactionRequest) -> ConfirmationRequest {
+		ConfirmationRequest {
+			id: 1.into(),
+			payload: ConfirmationPayload::SendTransaction(tx),
+			origin: Origin::Unknown,
+		}
+	}
+
+	fn transfer(to: Address, value: u64) -> TransactionRequest {
+		TransactionRequest {
+			to: Some(to),
+			value: Some(value.into()),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn should_reject_value_and_gas_price_above_caps() {
+		let policy = Policy {
+			max_value: Some(100.into()),
+			max_gas_price: Some(10.into()),
+			..Default::default()
+		};
+		let to = Address::repeat_byte(1);
+
+		assert_eq!(policy.check(&request(transfer(to, 100))), Ok(()));
+		assert!(policy.check(&request(transfer(to, 101))).unwrap_err().contains("above the cap of 100"));
+
+		let expensive = TransactionRequest { gas_price: Some(11.into()), ..transfer(to, 1) };
+		assert!(policy.check(&request(expensive)).unwrap_err().contains("gas price"));
+
+		// left for the node to fill in.
+		let unpriced = TransactionRequest { value: None, gas_price: None, ..transfer(to, 0) };
+		assert_eq!(policy.check(&request(unpriced)), Ok(()));
+	}
+
+	#[test]
+	fn should_reject_unknown_and_denied_recipients() {
+		let (allowed, denied, unknown) = (Address::repeat_byte(1), Address::repeat_byte(2), Address::repeat_byte(3));
+		let policy = Policy {
+			allowlist: Some(vec![allowed, denied]),
+			denylist: vec![denied],
+			..Default::default()
+		};
+
+		assert_eq!(policy.check(&request(transfer(allowed, 1))), Ok(()));
+		assert!(policy.check(&request(transfer(denied, 1))).unwrap_err().contains("is denied"));
+		assert!(policy.check(&request(transfer(unknown, 1))).unwrap_err().contains("is not allowed"));
+
+		let create = TransactionRequest { data: Some(vec![0x60].into()), ..Default::default() };
+		assert!(policy.check(&request(create.clone())).is_err());
+		assert_eq!(Policy::default().check(&request(create)), Ok(()));
+	}
+
+	#[test]
+	fn should_reject_data_if_not_allowed() {
+		let policy = Policy { allow_data: false, ..Default::default() };
+		let to = Address::repeat_byte(1);
+
+		let call = TransactionRequest { data: Some(vec![1, 2, 3].into()), ..transfer(to, 0) };
+		assert!(policy.check(&request(call)).unwrap_err().contains("data is not allowed"));
+
+		let empty = TransactionRequest { data: Some(vec![].into()), ..transfer(to, 0) };
+		assert_eq!(policy.check(&request(empty)), Ok(()));
+		assert!(policy.check(&request(TransactionRequest::default())).unwrap_err().contains("contract creation"));
+	}
+
+	#[test]
+	fn should_not_check_other_requests() {
+		let policy = Policy { max_value: Some(0.into()), allow_data: false, ..Default::default() };
+		let payload = r#"{"sign": {"address": "0x3535353535353535353535353535353535353535", "data": "0x01"}}"#;
+		let sign = ConfirmationRequest {
+			id: 1.into(),
+			payload: serde_json::from_str(payload).unwrap(),
+			origin: Origin::Unknown,
+		};
+
+		assert_eq!(policy.check(&sign), Ok(()));
+	}
+
+	#[test]
+	fn should_parse_toml_and_json_policies() {
+		let expected = Policy {
+			max_value: Some(U256::from(1_000_000_000_000_000_000u64)),
+			max_gas_price: None,
+			allowlist: Some(vec![Address::repeat_byte(0x35)]),
+			denylist: vec![],
+			allow_data: false,
+		};
+
+		let toml = r#"
+			max_value = "0xde0b6b3a7640000"
+			allowlist = ["0x3535353535353535353535353535353535353535"]
+			allow_data = false
+		"#;
+		assert_eq!(Policy::parse(Path::new("policy.toml"), toml), Ok(expected.clone()));
+
+		let json = r#"{
+			"max_value": "0xde0b6b3a7640000",
+			"allowlist": ["0x3535353535353535353535353535353535353535"],
+			"allow_data": false
+		}"#;
+		assert_eq!(Policy::parse(Path::new("policy.json"), json), Ok(expected));
+
+		assert_eq!(Policy::parse(Path::new("policy.toml"), ""), Ok(Policy::default()));
+		assert!(Policy::parse(Path::new("policy.toml"), "max_value = 1\nunknown = true").is_err());
+	}
+}
@@ -300,6 +300,10 @@ impl AsRef<[Node]> for NodeCache {
 // We have to use raw pointers to read/write uninit, using "normal" indexing causes LLVM to freak
 // out. It counts as a read and causes all writes afterwards to be elided. Yes, really. I know, I
 // want to refactor this to use less `unsafe` as much as the next rustacean.
+//
+// Unlike the ProgPoW cache DAG (see `progpow::generate_cdag`), this can't be generated in parallel:
+// every node of the initial fill is the hash of the previous one, and every RandMemoHash round
+// updates nodes in place from the node before it and a data-dependent other node.
 unsafe fn initialize_memory(memory: *mut Node, num_nodes: usize, ident: &H256) {
 	// We use raw pointers here, see above
 	let dst = slice::from_raw_parts_mut(memory as *mut u8, NODE_BYTES);
@@ -20,6 +20,7 @@ extern crate ethereum_types;
 extern crate memmap;
 extern crate parking_lot;
 extern crate primal;
+extern crate rayon;
 
 #[macro_use]
 extern crate log;
@@ -35,6 +35,7 @@
 
 use compute::{FNV_PRIME, calculate_dag_item};
 use keccak::H256;
+use rayon::prelude::*;
 use shared::{ETHASH_ACCESSES, ETHASH_MIX_BYTES, Node, get_data_size};
 
 const PROGPOW_CACHE_BYTES: usize = 16 * 1024;
@@ -405,15 +406,16 @@ pub fn progpow(
 	(digest, result)
 }
 
+/// Generate the ProgPoW cache DAG from the light cache.
+///
+/// Each row only depends on the (already generated) light cache, so rows are computed in parallel.
 pub fn generate_cdag(cache: &[Node]) -> CDag {
 	let mut c_dag = [0u32; PROGPOW_CACHE_WORDS];
 
-	for i in 0..PROGPOW_CACHE_WORDS / 16 {
+	c_dag.par_chunks_mut(16).enumerate().for_each(|(i, row)| {
 		let node = calculate_dag_item(i as u32, cache);
-		for j in 0..16 {
-			c_dag[i * 16 + j] = node.as_words()[j];
-		}
-	}
+		row.copy_from_slice(&node.as_words()[..16]);
+	});
 
 	c_dag
 }
@@ -458,6 +460,20 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn test_cdag_matches_sequential_generation() {
+		let builder = NodeCacheBuilder::new(OptimizeFor::Cpu, u64::max_value());
+		let tempdir = TempDir::new().unwrap();
+		let cache = builder.new_cache(tempdir.into_path(), 0);
+
+		let c_dag = generate_cdag(cache.as_ref());
+
+		for i in 0..PROGPOW_CACHE_WORDS / 16 {
+			let node = calculate_dag_item(i as u32, cache.as_ref());
+			assert_eq!(&c_dag[i * 16..(i + 1) * 16], &node.as_words()[..16]);
+		}
+	}
+
 	#[test]
 	fn test_random_merge() {
 		let tests = [
@@ -15,11 +15,14 @@
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 //! DB backend wrapper for Account trie
+use std::collections::HashMap;
+
 use ethereum_types::H256;
 use keccak_hash::{KECCAK_NULL_RLP, keccak};
 use hash_db::{HashDB, AsHashDB, Prefix};
 use keccak_hasher::KeccakHasher;
 use kvdb::DBValue;
+use parking_lot::Mutex;
 use rlp::NULL_RLP;
 
 // Combines a key with an address hash to ensure uniqueness.
@@ -70,6 +73,32 @@ impl Factory {
 	}
 }
 
+/// Records preimages of the hashed state keyspace, i.e. of addresses and storage keys.
+///
+/// Meant for debugging only: recording is enabled by sharing a recorder through the state
+/// factories, preimages are kept until drained to the database.
+#[derive(Debug, Default)]
+pub struct PreimageRecorder {
+	pending: Mutex<HashMap<H256, Vec<u8>>>,
+}
+
+impl PreimageRecorder {
+	/// Note the preimage of a hashed key.
+	pub fn note(&self, hash: H256, preimage: &[u8]) {
+		self.pending.lock().entry(hash).or_insert_with(|| preimage.to_vec());
+	}
+
+	/// Get the preimage of a hash noted since the last drain.
+	pub fn get(&self, hash: &H256) -> Option<Vec<u8>> {
+		self.pending.lock().get(hash).cloned()
+	}
+
+	/// Take all the preimages noted since the last drain.
+	pub fn drain(&self) -> Vec<(H256, Vec<u8>)> {
+		self.pending.lock().drain().collect()
+	}
+}
+
 // TODO: introduce HashDBMut?
 /// DB backend wrapper for Account trie
 /// Transforms trie node keys for the database
@@ -126,10 +126,21 @@ impl Backend for ProofCheck {
 /// The proof-of-execution can be extracted with `extract_proof`.
 ///
 /// This doesn't cache anything or rely on the canonical state caches.
+///
+/// The proof can be capped in size: once loading a value would grow it beyond
+/// the cap, no more values are served from the base database.
 pub struct Proving<H> {
 	base: H, // state we're proving values from.
 	changed: MemoryDB<KeccakHasher, HashKey<KeccakHasher>, DBValue>, // changed state via insertions.
-	proof: Mutex<HashSet<DBValue>>,
+	proof: Mutex<ProofItems>,
+	cap: Option<usize>,
+}
+
+#[derive(Clone, Default)]
+struct ProofItems {
+	items: HashSet<DBValue>,
+	size: usize,
+	exceeded: bool,
 }
 
 impl<AH: AsHashDB<KeccakHasher, DBValue> + Send + Sync> AsHashDB<KeccakHasher, DBValue> for Proving<AH> {
@@ -139,9 +150,23 @@ impl<AH: AsHashDB<KeccakHasher, DBValue> + Send + Sync> AsHashDB<KeccakHasher, D
 
 impl<H: AsHashDB<KeccakHasher, DBValue> + Send + Sync> HashDB<KeccakHasher, DBValue> for Proving<H> {
 	fn get(&self, key: &H256, prefix: Prefix) -> Option<DBValue> {
+		let mut proof = self.proof.lock();
+		if proof.exceeded {
+			return self.changed.get(key, prefix);
+		}
+
 		match self.base.as_hash_db().get(key, prefix) {
 			Some(val) => {
-				self.proof.lock().insert(val.clone());
+				if !proof.items.contains(&val) {
+					let size = proof.size + val.len();
+					if self.cap.map_or(false, |cap| size > cap) {
+						proof.size = size;
+						proof.exceeded = true;
+						return None;
+					}
+					proof.size = size;
+					proof.items.insert(val.clone());
+				}
 				Some(val)
 			}
 			None => self.changed.get(key, prefix)
@@ -195,14 +220,40 @@ impl<H: AsHashDB<KeccakHasher, DBValue>> Proving<H> {
 		Proving {
 			base,
 			changed: journaldb::new_memory_db(),
-			proof: Mutex::new(HashSet::new()),
+			proof: Mutex::new(ProofItems::default()),
+			cap: None,
 		}
 	}
 
+	/// Create a new `Proving` over a base database, refusing to gather more than
+	/// `cap` bytes of proof.
+	pub fn with_cap(base: H, cap: usize) -> Self {
+		Proving {
+			cap: Some(cap),
+			..Proving::new(base)
+		}
+	}
+
+	/// Total size in bytes of the values gathered so far. Once the cap has been
+	/// exceeded this includes the value that was refused.
+	pub fn proof_size(&self) -> usize {
+		self.proof.lock().size
+	}
+
+	/// Maximum size of the proof in bytes, if capped.
+	pub fn cap(&self) -> Option<usize> {
+		self.cap
+	}
+
+	/// Whether a value was refused because the proof would have outgrown the cap.
+	pub fn cap_exceeded(&self) -> bool {
+		self.proof.lock().exceeded
+	}
+
 	/// Consume the backend, extracting the gathered proof in lexicographical order
 	/// by value.
 	pub fn extract_proof(self) -> Vec<DBValue> {
-		self.proof.into_inner().into_iter().collect()
+		self.proof.into_inner().items.into_iter().collect()
 	}
 }
 
@@ -212,6 +263,7 @@ impl<H: AsHashDB<KeccakHasher, DBValue> + Clone> Clone for Proving<H> {
 			base: self.base.clone(),
 			changed: self.changed.clone(),
 			proof: Mutex::new(self.proof.lock().clone()),
+			cap: self.cap,
 		}
 	}
 }
@@ -38,7 +38,7 @@ use ethereum_types::{Address, H256, U256};
 use ethtrie::{TrieDB, Result as TrieResult};
 use trie_vm_factories::{Factories, VmFactory};
 use hash_db::HashDB;
-use keccak_hash::{KECCAK_EMPTY, KECCAK_NULL_RLP};
+use keccak_hash::{KECCAK_EMPTY, KECCAK_NULL_RLP, keccak};
 use keccak_hasher::KeccakHasher;
 use kvdb::DBValue;
 use log::{warn, trace};
@@ -711,6 +711,12 @@ impl<B: Backend> State<B> {
 		for (address, ref mut a) in accounts.iter_mut().filter(|&(_, ref a)| a.is_dirty()) {
 			if let Some(ref mut account) = a.account {
 				let addr_hash = account.address_hash(address);
+				if let Some(ref preimages) = self.factories.preimages {
+					preimages.note(addr_hash, address.as_bytes());
+					for key in account.storage_changes().keys() {
+						preimages.note(keccak(key), key.as_bytes());
+					}
+				}
 				{
 					let mut account_db = self.factories.accountdb.create(self.db.as_hash_db_mut(), addr_hash);
 					account.commit_storage(&self.factories.trie, account_db.as_hash_db_mut())?;
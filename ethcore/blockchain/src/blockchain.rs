@@ -17,9 +17,11 @@
 //! Blockchain database.
 
 use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use std::{mem, io};
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use ansi_term::Colour;
 use blooms_db;
@@ -55,7 +57,7 @@ use rlp_compress::{compress, decompress, blocks_swapper};
 
 use crate::best_block::{BestBlock, BestAncientBlock};
 use crate::update::{ExtrasUpdate, ExtrasInsert};
-use crate::{CacheSize, Config};
+use crate::{CacheSize, CacheUsage, CacheReport, CacheLimit, CacheLimits, Config};
 
 /// Database backing `BlockChain`.
 pub trait BlockChainDB: Send + Sync {
@@ -214,6 +216,107 @@ enum CacheId {
 	BlockReceipts(H256),
 }
 
+/// Cache managers, one per cache, each enforcing its own size limits.
+struct CacheManagers {
+	block_headers: CacheManager<H256>,
+	block_bodies: CacheManager<H256>,
+	block_details: CacheManager<H256>,
+	block_hashes: CacheManager<BlockNumber>,
+	transaction_addresses: CacheManager<H256>,
+	block_receipts: CacheManager<H256>,
+}
+
+impl CacheManagers {
+	fn new(limits: &CacheLimits) -> Self {
+		fn manager<T: Eq + Hash>(limit: &CacheLimit) -> CacheManager<T> {
+			// 400 is the average size of the key
+			CacheManager::new(limit.pref, limit.max, 400)
+		}
+
+		CacheManagers {
+			block_headers: manager(&limits.block_headers),
+			block_bodies: manager(&limits.block_bodies),
+			block_details: manager(&limits.block_details),
+			block_hashes: manager(&limits.block_hashes),
+			transaction_addresses: manager(&limits.transaction_addresses),
+			block_receipts: manager(&limits.block_receipts),
+		}
+	}
+
+	fn note_used(&mut self, id: CacheId) {
+		match id {
+			CacheId::BlockHeader(h) => self.block_headers.note_used(h),
+			CacheId::BlockBody(h) => self.block_bodies.note_used(h),
+			CacheId::BlockDetails(h) => self.block_details.note_used(h),
+			CacheId::BlockHashes(n) => self.block_hashes.note_used(n),
+			CacheId::TransactionAddresses(h) => self.transaction_addresses.note_used(h),
+			CacheId::BlockReceipts(h) => self.block_receipts.note_used(h),
+		}
+	}
+}
+
+/// Evicts unused entries of a single cache once it grows over its limits.
+fn collect_cache_garbage<K, V>(cache_man: &mut CacheManager<K>, cache: &mut HashMap<K, V>) where
+	K: Eq + Hash,
+	HashMap<K, V>: MallocSizeOf,
+{
+	let current_size = cache.size_of(&mut new_malloc_size_ops());
+	cache_man.collect_garbage(current_size, |ids| {
+		for id in &ids {
+			cache.remove(id);
+		}
+		cache.shrink_to_fit();
+		cache.size_of(&mut new_malloc_size_ops())
+	});
+}
+
+/// Hits and misses of reads from a single cache.
+#[derive(Default)]
+struct CacheStats {
+	hits: AtomicUsize,
+	misses: AtomicUsize,
+}
+
+impl CacheStats {
+	fn note_hit(&self) {
+		self.hits.fetch_add(1, Ordering::Relaxed);
+	}
+
+	fn note_miss(&self) {
+		self.misses.fetch_add(1, Ordering::Relaxed);
+	}
+
+	/// Note a read of `key`, which is about to be served from `cache` or the database.
+	fn note_read<K: Eq + Hash, V>(&self, cache: &RwLock<HashMap<K, V>>, key: &K) {
+		if cache.read().contains_key(key) {
+			self.note_hit();
+		} else {
+			self.note_miss();
+		}
+	}
+
+	fn usage(&self, used: usize, limit: &CacheLimit) -> CacheUsage {
+		CacheUsage {
+			used,
+			pref: limit.pref,
+			max: limit.max,
+			hits: self.hits.load(Ordering::Relaxed),
+			misses: self.misses.load(Ordering::Relaxed),
+		}
+	}
+}
+
+/// Read statistics of all the caches.
+#[derive(Default)]
+struct CachesStats {
+	block_headers: CacheStats,
+	block_bodies: CacheStats,
+	block_details: CacheStats,
+	block_hashes: CacheStats,
+	transaction_addresses: CacheStats,
+	block_receipts: CacheStats,
+}
+
 /// Structure providing fast access to blockchain data.
 ///
 /// **Does not do input data verification.**
@@ -240,13 +343,20 @@ pub struct BlockChain {
 
 	db: Arc<dyn BlockChainDB>,
 
-	cache_man: Mutex<CacheManager<CacheId>>,
+	cache_limits: CacheLimits,
+	cache_man: Mutex<CacheManagers>,
+	cache_stats: CachesStats,
 
 	pending_best_ancient_block: RwLock<Option<Option<BestAncientBlock>>>,
 	pending_best_block: RwLock<Option<BestBlock>>,
 	pending_block_hashes: RwLock<HashMap<BlockNumber, H256>>,
 	pending_block_details: RwLock<HashMap<H256, BlockDetails>>,
 	pending_transaction_addresses: RwLock<HashMap<H256, Option<TransactionAddress>>>,
+	pending_epoch_transitions: RwLock<HashSet<u64>>,
+	pending_epoch_compactions: RwLock<HashSet<u64>>,
+
+	// Number of stored epoch transition entries, updated on `commit`.
+	epoch_transitions_count: AtomicUsize,
 }
 
 impl BlockProvider for BlockChain {
@@ -281,6 +391,7 @@ impl BlockProvider for BlockChain {
 		{
 			let read = self.block_headers.read();
 			if let Some(v) = read.get(hash) {
+				self.cache_stats.block_headers.note_hit();
 				return Some(v.clone());
 			}
 		}
@@ -294,6 +405,7 @@ impl BlockProvider for BlockChain {
 		}
 
 		// Read from DB and populate cache
+		self.cache_stats.block_headers.note_miss();
 		let b = self.db.key_value().get(db::COL_HEADERS, hash.as_bytes())
 			.expect("Low level database error when fetching block header data. Some issue with disk?")?;
 
@@ -311,6 +423,7 @@ impl BlockProvider for BlockChain {
 		{
 			let read = self.block_bodies.read();
 			if let Some(v) = read.get(hash) {
+				self.cache_stats.block_bodies.note_hit();
 				return Some(v.clone());
 			}
 		}
@@ -324,6 +437,7 @@ impl BlockProvider for BlockChain {
 		}
 
 		// Read from DB and populate cache
+		self.cache_stats.block_bodies.note_miss();
 		let b = self.db.key_value().get(db::COL_BODIES, hash.as_bytes())
 			.expect("Low level database error when fetching block body data. Some issue with disk?")?;
 
@@ -337,6 +451,7 @@ impl BlockProvider for BlockChain {
 
 	/// Get the familial details concerning a block.
 	fn block_details(&self, hash: &H256) -> Option<BlockDetails> {
+		self.cache_stats.block_details.note_read(&self.block_details, hash);
 		let result = self.db.key_value().read_with_cache(db::COL_EXTRA, &self.block_details, hash)?;
 		self.cache_man.lock().note_used(CacheId::BlockDetails(*hash));
 		Some(result)
@@ -344,6 +459,7 @@ impl BlockProvider for BlockChain {
 
 	/// Get the hash of given block's number.
 	fn block_hash(&self, index: BlockNumber) -> Option<H256> {
+		self.cache_stats.block_hashes.note_read(&self.block_hashes, &index);
 		let result = self.db.key_value().read_with_cache(db::COL_EXTRA, &self.block_hashes, &index)?;
 		self.cache_man.lock().note_used(CacheId::BlockHashes(index));
 		Some(result)
@@ -351,6 +467,7 @@ impl BlockProvider for BlockChain {
 
 	/// Get the address of transaction with given hash.
 	fn transaction_address(&self, hash: &H256) -> Option<TransactionAddress> {
+		self.cache_stats.transaction_addresses.note_read(&self.transaction_addresses, hash);
 		let result = self.db.key_value().read_with_cache(db::COL_EXTRA, &self.transaction_addresses, hash)?;
 		self.cache_man.lock().note_used(CacheId::TransactionAddresses(*hash));
 		Some(result)
@@ -358,6 +475,7 @@ impl BlockProvider for BlockChain {
 
 	/// Get receipts of block with given hash.
 	fn block_receipts(&self, hash: &H256) -> Option<BlockReceipts> {
+		self.cache_stats.block_receipts.note_read(&self.block_receipts, hash);
 		let result = self.db.key_value().read_with_cache(db::COL_EXTRA, &self.block_receipts, hash)?;
 		self.cache_man.lock().note_used(CacheId::BlockReceipts(*hash));
 		Some(result)
@@ -545,8 +663,11 @@ impl<'a> Iterator for EpochTransitionIter<'a> {
 impl BlockChain {
 	/// Create new instance of blockchain from given Genesis.
 	pub fn new(config: Config, genesis: &[u8], db: Arc<dyn BlockChainDB>) -> BlockChain {
-		// 400 is the average size of the key
-		let cache_man = CacheManager::new(config.pref_cache_size, config.max_cache_size, 400);
+		let cache_limits = config.cache_limits();
+		let cache_man = CacheManagers::new(&cache_limits);
+		let epoch_transitions_count = db.key_value().iter_with_prefix(db::COL_EXTRA, &EPOCH_KEY_PREFIX[..])
+			.take_while(|(key, _)| key.starts_with(&EPOCH_KEY_PREFIX[..]))
+			.count();
 
 		let mut bc = BlockChain {
 			first_block: None,
@@ -564,12 +685,17 @@ impl BlockChain {
 			transaction_addresses: RwLock::new(HashMap::new()),
 			block_receipts: RwLock::new(HashMap::new()),
 			db: db.clone(),
+			cache_limits,
 			cache_man: Mutex::new(cache_man),
+			cache_stats: Default::default(),
 			pending_best_ancient_block: RwLock::new(None),
 			pending_best_block: RwLock::new(None),
 			pending_block_hashes: RwLock::new(HashMap::new()),
 			pending_block_details: RwLock::new(HashMap::new()),
 			pending_transaction_addresses: RwLock::new(HashMap::new()),
+			pending_epoch_transitions: RwLock::new(HashSet::new()),
+			pending_epoch_compactions: RwLock::new(HashSet::new()),
+			epoch_transitions_count: AtomicUsize::new(epoch_transitions_count),
 		};
 
 		// load best block
@@ -986,12 +1112,16 @@ impl BlockChain {
 	/// and epoch transition object.
 	///
 	/// The block the transition occurred at should have already been inserted into the chain.
+	/// Call `commit` once the batch is written to update `epoch_transitions_count`.
 	pub fn insert_epoch_transition(&self, batch: &mut DBTransaction, epoch_num: u64, transition: EpochTransition) {
 		let mut transitions = match self.db.key_value().read(db::COL_EXTRA, &epoch_num) {
 			Some(existing) => existing,
-			None => EpochTransitions {
-				number: epoch_num,
-				candidates: Vec::with_capacity(1),
+			None => {
+				self.pending_epoch_transitions.write().insert(epoch_num);
+				EpochTransitions {
+					number: epoch_num,
+					candidates: Vec::with_capacity(1),
+				}
 			}
 		};
 
@@ -1013,6 +1143,64 @@ impl BlockChain {
 		}
 	}
 
+	/// Iterate over the canonical epoch transitions which occurred within the
+	/// given (inclusive) range of block numbers, in ascending order.
+	pub fn epoch_transitions_range(&self, from_block: BlockNumber, to_block: BlockNumber) -> impl Iterator<Item = (u64, EpochTransition)> + '_ {
+		self.epoch_transitions()
+			.skip_while(move |(_, t)| t.block_number < from_block)
+			.take_while(move |(_, t)| t.block_number <= to_block)
+	}
+
+	/// Number of stored epoch transitions, including those which never occurred
+	/// on the canonical chain. Counted from the database once on startup and
+	/// updated with every committed insertion and compaction.
+	pub fn epoch_transitions_count(&self) -> u64 {
+		self.epoch_transitions_count.load(Ordering::SeqCst) as u64
+	}
+
+	/// Remove epoch transitions superseded before block `up_to`, returning the number of
+	/// epoch entries scheduled for deletion in `batch`.
+	///
+	/// The transition in force at `up_to` (the latest canonical one at or before it) is
+	/// always retained along with every later entry, since verifying blocks after `up_to`
+	/// requires its proof. The genesis epoch and the latest canonical transition are never
+	/// removed, whatever `up_to` is. Callers must only compact up to a block which is covered
+	/// by a snapshot and below which history may be pruned.
+	/// Call `commit` once the batch is written to update `epoch_transitions_count`.
+	pub fn compact_epoch_transitions(&self, batch: &mut DBTransaction, up_to: BlockNumber) -> usize {
+		let up_to = ::std::cmp::min(up_to, self.best_block_number());
+		let (retained_epoch, retained) = match self.epoch_transitions_range(0, up_to).last() {
+			Some(retained) => retained,
+			None => return 0,
+		};
+		let latest_epoch = self.epoch_transitions().last().map_or(retained_epoch, |(num, _)| num);
+
+		let mut removed = 0;
+		let mut pending_compactions = self.pending_epoch_compactions.write();
+		for (key, val) in self.db.key_value().iter_with_prefix(db::COL_EXTRA, &EPOCH_KEY_PREFIX[..]) {
+			if !key.starts_with(&EPOCH_KEY_PREFIX[..]) {
+				break
+			}
+
+			let transitions: EpochTransitions = ::rlp::decode(&val[..]).expect("decode error: the db is corrupted or the data structure has changed");
+			let protected = transitions.number == 0
+				|| transitions.number >= retained_epoch
+				|| transitions.number == latest_epoch;
+			let superseded = !protected && transitions.candidates.iter()
+				.all(|c| c.block_number < retained.block_number);
+
+			if superseded {
+				trace!(target: "blockchain", "Compacting epoch transition {}", transitions.number);
+				batch.delete(db::COL_EXTRA, &key);
+				pending_compactions.insert(transitions.number);
+				removed += 1;
+			}
+		}
+
+		debug!(target: "blockchain", "Compacting {} epoch transitions before block #{}", removed, retained.block_number);
+		removed
+	}
+
 	/// Get a specific epoch transition by block number and provided block hash.
 	pub fn epoch_transition(&self, block_num: u64, block_hash: H256) -> Option<EpochTransition> {
 		trace!(target: "blockchain", "Loading epoch transition at block {}, {}",
@@ -1295,6 +1483,19 @@ impl BlockChain {
 		for hash in pending_block_hashes {
 			cache_man.note_used(CacheId::BlockDetails(hash));
 		}
+
+		// only count transitions which made it to the database, the batch may have been dropped.
+		let added = mem::replace(&mut *self.pending_epoch_transitions.write(), HashSet::new())
+			.into_iter()
+			.filter(|epoch_num| self.db.key_value().exists::<EpochTransitions, _>(db::COL_EXTRA, epoch_num))
+			.count();
+		self.epoch_transitions_count.fetch_add(added, Ordering::SeqCst);
+
+		let removed = mem::replace(&mut *self.pending_epoch_compactions.write(), HashSet::new())
+			.into_iter()
+			.filter(|epoch_num| !self.db.key_value().exists::<EpochTransitions, _>(db::COL_EXTRA, epoch_num))
+			.count();
+		self.epoch_transitions_count.fetch_sub(removed, Ordering::SeqCst);
 	}
 
 	/// Iterator that lists `first` and then all of `first`'s ancestors, by hash.
@@ -1541,10 +1742,25 @@ impl BlockChain {
 		}
 	}
 
+	/// Get current usage, limits and hit rates of each cache.
+	pub fn cache_report(&self) -> CacheReport {
+		let mut ops = new_malloc_size_ops();
+		let limits = &self.cache_limits;
+		let stats = &self.cache_stats;
+		CacheReport {
+			block_headers: stats.block_headers.usage(self.block_headers.size_of(&mut ops), &limits.block_headers),
+			block_bodies: stats.block_bodies.usage(self.block_bodies.size_of(&mut ops), &limits.block_bodies),
+			block_details: stats.block_details.usage(self.block_details.size_of(&mut ops), &limits.block_details),
+			block_hashes: stats.block_hashes.usage(self.block_hashes.size_of(&mut ops), &limits.block_hashes),
+			transaction_addresses: stats.transaction_addresses.usage(self.transaction_addresses.size_of(&mut ops), &limits.transaction_addresses),
+			block_receipts: stats.block_receipts.usage(self.block_receipts.size_of(&mut ops), &limits.block_receipts),
+		}
+	}
+
 	/// Ticks our cache system and throws out any old data.
+	///
+	/// Each cache is kept within its own limits independently of the others.
 	pub fn collect_garbage(&self) {
-		let current_size = self.cache_size().total();
-
 		let mut block_headers = self.block_headers.write();
 		let mut block_bodies = self.block_bodies.write();
 		let mut block_details = self.block_details.write();
@@ -1553,33 +1769,12 @@ impl BlockChain {
 		let mut block_receipts = self.block_receipts.write();
 
 		let mut cache_man = self.cache_man.lock();
-		cache_man.collect_garbage(current_size, | ids | {
-			for id in &ids {
-				match *id {
-					CacheId::BlockHeader(ref h) => { block_headers.remove(h); },
-					CacheId::BlockBody(ref h) => { block_bodies.remove(h); },
-					CacheId::BlockDetails(ref h) => { block_details.remove(h); }
-					CacheId::BlockHashes(ref h) => { block_hashes.remove(h); }
-					CacheId::TransactionAddresses(ref h) => { transaction_addresses.remove(h); }
-					CacheId::BlockReceipts(ref h) => { block_receipts.remove(h); }
-				}
-			}
-
-			block_headers.shrink_to_fit();
-			block_bodies.shrink_to_fit();
-			block_details.shrink_to_fit();
-			block_hashes.shrink_to_fit();
-			transaction_addresses.shrink_to_fit();
-			block_receipts.shrink_to_fit();
-
-			let mut ops = new_malloc_size_ops();
-			block_headers.size_of(&mut ops) +
-			block_bodies.size_of(&mut ops) +
-			block_details.size_of(&mut ops) +
-			block_hashes.size_of(&mut ops) +
-			transaction_addresses.size_of(&mut ops) +
-			block_receipts.size_of(&mut ops)
-		});
+		collect_cache_garbage(&mut cache_man.block_headers, &mut *block_headers);
+		collect_cache_garbage(&mut cache_man.block_bodies, &mut *block_bodies);
+		collect_cache_garbage(&mut cache_man.block_details, &mut *block_details);
+		collect_cache_garbage(&mut cache_man.block_hashes, &mut *block_hashes);
+		collect_cache_garbage(&mut cache_man.transaction_addresses, &mut *transaction_addresses);
+		collect_cache_garbage(&mut cache_man.block_receipts, &mut *block_receipts);
 	}
 
 	/// Create a block body from a block.
@@ -1761,6 +1956,110 @@ mod tests {
 		assert_eq!(bc.block_hash(2), None);
 	}
 
+	#[test]
+	fn should_evict_caches_independently() {
+		let genesis = BlockBuilder::genesis();
+		let first_10 = genesis.add_blocks(10);
+		let generator = BlockGenerator::new(vec![first_10]);
+
+		let unlimited = CacheLimit { pref: 1 << 24, max: 1 << 24 };
+		let config = Config {
+			cache_limits: Some(CacheLimits {
+				block_headers: CacheLimit { pref: 0, max: 0 },
+				block_bodies: unlimited,
+				block_details: unlimited,
+				block_hashes: unlimited,
+				transaction_addresses: unlimited,
+				block_receipts: unlimited,
+			}),
+			..Default::default()
+		};
+
+		let db = new_db();
+		let bc = BlockChain::new(config, genesis.last().encoded().raw(), db.clone());
+
+		let mut block_hashes = vec![];
+		for block in generator {
+			block_hashes.push(block.hash());
+			insert_block(&db, &bc, block.encoded(), vec![]);
+		}
+
+		// skewed load, reading headers only
+		for hash in &block_hashes {
+			assert!(bc.block_header_data(hash).is_some());
+		}
+
+		let before = bc.cache_report();
+		assert!(before.block_headers.used > 0);
+		assert!(before.block_details.used > 0);
+
+		bc.collect_garbage();
+
+		let after = bc.cache_report();
+		assert!(after.block_headers.used < before.block_headers.used);
+		assert_eq!(after.block_details.used, before.block_details.used);
+		assert_eq!(after.block_hashes.used, before.block_hashes.used);
+
+		// details are still served from the cache
+		let hits = after.block_details.hits;
+		assert!(bc.block_details(&block_hashes[0]).is_some());
+		assert_eq!(bc.cache_report().block_details.hits, hits + 1);
+	}
+
+	#[test]
+	fn cache_report_should_match_accounting() {
+		let genesis = BlockBuilder::genesis();
+		let first_10 = genesis.add_blocks(10);
+		let generator = BlockGenerator::new(vec![first_10]);
+
+		let db = new_db();
+		let bc = new_chain(genesis.last().encoded(), db.clone());
+
+		let mut block_hashes = vec![];
+		for block in generator {
+			block_hashes.push(block.hash());
+			insert_block(&db, &bc, block.encoded(), vec![]);
+		}
+
+		// first read goes to the database, second one is served from the cache
+		assert!(bc.block_header_data(&block_hashes[0]).is_some());
+		assert!(bc.block_header_data(&block_hashes[0]).is_some());
+		assert!(bc.block_body(&block_hashes[0]).is_some());
+
+		let report = bc.cache_report();
+		let size = bc.cache_size();
+		assert_eq!(report.block_headers.used + report.block_bodies.used, size.blocks);
+		assert_eq!(report.block_details.used, size.block_details);
+		assert_eq!(report.transaction_addresses.used, size.transaction_addresses);
+		assert_eq!(report.block_receipts.used, size.block_receipts);
+		assert_eq!(report.total(), size.total() + report.block_hashes.used);
+
+		assert_eq!((report.block_headers.hits, report.block_headers.misses), (1, 1));
+		assert_eq!(report.block_headers.hit_rate(), 0.5);
+		assert_eq!((report.block_bodies.hits, report.block_bodies.misses), (0, 1));
+
+		let limits = Config::default().cache_limits();
+		assert_eq!((report.block_headers.pref, report.block_headers.max), (limits.block_headers.pref, limits.block_headers.max));
+	}
+
+	#[test]
+	fn cache_limits_split_whole_aggregate_size() {
+		let limits = CacheLimits::from_total(1600, 3200);
+		let all = [
+			limits.block_headers,
+			limits.block_bodies,
+			limits.block_details,
+			limits.block_hashes,
+			limits.transaction_addresses,
+			limits.block_receipts,
+		];
+
+		assert_eq!(all.iter().map(|limit| limit.pref).sum::<usize>(), 1600);
+		assert_eq!(all.iter().map(|limit| limit.max).sum::<usize>(), 3200);
+		assert!(limits.block_bodies.pref > limits.block_headers.pref);
+		assert!(limits.block_hashes.pref < limits.transaction_addresses.pref);
+	}
+
 	#[test]
 	fn check_ancestry_iter() {
 		let genesis = BlockBuilder::genesis();
@@ -2478,6 +2777,117 @@ mod tests {
 		assert_eq!(bc.epoch_transitions().map(|(i, _)| i).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
 	}
 
+	#[test]
+	fn epoch_transitions_range_and_count() {
+		use common_types::engines::epoch::Transition as EpochTransition;
+
+		let genesis = BlockBuilder::genesis();
+		let next_10 = genesis.add_blocks(10);
+		let generator = BlockGenerator::new(iter::once(next_10));
+
+		let db = new_db();
+		let bc = new_chain(genesis.last().encoded(), db.clone());
+
+		let mut batch = db.key_value().transaction();
+		bc.insert_epoch_transition(&mut batch, 0, EpochTransition {
+			block_hash: bc.genesis_hash(),
+			block_number: 0,
+			proof: vec![],
+		});
+		db.key_value().write(batch).unwrap();
+		bc.commit();
+		assert_eq!(bc.epoch_transitions_count(), 1);
+
+		for block in generator {
+			insert_block(&db, &bc, block.encoded(), vec![]);
+		}
+
+		// validator set changes at blocks 2, 5 and 8, the one at 5 inserted twice.
+		let mut batch = db.key_value().transaction();
+		for &n in &[2u64, 5, 5, 8] {
+			bc.insert_epoch_transition(&mut batch, n, EpochTransition {
+				block_hash: bc.block_hash(n).unwrap(),
+				block_number: n,
+				proof: vec![n as u8],
+			});
+		}
+
+		// nothing is counted before the batch is committed.
+		assert_eq!(bc.epoch_transitions_count(), 1);
+		db.key_value().write(batch).unwrap();
+		bc.commit();
+
+		assert_eq!(bc.epoch_transitions_count(), 4);
+		assert_eq!(new_chain(genesis.last().encoded(), db.clone()).epoch_transitions_count(), 4);
+		assert_eq!(bc.epoch_transitions_range(1, 8).map(|(_, t)| t.block_number).collect::<Vec<_>>(), vec![2, 5, 8]);
+		assert_eq!(bc.epoch_transitions_range(3, 7).map(|(_, t)| t.proof).collect::<Vec<_>>(), vec![vec![5]]);
+		assert_eq!(bc.epoch_transitions_range(9, 10).count(), 0);
+
+		// a dropped batch doesn't change the count.
+		let mut batch = db.key_value().transaction();
+		bc.insert_epoch_transition(&mut batch, 9, EpochTransition {
+			block_hash: bc.block_hash(9).unwrap(),
+			block_number: 9,
+			proof: vec![],
+		});
+		drop(batch);
+		bc.commit();
+		assert_eq!(bc.epoch_transitions_count(), 4);
+	}
+
+	#[test]
+	fn epoch_transitions_compaction() {
+		use common_types::engines::epoch::Transition as EpochTransition;
+
+		let genesis = BlockBuilder::genesis();
+		let next_10 = genesis.add_blocks(10);
+		let generator = BlockGenerator::new(iter::once(next_10));
+
+		let db = new_db();
+		let bc = new_chain(genesis.last().encoded(), db.clone());
+		for block in generator {
+			insert_block(&db, &bc, block.encoded(), vec![]);
+		}
+
+		// validator set changes at genesis and blocks 2, 5 and 8.
+		let mut batch = db.key_value().transaction();
+		for &n in &[0u64, 2, 5, 8] {
+			bc.insert_epoch_transition(&mut batch, n, EpochTransition {
+				block_hash: bc.block_hash(n).unwrap(),
+				block_number: n,
+				proof: vec![n as u8],
+			});
+		}
+		db.key_value().write(batch).unwrap();
+		bc.commit();
+		assert_eq!(bc.epoch_transitions_count(), 4);
+
+		// compacting up to block 7 must keep the transition at 5 which is still in force.
+		let mut batch = db.key_value().transaction();
+		assert_eq!(bc.compact_epoch_transitions(&mut batch, 7), 1);
+		db.key_value().write(batch).unwrap();
+		bc.commit();
+
+		assert_eq!(bc.epoch_transitions().map(|(_, t)| t.block_number).collect::<Vec<_>>(), vec![0, 5, 8]);
+		assert_eq!(bc.epoch_transitions_count(), 3);
+		assert_eq!(new_chain(genesis.last().encoded(), db.clone()).epoch_transitions_count(), 3);
+		assert_eq!(bc.epoch_transition_for(bc.block_hash(7).unwrap()).unwrap().block_number, 5);
+
+		// compacting past the best block never drops the latest transition.
+		let mut batch = db.key_value().transaction();
+		assert_eq!(bc.compact_epoch_transitions(&mut batch, u64::max_value()), 1);
+		db.key_value().write(batch).unwrap();
+		bc.commit();
+
+		assert_eq!(bc.epoch_transitions().map(|(_, t)| t.block_number).collect::<Vec<_>>(), vec![0, 8]);
+		assert_eq!(bc.epoch_transitions_count(), 2);
+		assert_eq!(bc.epoch_transition_for(bc.block_hash(9).unwrap()).unwrap().block_number, 8);
+
+		// compacting again is a no-op.
+		let mut batch = db.key_value().transaction();
+		assert_eq!(bc.compact_epoch_transitions(&mut batch, u64::max_value()), 0);
+	}
+
 	#[test]
 	fn epoch_transition_for() {
 		use common_types::engines::epoch::Transition as EpochTransition;
@@ -33,3 +33,57 @@ impl CacheSize {
 		self.blocks + self.block_details + self.transaction_addresses + self.block_receipts
 	}
 }
+
+/// Memory usage and effectiveness of a single blockchain cache.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct CacheUsage {
+	/// Bytes currently used by the cache.
+	pub used: usize,
+	/// Preferred cache size in bytes.
+	pub pref: usize,
+	/// Maximum cache size in bytes.
+	pub max: usize,
+	/// Number of reads served from the cache.
+	pub hits: usize,
+	/// Number of reads which had to go to the database.
+	pub misses: usize,
+}
+
+impl CacheUsage {
+	/// Fraction of reads served from the cache, `0.0` if there were no reads.
+	pub fn hit_rate(&self) -> f64 {
+		match self.hits + self.misses {
+			0 => 0.0,
+			reads => self.hits as f64 / reads as f64,
+		}
+	}
+}
+
+/// Per-cache report of blockchain's in-memory caches.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct CacheReport {
+	/// Block headers cache.
+	pub block_headers: CacheUsage,
+	/// Block bodies cache.
+	pub block_bodies: CacheUsage,
+	/// Block details cache.
+	pub block_details: CacheUsage,
+	/// Block hashes cache.
+	pub block_hashes: CacheUsage,
+	/// Transaction addresses cache.
+	pub transaction_addresses: CacheUsage,
+	/// Block receipts cache.
+	pub block_receipts: CacheUsage,
+}
+
+impl CacheReport {
+	/// Total amount of bytes used by all the caches.
+	pub fn total(&self) -> usize {
+		self.block_headers.used
+			+ self.block_bodies.used
+			+ self.block_details.used
+			+ self.block_hashes.used
+			+ self.transaction_addresses.used
+			+ self.block_receipts.used
+	}
+}
@@ -16,6 +16,71 @@
 
 //! Blockchain configuration.
 
+/// Preferred and maximum size of a single cache in bytes.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CacheLimit {
+	/// Preferred cache size in bytes.
+	pub pref: usize,
+	/// Maximum cache size in bytes.
+	pub max: usize,
+}
+
+/// Size limits of each of the blockchain caches.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CacheLimits {
+	/// Block headers cache limit.
+	pub block_headers: CacheLimit,
+	/// Block bodies cache limit.
+	pub block_bodies: CacheLimit,
+	/// Block details cache limit.
+	pub block_details: CacheLimit,
+	/// Block hashes cache limit.
+	pub block_hashes: CacheLimit,
+	/// Transaction addresses cache limit.
+	pub transaction_addresses: CacheLimit,
+	/// Block receipts cache limit.
+	pub block_receipts: CacheLimit,
+}
+
+// Shares of the aggregate cache size given to each cache, out of `CACHE_SHARES_TOTAL`.
+// The split weighs the size of a cached entry against how often it's needed.
+/// Headers are a few hundred bytes each, but are read on every import, fork choice and ancestry walk.
+const BLOCK_HEADERS_SHARE: usize = 3;
+/// Bodies hold whole-block transaction payloads, the largest entries by far.
+const BLOCK_BODIES_SHARE: usize = 4;
+/// Details are small, but are needed as often as headers.
+const BLOCK_DETAILS_SHARE: usize = 3;
+/// Block hashes are a single `H256` per block number.
+const BLOCK_HASHES_SHARE: usize = 1;
+/// Transaction addresses are tiny, but there is one per transaction.
+const TRANSACTION_ADDRESSES_SHARE: usize = 2;
+/// Receipts hold the logs of a whole block, second in size only to bodies.
+const BLOCK_RECEIPTS_SHARE: usize = 3;
+
+/// Sum of all the shares, so the caches together use the whole aggregate size.
+const CACHE_SHARES_TOTAL: usize = BLOCK_HEADERS_SHARE + BLOCK_BODIES_SHARE + BLOCK_DETAILS_SHARE
+	+ BLOCK_HASHES_SHARE + TRANSACTION_ADDRESSES_SHARE + BLOCK_RECEIPTS_SHARE;
+
+impl CacheLimits {
+	/// Split aggregate preferred and maximum sizes between the caches.
+	/// See `BLOCK_HEADERS_SHARE` and the following constants for the ratios.
+	pub fn from_total(pref_cache_size: usize, max_cache_size: usize) -> Self {
+		let limit = |share: usize| CacheLimit {
+			pref: pref_cache_size / CACHE_SHARES_TOTAL * share,
+			max: max_cache_size / CACHE_SHARES_TOTAL * share,
+		};
+
+		CacheLimits {
+			block_headers: limit(BLOCK_HEADERS_SHARE),
+			block_bodies: limit(BLOCK_BODIES_SHARE),
+			block_details: limit(BLOCK_DETAILS_SHARE),
+			block_hashes: limit(BLOCK_HASHES_SHARE),
+			transaction_addresses: limit(TRANSACTION_ADDRESSES_SHARE),
+			block_receipts: limit(BLOCK_RECEIPTS_SHARE),
+		}
+	}
+}
+
 /// Blockchain configuration.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Config {
@@ -23,6 +88,16 @@ pub struct Config {
 	pub pref_cache_size: usize,
 	/// Maximum cache size in bytes.
 	pub max_cache_size: usize,
+	/// Size limits of each cache, if `None` they're derived from `pref_cache_size` and `max_cache_size`.
+	pub cache_limits: Option<CacheLimits>,
+}
+
+impl Config {
+	/// Size limits of each of the caches.
+	pub fn cache_limits(&self) -> CacheLimits {
+		self.cache_limits.clone()
+			.unwrap_or_else(|| CacheLimits::from_total(self.pref_cache_size, self.max_cache_size))
+	}
 }
 
 impl Default for Config {
@@ -30,6 +105,7 @@ impl Default for Config {
 		Config {
 			pref_cache_size: 1 << 14,
 			max_cache_size: 1 << 20,
+			cache_limits: None,
 		}
 	}
 }
@@ -31,8 +31,8 @@ pub mod generator;
 
 pub use crate::{
 	blockchain::{BlockProvider, BlockChain, BlockChainDB, BlockChainDBHandler},
-	cache::CacheSize,
-	config::Config,
+	cache::{CacheSize, CacheUsage, CacheReport},
+	config::{Config, CacheLimit, CacheLimits},
 	update::ExtrasInsert,
 };
 pub use ethcore_db::keys::{BlockReceipts, BlockDetails, TransactionAddress, BlockNumberKey};
@@ -20,7 +20,7 @@ use std::{
 };
 
 use account_state::state::StateInfo;
-use blockchain::BlockProvider;
+use blockchain::{BlockProvider, CacheReport};
 use bytes::Bytes;
 use call_contract::CallContract;
 use registrar::RegistrarClient;
@@ -30,10 +30,10 @@ use common_types::{
 	blockchain_info::BlockChainInfo,
 	BlockNumber,
 	call_analytics::CallAnalytics,
-	chain_notify::{NewBlocks, ChainMessageType},
-	client_types::Mode,
+	chain_notify::{NewBlocks, ChainMessageType, ChainEvent, ChainEventsError},
+	client_types::{Mode, ExecutionProof, ProveError},
 	encoded,
-	engines::{epoch::Transition as EpochTransition, machine::Executed},
+	engines::{epoch::Transition as EpochTransition, machine::Executed, params::ForkActivation},
 	errors::{EthcoreError, EthcoreResult},
 	filter::Filter,
 	header::Header,
@@ -53,7 +53,7 @@ use kvdb::DBValue;
 use stats;
 use trace::{
 	FlatTrace,
-	localized::LocalizedTrace,
+	localized::{LocalizedTrace, TracePage},
 	VMTrace,
 };
 use common_types::{
@@ -306,6 +306,30 @@ pub trait BlockChainClient:
 	/// Get latest state node
 	fn state_data(&self, hash: &H256) -> Option<Bytes>;
 
+	/// Get the preimage of a hashed address or storage key, if preimages are recorded.
+	fn preimage(&self, _hash: &H256) -> Option<Bytes> {
+		None
+	}
+
+	/// Get usage, limits and hit rates of each of the blockchain caches.
+	fn blockchain_cache_report(&self) -> CacheReport;
+
+	/// Get the numbers and hashes of canonical blocks in `from..=to` authored by `author`,
+	/// in ascending order and at most `limit` of them. `None` if the author index is disabled.
+	fn blocks_authored_by(&self, _author: &Address, _from: BlockNumber, _to: BlockNumber, _limit: usize) -> Option<Vec<(BlockNumber, H256)>> {
+		None
+	}
+
+	/// Get the recorded changes of the canonical chain after `sequence`, oldest first and at most
+	/// `limit` of them. A consumer keeps the sequence number of the last event it processed and
+	/// calls this again on every `ChainNotify::new_blocks`, so that no change is missed across
+	/// restarts. Fails with `GapTooLarge` if some of the requested events were already evicted,
+	/// with `AheadOfLatest` if `sequence` was never recorded and with `Disabled` if chain events
+	/// are not recorded at all.
+	fn chain_events_since(&self, _sequence: u64, _limit: usize) -> Result<Vec<ChainEvent>, ChainEventsError> {
+		Err(ChainEventsError::Disabled)
+	}
+
 	/// Get block receipts data by block header hash.
 	fn block_receipts(&self, hash: &H256) -> Option<BlockReceipts>;
 
@@ -335,6 +359,12 @@ pub trait BlockChainClient:
 	/// Returns traces created by transaction.
 	fn transaction_traces(&self, trace: TransactionId) -> Option<Vec<LocalizedTrace>>;
 
+	/// Returns up to `count` traces created by transaction, skipping the first `after`,
+	/// together with the total number of its traces.
+	fn transaction_traces_page(&self, trace: TransactionId, after: usize, count: usize) -> Option<TracePage> {
+		self.transaction_traces(trace).map(|traces| TracePage::new(traces.into_iter(), after, count))
+	}
+
 	/// Returns traces created by transaction from block.
 	fn block_traces(&self, trace: BlockId) -> Option<Vec<LocalizedTrace>>;
 
@@ -379,6 +409,9 @@ pub trait BlockChainClient:
 	/// Get the chain spec name.
 	fn spec_name(&self) -> String;
 
+	/// Get activation blocks of all fork transitions of the chain spec.
+	fn fork_schedule(&self) -> Vec<ForkActivation>;
+
 	/// Set the chain via a spec name.
 	fn set_spec_name(&self, spec_name: String) -> Result<(), ()>;
 
@@ -510,6 +543,10 @@ pub trait ProvingBlockChainClient: BlockChainClient {
 	/// to reproduce it.
 	fn prove_transaction(&self, transaction: SignedTransaction, id: BlockId) -> Option<(Bytes, Vec<DBValue>)>;
 
+	/// Prove execution of a transaction at the given block, aborting once the
+	/// collected database items would exceed `witness_cap` bytes.
+	fn prove_transaction_with_cap(&self, transaction: SignedTransaction, id: BlockId, witness_cap: usize) -> Result<ExecutionProof, ProveError>;
+
 	/// Get an epoch change signal by block hash.
 	fn epoch_signal(&self, hash: H256) -> Option<Vec<u8>>;
 }
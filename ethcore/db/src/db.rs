@@ -47,6 +47,9 @@ pub const COL_PRIVATE_TRANSACTIONS_STATE: u32 = 8;
 /// Number of columns in DB
 pub const NUM_COLUMNS: u32 = 9;
 
+/// Prefix of the keys of recorded state key preimages, kept in `COL_NODE_INFO`.
+pub const PREIMAGE_KEY_PREFIX: &[u8] = b"PREIMAGE";
+
 /// Modes for updating caches.
 #[derive(Clone, Copy)]
 pub enum CacheUpdatePolicy {
@@ -45,6 +45,10 @@ pub enum ExtrasIndex {
 	EpochTransitions = 5,
 	/// Pending epoch transition data index.
 	PendingEpochTransition = 6,
+	/// Index of canonical blocks by author, if enabled.
+	BlockAuthor = 7,
+	/// Log of canonical chain changes.
+	ChainEvent = 8,
 }
 
 fn with_index(hash: &H256, i: ExtrasIndex) -> H264 {
@@ -14,15 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use common_types::{
 	BlockNumber,
 	header::Header,
 	engines::params::CommonParams,
-	errors::EthcoreError as Error,
+	errors::{BlockError, EthcoreError as Error},
 };
 use engine::Engine;
 use block_reward::{self, RewardKind};
-use ethereum_types::U256;
+use ethereum_types::{Address, U256};
 use machine::{
 	ExecutedBlock,
 	Machine,
@@ -32,21 +34,44 @@ use common_types::{
 	header::ExtendedHeader,
 	snapshot::Snapshotting
 };
+use time_utils::CheckedSystemTime;
+use unexpected::OutOfBounds;
+
+/// Default allowance (in seconds) for block timestamps ahead of the local clock.
+const DEFAULT_MAX_FUTURE_TIMESTAMP_SECS: u64 = 15;
 
 /// Params for a null engine.
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct NullEngineParams {
 	/// base reward for a block.
 	pub block_reward: U256,
 	/// Immediate finalization.
-	pub immediate_finalization: bool
+	pub immediate_finalization: bool,
+	/// Address receiving the author reward instead of the block author, if any.
+	/// Uncle rewards still go to uncle authors.
+	pub reward_beneficiary: Option<Address>,
+	/// Blocks with timestamps more than this many seconds ahead of the local clock are rejected.
+	pub max_future_timestamp_secs: u64,
+}
+
+impl Default for NullEngineParams {
+	fn default() -> Self {
+		NullEngineParams {
+			block_reward: U256::zero(),
+			immediate_finalization: false,
+			reward_beneficiary: None,
+			max_future_timestamp_secs: DEFAULT_MAX_FUTURE_TIMESTAMP_SECS,
+		}
+	}
 }
 
 impl From<ethjson::spec::NullEngineParams> for NullEngineParams {
 	fn from(p: ethjson::spec::NullEngineParams) -> Self {
 		NullEngineParams {
 			block_reward: p.block_reward.map_or_else(Default::default, Into::into),
-			immediate_finalization: p.immediate_finalization.unwrap_or(false)
+			immediate_finalization: p.immediate_finalization.unwrap_or(false),
+			reward_beneficiary: p.reward_beneficiary.map(Into::into),
+			max_future_timestamp_secs: p.max_future_timestamp_secs.unwrap_or(DEFAULT_MAX_FUTURE_TIMESTAMP_SECS),
 		}
 	}
 }
@@ -80,7 +105,7 @@ impl Engine for NullEngine {
 	) -> Result<(), Error> {
 		use std::ops::Shr;
 
-		let author = *block.header.author();
+		let author = self.params.reward_beneficiary.unwrap_or_else(|| *block.header.author());
 		let number = block.header.number();
 
 		let reward = self.params.block_reward;
@@ -108,6 +133,22 @@ impl Engine for NullEngine {
 		Ok(())
 	}
 
+	fn verify_block_basic(&self, header: &Header) -> Result<(), Error> {
+		let limit = CheckedSystemTime::checked_add(SystemTime::now(), Duration::from_secs(self.params.max_future_timestamp_secs))
+			.ok_or(BlockError::TimestampOverflow)?;
+		let found = CheckedSystemTime::checked_add(UNIX_EPOCH, Duration::from_secs(header.timestamp()))
+			.ok_or(BlockError::TimestampOverflow)?;
+
+		if found > limit {
+			return Err(Error::Block(BlockError::TemporarilyInvalid(OutOfBounds {
+				min: None,
+				max: Some(limit),
+				found,
+			}.into())));
+		}
+		Ok(())
+	}
+
 	fn snapshot_mode(&self) -> Snapshotting {
 		Snapshotting::PoW { blocks: 10_000, max_restore_blocks: 10_000 }
 	}
@@ -125,3 +166,63 @@ impl Engine for NullEngine {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::time::{SystemTime, UNIX_EPOCH};
+
+	use common_types::{
+		header::Header,
+		errors::{BlockError, EthcoreError as Error},
+	};
+	use engine::Engine;
+	use ethcore::{
+		block::*,
+		test_helpers::get_temp_state_db,
+	};
+	use ethereum_types::{Address, U256};
+
+	use super::{NullEngine, NullEngineParams};
+
+	#[test]
+	fn on_close_block_with_reward_beneficiary() {
+		let spec = spec::new_test();
+		let beneficiary = Address::from_low_u64_be(0x42);
+		let author = Address::from_low_u64_be(0x77);
+		let uncle_author = Address::from_low_u64_be(0x78);
+		let engine = NullEngine::new(NullEngineParams {
+			block_reward: U256::from(1_000),
+			reward_beneficiary: Some(beneficiary),
+			..Default::default()
+		}, spec::new_test_machine());
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let mut b = OpenBlock::new(&engine, Default::default(), false, db, &genesis_header, last_hashes, author, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let mut uncle = Header::new();
+		uncle.set_author(uncle_author);
+		b.push_uncle(uncle).unwrap();
+
+		let b = b.close().unwrap();
+		assert_eq!(b.state.balance(&beneficiary).unwrap(), U256::from(1_031));
+		assert_eq!(b.state.balance(&author).unwrap(), U256::zero());
+		assert_eq!(b.state.balance(&uncle_author).unwrap(), U256::from(875));
+	}
+
+	#[test]
+	fn rejects_blocks_from_the_far_future() {
+		let engine = NullEngine::new(NullEngineParams::default(), spec::new_test_machine());
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+		let mut header = Header::new();
+
+		header.set_timestamp(now + 5);
+		assert!(engine.verify_block_basic(&header).is_ok());
+
+		header.set_timestamp(now + 3600);
+		match engine.verify_block_basic(&header) {
+			Err(Error::Block(BlockError::TemporarilyInvalid(_))) => {},
+			other => panic!("expected a future timestamp error, got {:?}", other),
+		}
+	}
+}
@@ -59,7 +59,7 @@ const REPORTS_SKIP_BLOCKS: u64 = 1;
 const MEMOIZE_CAPACITY: usize = 500;
 
 // TODO: ethabi should be able to generate this.
-const EVENT_NAME: &'static [u8] = &*b"InitiateChange(bytes32,address[])";
+const EVENT_NAME: &'static str = "InitiateChange(bytes32,address[])";
 
 lazy_static! {
 	static ref EVENT_NAME_HASH: H256 = keccak(EVENT_NAME);
@@ -304,8 +304,8 @@ impl ValidatorSafeContract {
 		let check_log = |log: &LogEntry| {
 			log.address == self.contract_address &&
 				log.topics.len() == 2 &&
-				log.topics[0] == *EVENT_NAME_HASH &&
-				log.topics[1] == *header.parent_hash()
+				log.topic(0) == Some(&*EVENT_NAME_HASH) &&
+				log.topic(1) == Some(header.parent_hash())
 		};
 
 		//// iterate in reverse because only the _last_ change in a given
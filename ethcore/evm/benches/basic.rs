@@ -74,6 +74,8 @@ criterion_group!(
 	mem_gas_calculation_increasing_u256,
 	blockhash_mulmod_small,
 	blockhash_mulmod_large,
+	tiny_executions_unpooled,
+	tiny_executions_pooled,
 );
 criterion_main!(basic);
 
@@ -232,6 +234,41 @@ fn result(r: Result<evm::GasLeft>) -> U256 {
 	}
 }
 
+fn tiny_executions_unpooled(b: &mut Criterion) {
+	b.bench_function("1000 tiny executions, unpooled", |b| {
+		tiny_executions(Factory::new(1024 * 32), b);
+	});
+}
+
+fn tiny_executions_pooled(b: &mut Criterion) {
+	b.bench_function("1000 tiny executions, pooled", |b| {
+		tiny_executions(Factory::with_pool(1024 * 32, 16), b);
+	});
+}
+
+/// Executes a contract storing a single word to memory 1000 times.
+fn tiny_executions(factory: Factory, b: &mut Bencher) {
+	let mut ext = FakeExt::new();
+
+	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
+	// PUSH1 0x01 PUSH1 0x00 MSTORE
+	let code = Arc::new(black_box(hex!("6001600052").to_vec()));
+
+	b.iter(|| {
+		let mut gas_left = U256::zero();
+		for _ in 0..1000 {
+			let mut params = ActionParams::default();
+			params.address = address.clone();
+			params.gas = U256::from(100_000);
+			params.code = Some(code.clone());
+
+			let vm = factory.create(params, ext.schedule(), 0);
+			gas_left = gas_left + result(vm.exec(&mut ext).ok().unwrap());
+		}
+		gas_left
+	});
+}
+
 /// Runs a given EVM bytecode.
 fn run_code(b: &mut Bencher, code: Bytes) {
 	let factory = Factory::default();
@@ -20,22 +20,28 @@ use std::sync::Arc;
 use vm::{Exec, Schedule};
 use ethereum_types::U256;
 use super::vm::ActionParams;
-use super::interpreter::SharedCache;
+use super::interpreter::{SharedCache, InterpreterPool};
 
 /// Evm factory. Creates appropriate Evm.
 #[derive(Clone)]
 pub struct Factory {
 	evm_cache: Arc<SharedCache>,
+	pool: Option<Arc<InterpreterPool>>,
 }
 
 impl Factory {
 	/// Create fresh instance of VM
 	/// Might choose implementation depending on supplied gas.
 	pub fn create(&self, params: ActionParams, schedule: &Schedule, depth: usize) -> Box<dyn Exec> {
-		if Self::can_fit_in_usize(&params.gas) {
-			Box::new(super::interpreter::Interpreter::<usize>::new(params, self.evm_cache.clone(), schedule, depth))
-		} else {
-			Box::new(super::interpreter::Interpreter::<U256>::new(params, self.evm_cache.clone(), schedule, depth))
+		match (Self::can_fit_in_usize(&params.gas), self.pool.clone()) {
+			(true, Some(pool)) =>
+				Box::new(super::interpreter::Interpreter::<usize>::new_pooled(params, self.evm_cache.clone(), schedule, depth, pool)),
+			(false, Some(pool)) =>
+				Box::new(super::interpreter::Interpreter::<U256>::new_pooled(params, self.evm_cache.clone(), schedule, depth, pool)),
+			(true, None) =>
+				Box::new(super::interpreter::Interpreter::<usize>::new(params, self.evm_cache.clone(), schedule, depth)),
+			(false, None) =>
+				Box::new(super::interpreter::Interpreter::<U256>::new(params, self.evm_cache.clone(), schedule, depth)),
 		}
 	}
 
@@ -44,6 +50,20 @@ impl Factory {
 	pub fn new(cache_size: usize) -> Self {
 		Factory {
 			evm_cache: Arc::new(SharedCache::new(cache_size)),
+			pool: None,
+		}
+	}
+
+	/// Create new instance of a factory, with a size in bytes for caching jump destinations,
+	/// which reuses stack and memory buffers of finished interpreters.
+	///
+	/// Buffers are returned to the pool when an interpreter is dropped and are cleared
+	/// before being reused, at most `pool_size` of them are kept. The pool is shared by
+	/// all clones of the factory and is safe to use from multiple threads.
+	pub fn with_pool(cache_size: usize, pool_size: usize) -> Self {
+		Factory {
+			evm_cache: Arc::new(SharedCache::new(cache_size)),
+			pool: Some(Arc::new(InterpreterPool::new(pool_size))),
 		}
 	}
 
@@ -57,6 +77,7 @@ impl Default for Factory {
 	fn default() -> Factory {
 		Factory {
 			evm_cache: Arc::new(SharedCache::default()),
+			pool: None,
 		}
 	}
 }
@@ -73,6 +94,36 @@ fn test_create_vm() {
 	let _vm = Factory::default().create(params, ext.schedule(), ext.depth());
 }
 
+#[test]
+fn test_pooled_vm_reuses_buffers() {
+	use vm::Ext;
+	use vm::tests::FakeExt;
+	use bytes::Bytes;
+
+	let factory = Factory::with_pool(1024 * 32, 2);
+	let pool = factory.pool.clone().expect("factory created with pool; qed");
+	let mut ext = FakeExt::new();
+
+	// PUSH1 0x01 PUSH1 0x00 MSTORE STOP
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(Bytes::from(vec![0x60, 0x01, 0x60, 0x00, 0x52, 0x00])));
+
+	for _ in 0..3 {
+		let vm = factory.create(params.clone(), ext.schedule(), ext.depth());
+		assert_eq!(pool.len(), 0);
+		assert!(vm.exec(&mut ext).ok().expect("no traps in code; qed").is_ok());
+		assert_eq!(pool.len(), 1);
+	}
+
+	// the pool never holds more than its capacity
+	let first = factory.create(params.clone(), ext.schedule(), ext.depth());
+	let second = factory.create(params.clone(), ext.schedule(), ext.depth());
+	let third = factory.create(params, ext.schedule(), ext.depth());
+	drop((first, second, third));
+	assert_eq!(pool.len(), 2);
+}
+
 /// Create tests by injecting different VM factories
 #[macro_export]
 macro_rules! evm_test(
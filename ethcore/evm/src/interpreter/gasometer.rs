@@ -201,13 +201,13 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMem(gas, mem_needed(stack.peek(0), stack.peek(1))?)
 			},
 			instructions::CALL | instructions::CALLCODE => {
-				let mut gas = Gas::from(schedule.call_gas);
+				let address = u256_to_address(stack.peek(1));
+				let mut gas = calculate_call_access_gas(schedule, ext, &address, schedule.call_gas);
 				let mem = cmp::max(
 					mem_needed(stack.peek(5), stack.peek(6))?,
 					mem_needed(stack.peek(3), stack.peek(4))?
 				);
 
-				let address = u256_to_address(stack.peek(1));
 				let is_value_transfer = !stack.peek(2).is_zero();
 
 				if instruction == instructions::CALL && (
@@ -227,7 +227,8 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				Request::GasMemProvide(gas, mem, Some(requested))
 			},
 			instructions::DELEGATECALL => {
-				let gas = Gas::from(schedule.call_gas);
+				let code_address = u256_to_address(stack.peek(1));
+				let gas = calculate_call_access_gas(schedule, ext, &code_address, schedule.call_gas);
 				let mem = cmp::max(
 					mem_needed(stack.peek(4), stack.peek(5))?,
 					mem_needed(stack.peek(2), stack.peek(3))?
@@ -239,9 +240,9 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 			instructions::STATICCALL => {				
 				let code_address = u256_to_address(stack.peek(1));
 				let gas = if code_address <= PRECOMPILES_ADDRESS_LIMIT {
-					Gas::from(schedule.staticcall_precompile_gas)
+					calculate_call_access_gas(schedule, ext, &code_address, schedule.staticcall_precompile_gas)
 				} else {
-					Gas::from(schedule.call_gas)
+					calculate_call_access_gas(schedule, ext, &code_address, schedule.call_gas)
 				};
 
 				let mem = cmp::max(
@@ -390,6 +391,18 @@ fn to_word_size<Gas: evm::CostType>(value: Gas) -> (Gas, bool) {
 	(gas >> 5, false)
 }
 
+/// Base cost of a `*CALL*` to `address`: warm/cold access cost under EIP-2929, `call_gas` otherwise.
+#[inline]
+fn calculate_call_access_gas<Gas: evm::CostType>(schedule: &Schedule, ext: &dyn vm::Ext, address: &Address, call_gas: usize) -> Gas {
+	if !schedule.eip2929 {
+		Gas::from(call_gas)
+	} else if ext.is_address_warm(address) {
+		Gas::from(schedule.warm_storage_read_cost)
+	} else {
+		Gas::from(schedule.cold_account_access_cost)
+	}
+}
+
 #[inline]
 fn calculate_eip1283_sstore_gas<Gas: evm::CostType>(schedule: &Schedule, original: &U256, current: &U256, new: &U256) -> Gas {
 	Gas::from(
@@ -22,6 +22,7 @@ mod gasometer;
 mod stack;
 mod memory;
 mod shared_cache;
+mod pool;
 
 use std::marker::PhantomData;
 use std::{cmp, mem};
@@ -44,6 +45,8 @@ use self::gasometer::Gasometer;
 use self::stack::{Stack, VecStack};
 use self::memory::Memory;
 pub use self::shared_cache::SharedCache;
+pub(crate) use self::pool::InterpreterPool;
+use self::pool::Buffers;
 
 use bit_set::BitSet;
 
@@ -192,9 +195,22 @@ pub struct Interpreter<Cost: CostType> {
 	resume_output_range: Option<(U256, U256)>,
 	resume_result: Option<InstructionResult<Cost>>,
 	last_stack_ret_len: usize,
+	pool: Option<Arc<InterpreterPool>>,
 	_type: PhantomData<Cost>,
 }
 
+impl<Cost: CostType> Drop for Interpreter<Cost> {
+	fn drop(&mut self) {
+		if let Some(pool) = self.pool.take() {
+			pool.release(Buffers {
+				stack: mem::replace(&mut self.stack, VecStack::with_capacity(0, U256::zero())),
+				return_stack: mem::replace(&mut self.return_stack, Vec::new()),
+				mem: mem::replace(&mut self.mem, Vec::new()),
+			});
+		}
+	}
+}
+
 impl<Cost: 'static + CostType> vm::Exec for Interpreter<Cost> {
 	fn exec(mut self: Box<Self>, ext: &mut dyn vm::Ext) -> vm::ExecTrapResult<GasLeft> {
 		loop {
@@ -274,15 +290,25 @@ impl<Cost: 'static + CostType> vm::ResumeCreate for Interpreter<Cost> {
 
 impl<Cost: CostType> Interpreter<Cost> {
 	/// Create a new `Interpreter` instance with shared cache.
-	pub fn new(mut params: ActionParams, cache: Arc<SharedCache>, schedule: &Schedule, depth: usize) -> Interpreter<Cost> {
+	pub fn new(params: ActionParams, cache: Arc<SharedCache>, schedule: &Schedule, depth: usize) -> Interpreter<Cost> {
+		Self::with_buffers(params, cache, depth, Buffers::new(schedule.stack_limit), None)
+	}
+
+	/// Create a new `Interpreter` reusing buffers from the pool, they're returned to it
+	/// once the interpreter is dropped.
+	pub(crate) fn new_pooled(params: ActionParams, cache: Arc<SharedCache>, schedule: &Schedule, depth: usize, pool: Arc<InterpreterPool>) -> Interpreter<Cost> {
+		let buffers = pool.acquire(schedule.stack_limit);
+		Self::with_buffers(params, cache, depth, buffers, Some(pool))
+	}
+
+	fn with_buffers(mut params: ActionParams, cache: Arc<SharedCache>, depth: usize, buffers: Buffers, pool: Option<Arc<InterpreterPool>>) -> Interpreter<Cost> {
 		let reader = CodeReader::new(params.code.take().expect("VM always called with code; qed"));
 		let params = InterpreterParams::from(params);
 		let informant = informant::EvmInformant::new(depth);
 		let valid_jump_destinations = None;
 		let valid_subroutine_destinations = None;
 		let gasometer = Cost::from_u256(params.gas).ok().map(|gas| Gasometer::<Cost>::new(gas));
-		let stack = VecStack::with_capacity(schedule.stack_limit, U256::zero());
-		let return_stack = Vec::with_capacity(MAX_SUB_STACK_SIZE);
+		let Buffers { stack, return_stack, mem } = buffers;
 		Interpreter {
 			cache, params, reader, informant,
 			valid_jump_destinations, valid_subroutine_destinations,
@@ -291,11 +317,12 @@ impl<Cost: CostType> Interpreter<Cost> {
 			// Overridden in `step_inner` based on
 			// the result of `ext.trace_next_instruction`.
 			do_trace: true,
-			mem: Vec::new(),
+			mem,
 			return_data: ReturnData::empty(),
 			last_stack_ret_len: 0,
 			resume_output_range: None,
 			resume_result: None,
+			pool,
 			_type: PhantomData,
 		}
 	}
@@ -641,6 +668,9 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let call_gas = provided.expect("`provided` comes through Self::exec from `Gasometer::get_gas_cost_mem`; `gas_gas_mem_cost` guarantees `Some` when instruction is `CALL`/`CALLCODE`/`DELEGATECALL`/`CREATE`; this is one of `CALL`/`CALLCODE`/`DELEGATECALL`; qed");
 				let code_address = self.stack.pop_back();
 				let code_address = u256_to_address(&code_address);
+				if ext.schedule().eip2929 {
+					ext.mark_address_warm(code_address);
+				}
 
 				let value = if instruction == instructions::DELEGATECALL {
 					None
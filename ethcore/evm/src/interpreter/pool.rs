@@ -0,0 +1,96 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pool of interpreter buffers
+
+use ethereum_types::U256;
+use parking_lot::Mutex;
+
+use super::stack::VecStack;
+use super::MAX_SUB_STACK_SIZE;
+
+/// Memory buffers above this capacity are freed instead of being pooled.
+const MAX_POOLED_MEMORY: usize = 64 * 1024;
+
+/// Heap buffers of an interpreter, which are the bulk of its allocations.
+pub(crate) struct Buffers {
+	pub stack: VecStack<U256>,
+	pub return_stack: Vec<usize>,
+	pub mem: Vec<u8>,
+}
+
+impl Buffers {
+	pub fn new(stack_limit: usize) -> Self {
+		Buffers {
+			stack: VecStack::with_capacity(stack_limit, U256::zero()),
+			return_stack: Vec::with_capacity(MAX_SUB_STACK_SIZE),
+			mem: Vec::new(),
+		}
+	}
+}
+
+/// Pool of buffers of finished interpreters.
+///
+/// Buffers are returned when an interpreter is dropped, which happens once it's done
+/// executing, and are cleared before being handed to the next interpreter, so no
+/// state leaks between executions. The pool is shared between threads, access is
+/// synchronized with a lock held only to push or pop a single entry.
+pub(crate) struct InterpreterPool {
+	buffers: Mutex<Vec<Buffers>>,
+	capacity: usize,
+}
+
+impl InterpreterPool {
+	/// Create a pool keeping at most `capacity` sets of buffers around.
+	pub fn new(capacity: usize) -> Self {
+		InterpreterPool {
+			buffers: Mutex::new(Vec::with_capacity(capacity)),
+			capacity,
+		}
+	}
+
+	/// Take buffers out of the pool or allocate new ones.
+	pub fn acquire(&self, stack_limit: usize) -> Buffers {
+		match self.buffers.lock().pop() {
+			Some(mut buffers) => {
+				buffers.stack.reserve(stack_limit);
+				buffers
+			},
+			None => Buffers::new(stack_limit),
+		}
+	}
+
+	/// Clear the buffers and put them back into the pool, unless it's full.
+	pub fn release(&self, mut buffers: Buffers) {
+		buffers.stack.clear();
+		buffers.return_stack.clear();
+		buffers.mem.clear();
+		if buffers.mem.capacity() > MAX_POOLED_MEMORY {
+			buffers.mem = Vec::new();
+		}
+
+		let mut pooled = self.buffers.lock();
+		if pooled.len() < self.capacity {
+			pooled.push(buffers);
+		}
+	}
+
+	/// Number of buffers ready to be reused.
+	#[cfg(test)]
+	pub fn len(&self) -> usize {
+		self.buffers.lock().len()
+	}
+}
@@ -49,6 +49,17 @@ impl<S : Copy> VecStack<S> {
 			logs: [zero; instructions::MAX_NO_OF_TOPICS]
 		}
 	}
+
+	/// Remove all elements, keeping the allocated capacity.
+	pub fn clear(&mut self) {
+		self.stack.clear();
+	}
+
+	/// Make sure the stack can hold `capacity` elements without reallocating.
+	pub fn reserve(&mut self, capacity: usize) {
+		let additional = capacity.saturating_sub(self.stack.len());
+		self.stack.reserve(additional);
+	}
 }
 
 impl<S : fmt::Display> Stack<S> for VecStack<S> {
@@ -993,6 +993,28 @@ fn test_calls(factory: super::Factory) {
 	assert_eq!(ext.calls.len(), 2);
 }
 
+evm_test!{test_calls_warm_cold_access: test_calls_warm_cold_access_int}
+fn test_calls_warm_cold_access(factory: super::Factory) {
+	// two identical STATICCALLs to 0x998: the first pays the cold access cost, the second the warm one.
+	let code = hex!("60006000600060006109986000fa5060006000600060006109986000fa50").to_vec();
+
+	let code_address = Address::from_low_u64_be(0x998);
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_istanbul();
+	ext.schedule.eip2929 = true;
+
+	let gas_left = {
+		let vm = factory.create(params, ext.schedule(), ext.depth());
+		test_finalize(vm.exec(&mut ext).ok().unwrap()).unwrap()
+	};
+
+	assert!(ext.accessed_addresses.contains(&code_address));
+	assert_eq!(ext.calls.len(), 1);
+	assert_eq!(gas_left, U256::from(100_000 - 2 * 20 - 2600 - 100));
+}
+
 evm_test!{test_create_in_staticcall: test_create_in_staticcall_int}
 fn test_create_in_staticcall(factory: super::Factory) {
 	let code = hex!("600060006064f000").to_vec();
@@ -24,6 +24,7 @@ use account_state::{
 };
 use bytes::Bytes;
 use common_types::{
+	client_types::{ExecutionProof, ProveError},
 	engines::machine::Executed as RawExecuted,
 	errors::{ExecutionError, EthcoreError as Error},
 	transaction::SignedTransaction,
@@ -63,7 +64,10 @@ pub struct ApplyOutcome<T, V> {
 	/// The trace for the applied transaction, empty if tracing was not produced.
 	pub trace: Vec<T>,
 	/// The VM trace for the applied transaction, None if tracing was not produced.
-	pub vm_trace: Option<V>
+	pub vm_trace: Option<V>,
+	/// The exception which stopped execution of the transaction (e.g. a revert or running out of gas).
+	/// Unlike the receipt status code it is known before EIP-658 as well.
+	pub exception: Option<vm::Error>,
 }
 
 /// Result type for the execution ("application") of a transaction.
@@ -114,11 +118,36 @@ pub fn prove_transaction_virtual<H: AsHashDB<KeccakHasher, DBValue> + Send + Syn
 	env_info: &EnvInfo,
 	factories: Factories,
 ) -> Option<(Bytes, Vec<DBValue>)> {
-	use account_state::backend::Proving;
+	prove_with_backend(backend::Proving::new(db), root, transaction, machine, env_info, factories)
+		.ok()
+		.map(|proof| (proof.output, proof.items))
+}
 
-	let backend = Proving::new(db);
+/// Prove a `virtual` transaction on the given state, aborting execution as soon as the
+/// collected witness would grow beyond `witness_cap` bytes.
+pub fn prove_transaction_virtual_with_cap<H: AsHashDB<KeccakHasher, DBValue> + Send + Sync>(
+	db: H,
+	root: H256,
+	transaction: &SignedTransaction,
+	machine: &Machine,
+	env_info: &EnvInfo,
+	factories: Factories,
+	witness_cap: usize,
+) -> Result<ExecutionProof, ProveError> {
+	let proving = backend::Proving::with_cap(db, witness_cap);
+	prove_with_backend(proving, root, transaction, machine, env_info, factories)
+}
+
+fn prove_with_backend<H: AsHashDB<KeccakHasher, DBValue> + Send + Sync>(
+	proving: backend::Proving<H>,
+	root: H256,
+	transaction: &SignedTransaction,
+	machine: &Machine,
+	env_info: &EnvInfo,
+	factories: Factories,
+) -> Result<ExecutionProof, ProveError> {
 	let res = State::from_existing(
-		backend,
+		proving,
 		root,
 		machine.account_start_nonce(env_info.number),
 		factories,
@@ -126,18 +155,35 @@ pub fn prove_transaction_virtual<H: AsHashDB<KeccakHasher, DBValue> + Send + Syn
 
 	let mut state = match res {
 		Ok(state) => state,
-		Err(_) => return None,
+		Err(_) => return Err(ProveError::StateUnavailable),
 	};
 
 	let options = TransactOptions::with_no_tracing().dont_check_nonce().save_output_from_contract();
-	match execute(&mut state, env_info, machine, transaction, options, true) {
-		Err(ExecutionError::Internal(_)) => None,
+	let res = execute(&mut state, env_info, machine, transaction, options, true);
+	let proving = state.drop().1;
+
+	// running out of witness budget surfaces as missing trie nodes, check for it first.
+	if proving.cap_exceeded() {
+		let collected = proving.proof_size();
+		trace!(target: "state", "Proved call aborted: witness of {} bytes exceeds the cap", collected);
+		return Err(ProveError::WitnessTooLarge { collected, cap: proving.cap().unwrap_or(collected) });
+	}
+
+	let output = match res {
+		Err(ExecutionError::Internal(_)) => return Err(ProveError::StateUnavailable),
 		Err(e) => {
 			trace!(target: "state", "Proved call failed: {}", e);
-			Some((Vec::new(), state.drop().1.extract_proof()))
+			Vec::new()
 		}
-		Ok(res) => Some((res.output, state.drop().1.extract_proof())),
-	}
+		Ok(res) => res.output,
+	};
+
+	let witness_size = proving.proof_size();
+	Ok(ExecutionProof {
+		output,
+		items: proving.extract_proof(),
+		witness_size,
+	})
 }
 
 /// Collects code that needs a Machine and/or Executive
@@ -218,6 +264,7 @@ impl<B: Backend> ExecutiveState for State<B> {
 		};
 
 		let output = e.output;
+		let exception = e.exception;
 		let receipt = Receipt::new(outcome, e.cumulative_gas_used, e.logs);
 		trace!(target: "state", "Transaction receipt: {:?}", receipt);
 
@@ -226,6 +273,7 @@ impl<B: Backend> ExecutiveState for State<B> {
 			output,
 			trace: e.trace,
 			vm_trace: e.vm_trace,
+			exception,
 		})
 	}
 }
@@ -328,6 +376,81 @@ mod tests {
 		assert_eq!(result.trace, expected_trace);
 	}
 
+	// a contract reading storage slots 1 to 64, each of them set.
+	fn storage_heavy_state() -> (H256, state_db::StateDB, Address) {
+		let a = Address::from_low_u64_be(0xaa);
+		let code = (1u8..=64).flat_map(|i| vec![0x60, i, 0x54, 0x50]).collect::<Vec<_>>();
+		let mut state = get_temp_state();
+		state.new_contract(&a, 0.into(), 0.into(), 0.into()).unwrap();
+		state.init_code(&a, code).unwrap();
+		for i in 1u64..=64 {
+			state.set_storage(&a, BigEndianHash::from_uint(&U256::from(i)), BigEndianHash::from_uint(&U256::from(i))).unwrap();
+		}
+		state.commit().unwrap();
+		let (root, db) = state.drop();
+		(root, db, a)
+	}
+
+	fn read_storage_transaction(a: Address) -> SignedTransaction {
+		Transaction {
+			nonce: 0.into(),
+			gas_price: 0.into(),
+			gas: 100_000.into(),
+			action: Action::Call(a),
+			value: 0.into(),
+			data: vec![],
+		}.fake_sign(Address::zero())
+	}
+
+	#[test]
+	fn should_prove_transaction_under_witness_cap() {
+		let (root, db, a) = storage_heavy_state();
+		let t = read_storage_transaction(a);
+		let mut info = EnvInfo::default();
+		info.gas_limit = 1_000_000.into();
+		let machine = make_frontier_machine(5);
+
+		let mut jdb = db.journal_db().boxed_clone();
+		let (output, mut uncapped) = prove_transaction_virtual(
+			jdb.as_hash_db_mut(), root, &t, &machine, &info, Default::default(),
+		).unwrap();
+
+		let mut jdb = db.journal_db().boxed_clone();
+		let mut proof = prove_transaction_virtual_with_cap(
+			jdb.as_hash_db_mut(), root, &t, &machine, &info, Default::default(), 1024 * 1024,
+		).unwrap();
+
+		uncapped.sort();
+		proof.items.sort();
+		assert_eq!(proof.output, output);
+		assert_eq!(proof.items, uncapped);
+		assert_eq!(proof.witness_size, uncapped.iter().map(|item| item.len()).sum::<usize>());
+	}
+
+	#[test]
+	fn should_abort_proof_over_witness_cap() {
+		let (root, db, a) = storage_heavy_state();
+		let t = read_storage_transaction(a);
+		let mut info = EnvInfo::default();
+		info.gas_limit = 1_000_000.into();
+		let machine = make_frontier_machine(5);
+
+		let mut jdb = db.journal_db().boxed_clone();
+		let witness_size = prove_transaction_virtual_with_cap(
+			jdb.as_hash_db_mut(), root, &t, &machine, &info, Default::default(), 1024 * 1024,
+		).unwrap().witness_size;
+
+		let cap = witness_size / 2;
+		let mut jdb = db.journal_db().boxed_clone();
+		match prove_transaction_virtual_with_cap(jdb.as_hash_db_mut(), root, &t, &machine, &info, Default::default(), cap) {
+			Err(ProveError::WitnessTooLarge { collected, cap: reported_cap }) => {
+				assert_eq!(reported_cap, cap);
+				assert!(collected > cap);
+			}
+			other => panic!("expected the witness cap to be hit, got {:?}", other),
+		}
+	}
+
 	#[test]
 	fn should_work_when_cloned() {
 		let _ = env_logger::try_init();
@@ -1664,6 +1787,7 @@ mod tests {
 			vm: Default::default(),
 			trie: TrieFactory::new(TrieSpec::Fat, ethtrie::Layout),
 			accountdb: Default::default(),
+			preimages: None,
 		};
 
 		let get_pod_state_val = |pod_state : &PodState, ak, k| {
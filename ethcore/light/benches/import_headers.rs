@@ -0,0 +1,94 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmark importing one header response through the verification queue
+//! versus as a single batch.
+
+extern crate common_types;
+extern crate criterion;
+extern crate ethcore_io as io;
+extern crate ethcore_light as light;
+extern crate kvdb_memorydb;
+extern crate parking_lot;
+extern crate rlp;
+extern crate spec;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_types::{encoded, header::Header};
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use io::IoChannel;
+use light::cache::Cache;
+use light::client::{fetch, Client, Config};
+use parking_lot::Mutex;
+
+const RESPONSE_SIZE: u64 = 192;
+
+fn new_client(spec: &spec::Spec) -> Client<fetch::Unavailable> {
+	let db = Arc::new(kvdb_memorydb::create(1));
+	let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::from_secs(6 * 3600))));
+	Client::new(Config::default(), db, 0, spec, fetch::unavailable(), IoChannel::disconnected(), cache).unwrap()
+}
+
+fn make_chain(genesis: &Header, len: u64) -> Vec<Header> {
+	let mut parent = genesis.clone();
+	(1..=len).map(|i| {
+		let mut header = Header::new();
+		header.set_parent_hash(parent.hash());
+		header.set_number(i);
+		header.set_timestamp(genesis.timestamp() + i * 10);
+		header.set_difficulty(*genesis.difficulty());
+		header.set_gas_limit(*genesis.gas_limit());
+		parent = header.clone();
+		header
+	}).collect()
+}
+
+fn import_headers(c: &mut Criterion) {
+	let spec = spec::new_test();
+	let chain = make_chain(&spec.genesis_header(), RESPONSE_SIZE);
+	let encoded: Vec<_> = chain.iter().map(|h| encoded::Header::new(rlp::encode(h))).collect();
+
+	c.bench_function("import 192 headers through the queue", |b| {
+		b.iter_batched(
+			|| (new_client(&spec), chain.clone()),
+			|(client, headers)| {
+				for header in headers {
+					client.import_header(header).unwrap();
+				}
+				client.flush_queue();
+				client.import_verified();
+				assert_eq!(client.chain_info().best_block_number, RESPONSE_SIZE);
+			},
+			BatchSize::SmallInput,
+		)
+	});
+
+	c.bench_function("import 192 headers as a batch", |b| {
+		b.iter_batched(
+			|| (new_client(&spec), encoded.clone()),
+			|(client, headers)| {
+				client.import_headers(headers).unwrap();
+				assert_eq!(client.chain_info().best_block_number, RESPONSE_SIZE);
+			},
+			BatchSize::SmallInput,
+		)
+	});
+}
+
+criterion_group!(benches, import_headers);
+criterion_main!(benches);
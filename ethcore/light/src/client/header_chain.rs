@@ -89,12 +89,14 @@ impl BestAndLatest {
 }
 
 // candidate block description.
+#[derive(Clone)]
 struct Candidate {
 	hash: H256,
 	parent_hash: H256,
 	total_difficulty: U256,
 }
 
+#[derive(Clone)]
 struct Entry {
 	candidates: SmallVec<[Candidate; 3]>, // 3 arbitrarily chosen
 	canonical_hash: H256,
@@ -370,7 +372,59 @@ impl HeaderChain {
 		header: &Header,
 		transition_proof: Option<Vec<u8>>,
 	) -> EthcoreResult<PendingChanges> {
-		self.insert_inner(transaction, header, None, transition_proof)
+		let cur_best = self.best_block.read().clone();
+		let mut candidates = self.candidates.write();
+		let mut live_epoch_proofs = self.live_epoch_proofs.write();
+		self.insert_inner(transaction, header, None, transition_proof, &cur_best, &mut candidates, &mut live_epoch_proofs)
+	}
+
+	/// Insert a contiguous batch of pre-verified headers, each with an optional epoch
+	/// transition proof, into a single transaction. Returns the pending changes of the
+	/// whole batch, to be applied once the transaction is written.
+	///
+	/// Either every header is inserted or none: the batch is applied to a copy of the
+	/// in-memory state, which replaces it only if every header succeeded. Readers are held
+	/// off until then, so they never observe part of a batch. On error the transaction
+	/// must be discarded.
+	pub fn insert_batch(
+		&self,
+		transaction: &mut DBTransaction,
+		headers: &[(Header, Option<Vec<u8>>)],
+	) -> EthcoreResult<PendingChanges> {
+		// pruning reads ancient headers back from the database, so they must not be
+		// part of the batch being written.
+		if headers.len() as u64 > HISTORY {
+			return Err(Error::Msg(format!("Header batch of {} exceeds the maximum of {}", headers.len(), HISTORY)));
+		}
+
+		let mut cur_best = self.best_block.read().clone();
+		let mut candidates = self.candidates.write();
+		let mut live_epoch_proofs = self.live_epoch_proofs.write();
+		let mut batch_candidates = candidates.clone();
+		let mut batch_epoch_proofs = live_epoch_proofs.clone();
+		let mut pending = PendingChanges {
+			best_block: None,
+		};
+
+		for (header, transition_proof) in headers {
+			let changes = self.insert_inner(
+				transaction,
+				header,
+				None,
+				transition_proof.clone(),
+				&cur_best,
+				&mut batch_candidates,
+				&mut batch_epoch_proofs,
+			)?;
+			if let Some(best_block) = changes.best_block {
+				cur_best = best_block.clone();
+				pending.best_block = Some(best_block);
+			}
+		}
+
+		*candidates = batch_candidates;
+		*live_epoch_proofs = batch_epoch_proofs;
+		Ok(pending)
 	}
 
 	/// Insert a pre-verified header, with a known total difficulty. Similary to `insert`.
@@ -383,7 +437,10 @@ impl HeaderChain {
 		total_difficulty: U256,
 		transition_proof: Option<Vec<u8>>,
 	) -> EthcoreResult<PendingChanges> {
-		self.insert_inner(transaction, header, Some(total_difficulty), transition_proof)
+		let cur_best = self.best_block.read().clone();
+		let mut candidates = self.candidates.write();
+		let mut live_epoch_proofs = self.live_epoch_proofs.write();
+		self.insert_inner(transaction, header, Some(total_difficulty), transition_proof, &cur_best, &mut candidates, &mut live_epoch_proofs)
 	}
 
 	fn insert_inner(
@@ -392,6 +449,9 @@ impl HeaderChain {
 		header: &Header,
 		total_difficulty: Option<U256>,
 		transition_proof: Option<Vec<u8>>,
+		cur_best: &BlockDescriptor,
+		candidates: &mut BTreeMap<u64, Entry>,
+		live_epoch_proofs: &mut H256FastMap<EpochTransition>,
 	) -> EthcoreResult<PendingChanges> {
 		let hash = header.hash();
 		let number = header.number();
@@ -406,9 +466,6 @@ impl HeaderChain {
 			best_block: None,
 		};
 
-		// hold candidates the whole time to guard import order.
-		let mut candidates = self.candidates.write();
-
 		// find total difficulty.
 		let total_difficulty = match total_difficulty {
 			Some(td) => td,
@@ -450,20 +507,17 @@ impl HeaderChain {
 
 		if let Some(transition) = transition {
 			transaction.put(self.col, transition_key(hash).as_bytes(), &transition.proof);
-			self.live_epoch_proofs.write().insert(hash, transition);
+			live_epoch_proofs.insert(hash, transition);
 		}
 
 		let raw = header.encoded().into_inner();
 		transaction.put_vec(self.col, &hash[..], raw);
 
 		// TODO: For engines when required, use cryptoeconomic guarantees.
-		let (best_num, is_new_best) = {
-			let cur_best = self.best_block.read();
-			if cur_best.total_difficulty < total_difficulty {
-				(number, true)
-			} else {
-				(cur_best.number, false)
-			}
+		let (best_num, is_new_best) = if cur_best.total_difficulty < total_difficulty {
+			(number, true)
+		} else {
+			(cur_best.number, false)
 		};
 
 		// reorganize ancestors so canonical entries are first in their
@@ -511,7 +565,6 @@ impl HeaderChain {
 				let mut last_canonical_transition = None;
 				let cht_root = {
 					let mut i = earliest_era;
-					let mut live_epoch_proofs = self.live_epoch_proofs.write();
 
 					// iterable function which removes the candidates as it goes
 					// along. this will only be called until the CHT is complete.
@@ -1235,4 +1288,46 @@ mod tests {
 		let decoded: Header = hardcoded_sync.header.decode().expect("decoding failed");
 		assert_eq!(decoded.number(), h_num);
 	}
+
+	#[test]
+	fn insert_batch_is_atomic() {
+		let spec = spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let db = make_db();
+		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::from_secs(6 * 3600))));
+
+		let chain = HeaderChain::new(db.clone(), 0, &spec, cache, HardcodedSync::Allow).unwrap();
+
+		let mut parent_hash = genesis_header.hash();
+		let mut rolling_timestamp = genesis_header.timestamp();
+		let mut batch = Vec::new();
+		for i in 1..=100 {
+			let mut header = Header::new();
+			header.set_parent_hash(parent_hash);
+			header.set_number(i);
+			header.set_timestamp(rolling_timestamp);
+			header.set_difficulty(*genesis_header.difficulty() * i as u32);
+			parent_hash = header.hash();
+			batch.push((header, None));
+
+			rolling_timestamp += 10;
+		}
+
+		// a header in the middle of the batch with an unknown parent fails the whole batch.
+		let mut broken = batch.clone();
+		broken[50].0.set_parent_hash(Default::default());
+		let mut tx = db.transaction();
+		assert!(chain.insert_batch(&mut tx, &broken).is_err());
+		assert_eq!(chain.best_block().number, 0);
+		assert!(chain.block_hash(BlockId::Number(1)).is_none());
+
+		let mut tx = db.transaction();
+		let pending = chain.insert_batch(&mut tx, &batch).unwrap();
+		db.write(tx).unwrap();
+		chain.apply_pending(pending);
+
+		assert_eq!(chain.best_block().number, 100);
+		assert_eq!(chain.best_block().hash, parent_hash);
+		assert!(chain.block_header(BlockId::Number(50)).is_some());
+	}
 }
@@ -16,10 +16,12 @@
 
 //! Light client implementation. Stores data from light sync
 
+use std::collections::HashMap;
 use std::sync::{Weak, Arc};
 
 use engine::{Engine, EpochChange, Proof};
 use verification::queue::{self, HeaderQueue};
+use verification::queue::kind::{Kind, headers::Headers};
 use spec::{Spec, SpecHardcodedSync};
 use io::IoChannel;
 use parking_lot::{Mutex, RwLock};
@@ -33,13 +35,14 @@ use common_types::{
 	encoded,
 	engines::epoch::{Transition as EpochTransition, PendingTransition},
 	errors::EthcoreError as Error,
-	errors::EthcoreResult,
+	errors::{BlockError, EthcoreResult},
 	header::Header,
 	ids::BlockId,
 	io_message::ClientIoMessage,
 	verification::VerificationQueueInfo as BlockQueueInfo,
 };
 use kvdb::KeyValueDB;
+use rayon::prelude::*;
 use vm::EnvInfo;
 
 use self::fetch::ChainDataFetcher;
@@ -94,6 +97,10 @@ pub trait LightChainClient: Send + Sync {
 	/// parent queued prior.
 	fn queue_header(&self, header: Header) -> EthcoreResult<H256>;
 
+	/// Import a contiguous batch of headers, bypassing the queue. The parent of the first
+	/// header must already be imported. Either all headers are imported or none.
+	fn import_header_batch(&self, headers: Vec<Header>) -> EthcoreResult<Vec<H256>>;
+
 	/// Attempt to get a block hash by block id.
 	fn block_hash(&self, id: BlockId) -> Option<H256>;
 
@@ -173,6 +180,7 @@ pub struct Client<T> {
 	listeners: RwLock<Vec<Weak<dyn LightChainNotify>>>,
 	fetcher: T,
 	verify_full: bool,
+	check_seal: bool,
 	/// A closure to call when we want to restart the client
 	exit_handler: Mutex<Option<Box<dyn Fn(String) + 'static + Send>>>,
 }
@@ -201,6 +209,7 @@ impl<T: ChainDataFetcher> Client<T> {
 			listeners: RwLock::new(vec![]),
 			fetcher,
 			verify_full: config.verify_full,
+			check_seal: config.check_seal,
 			exit_handler: Mutex::new(None),
 		})
 	}
@@ -356,6 +365,101 @@ impl<T: ChainDataFetcher> Client<T> {
 		self.notify(|listener| listener.new_headers(&good));
 	}
 
+	/// Import a contiguous batch of headers, e.g. from a single response, bypassing the
+	/// verification queue. Single announced headers should go through `import_header`.
+	///
+	/// Each header must be the parent of the next one and the parent of the first one must
+	/// already be imported. Seals are verified in parallel and ancestry in memory, then the
+	/// whole batch is written in a single database transaction: if any header fails, none
+	/// of them is imported.
+	pub fn import_headers(&self, batch: Vec<encoded::Header>) -> EthcoreResult<Vec<H256>> {
+		let headers = batch.iter()
+			.map(|header| header.decode().map_err(Error::from))
+			.collect::<EthcoreResult<Vec<_>>>()?;
+
+		self.import_decoded_headers(headers)
+	}
+
+	fn import_decoded_headers(&self, headers: Vec<Header>) -> EthcoreResult<Vec<H256>> {
+		let _lock = self.import_lock.lock();
+
+		let first_parent = match headers.first() {
+			Some(first) => self.chain.block_header(BlockId::Hash(*first.parent_hash()))
+				.ok_or_else(|| Error::Block(BlockError::UnknownParent(*first.parent_hash())))?
+				.decode()?,
+			None => return Ok(Vec::new()),
+		};
+
+		// check intra-batch linkage before doing any expensive work.
+		let mut parent = &first_parent;
+		for header in &headers {
+			if *header.parent_hash() != parent.hash() || header.number() != parent.number() + 1 {
+				return Err(Error::Block(BlockError::UnknownParent(*header.parent_hash())));
+			}
+			parent = header;
+		}
+
+		// seal verification doesn't depend on ancestry; it runs in parallel like in the queue.
+		let engine = &*self.engine;
+		let check_seal = self.check_seal;
+		let headers = headers.into_par_iter()
+			.map(|header| {
+				let header = Headers::create(header, engine, check_seal).map_err(|(e, _)| e)?;
+				Headers::verify(header, engine, check_seal)
+			})
+			.collect::<EthcoreResult<Vec<_>>>()?;
+
+		let mut tx = self.db.transaction();
+		let mut epoch_proofs = Vec::with_capacity(headers.len());
+		{
+			let batch_headers: HashMap<H256, &Header> = headers.iter().map(|h| (h.hash(), h)).collect();
+			let mut pending_transitions: HashMap<H256, Vec<u8>> = HashMap::new();
+
+			let mut parent = &first_parent;
+			for header in &headers {
+				if self.verify_full {
+					self.engine.verify_block_family(header, parent)?;
+					self.engine.verify_block_external(header)?;
+				}
+
+				let proof = self.check_epoch_signal(header)
+					.and_then(|signal| match signal {
+						Some(proof) => self.fetch_epoch_proof(header, proof).map(Some),
+						None => Ok(None),
+					})
+					.map_err(|e| Error::Msg(format!("Unable to fetch epoch transition proof: {:?}", e)))?;
+				if let Some(proof) = proof {
+					self.chain.insert_pending_transition(&mut tx, header.hash(), &PendingTransition {
+						proof: proof.clone(),
+					});
+					pending_transitions.insert(header.hash(), proof);
+				}
+
+				// ancestors from this batch aren't in the database yet.
+				epoch_proofs.push(self.engine.is_epoch_end_light(
+					header,
+					&|h| batch_headers.get(&h).map(|h| (*h).clone())
+						.or_else(|| self.chain.block_header(BlockId::Hash(h)).and_then(|hdr| hdr.decode().ok())),
+					&|h| pending_transitions.get(&h).map(|proof| PendingTransition { proof: proof.clone() })
+						.or_else(|| self.chain.pending_transition(h)),
+				));
+
+				parent = header;
+			}
+		}
+
+		let hashes: Vec<H256> = headers.iter().map(|h| h.hash()).collect();
+		let headers: Vec<_> = headers.into_iter().zip(epoch_proofs).collect();
+		let pending = self.chain.insert_batch(&mut tx, &headers)?;
+
+		self.db.write(tx).expect("Low level database error writing a transaction. Some issue with the disk?");
+		self.chain.apply_pending(pending);
+		self.report.write().blocks_imported += hashes.len();
+
+		self.notify(|listener| listener.new_headers(&hashes));
+		Ok(hashes)
+	}
+
 	/// Get a report about blocks imported.
 	pub fn report(&self) -> ClientReport {
 		self.report.read().clone()
@@ -490,17 +594,21 @@ impl<T: ChainDataFetcher> Client<T> {
 	}
 
 	// attempts to fetch the epoch proof from the network until successful.
-	fn write_pending_proof(&self, header: &Header, proof: Proof) -> Result<(), T::Error> {
-		let proof = match proof {
-			Proof::Known(known) => known,
+	fn fetch_epoch_proof(&self, header: &Header, proof: Proof) -> Result<Vec<u8>, T::Error> {
+		match proof {
+			Proof::Known(known) => Ok(known),
 			Proof::WithState(state_dependent) => {
 				self.fetcher.epoch_transition(
 					header.hash(),
 					self.engine.clone(),
 					state_dependent
-				).into_future().wait()?
+				).into_future().wait()
 			}
-		};
+		}
+	}
+
+	fn write_pending_proof(&self, header: &Header, proof: Proof) -> Result<(), T::Error> {
+		let proof = self.fetch_epoch_proof(header, proof)?;
 
 		let mut batch = self.db.transaction();
 		self.chain.insert_pending_transition(&mut batch, header.hash(), &PendingTransition {
@@ -527,6 +635,10 @@ impl<T: ChainDataFetcher> LightChainClient for Client<T> {
 		self.import_header(header)
 	}
 
+	fn import_header_batch(&self, headers: Vec<Header>) -> EthcoreResult<Vec<H256>> {
+		self.import_decoded_headers(headers)
+	}
+
 	fn block_hash(&self, id: BlockId) -> Option<H256> {
 		Client::block_hash(self, id)
 	}
@@ -624,3 +736,87 @@ impl<T: ChainDataFetcher> client_traits::EngineClient for Client<T> {
 }
 
 impl<T> client_traits::Tick for Client<T> {}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::time::Duration;
+
+	use cache::Cache;
+	use common_types::{encoded, header::Header, ids::BlockId};
+	use io::IoChannel;
+	use kvdb_memorydb;
+	use parking_lot::Mutex;
+	use spec;
+
+	use super::{Client, Config, fetch};
+
+	fn new_client(spec: &spec::Spec) -> Client<fetch::Unavailable> {
+		let db = Arc::new(kvdb_memorydb::create(1));
+		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::from_secs(6 * 3600))));
+		Client::new(Config::default(), db, 0, spec, fetch::unavailable(), IoChannel::disconnected(), cache).unwrap()
+	}
+
+	fn make_chain(genesis: &Header, len: u64) -> Vec<Header> {
+		let mut parent = genesis.clone();
+		(1..=len).map(|i| {
+			let mut header = Header::new();
+			header.set_parent_hash(parent.hash());
+			header.set_number(i);
+			header.set_timestamp(genesis.timestamp() + i * 10);
+			header.set_difficulty(*genesis.difficulty());
+			header.set_gas_limit(*genesis.gas_limit());
+			parent = header.clone();
+			header
+		}).collect()
+	}
+
+	fn encode(headers: &[Header]) -> Vec<encoded::Header> {
+		headers.iter().map(|h| encoded::Header::new(::rlp::encode(h))).collect()
+	}
+
+	#[test]
+	fn imports_header_batch() {
+		let spec = spec::new_test();
+		let client = new_client(&spec);
+		let chain = make_chain(&spec.genesis_header(), 2048);
+
+		let imported = client.import_headers(encode(&chain)).unwrap();
+
+		assert_eq!(imported.len(), 2048);
+		assert_eq!(client.chain_info().best_block_number, 2048);
+		assert_eq!(client.chain_info().best_block_hash, chain[2047].hash());
+		assert_eq!(client.block_hash(BlockId::Number(1024)), Some(chain[1023].hash()));
+		assert_eq!(client.report().blocks_imported, 2048);
+	}
+
+	#[test]
+	fn corrupted_header_rolls_back_batch() {
+		let spec = spec::new_test();
+		let client = new_client(&spec);
+		let mut chain = make_chain(&spec.genesis_header(), 192);
+		// gas used above the gas limit fails basic verification.
+		chain[100].set_gas_used(*chain[100].gas_limit() + 1);
+		for i in 101..chain.len() {
+			let parent_hash = chain[i - 1].hash();
+			chain[i].set_parent_hash(parent_hash);
+		}
+
+		assert!(client.import_headers(encode(&chain)).is_err());
+
+		assert_eq!(client.chain_info().best_block_number, 0);
+		assert!(client.block_header(BlockId::Number(1)).is_none());
+		assert_eq!(client.report().blocks_imported, 0);
+	}
+
+	#[test]
+	fn rejects_batch_with_broken_linkage() {
+		let spec = spec::new_test();
+		let client = new_client(&spec);
+		let mut chain = make_chain(&spec.genesis_header(), 10);
+		chain.remove(5);
+
+		assert!(client.import_headers(encode(&chain)).is_err());
+		assert_eq!(client.chain_info().best_block_number, 0);
+	}
+}
@@ -43,7 +43,7 @@ pub mod provider;
 mod types;
 
 pub use self::cache::Cache;
-pub use self::provider::{Provider, MAX_HEADERS_PER_REQUEST};
+pub use self::provider::{Provider, MAX_HEADERS_PER_REQUEST, MAX_RESPONSE_BYTES};
 pub use self::transaction_queue::TransactionQueue;
 pub use types::request as request;
 
@@ -77,6 +77,7 @@ extern crate trie_db as trie;
 extern crate patricia_trie_ethereum as ethtrie;
 extern crate fastmap;
 extern crate rand;
+extern crate rayon;
 extern crate rlp;
 extern crate parking_lot;
 #[macro_use]
@@ -98,5 +99,8 @@ extern crate ethcore;
 #[cfg(test)]
 extern crate kvdb_memorydb;
 #[cfg(test)]
+#[macro_use]
+extern crate proptest;
+#[cfg(test)]
 extern crate tempfile;
 extern crate journaldb;
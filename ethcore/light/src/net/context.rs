@@ -26,8 +26,8 @@ use request::NetworkRequests as Requests;
 /// disconnecting peers. This is used as a generalization of the portions
 /// of a p2p network which the light protocol structure makes use of.
 pub trait IoContext {
-	/// Send a packet to a specific peer.
-	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>);
+	/// Send a packet to a specific peer. Returns whether the packet was queued for sending.
+	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>) -> bool;
 
 	/// Respond to a peer's message. Only works if this context is a byproduct
 	/// of a packet handler.
@@ -50,9 +50,13 @@ pub trait IoContext {
 }
 
 impl<T> IoContext for T where T: ?Sized + NetworkContext {
-	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>) {
-		if let Err(e) = self.send(peer, packet_id, packet_body) {
-			debug!(target: "pip", "Error sending packet to peer {}: {}", peer, e);
+	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>) -> bool {
+		match self.send(peer, packet_id, packet_body) {
+			Ok(()) => true,
+			Err(e) => {
+				debug!(target: "pip", "Error sending packet to peer {}: {}", peer, e);
+				false
+			}
 		}
 	}
 
@@ -18,6 +18,7 @@
 //!
 //! This uses a "Provider" to answer requests.
 
+use common_types::client_types::ProveError;
 use common_types::transaction::UnverifiedTransaction;
 use ethereum_types::{H256, U256};
 use io::TimerToken;
@@ -27,6 +28,7 @@ use parking_lot::{Mutex, RwLock};
 use provider::Provider;
 use request::{Request, NetworkRequests as Requests, Response};
 use rlp::{RlpStream, Rlp};
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt;
 use std::ops::{BitOr, BitAnd, Not};
@@ -267,6 +269,12 @@ pub struct Config {
 	pub max_stored_seconds: u64,
 	/// The network config median peers (used as default peer count)
 	pub median_peers: f64,
+	/// Upper bound on the size of the state items collected for an execution proof, in bytes.
+	/// Execution is aborted once the proof would grow beyond it.
+	pub max_execution_witness_bytes: usize,
+	/// Upper bound on the size of a headers response, in bytes.
+	/// Responses are truncated to the number of headers fitting within it.
+	pub max_headers_response_bytes: usize,
 }
 
 impl Default for Config {
@@ -277,6 +285,8 @@ impl Default for Config {
 		Config {
 			max_stored_seconds: MAX_ACCUMULATED,
 			median_peers: MEDIAN_PEERS,
+			max_execution_witness_bytes: ::provider::MAX_EXECUTION_WITNESS_BYTES,
+			max_headers_response_bytes: ::provider::MAX_RESPONSE_BYTES,
 		}
 	}
 }
@@ -727,21 +737,30 @@ impl LightProtocol {
 
 			// fill the buffer with all non-propagated transactions.
 			let to_propagate = ready_transactions.iter()
-				.filter(|tx| prop_filter.insert(tx.hash()))
-				.map(|tx| &tx.transaction);
+				.filter(|tx| prop_filter.insert(tx.hash()));
 
 			buf.extend(to_propagate);
 
 			// propagate to the given peer.
 			if buf.is_empty() { continue }
-			io.send(*peer_id, packet::SEND_TRANSACTIONS, {
+			let hashes: Vec<_> = buf.iter().map(|tx| tx.hash()).collect();
+			let sent = io.send(*peer_id, packet::SEND_TRANSACTIONS, {
 				let mut stream = RlpStream::new_list(buf.len());
 				for pending_tx in buf.drain(..) {
-					stream.append(pending_tx);
+					stream.append(&pending_tx.transaction);
 				}
 
 				stream.out()
-			})
+			});
+
+			if sent {
+				self.provider.transactions_propagated(*peer_id, io.persistent_peer_id(*peer_id), &hashes);
+			} else {
+				// retry on the next round.
+				for hash in &hashes {
+					peer_info.propagated_transactions.remove(hash);
+				}
+			}
 		}
 	}
 
@@ -1020,11 +1039,24 @@ impl LightProtocol {
 		let num_requests = requests.requests().len();
 		trace!(target: "pip", "Beginning to respond to requests (id: {}) from peer {}", req_id, peer_id);
 
+		// headers requests are charged for the requested maximum up front,
+		// the difference is refunded when fewer headers are available.
+		let unserved_headers = Cell::new(0u64);
+
+		// set when the peer asked for an execution whose proof outgrew the witness cap.
+		let oversized_witness = Cell::new(false);
+
 		// respond to all requests until one fails.
 		let responses = requests.respond_to_all(|complete_req| {
 			let _timer = self.load_distribution.begin_timer(&complete_req);
 			match complete_req {
-				CompleteRequest::Headers(req) => self.provider.block_headers(req).map(Response::Headers),
+				CompleteRequest::Headers(req) => {
+					let max = req.max;
+					let response = self.provider.block_headers(req, self.config.max_headers_response_bytes);
+					let served = response.as_ref().map_or(0, |res| res.headers.len() as u64);
+					unserved_headers.set(unserved_headers.get().saturating_add(max.saturating_sub(served)));
+					response.map(Response::Headers)
+				},
 				CompleteRequest::HeaderProof(req) => self.provider.header_proof(req).map(Response::HeaderProof),
 				CompleteRequest::TransactionIndex(req) => self.provider.transaction_index(req).map(Response::TransactionIndex),
 				CompleteRequest::Body(req) => self.provider.block_body(req).map(Response::Body),
@@ -1032,12 +1064,30 @@ impl LightProtocol {
 				CompleteRequest::Account(req) => self.provider.account_proof(req).map(Response::Account),
 				CompleteRequest::Storage(req) => self.provider.storage_proof(req).map(Response::Storage),
 				CompleteRequest::Code(req) => self.provider.contract_code(req).map(Response::Code),
-				CompleteRequest::Execution(req) => self.provider.transaction_proof(req).map(Response::Execution),
+				CompleteRequest::Execution(req) => match self.provider.transaction_proof(req, self.config.max_execution_witness_bytes) {
+					Ok(response) => Some(Response::Execution(response)),
+					Err(ProveError::WitnessTooLarge { collected, cap }) => {
+						debug!(target: "pip", "Peer {} requested an execution proof over the witness cap ({} > {} bytes)", peer_id, collected, cap);
+						oversized_witness.set(true);
+						None
+					}
+					Err(ProveError::StateUnavailable) => None,
+				},
 				CompleteRequest::Signal(req) => self.provider.epoch_signal(req).map(Response::Signal),
 			}
 		});
 
 		trace!(target: "pip", "Responded to {}/{} requests in packet {}", responses.len(), num_requests, req_id);
+
+		if let Some(refund) = peer.local_flow.headers_cost(unserved_headers.get()) {
+			peer.local_flow.refund(&mut peer.local_credits, refund);
+		}
+
+		// the work done building the oversized proof isn't covered by the
+		// per-gas cost, so the peer forfeits the rest of its credits.
+		if oversized_witness.get() {
+			peer.local_credits.update_to(U256::zero());
+		}
 		trace!(target: "pip", "Peer {} has {} credits remaining.", peer_id, peer.local_credits.current());
 
 		io.respond(packet::RESPONSE, {
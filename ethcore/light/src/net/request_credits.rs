@@ -322,11 +322,16 @@ impl FlowParams {
 	/// Get a reference to the recharge rate.
 	pub fn recharge_rate(&self) -> &U256 { &self.recharge }
 
+	/// Compute the cost of serving the given number of headers.
+	pub fn headers_cost(&self, count: u64) -> Option<U256> {
+		self.costs.headers.map(|c| c * U256::from(count))
+	}
+
 	/// Compute the actual cost of a request, given the kind of request
 	/// and number of requests made.
 	pub fn compute_cost(&self, request: &Request) -> Option<U256> {
 		match *request {
-			Request::Headers(ref req) => self.costs.headers.map(|c| c * U256::from(req.max)),
+			Request::Headers(ref req) => self.headers_cost(req.max),
 			Request::HeaderProof(_) => self.costs.header_proof,
 			Request::TransactionIndex(_) => self.costs.transaction_index,
 			Request::Body(_) => self.costs.body,
@@ -18,9 +18,10 @@
 //! These don't test of the higher level logic on top of
 
 use common_types::blockchain_info::BlockChainInfo;
+use common_types::client_types::ProveError;
 use common_types::encoded;
 use common_types::ids::BlockId;
-use common_types::transaction::{Action, PendingTransaction};
+use common_types::transaction::{Action, PendingTransaction, Transaction};
 use ethcore::test_helpers::{EachBlockWith, TestBlockChainClient};
 use ethereum_types::{H256, U256, Address, BigEndianHash};
 use net::context::IoContext;
@@ -28,10 +29,12 @@ use net::load_timer::MOVING_SAMPLE_SIZE;
 use net::status::{Capabilities, Status};
 use net::{LightProtocol, Params, packet, Peer, Statistics};
 use network::{PeerId, NodeId};
+use parking_lot::RwLock;
 use provider::Provider;
 use request::*;
 use request;
 use rlp::{Rlp, RlpStream};
+use transaction_queue::TransactionQueue;
 
 use std::sync::Arc;
 use std::time::Instant;
@@ -65,8 +68,9 @@ enum Expect {
 }
 
 impl IoContext for Expect {
-	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>) {
+	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>) -> bool {
 		assert_eq!(self, &Expect::Send(peer, packet_id, packet_body));
+		true
 	}
 
 	fn respond(&self, packet_id: u8, packet_body: Vec<u8>) {
@@ -99,6 +103,7 @@ struct TestProvider(Arc<TestProviderInner>);
 
 struct TestProviderInner {
 	client: TestBlockChainClient,
+	txq: RwLock<TransactionQueue>,
 }
 
 impl Provider for TestProvider {
@@ -166,8 +171,8 @@ impl Provider for TestProvider {
 		None
 	}
 
-	fn transaction_proof(&self, _req: request::CompleteExecutionRequest) -> Option<request::ExecutionResponse> {
-		None
+	fn transaction_proof(&self, _req: request::CompleteExecutionRequest, _max_witness_bytes: usize) -> Result<request::ExecutionResponse, ProveError> {
+		Err(ProveError::StateUnavailable)
 	}
 
 	fn epoch_signal(&self, _req: request::CompleteSignalRequest) -> Option<request::SignalResponse> {
@@ -177,7 +182,13 @@ impl Provider for TestProvider {
 	}
 
 	fn transactions_to_propagate(&self) -> Vec<PendingTransaction> {
-		self.0.client.transactions_to_propagate()
+		let mut transactions = self.0.client.transactions_to_propagate();
+		transactions.extend(self.0.txq.read().transactions_to_propagate(0, 0));
+		transactions
+	}
+
+	fn transactions_propagated(&self, peer: PeerId, node_id: Option<NodeId>, hashes: &[H256]) {
+		self.0.txq.write().note_propagated(hashes, peer, node_id, 0);
 	}
 }
 
@@ -203,6 +214,7 @@ fn write_free_handshake(status: &Status, capabilities: &Capabilities, proto: &Li
 fn setup(capabilities: Capabilities) -> (Arc<TestProviderInner>, LightProtocol) {
 	let provider = Arc::new(TestProviderInner {
 		client: TestBlockChainClient::new(),
+		txq: RwLock::new(TransactionQueue::default()),
 	});
 
 	let proto = LightProtocol::new(Arc::new(TestProvider(provider.clone())), Params {
@@ -351,6 +363,60 @@ fn get_block_headers() {
 	proto.handle_packet(&expected, 1, packet::REQUEST, &request_body);
 }
 
+#[test]
+fn get_partial_block_headers() {
+	let capabilities = capabilities();
+
+	let (provider, proto) = setup(capabilities);
+	let flow_params = proto.flow_params.read().clone();
+
+	let cur_status = status(provider.client.chain_info());
+	let my_status = write_handshake(&cur_status, &capabilities, &proto);
+
+	provider.client.add_blocks(100, EachBlockWith::Nothing);
+
+	let cur_status = status(provider.client.chain_info());
+
+	{
+		let packet_body = write_handshake(&cur_status, &capabilities, &proto);
+		proto.on_connect(1, &Expect::Send(1, packet::STATUS, packet_body));
+		proto.handle_packet(&Expect::Nothing, 1, packet::STATUS, &my_status);
+	}
+
+	// only 10 of the requested headers are available.
+	let request = Request::Headers(IncompleteHeadersRequest {
+		start: HashOrNumber::Number(91).into(),
+		max: 20,
+		skip: 0,
+		reverse: false,
+	});
+
+	let req_id = 112;
+
+	let requests = encode_single(request.clone());
+	let request_body = make_packet(req_id, &requests);
+
+	let response = {
+		let headers: Vec<_> = (91..101).map(|i| provider.client.block_header(BlockId::Number(i)).unwrap()).collect();
+		assert_eq!(headers.len(), 10);
+
+		// charged only for the headers actually served.
+		let new_creds = *flow_params.limit()
+			- flow_params.compute_cost_multi(requests.requests()).unwrap()
+			+ flow_params.headers_cost(10).unwrap();
+
+		let response = vec![Response::Headers(HeadersResponse { headers })];
+
+		let mut stream = RlpStream::new_list(3);
+		stream.append(&req_id).append(&new_creds).append_list(&response);
+
+		stream.out()
+	};
+
+	let expected = Expect::Respond(packet::RESPONSE, response);
+	proto.handle_packet(&expected, 1, packet::REQUEST, &request_body);
+}
+
 #[test]
 fn get_block_bodies() {
 	let capabilities = capabilities();
@@ -782,6 +848,48 @@ fn get_transaction_index() {
 	proto.handle_packet(&expected, 1, packet::REQUEST, &request_body);
 }
 
+#[test]
+fn transactions_are_relayed_to_late_peers_until_confirmed() {
+	let mut our_capabilities = capabilities();
+	our_capabilities.tx_relay = false;
+
+	let (provider, proto) = setup(our_capabilities);
+	let status = status(provider.client.chain_info());
+	let connect = |peer: PeerId| {
+		let packet_body = write_handshake(&status, &our_capabilities, &proto);
+		proto.on_connect(peer, &Expect::Send(peer, packet::STATUS, packet_body));
+
+		let their_status = write_handshake(&status, &capabilities(), &proto);
+		proto.handle_packet(&Expect::Nothing, peer, packet::STATUS, &their_status);
+	};
+
+	let tx: PendingTransaction = Transaction::default().fake_sign(Address::zero()).into();
+	let hash = tx.hash();
+	let relayed = {
+		let mut stream = RlpStream::new_list(1);
+		stream.append(&tx.transaction);
+		stream.out()
+	};
+	provider.txq.write().import(tx).unwrap();
+
+	connect(1);
+	proto.propagate_transactions(&Expect::Send(1, packet::SEND_TRANSACTIONS, relayed.clone()));
+	proto.propagate_transactions(&Expect::Nothing);
+
+	// peers joining later get the transaction as well.
+	connect(2);
+	proto.propagate_transactions(&Expect::Send(2, packet::SEND_TRANSACTIONS, relayed));
+	let peers: Vec<_> = provider.txq.read().propagation_status(&hash).unwrap().peers.keys().cloned().collect();
+	assert_eq!(peers, vec![1, 2]);
+
+	// until the transaction is seen in a block.
+	let block_hash = H256::repeat_byte(1);
+	provider.txq.write().note_included(&hash, 1, block_hash);
+	assert_eq!(provider.txq.write().confirm_included(|_| Some(block_hash)), vec![hash]);
+	connect(3);
+	proto.propagate_transactions(&Expect::Nothing);
+}
+
 #[test]
 fn sync_statistics() {
 	let mut stats = Statistics::new();
@@ -18,10 +18,11 @@
 //! The request service is implemented using Futures. Higher level request handlers
 //! will take the raw data received here and extract meaningful results from it.
 
-use std::cmp;
-use std::collections::HashMap;
+use std::cmp::{self, Ordering};
+use std::collections::{BinaryHeap, HashMap};
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
 use std::time::Duration;
 
 use futures::{Poll, Future, Async};
@@ -63,6 +64,8 @@ pub const DEFAULT_RESPONSE_TIME_TO_LIVE: Duration = Duration::from_secs(10);
 pub const DEFAULT_MAX_REQUEST_BACKOFF_ROUNDS: usize = 10;
 /// The default number failed request to be regarded as failure
 pub const DEFAULT_NUM_CONSECUTIVE_FAILED_REQUESTS: usize = 1;
+/// The default maximum number of requests in flight at once
+pub const DEFAULT_MAX_IN_FLIGHT_REQUESTS: usize = usize::max_value();
 
 /// OnDemand related errors
 pub mod error {
@@ -93,21 +96,61 @@ pub mod error {
 	pub type Result<T> = std::result::Result<T, Error>;
 }
 
+/// Scheduling priority of a request batch. When more requests are queued than
+/// can be dispatched, higher priorities go first and equal priorities keep
+/// submission order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+	/// Background work, e.g. epoch proofs for header import or inclusion checks.
+	Low,
+	/// The default priority.
+	Normal,
+	/// User-facing queries, e.g. RPC requests.
+	High,
+}
+
+impl Default for Priority {
+	fn default() -> Self {
+		Priority::Normal
+	}
+}
+
 /// Public interface for performing network requests `OnDemand`
 pub trait OnDemandRequester: Send + Sync {
-	/// Submit a strongly-typed batch of requests.
+	/// Submit a strongly-typed batch of requests with the given priority.
 	///
 	/// Fails if back-reference are not coherent.
-	fn request<T>(&self, ctx: &dyn BasicContext, requests: T) -> Result<OnResponses<T>, basic_request::NoSuchOutput>
+	fn request_with_priority<T>(&self, ctx: &dyn BasicContext, requests: T, priority: Priority)
+		-> Result<OnResponses<T>, basic_request::NoSuchOutput>
 	where
 		T: request::RequestAdapter;
 
-	/// Submit a vector of requests to be processed together.
+	/// Submit a vector of requests to be processed together with the given priority.
 	///
 	/// Fails if back-references are not coherent.
 	/// The returned vector of responses will correspond to the requests exactly.
-	fn request_raw(&self, ctx: &dyn BasicContext, requests: Vec<Request>)
+	fn request_raw_with_priority(&self, ctx: &dyn BasicContext, requests: Vec<Request>, priority: Priority)
 		-> Result<Receiver<PendingResponse>, basic_request::NoSuchOutput>;
+
+	/// Submit a strongly-typed batch of requests with `Priority::Normal`.
+	///
+	/// Fails if back-reference are not coherent.
+	fn request<T>(&self, ctx: &dyn BasicContext, requests: T) -> Result<OnResponses<T>, basic_request::NoSuchOutput>
+	where
+		T: request::RequestAdapter
+	{
+		self.request_with_priority(ctx, requests, Priority::default())
+	}
+
+	/// Submit a vector of requests to be processed together with `Priority::Normal`.
+	///
+	/// Fails if back-references are not coherent.
+	/// The returned vector of responses will correspond to the requests exactly.
+	fn request_raw(&self, ctx: &dyn BasicContext, requests: Vec<Request>)
+		-> Result<Receiver<PendingResponse>, basic_request::NoSuchOutput>
+	{
+		self.request_raw_with_priority(ctx, requests, Priority::default())
+	}
 }
 
 
@@ -149,8 +192,33 @@ struct Pending {
 	sender: oneshot::Sender<PendingResponse>,
 	request_guard: RequestGuard,
 	response_guard: ResponseGuard,
+	priority: Priority,
+	// submission order, used to keep requests of equal priority first-in first-out.
+	sequence: usize,
+}
+
+// `Pending` is ordered by scheduling precedence: the greatest element is dispatched first.
+impl Ord for Pending {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.priority.cmp(&other.priority)
+			.then_with(|| other.sequence.cmp(&self.sequence))
+	}
 }
 
+impl PartialOrd for Pending {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl PartialEq for Pending {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl Eq for Pending {}
+
 impl Pending {
 	// answer as many of the given requests from the supplied cache as possible.
 	// TODO: support re-shuffling.
@@ -179,7 +247,7 @@ impl Pending {
 	// verification.
 	// `idx` is the index of the request the response corresponds to.
 	fn update_header_refs(&mut self, idx: usize, response: &Response) {
-		if let Response::HeaderByHash(ref hdr) = *response {
+		if let Response::HeaderByHash(ref hdr) | Response::HeaderDescendant(ref hdr) = *response {
 				// fill the header for all requests waiting on this one.
 				// TODO: could be faster if we stored a map usize => Vec<usize>
 				// but typical use just has one header request that others
@@ -199,6 +267,7 @@ impl Pending {
 		match self.requests.supply_response(&cache, response) {
 			Ok(response) => {
 				let idx = self.responses.len();
+				self.requests.supply_checked_outputs(&response);
 				self.update_header_refs(idx, &response);
 				self.responses.push(response);
 				Ok(())
@@ -238,8 +307,12 @@ impl Pending {
 			// been filled by now. all remaining requests point to nothing earlier
 			// than the next unanswered request.
 			net_req.adjust_refs(&mut mapping);
-			builder.push(net_req)
-				.expect("all back-references to answered requests have been filled; qed");
+
+			// requests referring to outputs which are only known once an earlier response
+			// is checked, like the hash of a `HeaderDescendant`, are sent in a later round.
+			if builder.push(net_req).is_err() {
+				break
+			}
 		}
 
 		// update pending fields.
@@ -255,6 +328,7 @@ impl Pending {
 				CheckedRequest::HeaderProof(_, _) => "HeaderProof",
 				CheckedRequest::HeaderByHash(_, _) => "HeaderByHash",
 				CheckedRequest::HeaderWithAncestors(_, _) => "HeaderWithAncestors",
+				CheckedRequest::HeaderDescendant(_, _) => "HeaderDescendant",
 				CheckedRequest::TransactionIndex(_, _) => "TransactionIndex",
 				CheckedRequest::Receipts(_, _) => "Receipts",
 				CheckedRequest::Body(_, _) => "Body",
@@ -310,6 +384,8 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 				caps.serve_headers = true,
 			CheckedRequest::HeaderWithAncestors(_, _) =>
 				caps.serve_headers = true,
+			CheckedRequest::HeaderDescendant(_, _) =>
+				caps.serve_headers = true,
 			CheckedRequest::TransactionIndex(_, _) => {} // hashes yield no info.
 			CheckedRequest::Signal(_, _) =>
 				caps.serve_headers = true,
@@ -360,7 +436,7 @@ impl<T: request::RequestAdapter> Future for OnResponses<T> {
 /// requests to them accordingly.
 // lock in declaration order.
 pub struct OnDemand {
-	pending: RwLock<Vec<Pending>>,
+	pending: RwLock<BinaryHeap<Pending>>,
 	peers: RwLock<HashMap<PeerId, Peer>>,
 	in_transit: RwLock<HashMap<ReqId, Pending>>,
 	cache: Arc<Mutex<Cache>>,
@@ -369,11 +445,13 @@ pub struct OnDemand {
 	request_backoff_start: Duration,
 	request_backoff_max: Duration,
 	request_backoff_rounds_max: usize,
-	request_number_of_consecutive_errors: usize
+	request_number_of_consecutive_errors: usize,
+	max_in_flight: usize,
+	next_sequence: AtomicUsize,
 }
 
 impl OnDemandRequester for OnDemand {
-	fn request_raw(&self, ctx: &dyn BasicContext, requests: Vec<Request>)
+	fn request_raw_with_priority(&self, ctx: &dyn BasicContext, requests: Vec<Request>, priority: Priority)
 		-> Result<Receiver<PendingResponse>, basic_request::NoSuchOutput>
 	{
 		let (sender, receiver) = oneshot::channel();
@@ -400,8 +478,10 @@ impl OnDemandRequester for OnDemand {
 					_ => return Err(basic_request::NoSuchOutput),
 				}
 			}
-			if let CheckedRequest::HeaderByHash(ref req, _) = request {
-				header_producers.insert(i, req.0);
+			match request {
+				CheckedRequest::HeaderByHash(ref req, _) => { header_producers.insert(i, req.0); }
+				CheckedRequest::HeaderDescendant(..) => { header_producers.insert(i, basic_request::Field::back_ref(i, 0)); }
+				_ => {}
 			}
 
 			builder.push(request)?;
@@ -424,15 +504,18 @@ impl OnDemandRequester for OnDemand {
 				self.request_backoff_max,
 			),
 			response_guard: ResponseGuard::new(self.response_time_window),
+			priority,
+			sequence: self.next_sequence.fetch_add(1, AtomicOrdering::Relaxed),
 		});
 
 		Ok(receiver)
 	}
 
-	fn request<T>(&self, ctx: &dyn BasicContext, requests: T) -> Result<OnResponses<T>, basic_request::NoSuchOutput>
+	fn request_with_priority<T>(&self, ctx: &dyn BasicContext, requests: T, priority: Priority)
+		-> Result<OnResponses<T>, basic_request::NoSuchOutput>
 		where T: request::RequestAdapter
 	{
-		self.request_raw(ctx, requests.make_requests()).map(|recv| OnResponses {
+		self.request_raw_with_priority(ctx, requests.make_requests(), priority).map(|recv| OnResponses {
 			receiver: recv,
 			_marker: PhantomData,
 		})
@@ -450,10 +533,11 @@ impl OnDemand {
 		request_backoff_max: Duration,
 		request_backoff_rounds_max: usize,
 		request_number_of_consecutive_errors: usize,
+		max_in_flight: usize,
 	) -> Self {
 
 		Self {
-			pending: RwLock::new(Vec::new()),
+			pending: RwLock::new(BinaryHeap::new()),
 			peers: RwLock::new(HashMap::new()),
 			in_transit: RwLock::new(HashMap::new()),
 			cache,
@@ -463,6 +547,8 @@ impl OnDemand {
 			request_backoff_max: Self::sanitize_circuit_breaker_input(request_backoff_max, "Request maximum backoff time window"),
 			request_backoff_rounds_max,
 			request_number_of_consecutive_errors,
+			max_in_flight: Self::sanitize_max_in_flight(max_in_flight),
+			next_sequence: AtomicUsize::new(0),
 		}
 	}
 
@@ -476,6 +562,16 @@ impl OnDemand {
 		}
 	}
 
+	fn sanitize_max_in_flight(max_in_flight: usize) -> usize {
+		if max_in_flight == 0 {
+			warn!(target: "on_demand",
+				"Maximum number of requests in flight must be at least 1, configuring it to 1");
+			1
+		} else {
+			max_in_flight
+		}
+	}
+
 	// make a test version: this doesn't dispatch pending requests
 	// until you trigger it manually.
 	#[cfg(test)]
@@ -486,6 +582,7 @@ impl OnDemand {
 		request_backoff_max: Duration,
 		request_backoff_rounds_max: usize,
 		request_number_of_consecutive_errors: usize,
+		max_in_flight: usize,
 	) -> Self {
 		let mut me = OnDemand::new(
 			cache,
@@ -494,6 +591,7 @@ impl OnDemand {
 			request_backoff_max,
 			request_backoff_rounds_max,
 			request_number_of_consecutive_errors,
+			max_in_flight,
 		);
 		me.no_immediate_dispatch = true;
 
@@ -510,7 +608,8 @@ impl OnDemand {
 	}
 
 	// dispatch pending requests, and discard those for which the corresponding
-	// receiver has been dropped.
+	// receiver has been dropped. requests are tried in priority order, and those
+	// beyond `max_in_flight` stay queued until responses free up their slots.
 	fn dispatch_pending(&self, ctx: &dyn BasicContext) {
 		if self.pending.read().is_empty() {
 			return
@@ -521,11 +620,17 @@ impl OnDemand {
 		// iterate over all pending requests, and check them for hang-up.
 		// then, try and find a peer who can serve it.
 		let peers = self.peers.read();
+		let mut in_flight = self.in_transit.read().len();
 
-		*pending = ::std::mem::replace(&mut *pending, Vec::new())
+		*pending = ::std::mem::replace(&mut *pending, BinaryHeap::new())
+			.into_sorted_vec()
 			.into_iter()
+			.rev()
 			.filter(|pending| !pending.sender.is_canceled())
 			.filter_map(|mut pending| {
+				if in_flight >= self.max_in_flight {
+					return Some(pending);
+				}
 
 				let num_peers = peers.len();
 				// The first peer to dispatch the request is chosen at random
@@ -546,6 +651,7 @@ impl OnDemand {
 					if pending.request_guard.is_call_permitted() {
 						if let Ok(req_id) = ctx.request_from(*peer_id, pending.net_requests.clone()) {
 							self.in_transit.write().insert(req_id, pending);
+							in_flight += 1;
 							return None;
 						}
 					}
@@ -580,6 +686,38 @@ impl OnDemand {
 			self.attempt_dispatch(ctx);
 		}
 	}
+
+	// process responses to a request which is no longer in transit.
+	fn handle_responses(&self, ctx: &dyn EventContext, mut pending: Pending, responses: &[basic_request::Response]) {
+		if responses.is_empty() {
+			// Max number of `bad` responses reached, drop the request
+			if let Err(e) = pending.response_guard.register_error(&ResponseError::Validity(ValidityError::Empty)) {
+				pending.bad_response(e);
+				return;
+			}
+		}
+
+		// for each incoming response
+		//   1. ensure verification data filled.
+		//   2. pending.requests.supply_response
+		//   3. if extracted on-demand response, keep it for later.
+		for response in responses {
+			if let Err(e) = pending.supply_response(&*self.cache, response) {
+				let peer = ctx.peer();
+				debug!(target: "on_demand", "Peer {} gave bad response: {:?}", peer, e);
+				ctx.disable_peer(peer);
+
+				// Max number of `bad` responses reached, drop the request
+				if let Err(err) = pending.response_guard.register_error(&e) {
+					pending.bad_response(err);
+					return;
+				}
+			}
+		}
+
+		pending.fill_unanswered();
+		self.submit_pending(ctx.as_basic(), pending);
+	}
 }
 
 impl Handler for OnDemand {
@@ -627,39 +765,15 @@ impl Handler for OnDemand {
 	}
 
 	fn on_responses(&self, ctx: &dyn EventContext, req_id: ReqId, responses: &[basic_request::Response]) {
-		let mut pending = match self.in_transit.write().remove(&req_id) {
+		let pending = match self.in_transit.write().remove(&req_id) {
 			Some(req) => req,
 			None => return,
 		};
 
-		if responses.is_empty() {
-			// Max number of `bad` responses reached, drop the request
-			if let Err(e) = pending.response_guard.register_error(&ResponseError::Validity(ValidityError::Empty)) {
-				pending.bad_response(e);
-				return;
-			}
-		}
-
-		// for each incoming response
-		//   1. ensure verification data filled.
-		//   2. pending.requests.supply_response
-		//   3. if extracted on-demand response, keep it for later.
-		for response in responses {
-			if let Err(e) = pending.supply_response(&*self.cache, response) {
-				let peer = ctx.peer();
-				debug!(target: "on_demand", "Peer {} gave bad response: {:?}", peer, e);
-				ctx.disable_peer(peer);
-
-				// Max number of `bad` responses reached, drop the request
-				if let Err(err) = pending.response_guard.register_error(&e) {
-					pending.bad_response(err);
-					return;
-				}
-			}
-		}
+		self.handle_responses(ctx, pending, responses);
 
-		pending.fill_unanswered();
-		self.submit_pending(ctx.as_basic(), pending);
+		// the request no longer counts towards `max_in_flight`.
+		self.attempt_dispatch(ctx.as_basic());
 	}
 
 	fn tick(&self, ctx: &dyn BasicContext) {
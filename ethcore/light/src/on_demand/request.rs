@@ -48,6 +48,8 @@ pub enum Request {
 	HeaderByHash(HeaderByHash),
 	/// A request for a header by hash with a range of its ancestors.
 	HeaderWithAncestors(HeaderWithAncestors),
+	/// A request for a header by number, linked to a known ancestor.
+	HeaderDescendant(HeaderDescendant),
 	/// A request for the index of a transaction.
 	TransactionIndex(TransactionIndex),
 	/// A request for block receipts.
@@ -138,6 +140,7 @@ macro_rules! impl_single {
 impl_single!(HeaderProof, HeaderProof, (H256, U256));
 impl_single!(HeaderByHash, HeaderByHash, encoded::Header);
 impl_single!(HeaderWithAncestors, HeaderWithAncestors, Vec<encoded::Header>);
+impl_single!(HeaderDescendant, HeaderDescendant, encoded::Header);
 impl_single!(TransactionIndex, TransactionIndex, net_request::TransactionIndexResponse);
 impl_single!(Receipts, BlockReceipts, Vec<Receipt>);
 impl_single!(Body, Body, encoded::Block);
@@ -249,6 +252,7 @@ pub enum CheckedRequest {
 	HeaderProof(HeaderProof, net_request::IncompleteHeaderProofRequest),
 	HeaderByHash(HeaderByHash, net_request::IncompleteHeadersRequest),
 	HeaderWithAncestors(HeaderWithAncestors, net_request::IncompleteHeadersRequest),
+	HeaderDescendant(HeaderDescendant, net_request::IncompleteHeadersRequest),
 	TransactionIndex(TransactionIndex, net_request::IncompleteTransactionIndexRequest),
 	Receipts(BlockReceipts, net_request::IncompleteReceiptsRequest),
 	Body(Body, net_request::IncompleteBodyRequest),
@@ -281,6 +285,16 @@ impl From<Request> for CheckedRequest {
 				trace!(target: "on_demand", "HeaderWithAncestors Request, {:?}", net_req);
 				CheckedRequest::HeaderWithAncestors(req, net_req)
 			}
+			Request::HeaderDescendant(req) => {
+				let net_req = net_request::IncompleteHeadersRequest {
+					start: req.ancestor_hash.map(Into::into),
+					skip: 0,
+					max: req.num - req.ancestor_number + 1,
+					reverse: false,
+				};
+				trace!(target: "on_demand", "HeaderDescendant Request, {:?}", net_req);
+				CheckedRequest::HeaderDescendant(req, net_req)
+			}
 			Request::HeaderProof(req) => {
 				let net_req = net_request::IncompleteHeaderProofRequest {
 					num: req.num().into(),
@@ -358,6 +372,7 @@ impl CheckedRequest {
 			CheckedRequest::HeaderProof(_, req) => NetRequest::HeaderProof(req),
 			CheckedRequest::HeaderByHash(_, req) => NetRequest::Headers(req),
 			CheckedRequest::HeaderWithAncestors(_, req) => NetRequest::Headers(req),
+			CheckedRequest::HeaderDescendant(_, req) => NetRequest::Headers(req),
 			CheckedRequest::TransactionIndex(_, req) => NetRequest::TransactionIndex(req),
 			CheckedRequest::Receipts(_, req) => NetRequest::Receipts(req),
 			CheckedRequest::Body(_, req) => NetRequest::Body(req),
@@ -503,6 +518,7 @@ macro_rules! match_me {
 			CheckedRequest::HeaderProof($check, $req) => $e,
 			CheckedRequest::HeaderByHash($check, $req) => $e,
 			CheckedRequest::HeaderWithAncestors($check, $req) => $e,
+			CheckedRequest::HeaderDescendant($check, $req) => $e,
 			CheckedRequest::TransactionIndex($check, $req) => $e,
 			CheckedRequest::Receipts($check, $req) => $e,
 			CheckedRequest::Body($check, $req) => $e,
@@ -541,6 +557,15 @@ impl IncompleteRequest for CheckedRequest {
 					_ => Ok(()),
 				}
 			}
+			CheckedRequest::HeaderDescendant(ref check, ref req) => {
+				req.check_outputs(&mut f)?;
+
+				// make sure the output given is definitively a hash.
+				match check.ancestor_hash {
+					Field::BackReference(r, idx) => f(r, idx, OutputKind::Hash),
+					_ => Ok(()),
+				}
+			}
 			CheckedRequest::TransactionIndex(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Receipts(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Body(_, ref req) => req.check_outputs(f),
@@ -551,8 +576,13 @@ impl IncompleteRequest for CheckedRequest {
 		}
 	}
 
-	fn note_outputs<F>(&self, f: F) where F: FnMut(usize, OutputKind) {
-		match_me!(*self, (_, ref req) => req.note_outputs(f))
+	fn note_outputs<F>(&self, mut f: F) where F: FnMut(usize, OutputKind) {
+		match *self {
+			// the hash of the requested header is only known once the response is checked,
+			// so requests referring to it can't be sent along with this one.
+			CheckedRequest::HeaderDescendant(..) => f(0, OutputKind::Hash),
+			_ => match_me!(*self, (_, ref req) => req.note_outputs(f)),
+		}
 	}
 
 	fn fill<F>(&mut self, f: F) where F: Fn(usize, usize) -> Result<Output, net_request::NoSuchOutput> {
@@ -573,6 +603,10 @@ impl IncompleteRequest for CheckedRequest {
 				trace!(target: "on_demand", "HeaderWithAncestors request completed {:?}", req);
 				req.complete().map(CompleteRequest::Headers)
 			}
+			CheckedRequest::HeaderDescendant(_, req) => {
+				trace!(target: "on_demand", "HeaderDescendant request completed {:?}", req);
+				req.complete().map(CompleteRequest::Headers)
+			}
 			CheckedRequest::TransactionIndex(_, req) => {
 				trace!(target: "on_demand", "TransactionIndex request completed {:?}", req);
 				req.complete().map(CompleteRequest::TransactionIndex)
@@ -639,6 +673,9 @@ impl net_request::CheckedRequest for CheckedRequest {
 			CheckedRequest::HeaderWithAncestors(ref prover, _) =>
 				expect!((&NetResponse::Headers(ref res), &CompleteRequest::Headers(ref req)) =>
 					prover.check_response(cache, &req.start, &res.headers).map(Response::HeaderWithAncestors)),
+			CheckedRequest::HeaderDescendant(ref prover, _) =>
+				expect!((&NetResponse::Headers(ref res), &CompleteRequest::Headers(ref req)) =>
+					prover.check_response(cache, &req.start, &res.headers).map(Response::HeaderDescendant)),
 			CheckedRequest::TransactionIndex(ref prover, _) =>
 				expect!((&NetResponse::TransactionIndex(ref res), _) =>
 					prover.check_response(cache, res).map(Response::TransactionIndex)),
@@ -674,6 +711,8 @@ pub enum Response {
 	HeaderByHash(encoded::Header),
 	/// Response to a header-by-hash with ancestors request.
 	HeaderWithAncestors(Vec<encoded::Header>),
+	/// Response to a header-by-number linked to an ancestor request.
+	HeaderDescendant(encoded::Header),
 	/// Response to a transaction-index request.
 	TransactionIndex(net_request::TransactionIndexResponse),
 	/// Response to a receipts request.
@@ -695,6 +734,7 @@ impl net_request::ResponseLike for Response {
 	fn fill_outputs<F>(&self, mut f: F) where F: FnMut(usize, Output) {
 		match *self {
 			Response::HeaderProof((ref hash, _)) => f(0, Output::Hash(*hash)),
+			Response::HeaderDescendant(ref header) => f(0, Output::Hash(header.hash())),
 			Response::Account(None) => {
 				f(0, Output::Hash(KECCAK_EMPTY)); // code hash
 				f(1, Output::Hash(KECCAK_NULL_RLP)); // storage root.
@@ -737,6 +777,8 @@ pub enum Error {
 	WrongKind,
 	/// Wrong sequence of headers.
 	WrongHeaderSequence,
+	/// Header with an invalid seal or difficulty.
+	InvalidHeader(H256),
 }
 
 impl From<::rlp::DecoderError> for Error {
@@ -856,6 +898,104 @@ impl HeaderWithAncestors {
 	}
 }
 
+/// Request for a header by number which isn't covered by a CHT yet, e.g. near the chain head.
+/// The header is fetched along with the headers linking it to a known ancestor.
+#[derive(Clone)]
+pub struct HeaderDescendant {
+	/// Hash of the known ancestor.
+	ancestor_hash: Field<H256>,
+	/// Number of the known ancestor.
+	ancestor_number: u64,
+	/// The requested header's number.
+	num: u64,
+	/// Consensus engine, used to check the seal and difficulty of the fetched headers.
+	engine: Arc<dyn Engine>,
+}
+
+impl HeaderDescendant {
+	/// Construct a new request for header `num`, linked to the ancestor with the given hash and
+	/// number. Fails if `num` is not after the ancestor or too far from it for a single request.
+	pub fn new(num: u64, ancestor_hash: Field<H256>, ancestor_number: u64, engine: Arc<dyn Engine>) -> Option<Self> {
+		if num <= ancestor_number || num - ancestor_number >= ::MAX_HEADERS_PER_REQUEST {
+			return None;
+		}
+
+		Some(HeaderDescendant {
+			ancestor_hash,
+			ancestor_number,
+			num,
+			engine,
+		})
+	}
+
+	/// Access the requested block number.
+	pub fn num(&self) -> u64 { self.num }
+
+	/// Check a response for the headers, get the requested header back.
+	pub fn check_response(
+		&self,
+		cache: &Mutex<::cache::Cache>,
+		start: &net_request::HashOrNumber,
+		headers: &[encoded::Header]
+	) -> Result<encoded::Header, Error> {
+		let expected_hash = match (self.ancestor_hash, start) {
+			(Field::Scalar(h), &net_request::HashOrNumber::Hash(h2)) => {
+				if h != h2 { return Err(Error::WrongHash(h, h2)) }
+				h
+			}
+			(_, &net_request::HashOrNumber::Hash(h2)) => h2,
+			_ => return Err(Error::HeaderByNumber),
+		};
+
+		let ancestor = headers.first().ok_or(Error::Empty)?;
+		let ancestor_hash = ancestor.hash();
+		if ancestor_hash != expected_hash {
+			return Err(Error::WrongHash(expected_hash, ancestor_hash));
+		}
+		if ancestor.number() != self.ancestor_number {
+			return Err(Error::WrongNumber(self.ancestor_number, ancestor.number()));
+		}
+
+		let expected_len = self.num - self.ancestor_number + 1;
+		let actual_len = headers.len() as u64;
+		match actual_len.cmp(&expected_len) {
+			cmp::Ordering::Less =>
+				return Err(Error::TooFewResults(expected_len, actual_len)),
+			cmp::Ordering::Greater =>
+				return Err(Error::TooManyResults(expected_len, actual_len)),
+			cmp::Ordering::Equal => (),
+		};
+
+		for (parent, header) in headers.iter().zip(headers[1..].iter()) {
+			if header.number() != parent.number() + 1 ||
+				header.parent_hash() != parent.hash()
+			{
+				return Err(Error::WrongHeaderSequence)
+			}
+		}
+
+		// the ancestor is already known, but none of the headers after it are:
+		// check their seals and difficulties like the header queue would.
+		let decoded = headers.iter().map(|hdr| hdr.decode()).collect::<Result<Vec<_>, _>>()?;
+		for (parent, header) in decoded.iter().zip(decoded[1..].iter()) {
+			self.engine.verify_block_basic(header)
+				.and_then(|_| self.engine.verify_block_unordered(header))
+				.and_then(|_| self.engine.verify_block_family(header, parent))
+				.map_err(|e| {
+					trace!(target: "on_demand", "Invalid descendant header {}: {}", header.hash(), e);
+					Error::InvalidHeader(header.hash())
+				})?;
+		}
+
+		let mut cache = cache.lock();
+		for header in &headers[1..] {
+			cache.insert_block_header(header.hash(), header.clone());
+		}
+
+		Ok(headers[headers.len() - 1].clone())
+	}
+}
+
 /// Request for a header by hash.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HeaderByHash(pub Field<H256>);
@@ -1231,6 +1371,59 @@ mod tests {
 				Err(Error::WrongHeaderSequence));
 	}
 
+	#[test]
+	fn check_header_descendant() {
+		let mut last_header_hash = H256::zero();
+		let headers = (10..15).map(|num| {
+			let mut header = Header::new();
+			header.set_number(num);
+			header.set_parent_hash(last_header_hash);
+
+			last_header_hash = header.hash();
+			header
+		}).collect::<Vec<_>>();
+
+		let raw_headers = headers.iter()
+			.map(|hdr| encoded::Header::new(::rlp::encode(hdr)))
+			.collect::<Vec<_>>();
+
+		let cache = Mutex::new(make_cache());
+		let ancestor = headers[0].hash();
+		let engine = ::spec::new_test().engine;
+		let descendant = |num| HeaderDescendant::new(num, ancestor.into(), 10, engine.clone()).unwrap();
+
+		assert!(HeaderDescendant::new(10, ancestor.into(), 10, engine.clone()).is_none());
+		assert!(HeaderDescendant::new(10 + ::MAX_HEADERS_PER_REQUEST, ancestor.into(), 10, engine.clone()).is_none());
+
+		// Correct responses
+		assert_eq!(descendant(11).check_response(&cache, &ancestor.into(), &raw_headers[0..2]), Ok(raw_headers[1].clone()));
+		assert_eq!(descendant(14).check_response(&cache, &ancestor.into(), &raw_headers), Ok(raw_headers[4].clone()));
+		assert_eq!(cache.lock().block_header(&headers[4].hash()), Some(raw_headers[4].clone()));
+
+		// Incorrect responses
+		assert_eq!(descendant(14).check_response(&cache, &ancestor.into(), &raw_headers[1..]),
+				Err(Error::WrongHash(ancestor, headers[1].hash())));
+		assert_eq!(descendant(14).check_response(&cache, &ancestor.into(), &raw_headers[0..4]),
+				Err(Error::TooFewResults(5, 4)));
+		assert_eq!(descendant(13).check_response(&cache, &ancestor.into(), &raw_headers),
+				Err(Error::TooManyResults(4, 5)));
+		assert_eq!(descendant(14).check_response(&cache, &ancestor.into(), &[]), Err(Error::Empty));
+
+		// a forged header which doesn't descend from the ancestor.
+		let mut forged = headers[4].clone();
+		forged.set_parent_hash(H256::repeat_byte(1));
+		let mut response = raw_headers[0..4].to_vec();
+		response.push(encoded::Header::new(::rlp::encode(&forged)));
+		assert_eq!(descendant(14).check_response(&cache, &ancestor.into(), &response), Err(Error::WrongHeaderSequence));
+		assert_eq!(cache.lock().block_header(&forged.hash()), None);
+
+		// correctly linked headers without a valid proof of work.
+		let ethash = HeaderDescendant::new(14, ancestor.into(), 10, ::spec::new_frontier_test().engine).unwrap();
+		let cache = Mutex::new(make_cache());
+		assert_eq!(ethash.check_response(&cache, &ancestor.into(), &raw_headers), Err(Error::InvalidHeader(headers[1].hash())));
+		assert_eq!(cache.lock().block_header(&headers[4].hash()), None);
+	}
+
 	#[test]
 	fn check_body() {
 		use rlp::RlpStream;
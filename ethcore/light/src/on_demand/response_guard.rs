@@ -64,6 +64,8 @@ pub enum Inner {
 	TooFewResults,
 	/// Too many results
 	TooManyResults,
+	/// Header with an invalid seal or difficulty
+	InvalidHeader,
 	/// Trie error
 	Trie,
 	/// Unresolved header
@@ -111,6 +113,7 @@ impl ResponseGuard {
 			ResponseError::Validity(ValidityError::HeaderByNumber) => Inner::HeaderByNumber,
 			ResponseError::Validity(ValidityError::TooFewResults(_, _)) => Inner::TooFewResults,
 			ResponseError::Validity(ValidityError::TooManyResults(_, _)) => Inner::TooManyResults,
+			ResponseError::Validity(ValidityError::InvalidHeader(_)) => Inner::InvalidHeader,
 			ResponseError::Validity(ValidityError::Trie(_)) => Inner::Trie,
 			ResponseError::Validity(ValidityError::UnresolvedHeader(_)) => Inner::UnresolvedHeader,
 			ResponseError::Validity(ValidityError::WrongHash(_, _)) => Inner::WrongHash,
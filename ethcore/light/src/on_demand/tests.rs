@@ -29,7 +29,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use std::thread;
 
-use super::{request, OnDemand, OnDemandRequester, Peer, HeaderRef};
+use super::{request, OnDemand, OnDemandRequester, Peer, HeaderRef, Priority};
 
 // useful contexts to give the service.
 enum Context {
@@ -91,6 +91,10 @@ struct Harness {
 
 impl Harness {
 	fn create() -> Self {
+		Self::with_max_in_flight(super::DEFAULT_MAX_IN_FLIGHT_REQUESTS)
+	}
+
+	fn with_max_in_flight(max_in_flight: usize) -> Self {
 		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::from_secs(60))));
 		Harness {
 			service: OnDemand::new_test(
@@ -102,7 +106,8 @@ impl Harness {
 				// Request max backoff
 				Duration::from_secs(20),
 				super::DEFAULT_MAX_REQUEST_BACKOFF_ROUNDS,
-				super::DEFAULT_NUM_CONSECUTIVE_FAILED_REQUESTS
+				super::DEFAULT_NUM_CONSECUTIVE_FAILED_REQUESTS,
+				max_in_flight,
 			)
 		}
 	}
@@ -183,6 +188,119 @@ fn single_request() {
 	assert!(recv.wait().is_ok());
 }
 
+#[test]
+fn max_in_flight_queues_requests() {
+	let harness = Harness::with_max_in_flight(1);
+
+	let peer_id = 10101;
+	let first_id = ReqId(14426);
+	let second_id = ReqId(14427);
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let first = Header::default();
+	let mut second = Header::default();
+	second.set_number(1);
+
+	let first_recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![request::HeaderByHash(first.hash().into()).into()]
+	).unwrap();
+	let second_recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![request::HeaderByHash(second.hash().into()).into()]
+	).unwrap();
+
+	assert_eq!(harness.service.pending.read().len(), 2);
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, first_id));
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	// the peer would accept the second request, but the only slot is taken.
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, second_id));
+	assert_eq!(harness.service.pending.read().len(), 1);
+	assert!(!harness.service.in_transit.read().contains_key(&second_id));
+
+	harness.service.on_responses(
+		&Context::WithPeer(peer_id),
+		first_id,
+		&[Response::Headers(basic_request::HeadersResponse { headers: vec![first.encoded()] })]
+	);
+	assert!(first_recv.wait().is_ok());
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, second_id));
+	assert_eq!(harness.service.pending.read().len(), 0);
+	assert!(harness.service.in_transit.read().contains_key(&second_id));
+
+	harness.service.on_responses(
+		&Context::WithPeer(peer_id),
+		second_id,
+		&[Response::Headers(basic_request::HeadersResponse { headers: vec![second.encoded()] })]
+	);
+	assert!(second_recv.wait().is_ok());
+}
+
+#[test]
+fn higher_priority_dispatched_first() {
+	let harness = Harness::with_max_in_flight(1);
+
+	let peer_id = 10101;
+	let req_id = ReqId(14426);
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let low = Header::default();
+	let mut high = Header::default();
+	high.set_number(1);
+
+	let _low_recv = harness.service.request_raw_with_priority(
+		&Context::NoOp,
+		vec![request::HeaderByHash(low.hash().into()).into()],
+		Priority::Low,
+	).unwrap();
+	let _high_recv = harness.service.request_raw_with_priority(
+		&Context::NoOp,
+		vec![request::HeaderByHash(high.hash().into()).into()],
+		Priority::High,
+	).unwrap();
+
+	assert_eq!(harness.service.pending.read().len(), 2);
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, req_id));
+
+	assert_eq!(harness.service.pending.read().len(), 1);
+	assert_eq!(harness.service.in_transit.read()[&req_id].priority, Priority::High);
+	assert_eq!(harness.service.pending.read().peek().unwrap().priority, Priority::Low);
+}
+
+#[test]
+fn equal_priority_keeps_submission_order() {
+	let harness = Harness::create();
+
+	let first = Header::default();
+	let mut second = Header::default();
+	second.set_number(1);
+
+	let _first_recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![request::HeaderByHash(first.hash().into()).into()]
+	).unwrap();
+	let _second_recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![request::HeaderByHash(second.hash().into()).into()]
+	).unwrap();
+
+	let mut pending = harness.service.pending.write();
+	assert_eq!(pending.pop().unwrap().sequence, 0);
+	assert_eq!(pending.pop().unwrap().sequence, 1);
+}
+
 #[test]
 fn no_capabilities() {
 	let harness = Harness::create();
@@ -454,6 +572,86 @@ fn back_references() {
 	assert!(recv.wait().is_ok());
 }
 
+#[test]
+fn header_descendant_back_references() {
+	let harness = Harness::create();
+
+	let peer_id = 10101;
+	let req_ids = (ReqId(14426), ReqId(555));
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let mut other = Header::default();
+	other.set_number(5);
+
+	let mut parent_hash = H256::zero();
+	let headers: Vec<_> = (1..4).map(|num| {
+		let mut hdr = Header::default();
+		hdr.set_number(num);
+		hdr.set_parent_hash(parent_hash);
+		parent_hash = hdr.hash();
+		hdr
+	}).collect();
+
+	let mut forged = headers[2].clone();
+	forged.set_parent_hash(H256::repeat_byte(1));
+
+	let descendant = request::HeaderDescendant::new(3, headers[0].hash().into(), 1, ::spec::new_test().engine).unwrap();
+	let recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![
+			request::HeaderByHash(other.hash().into()).into(),
+			descendant.into(),
+			request::BlockReceipts(HeaderRef::Unresolved(1, basic_request::Field::back_ref(1, 0))).into(),
+		]
+	).unwrap();
+
+	// the receipts request needs the descendant's hash, so it isn't sent in the same round.
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, req_ids.0));
+	assert_eq!(harness.service.in_transit.read()[&req_ids.0].net_requests.requests().len(), 2);
+
+	// a header which doesn't link to the ancestor is rejected.
+	harness.service.on_responses(
+		&Context::Punish(peer_id),
+		req_ids.0,
+		&[
+			Response::Headers(basic_request::HeadersResponse { headers: vec![other.encoded()] }),
+			Response::Headers(basic_request::HeadersResponse {
+				headers: vec![headers[0].encoded(), headers[1].encoded(), forged.encoded()],
+			}),
+		]
+	);
+
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, req_ids.1));
+	assert_eq!(harness.service.in_transit.read()[&req_ids.1].net_requests.requests().len(), 1);
+
+	// the receipts are known to be empty from the verified header.
+	harness.service.on_responses(
+		&Context::WithPeer(peer_id),
+		req_ids.1,
+		&[Response::Headers(basic_request::HeadersResponse {
+			headers: headers.iter().map(|hdr| hdr.encoded()).collect(),
+		})]
+	);
+
+	let responses = recv.wait().unwrap().unwrap();
+	assert_eq!(responses.len(), 3);
+	match responses[1] {
+		super::Response::HeaderDescendant(ref hdr) => assert_eq!(hdr.hash(), headers[2].hash()),
+		_ => panic!("expected the descendant header"),
+	}
+}
+
 #[test]
 #[should_panic]
 fn bad_back_reference() {
@@ -579,7 +777,7 @@ fn empty_responses_exceeds_limit_should_be_dropped() {
 			&[]
 		);
 		assert!(harness.service.pending.read().len() != 0);
-		let pending = harness.service.pending.write().remove(0);
+		let pending = harness.service.pending.write().pop().unwrap();
 		harness.service.in_transit.write().insert(req_id, pending);
 	}
 
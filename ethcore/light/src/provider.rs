@@ -21,6 +21,7 @@ use std::sync::Arc;
 
 use common_types::{
 	blockchain_info::BlockChainInfo,
+	client_types::ProveError,
 	encoded,
 	ids::BlockId,
 	transaction::PendingTransaction,
@@ -32,6 +33,7 @@ use client_traits::{
 	ProvingBlockChainClient,
 };
 use ethereum_types::H256;
+use network::{NodeId, PeerId};
 use parking_lot::RwLock;
 
 use cht::{self, BlockInfo};
@@ -41,7 +43,25 @@ use transaction_queue::TransactionQueue;
 use request;
 
 /// Maximum allowed size of a headers request.
-pub const MAX_HEADERS_PER_REQUEST: u64 = 512;
+pub const MAX_HEADERS_PER_REQUEST: u64 = request::header::MAX_HEADERS as u64;
+
+/// Default upper bound on the size of a headers response, in bytes.
+/// Configurable through `net::Config::max_headers_response_bytes`.
+pub const MAX_RESPONSE_BYTES: usize = 256 * 1024;
+
+/// Rough size of an RLP-encoded header, used to estimate response sizes.
+pub const AVERAGE_HEADER_SIZE: usize = 512;
+
+/// Default upper bound on the size of an execution proof, in bytes.
+/// Configurable through `net::Config::max_execution_witness_bytes`.
+pub const MAX_EXECUTION_WITNESS_BYTES: usize = 4 * 1024 * 1024;
+
+/// Number of headers to serve for a request of `requested` headers, so that
+/// the response stays within `max_response_bytes`.
+fn headers_limit(requested: u64, max_response_bytes: usize) -> u64 {
+	let by_size = (max_response_bytes / AVERAGE_HEADER_SIZE) as u64;
+	::std::cmp::min(::std::cmp::min(MAX_HEADERS_PER_REQUEST, requested), by_size)
+}
 
 /// Defines the operations that a provider for the light subprotocol must fulfill.
 pub trait Provider: Send + Sync {
@@ -62,10 +82,12 @@ pub trait Provider: Send + Sync {
 	///
 	/// The returned vector may have any length in the range [0, `max`], but the
 	/// results within must adhere to the `skip` and `reverse` parameters.
-	fn block_headers(&self, req: request::CompleteHeadersRequest) -> Option<request::HeadersResponse> {
+	/// It's truncated to the number of headers of average size fitting into `max_response_bytes`.
+	fn block_headers(&self, req: request::CompleteHeadersRequest, max_response_bytes: usize) -> Option<request::HeadersResponse> {
 		use request::HashOrNumber;
 
-		if req.max == 0 { return None }
+		let max = headers_limit(req.max, max_response_bytes);
+		if max == 0 { return None }
 
 		let best_num = self.chain_info().best_block_number;
 		let start_num = match req.start {
@@ -80,7 +102,7 @@ pub trait Provider: Send + Sync {
 					let canon_hash = self.block_header(BlockId::Number(num))
 						.map(|h| h.hash());
 
-					if req.max == 1 || canon_hash != Some(hash) {
+					if max == 1 || canon_hash != Some(hash) {
 						// Non-canonical header or single header requested.
 						return Some(::request::HeadersResponse {
 							headers: vec![header],
@@ -92,8 +114,6 @@ pub trait Provider: Send + Sync {
 			}
 		};
 
-		let max = ::std::cmp::min(MAX_HEADERS_PER_REQUEST, req.max);
-
 		let headers: Vec<_> = (0_u64..max)
 			.map(|x: u64| x.saturating_mul(req.skip.saturating_add(1)))
 			.take_while(|&x| if req.reverse { x < start_num } else { best_num.saturating_sub(start_num) >= x })
@@ -139,9 +159,13 @@ pub trait Provider: Send + Sync {
 	/// Provide pending transactions.
 	fn transactions_to_propagate(&self) -> Vec<PendingTransaction>;
 
+	/// Note that transactions with given hashes were relayed to a peer.
+	fn transactions_propagated(&self, _peer: PeerId, _node_id: Option<NodeId>, _hashes: &[H256]) { }
+
 	/// Provide a proof-of-execution for the given transaction proof request.
 	/// Returns a vector of all state items necessary to execute the transaction.
-	fn transaction_proof(&self, req: request::CompleteExecutionRequest) -> Option<request::ExecutionResponse>;
+	/// Fails with `ProveError::WitnessTooLarge` if they would exceed `max_witness_bytes`.
+	fn transaction_proof(&self, req: request::CompleteExecutionRequest, max_witness_bytes: usize) -> Result<request::ExecutionResponse, ProveError>;
 
 	/// Provide epoch signal data at given block hash. This should be just the
 	fn epoch_signal(&self, req: request::CompleteSignalRequest) -> Option<request::SignalResponse>;
@@ -271,13 +295,13 @@ impl<T: ProvingBlockChainClient + ?Sized> Provider for T {
 		}
 	}
 
-	fn transaction_proof(&self, req: request::CompleteExecutionRequest) -> Option<request::ExecutionResponse> {
+	fn transaction_proof(&self, req: request::CompleteExecutionRequest, max_witness_bytes: usize) -> Result<request::ExecutionResponse, ProveError> {
 		use common_types::transaction::Transaction;
 
 		let id = BlockId::Hash(req.block_hash);
 		let nonce = match self.nonce(&req.from, id) {
 			Some(nonce) => nonce,
-			None => return None,
+			None => return Err(ProveError::StateUnavailable),
 		};
 		let transaction = Transaction {
 			nonce,
@@ -288,8 +312,11 @@ impl<T: ProvingBlockChainClient + ?Sized> Provider for T {
 			data: req.data,
 		}.fake_sign(req.from);
 
-		self.prove_transaction(transaction, id)
-			.map(|(_, proof)| ::request::ExecutionResponse { items: proof })
+		self.prove_transaction_with_cap(transaction, id, max_witness_bytes)
+			.map(|proof| {
+				trace!(target: "pip_provider", "Proved execution with a witness of {} bytes", proof.witness_size);
+				::request::ExecutionResponse { items: proof.items }
+			})
 	}
 
 	fn transactions_to_propagate(&self) -> Vec<PendingTransaction> {
@@ -371,8 +398,8 @@ impl<L: AsLightClient + Send + Sync> Provider for LightProvider<L> {
 		None
 	}
 
-	fn transaction_proof(&self, _req: request::CompleteExecutionRequest) -> Option<request::ExecutionResponse> {
-		None
+	fn transaction_proof(&self, _req: request::CompleteExecutionRequest, _max_witness_bytes: usize) -> Result<request::ExecutionResponse, ProveError> {
+		Err(ProveError::StateUnavailable)
 	}
 
 	fn epoch_signal(&self, _req: request::CompleteSignalRequest) -> Option<request::SignalResponse> {
@@ -382,7 +409,12 @@ impl<L: AsLightClient + Send + Sync> Provider for LightProvider<L> {
 	fn transactions_to_propagate(&self) -> Vec<PendingTransaction> {
 		let chain_info = self.chain_info();
 		self.txqueue.read()
-			.ready_transactions(chain_info.best_block_number, chain_info.best_block_timestamp)
+			.transactions_to_propagate(chain_info.best_block_number, chain_info.best_block_timestamp)
+	}
+
+	fn transactions_propagated(&self, peer: PeerId, node_id: Option<NodeId>, hashes: &[H256]) {
+		let best_block_number = self.chain_info().best_block_number;
+		self.txqueue.write().note_propagated(hashes, peer, node_id, best_block_number);
 	}
 }
 
@@ -397,7 +429,31 @@ impl<L: AsLightClient> AsLightClient for LightProvider<L> {
 #[cfg(test)]
 mod tests {
 	use ethcore::test_helpers::{EachBlockWith, TestBlockChainClient};
-	use super::Provider;
+	use super::{headers_limit, Provider, AVERAGE_HEADER_SIZE, MAX_HEADERS_PER_REQUEST, MAX_RESPONSE_BYTES};
+
+	#[test]
+	fn headers_response_is_bounded() {
+		assert_eq!(headers_limit(10, MAX_RESPONSE_BYTES), 10);
+		assert_eq!(headers_limit(10_000, MAX_RESPONSE_BYTES), MAX_HEADERS_PER_REQUEST);
+		assert_eq!(headers_limit(10_000, 20 * AVERAGE_HEADER_SIZE), 20);
+		assert_eq!(headers_limit(10_000, AVERAGE_HEADER_SIZE - 1), 0);
+
+		let client = TestBlockChainClient::new();
+		client.add_blocks(1000, EachBlockWith::Nothing);
+
+		let req = ::request::CompleteHeadersRequest {
+			start: ::request::HashOrNumber::Number(1),
+			skip: 0,
+			max: 10_000,
+			reverse: false,
+		};
+
+		let response = client.block_headers(req.clone(), MAX_RESPONSE_BYTES).unwrap();
+		assert_eq!(response.headers.len() as u64, MAX_HEADERS_PER_REQUEST);
+
+		let response = client.block_headers(req, 20 * AVERAGE_HEADER_SIZE).unwrap();
+		assert_eq!(response.headers.len(), 20);
+	}
 
 	#[test]
 	fn cht_proof() {
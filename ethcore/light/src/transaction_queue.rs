@@ -22,10 +22,13 @@
 //! Under the assumption that light nodes will have a relatively limited set of
 //! accounts for which they create transactions, this queue is structured in an
 //! address-wise manner.
+//!
+//! The queue also tracks propagation of local transactions: peers they were relayed
+//! to and the first imported block which includes them.
 
 use std::fmt;
 use std::sync::Arc;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::collections::hash_map::Entry;
 
 use common_types::transaction::{self, Condition, PendingTransaction, SignedTransaction};
@@ -33,6 +36,10 @@ use ethereum_types::{H256, U256, Address};
 use fastmap::H256FastMap;
 use futures::sync::mpsc;
 use miner::pool::TxStatus;
+use network::{NodeId, PeerId};
+
+/// Maximum number of propagation statuses kept for confirmed transactions which left the queue.
+const MAX_CONFIRMED_STATUSES: usize = 1024;
 
 // Knowledge of an account's current nonce.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -129,11 +136,36 @@ pub enum ImportDestination {
 	Future,
 }
 
+/// Propagation of a local transaction to full peers.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PropagationStatus {
+	/// Best block number at the time the transaction was first relayed.
+	pub first_propagated: u64,
+	/// Peers the transaction was relayed to, with their node ids if known.
+	pub peers: BTreeMap<PeerId, Option<NodeId>>,
+	/// Block reported by a full peer to include the transaction, until its header is imported.
+	pub reported_in: Option<(u64, H256)>,
+	/// Number and hash of the first imported block which includes the transaction.
+	pub first_seen_in: Option<(u64, H256)>,
+}
+
+impl PropagationStatus {
+	/// Whether the transaction was seen in an imported block.
+	pub fn is_confirmed(&self) -> bool {
+		self.first_seen_in.is_some()
+	}
+}
+
 /// Light transaction queue. See module docs for more details.
 #[derive(Default)]
 pub struct TransactionQueue {
 	by_account: HashMap<Address, AccountTransactions>,
 	by_hash: H256FastMap<PendingTransaction>,
+	propagation: H256FastMap<PropagationStatus>,
+	// confirmed transactions which left the queue, oldest first.
+	confirmed_removed: VecDeque<H256>,
+	// confirmed transactions which left the queue, kept along with their statuses.
+	removed_mined: H256FastMap<SignedTransaction>,
 	pending_listeners: Vec<mpsc::UnboundedSender<Arc<Vec<H256>>>>,
 	full_listeners: Vec<mpsc::UnboundedSender<Arc<Vec<(H256, TxStatus)>>>>,
 }
@@ -143,6 +175,7 @@ impl fmt::Debug for TransactionQueue {
 		fmt.debug_struct("TransactionQueue")
 			.field("by_account", &self.by_account)
 			.field("by_hash", &self.by_hash)
+			.field("propagation", &self.propagation)
 			.field("pending_listeners", &self.pending_listeners.len())
 			.field("full_listeners", &self.pending_listeners.len())
 			.finish()
@@ -187,7 +220,7 @@ impl TransactionQueue {
 							sender, nonce);
 
 						let old = ::std::mem::replace(&mut acct_txs.current[idx], tx_info);
-						self.by_hash.remove(&old.hash);
+						self.remove(&old.hash);
 
 						(ImportDestination::Current, vec![hash])
 					}
@@ -349,7 +382,155 @@ impl TransactionQueue {
 		self.notify(&removed_hashes, TxStatus::Culled);
 
 		for hash in removed_hashes {
-			self.by_hash.remove(&hash);
+			self.remove(&hash);
+		}
+	}
+
+	/// Get all transactions which should be relayed to full peers: ready transactions
+	/// which weren't seen in an imported block yet.
+	pub fn transactions_to_propagate(&self, best_block_number: u64, best_block_timestamp: u64) -> Vec<PendingTransaction> {
+		let mut ready = self.ready_transactions(best_block_number, best_block_timestamp);
+		ready.retain(|tx| !self.propagation.get(&tx.hash()).map_or(false, PropagationStatus::is_confirmed));
+		ready
+	}
+
+	/// Note that queued transactions were relayed to a peer, `best_block_number` is the
+	/// current best block.
+	pub fn note_propagated(&mut self, hashes: &[H256], peer: PeerId, node_id: Option<NodeId>, best_block_number: u64) {
+		for hash in hashes {
+			if !self.by_hash.contains_key(hash) { continue }
+
+			self.propagation.entry(*hash)
+				.or_insert_with(|| PropagationStatus {
+					first_propagated: best_block_number,
+					..Default::default()
+				})
+				.peers.insert(peer, node_id);
+		}
+	}
+
+	/// Note that a full peer reported the transaction to be included in the given block.
+	///
+	/// The transaction is considered confirmed once `confirm_included` finds the block imported.
+	pub fn note_included(&mut self, hash: &H256, block_number: u64, block_hash: H256) {
+		if let Some(status) = self.propagation.get_mut(hash) {
+			if !status.is_confirmed() {
+				status.reported_in = Some((block_number, block_hash));
+			}
+		}
+	}
+
+	/// Confirm reported inclusions of transactions against imported headers.
+	/// `canonical_hash` returns the hash of the imported canonical block with given number.
+	///
+	/// Returns hashes of the newly confirmed transactions.
+	pub fn confirm_included<F>(&mut self, canonical_hash: F) -> Vec<H256> where F: Fn(u64) -> Option<H256> {
+		let mut confirmed = Vec::new();
+		for (hash, status) in self.propagation.iter_mut() {
+			let (number, block_hash) = match status.reported_in {
+				Some(reported) => reported,
+				None => continue,
+			};
+
+			match canonical_hash(number) {
+				Some(canonical) if canonical == block_hash => {
+					status.first_seen_in = status.reported_in.take();
+					confirmed.push(*hash);
+				}
+				// reported block isn't canonical, inclusion has to be checked again.
+				Some(_) => status.reported_in = None,
+				// header isn't imported yet.
+				None => {}
+			}
+		}
+
+		confirmed
+	}
+
+	/// Revert confirmations of transactions whose block is no longer canonical.
+	/// `canonical_hash` returns the hash of the imported canonical block with given number.
+	///
+	/// Queued transactions are relayed and checked for inclusion again, statuses of those
+	/// which already left the queue are dropped. Returns hashes of the reverted transactions.
+	pub fn revert_retracted<F>(&mut self, canonical_hash: F) -> Vec<H256> where F: Fn(u64) -> Option<H256> {
+		let mut reverted = Vec::new();
+		for (hash, status) in self.propagation.iter_mut() {
+			let (number, block_hash) = match status.first_seen_in {
+				Some(seen_in) => seen_in,
+				None => continue,
+			};
+
+			if canonical_hash(number).map_or(false, |canonical| canonical != block_hash) {
+				status.first_seen_in = None;
+				reverted.push(*hash);
+			}
+		}
+
+		for hash in &reverted {
+			if !self.by_hash.contains_key(hash) {
+				self.propagation.remove(hash);
+				self.removed_mined.remove(hash);
+			}
+		}
+		let propagation = &self.propagation;
+		self.confirmed_removed.retain(|hash| propagation.contains_key(hash));
+
+		reverted
+	}
+
+	/// Transactions seen in an imported block, with the number and hash of that block.
+	/// Confirmed transactions are kept for a while after they leave the queue.
+	pub fn mined_transactions(&self) -> Vec<(SignedTransaction, u64, H256)> {
+		self.propagation.iter()
+			.filter_map(|(hash, status)| {
+				let (number, block_hash) = status.first_seen_in?;
+				let tx = self.by_hash.get(hash).map(|tx| &tx.transaction)
+					.or_else(|| self.removed_mined.get(hash))?;
+				Some((tx.clone(), number, block_hash))
+			})
+			.collect()
+	}
+
+	/// Propagated transactions which are still waiting for a report of their inclusion.
+	pub fn awaiting_confirmation(&self) -> Vec<H256> {
+		self.propagation.iter()
+			.filter(|(hash, status)| !status.is_confirmed() && status.reported_in.is_none() && self.by_hash.contains_key(*hash))
+			.map(|(hash, _)| *hash)
+			.collect()
+	}
+
+	/// Get propagation status of a local transaction.
+	/// Statuses of confirmed transactions are kept for a while after they leave the queue.
+	pub fn propagation_status(&self, hash: &H256) -> Option<&PropagationStatus> {
+		self.propagation.get(hash)
+	}
+
+	/// Get propagation statuses of all tracked transactions.
+	pub fn propagation_statuses(&self) -> Vec<(H256, PropagationStatus)> {
+		self.propagation.iter()
+			.map(|(hash, status)| (*hash, status.clone()))
+			.collect()
+	}
+
+	// remove a transaction which left the queue. Its propagation status is dropped,
+	// unless it's confirmed: then the status and the transaction are kept for a while.
+	fn remove(&mut self, hash: &H256) {
+		let removed = self.by_hash.remove(hash);
+		match self.propagation.get(hash).map(PropagationStatus::is_confirmed) {
+			Some(true) => {
+				if let Some(tx) = removed {
+					self.removed_mined.insert(*hash, tx.transaction);
+				}
+				self.confirmed_removed.push_back(*hash);
+				if self.confirmed_removed.len() > MAX_CONFIRMED_STATUSES {
+					if let Some(oldest) = self.confirmed_removed.pop_front() {
+						self.propagation.remove(&oldest);
+						self.removed_mined.remove(&oldest);
+					}
+				}
+			}
+			Some(false) => { self.propagation.remove(hash); }
+			None => {}
 		}
 	}
 
@@ -399,7 +580,7 @@ impl TransactionQueue {
 #[cfg(test)]
 mod tests {
 	use super::TransactionQueue;
-	use ethereum_types::Address;
+	use ethereum_types::{Address, H256};
 	use common_types::transaction::{Transaction, PendingTransaction, Condition};
 
 	#[test]
@@ -588,4 +769,102 @@ mod tests {
 		assert_eq!(txq.future_transactions(0, 0).len(), 7);
 		assert_eq!(txq.next_nonce(&sender).unwrap(), 1.into());
 	}
+
+	#[test]
+	fn propagation_is_tracked_until_confirmed() {
+		let sender = Address::zero();
+		let mut txq = TransactionQueue::default();
+		let tx: PendingTransaction = Transaction::default().fake_sign(sender).into();
+		let hash = tx.hash();
+		txq.import(tx).unwrap();
+
+		assert!(txq.propagation_status(&hash).is_none());
+		txq.note_propagated(&[hash], 1, None, 5);
+		txq.note_propagated(&[hash], 2, None, 6);
+		{
+			let status = txq.propagation_status(&hash).unwrap();
+			assert_eq!(status.first_propagated, 5);
+			assert_eq!(status.peers.keys().cloned().collect::<Vec<_>>(), vec![1, 2]);
+		}
+		assert_eq!(txq.awaiting_confirmation(), vec![hash]);
+
+		// reported block header isn't imported yet.
+		let block_hash = H256::repeat_byte(7);
+		txq.note_included(&hash, 10, block_hash);
+		assert!(txq.confirm_included(|_| None).is_empty());
+		assert!(txq.awaiting_confirmation().is_empty());
+		assert_eq!(txq.transactions_to_propagate(0, 0).len(), 1);
+
+		assert_eq!(txq.confirm_included(|n| if n == 10 { Some(block_hash) } else { None }), vec![hash]);
+		assert_eq!(txq.propagation_status(&hash).unwrap().first_seen_in, Some((10, block_hash)));
+		assert!(txq.transactions_to_propagate(0, 0).is_empty());
+
+		// status of a confirmed transaction outlives culling.
+		txq.cull(sender, 1.into());
+		assert!(txq.propagation_status(&hash).unwrap().is_confirmed());
+		let mined = txq.mined_transactions();
+		assert_eq!(mined.len(), 1);
+		assert_eq!((mined[0].0.hash(), mined[0].1, mined[0].2), (hash, 10, block_hash));
+	}
+
+	#[test]
+	fn retracted_inclusion_is_reverted() {
+		let sender = Address::zero();
+		let mut txq = TransactionQueue::default();
+		let (mut tx_a, mut tx_b) = (Transaction::default(), Transaction::default());
+		tx_a.nonce = 0.into();
+		tx_b.nonce = 1.into();
+		let tx_a: PendingTransaction = tx_a.fake_sign(sender).into();
+		let tx_b: PendingTransaction = tx_b.fake_sign(sender).into();
+		let (hash_a, hash_b) = (tx_a.hash(), tx_b.hash());
+		txq.import(tx_a).unwrap();
+		txq.import(tx_b).unwrap();
+
+		let block_hash = H256::repeat_byte(7);
+		txq.note_propagated(&[hash_a, hash_b], 1, None, 5);
+		txq.note_included(&hash_a, 10, block_hash);
+		txq.note_included(&hash_b, 10, block_hash);
+		assert_eq!(txq.confirm_included(|_| Some(block_hash)).len(), 2);
+		txq.cull(sender, 1.into());
+
+		// headers which aren't imported don't revert anything.
+		assert!(txq.revert_retracted(|_| None).is_empty());
+		assert!(txq.revert_retracted(|_| Some(block_hash)).is_empty());
+
+		let mut reverted = txq.revert_retracted(|_| Some(H256::repeat_byte(8)));
+		reverted.sort();
+		let mut expected = vec![hash_a, hash_b];
+		expected.sort();
+		assert_eq!(reverted, expected);
+
+		// the queued transaction is relayed again, the culled one is forgotten.
+		assert!(txq.mined_transactions().is_empty());
+		assert_eq!(txq.propagation_status(&hash_a), None);
+		assert!(!txq.propagation_status(&hash_b).unwrap().is_confirmed());
+		assert_eq!(txq.transactions_to_propagate(0, 0).len(), 1);
+		assert_eq!(txq.awaiting_confirmation(), vec![hash_b]);
+	}
+
+	#[test]
+	fn non_canonical_inclusion_is_checked_again() {
+		let sender = Address::zero();
+		let mut txq = TransactionQueue::default();
+		let tx: PendingTransaction = Transaction::default().fake_sign(sender).into();
+		let hash = tx.hash();
+		txq.import(tx).unwrap();
+
+		// transactions which weren't relayed aren't tracked.
+		txq.note_included(&hash, 10, H256::repeat_byte(7));
+		assert!(txq.propagation_status(&hash).is_none());
+
+		txq.note_propagated(&[hash], 1, None, 5);
+		txq.note_included(&hash, 10, H256::repeat_byte(7));
+		assert!(txq.confirm_included(|_| Some(H256::repeat_byte(8))).is_empty());
+		assert_eq!(txq.propagation_status(&hash).unwrap().reported_in, None);
+		assert_eq!(txq.awaiting_confirmation(), vec![hash]);
+
+		// unconfirmed statuses are dropped with the transaction.
+		txq.cull(sender, 1.into());
+		assert!(txq.propagation_status(&hash).is_none());
+	}
 }
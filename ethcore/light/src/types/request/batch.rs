@@ -103,6 +103,19 @@ impl<T> Batch<T> {
 			answered: self.answered,
 		}
 	}
+
+	/// Record outputs of the last answered request which aren't part of its response,
+	/// but only known once the response is checked. They are filled into unanswered
+	/// requests by `fill_unanswered`.
+	pub fn supply_checked_outputs<R: ResponseLike>(&mut self, response: &R) {
+		if self.answered == 0 { return }
+
+		let outputs = &mut self.outputs;
+		let idx = self.answered - 1;
+		response.fill_outputs(|out_idx, output| {
+			outputs.insert((idx, out_idx), output);
+		});
+	}
 }
 
 impl<T: IncompleteRequest + Clone> Batch<T> {
@@ -191,6 +204,49 @@ impl Batch<super::Request> {
 
 		responses
 	}
+
+	/// Reorder the requests into a canonical order, rewriting back-references to match.
+	///
+	/// Every request still comes after the requests it references. Among the requests whose
+	/// references are already placed, the one with the lowest kind and then the lowest
+	/// encoding (with back-references already rewritten) goes next. Batches which differ
+	/// only in the order of their requests therefore encode identically afterwards.
+	///
+	/// Has no effect once any request has been answered.
+	pub fn canonicalize(&mut self) {
+		if self.answered != 0 { return }
+
+		let len = self.requests.len();
+		let deps: Vec<Vec<usize>> = self.requests.iter().map(|req| {
+			let mut deps = Vec::new();
+			let _ = req.check_outputs(|req_idx, _, _| { deps.push(req_idx); Ok(()) });
+			deps
+		}).collect();
+
+		let mut new_idx: Vec<Option<usize>> = vec![None; len];
+		let mut order = Vec::with_capacity(len);
+		while order.len() < len {
+			let (_, _, next) = (0..len)
+				.filter(|&i| new_idx[i].is_none() && deps[i].iter().all(|&dep| new_idx[dep].is_some()))
+				.map(|i| {
+					let mut req = self.requests[i].clone();
+					req.adjust_refs(|dep| new_idx[dep].expect("only requests with placed dependencies are considered; qed"));
+					(req.kind() as u8, ::rlp::encode(&req), i)
+				})
+				.min()
+				.expect("back-references only point to earlier requests, so one is always ready; qed");
+
+			new_idx[next] = Some(order.len());
+			order.push(next);
+		}
+
+		let mut requests: Vec<_> = self.requests.drain(..).map(Some).collect();
+		self.requests = order.into_iter().map(|i| {
+			let mut req = requests[i].take().expect("each request is placed exactly once; qed");
+			req.adjust_refs(|dep| new_idx[dep].expect("all requests are placed; qed"));
+			req
+		}).collect();
+	}
 }
 
 impl<T: IncompleteRequest> Deref for Batch<T> {
@@ -210,7 +266,7 @@ impl<T: IncompleteRequest> DerefMut for Batch<T> {
 #[cfg(test)]
 mod tests {
 	use request::*;
-	use super::Builder;
+	use super::{Batch, Builder};
 	use ethereum_types::H256;
 
 	#[test]
@@ -314,4 +370,51 @@ mod tests {
 		batch.answered += 1;
 		assert!(batch.next_complete().is_some());
 	}
+
+	#[test]
+	fn canonicalize_equivalent_batches() {
+		fn batch(requests: Vec<Request>) -> Batch<Request> {
+			let mut builder = Builder::default();
+			for request in requests {
+				builder.push(request).unwrap();
+			}
+			builder.build()
+		}
+
+		let body_hash = H256::from_low_u64_be(42);
+		let mut first = batch(vec![
+			Request::HeaderProof(IncompleteHeaderProofRequest { num: 100.into() }),
+			Request::Receipts(IncompleteReceiptsRequest { hash: Field::BackReference(0, 0) }),
+			Request::HeaderProof(IncompleteHeaderProofRequest { num: 50.into() }),
+			Request::Body(IncompleteBodyRequest { hash: body_hash.into() }),
+			Request::TransactionIndex(IncompleteTransactionIndexRequest { hash: Field::BackReference(2, 0) }),
+		]);
+		let mut second = batch(vec![
+			Request::Body(IncompleteBodyRequest { hash: body_hash.into() }),
+			Request::HeaderProof(IncompleteHeaderProofRequest { num: 50.into() }),
+			Request::TransactionIndex(IncompleteTransactionIndexRequest { hash: Field::BackReference(1, 0) }),
+			Request::HeaderProof(IncompleteHeaderProofRequest { num: 100.into() }),
+			Request::Receipts(IncompleteReceiptsRequest { hash: Field::BackReference(3, 0) }),
+		]);
+		assert_ne!(::rlp::encode_list(first.requests()), ::rlp::encode_list(second.requests()));
+
+		first.canonicalize();
+		second.canonicalize();
+		assert_eq!(::rlp::encode_list(first.requests()), ::rlp::encode_list(second.requests()));
+
+		// back-references still point to the right, earlier requests.
+		let canonical = first.requests().to_vec();
+		let rebuilt = batch(canonical.clone());
+		assert_eq!(rebuilt.requests(), &canonical[..]);
+		let proof_of = |num: u64| canonical.iter().position(|req| match *req {
+			Request::HeaderProof(ref req) => req.num == num.into(),
+			_ => false,
+		}).unwrap();
+		assert!(canonical.contains(&Request::Receipts(IncompleteReceiptsRequest {
+			hash: Field::BackReference(proof_of(100), 0),
+		})));
+		assert!(canonical.contains(&Request::TransactionIndex(IncompleteTransactionIndexRequest {
+			hash: Field::BackReference(proof_of(50), 0),
+		})));
+	}
 }
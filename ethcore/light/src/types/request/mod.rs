@@ -673,6 +673,9 @@ pub mod header {
 	use common_types::encoded;
 	use rlp::{Encodable, Decodable, DecoderError, RlpStream, Rlp};
 
+	/// Maximum number of headers accepted in a single response.
+	pub const MAX_HEADERS: usize = 512;
+
 	/// Potentially incomplete headers request.
 	#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
 	pub struct Incomplete {
@@ -755,7 +758,12 @@ pub mod header {
 		fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
 			use common_types::header::Header as FullHeader;
 
-			let mut headers = Vec::new();
+			let count = rlp.item_count()?;
+			if count > MAX_HEADERS {
+				return Err(DecoderError::Custom("Too many headers in PIP response."));
+			}
+
+			let mut headers = Vec::with_capacity(count);
 
 			for item in rlp.iter() {
 				// check that it's a valid encoding.
@@ -1685,6 +1693,24 @@ mod tests {
 		check_roundtrip(full_res);
 	}
 
+	#[test]
+	fn headers_response_too_many_headers() {
+		let header = ::rlp::encode(&Header::default());
+		let encode = |count: usize| {
+			let mut stream = RlpStream::new_list(count);
+			for _ in 0..count {
+				stream.append_raw(&header, 1);
+			}
+			stream.out()
+		};
+
+		let res: HeadersResponse = ::rlp::decode(&encode(header::MAX_HEADERS)).unwrap();
+		assert_eq!(res.headers.len(), header::MAX_HEADERS);
+
+		let res = ::rlp::decode::<HeadersResponse>(&encode(header::MAX_HEADERS + 1));
+		assert_eq!(res, Err(DecoderError::Custom("Too many headers in PIP response.")));
+	}
+
 	#[test]
 	fn header_proof_roundtrip() {
 		let req = IncompleteHeaderProofRequest {
@@ -1925,4 +1951,183 @@ mod tests {
 		check_roundtrip(res);
 		check_roundtrip(full_res);
 	}
+
+	/// Generators for random requests and responses of every kind.
+	mod arbitrary {
+		use super::*;
+		use common_types::encoded;
+		use common_types::log_entry::LogEntry;
+		use common_types::receipt::{Receipt, TransactionOutcome};
+		use common_types::transaction::{Action, Transaction, UnverifiedTransaction};
+		use ethereum_types::{Address, U256};
+		use proptest::collection::vec;
+		use proptest::prelude::*;
+
+		pub fn h256() -> impl Strategy<Value = H256> {
+			any::<[u8; 32]>().prop_map(H256)
+		}
+
+		pub fn u256() -> impl Strategy<Value = U256> {
+			any::<[u64; 4]>().prop_map(U256)
+		}
+
+		pub fn address() -> impl Strategy<Value = Address> {
+			any::<[u8; 20]>().prop_map(Address::from)
+		}
+
+		pub fn bytes() -> impl Strategy<Value = Vec<u8>> {
+			vec(any::<u8>(), 0..80)
+		}
+
+		pub fn proof() -> impl Strategy<Value = Vec<Vec<u8>>> {
+			vec(bytes(), 0..4)
+		}
+
+		pub fn hash_or_number() -> impl Strategy<Value = HashOrNumber> {
+			prop_oneof![
+				h256().prop_map(HashOrNumber::Hash),
+				any::<u64>().prop_map(HashOrNumber::Number),
+			]
+		}
+
+		pub fn field<T, S>(scalar: S) -> impl Strategy<Value = Field<T>>
+			where T: Clone + ::std::fmt::Debug, S: Strategy<Value = T>
+		{
+			prop_oneof![
+				scalar.prop_map(Field::Scalar),
+				(any::<usize>(), any::<usize>()).prop_map(|(req, idx)| Field::BackReference(req, idx)),
+			]
+		}
+
+		fn action() -> impl Strategy<Value = Action> {
+			prop_oneof![Just(Action::Create), address().prop_map(Action::Call)]
+		}
+
+		fn header() -> impl Strategy<Value = Header> {
+			(h256(), any::<u64>(), u256(), u256(), u256(), any::<u64>(), address(), bytes())
+				.prop_map(|(parent, number, difficulty, gas_limit, gas_used, timestamp, author, extra_data)| {
+					let mut header = Header::new();
+					header.set_parent_hash(parent);
+					header.set_number(number);
+					header.set_difficulty(difficulty);
+					header.set_gas_limit(gas_limit);
+					header.set_gas_used(gas_used);
+					header.set_timestamp(timestamp);
+					header.set_author(author);
+					header.set_extra_data(extra_data);
+					header
+				})
+		}
+
+		fn transaction() -> impl Strategy<Value = UnverifiedTransaction> {
+			(u256(), u256(), u256(), action(), u256(), bytes(), address())
+				.prop_map(|(nonce, gas_price, gas, action, value, data, sender)| {
+					UnverifiedTransaction::from(Transaction { nonce, gas_price, gas, action, value, data }.fake_sign(sender))
+				})
+		}
+
+		fn receipt() -> impl Strategy<Value = Receipt> {
+			let outcome = prop_oneof![
+				Just(TransactionOutcome::Unknown),
+				h256().prop_map(TransactionOutcome::StateRoot),
+				any::<u8>().prop_map(TransactionOutcome::StatusCode),
+			];
+			let log = (address(), vec(h256(), 0..4), bytes())
+				.prop_map(|(address, topics, data)| LogEntry { address, topics, data });
+			(outcome, u256(), vec(log, 0..3))
+				.prop_map(|(outcome, gas_used, logs)| Receipt::new(outcome, gas_used, logs))
+		}
+
+		fn body() -> impl Strategy<Value = encoded::Body> {
+			(vec(transaction(), 0..3), vec(header(), 0..2)).prop_map(|(transactions, uncles)| {
+				let mut stream = RlpStream::new_list(2);
+				stream.append_list(&transactions).append_list(&uncles);
+				encoded::Body::new(stream.out())
+			})
+		}
+
+		pub fn request() -> impl Strategy<Value = Request> {
+			prop_oneof![
+				(field(hash_or_number()), any::<u64>(), any::<u64>(), any::<bool>())
+					.prop_map(|(start, skip, max, reverse)| Request::Headers(IncompleteHeadersRequest { start, skip, max, reverse })),
+				field(any::<u64>())
+					.prop_map(|num| Request::HeaderProof(IncompleteHeaderProofRequest { num })),
+				field(h256())
+					.prop_map(|hash| Request::TransactionIndex(IncompleteTransactionIndexRequest { hash })),
+				field(h256())
+					.prop_map(|hash| Request::Receipts(IncompleteReceiptsRequest { hash })),
+				field(h256())
+					.prop_map(|hash| Request::Body(IncompleteBodyRequest { hash })),
+				(field(h256()), field(h256()))
+					.prop_map(|(block_hash, address_hash)| Request::Account(IncompleteAccountRequest { block_hash, address_hash })),
+				(field(h256()), field(h256()), field(h256()))
+					.prop_map(|(block_hash, address_hash, key_hash)| Request::Storage(IncompleteStorageRequest { block_hash, address_hash, key_hash })),
+				(field(h256()), field(h256()))
+					.prop_map(|(block_hash, code_hash)| Request::Code(IncompleteCodeRequest { block_hash, code_hash })),
+				(field(h256()), address(), action(), u256(), u256(), u256(), bytes())
+					.prop_map(|(block_hash, from, action, gas, gas_price, value, data)| Request::Execution(IncompleteExecutionRequest {
+						block_hash, from, action, gas, gas_price, value, data,
+					})),
+				field(h256())
+					.prop_map(|block_hash| Request::Signal(IncompleteSignalRequest { block_hash })),
+			]
+		}
+
+		pub fn response() -> impl Strategy<Value = Response> {
+			prop_oneof![
+				vec(header(), 0..3)
+					.prop_map(|headers| Response::Headers(HeadersResponse {
+						headers: headers.iter().map(|h| encoded::Header::new(::rlp::encode(h))).collect(),
+					})),
+				(proof(), h256(), u256())
+					.prop_map(|(proof, hash, td)| Response::HeaderProof(HeaderProofResponse { proof, hash, td })),
+				(any::<u64>(), h256(), any::<u64>())
+					.prop_map(|(num, hash, index)| Response::TransactionIndex(TransactionIndexResponse { num, hash, index })),
+				vec(receipt(), 0..3)
+					.prop_map(|receipts| Response::Receipts(ReceiptsResponse { receipts })),
+				body()
+					.prop_map(|body| Response::Body(BodyResponse { body })),
+				(proof(), u256(), u256(), h256(), h256())
+					.prop_map(|(proof, nonce, balance, code_hash, storage_root)| Response::Account(AccountResponse {
+						proof, nonce, balance, code_hash, storage_root,
+					})),
+				(proof(), h256())
+					.prop_map(|(proof, value)| Response::Storage(StorageResponse { proof, value })),
+				bytes()
+					.prop_map(|code| Response::Code(CodeResponse { code })),
+				vec(bytes(), 0..4)
+					.prop_map(|items| Response::Execution(ExecutionResponse { items })),
+				bytes()
+					.prop_map(|signal| Response::Signal(SignalResponse { signal })),
+			]
+		}
+	}
+
+	proptest! {
+		#[test]
+		fn random_hash_or_number_roundtrip(val in arbitrary::hash_or_number()) {
+			check_roundtrip(val);
+		}
+
+		#[test]
+		fn random_field_roundtrip(field in arbitrary::field(arbitrary::h256())) {
+			check_roundtrip(field);
+		}
+
+		#[test]
+		fn random_request_roundtrip(req in arbitrary::request()) {
+			check_roundtrip(req);
+		}
+
+		#[test]
+		fn random_response_roundtrip(res in arbitrary::response()) {
+			check_roundtrip(res);
+		}
+
+		#[test]
+		fn random_request_batch_roundtrip(reqs in ::proptest::collection::vec(arbitrary::request(), 0..8)) {
+			let raw = ::rlp::encode_list(&reqs);
+			prop_assert_eq!(::rlp::decode_list::<Request>(&raw), reqs);
+		}
+	}
 }
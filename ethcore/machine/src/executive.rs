@@ -299,6 +299,13 @@ impl<'a> CallCreateExecutive<'a> {
 		}
 	}
 
+	/// Seed this executive's unconfirmed substate with the accounts already accessed by its parent (EIP-2929).
+	fn inherit_accessed_addresses(&mut self, parent: &Substate) {
+		if let Some(unsub) = self.unconfirmed_substate() {
+			unsub.accessed_addresses.extend(parent.accessed_addresses.iter().cloned());
+		}
+	}
+
 	fn check_static_flag(params: &ActionParams, static_flag: bool, is_create: bool) -> vm::Result<()> {
 		if is_create {
 			if static_flag {
@@ -324,6 +331,16 @@ impl<'a> CallCreateExecutive<'a> {
 		Ok(())
 	}
 
+	fn check_init_code_size(params: &ActionParams, schedule: &Schedule) -> vm::Result<()> {
+		if let (Some(limit), Some(code)) = (schedule.max_init_code_size, params.code.as_ref()) {
+			if code.len() > limit {
+				return Err(vm::Error::InitCodeTooLarge { limit, got: code.len() });
+			}
+		}
+
+		Ok(())
+	}
+
 	fn transfer_exec_balance<B: 'a + StateBackend>(params: &ActionParams, schedule: &Schedule, state: &mut State<B>, substate: &mut Substate) -> vm::Result<()> {
 		if let ActionValue::Transfer(val) = params.value {
 			state.transfer_balance(&params.sender, &params.address, &val, cleanup_mode(substate, &schedule))?;
@@ -360,6 +377,7 @@ impl<'a> CallCreateExecutive<'a> {
 				| Err(vm::Error::SubStackUnderflow {..})
 				| Err(vm::Error::OutOfSubStack {..})
 				| Err(vm::Error::InvalidSubEntry)
+				| Err(vm::Error::InitCodeTooLarge {..})
 				| Ok(FinalizationResult { apply_state: false, .. }) => {
 					if let Some(addr) = UNPRUNABLE_PRECOMPILE_ADDRESS {
 						if un_substate.touched.contains(&addr) {
@@ -528,6 +546,7 @@ impl<'a> CallCreateExecutive<'a> {
 					let schedule = self.schedule;
 
 					let mut pre_inner = || {
+						Self::check_init_code_size(&params, schedule)?;
 						Self::check_eip684(&params, state)?;
 						Self::check_static_flag(&params, static_flag, is_create)?;
 						state.checkpoint();
@@ -653,7 +672,8 @@ impl<'a> CallCreateExecutive<'a> {
 	}
 
 	/// Execute and consume the current executive. This function handles resume traps and sub-level tracing. The caller is expected to handle current-level tracing.
-	pub fn consume<B: 'a + StateBackend, T: Tracer, V: VMTracer>(self, state: &mut State<B>, top_substate: &mut Substate, tracer: &mut T, vm_tracer: &mut V) -> vm::Result<FinalizationResult> {
+	pub fn consume<B: 'a + StateBackend, T: Tracer, V: VMTracer>(mut self, state: &mut State<B>, top_substate: &mut Substate, tracer: &mut T, vm_tracer: &mut V) -> vm::Result<FinalizationResult> {
+		self.inherit_accessed_addresses(top_substate);
 		let mut last_res = Some((false, self.gas, self.exec(state, top_substate, tracer, vm_tracer)));
 
 		let mut callstack: Vec<(Option<Address>, CallCreateExecutive<'a>)> = Vec::new();
@@ -749,11 +769,11 @@ impl<'a> CallCreateExecutive<'a> {
 						None => return val,
 					}
 				},
-				Some((_, _, Err(TrapError::Call(subparams, resume)))) => {
+				Some((_, _, Err(TrapError::Call(subparams, mut resume)))) => {
 					tracer.prepare_trace_call(&subparams, resume.depth + 1, resume.machine.builtin(&subparams.address, resume.info.number).is_some());
 					vm_tracer.prepare_subtrace(subparams.code.as_ref().map_or_else(|| &[] as &[u8], |d| &*d as &[u8]));
 
-					let sub_exec = CallCreateExecutive::new_call_raw(
+					let mut sub_exec = CallCreateExecutive::new_call_raw(
 						subparams,
 						resume.info,
 						resume.machine,
@@ -764,15 +784,19 @@ impl<'a> CallCreateExecutive<'a> {
 						resume.static_flag,
 					);
 
+					if let Some(parent_substate) = resume.unconfirmed_substate() {
+						sub_exec.inherit_accessed_addresses(parent_substate);
+					}
+
 					callstack.push((None, resume));
 					callstack.push((None, sub_exec));
 					last_res = None;
 				},
-				Some((_, _, Err(TrapError::Create(subparams, address, resume)))) => {
+				Some((_, _, Err(TrapError::Create(subparams, address, mut resume)))) => {
 					tracer.prepare_trace_create(&subparams);
 					vm_tracer.prepare_subtrace(subparams.code.as_ref().map_or_else(|| &[] as &[u8], |d| &*d as &[u8]));
 
-					let sub_exec = CallCreateExecutive::new_create_raw(
+					let mut sub_exec = CallCreateExecutive::new_create_raw(
 						subparams,
 						resume.info,
 						resume.machine,
@@ -783,6 +807,10 @@ impl<'a> CallCreateExecutive<'a> {
 						resume.static_flag
 					);
 
+					if let Some(parent_substate) = resume.unconfirmed_substate() {
+						sub_exec.inherit_accessed_addresses(parent_substate);
+					}
+
 					callstack.push((Some(address), resume));
 					callstack.push((None, sub_exec));
 					last_res = None;
@@ -876,6 +904,12 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			return Err(ExecutionError::NotEnoughBaseGas { required: base_gas_required, got: t.gas });
 		}
 
+		if let (Action::Create, Some(limit)) = (&t.action, schedule.max_init_code_size) {
+			if t.data.len() > limit {
+				return Err(ExecutionError::InitCodeTooLarge { limit, got: t.data.len() });
+			}
+		}
+
 		if check_nonce && schedule.kill_dust != CleanDustMode::Off && !self.state.exists(&sender)? {
 			return Err(ExecutionError::SenderMustExist);
 		}
@@ -908,6 +942,11 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		}
 
 		let mut substate = Substate::new();
+		if schedule.eip2929 {
+			// EIP-2929: the sender and the precompiles start out warm.
+			substate.accessed_addresses.insert(sender);
+			substate.accessed_addresses.extend(self.machine.builtins().keys().cloned());
+		}
 
 		self.state.inc_nonce(&sender)?;
 
@@ -920,6 +959,9 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		let (result, output) = match t.action {
 			Action::Create => {
 				let (new_address, code_hash) = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &nonce, &t.data);
+				if schedule.eip2929 {
+					substate.accessed_addresses.insert(new_address);
+				}
 				let params = ActionParams {
 					code_address: new_address.clone(),
 					code_hash: code_hash,
@@ -943,6 +985,9 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 				(res, out)
 			},
 			Action::Call(ref address) => {
+				if schedule.eip2929 {
+					substate.accessed_addresses.insert(*address);
+				}
 				let params = ActionParams {
 					code_address: address.clone(),
 					address: address.clone(),
@@ -1298,6 +1343,54 @@ mod tests {
 		machine
 	}
 
+	fn make_byzantium_machine_with_code_limits(max_code_size: Option<u64>, max_init_code_size: Option<u64>) -> Machine {
+		let mut params = new_byzantium_test_machine().params().clone();
+		if let Some(max_code_size) = max_code_size {
+			params.max_code_size = max_code_size;
+		}
+		params.max_init_code_size = max_init_code_size;
+		Machine::regular(params, Default::default())
+	}
+
+	fn transact_create(factory: Factory, machine: &Machine, data: Vec<u8>) -> Result<(), ExecutionError> {
+		let t = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data,
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(Random.generate().secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let schedule = machine.schedule(info.number);
+
+		let mut ex = Executive::new(&mut state, &info, machine, &schedule);
+		ex.transact(&t, TransactOptions::with_no_tracing()).map(|_| ())
+	}
+
+	fn create_with_code(factory: Factory, machine: &Machine, code: Vec<u8>) -> (vm::Result<FinalizationResult>, Option<Arc<Bytes>>) {
+		let sender = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.sender = sender;
+		params.origin = sender;
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code));
+
+		let mut state = get_temp_state_with_factory(factory);
+		let info = EnvInfo::default();
+		let schedule = machine.schedule(info.number);
+		let result = {
+			let mut ex = Executive::new(&mut state, &info, machine, &schedule);
+			ex.create(params, &mut Substate::new(), &mut NoopTracer, &mut NoopVMTracer)
+		};
+		(result, state.code(&address).unwrap())
+	}
+
 	#[test]
 	fn test_cleanup_mode() {
 		let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
@@ -2316,4 +2409,56 @@ mod tests {
 		// Since transaction errored due to wasm was not activated, result is just empty
 		assert_eq!(output[..], [0u8; 20][..]);
 	}
+
+	evm_test!{test_transact_init_code_size_limit: test_transact_init_code_size_limit_int}
+	fn test_transact_init_code_size_limit(factory: Factory) {
+		// 5 bytes of init code, and the same code padded with a trailing STOP.
+		let below = hex!("3331600055").to_vec();
+		let above = hex!("333160005500").to_vec();
+
+		let machine = make_byzantium_machine_with_code_limits(None, None);
+		assert_eq!(machine.schedule(0).max_init_code_size, None);
+		assert_eq!(transact_create(factory.clone(), &machine, above.clone()), Ok(()));
+
+		let machine = make_byzantium_machine_with_code_limits(None, Some(5));
+		assert_eq!(machine.schedule(0).max_init_code_size, Some(5));
+		assert_eq!(transact_create(factory.clone(), &machine, below), Ok(()));
+		assert_eq!(
+			transact_create(factory, &machine, above),
+			Err(ExecutionError::InitCodeTooLarge { limit: 5, got: 6 })
+		);
+	}
+
+	evm_test!{test_create_init_code_size_limit: test_create_init_code_size_limit_int}
+	fn test_create_init_code_size_limit(factory: Factory) {
+		let machine = make_byzantium_machine_with_code_limits(None, Some(5));
+
+		let (result, _) = create_with_code(factory.clone(), &machine, hex!("3331600055").to_vec());
+		assert!(result.is_ok());
+
+		let (result, code) = create_with_code(factory, &machine, hex!("333160005500").to_vec());
+		assert_eq!(result.unwrap_err(), vm::Error::InitCodeTooLarge { limit: 5, got: 6 });
+		assert_eq!(code, None);
+	}
+
+	evm_test!{test_create_code_size_limit: test_create_code_size_limit_int}
+	fn test_create_code_size_limit(factory: Factory) {
+		// PUSH1 n PUSH1 0 RETURN: deploys `n` zero bytes.
+		let deploy_2 = hex!("60026000f3").to_vec();
+		let deploy_3 = hex!("60036000f3").to_vec();
+
+		let machine = make_byzantium_machine_with_code_limits(None, None);
+		assert_eq!(machine.schedule(0).create_data_limit, 24576);
+		let (result, code) = create_with_code(factory.clone(), &machine, deploy_3.clone());
+		assert!(result.is_ok());
+		assert_eq!(code.map(|code| code.len()), Some(3));
+
+		let machine = make_byzantium_machine_with_code_limits(Some(2), None);
+		let (result, code) = create_with_code(factory.clone(), &machine, deploy_2);
+		assert!(result.is_ok());
+		assert_eq!(code.map(|code| code.len()), Some(2));
+
+		let (result, _) = create_with_code(factory, &machine, deploy_3);
+		assert_eq!(result.unwrap_err(), vm::Error::OutOfGas);
+	}
 }
@@ -435,6 +435,14 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 		self.substate.sstore_clears_refund -= value as i128;
 	}
 
+	fn is_address_warm(&self, address: &Address) -> bool {
+		self.substate.accessed_addresses.contains(address)
+	}
+
+	fn mark_address_warm(&mut self, address: Address) {
+		self.substate.accessed_addresses.insert(address);
+	}
+
 	fn trace_next_instruction(&mut self, pc: usize, instruction: u8, current_gas: U256) -> bool {
 		self.vm_tracer.trace_next_instruction(pc, instruction, current_gas)
 	}
@@ -345,6 +345,14 @@ impl Machine {
 		};
 		t.verify_basic(check_low_s, chain_id)?;
 
+		if let transaction::Action::Create = t.action {
+			if let Some(limit) = self.params().max_init_code_size(header.number()) {
+				if t.data.len() as u64 > limit {
+					return Err(transaction::Error::InitCodeTooLarge { limit: limit as usize, got: t.data.len() });
+				}
+			}
+		}
+
 		Ok(())
 	}
 
@@ -431,6 +439,46 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_limit_init_code_size_of_create_transactions() {
+		use parity_crypto::publickey::{Generator, Random};
+
+		let create = |data: Vec<u8>| -> UnverifiedTransaction {
+			transaction::Transaction {
+				action: transaction::Action::Create,
+				value: U256::zero(),
+				data,
+				gas: U256::from(100_000),
+				gas_price: U256::zero(),
+				nonce: U256::zero(),
+			}.sign(Random.generate().secret(), None).into()
+		};
+		let header = Header::new();
+
+		let machine = Machine::regular(spec::new_byzantium_test().params().clone(), Default::default());
+		assert_eq!(machine.verify_transaction_basic(&create(vec![0; 6]), &header), Ok(()));
+
+		let mut params = spec::new_byzantium_test().params().clone();
+		params.max_init_code_size = Some(5);
+		let machine = Machine::regular(params, Default::default());
+		assert_eq!(machine.verify_transaction_basic(&create(vec![0; 5]), &header), Ok(()));
+		assert_eq!(
+			machine.verify_transaction_basic(&create(vec![0; 6]), &header),
+			Err(transaction::Error::InitCodeTooLarge { limit: 5, got: 6 }),
+		);
+
+		// Message calls are not affected by the limit.
+		let call = transaction::Transaction {
+			action: transaction::Action::Call(Address::zero()),
+			value: U256::zero(),
+			data: vec![0; 6],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero(),
+		};
+		assert_eq!(machine.verify_transaction_basic(&call.sign(Random.generate().secret(), None).into(), &header), Ok(()));
+	}
+
 	#[test]
 	fn ethash_gas_limit_is_multiple_of_determinant() {
 		use ethereum_types::U256;
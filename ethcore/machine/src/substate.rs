@@ -38,6 +38,9 @@ pub struct Substate {
 
 	/// Created contracts.
 	pub contracts_created: Vec<Address>,
+
+	/// Accounts accessed so far in the transaction (EIP-2929).
+	pub accessed_addresses: HashSet<Address>,
 }
 
 impl Substate {
@@ -53,6 +56,7 @@ impl Substate {
 		self.logs.extend(s.logs);
 		self.sstore_clears_refund += s.sstore_clears_refund;
 		self.contracts_created.extend(s.contracts_created);
+		self.accessed_addresses.extend(s.accessed_addresses);
 	}
 }
 
@@ -79,6 +83,7 @@ mod tests {
 		});
 		sub_state.sstore_clears_refund = (15000 * 5).into();
 		sub_state.suicides.insert(Address::from_low_u64_be(10));
+		sub_state.accessed_addresses.insert(Address::from_low_u64_be(1));
 
 		let mut sub_state_2 = Substate::new();
 		sub_state_2.contracts_created.push(Address::from_low_u64_be(2u64));
@@ -88,10 +93,13 @@ mod tests {
 			data: vec![]
 		});
 		sub_state_2.sstore_clears_refund = (15000 * 7).into();
+		sub_state_2.accessed_addresses.insert(Address::from_low_u64_be(1));
+		sub_state_2.accessed_addresses.insert(Address::from_low_u64_be(2));
 
 		sub_state.accrue(sub_state_2);
 		assert_eq!(sub_state.contracts_created.len(), 2);
 		assert_eq!(sub_state.sstore_clears_refund, (15000 * 12).into());
 		assert_eq!(sub_state.suicides.len(), 1);
+		assert_eq!(sub_state.accessed_addresses.len(), 2);
 	}
 }
@@ -110,6 +110,7 @@ use types::{
 use ethcore::client::{Client, Call};
 use client_traits::{BlockInfo, ChainNotify};
 use ethcore::miner::{self, Miner, MinerService, pool_client::NonceCache};
+use ethcore_miner::transaction_permissioning::AllowAll;
 use state_db::StateDB;
 use account_state::State;
 use trace::{Tracer, VMTracer};
@@ -355,6 +356,7 @@ impl Provider {
 			engine,
 			local_accounts,
 			None, // refuse_service_transactions = true
+			&AllowAll,
 		)
 	}
 
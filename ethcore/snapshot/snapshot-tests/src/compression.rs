@@ -0,0 +1,65 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Snapshot chunk compression tests.
+
+use common_types::{errors::SnapshotError, snapshot::ChunkCompression};
+use snapshot::compression::{CompressionConfig, Compressor, Decompressor};
+
+fn round_trip(algorithm: ChunkCompression) {
+	let raw: Vec<u8> = (0..10_000u32).flat_map(|i| (i % 251).to_be_bytes().to_vec()).collect();
+	let mut compressor = Compressor::new(CompressionConfig { algorithm, ..Default::default() });
+	let compressed = compressor.compress(&raw).unwrap().to_vec();
+	assert!(compressed.len() < raw.len());
+
+	let mut decompressor = Decompressor::new(algorithm);
+	assert_eq!(decompressor.decompress(&compressed).unwrap(), &raw[..]);
+}
+
+#[test]
+fn snappy_round_trip() {
+	round_trip(ChunkCompression::Snappy);
+}
+
+#[test]
+fn zstd_round_trip() {
+	round_trip(ChunkCompression::Zstd);
+}
+
+#[test]
+fn corrupted_zstd_chunk_is_an_error() {
+	let mut compressor = Compressor::new(CompressionConfig { algorithm: ChunkCompression::Zstd, ..Default::default() });
+	let mut compressed = compressor.compress(&[42u8; 4096]).unwrap().to_vec();
+	let len = compressed.len();
+	compressed.truncate(len / 2);
+
+	match Decompressor::new(ChunkCompression::Zstd).decompress(&compressed) {
+		Err(SnapshotError::WrongChunkFormat(_)) => {}
+		other => panic!("unexpected result: {:?}", other.map(|c| c.len())),
+	}
+}
+
+#[test]
+fn oversized_zstd_chunk_is_too_large() {
+	let mut compressor = Compressor::new(CompressionConfig { algorithm: ChunkCompression::Zstd, ..Default::default() });
+	// well beyond the 5MB decompressed chunk limit.
+	let compressed = compressor.compress(&vec![0u8; 8 * 1024 * 1024]).unwrap().to_vec();
+
+	match Decompressor::new(ChunkCompression::Zstd).decompress(&compressed) {
+		Err(SnapshotError::ChunkTooLarge) => {}
+		other => panic!("unexpected result: {:?}", other.map(|c| c.len())),
+	}
+}
@@ -46,6 +46,7 @@ use rlp;
 use snapshot::{
 	SnapshotClient,
 	StateRebuilder,
+	compression::Decompressor,
 	io::{SnapshotReader, PackedWriter, PackedReader},
 	chunker,
 };
@@ -175,29 +176,19 @@ pub fn restore(
 		chunker.rebuilder(chain, db, manifest).unwrap()
 	};
 
-	let mut snappy_buffer = Vec::new();
+	let mut decompressor = Decompressor::new(manifest.compression);
 
 	trace!(target: "snapshot", "restoring state");
 	for state_chunk_hash in manifest.state_hashes.iter() {
 		trace!(target: "snapshot", "state chunk hash: {}", state_chunk_hash);
 		let chunk = reader.chunk(*state_chunk_hash).unwrap();
-		let required_len = snap::raw::decompress_len(&chunk)?;
-		if snappy_buffer.len() < required_len {
-			snappy_buffer.resize_with(required_len, Default::default);
-		}
-		let len = snap::raw::Decoder::new().decompress(&chunk, &mut snappy_buffer).unwrap();
-		state.feed(&snappy_buffer[..len], &flag)?;
+		state.feed(decompressor.decompress(&chunk)?, &flag)?;
 	}
 
 	trace!(target: "snapshot", "restoring secondary");
 	for chunk_hash in manifest.block_hashes.iter() {
 		let chunk = reader.chunk(*chunk_hash).unwrap();
-		let required_len = snap::raw::decompress_len(&chunk)?;
-		if snappy_buffer.len() < required_len {
-			snappy_buffer.resize_with(required_len, Default::default);
-		}
-		let len = snap::raw::Decoder::new().decompress(&chunk, &mut snappy_buffer).unwrap();
-		secondary.feed(&snappy_buffer[..len], engine, &flag)?;
+		secondary.feed(decompressor.decompress(&chunk)?, engine, &flag)?;
 	}
 
 	trace!(target: "snapshot", "finalizing");
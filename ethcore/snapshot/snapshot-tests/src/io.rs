@@ -57,6 +57,8 @@ fn packed_write_and_read() {
 		state_root: keccak(b"notarealroot"),
 		block_number: 12345678987654321,
 		block_hash: keccak(b"notarealblock"),
+		engine: "Ethash".into(),
+		compression: Default::default(),
 	};
 
 	writer.finish(manifest.clone()).unwrap();
@@ -96,6 +98,8 @@ fn loose_write_and_read() {
 		state_root: keccak(b"notarealroot"),
 		block_number: 12345678987654321,
 		block_hash: keccak(b"notarealblock)"),
+		engine: "Ethash".into(),
+		compression: Default::default(),
 	};
 
 	writer.finish(manifest.clone()).unwrap();
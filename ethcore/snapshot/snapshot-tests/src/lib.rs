@@ -20,6 +20,7 @@
 
 mod abridged_block;
 mod account;
+mod compression;
 mod io;
 mod proof_of_work;
 mod proof_of_authority;
@@ -31,7 +32,7 @@ mod helpers;
 
 #[test]
 fn manifest_rlp() {
-    use common_types::snapshot::ManifestData;
+    use common_types::snapshot::{ChunkCompression, ManifestData};
     let manifest = ManifestData {
         version: 2,
         block_hashes: Vec::new(),
@@ -39,7 +40,45 @@ fn manifest_rlp() {
         block_number: 1234567,
         state_root: Default::default(),
         block_hash: Default::default(),
+        engine: "Ethash".into(),
+        compression: Default::default(),
     };
     let raw = manifest.clone().into_rlp();
     assert_eq!(ManifestData::from_rlp(&raw).unwrap(), manifest);
+
+    let zstd = ManifestData { compression: ChunkCompression::Zstd, ..manifest.clone() };
+    let raw_zstd = zstd.clone().into_rlp();
+    assert_ne!(raw_zstd, raw);
+    assert_eq!(ManifestData::from_rlp(&raw_zstd).unwrap(), zstd);
+}
+
+#[test]
+fn legacy_manifest_rlp() {
+    use common_types::snapshot::ManifestData;
+    use ethereum_types::H256;
+    use rlp::RlpStream;
+
+    let hashes = vec![H256::repeat_byte(1)];
+    let expected = ManifestData {
+        version: 2,
+        block_hashes: hashes.clone(),
+        state_hashes: hashes.clone(),
+        block_number: 1234567,
+        state_root: H256::repeat_byte(2),
+        block_hash: H256::repeat_byte(3),
+        engine: String::new(),
+        compression: Default::default(),
+    };
+
+    // versioned manifest without an engine.
+    let mut stream = RlpStream::new_list(6);
+    stream.append(&2u64).append_list(&hashes).append_list(&hashes)
+        .append(&expected.state_root).append(&expected.block_number).append(&expected.block_hash);
+    assert_eq!(ManifestData::from_rlp(&stream.out()).unwrap(), expected);
+
+    // unversioned manifest.
+    let mut stream = RlpStream::new_list(5);
+    stream.append_list(&hashes).append_list(&hashes)
+        .append(&expected.state_root).append(&expected.block_number).append(&expected.block_hash);
+    assert_eq!(ManifestData::from_rlp(&stream.out()).unwrap(), ManifestData { version: 1, ..expected });
 }
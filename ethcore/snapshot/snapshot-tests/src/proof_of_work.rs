@@ -21,12 +21,14 @@ use tempfile::TempDir;
 use common_types::{
 	errors::{EthcoreError as Error, SnapshotError},
 	engines::ForkChoice,
-	snapshot::{Progress, ManifestData},
+	snapshot::{ChunkCompression, Progress, ManifestData},
 };
 use blockchain::generator::{BlockGenerator, BlockBuilder};
 use blockchain::{BlockChain, ExtrasInsert};
 use snapshot::{
 	chunk_secondary,
+	compression::Decompressor,
+	CompressionConfig,
 	SnapshotComponents,
 	io::{PackedReader, PackedWriter, SnapshotReader, SnapshotWriter},
 	PowSnapshot,
@@ -39,7 +41,7 @@ use spec;
 
 const SNAPSHOT_MODE: PowSnapshot = PowSnapshot { blocks: 30000, max_restore_blocks: 30000 };
 
-fn chunk_and_restore(amount: u64) {
+fn chunk_and_restore(amount: u64, compression: ChunkCompression) {
 	let genesis = BlockBuilder::genesis();
 	let rest = genesis.add_blocks(amount as usize);
 	let generator = BlockGenerator::new(vec![rest]);
@@ -73,7 +75,8 @@ fn chunk_and_restore(amount: u64) {
 		&bc,
 		best_hash,
 		&writer,
-		&RwLock::new(Progress::new())
+		&RwLock::new(Progress::new()),
+		CompressionConfig { algorithm: compression, ..Default::default() },
 	).unwrap();
 
 	let manifest = ManifestData {
@@ -83,6 +86,8 @@ fn chunk_and_restore(amount: u64) {
 		state_root: KECCAK_NULL_RLP,
 		block_number: amount,
 		block_hash: best_hash,
+		engine: String::new(),
+		compression,
 	};
 
 	writer.into_inner().finish(manifest.clone()).unwrap();
@@ -94,10 +99,11 @@ fn chunk_and_restore(amount: u64) {
 
 	let reader = PackedReader::new(&snapshot_path).unwrap().unwrap();
 	let flag = AtomicBool::new(true);
+	let mut decompressor = Decompressor::new(reader.manifest().compression);
 	for chunk_hash in &reader.manifest().block_hashes {
 		let compressed = reader.chunk(*chunk_hash).unwrap();
-		let chunk = snap::raw::Decoder::new().decompress_vec(&compressed).unwrap();
-		rebuilder.feed(&chunk, engine.as_ref(), &flag).unwrap();
+		let chunk = decompressor.decompress(&compressed).unwrap();
+		rebuilder.feed(chunk, engine.as_ref(), &flag).unwrap();
 	}
 
 	rebuilder.finalize().unwrap();
@@ -110,12 +116,17 @@ fn chunk_and_restore(amount: u64) {
 
 #[test]
 fn chunk_and_restore_500() {
-	chunk_and_restore(500)
+	chunk_and_restore(500, ChunkCompression::Snappy)
+}
+
+#[test]
+fn chunk_and_restore_500_zstd() {
+	chunk_and_restore(500, ChunkCompression::Zstd)
 }
 
 #[test]
 fn chunk_and_restore_4k() {
-	chunk_and_restore(4000)
+	chunk_and_restore(4000, ChunkCompression::Snappy)
 }
 
 #[test]
@@ -145,6 +156,8 @@ fn checks_flag() {
 		state_root: KECCAK_NULL_RLP,
 		block_number: 102,
 		block_hash: H256::zero(),
+		engine: String::new(),
+		compression: Default::default(),
 	};
 
 	let mut rebuilder = SNAPSHOT_MODE.rebuilder(chain, db.clone(), &manifest).unwrap();
@@ -28,10 +28,11 @@ use common_types::{
 	ids::BlockId,
 	snapshot::Progress,
 	verification::Unverified,
-	snapshot::{ManifestData, RestorationStatus},
+	snapshot::{ChunkCompression, ManifestData, RestorationStatus},
 };
 use snapshot::{
-	chunk_state, chunk_secondary, SnapshotService,
+	chunk_state, chunk_secondary, CompressionConfig, SnapshotService,
+	compression::Compressor,
 	io::{PackedReader, PackedWriter, SnapshotReader, SnapshotWriter},
 	service::{Service, ServiceParams, Guard, Restoration, RestorationParams},
 	PowSnapshot,
@@ -80,6 +81,8 @@ fn sends_async_messages() {
 		state_root: Default::default(),
 		block_number: 0,
 		block_hash: Default::default(),
+		engine: String::new(),
+		compression: Default::default(),
 	};
 
 	service.begin_restore(manifest);
@@ -111,6 +114,8 @@ fn cannot_finish_with_invalid_chunks() {
 			state_root: H256::zero(),
 			block_number: 100000,
 			block_hash: H256::zero(),
+			engine: String::new(),
+			compression: Default::default(),
 		},
 		Algorithm::Archive,
 		restoration_db_handler(db_config).open(&tempdir.path().to_owned()).unwrap(),
@@ -134,6 +139,51 @@ fn cannot_finish_with_invalid_chunks() {
 	}
 }
 
+#[test]
+fn corrupted_zstd_chunk_fails_cleanly() {
+	use ethereum_types::H256;
+	use kvdb_rocksdb::DatabaseConfig;
+
+	let spec = spec::new_test();
+	let tempdir = TempDir::new().unwrap();
+
+	let state_hash = H256::random();
+	let block_hash = H256::random();
+	let db_config = DatabaseConfig::with_columns(ethcore_db::NUM_COLUMNS);
+	let gb = spec.genesis_block();
+	let flag = ::std::sync::atomic::AtomicBool::new(true);
+
+	let engine = &*spec.engine.clone();
+	let params = RestorationParams::new(
+		ManifestData {
+			version: 2,
+			state_hashes: vec![state_hash],
+			block_hashes: vec![block_hash],
+			state_root: H256::zero(),
+			block_number: 100000,
+			block_hash: H256::zero(),
+			engine: String::new(),
+			compression: ChunkCompression::Zstd,
+		},
+		Algorithm::Archive,
+		restoration_db_handler(db_config).open(&tempdir.path().to_owned()).unwrap(),
+		None,
+		&gb,
+		Guard::benign(),
+		engine,
+	);
+
+	let mut restoration = Restoration::new(params).unwrap();
+
+	let mut compressor = Compressor::new(CompressionConfig { algorithm: ChunkCompression::Zstd, ..Default::default() });
+	let mut corrupted = compressor.compress(&[0xc0; 1024]).unwrap().to_vec();
+	let len = corrupted.len();
+	corrupted.truncate(len - 4);
+
+	assert!(restoration.feed_state(state_hash, &corrupted, &flag).is_err());
+	assert!(restoration.feed_blocks(block_hash, &corrupted, &*spec.engine, &flag).is_err());
+	assert!(!restoration.is_done());
+}
 
 #[test]
 fn restored_is_equivalent() {
@@ -177,8 +227,10 @@ fn restored_is_equivalent() {
 
 	let manifest = service.manifest().unwrap();
 
+	assert_eq!(service.restoration_target(), None);
 	service.init_restore(manifest.clone(), true).unwrap();
 	assert!(service.init_restore(manifest.clone(), true).is_ok());
+	assert_eq!(service.restoration_target(), Some(manifest.block_number));
 
 	for hash in manifest.state_hashes {
 		let chunk = service.chunk(hash).unwrap();
@@ -191,6 +243,7 @@ fn restored_is_equivalent() {
 	}
 
 	assert_eq!(service.status(), RestorationStatus::Inactive);
+	assert_eq!(service.restoration_target(), None);
 
 	for x in 0..NUM_BLOCKS {
 		let block1 = client.block(BlockId::Number(x as u64)).unwrap();
@@ -230,6 +283,8 @@ fn guards_delete_folders() {
 		block_hashes: vec![],
 		block_number: 0,
 		block_hash: Default::default(),
+		engine: String::new(),
+		compression: Default::default(),
 		state_root: Default::default(),
 	};
 
@@ -250,6 +305,50 @@ fn guards_delete_folders() {
 	assert!(path.join("temp").exists());
 }
 
+#[test]
+fn refuses_incompatible_manifests() {
+	let gas_prices = vec![1.into(), 2.into(), 3.into(), 999.into()];
+	let client = generate_dummy_client_with_spec_and_data(spec::new_null, 10, 5, &gas_prices, false);
+
+	let spec = spec::new_null();
+	let tempdir = TempDir::new().unwrap();
+	let service_params = ServiceParams {
+		engine: spec.engine.clone(),
+		genesis_block: spec.genesis_block(),
+		restoration_db_handler: restoration_db_handler(DatabaseConfig::with_columns(ethcore_db::NUM_COLUMNS)),
+		pruning: ::journaldb::Algorithm::Archive,
+		channel: IoChannel::disconnected(),
+		snapshot_root: tempdir.path().to_owned(),
+		client: client,
+	};
+
+	let service = Service::new(service_params).unwrap();
+	let manifest = ManifestData {
+		version: 2,
+		state_hashes: vec![],
+		block_hashes: vec![],
+		block_number: 0,
+		block_hash: Default::default(),
+		engine: "NullEngine".into(),
+		compression: Default::default(),
+		state_root: Default::default(),
+	};
+
+	let newer = ManifestData { version: 3, ..manifest.clone() };
+	let err = service.init_restore(newer, false).unwrap_err();
+	assert_eq!(err.to_string(), "Snapshot error Snapshot format v3 unsupported (we support ≤2)");
+
+	let other_engine = ManifestData { engine: "Ethash".into(), ..manifest.clone() };
+	let err = service.init_restore(other_engine, false).unwrap_err();
+	assert_eq!(err.to_string(), "Snapshot error Snapshot was created by the Ethash engine, expected NullEngine");
+
+	// refused before touching the restoration directory.
+	assert_eq!(service.status(), RestorationStatus::Inactive);
+	assert!(!tempdir.path().join("restoration").exists());
+
+	assert!(service.init_restore(manifest, false).is_ok());
+}
+
 #[test]
 fn keep_ancient_blocks() {
 	let _ = ::env_logger::try_init();
@@ -278,7 +377,8 @@ fn keep_ancient_blocks() {
 		&bc,
 		best_hash,
 		&writer,
-		&RwLock::new(Progress::new())
+		&RwLock::new(Progress::new()),
+		CompressionConfig::default(),
 	).unwrap();
 	let state_db = client.state_db().journal_db().boxed_clone();
 	let start_header = bc.block_header_data(&best_hash).unwrap();
@@ -289,7 +389,8 @@ fn keep_ancient_blocks() {
 		&writer,
 		&RwLock::new(Progress::new()),
 		None,
-		0
+		0,
+		CompressionConfig::default(),
 	).unwrap();
 
 	let manifest = ManifestData {
@@ -299,6 +400,8 @@ fn keep_ancient_blocks() {
 		block_hashes,
 		block_number: NUM_BLOCKS,
 		block_hash: best_hash,
+		engine: String::new(),
+		compression: Default::default(),
 	};
 
 	writer.into_inner().finish(manifest.clone()).unwrap();
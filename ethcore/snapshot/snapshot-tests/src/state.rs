@@ -23,11 +23,12 @@ use keccak_hash::{KECCAK_NULL_RLP, keccak};
 use common_types::{
 	basic_account::BasicAccount,
 	errors::{EthcoreError as Error, SnapshotError},
-	snapshot::{ManifestData, Progress},
+	snapshot::{ChunkCompression, ManifestData, Progress},
 };
 use snapshot::{
 	test_helpers::to_fat_rlps,
-	chunk_state, StateRebuilder, SNAPSHOT_SUBPARTS,
+	chunk_state, CompressionConfig, StateRebuilder, SNAPSHOT_SUBPARTS,
+	compression::Decompressor,
 	io::{PackedReader, PackedWriter, SnapshotReader, SnapshotWriter},
 };
 use rand::SeedableRng;
@@ -42,8 +43,7 @@ use crate::helpers::StateProducer;
 
 const RNG_SEED: [u8; 16] = [1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16];
 
-#[test]
-fn snap_and_restore() {
+fn snap_and_restore_with(compression: ChunkCompression) {
 	use hash_db::{HashDB, EMPTY_PREFIX};
 	let mut producer = StateProducer::new();
 	let mut rng = XorShiftRng::from_seed(RNG_SEED);
@@ -62,8 +62,9 @@ fn snap_and_restore() {
 
 	let mut state_hashes = Vec::new();
 	let progress = RwLock::new(Progress::new());
+	let config = CompressionConfig { algorithm: compression, ..Default::default() };
 	for part in 0..SNAPSHOT_SUBPARTS {
-		let mut hashes = chunk_state(&old_db, &state_root, &writer, &progress, Some(part), 0).unwrap();
+		let mut hashes = chunk_state(&old_db, &state_root, &writer, &progress, Some(part), 0, config).unwrap();
 		state_hashes.append(&mut hashes);
 	}
 
@@ -74,6 +75,8 @@ fn snap_and_restore() {
 		state_root,
 		block_number: 1000,
 		block_hash: H256::zero(),
+		engine: String::new(),
+		compression,
 	}).unwrap();
 
 	let db_path = tempdir.path().join("db");
@@ -83,12 +86,14 @@ fn snap_and_restore() {
 		let reader = PackedReader::new(&snap_file).unwrap().unwrap();
 
 		let flag = AtomicBool::new(true);
+		assert_eq!(reader.manifest().compression, compression);
+		let mut decompressor = Decompressor::new(reader.manifest().compression);
 
 		for chunk_hash in &reader.manifest().state_hashes {
 			let raw = reader.chunk(*chunk_hash).unwrap();
-			let chunk = snap::raw::Decoder::new().decompress_vec(&raw).unwrap();
+			let chunk = decompressor.decompress(&raw).unwrap();
 
-			rebuilder.feed(&chunk, &flag).unwrap();
+			rebuilder.feed(chunk, &flag).unwrap();
 		}
 
 		assert_eq!(rebuilder.state_root(), state_root);
@@ -106,6 +111,16 @@ fn snap_and_restore() {
 	}
 }
 
+#[test]
+fn snap_and_restore() {
+	snap_and_restore_with(ChunkCompression::Snappy)
+}
+
+#[test]
+fn snap_and_restore_zstd() {
+	snap_and_restore_with(ChunkCompression::Zstd)
+}
+
 #[test]
 fn get_code_from_prev_chunk() {
 	use std::collections::HashSet;
@@ -188,7 +203,7 @@ fn checks_flag() {
 	let writer = Mutex::new(PackedWriter::new(&snap_file).unwrap());
 	let progress = RwLock::new(Progress::new());
 
-	let state_hashes = chunk_state(&old_db, &state_root, &writer, &progress, None, 0).unwrap();
+	let state_hashes = chunk_state(&old_db, &state_root, &writer, &progress, None, 0, CompressionConfig::default()).unwrap();
 
 	writer.into_inner().finish(ManifestData {
 		version: 2,
@@ -197,6 +212,8 @@ fn checks_flag() {
 		state_root,
 		block_number: 0,
 		block_hash: H256::zero(),
+		engine: String::new(),
+		compression: Default::default(),
 	}).unwrap();
 
 	let tempdir = TempDir::new().unwrap();
@@ -0,0 +1,132 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compression of snapshot chunks.
+//!
+//! The algorithm a snapshot was written with is recorded in its manifest, so
+//! restoration picks the matching decompressor.
+
+use std::io::{self, Read};
+
+use common_types::{
+	errors::SnapshotError as Error,
+	snapshot::ChunkCompression,
+};
+use log::trace;
+
+use super::MAX_CHUNK_SIZE;
+
+/// Default zstd compression level.
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// How chunks are compressed when taking a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressionConfig {
+	/// Compression algorithm. Snappy is the default, as older peers can't restore anything else.
+	pub algorithm: ChunkCompression,
+	/// Compression level used for zstd; ignored for snappy.
+	pub zstd_level: i32,
+}
+
+impl Default for CompressionConfig {
+	fn default() -> Self {
+		CompressionConfig {
+			algorithm: ChunkCompression::Snappy,
+			zstd_level: DEFAULT_ZSTD_LEVEL,
+		}
+	}
+}
+
+/// Compresses chunks, reusing its output buffer.
+pub struct Compressor {
+	config: CompressionConfig,
+	buffer: Vec<u8>,
+}
+
+impl Compressor {
+	/// Create a compressor with the given configuration.
+	pub fn new(config: CompressionConfig) -> Self {
+		Compressor { config, buffer: Vec::new() }
+	}
+
+	/// Compress a raw chunk.
+	pub fn compress(&mut self, raw: &[u8]) -> io::Result<&[u8]> {
+		match self.config.algorithm {
+			ChunkCompression::Snappy => {
+				let required = snap::raw::max_compress_len(raw.len());
+				if self.buffer.len() < required {
+					self.buffer.resize_with(required, Default::default);
+				}
+				let len = snap::raw::Encoder::new().compress(raw, &mut self.buffer)?;
+				Ok(&self.buffer[..len])
+			}
+			ChunkCompression::Zstd => {
+				let required = zstd_safe::compress_bound(raw.len());
+				if self.buffer.len() < required {
+					self.buffer.resize_with(required, Default::default);
+				}
+				let len = zstd::block::compress_to_buffer(raw, &mut self.buffer, self.config.zstd_level)?;
+				Ok(&self.buffer[..len])
+			}
+		}
+	}
+}
+
+/// Decompresses chunks, reusing its output buffer. Chunks which would
+/// decompress to more than `MAX_CHUNK_SIZE` bytes are rejected.
+pub struct Decompressor {
+	algorithm: ChunkCompression,
+	buffer: Vec<u8>,
+}
+
+impl Decompressor {
+	/// Create a decompressor for chunks written with `algorithm`.
+	pub fn new(algorithm: ChunkCompression) -> Self {
+		Decompressor { algorithm, buffer: Vec::new() }
+	}
+
+	/// Decompress a chunk.
+	pub fn decompress(&mut self, chunk: &[u8]) -> Result<&[u8], Error> {
+		match self.algorithm {
+			ChunkCompression::Snappy => {
+				let expected_len = snap::raw::decompress_len(chunk)?;
+				if expected_len > MAX_CHUNK_SIZE {
+					trace!(target: "snapshot", "Discarding large chunk: {} vs {}", expected_len, MAX_CHUNK_SIZE);
+					return Err(Error::ChunkTooLarge);
+				}
+				if self.buffer.len() < expected_len {
+					self.buffer.resize_with(expected_len, Default::default);
+				}
+				let len = snap::raw::Decoder::new().decompress(chunk, &mut self.buffer)?;
+				Ok(&self.buffer[..len])
+			}
+			ChunkCompression::Zstd => {
+				// read at most one byte past the limit, so oversized chunks are told
+				// apart from malformed ones without decompressing them in full.
+				self.buffer.clear();
+				let decoder = zstd::stream::read::Decoder::new(chunk)
+					.map_err(|e| Error::WrongChunkFormat(format!("Invalid zstd chunk: {}", e)))?;
+				decoder.take(MAX_CHUNK_SIZE as u64 + 1).read_to_end(&mut self.buffer)
+					.map_err(|e| Error::WrongChunkFormat(format!("Invalid zstd chunk: {}", e)))?;
+				if self.buffer.len() > MAX_CHUNK_SIZE {
+					trace!(target: "snapshot", "Discarding large chunk: more than {} bytes", MAX_CHUNK_SIZE);
+					return Err(Error::ChunkTooLarge);
+				}
+				Ok(&self.buffer)
+			}
+		}
+	}
+}
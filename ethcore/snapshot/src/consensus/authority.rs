@@ -318,6 +318,7 @@ impl Rebuilder for ChunkRebuilder {
 			self.chain.insert_epoch_transition(&mut batch, verified.header.number(),
 				verified.epoch_transition);
 			self.db.write(batch)?;
+			self.chain.commit();
 
 			trace!(target: "snapshot", "Verified epoch transition for epoch at block {}", verified.header.number());
 		}
@@ -330,6 +330,7 @@ impl Rebuilder for PowRebuilder {
 		});
 
 		self.db.write(batch)?;
+		self.chain.commit();
 		Ok(())
 	}
 }
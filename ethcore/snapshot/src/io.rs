@@ -28,7 +28,7 @@ use std::path::{Path, PathBuf};
 use bytes::Bytes;
 use common_types::{
 	errors::{SnapshotError, EthcoreError},
-	snapshot::ManifestData,
+	snapshot::{ChunkCompression, ManifestData},
 };
 use ethereum_types::H256;
 use log::trace;
@@ -94,14 +94,19 @@ impl SnapshotWriter for PackedWriter {
 	fn finish(mut self, manifest: ManifestData) -> io::Result<()> {
 		// we ignore the hashes fields of the manifest under the assumption that
 		// they are consistent with ours.
-		let mut stream = RlpStream::new_list(6);
+		let with_compression = manifest.compression != ChunkCompression::Snappy;
+		let mut stream = RlpStream::new_list(if with_compression { 8 } else { 7 });
 		stream
 			.append(&SNAPSHOT_VERSION)
 			.append_list(&self.state_hashes)
 			.append_list(&self.block_hashes)
 			.append(&manifest.state_root)
 			.append(&manifest.block_number)
-			.append(&manifest.block_hash);
+			.append(&manifest.block_hash)
+			.append(&manifest.engine);
+		if with_compression {
+			stream.append(&manifest.compression);
+		}
 
 		let manifest_rlp = stream.out();
 
@@ -243,7 +248,8 @@ impl PackedReader {
 
 		let rlp = Rlp::new(&manifest_buf);
 
-		let (start, version) = if rlp.item_count()? == 5 {
+		let item_count = rlp.item_count()?;
+		let (start, version) = if item_count == 5 {
 			(0, 1)
 		} else {
 			(1, rlp.val_at(0)?)
@@ -263,6 +269,8 @@ impl PackedReader {
 			state_root: rlp.val_at(2 + start)?,
 			block_number: rlp.val_at(3 + start)?,
 			block_hash: rlp.val_at(4 + start)?,
+			engine: if item_count > 5 + start { rlp.val_at(5 + start)? } else { String::new() },
+			compression: if item_count > 6 + start { rlp.val_at(6 + start)? } else { ChunkCompression::Snappy },
 		};
 
 		Ok(Some(PackedReader {
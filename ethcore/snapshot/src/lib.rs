@@ -53,10 +53,13 @@ use trie_db::{Trie, TrieMut};
 pub use self::consensus::*;
 pub use self::service::{Service, Guard, Restoration, RestorationParams};
 pub use self::traits::{Broadcast, Oracle, SnapshotService, SnapshotClient, SnapshotComponents, Rebuilder};
+pub use self::compression::CompressionConfig;
+use self::compression::Compressor;
 pub use self::io::SnapshotWriter;
 pub use self::watcher::Watcher;
 use common_types::basic_account::BasicAccount;
 
+pub mod compression;
 pub mod io;
 pub mod service;
 
@@ -99,6 +102,8 @@ pub struct SnapshotConfiguration {
 	pub enable: bool,
 	/// Number of threads for creating snapshots
 	pub processing_threads: usize,
+	/// Compression of the chunks of created snapshots
+	pub compression: CompressionConfig,
 }
 
 impl Default for SnapshotConfiguration {
@@ -106,12 +111,14 @@ impl Default for SnapshotConfiguration {
 		SnapshotConfiguration {
 			enable: false,
 			processing_threads: ::std::cmp::max(1, num_cpus::get_physical() / 2),
+			compression: CompressionConfig::default(),
 		}
 	}
 }
 
 /// Take a snapshot using the given blockchain, starting block hash, and database, writing into the given writer.
 pub fn take_snapshot<W: SnapshotWriter + Send>(
+	engine: &dyn Engine,
 	chunker: Box<dyn SnapshotComponents>,
 	chain: &BlockChain,
 	block_hash: H256,
@@ -119,6 +126,7 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 	writer: W,
 	p: &RwLock<Progress>,
 	processing_threads: usize,
+	compression: CompressionConfig,
 ) -> Result<(), Error> {
 	let start_header = chain.block_header_data(&block_hash)
 		.ok_or_else(|| Error::InvalidStartingBlock(BlockId::Hash(block_hash)))?;
@@ -132,7 +140,7 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 		let writer = &writer;
 		let tb = scope.builder().name("Snapshot Worker - Blocks".to_string());
 		let block_guard = tb.spawn(move |_| {
-			chunk_secondary(chunker, chain, block_hash, writer, p)
+			chunk_secondary(chunker, chain, block_hash, writer, p, compression)
 		})?;
 
 		// The number of threads must be between 1 and SNAPSHOT_SUBPARTS
@@ -148,7 +156,7 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 				let mut chunk_hashes = Vec::new();
 				for part in (thread_idx..SNAPSHOT_SUBPARTS).step_by(num_threads) {
 					debug!(target: "snapshot", "Chunking part {} of the state at {} in thread {}", part, block_number, thread_idx);
-					let mut hashes = chunk_state(state_db, &state_root, writer, p, Some(part), thread_idx)?;
+					let mut hashes = chunk_state(state_db, &state_root, writer, p, Some(part), thread_idx, compression)?;
 					chunk_hashes.append(&mut hashes);
 				}
 				Ok(chunk_hashes)
@@ -178,6 +186,8 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 		state_root,
 		block_number,
 		block_hash,
+		engine: engine.name().to_owned(),
+		compression: compression.algorithm,
 	};
 
 	writer.into_inner().finish(manifest_data)?;
@@ -198,15 +208,15 @@ pub fn chunk_secondary<'a>(
 	chain: &'a BlockChain,
 	start_hash: H256,
 	writer: &Mutex<dyn SnapshotWriter + 'a>,
-	progress: &'a RwLock<Progress>
+	progress: &'a RwLock<Progress>,
+	compression: CompressionConfig,
 ) -> Result<Vec<H256>, Error> {
 	let mut chunk_hashes = Vec::new();
-	let mut snappy_buffer = vec![0; snap::raw::max_compress_len(PREFERRED_CHUNK_SIZE)];
+	let mut compressor = Compressor::new(compression);
 
 	{
 		let mut chunk_sink = |raw_data: &[u8]| {
-			let compressed_size = snap::raw::Encoder::new().compress(raw_data, &mut snappy_buffer)?;
-			let compressed = &snappy_buffer[..compressed_size];
+			let compressed = compressor.compress(raw_data)?;
 			let hash = keccak(&compressed);
 			let size = compressed.len();
 
@@ -236,7 +246,7 @@ struct StateChunker<'a> {
 	hashes: Vec<H256>,
 	rlps: Vec<Bytes>,
 	cur_size: usize,
-	snappy_buffer: Vec<u8>,
+	compressor: Compressor,
 	writer: &'a Mutex<dyn SnapshotWriter + 'a>,
 	progress: &'a RwLock<Progress>,
 	thread_idx: usize,
@@ -264,12 +274,8 @@ impl<'a> StateChunker<'a> {
 
 		let raw_data = stream.out();
 
-		let required = snap::raw::max_compress_len(raw_data.len());
-		if self.snappy_buffer.len() < required {
-			self.snappy_buffer.resize_with(required, Default::default);
-		}
-		let compressed_size = snap::raw::Encoder::new().compress(&raw_data, &mut self.snappy_buffer)?;
-		let compressed = &self.snappy_buffer[..compressed_size];
+		let compressed = self.compressor.compress(&raw_data)?;
+		let compressed_size = compressed.len();
 		let hash = keccak(&compressed);
 
 		self.writer.lock().write_state_chunk(hash, compressed)?;
@@ -303,6 +309,7 @@ pub fn chunk_state<'a>(
 	progress: &'a RwLock<Progress>,
 	part: Option<usize>,
 	thread_idx: usize,
+	compression: CompressionConfig,
 ) -> Result<Vec<H256>, Error> {
 	let account_trie = TrieDB::new(&db, &root)?;
 
@@ -310,7 +317,7 @@ pub fn chunk_state<'a>(
 		hashes: Vec::new(),
 		rlps: Vec::new(),
 		cur_size: 0,
-		snappy_buffer: vec![0; snap::raw::max_compress_len(PREFERRED_CHUNK_SIZE)],
+		compressor: Compressor::new(compression),
 		writer,
 		progress,
 		thread_idx,
@@ -49,7 +49,7 @@ use super::{
 	StateRebuilder,
 	SnapshotService,
 	Rebuilder,
-	MAX_CHUNK_SIZE,
+	compression::Decompressor,
 	io::{SnapshotReader, LooseReader,  LooseWriter},
 	chunker,
 };
@@ -82,7 +82,7 @@ pub struct Restoration {
 	state: StateRebuilder,
 	secondary: Box<dyn Rebuilder>,
 	writer: Option<LooseWriter>,
-	snappy_buffer: Bytes,
+	decompressor: Decompressor,
 	final_state_root: H256,
 	guard: Guard,
 }
@@ -130,6 +130,7 @@ impl Restoration {
 		let secondary = chunker.rebuilder(chain, raw_db.clone(), &manifest)?;
 
 		let final_state_root = manifest.state_root.clone();
+		let decompressor = Decompressor::new(manifest.compression);
 
 		Ok(Restoration {
 			manifest,
@@ -138,7 +139,7 @@ impl Restoration {
 			state: StateRebuilder::new(raw_db.key_value().clone(), params.pruning),
 			secondary,
 			writer: params.writer,
-			snappy_buffer: Vec::new(),
+			decompressor,
 			final_state_root,
 			guard: params.guard,
 		})
@@ -147,17 +148,10 @@ impl Restoration {
 	/// Feeds a chunk of state data to the Restoration. Aborts early if `flag` becomes false.
 	pub fn feed_state(&mut self, hash: H256, chunk: &[u8], flag: &AtomicBool) -> Result<(), Error> {
 		if self.state_chunks_left.contains(&hash) {
-			let expected_len = snap::raw::decompress_len(chunk)?;
-			if expected_len > MAX_CHUNK_SIZE {
-				trace!(target: "snapshot", "Discarding large chunk: {} vs {}", expected_len, MAX_CHUNK_SIZE);
-				return Err(SnapshotError::ChunkTooLarge.into());
-			}
-			if self.snappy_buffer.len() < expected_len {
-				self.snappy_buffer.resize_with(expected_len, Default::default);
-			}
-			let len = snap::raw::Decoder::new().decompress(chunk, &mut self.snappy_buffer)?;
+			let raw = self.decompressor.decompress(chunk)?;
+			let len = raw.len();
 
-			self.state.feed(&self.snappy_buffer[..len], flag)?;
+			self.state.feed(raw, flag)?;
 
 			if let Some(ref mut writer) = self.writer.as_mut() {
 				writer.write_state_chunk(hash, chunk)?;
@@ -173,17 +167,9 @@ impl Restoration {
 	/// Feeds a chunk of block data to the `Restoration`. Aborts early if `flag` becomes false.
 	pub fn feed_blocks(&mut self, hash: H256, chunk: &[u8], engine: &dyn Engine, flag: &AtomicBool) -> Result<(), Error> {
 		if self.block_chunks_left.contains(&hash) {
-			let expected_len = snap::raw::decompress_len(chunk)?;
-			if expected_len > MAX_CHUNK_SIZE {
-				trace!(target: "snapshot", "Discarding large chunk: {} vs {}", expected_len, MAX_CHUNK_SIZE);
-				return Err(SnapshotError::ChunkTooLarge.into());
-			}
-			if self.snappy_buffer.len() < expected_len {
-				self.snappy_buffer.resize_with(expected_len, Default::default);
-			}
-			let len = snap::raw::Decoder::new().decompress(chunk, &mut self.snappy_buffer)?;
+			let raw = self.decompressor.decompress(chunk)?;
 
-			self.secondary.feed(&self.snappy_buffer[..len], engine, flag)?;
+			self.secondary.feed(raw, engine, flag)?;
 			if let Some(ref mut writer) = self.writer.as_mut() {
 				 writer.write_block_chunk(hash, chunk)?;
 			}
@@ -257,6 +243,7 @@ pub struct Service<C: Send + Sync + 'static> {
 	io_channel: Mutex<Channel<C>>,
 	pruning: Algorithm,
 	status: Mutex<RestorationStatus>,
+	restoration_target: Mutex<Option<u64>>,
 	reader: RwLock<Option<LooseReader>>,
 	engine: Arc<dyn Engine>,
 	genesis_block: Bytes,
@@ -278,6 +265,7 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 			io_channel: Mutex::new(params.channel),
 			pruning: params.pruning,
 			status: Mutex::new(RestorationStatus::Inactive),
+			restoration_target: Mutex::new(None),
 			reader: RwLock::new(None),
 			engine: params.engine,
 			genesis_block: params.genesis_block,
@@ -543,6 +531,8 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 	/// Initialize the restoration synchronously.
 	/// The recover flag indicates whether to recover the restored snapshot.
 	pub fn init_restore(&self, manifest: ManifestData, recover: bool) -> Result<(), Error> {
+		self.check_manifest(&manifest)?;
+
 		let mut res = self.restoration.lock();
 
 		let rest_dir = self.restoration_dir();
@@ -583,6 +573,7 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 			state_chunks: manifest.state_hashes.len() as u32,
 			block_chunks: manifest.block_hashes.len() as u32,
 		};
+		*self.restoration_target.lock() = Some(manifest.block_number);
 
 		fs::create_dir_all(&rest_dir)?;
 
@@ -726,6 +717,7 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 
 		let _ = fs::remove_dir_all(self.restoration_dir());
 		*self.status.lock() = RestorationStatus::Inactive;
+		*self.restoration_target.lock() = None;
 
 		Ok(())
 	}
@@ -806,6 +798,10 @@ impl<C: Send + Sync> SnapshotService for Service<C> {
 			.map(|c| (c.min_supported_version(), c.current_version()))
 	}
 
+	fn engine_name(&self) -> String {
+		self.engine.name().to_owned()
+	}
+
 	fn completed_chunks(&self) -> Option<Vec<H256>> {
 		let restoration = self.restoration.lock();
 
@@ -850,6 +846,10 @@ impl<C: Send + Sync> SnapshotService for Service<C> {
 		cur_status.clone()
 	}
 
+	fn restoration_target(&self) -> Option<u64> {
+		*self.restoration_target.lock()
+	}
+
 	fn begin_restore(&self, manifest: ManifestData) {
 		if let Err(e) = self.io_channel.lock().send(ClientIoMessage::BeginRestoration(manifest)) {
 			trace!("Error sending snapshot service message: {:?}", e);
@@ -861,6 +861,7 @@ impl<C: Send + Sync> SnapshotService for Service<C> {
 		self.restoring_snapshot.store(false, Ordering::SeqCst);
 		*self.restoration.lock() = None;
 		*self.status.lock() = RestorationStatus::Inactive;
+		*self.restoration_target.lock() = None;
 	}
 
 	fn restore_state_chunk(&self, hash: H256, chunk: Bytes) {
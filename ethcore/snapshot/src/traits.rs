@@ -42,6 +42,26 @@ pub trait SnapshotService : Sync + Send {
 	/// `None` indicates warp sync isn't supported by the consensus engine.
 	fn supported_versions(&self) -> Option<(u64, u64)>;
 
+	/// Name of the consensus engine snapshots are created and restored with.
+	fn engine_name(&self) -> String;
+
+	/// Check that a snapshot with given manifest can be restored, before fetching any chunks.
+	/// Its format version must be supported and, if the manifest names one, it must have been
+	/// created by the same consensus engine.
+	fn check_manifest(&self, manifest: &ManifestData) -> Result<(), SnapshotError> {
+		let (min, max) = self.supported_versions().ok_or(SnapshotError::SnapshotsUnsupported)?;
+		if manifest.version < min || manifest.version > max {
+			return Err(SnapshotError::FormatNotSupported(manifest.version, min, max));
+		}
+
+		let engine = self.engine_name();
+		if !manifest.engine.is_empty() && manifest.engine != engine {
+			return Err(SnapshotError::WrongEngine(manifest.engine.clone(), engine));
+		}
+
+		Ok(())
+	}
+
 	/// Returns a list of the completed chunks
 	fn completed_chunks(&self) -> Option<Vec<H256>>;
 
@@ -51,6 +71,10 @@ pub trait SnapshotService : Sync + Send {
 	/// Ask the snapshot service for the restoration status.
 	fn status(&self) -> RestorationStatus;
 
+	/// Block number the ongoing restoration is bringing the chain to.
+	/// `None` if not currently restoring.
+	fn restoration_target(&self) -> Option<u64>;
+
 	/// Begin snapshot restoration.
 	/// If a restoration is in progress, this will reset it and clear all data.
 	fn begin_restore(&self, manifest: ManifestData);
@@ -409,11 +409,13 @@ impl Spec {
 			params.eip2046_transition,
 			params.eip2200_advance_transition,
 			params.eip2315_transition,
+			params.eip2929_transition,
 			params.dust_protection_transition,
 			params.wasm_activation_transition,
 			params.kip4_transition,
 			params.kip6_transition,
 			params.max_code_size_transition,
+			params.max_init_code_size_transition,
 			params.transaction_permission_contract_transition,
 		];
 		// BUG: Rinkeby has homestead transition at block 1 but we can't reflect that in specs for non-Ethash networks
@@ -168,6 +168,13 @@ impl<'x> OpenBlock<'x> {
 	///
 	/// If valid, it will be executed, and archived together with the receipt.
 	pub fn push_transaction(&mut self, t: SignedTransaction) -> Result<&Receipt, Error> {
+		self.push_transaction_with_exception(t).map(|(receipt, _)| receipt)
+	}
+
+	/// Push a transaction into the block, like `push_transaction`.
+	///
+	/// Also returns the exception which stopped its execution, if any.
+	pub fn push_transaction_with_exception(&mut self, t: SignedTransaction) -> Result<(&Receipt, Option<vm::Error>), Error> {
 		if self.block.transactions_set.contains(&t.hash()) {
 			return Err(TransactionError::AlreadyImported.into());
 		}
@@ -181,7 +188,7 @@ impl<'x> OpenBlock<'x> {
 			traces.push(outcome.trace.into());
 		}
 		self.block.receipts.push(outcome.receipt);
-		Ok(self.block.receipts.last().expect("receipt just pushed; qed"))
+		Ok((self.block.receipts.last().expect("receipt just pushed; qed"), outcome.exception))
 	}
 
 	/// Push transactions onto the block.
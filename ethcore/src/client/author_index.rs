@@ -0,0 +1,188 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Index of canonical blocks by author, kept in `COL_EXTRA` under the
+//! `ExtrasIndex::BlockAuthor` prefix.
+//!
+//! Entries map `prefix ++ author ++ number` to the block hash. They are written for enacted
+//! blocks and removed for retracted ones on import, and blocks imported before the
+//! index was enabled are indexed in the background, from the best block down.
+//! Lookups check entries against the canonical chain, so an entry left behind
+//! while the index was disabled is never returned.
+
+use std::mem;
+
+use blockchain::{BlockChain, BlockProvider};
+use db::keys::ExtrasIndex;
+use db::COL_EXTRA;
+use ethereum_types::{Address, H256};
+use kvdb::{DBTransaction, KeyValueDB};
+use types::BlockNumber;
+
+/// Maximal number of blocks indexed by a single backfill round.
+const BACKFILL_BATCH: u64 = 4096;
+
+const AUTHOR_PREFIX_LEN: usize = 1 + 20;
+const KEY_LEN: usize = AUTHOR_PREFIX_LEN + mem::size_of::<BlockNumber>();
+
+fn author_prefix(author: &Address) -> [u8; AUTHOR_PREFIX_LEN] {
+	let mut prefix = [0u8; AUTHOR_PREFIX_LEN];
+	prefix[0] = ExtrasIndex::BlockAuthor as u8;
+	prefix[1..].copy_from_slice(author.as_bytes());
+	prefix
+}
+
+/// Key of the backfill progress: blocks below the stored number are not indexed yet.
+fn backfill_key() -> Vec<u8> {
+	[&[ExtrasIndex::BlockAuthor as u8][..], b"backfill"].concat()
+}
+
+fn key(author: &Address, number: BlockNumber) -> [u8; KEY_LEN] {
+	let mut key = [0u8; KEY_LEN];
+	key[..AUTHOR_PREFIX_LEN].copy_from_slice(&author_prefix(author));
+	key[AUTHOR_PREFIX_LEN..].copy_from_slice(&number.to_be_bytes());
+	key
+}
+
+/// Splits `from..=to` into aligned runs of block numbers sharing a big-endian prefix,
+/// in ascending order. Iterating over these prefixes visits only the keys in range.
+fn number_prefixes(from: BlockNumber, to: BlockNumber) -> Vec<Vec<u8>> {
+	let to = to as u128;
+	let mut prefixes = Vec::new();
+	let mut next = from as u128;
+	while next <= to {
+		// widen the run one byte at a time while it stays aligned and ends before `to`.
+		let mut width = 0;
+		while width < mem::size_of::<BlockNumber>() {
+			let span = 1u128 << (8 * (width + 1));
+			if next % span != 0 || next + span - 1 > to {
+				break;
+			}
+			width += 1;
+		}
+		prefixes.push((next as BlockNumber).to_be_bytes()[..mem::size_of::<BlockNumber>() - width].to_vec());
+		next += 1u128 << (8 * width);
+	}
+	prefixes
+}
+
+/// Index a canonical block.
+pub fn insert(batch: &mut DBTransaction, author: &Address, number: BlockNumber, hash: &H256) {
+	batch.put(COL_EXTRA, &key(author, number), hash.as_bytes());
+}
+
+/// Remove a retracted block from the index.
+pub fn remove(batch: &mut DBTransaction, author: &Address, number: BlockNumber) {
+	batch.delete(COL_EXTRA, &key(author, number));
+}
+
+/// Forget the backfill progress, so that the whole chain is indexed again once
+/// the index is re-enabled.
+pub fn reset_backfill(db: &dyn KeyValueDB) {
+	let progress = db.get(COL_EXTRA, &backfill_key()).expect("Low level database error. Some issue with disk?");
+	if progress.is_some() {
+		let mut batch = DBTransaction::new();
+		batch.delete(COL_EXTRA, &backfill_key());
+		db.write(batch).expect("Low level database error. Some issue with disk?");
+	}
+}
+
+/// Index up to `BACKFILL_BATCH` blocks below the backfill progress, starting from
+/// the best block on the first run. Stops early at blocks which are not in the
+/// database yet, e.g. ancient blocks after a warp sync, and resumes there later.
+///
+/// Returns the new progress, zero once the whole chain is indexed.
+pub fn backfill(db: &dyn KeyValueDB, chain: &BlockChain) -> BlockNumber {
+	let progress = db.get(COL_EXTRA, &backfill_key())
+		.expect("Low level database error. Some issue with disk?")
+		.map(|bytes| {
+			let mut number = [0u8; 8];
+			number.copy_from_slice(&bytes);
+			BlockNumber::from_be_bytes(number)
+		});
+
+	let start = match progress {
+		Some(0) => return 0,
+		Some(n) => n,
+		None => chain.best_block_number() + 1,
+	};
+
+	let mut batch = DBTransaction::new();
+	let mut next = start;
+	while next > start.saturating_sub(BACKFILL_BATCH) {
+		let header = match chain.block_hash(next - 1).and_then(|hash| chain.block_header_data(&hash)) {
+			Some(header) => header,
+			None => break,
+		};
+		insert(&mut batch, &header.author(), header.number(), &header.hash());
+		next -= 1;
+	}
+
+	if progress != Some(next) {
+		batch.put(COL_EXTRA, &backfill_key(), &next.to_be_bytes());
+		db.write(batch).expect("Low level database error. Some issue with disk?");
+	}
+
+	next
+}
+
+/// Numbers and hashes of the canonical blocks in `from..=to` authored by `author`,
+/// in ascending order and at most `limit` of them.
+pub fn blocks_authored_by(
+	db: &dyn KeyValueDB,
+	chain: &BlockChain,
+	author: &Address,
+	from: BlockNumber,
+	to: BlockNumber,
+	limit: usize,
+) -> Vec<(BlockNumber, H256)> {
+	let author_prefix = author_prefix(author);
+	let prefixes: Vec<Vec<u8>> = number_prefixes(from, to).into_iter()
+		.map(|number_prefix| [&author_prefix[..], &number_prefix[..]].concat())
+		.collect();
+
+	prefixes.iter()
+		.flat_map(|prefix| db.iter_with_prefix(COL_EXTRA, prefix))
+		.filter(|(key, _)| key.len() == KEY_LEN)
+		.map(|(key, hash)| {
+			let mut number = [0u8; 8];
+			number.copy_from_slice(&key[AUTHOR_PREFIX_LEN..]);
+			(BlockNumber::from_be_bytes(number), H256::from_slice(&hash))
+		})
+		.filter(|&(number, hash)| chain.block_hash(number) == Some(hash))
+		.take(limit)
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::number_prefixes;
+
+	#[test]
+	fn number_prefixes_cover_exactly_the_range() {
+		assert_eq!(number_prefixes(3, 2), Vec::<Vec<u8>>::new());
+		assert_eq!(number_prefixes(5, 5), vec![5u64.to_be_bytes().to_vec()]);
+		assert_eq!(number_prefixes(0, 0xff), vec![vec![0u8; 7]]);
+		assert_eq!(number_prefixes(0xfe, 0x201), vec![
+			0xfeu64.to_be_bytes().to_vec(),
+			0xffu64.to_be_bytes().to_vec(),
+			0x100u64.to_be_bytes()[..7].to_vec(),
+			0x200u64.to_be_bytes().to_vec(),
+			0x201u64.to_be_bytes().to_vec(),
+		]);
+		assert_eq!(number_prefixes(0, u64::max_value()), vec![Vec::<u8>::new()]);
+	}
+}
@@ -0,0 +1,205 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bounded log of canonical chain changes, kept in `COL_EXTRA` under the
+//! `ExtrasIndex::ChainEvent` prefix.
+//!
+//! Every import which changes the canonical chain is recorded under the next sequence
+//! number, in the same database transaction as the block itself. Only the latest
+//! `depth` events are retained, so a consumer which falls further behind has to resync.
+
+use std::cmp;
+use std::collections::VecDeque;
+
+use db::keys::ExtrasIndex;
+use db::COL_EXTRA;
+use ethereum_types::H256;
+use kvdb::{DBTransaction, KeyValueDB};
+use rlp;
+use types::chain_notify::{ChainEvent, ChainEventsError};
+
+const KEY_PREFIX: [u8; 1] = [ExtrasIndex::ChainEvent as u8];
+const KEY_LEN: usize = 1 + 8;
+
+/// Key of the latest recorded sequence number.
+fn latest_key() -> Vec<u8> {
+	[&KEY_PREFIX[..], b"latest"].concat()
+}
+
+fn key(sequence: u64) -> [u8; KEY_LEN] {
+	let mut key = [0u8; KEY_LEN];
+	key[0] = KEY_PREFIX[0];
+	key[1..].copy_from_slice(&sequence.to_be_bytes());
+	key
+}
+
+/// Retained chain events, mirrored in memory.
+pub struct ChainEvents {
+	depth: usize,
+	latest: u64,
+	events: VecDeque<ChainEvent>,
+}
+
+impl ChainEvents {
+	/// Load the retained events from the database, dropping those beyond `depth`.
+	pub fn load(db: &dyn KeyValueDB, depth: usize) -> Self {
+		let latest = db.get(COL_EXTRA, &latest_key())
+			.expect("Low level database error. Some issue with disk?")
+			.map_or(0, |bytes| {
+				let mut sequence = [0u8; 8];
+				sequence.copy_from_slice(&bytes);
+				u64::from_be_bytes(sequence)
+			});
+		let oldest = latest.saturating_sub(depth as u64) + 1;
+
+		// at most `latest` events were ever recorded, don't trust `depth` for the allocation.
+		let mut events = VecDeque::with_capacity(cmp::min(depth as u64, latest) as usize);
+		let mut batch = DBTransaction::new();
+		let mut pruned = false;
+		for (k, v) in db.iter_with_prefix(COL_EXTRA, &KEY_PREFIX).filter(|(k, _)| k.len() == KEY_LEN) {
+			let event: ChainEvent = rlp::decode(&v).expect("chain events are written as valid RLP; qed");
+			if event.sequence < oldest {
+				batch.delete(COL_EXTRA, &k);
+				pruned = true;
+			} else {
+				events.push_back(event);
+			}
+		}
+		if pruned {
+			db.write(batch).expect("Low level database error. Some issue with disk?");
+		}
+
+		ChainEvents { depth, latest, events }
+	}
+
+	/// Record a change of the canonical chain in `batch`. Returns the event to `commit`
+	/// once the batch is written, or `None` if nothing is recorded.
+	///
+	/// Must not be called again before the returned event is committed.
+	pub fn prepare(&self, batch: &mut DBTransaction, enacted: &[H256], retracted: &[H256]) -> Option<ChainEvent> {
+		if self.depth == 0 || (enacted.is_empty() && retracted.is_empty()) {
+			return None;
+		}
+
+		let event = ChainEvent {
+			sequence: self.latest + 1,
+			enacted: enacted.to_vec(),
+			retracted: retracted.to_vec(),
+		};
+		batch.put(COL_EXTRA, &key(event.sequence), &rlp::encode(&event));
+		batch.put(COL_EXTRA, &latest_key(), &event.sequence.to_be_bytes());
+		if let Some(evicted) = event.sequence.checked_sub(self.depth as u64).filter(|s| *s > 0) {
+			batch.delete(COL_EXTRA, &key(evicted));
+		}
+		Some(event)
+	}
+
+	/// Make a prepared event visible after its batch has been written.
+	pub fn commit(&mut self, event: ChainEvent) {
+		self.latest = event.sequence;
+		self.events.push_back(event);
+		while self.events.len() > self.depth {
+			self.events.pop_front();
+		}
+	}
+
+	/// Events after `sequence`, oldest first and at most `limit` of them. Fails with `Disabled`
+	/// if events are not recorded.
+	pub fn since(&self, sequence: u64, limit: usize) -> Result<Vec<ChainEvent>, ChainEventsError> {
+		if self.depth == 0 {
+			return Err(ChainEventsError::Disabled);
+		}
+		if sequence > self.latest {
+			return Err(ChainEventsError::AheadOfLatest { requested: sequence, latest: self.latest });
+		}
+		let oldest = self.events.front().map_or(self.latest + 1, |event| event.sequence);
+		if sequence < self.latest && sequence + 1 < oldest {
+			return Err(ChainEventsError::GapTooLarge { requested: sequence, oldest, latest: self.latest });
+		}
+
+		Ok(self.events.iter()
+			.skip_while(|event| event.sequence <= sequence)
+			.take(limit)
+			.cloned()
+			.collect())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hash(n: u64) -> H256 {
+		H256::from_low_u64_be(n)
+	}
+
+	fn record(events: &mut ChainEvents, db: &dyn KeyValueDB, enacted: &[H256], retracted: &[H256]) {
+		let mut batch = DBTransaction::new();
+		let event = events.prepare(&mut batch, enacted, retracted).unwrap();
+		db.write(batch).unwrap();
+		events.commit(event);
+	}
+
+	#[test]
+	fn replays_and_evicts_events() {
+		let db = ::kvdb_memorydb::create(::db::NUM_COLUMNS);
+		let mut events = ChainEvents::load(&db, 2);
+		assert_eq!(events.since(0, 10), Ok(vec![]));
+
+		for n in 1..=3 {
+			record(&mut events, &db, &[hash(n)], &[]);
+		}
+
+		let replayed = events.since(1, 10).unwrap();
+		assert_eq!(replayed.iter().map(|e| e.sequence).collect::<Vec<_>>(), vec![2, 3]);
+		assert_eq!(replayed[1].enacted, vec![hash(3)]);
+		assert_eq!(events.since(1, 1).unwrap().len(), 1);
+		assert_eq!(events.since(3, 10), Ok(vec![]));
+		assert_eq!(events.since(4, 10), Err(ChainEventsError::AheadOfLatest { requested: 4, latest: 3 }));
+		assert_eq!(events.since(0, 10), Err(ChainEventsError::GapTooLarge { requested: 0, oldest: 2, latest: 3 }));
+		assert_eq!(db.get(COL_EXTRA, &key(1)).unwrap(), None);
+	}
+
+	#[test]
+	fn survives_reload_with_smaller_depth() {
+		let db = ::kvdb_memorydb::create(::db::NUM_COLUMNS);
+		let mut events = ChainEvents::load(&db, 4);
+		for n in 1..=4 {
+			record(&mut events, &db, &[hash(n)], &[hash(n + 10)]);
+		}
+		let expected = events.since(2, 10).unwrap();
+
+		let mut events = ChainEvents::load(&db, 2);
+		assert_eq!(events.since(2, 10), Ok(expected));
+		assert!(events.since(1, 10).is_err());
+		assert_eq!(db.get(COL_EXTRA, &key(2)).unwrap(), None);
+
+		record(&mut events, &db, &[hash(5)], &[]);
+		assert_eq!(events.since(4, 10).unwrap()[0].sequence, 5);
+	}
+
+	#[test]
+	fn reports_disabled_recording() {
+		let events = ChainEvents::load(&::kvdb_memorydb::create(::db::NUM_COLUMNS), 0);
+		assert_eq!(events.since(0, 10), Err(ChainEventsError::Disabled));
+	}
+
+	#[test]
+	fn does_not_record_empty_routes() {
+		let events = ChainEvents::load(&::kvdb_memorydb::create(::db::NUM_COLUMNS), 2);
+		assert_eq!(events.prepare(&mut DBTransaction::new(), &[], &[]), None);
+	}
+}
@@ -45,6 +45,7 @@ use blockchain::{
 	BlockNumberKey,
 	BlockProvider,
 	BlockReceipts,
+	CacheReport as BlockChainCacheReport,
 	CacheSize as BlockChainCacheSize,
 	ExtrasInsert,
 	TransactionAddress,
@@ -57,6 +58,8 @@ use client::{
 	ReopenBlock, SealedBlockImporter,
 };
 use client::ancient_import::AncientVerifier;
+use client::author_index;
+use client::chain_events::ChainEvents;
 use client_traits::{
 	AccountData,
 	BadBlocks,
@@ -99,7 +102,7 @@ use registrar::RegistrarClient;
 use snapshot::{self, SnapshotClient, SnapshotWriter};
 use spec::Spec;
 use state_db::StateDB;
-use trace::{self, Database as TraceDatabase, ImportRequest as TraceImportRequest, LocalizedTrace, TraceDB};
+use trace::{self, Database as TraceDatabase, ImportRequest as TraceImportRequest, LocalizedTrace, TraceDB, TracePage};
 use trie_vm_factories::{Factories, VmFactory};
 use types::{
 	ancestry_action::AncestryAction,
@@ -108,14 +111,15 @@ use types::{
 	blockchain_info::BlockChainInfo,
 	BlockNumber,
 	call_analytics::CallAnalytics,
-	chain_notify::{ChainMessageType, ChainRoute, NewBlocks},
-	client_types::{ClientReport, IoStats, Mode, StateResult},
+	chain_notify::{ChainEvent, ChainEventsError, ChainMessageType, ChainRoute, NewBlocks},
+	client_types::{ClientReport, ExecutionProof, IoStats, Mode, ProveError, StateResult},
 	encoded,
 	engines::{
 		epoch::{PendingTransition, Transition as EpochTransition},
 		ForkChoice,
 		machine::Call as MachineCall,
 		MAX_UNCLE_AGE,
+		params::ForkActivation,
 		SealingState,
 	},
 	errors::{BlockError, EngineError, EthcoreError, EthcoreResult, ExecutionError, ImportError, SnapshotError},
@@ -221,6 +225,9 @@ pub struct Client {
 	/// List of actors to be notified on certain chain events
 	notify: RwLock<Vec<Weak<dyn ChainNotify>>>,
 
+	/// Recent changes of the canonical chain, for consumers catching up after a restart.
+	chain_events: RwLock<ChainEvents>,
+
 	/// Queued transactions from IO
 	queue_transactions: IoChannelQueue,
 	/// Ancient blocks import queue
@@ -563,6 +570,12 @@ impl Importer {
 
 		state.journal_under(&mut batch, number, hash).expect("DB commit failed");
 
+		if let Some(ref preimages) = client.factories.preimages {
+			for (preimage_hash, preimage) in preimages.drain() {
+				batch.put(::db::COL_NODE_INFO, &preimage_key(&preimage_hash), &preimage);
+			}
+		}
+
 		let finalized: Vec<_> = ancestry_actions.into_iter().map(|ancestry_action| {
 			let AncestryAction::MarkFinalized(a) = ancestry_action;
 
@@ -589,11 +602,31 @@ impl Importer {
 			retracted: route.retracted.len()
 		});
 
+		if client.config.author_index {
+			for retracted in &route.retracted {
+				if let Some(retracted) = chain.block_header_data(retracted) {
+					author_index::remove(&mut batch, &retracted.author(), retracted.number());
+				}
+			}
+			for enacted in &route.enacted {
+				if enacted == hash {
+					author_index::insert(&mut batch, header.author(), number, hash);
+				} else if let Some(enacted) = chain.block_header_data(enacted) {
+					author_index::insert(&mut batch, &enacted.author(), enacted.number(), &enacted.hash());
+				}
+			}
+		}
+
+		let chain_event = client.chain_events.read().prepare(&mut batch, &route.enacted, &route.retracted);
+
 		let is_canon = route.enacted.last().map_or(false, |h| h == hash);
 		state.sync_cache(&route.enacted, &route.retracted, is_canon);
 		// Final commit to the DB
 		client.db.read().key_value().write(batch).expect("Low level database error writing a transaction. Some issue with the disk?");
 		chain.commit();
+		if let Some(event) = chain_event {
+			client.chain_events.write().commit(event);
+		}
 
 		self.check_epoch_end(&header, &finalized, &chain, client);
 
@@ -711,6 +744,7 @@ impl Importer {
 			// fetched from a DB iterator and DB iterators are only available on
 			// flushed data.
 			client.db.read().key_value().write(batch).expect("DB flush failed");
+			chain.commit();
 		}
 	}
 }
@@ -735,6 +769,7 @@ impl Client {
 			vm: VmFactory::new(config.jump_table_size),
 			trie: trie_factory,
 			accountdb: Default::default(),
+			preimages: if config.record_preimages { Some(Default::default()) } else { None },
 		};
 
 		let journal_db = journaldb::new(db.key_value().clone(), config.pruning, ::db::COL_STATE);
@@ -751,6 +786,12 @@ impl Client {
 		let chain = Arc::new(BlockChain::new(config.blockchain.clone(), &gb, db.clone()));
 		let tracedb = RwLock::new(TraceDB::new(config.tracing.clone(), db.clone(), chain.clone()));
 
+		if !config.author_index {
+			// blocks imported from now on are not indexed, backfill them if the index is re-enabled.
+			author_index::reset_backfill(&**db.key_value());
+		}
+		let chain_events = RwLock::new(ChainEvents::load(&**db.key_value(), config.chain_events_depth));
+
 		debug!(target: "client", "Cleanup journal: DB Earliest = {:?}, Latest = {:?}", state_db.journal_db().earliest_era(), state_db.journal_db().latest_era());
 
 		let history = if config.history < MIN_HISTORY_SIZE {
@@ -791,6 +832,7 @@ impl Client {
 			report: RwLock::new(Default::default()),
 			io_channel: RwLock::new(message_channel),
 			notify: RwLock::new(Vec::new()),
+			chain_events,
 			queue_transactions: IoChannelQueue::new(config.transaction_verification_queue_size),
 			queue_ancient_blocks: IoChannelQueue::new(MAX_ANCIENT_BLOCKS_QUEUE_SIZE),
 			queued_ancient_blocks: Default::default(),
@@ -835,6 +877,7 @@ impl Client {
 				});
 
 				client.db.read().key_value().write(batch)?;
+				chain.commit();
 			}
 		}
 
@@ -913,6 +956,19 @@ impl Client {
 		})
 	}
 
+	/// Environment for virtual calls executed on top of the given header.
+	fn virtual_call_env_info(&self, header: &Header, gas_limit: U256) -> EnvInfo {
+		EnvInfo {
+			number: header.number(),
+			author: *header.author(),
+			timestamp: header.timestamp(),
+			difficulty: *header.difficulty(),
+			last_hashes: self.build_last_hashes(*header.parent_hash()),
+			gas_used: U256::default(),
+			gas_limit,
+		}
+	}
+
 	fn build_last_hashes(&self, parent_hash: H256) -> Arc<LastHashes> {
 		{
 			let hashes = self.last_hashes.read();
@@ -1122,6 +1178,7 @@ impl Client {
 			cache_read_bytes: io_stats.cache_read_bytes,
 			bytes_written: io_stats.bytes_written,
 		};
+		report.epoch_transitions = self.chain.read().epoch_transitions_count();
 
 		report
 	}
@@ -1132,6 +1189,18 @@ impl Client {
 		self.tracedb.read().collect_garbage();
 	}
 
+	fn backfill_author_index(&self) {
+		if !self.config.author_index {
+			return;
+		}
+
+		// hold the import lock so no reorg happens between reading and indexing the canonical chain.
+		let _import_lock = self.importer.import_lock.lock();
+		let db = self.db.read();
+		let left = author_index::backfill(&**db.key_value(), &self.chain.read());
+		trace!(target: "client", "Author index backfill: {} blocks left", left);
+	}
+
 	fn check_snooze(&self) {
 		let mode = self.mode.lock().clone();
 		match mode {
@@ -1183,6 +1252,18 @@ impl Client {
 		}
 	}
 
+	fn transaction_traces_iter(&self, transaction: TransactionId) -> Option<Box<dyn ExactSizeIterator<Item = LocalizedTrace>>> {
+		if !self.tracedb.read().tracing_enabled() {
+			return None;
+		}
+
+		self.transaction_address(transaction)
+			.and_then(|tx_address| {
+				self.block_number(BlockId::Hash(tx_address.block_hash))
+					.and_then(|number| self.tracedb.read().transaction_traces(number, tx_address.index))
+			})
+	}
+
 	fn wake_up(&self) {
 		if !self.liveness.load(AtomicOrdering::Relaxed) {
 			self.liveness.store(true, AtomicOrdering::Relaxed);
@@ -1306,6 +1387,7 @@ impl DatabaseRestore for Client {
 		*state_db = StateDB::new(journaldb::new(db.key_value().clone(), self.pruning, ::db::COL_STATE), cache_size);
 		*chain = Arc::new(BlockChain::new(self.config.blockchain.clone(), &[], db.clone()));
 		*tracedb = TraceDB::new(self.config.tracing.clone(), db.clone(), chain.clone());
+		*self.chain_events.write() = ChainEvents::load(&**db.key_value(), self.config.chain_events_depth);
 		Ok(())
 	}
 }
@@ -1368,10 +1450,16 @@ impl BlockChainReset for Client {
 		// update the new best block hash
 		batch.put(::db::COL_EXTRA, b"best", best_block_hash.as_bytes());
 
+		let retracted = blocks_to_delete.iter().map(|(_, hash)| *hash).collect::<Vec<_>>();
+		let chain_event = self.chain_events.read().prepare(&mut batch, &[], &retracted);
+
 		self.db.read()
 			.key_value()
 			.write(batch)
 			.map_err(|err| format!("could not delete blocks; io error occurred: {}", err))?;
+		if let Some(event) = chain_event {
+			self.chain_events.write().commit(event);
+		}
 
 		info!("New best block hash {}", Colour::Green.bold().paint(format!("{:?}", best_block_hash)));
 
@@ -1519,30 +1607,14 @@ impl Call for Client {
 	type State = State<::state_db::StateDB>;
 
 	fn call(&self, transaction: &SignedTransaction, analytics: CallAnalytics, state: &mut Self::State, header: &Header) -> Result<Executed, CallError> {
-		let env_info = EnvInfo {
-			number: header.number(),
-			author: *header.author(),
-			timestamp: header.timestamp(),
-			difficulty: *header.difficulty(),
-			last_hashes: self.build_last_hashes(*header.parent_hash()),
-			gas_used: U256::default(),
-			gas_limit: U256::max_value(),
-		};
+		let env_info = self.virtual_call_env_info(header, U256::max_value());
 		let machine = self.engine.machine();
 
 		Self::do_virtual_call(&machine, &env_info, state, transaction, analytics)
 	}
 
 	fn call_many(&self, transactions: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError> {
-		let mut env_info = EnvInfo {
-			number: header.number(),
-			author: *header.author(),
-			timestamp: header.timestamp(),
-			difficulty: *header.difficulty(),
-			last_hashes: self.build_last_hashes(*header.parent_hash()),
-			gas_used: U256::default(),
-			gas_limit: U256::max_value(),
-		};
+		let mut env_info = self.virtual_call_env_info(header, U256::max_value());
 
 		let mut results = Vec::with_capacity(transactions.len());
 		let machine = self.engine.machine();
@@ -1556,20 +1628,42 @@ impl Call for Client {
 		Ok(results)
 	}
 
+	fn call_bundle(&self, transactions: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header, stop_on_error: bool) -> Vec<Result<Executed, CallError>> {
+		let mut env_info = self.virtual_call_env_info(header, U256::max_value());
+
+		let mut results = Vec::with_capacity(transactions.len());
+		let machine = self.engine.machine();
+
+		for &(ref t, analytics) in transactions {
+			state.checkpoint();
+			let ret = Self::do_virtual_call(machine, &env_info, state, t, analytics);
+			let failed = match ret {
+				Ok(ref executed) => {
+					state.discard_checkpoint();
+					env_info.gas_used = executed.cumulative_gas_used;
+					executed.exception.is_some()
+				}
+				Err(_) => {
+					state.revert_to_checkpoint();
+					true
+				}
+			};
+			results.push(ret);
+
+			if failed && stop_on_error {
+				break;
+			}
+		}
+
+		results
+	}
+
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError> {
 		let (mut upper, max_upper, env_info) = {
 			let init = *header.gas_limit();
 			let max = init * U256::from(10);
 
-			let env_info = EnvInfo {
-				number: header.number(),
-				author: *header.author(),
-				timestamp: header.timestamp(),
-				difficulty: *header.difficulty(),
-				last_hashes: self.build_last_hashes(*header.parent_hash()),
-				gas_used: U256::default(),
-				gas_limit: max,
-			};
+			let env_info = self.virtual_call_env_info(header, max);
 
 			(init, max, env_info)
 		};
@@ -1722,6 +1816,10 @@ impl BlockChainClient for Client {
 		self.config.spec_name.clone()
 	}
 
+	fn fork_schedule(&self) -> Vec<ForkActivation> {
+		self.engine.params().fork_schedule()
+	}
+
 	fn chain(&self) -> Arc<dyn BlockProvider> {
 		self.chain.read().clone()
 	}
@@ -1964,6 +2062,29 @@ impl BlockChainClient for Client {
 		self.state_db.read().journal_db().state(hash)
 	}
 
+	fn preimage(&self, hash: &H256) -> Option<Bytes> {
+		self.db.read().key_value().get(::db::COL_NODE_INFO, &preimage_key(hash))
+			.expect("Low level database error. Some issue with disk?")
+			.or_else(|| self.factories.preimages.as_ref().and_then(|preimages| preimages.get(hash)))
+	}
+
+	fn blockchain_cache_report(&self) -> BlockChainCacheReport {
+		self.chain.read().cache_report()
+	}
+
+	fn blocks_authored_by(&self, author: &Address, from: BlockNumber, to: BlockNumber, limit: usize) -> Option<Vec<(BlockNumber, H256)>> {
+		if !self.config.author_index {
+			return None;
+		}
+
+		let db = self.db.read();
+		Some(author_index::blocks_authored_by(&**db.key_value(), &self.chain.read(), author, from, to, limit))
+	}
+
+	fn chain_events_since(&self, sequence: u64, limit: usize) -> Result<Vec<ChainEvent>, ChainEventsError> {
+		self.chain_events.read().since(sequence, limit)
+	}
+
 	fn block_receipts(&self, hash: &H256) -> Option<BlockReceipts> {
 		self.chain.read().block_receipts(hash)
 	}
@@ -2109,15 +2230,11 @@ impl BlockChainClient for Client {
 	}
 
 	fn transaction_traces(&self, transaction: TransactionId) -> Option<Vec<LocalizedTrace>> {
-		if !self.tracedb.read().tracing_enabled() {
-			return None;
-		}
+		self.transaction_traces_iter(transaction).map(Iterator::collect)
+	}
 
-		self.transaction_address(transaction)
-			.and_then(|tx_address| {
-				self.block_number(BlockId::Hash(tx_address.block_hash))
-					.and_then(|number| self.tracedb.read().transaction_traces(number, tx_address.index))
-			})
+	fn transaction_traces_page(&self, transaction: TransactionId, after: usize, count: usize) -> Option<TracePage> {
+		self.transaction_traces_iter(transaction).map(|traces| TracePage::new(traces, after, count))
 	}
 
 	fn block_traces(&self, block: BlockId) -> Option<Vec<LocalizedTrace>> {
@@ -2315,6 +2432,7 @@ impl Tick for Client {
 	// TODO: manage by real events.
 	fn tick(&self, prevent_sleep: bool) {
 		self.check_garbage();
+		self.backfill_author_index();
 		if !prevent_sleep {
 			self.check_snooze();
 		}
@@ -2555,6 +2673,26 @@ impl ProvingBlockChainClient for Client {
 		)
 	}
 
+	fn prove_transaction_with_cap(&self, transaction: SignedTransaction, id: BlockId, witness_cap: usize) -> Result<ExecutionProof, ProveError> {
+		let (header, mut env_info) = match (self.block_header(id), self.env_info(id)) {
+			(Some(s), Some(e)) => (s, e),
+			_ => return Err(ProveError::StateUnavailable),
+		};
+
+		env_info.gas_limit = transaction.gas;
+		let mut jdb = self.state_db.read().journal_db().boxed_clone();
+
+		executive_state::prove_transaction_virtual_with_cap(
+			jdb.as_hash_db_mut(),
+			header.state_root(),
+			&transaction,
+			self.engine.machine(),
+			&env_info,
+			self.factories.clone(),
+			witness_cap,
+		)
+	}
+
 	fn epoch_signal(&self, hash: H256) -> Option<Vec<u8>> {
 		// pending transitions are never deleted, and do not contain
 		// finality proofs by definition.
@@ -2618,6 +2756,7 @@ impl SnapshotClient for Client {
 			let chunker = snapshot::chunker(self.engine.snapshot_mode()).ok_or_else(|| SnapshotError::SnapshotsUnsupported)?;
 			// Spawn threads and take snapshot
 			snapshot::take_snapshot(
+				&*self.engine,
 				chunker,
 				&self.chain.read(),
 				block_hash,
@@ -2625,6 +2764,7 @@ impl SnapshotClient for Client {
 				writer,
 				p,
 				processing_threads,
+				self.config.snapshot.compression,
 			)?;
 			Ok(())
 		}
@@ -2767,6 +2907,11 @@ impl ImportExportBlocks for Client {
 	}
 }
 
+/// Database key of a recorded state key preimage.
+fn preimage_key(hash: &H256) -> Vec<u8> {
+	[::db::PREIMAGE_KEY_PREFIX, hash.as_bytes()].concat()
+}
+
 /// Returns `LocalizedReceipt` given `LocalizedTransaction`
 /// and a vector of receipts from given block up to transaction index.
 fn transaction_receipt(
@@ -96,6 +96,12 @@ pub struct ClientConfig {
 	pub snapshot: SnapshotConfiguration,
 	/// Stop importing at this block and enter sleep mode.
 	pub sync_until: Option<u64>,
+	/// Record preimages of hashed addresses and storage keys, for debugging.
+	pub record_preimages: bool,
+	/// Maintain an index of canonical blocks by author.
+	pub author_index: bool,
+	/// Number of canonical chain changes retained for replay, zero disables recording.
+	pub chain_events_depth: usize,
 }
 
 impl Default for ClientConfig {
@@ -125,6 +131,9 @@ impl Default for ClientConfig {
 			max_round_blocks_to_import: 12,
 			snapshot: Default::default(),
 			sync_until: None,
+			record_preimages: false,
+			author_index: false,
+			chain_events_depth: 0,
 		}
 	}
 }
@@ -17,7 +17,9 @@
 //! Blockchain database client.
 
 mod ancient_import;
+mod author_index;
 mod bad_blocks;
+mod chain_events;
 mod client;
 mod config;
 mod traits;
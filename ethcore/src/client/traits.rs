@@ -41,6 +41,11 @@ pub trait Call {
 	/// Returns a vector of successes or a failure if any of the transaction fails.
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError>;
 
+	/// Makes multiple non-persistent but dependent transaction calls, returning the outcome of each.
+	/// A call which can't be executed leaves the state untouched and the following calls still run,
+	/// unless `stop_on_error` is set: then the results end with the first call which fails or throws.
+	fn call_bundle(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header, stop_on_error: bool) -> Vec<Result<Executed, CallError>>;
+
 	/// Estimates how much gas will be necessary for a call.
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError>;
 }
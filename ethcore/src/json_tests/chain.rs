@@ -33,6 +33,7 @@ use types::{
 };
 use verification::{VerifierType, queue::kind::BlockLike};
 use super::{HookType, SKIP_TESTS};
+use super::test_common::failure_id;
 use rustc_hex::ToHex;
 use ethereum_types::{U256, H256};
 
@@ -140,7 +141,8 @@ pub fn json_chain_test<H: FnMut(&str, HookType)>(path: &Path, json_data: &[u8],
 		{
 			let mut fail_unless = |cond: bool| {
 				if !cond && !fail {
-					failed.push(name.clone());
+					// a blockchain test runs against a single fork.
+					failed.push(failure_id(path, &name, &blockchain.network, 1));
 					flushed_writeln!("FAIL");
 					fail = true;
 					true
@@ -32,7 +32,7 @@ use machine::{
 };
 
 use test_helpers::get_temp_state;
-use ethjson;
+use ethjson::{self, spec::ForkSpec};
 use trace::{Tracer, NoopTracer, VMTracer, NoopVMTracer};
 use bytes::Bytes;
 use ethtrie;
@@ -233,6 +233,14 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for TestExt<'a, T, V, B>
 	fn sub_sstore_refund(&mut self, value: usize) {
 		self.ext.sub_sstore_refund(value)
 	}
+
+	fn is_address_warm(&self, address: &Address) -> bool {
+		self.ext.is_address_warm(address)
+	}
+
+	fn mark_address_warm(&mut self, address: Address) {
+		self.ext.mark_address_warm(address)
+	}
 }
 
 fn do_json_test<H: FnMut(&str, HookType)>(
@@ -251,7 +259,8 @@ fn do_json_test<H: FnMut(&str, HookType)>(
 		let mut fail = false;
 
 		let mut fail_unless = |cond: bool, s: &str | if !cond && !fail {
-			failed.push(format!("{}: {}", name, s));
+			// VM tests run on a frontier machine only.
+			failed.push(format!("{}: {}", failure_id(path, &name, ForkSpec::Frontier, 1), s));
 			fail = true
 		};
 
@@ -67,11 +67,10 @@ macro_rules! declare_test {
 #[cfg(test)]
 macro_rules! test {
 	($name: expr, $skip: expr) => {
-		$crate::json_tests::test_common::run_test_path(
+		$crate::json_tests::runner::run_test_path(
 			std::path::Path::new(concat!("res/ethereum/tests/", $name)),
 			&$skip,
 			do_json_test,
-			&mut |_, _| ()
 		);
 	}
 }
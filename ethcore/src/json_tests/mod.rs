@@ -21,6 +21,7 @@ mod macros;
 
 mod chain;
 mod executive;
+pub mod runner;
 mod skip;
 mod state;
 mod test_common;
@@ -0,0 +1,394 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parallel runner for the `JSON tests`.
+//!
+//! Test files of a suite run in parallel, each on its own thread. Every test within a file gets
+//! a wall-clock timeout, starting when the runner reports it through the start hook. A test which
+//! doesn't finish in time fails its file with a `Timeout` and the thread is left behind, so that
+//! a single hanging test doesn't stall the whole suite.
+//!
+//! Configured through the environment:
+//! - `JSON_TESTS_TIMEOUT`: per-test timeout in seconds (default: 600).
+//! - `JSON_TESTS_JUNIT_DIR`: if set, a JUnit XML report of each suite is written to this directory.
+
+use std::{env, fs, thread};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use super::test_common::{collect_test_files, HookType};
+
+/// Environment variable with the per-test timeout, in seconds.
+pub const TIMEOUT_ENV: &str = "JSON_TESTS_TIMEOUT";
+/// Environment variable with the directory to write JUnit XML reports to.
+pub const JUNIT_DIR_ENV: &str = "JSON_TESTS_JUNIT_DIR";
+/// Default per-test timeout.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Called by a runner when each test of a file starts and stops.
+pub type Hook = Box<dyn FnMut(&str, HookType) + Send>;
+
+/// Runs all tests contained in a single test file, returning the failures.
+pub type Runner = fn(path: &Path, json_data: &[u8], start_stop_hook: &mut Hook) -> Vec<String>;
+
+/// Runner configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+	/// Wall-clock timeout of a single test.
+	pub timeout: Duration,
+	/// Directory to write JUnit XML reports to.
+	pub junit_dir: Option<PathBuf>,
+}
+
+impl Default for Config {
+	fn default() -> Self {
+		Config {
+			timeout: DEFAULT_TIMEOUT,
+			junit_dir: None,
+		}
+	}
+}
+
+impl Config {
+	/// Read the configuration from the environment, falling back to the defaults.
+	pub fn from_env() -> Self {
+		let timeout = env::var(TIMEOUT_ENV).ok()
+			.and_then(|secs| secs.parse().ok())
+			.map_or(DEFAULT_TIMEOUT, Duration::from_secs);
+
+		Config {
+			timeout,
+			junit_dir: env::var_os(JUNIT_DIR_ENV).map(PathBuf::from),
+		}
+	}
+}
+
+/// Outcome of a single test file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+	/// All tests passed.
+	Passed,
+	/// Some tests failed.
+	Failed(Vec<String>),
+	/// The runner panicked.
+	Panicked,
+	/// The named test didn't finish within the timeout.
+	Timeout(String),
+}
+
+/// Result of a single test file.
+#[derive(Debug, Clone)]
+pub struct TestResult {
+	/// Path to the test file.
+	pub path: PathBuf,
+	/// Outcome of the tests.
+	pub outcome: Outcome,
+	/// Time spent running the tests.
+	pub duration: Duration,
+}
+
+impl TestResult {
+	/// Failure messages, empty if the tests passed.
+	pub fn failures(&self) -> Vec<String> {
+		match self.outcome {
+			Outcome::Passed => Vec::new(),
+			Outcome::Failed(ref failures) => failures.clone(),
+			Outcome::Panicked => vec![format!("{}: Panicked", self.path.display())],
+			Outcome::Timeout(ref name) => vec![format!("{}: {} | Timeout", self.path.display(), name)],
+		}
+	}
+}
+
+/// Aggregated results of a test suite.
+#[derive(Debug, Clone)]
+pub struct SuiteResult {
+	/// Name of the suite.
+	pub name: String,
+	/// Results of the test files, in the order they were collected.
+	pub results: Vec<TestResult>,
+	/// Time spent running the suite.
+	pub duration: Duration,
+}
+
+impl SuiteResult {
+	/// Failure messages of all test files in the suite.
+	pub fn failures(&self) -> Vec<String> {
+		self.results.iter().flat_map(TestResult::failures).collect()
+	}
+
+	/// Number of test files which didn't pass.
+	pub fn failed_count(&self) -> usize {
+		self.results.iter().filter(|result| result.outcome != Outcome::Passed).count()
+	}
+
+	/// Write the results as a JUnit XML report.
+	pub fn write_junit<W: Write>(&self, out: &mut W) -> io::Result<()> {
+		writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+		writeln!(
+			out,
+			r#"<testsuite name="{}" tests="{}" failures="{}" errors="0" time="{:.3}">"#,
+			escape_xml(&self.name),
+			self.results.len(),
+			self.failed_count(),
+			self.duration.as_secs_f64(),
+		)?;
+
+		for result in &self.results {
+			write!(
+				out,
+				r#"	<testcase classname="{}" name="{}" time="{:.3}""#,
+				escape_xml(&self.name),
+				escape_xml(&result.path.display().to_string()),
+				result.duration.as_secs_f64(),
+			)?;
+
+			let kind = match result.outcome {
+				Outcome::Passed => {
+					writeln!(out, "/>")?;
+					continue;
+				}
+				Outcome::Failed(_) => "Failure",
+				Outcome::Panicked => "Panic",
+				Outcome::Timeout(_) => "Timeout",
+			};
+
+			let failures = result.failures();
+			writeln!(out, ">")?;
+			writeln!(
+				out,
+				r#"		<failure type="{}" message="{}">{}</failure>"#,
+				kind,
+				escape_xml(failures.first().map_or("", String::as_str)),
+				escape_xml(&failures.join("\n")),
+			)?;
+			writeln!(out, "	</testcase>")?;
+		}
+
+		writeln!(out, "</testsuite>")
+	}
+}
+
+fn escape_xml(s: &str) -> String {
+	let mut escaped = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'&' => escaped.push_str("&amp;"),
+			'<' => escaped.push_str("&lt;"),
+			'>' => escaped.push_str("&gt;"),
+			'"' => escaped.push_str("&quot;"),
+			'\'' => escaped.push_str("&apos;"),
+			c => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// Run all test files under the given path in parallel, except for those named in the skip list.
+pub fn run_suite(path: &Path, skip: &[&'static str], runner: Runner, config: &Config) -> SuiteResult {
+	let start = Instant::now();
+	let timeout = config.timeout;
+	let results = collect_test_files(path, skip)
+		.into_par_iter()
+		.map(|test_file| run_test_file(test_file, runner, timeout))
+		.collect();
+
+	SuiteResult {
+		name: path.display().to_string(),
+		results,
+		duration: start.elapsed(),
+	}
+}
+
+enum Progress {
+	Started(String),
+	Stopped,
+	Finished(Vec<String>),
+}
+
+fn run_test_file(path: PathBuf, runner: Runner, timeout: Duration) -> TestResult {
+	let start = Instant::now();
+	let (sender, receiver) = mpsc::channel();
+	let thread_path = path.clone();
+	let spawned = thread::Builder::new()
+		.name("json-tests".into())
+		.spawn(move || {
+			let data = fs::read(&thread_path)
+				.unwrap_or_else(|e| panic!("Error reading test file at {:?}: {}", thread_path, e));
+			let hook_sender = sender.clone();
+			let mut start_stop_hook: Hook = Box::new(move |name, hook_type| {
+				let _ = hook_sender.send(match hook_type {
+					HookType::OnStart => Progress::Started(name.to_owned()),
+					HookType::OnStop => Progress::Stopped,
+				});
+			});
+			let failures = runner(&thread_path, &data, &mut start_stop_hook);
+			let _ = sender.send(Progress::Finished(failures));
+		});
+
+	let outcome = match spawned {
+		Err(e) => Outcome::Failed(vec![format!("{}: Unable to spawn test thread: {}", path.display(), e)]),
+		Ok(_) => wait_for_test_file(&path, &receiver, timeout),
+	};
+
+	TestResult {
+		path,
+		outcome,
+		duration: start.elapsed(),
+	}
+}
+
+// the timeout only runs while a test is in progress, it restarts for every test of the file.
+fn wait_for_test_file(path: &Path, receiver: &mpsc::Receiver<Progress>, timeout: Duration) -> Outcome {
+	let mut current = None;
+	loop {
+		let progress = match current {
+			Some(_) => receiver.recv_timeout(timeout),
+			None => receiver.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected),
+		};
+
+		match progress {
+			Ok(Progress::Started(name)) => current = Some(name),
+			Ok(Progress::Stopped) => current = None,
+			Ok(Progress::Finished(ref failures)) if failures.is_empty() => return Outcome::Passed,
+			Ok(Progress::Finished(failures)) => return Outcome::Failed(failures),
+			// the senders are dropped without finishing when the runner panics.
+			Err(mpsc::RecvTimeoutError::Disconnected) => return Outcome::Panicked,
+			// threads can't be killed, the test is abandoned instead.
+			Err(mpsc::RecvTimeoutError::Timeout) => {
+				let name = current.unwrap_or_default();
+				warn!(target: "json-tests", "'{}' in '{}' timed out after {:?}, abandoning it", name, path.display(), timeout);
+				return Outcome::Timeout(name);
+			}
+		}
+	}
+}
+
+fn write_junit_report(dir: &Path, suite: &SuiteResult) -> io::Result<()> {
+	let file_name: String = suite.name.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+		.collect();
+
+	fs::create_dir_all(dir)?;
+	let mut file = io::BufWriter::new(fs::File::create(dir.join(file_name + ".xml"))?);
+	suite.write_junit(&mut file)?;
+	file.flush()
+}
+
+/// Run all test files under the given path in parallel (except for the test files named in the
+/// skip list), configured through the environment, and assert that they passed.
+pub fn run_test_path(path: &Path, skip: &[&'static str], runner: Runner) {
+	if !skip.is_empty() {
+		flushed_writeln!("[run_test_path] Skipping tests in {}: {:?}", path.display(), skip);
+	}
+
+	let config = Config::from_env();
+	let suite = run_suite(path, skip, runner, &config);
+	if let Some(ref dir) = config.junit_dir {
+		if let Err(e) = write_junit_report(dir, &suite) {
+			warn!(target: "json-tests", "Unable to write JUnit report to {}: {}", dir.display(), e);
+		}
+	}
+
+	let failures = suite.failures();
+	let empty: [String; 0] = [];
+	assert_eq!(failures, empty, "\nThere were {} tests in '{}' that failed.", suite.failed_count(), path.display());
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::path::Path;
+	use std::thread;
+	use std::time::Duration;
+
+	use tempfile::TempDir;
+
+	use super::{run_suite, Config, Hook, Outcome};
+	use json_tests::HookType;
+
+	// every word of a fixture file is a test: "fail" fails, "panic" panics, "slow" hangs and
+	// "busy" takes a while, but less than the timeout used below.
+	fn fixture_runner(path: &Path, json_data: &[u8], start_stop_hook: &mut Hook) -> Vec<String> {
+		let mut failed = Vec::new();
+		for name in String::from_utf8_lossy(json_data).split_whitespace() {
+			start_stop_hook(name, HookType::OnStart);
+			match name {
+				"slow" => thread::sleep(Duration::from_secs(5)),
+				"busy" => thread::sleep(Duration::from_millis(300)),
+				"fail" => failed.push(format!("{}: {} | Istanbul | 1", path.display(), name)),
+				"panic" => panic!("fixture panicked"),
+				_ => {}
+			}
+			start_stop_hook(name, HookType::OnStop);
+		}
+		failed
+	}
+
+	fn fixture_suite() -> TempDir {
+		let tempdir = TempDir::new().unwrap();
+		for name in &["fail", "ok", "panic", "slow", "skipped"] {
+			fs::write(tempdir.path().join(format!("{}.json", name)), name.as_bytes()).unwrap();
+		}
+		fs::write(tempdir.path().join("busy.json"), b"busy busy busy").unwrap();
+		fs::write(tempdir.path().join("junk.swp"), b"fail").unwrap();
+		tempdir
+	}
+
+	#[test]
+	fn runs_suite_with_timeout() {
+		let tempdir = fixture_suite();
+		let config = Config { timeout: Duration::from_millis(500), junit_dir: None };
+		let suite = run_suite(tempdir.path(), &["skipped"], fixture_runner, &config);
+
+		let outcome = |name: &str| suite.results.iter()
+			.find(|result| result.path.file_stem().unwrap() == name)
+			.map(|result| result.outcome.clone());
+
+		let failed_path = tempdir.path().join("fail.json");
+		assert_eq!(suite.results.len(), 5);
+		assert_eq!(outcome("ok"), Some(Outcome::Passed));
+		// the file takes longer than the timeout, none of its tests does.
+		assert_eq!(outcome("busy"), Some(Outcome::Passed));
+		assert_eq!(outcome("fail"), Some(Outcome::Failed(vec![format!("{}: fail | Istanbul | 1", failed_path.display())])));
+		assert_eq!(outcome("panic"), Some(Outcome::Panicked));
+		assert_eq!(outcome("slow"), Some(Outcome::Timeout("slow".into())));
+		assert_eq!(outcome("skipped"), None);
+		assert_eq!(suite.failed_count(), 3);
+		assert!(suite.results.iter().all(|result| result.duration < Duration::from_secs(5)));
+	}
+
+	#[test]
+	fn writes_junit_report() {
+		let tempdir = fixture_suite();
+		let config = Config { timeout: Duration::from_millis(500), junit_dir: None };
+		let suite = run_suite(tempdir.path(), &["busy", "panic", "slow"], fixture_runner, &config);
+
+		let mut report = Vec::new();
+		suite.write_junit(&mut report).unwrap();
+		let report = String::from_utf8(report).unwrap();
+
+		assert!(report.starts_with(r#"<?xml version="1.0" encoding="UTF-8"?>"#));
+		assert!(report.contains(r#"tests="3" failures="1" errors="0""#));
+		assert_eq!(report.matches("<testcase ").count(), 3);
+		assert!(report.contains(r#"<failure type="Failure" message=""#));
+		assert!(report.contains("fail | Istanbul | 1</failure>"));
+		assert!(report.trim_end().ends_with("</testsuite>"));
+	}
+}
@@ -86,18 +86,18 @@ pub fn json_chain_test<H: FnMut(&str, HookType)>(path: &Path, json_data: &[u8],
 						Err(err) => {
 							println!("{} !!! Unexpected internal error: {:?}", info, err);
 							flushed_writeln!("{} fail", info);
-							failed.push(name.clone());
+							failed.push(failure_id(path, &name, &spec_name, i + 1));
 						},
 						Ok(Ok(TransactSuccess { state_root, .. })) if state_root != post_root => {
 							println!("{} !!! State mismatch (got: {}, expect: {}", info, state_root, post_root);
 							flushed_writeln!("{} fail", info);
-							failed.push(name.clone());
+							failed.push(failure_id(path, &name, &spec_name, i + 1));
 						},
 						Ok(Err(TransactErr { state_root, ref error, .. })) if state_root != post_root => {
 							println!("{} !!! State mismatch (got: {}, expect: {}", info, state_root, post_root);
 							println!("{} !!! Execution error: {:?}", info, error);
 							flushed_writeln!("{} fail", info);
-							failed.push(name.clone());
+							failed.push(failure_id(path, &name, &spec_name, i + 1));
 						},
 						Ok(Err(TransactErr { error, .. })) => {
 							flushed_writeln!("{} ok ({:?})", info, error);
@@ -15,9 +15,10 @@
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::HashSet;
+use std::fmt;
 use std::io::Read;
 use std::fs::{File, read_dir};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ffi::OsString;
 
 pub use ethereum_types::{H256, U256, Address};
@@ -31,6 +32,11 @@ pub enum HookType {
 	OnStop
 }
 
+/// Identifies a failed test by its file path, name, fork and (1-based) index within the fork.
+pub fn failure_id<F: fmt::Debug>(path: &Path, name: &str, fork: F, index: usize) -> String {
+	format!("{}: {} | {:?} | {}", path.display(), name, fork, index)
+}
+
 /// Run all tests under the given path (except for the test files named in the skip list) using the
 /// provided runner function.
 pub fn run_test_path<H: FnMut(&str, HookType)>(
@@ -45,30 +51,33 @@ pub fn run_test_path<H: FnMut(&str, HookType)>(
 		flushed_writeln!("[run_test_path] Skipping tests in {}: {:?}", path.display(), skip);
 	}
 	let mut errors = Vec::new();
-	run_test_path_inner(path, skip, runner, start_stop_hook, &mut errors);
+	for test_file in collect_test_files(path, skip) {
+		run_test_file_append(&test_file, runner, start_stop_hook, &mut errors);
+	}
 	let empty: [String; 0] = [];
 	assert_eq!(errors, empty, "\nThere were {} tests in '{}' that failed.", errors.len(), path.display());
 }
 
-fn run_test_path_inner<H: FnMut(&str, HookType)>(
-	p: &Path,
-	skip: &[&'static str],
-	runner: fn(path: &Path, json_data: &[u8], start_stop_hook: &mut H) -> Vec<String>,
-	start_stop_hook: &mut H,
-	errors: &mut Vec<String>
-) {
-	let path = Path::new(p);
-	let extension = path.extension().and_then(|s| s.to_str());
+/// Collect all test files under the given path, except for the test files named in the skip list.
+pub fn collect_test_files(path: &Path, skip: &[&'static str]) -> Vec<PathBuf> {
 	let skip_list: HashSet<OsString> = skip.iter().map(|s| {
 		let mut os: OsString = s.into();
 		os.push(".json");
 		os
 	}).collect();
 
+	let mut files = Vec::new();
+	collect_test_files_inner(path, &skip_list, &mut files);
+	files
+}
+
+fn collect_test_files_inner(path: &Path, skip_list: &HashSet<OsString>, files: &mut Vec<PathBuf>) {
+	let extension = path.extension().and_then(|s| s.to_str());
+
 	if !path.exists() {
 		panic!("Error opening test at: {:?}", path.display());
 	} else if path.is_dir() {
-		trace!(target: "json-tests", "running tests contained in '{}'", path.display());
+		trace!(target: "json-tests", "collecting tests contained in '{}'", path.display());
 		let test_files = read_dir(path)
 			.expect("Directory exists on disk")
 			.filter_map(|dir_entry| {
@@ -81,16 +90,15 @@ fn run_test_path_inner<H: FnMut(&str, HookType)>(
 				}
 			});
 		for test_file in test_files {
-			run_test_path_inner(&test_file, skip, runner, start_stop_hook, errors);
+			collect_test_files_inner(&test_file, skip_list, files);
 		}
 	} else if extension == Some("swp") || extension == None {
 		trace!(target: "json-tests", "ignoring '{}', extension {:?} – Junk?", path.display(), extension);
 		// Ignore junk
 	} else {
-		trace!(target: "json-tests", "running tests in '{}'", path.display());
-		let mut path = p.to_path_buf();
+		let mut path = path.to_path_buf();
 		path.set_extension("json");
-		run_test_file_append(&path, runner, start_stop_hook, errors)
+		files.push(path);
 	}
 }
 
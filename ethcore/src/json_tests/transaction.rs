@@ -48,7 +48,8 @@ fn do_json_test<H: FnMut(&str, HookType)>(path: &Path, json_data: &[u8], start_s
 			};
 
 			let mut fail_unless = |cond: bool, title: &str| if !cond {
-				failed.push(format!("{}-{:?}", name, spec_name));
+				// a transaction test has a single expectation per fork.
+				failed.push(format!("{}: {}", failure_id(path, &name, &spec_name, 1), title));
 				println!("Transaction failed: {:?}-{:?}: {:?}", name, spec_name, title);
 			};
 
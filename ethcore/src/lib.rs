@@ -87,7 +87,7 @@ extern crate pod;
 extern crate blooms_db;
 #[cfg(feature = "env_logger")]
 extern crate env_logger;
-#[cfg(test)]
+#[cfg(any(test, feature = "test-helpers"))]
 extern crate serde_json;
 #[cfg(any(test, feature = "tempdir"))]
 extern crate tempfile;
@@ -0,0 +1,216 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Memory of transactions failing execution during pending block assembly.
+//!
+//! Transactions that revert or run out of gas are still valid block transactions,
+//! so the pool happily offers them again for every pending block. Counting consecutive
+//! failures lets the miner push such transactions to the end of the block
+//! and eventually drop them from the pool altogether.
+
+use std::collections::HashMap;
+
+use ethereum_types::{H256, U256, Address};
+
+/// What to do with a transaction after its execution failed again.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FailureAction {
+	/// Keep including the transaction as usual.
+	Keep,
+	/// Push the transaction to the end of the pending block.
+	Deprioritize,
+	/// Drop the transaction from the pool.
+	Drop,
+}
+
+#[derive(Debug)]
+struct Entry {
+	sender: Address,
+	sender_nonce: U256,
+	failures: usize,
+	last_parent: Option<H256>,
+}
+
+/// Bounded map of consecutive execution failures by transaction hash.
+#[derive(Debug)]
+pub struct AssemblyFailures {
+	deprioritize_after: usize,
+	drop_after: usize,
+	limit: usize,
+	entries: HashMap<H256, Entry>,
+}
+
+impl AssemblyFailures {
+	/// Create a new tracker deprioritizing transactions after `deprioritize_after` consecutive failures
+	/// and dropping them after `drop_after`, remembering at most `limit` transactions.
+	pub fn new(deprioritize_after: usize, drop_after: usize, limit: usize) -> Self {
+		AssemblyFailures {
+			deprioritize_after,
+			drop_after,
+			limit,
+			entries: HashMap::new(),
+		}
+	}
+
+	/// Number of consecutive failures recorded for given transaction.
+	pub fn failures(&self, hash: &H256) -> usize {
+		self.entries.get(hash).map_or(0, |entry| entry.failures)
+	}
+
+	/// Should the transaction go to the end of the pending block?
+	pub fn is_deprioritized(&self, hash: &H256) -> bool {
+		self.failures(hash) >= self.deprioritize_after
+	}
+
+	/// Record failed execution of a transaction in a pending block built on top of `parent`.
+	///
+	/// Failures are counted at most once per parent block, no matter how many times
+	/// the pending block is rebuilt. `sender_nonce` is the nonce of the sender at the best
+	/// block; if it changed since the last failure the count starts over.
+	pub fn note_failure(&mut self, hash: H256, sender: Address, sender_nonce: U256, parent: H256) -> FailureAction {
+		if !self.entries.contains_key(&hash) && self.entries.len() >= self.limit {
+			// randomly remove half of the entries
+			let to_remove: Vec<_> = self.entries.keys().take(self.limit / 2).cloned().collect();
+			for key in to_remove {
+				self.entries.remove(&key);
+			}
+		}
+
+		let entry = self.entries.entry(hash).or_insert_with(|| Entry { sender, sender_nonce, failures: 0, last_parent: None });
+		if entry.sender_nonce != sender_nonce {
+			entry.sender_nonce = sender_nonce;
+			entry.failures = 0;
+			entry.last_parent = None;
+		}
+		if entry.last_parent != Some(parent) {
+			entry.last_parent = Some(parent);
+			entry.failures += 1;
+		}
+		let failures = entry.failures;
+
+		if failures >= self.drop_after {
+			FailureAction::Drop
+		} else if failures >= self.deprioritize_after {
+			FailureAction::Deprioritize
+		} else {
+			FailureAction::Keep
+		}
+	}
+
+	/// Forget the failures of a transaction, after it executed successfully or was dropped.
+	pub fn forget(&mut self, hash: &H256) {
+		self.entries.remove(hash);
+	}
+
+	/// Forget failures of transactions whose sender nonce changed.
+	pub fn retain_nonces<F>(&mut self, nonce: F) where
+		F: Fn(&Address) -> U256,
+	{
+		let mut nonces = HashMap::new();
+		self.entries.retain(|_, entry| {
+			let current = *nonces.entry(entry.sender).or_insert_with(|| nonce(&entry.sender));
+			current == entry.sender_nonce
+		});
+	}
+
+	/// Forget all recorded failures.
+	pub fn clear(&mut self) {
+		self.entries.clear();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn failures() -> AssemblyFailures {
+		AssemblyFailures::new(2, 4, 16)
+	}
+
+	fn parent(n: u64) -> H256 {
+		H256::from_low_u64_be(n)
+	}
+
+	#[test]
+	fn should_deprioritize_and_then_drop_after_consecutive_failures() {
+		let mut failures = failures();
+		let hash = H256::from_low_u64_be(1);
+		let sender = Address::from_low_u64_be(1);
+
+		assert_eq!(failures.note_failure(hash, sender, 0.into(), parent(1)), FailureAction::Keep);
+		assert!(!failures.is_deprioritized(&hash));
+		assert_eq!(failures.note_failure(hash, sender, 0.into(), parent(2)), FailureAction::Deprioritize);
+		assert!(failures.is_deprioritized(&hash));
+		assert_eq!(failures.note_failure(hash, sender, 0.into(), parent(3)), FailureAction::Deprioritize);
+		assert_eq!(failures.note_failure(hash, sender, 0.into(), parent(4)), FailureAction::Drop);
+		assert_eq!(failures.failures(&hash), 4);
+		failures.forget(&hash);
+		assert_eq!(failures.failures(&hash), 0);
+	}
+
+	#[test]
+	fn should_count_once_per_parent() {
+		let mut failures = failures();
+		let hash = H256::from_low_u64_be(1);
+		let sender = Address::from_low_u64_be(1);
+
+		for _ in 0..3 {
+			assert_eq!(failures.note_failure(hash, sender, 0.into(), parent(1)), FailureAction::Keep);
+		}
+		assert_eq!(failures.failures(&hash), 1);
+		assert_eq!(failures.note_failure(hash, sender, 0.into(), parent(2)), FailureAction::Deprioritize);
+	}
+
+	#[test]
+	fn should_reset_on_success() {
+		let mut failures = failures();
+		let hash = H256::from_low_u64_be(1);
+		let sender = Address::from_low_u64_be(1);
+
+		failures.note_failure(hash, sender, 0.into(), parent(1));
+		failures.note_failure(hash, sender, 0.into(), parent(2));
+		failures.forget(&hash);
+
+		assert_eq!(failures.failures(&hash), 0);
+		assert_eq!(failures.note_failure(hash, sender, 0.into(), parent(3)), FailureAction::Keep);
+	}
+
+	#[test]
+	fn should_reset_when_sender_nonce_changes() {
+		let mut failures = failures();
+		let (hash, hash2) = (H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+		let (sender, sender2) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2));
+
+		failures.note_failure(hash, sender, 0.into(), parent(1));
+		failures.note_failure(hash, sender, 0.into(), parent(2));
+		assert_eq!(failures.note_failure(hash, sender, 1.into(), parent(2)), FailureAction::Keep);
+
+		failures.note_failure(hash2, sender2, 0.into(), parent(2));
+		failures.retain_nonces(|address| if *address == sender { 2.into() } else { 0.into() });
+		assert_eq!(failures.failures(&hash), 0);
+		assert_eq!(failures.failures(&hash2), 1);
+	}
+
+	#[test]
+	fn should_stay_bounded() {
+		let mut failures = failures();
+		for i in 0..100 {
+			failures.note_failure(H256::from_low_u64_be(i), Address::from_low_u64_be(i), 0.into(), parent(1));
+		}
+
+		assert!(failures.entries.len() <= 16);
+	}
+}
@@ -22,15 +22,18 @@ use std::sync::Arc;
 use ansi_term::Colour;
 use bytes::Bytes;
 use call_contract::CallContract;
-use ethcore_miner::gas_pricer::GasPricer;
+use ethcore_miner::gas_pricer::{CalibrationStatus, GasPricer};
 use ethcore_miner::local_accounts::LocalAccounts;
-use ethcore_miner::pool::{self, TransactionQueue, VerifiedTransaction, QueueStatus, PrioritizationStrategy, TxStatus};
+use ethcore_miner::pool::{self, DropReason, NonceCap, ScoredTransaction, TransactionQueue, VerifiedTransaction, QueueStatus, PrioritizationStrategy, TxStatus};
+use ethcore_miner::pool::client::NonceClient;
 use ethcore_miner::service_transaction_checker::ServiceTransactionChecker;
+use ethcore_miner::transaction_permissioning::{TransactionPermissioning, AllowAll, ContractPermissioning};
 #[cfg(feature = "work-notify")]
 use ethcore_miner::work_notify::NotifyWork;
 use ethereum_types::{H256, U256, Address};
 use futures::sync::mpsc;
 use io::IoChannel;
+use miner::assembly_failures::{AssemblyFailures, FailureAction};
 use miner::filter_options::FilterOptions;
 use miner::pool_client::{PoolClient, CachedNonceClient, NonceCache};
 use miner::{self, MinerService};
@@ -120,6 +123,19 @@ const DEFAULT_MINIMAL_GAS_PRICE: u64 = 20_000_000_000;
 /// in case we have only a fraction of available block gas limit left.
 const MAX_SKIPPED_TRANSACTIONS: usize = 128;
 
+/// Number of consecutive execution failures in pending blocks before a transaction
+/// is pushed to the end of the block.
+///
+/// Transactions that revert or run out of gas are still valid and may be included by other miners,
+/// but there is no point trying them first in every pending block we build.
+const DEPRIORITIZE_AFTER_FAILURES: usize = 3;
+
+/// Number of consecutive execution failures in pending blocks before a transaction is dropped from the pool.
+const DROP_AFTER_FAILURES: usize = 16;
+
+/// Maximal number of transactions we remember execution failures for.
+const MAX_TRACKED_FAILURES: usize = 4096;
+
 /// Configures the behaviour of the miner.
 #[derive(Debug, PartialEq)]
 pub struct MinerOptions {
@@ -152,8 +168,13 @@ pub struct MinerOptions {
 	pub tx_queue_penalization: Penalization,
 	/// Do we want to mark transactions received locally (e.g. RPC) as local if we don't have the sending account?
 	pub tx_queue_no_unfamiliar_locals: bool,
+	/// Gas price below which external transactions are rejected at import.
+	/// Local and service transactions are accepted regardless.
+	pub tx_queue_gas_price_floor: U256,
 	/// Do we refuse to accept service transactions even if sender is certified.
 	pub refuse_service_transactions: bool,
+	/// Contract deciding which senders may submit transactions to the pool, all senders are permitted if `None`.
+	pub sender_permission_contract: Option<Address>,
 	/// Transaction pool limits.
 	pub pool_limits: pool::Options,
 	/// Initial transaction verification options.
@@ -176,7 +197,9 @@ impl Default for MinerOptions {
 			tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
 			tx_queue_penalization: Penalization::Disabled,
 			tx_queue_no_unfamiliar_locals: false,
+			tx_queue_gas_price_floor: U256::zero(),
 			refuse_service_transactions: false,
+			sender_permission_contract: None,
 			pool_limits: pool::Options {
 				max_count: 8_192,
 				max_per_sender: 81,
@@ -228,6 +251,8 @@ struct SealingWork {
 	next_mandatory_reseal: Instant,
 	// block number when sealing work was last requested
 	last_request: Option<u64>,
+	// next nonces of senders included in the last pending block
+	nonce_cap: NonceCap,
 }
 
 impl SealingWork {
@@ -258,6 +283,8 @@ pub struct Miner {
 	accounts: Arc<dyn LocalAccounts>,
 	io_channel: RwLock<Option<IoChannel<ClientIoMessage<Client>>>>,
 	service_transaction_checker: Option<ServiceTransactionChecker>,
+	transaction_permissioning: Arc<dyn TransactionPermissioning>,
+	assembly_failures: Mutex<AssemblyFailures>,
 }
 
 impl Miner {
@@ -293,7 +320,13 @@ impl Miner {
 		let verifier_options = options.pool_verification_options.clone();
 		let tx_queue_strategy = options.tx_queue_strategy;
 		let nonce_cache_size = cmp::max(4096, limits.max_count / 4);
+		let transaction_queue = TransactionQueue::new(limits, verifier_options, tx_queue_strategy);
+		transaction_queue.set_minimum_gas_price(options.tx_queue_gas_price_floor);
 		let refuse_service_transactions = options.refuse_service_transactions;
+		let transaction_permissioning: Arc<dyn TransactionPermissioning> = match options.sender_permission_contract {
+			Some(address) => Arc::new(ContractPermissioning::new(address)),
+			None => Arc::new(AllowAll),
+		};
 		let engine = spec.engine.clone();
 
 		Miner {
@@ -304,6 +337,7 @@ impl Miner {
 				next_allowed_reseal: Instant::now(),
 				next_mandatory_reseal: Instant::now() + options.reseal_max_period,
 				last_request: None,
+				nonce_cap: NonceCap::default(),
 			}),
 			params: RwLock::new(AuthoringParams::default()),
 			#[cfg(feature = "work-notify")]
@@ -311,7 +345,7 @@ impl Miner {
 			gas_pricer: Mutex::new(gas_pricer),
 			nonce_cache: NonceCache::new(nonce_cache_size),
 			options,
-			transaction_queue: Arc::new(TransactionQueue::new(limits, verifier_options, tx_queue_strategy)),
+			transaction_queue: Arc::new(transaction_queue),
 			accounts: Arc::new(accounts),
 			engine,
 			io_channel: RwLock::new(None),
@@ -320,6 +354,8 @@ impl Miner {
 			} else {
 				Some(ServiceTransactionChecker::default())
 			},
+			transaction_permissioning,
+			assembly_failures: Mutex::new(AssemblyFailures::new(DEPRIORITIZE_AFTER_FAILURES, DROP_AFTER_FAILURES, MAX_TRACKED_FAILURES)),
 		}
 	}
 
@@ -368,7 +404,9 @@ impl Miner {
 
 	/// Clear all pending block states
 	pub fn clear(&self) {
-		self.sealing.lock().queue.reset();
+		let mut sealing = self.sealing.lock();
+		sealing.queue.reset();
+		sealing.nonce_cap = NonceCap::default();
 	}
 
 	/// Updates transaction queue verification limits.
@@ -391,6 +429,11 @@ impl Miner {
 		self.service_transaction_checker.clone()
 	}
 
+	/// Current minimal gas price if it follows the fullness of recent blocks.
+	pub fn dynamic_min_gas_price(&self) -> Option<U256> {
+		self.gas_pricer.lock().dynamic_min_gas_price()
+	}
+
 	/// Retrieves an existing pending block iff it's not older than given block number.
 	///
 	/// NOTE: This will not prepare a new pending block if it's not existing.
@@ -419,6 +462,7 @@ impl Miner {
 			&*self.engine,
 			&*self.accounts,
 			self.service_transaction_checker.as_ref(),
+			&*self.transaction_permissioning,
 		)
 	}
 
@@ -487,7 +531,10 @@ impl Miner {
 		let mut invalid_transactions = HashSet::new();
 		let mut not_allowed_transactions = HashSet::new();
 		let mut senders_to_penalize = HashSet::new();
+		let mut failed_transactions = Vec::new();
+		let mut succeeded_transactions = Vec::new();
 		let block_number = open_block.header.number();
+		let parent_hash = *open_block.header.parent_hash();
 
 		let mut tx_count = 0usize;
 		let mut skipped_transactions = 0usize;
@@ -518,14 +565,34 @@ impl Miner {
 			}
 		);
 
+		// Local transactions are never dropped for failing execution, see below.
+		let local_txs: HashSet<H256> = queue_txs.iter()
+			.filter(|tx| tx.priority() == pool::Priority::Local)
+			.map(|tx| tx.signed().hash())
+			.collect();
+
+		// Transactions that kept failing execution go last, together with the following transactions of their senders.
+		let (queue_txs, deprioritized_txs) = {
+			let assembly_failures = self.assembly_failures.lock();
+			let mut deprioritized_senders = HashSet::new();
+			queue_txs.into_iter().partition::<Vec<_>, _>(|tx| {
+				let tx = tx.signed();
+				if assembly_failures.is_deprioritized(&tx.hash()) {
+					deprioritized_senders.insert(tx.sender());
+				}
+				!deprioritized_senders.contains(&tx.sender())
+			})
+		};
+
 		let took_ms = |elapsed: &Duration| {
 			elapsed.as_secs() * 1000 + elapsed.subsec_nanos() as u64 / 1_000_000
 		};
 
 		let block_start = Instant::now();
-		debug!(target: "miner", "Attempting to push {} transactions.", engine_txs.len() + queue_txs.len());
+		debug!(target: "miner", "Attempting to push {} transactions ({} deprioritized).", engine_txs.len() + queue_txs.len() + deprioritized_txs.len(), deprioritized_txs.len());
 
-		for transaction in engine_txs.into_iter().chain(queue_txs.into_iter().map(|tx| tx.signed().clone())) {
+		let queue_txs = queue_txs.into_iter().chain(deprioritized_txs.into_iter());
+		for transaction in engine_txs.into_iter().chain(queue_txs.map(|tx| tx.signed().clone())) {
 			let start = Instant::now();
 
 			let hash = transaction.hash();
@@ -535,7 +602,7 @@ impl Miner {
 			let result = client.verify_for_pending_block(&transaction, &open_block.header)
 				.map_err(|e| e.into())
 				.and_then(|_| {
-					open_block.push_transaction(transaction)
+					open_block.push_transaction_with_exception(transaction).map(|(_, exception)| exception)
 				});
 
 			let took = start.elapsed();
@@ -592,8 +659,15 @@ impl Miner {
 					);
 					invalid_transactions.insert(hash);
 				},
-				// imported ok
-				_ => tx_count += 1,
+				// imported ok, but execution might have failed (reverted or ran out of gas)
+				Ok(exception) => {
+					if exception.is_some() {
+						failed_transactions.push((hash, sender));
+					} else {
+						succeeded_transactions.push(hash);
+					}
+					tx_count += 1;
+				},
 			}
 		}
 		let elapsed = block_start.elapsed();
@@ -613,6 +687,25 @@ impl Miner {
 			self.transaction_queue.penalize(senders_to_penalize.iter());
 		}
 
+		let repeatedly_failing = {
+			let mut assembly_failures = self.assembly_failures.lock();
+			for hash in &succeeded_transactions {
+				assembly_failures.forget(hash);
+			}
+			failed_transactions.into_iter().filter_map(|(hash, sender)| {
+				match assembly_failures.note_failure(hash, sender, client.account_nonce(&sender), parent_hash) {
+					// local transactions stay in the pool, at the end of the pending block.
+					FailureAction::Drop if !local_txs.contains(&hash) => {
+						debug!(target: "txqueue", "[{:?}] Dropping transaction failing execution repeatedly.", hash);
+						assembly_failures.forget(&hash);
+						Some(hash)
+					},
+					_ => None,
+				}
+			}).collect::<Vec<_>>()
+		};
+		self.transaction_queue.drop_transactions(repeatedly_failing.iter(), DropReason::RepeatedlyFailing);
+
 		Some((block, original_work_hash))
 	}
 
@@ -673,6 +766,7 @@ impl Miner {
 			trace!(target: "miner", "Miner sleeping (current {}, last {})", best_block, sealing.last_request.unwrap_or(0));
 			sealing.enabled = false;
 			sealing.queue.reset();
+			sealing.nonce_cap = NonceCap::default();
 			false
 		} else {
 			// sealing enabled and we don't want to sleep.
@@ -769,6 +863,12 @@ impl Miner {
 				);
 				let is_new = original_work_hash.map_or(true, |h| h != block_hash);
 
+				// Update the snapshot together with the pending block, so that nonce advice
+				// never observes one without the other.
+				sealing.nonce_cap = NonceCap::new(
+					block_header.number(),
+					block.transactions.iter().map(|tx| (tx.sender(), tx.nonce)),
+				);
 				sealing.queue.set_pending(block);
 
 				#[cfg(feature = "work-notify")]
@@ -978,9 +1078,21 @@ impl miner::MinerService for Miner {
 				let error_msg = "Can't update fixed gas price while automatic gas calibration is enabled.";
 				return Err(error_msg);
 			},
+			GasPricer::MedianOfRecent { .. } => {
+				let error_msg = "Can't update fixed gas price while following the median of recent blocks.";
+				return Err(error_msg);
+			},
+			GasPricer::DynamicMinGasPrice { .. } => {
+				let error_msg = "Can't update fixed gas price while following the fullness of recent blocks.";
+				return Err(error_msg);
+			},
 		}
 	}
 
+	fn gas_price_calibration(&self) -> Option<CalibrationStatus> {
+		self.gas_pricer.lock().calibration_status()
+	}
+
 	fn import_external_transactions<C: miner::BlockChainClient>(
 		&self,
 		chain: &C,
@@ -1123,8 +1235,14 @@ impl miner::MinerService for Miner {
 			// those transactions are valid and will just be ready to be included in next block.
 			let nonce_cap = None;
 
+			// Transactions following the ones sealed in the pending block are ready, even if
+			// the sealed ones are gone from the queue already. Removing them from the queue
+			// invalidates the cached pending set, so it's recomputed with the current snapshot.
+			// The sealing lock is held until the pending set is computed, so that a new pending
+			// block can't replace the snapshot in the meantime (same lock order as `requires_reseal`).
+			let sealing = self.sealing.lock();
 			let mut pending = self.transaction_queue.pending(
-				CachedNonceClient::new(chain, &self.nonce_cache),
+				sealing.nonce_cap.client(CachedNonceClient::new(chain, &self.nonce_cache)),
 				pool::PendingSettings {
 					block_number: chain_info.best_block_number,
 					current_timestamp: chain_info.best_block_timestamp,
@@ -1133,6 +1251,7 @@ impl miner::MinerService for Miner {
 					ordering,
 				},
 			);
+			drop(sealing);
 
 			pending.retain(|tx| {
 				filter.as_ref().map_or(true, |filter| {
@@ -1175,8 +1294,13 @@ impl miner::MinerService for Miner {
 	fn next_nonce<C>(&self, chain: &C, address: &Address) -> U256 where
 		C: Nonce + Sync,
 	{
-		self.transaction_queue.next_nonce(CachedNonceClient::new(chain, &self.nonce_cache), address)
-			.unwrap_or_else(|| chain.latest_nonce(address))
+		// Transactions in the pending block might not be in the queue anymore,
+		// so take them into account. The lock is held while querying the queue
+		// to prevent the pending block from being replaced in the meantime.
+		let sealing = self.sealing.lock();
+		let client = sealing.nonce_cap.client(CachedNonceClient::new(chain, &self.nonce_cache));
+		self.transaction_queue.next_nonce(client.clone(), address)
+			.unwrap_or_else(|| client.account_nonce(address))
 	}
 
 	fn transaction(&self, hash: &H256) -> Option<Arc<VerifiedTransaction>> {
@@ -1193,6 +1317,17 @@ impl miner::MinerService for Miner {
 		self.transaction_queue.status()
 	}
 
+	fn queue_status_grouped<C>(&self, chain: &C) -> Vec<pool::SenderStatus> where
+		C: Nonce + Sync,
+	{
+		let sealing = self.sealing.lock();
+		self.transaction_queue.queue_status_grouped(sealing.nonce_cap.client(CachedNonceClient::new(chain, &self.nonce_cache)))
+	}
+
+	fn rejected_transactions(&self) -> Vec<pool::RejectedTransaction> {
+		self.transaction_queue.rejected_transactions()
+	}
+
 	fn pending_receipts(&self, best_block: BlockNumber) -> Option<Vec<RichReceipt>> {
 		self.map_existing_pending_block(|pending| {
 			let receipts = &pending.receipts;
@@ -1357,6 +1492,32 @@ impl miner::MinerService for Miner {
 		if has_new_best_block {
 			// Clear nonce cache
 			self.nonce_cache.clear();
+			// Pending block snapshot is superseded by the new state
+			self.sealing.lock().nonce_cap = NonceCap::default();
+
+			// Feed gas prices of new blocks to the gas pricer
+			let mut gas_pricer = self.gas_pricer.lock();
+			match *gas_pricer {
+				GasPricer::MedianOfRecent { .. } => {
+					for hash in retracted {
+						gas_pricer.note_retracted(hash);
+					}
+					for hash in enacted {
+						if let Some(block) = chain.block(BlockId::Hash(*hash)) {
+							gas_pricer.note_block(*hash, block.transaction_views().iter().map(|t| t.gas_price()).collect());
+						}
+					}
+				},
+				GasPricer::DynamicMinGasPrice { .. } => for hash in enacted {
+					if let Some(header) = chain.block_header(BlockId::Hash(*hash)) {
+						if let Some(gas_price) = gas_pricer.note_block_fullness(header.gas_used(), header.gas_limit()) {
+							info!(target: "miner", "Block #{} is {}% full, minimal gas price is now {} wei",
+								header.number(), (header.gas_used() * 100 / cmp::max(header.gas_limit(), 1.into())).low_u64(), gas_price);
+						}
+					}
+				},
+				_ => {},
+			}
 		}
 
 		// First update gas limit in transaction queue and minimal gas price.
@@ -1365,6 +1526,15 @@ impl miner::MinerService for Miner {
 
 		// Then import all transactions from retracted blocks.
 		let client = self.pool_client(chain);
+
+		// Execution failures are only meaningful against the state they happened in,
+		// so forget them on reorgs and when the sender's nonce changes.
+		if !retracted.is_empty() {
+			self.assembly_failures.lock().clear();
+		} else if has_new_best_block {
+			self.assembly_failures.lock().retain_nonces(|sender| client.account_nonce(sender));
+		}
+
 		{
 			retracted
 				.par_iter()
@@ -1410,6 +1580,7 @@ impl miner::MinerService for Miner {
 				let engine = self.engine.clone();
 				let accounts = self.accounts.clone();
 				let service_transaction_checker = self.service_transaction_checker.clone();
+				let transaction_permissioning = self.transaction_permissioning.clone();
 				let cull = move |chain: &Client| {
 					let client = PoolClient::new(
 						chain,
@@ -1417,6 +1588,7 @@ impl miner::MinerService for Miner {
 						&*engine,
 						&*accounts,
 						service_transaction_checker.as_ref(),
+						&*transaction_permissioning,
 					);
 					queue.cull(client);
 					if engine.should_reseal_on_update() {
@@ -1529,6 +1701,10 @@ mod tests {
 	}
 
 	fn miner() -> Miner {
+		miner_with_spec(&spec::new_test())
+	}
+
+	fn miner_with_spec(spec: &Spec) -> Miner {
 		Miner::new(
 			MinerOptions {
 				force_sealing: false,
@@ -1544,7 +1720,9 @@ mod tests {
 				tx_queue_penalization: Penalization::Disabled,
 				tx_queue_strategy: PrioritizationStrategy::GasPriceOnly,
 				tx_queue_no_unfamiliar_locals: false,
+				tx_queue_gas_price_floor: 0.into(),
 				refuse_service_transactions: false,
+				sender_permission_contract: None,
 				pool_limits: Default::default(),
 				pool_verification_options: pool::verifier::Options {
 					minimal_gas_price: 0.into(),
@@ -1554,7 +1732,7 @@ mod tests {
 				},
 			},
 			GasPricer::new_fixed(0u64.into()),
-			&spec::new_test(),
+			spec,
 			::std::collections::HashSet::new(), // local accounts
 		)
 	}
@@ -1577,6 +1755,139 @@ mod tests {
 		}.sign(keypair.secret(), Some(chain_id))
 	}
 
+	#[test]
+	fn should_reject_transactions_of_senders_not_permitted_by_contract() {
+		use std::str::FromStr;
+		use parity_crypto::publickey::Secret;
+		use test_helpers::generate_dummy_client_with_spec_and_data;
+
+		// The contract at 0x05 permits a sender while the block number is below
+		// the value stored under the sender's address:
+		// 0x7e5f.. (secret 1) until block 1, 0x2b5a.. (secret 2) until block 16, others never.
+		fn spec() -> Spec {
+			let tempdir = ::std::env::temp_dir();
+			Spec::load(&tempdir, &include_bytes!("../../res/sender_permissioning.json")[..]).unwrap()
+		}
+
+		fn permissioned_transaction(secret: u64, nonce: u64) -> SignedTransaction {
+			let secret = Secret::from_str(&format!("{:064x}", secret)).unwrap();
+			Transaction {
+				action: Action::Call(Address::from_low_u64_be(0xff)),
+				value: U256::zero(),
+				data: vec![],
+				gas: U256::from(100_000),
+				gas_price: U256::zero(),
+				nonce: nonce.into(),
+			}.sign(&secret, Some(spec().chain_id()))
+		}
+
+		// given
+		let genesis_client = generate_dummy_client_with_spec(spec);
+		let client = generate_dummy_client_with_spec_and_data(spec, 1, 0, &[], false);
+		let miner = Miner::new(
+			MinerOptions {
+				sender_permission_contract: Some(Address::from_low_u64_be(5)),
+				..miner().options
+			},
+			GasPricer::new_fixed(0u64.into()),
+			&spec(),
+			HashSet::new(),
+		);
+
+		// when
+		let res = miner.import_external_transactions(&*genesis_client, vec![
+			permissioned_transaction(1, 0).into(),
+			permissioned_transaction(3, 0).into(),
+		]);
+
+		// then
+		assert_eq!(res, vec![Ok(()), Err(transaction::Error::SenderNotPermitted)]);
+
+		// when
+		let res = miner.import_external_transactions(&*client, vec![
+			permissioned_transaction(1, 1).into(),
+			permissioned_transaction(2, 0).into(),
+		]);
+
+		// then
+		assert_eq!(res, vec![Err(transaction::Error::SenderNotPermitted), Ok(())]);
+
+		// local transactions are checked as well
+		let res = miner.import_own_transaction(&*client, PendingTransaction::new(permissioned_transaction(3, 1), None));
+		assert_eq!(res, Err(transaction::Error::SenderNotPermitted));
+	}
+
+	fn reverting_transaction(chain_id: u64) -> SignedTransaction {
+		// contract creation that always reverts (PUSH1 0 PUSH1 0 REVERT)
+		Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: "60006000fd".from_hex().unwrap(),
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero(),
+		}.sign(Random.generate().secret(), Some(chain_id))
+	}
+
+	#[test]
+	fn should_deprioritize_and_eventually_drop_repeatedly_failing_transactions() {
+		// given
+		let client = TestBlockChainClient::new_with_spec(spec::new_byzantium_test());
+		let miner = miner_with_spec(&spec::new_byzantium_test());
+		let chain_id = 1;
+		let failing = reverting_transaction(chain_id);
+		let succeeding = transaction_with_chain_id(chain_id);
+		let (failing_hash, failing_sender, succeeding_hash) = (failing.hash(), failing.sender(), succeeding.hash());
+		let res = miner.import_external_transactions(&client, vec![failing.into(), succeeding.into()]);
+		assert_eq!(res, vec![Ok(()), Ok(())]);
+		let pending_hashes = |miner: &Miner| {
+			let (block, _) = miner.prepare_block(&client).unwrap();
+			block.transactions.iter().map(|tx| tx.hash()).collect::<Vec<_>>()
+		};
+		// the test client always builds on the genesis block, so simulate failures on other parents.
+		let fail_on_other_parents = |miner: &Miner, count: usize| {
+			for parent in 0..count {
+				miner.assembly_failures.lock().note_failure(failing_hash, failing_sender, 0.into(), H256::from_low_u64_be(parent as u64 + 1));
+			}
+		};
+
+		// when
+		for _ in 0..3 {
+			assert_eq!(pending_hashes(&miner), vec![failing_hash, succeeding_hash]);
+		}
+		assert_eq!(miner.assembly_failures.lock().failures(&failing_hash), 1);
+		fail_on_other_parents(&miner, DEPRIORITIZE_AFTER_FAILURES - 1);
+
+		// then
+		assert_eq!(pending_hashes(&miner), vec![succeeding_hash, failing_hash]);
+		fail_on_other_parents(&miner, DROP_AFTER_FAILURES - DEPRIORITIZE_AFTER_FAILURES - 2);
+		assert_eq!(pending_hashes(&miner), vec![succeeding_hash, failing_hash]);
+		assert_eq!(pending_hashes(&miner), vec![succeeding_hash]);
+		assert_eq!(miner.queue_status().status.transaction_count, 1);
+		assert_eq!(miner.transaction_queue.eviction_stats().get(&pool::EvictReason::RepeatedlyFailing), Some(&1));
+	}
+
+	#[test]
+	fn should_not_drop_repeatedly_failing_local_transactions() {
+		// given
+		let client = TestBlockChainClient::new_with_spec(spec::new_byzantium_test());
+		let miner = miner_with_spec(&spec::new_byzantium_test());
+		let failing = reverting_transaction(1);
+		let (hash, sender) = (failing.hash(), failing.sender());
+		let res = miner.import_own_transaction(&client, PendingTransaction::new(failing, None));
+		assert_eq!(res, Ok(()));
+
+		// when
+		for parent in 0..DROP_AFTER_FAILURES {
+			miner.assembly_failures.lock().note_failure(hash, sender, 0.into(), H256::from_low_u64_be(parent as u64 + 1));
+		}
+		let (block, _) = miner.prepare_block(&client).unwrap();
+
+		// then
+		assert_eq!(block.transactions.iter().map(|tx| tx.hash()).collect::<Vec<_>>(), vec![hash]);
+		assert_eq!(miner.queue_status().status.transaction_count, 1);
+	}
+
 	#[test]
 	fn should_make_pending_block_when_importing_own_transaction() {
 		// given
@@ -1623,6 +1934,30 @@ mod tests {
 		assert_eq!(miner.prepare_pending_block(&*client), BlockPreparationStatus::NotPrepared);
 	}
 
+	#[test]
+	fn should_not_advise_nonce_of_transaction_in_pending_block() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let transaction = transaction();
+		let sender = transaction.sender();
+		let hash = transaction.hash();
+		let res = miner.import_own_transaction(&client, PendingTransaction::new(transaction, None));
+		assert_eq!(res.unwrap(), ());
+		assert_eq!(miner.pending_transactions(0).unwrap().len(), 1);
+
+		// when
+		// transaction is gone from the queue, but it's still being sealed.
+		assert!(miner.remove_transaction(&hash).is_some());
+
+		// then
+		assert_eq!(miner.next_nonce(&client, &sender), U256::from(1));
+
+		// and once the pending block is dropped the state nonce is used again.
+		miner.clear();
+		assert_eq!(miner.next_nonce(&client, &sender), U256::zero());
+	}
+
 	#[test]
 	fn should_not_use_pending_block_if_best_block_is_higher() {
 		// given
@@ -1640,6 +1975,30 @@ mod tests {
 		assert_eq!(miner.ready_transactions(&client, 10, PendingOrdering::Priority).len(), 1);
 	}
 
+	#[test]
+	fn should_enforce_gas_price_floor_on_external_transactions_only() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = Miner::new(
+			MinerOptions {
+				tx_queue_gas_price_floor: 1.into(),
+				..miner().options
+			},
+			GasPricer::new_fixed(0u64.into()),
+			&spec::new_test(),
+			::std::collections::HashSet::new(),
+		);
+
+		// when
+		let external = miner.import_external_transactions(&client, vec![transaction().into()]).pop().unwrap();
+		let own = miner.import_own_transaction(&client, PendingTransaction::new(transaction(), None));
+
+		// then
+		assert_eq!(external, Err(transaction::Error::GasPriceBelowFloor { floor: 1.into(), got: 0.into() }));
+		assert_eq!(own, Ok(()));
+		assert_eq!(miner.queue_status().status.transaction_count, 1);
+	}
+
 	#[test]
 	fn should_import_external_transaction() {
 		// given
@@ -1901,6 +2260,8 @@ mod tests {
 				GasPriceCalibratorOptions {
 					usd_per_tx: 0.0,
 					recalibration_period: Duration::from_secs(0),
+					stale_after: None,
+					fallback_gas_price: 0.into(),
 				},
 				fetch,
 				p,
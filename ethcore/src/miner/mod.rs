@@ -21,6 +21,7 @@
 
 mod miner;
 mod filter_options;
+mod assembly_failures;
 pub mod pool_client;
 #[cfg(feature = "stratum")]
 pub mod stratum;
@@ -34,7 +35,8 @@ use std::sync::Arc;
 use std::collections::{BTreeSet, BTreeMap};
 
 use bytes::Bytes;
-use ethcore_miner::pool::{VerifiedTransaction, QueueStatus, local_transactions};
+use ethcore_miner::gas_pricer::CalibrationStatus;
+use ethcore_miner::pool::{VerifiedTransaction, QueueStatus, RejectedTransaction, SenderStatus, local_transactions};
 use ethereum_types::{H256, U256, Address};
 use types::transaction::{self, UnverifiedTransaction, SignedTransaction, PendingTransaction};
 use types::{
@@ -210,6 +212,13 @@ pub trait MinerService : Send + Sync {
 	/// Status includes verification thresholds and current pool utilization and limits.
 	fn queue_status(&self) -> QueueStatus;
 
+	/// Get all transactions in the pool grouped by sender, along with senders' state nonces.
+	fn queue_status_grouped<C>(&self, chain: &C) -> Vec<SenderStatus>
+		where C: Nonce + Sync;
+
+	/// Get the most recently rejected transactions, oldest first.
+	fn rejected_transactions(&self) -> Vec<RejectedTransaction>;
+
 	// Misc
 
 	/// Suggested gas price.
@@ -221,4 +230,7 @@ pub trait MinerService : Send + Sync {
 	/// Set a new minimum gas limit.
 	/// Will not work if dynamic gas calibration is set.
 	fn set_minimal_gas_price(&self, gas_price: U256) -> Result<bool, &str>;
+
+	/// Last calibration and price source health, `None` unless the gas price is calibrated.
+	fn gas_price_calibration(&self) -> Option<CalibrationStatus>;
 }
@@ -27,6 +27,7 @@ use ethcore_miner::local_accounts::LocalAccounts;
 use ethcore_miner::pool;
 use ethcore_miner::pool::client::NonceClient;
 use ethcore_miner::service_transaction_checker::ServiceTransactionChecker;
+use ethcore_miner::transaction_permissioning::TransactionPermissioning;
 use types::transaction::{
 	self,
 	UnverifiedTransaction,
@@ -79,6 +80,7 @@ pub struct PoolClient<'a, C: 'a> {
 	accounts: &'a dyn LocalAccounts,
 	best_block_header: Header,
 	service_transaction_checker: Option<&'a ServiceTransactionChecker>,
+	transaction_permissioning: &'a dyn TransactionPermissioning,
 }
 
 impl<'a, C: 'a> Clone for PoolClient<'a, C> {
@@ -90,6 +92,7 @@ impl<'a, C: 'a> Clone for PoolClient<'a, C> {
 			accounts: self.accounts.clone(),
 			best_block_header: self.best_block_header.clone(),
 			service_transaction_checker: self.service_transaction_checker.clone(),
+			transaction_permissioning: self.transaction_permissioning,
 		}
 	}
 }
@@ -97,13 +100,15 @@ impl<'a, C: 'a> Clone for PoolClient<'a, C> {
 impl<'a, C: 'a> PoolClient<'a, C> where
 	C: BlockInfo + CallContract,
 {
-	/// Creates new client given chain, nonce cache, accounts and service transaction verifier.
+	/// Creates new client given chain, nonce cache, accounts, service transaction verifier
+	/// and sender permissioning.
 	pub fn new(
 		chain: &'a C,
 		cache: &'a NonceCache,
 		engine: &'a dyn Engine,
 		accounts: &'a dyn LocalAccounts,
 		service_transaction_checker: Option<&'a ServiceTransactionChecker>,
+		transaction_permissioning: &'a dyn TransactionPermissioning,
 	) -> Self {
 		let best_block_header = chain.best_block_header();
 		PoolClient {
@@ -113,6 +118,7 @@ impl<'a, C: 'a> PoolClient<'a, C> where
 			accounts,
 			best_block_header,
 			service_transaction_checker,
+			transaction_permissioning,
 		}
 	}
 
@@ -152,6 +158,13 @@ impl<'a, C: 'a> pool::client::Client for PoolClient<'a, C> where
 		Ok(tx)
 	}
 
+	fn verify_sender_permitted(&self, tx: &SignedTransaction) -> Result<(), transaction::Error> {
+		match self.transaction_permissioning.is_permitted(self.chain, tx, &tx.sender(), &self.best_block_header) {
+			true => Ok(()),
+			false => Err(transaction::Error::SenderNotPermitted),
+		}
+	}
+
 	fn account_details(&self, address: &Address) -> pool::client::AccountDetails {
 		pool::client::AccountDetails {
 			nonce: self.cached_nonces.account_nonce(address),
@@ -0,0 +1,353 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Programmatic construction of test chains.
+//!
+//! `ChainBuilder` assembles a spec from a handful of knobs (engine, prefunded accounts,
+//! `CommonParams` overrides, genesis gas limit) and starts a `Client` on top of it.
+//! `BlockBuilder` then appends blocks to that client, filling in sender nonces.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ethereum_types::{H256, U256, Address};
+use hash::keccak;
+use io::IoChannel;
+use parity_crypto::publickey::KeyPair;
+use serde_json::{self, json, Map, Value};
+use types::transaction::{Action, Transaction, SignedTransaction};
+
+use client::{Client, ClientConfig, PrepareOpenBlock, ImportSealedBlock};
+use client_traits::Nonce;
+use miner::Miner;
+use spec::Spec;
+
+use super::new_db;
+
+/// Consensus engine of a chain built by `ChainBuilder`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestEngine {
+	/// `NullEngine`: no seal, no rewards.
+	Null,
+	/// `InstantSeal`: every block is sealed as soon as it is prepared.
+	InstantSeal,
+}
+
+/// Builder for a test `Spec` and a `Client` running it.
+///
+/// Defaults mirror `spec::new_null()`: null engine, network id 2 and a genesis gas limit of
+/// `0x2fefd8`, with the four original builtins deployed.
+pub struct ChainBuilder {
+	engine: TestEngine,
+	params: Map<String, Value>,
+	gas_limit: U256,
+	balances: Vec<U256>,
+	client_config: ClientConfig,
+}
+
+impl Default for ChainBuilder {
+	fn default() -> Self {
+		let mut params = Map::new();
+		params.insert("gasLimitBoundDivisor".into(), "0x0400".into());
+		params.insert("accountStartNonce".into(), "0x0".into());
+		params.insert("maximumExtraDataSize".into(), "0x20".into());
+		params.insert("minGasLimit".into(), "0x1388".into());
+		params.insert("networkID".into(), "0x2".into());
+
+		ChainBuilder {
+			engine: TestEngine::Null,
+			params,
+			gas_limit: 0x2fefd8.into(),
+			balances: Vec::new(),
+			client_config: ClientConfig::default(),
+		}
+	}
+}
+
+impl ChainBuilder {
+	/// Create a builder with the default settings.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Select the consensus engine.
+	pub fn engine(mut self, engine: TestEngine) -> Self {
+		self.engine = engine;
+		self
+	}
+
+	/// Add an account holding `balance` at genesis. Accounts are indexed in the order they
+	/// are added; see `account_keypair`.
+	pub fn prefunded_account<B: Into<U256>>(mut self, balance: B) -> Self {
+		self.balances.push(balance.into());
+		self
+	}
+
+	/// Set a raw `params` entry of the spec, e.g. `("maxCodeSize", 24576.into())`.
+	pub fn param<V: Into<Value>>(mut self, key: &str, value: V) -> Self {
+		self.params.insert(key.into(), value.into());
+		self
+	}
+
+	/// Activate `eip` (e.g. `"eip1283"`) from block `number` onwards.
+	pub fn transition(self, eip: &str, number: u64) -> Self {
+		let key = format!("{}Transition", eip);
+		self.param(&key, format!("{:#x}", number))
+	}
+
+	/// Set the gas limit of the genesis block.
+	pub fn gas_limit<G: Into<U256>>(mut self, gas_limit: G) -> Self {
+		self.gas_limit = gas_limit.into();
+		self
+	}
+
+	/// Use `config` when starting the client.
+	pub fn client_config(mut self, config: ClientConfig) -> Self {
+		self.client_config = config;
+		self
+	}
+
+	/// Build the spec described by this builder.
+	pub fn spec(&self) -> Spec {
+		let (engine, seal) = match self.engine {
+			TestEngine::Null => (
+				json!({ "null": { "params": {} } }),
+				json!({ "ethereum": {
+					"nonce": "0x00006d6f7264656e",
+					"mixHash": "0x00000000000000000000000000000000000000647572616c65787365646c6578"
+				} }),
+			),
+			TestEngine::InstantSeal => (
+				json!({ "instantSeal": { "params": {} } }),
+				json!({ "generic": "0x0" }),
+			),
+		};
+
+		let mut accounts = Map::new();
+		for (n, name, base, word) in &[
+			(1u64, "ecrecover", 3000, 0),
+			(2, "sha256", 60, 12),
+			(3, "ripemd160", 600, 120),
+			(4, "identity", 15, 3),
+		] {
+			accounts.insert(format!("{:#x}", Address::from_low_u64_be(*n)), json!({
+				"balance": "1",
+				"builtin": { "name": name, "pricing": { "linear": { "base": base, "word": word } } }
+			}));
+		}
+		for (index, balance) in self.balances.iter().enumerate() {
+			accounts.insert(
+				format!("{:#x}", account_keypair(index).address()),
+				json!({ "balance": format!("{:#x}", balance) }),
+			);
+		}
+
+		let spec = json!({
+			"name": "ChainBuilder",
+			"engine": engine,
+			"params": self.params,
+			"genesis": {
+				"seal": seal,
+				"difficulty": "0x20000",
+				"author": "0x0000000000000000000000000000000000000000",
+				"timestamp": "0x00",
+				"parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+				"extraData": "0x",
+				"gasLimit": format!("{:#x}", self.gas_limit)
+			},
+			"accounts": accounts
+		});
+
+		let json = serde_json::to_vec(&spec).expect("serializing a json::Value never fails; qed");
+		Spec::load(&::std::env::temp_dir(), &*json).expect("ChainBuilder generates a valid spec; qed")
+	}
+
+	/// Build the spec and start a client on a fresh database.
+	pub fn build(self) -> TestChain {
+		let spec = self.spec();
+		let client = Client::new(
+			self.client_config,
+			&spec,
+			new_db(),
+			Arc::new(Miner::new_for_tests(&spec, None)),
+			IoChannel::disconnected(),
+		).unwrap();
+		let accounts = (0..self.balances.len()).map(account_keypair).collect();
+
+		TestChain { spec, client, accounts }
+	}
+}
+
+/// Deterministic key pair of the `index`th account funded by `ChainBuilder::prefunded_account`.
+pub fn account_keypair(index: usize) -> KeyPair {
+	let secret = keccak(format!("chain-builder-account-{}", index));
+	KeyPair::from_secret_slice(secret.as_bytes()).expect("keccak output is a valid secret; qed")
+}
+
+/// A started client together with the spec it runs and its prefunded accounts.
+pub struct TestChain {
+	/// Spec the client was started with.
+	pub spec: Spec,
+	/// The client.
+	pub client: Arc<Client>,
+	/// Key pairs of the prefunded accounts, in the order they were added.
+	pub accounts: Vec<KeyPair>,
+}
+
+impl TestChain {
+	/// Start a new block on top of the current best block.
+	pub fn block(&self) -> BlockBuilder {
+		BlockBuilder {
+			chain: self,
+			transactions: Vec::new(),
+			nonces: HashMap::new(),
+		}
+	}
+
+	/// Append `count` empty blocks and return their hashes.
+	pub fn push_empty_blocks(&self, count: usize) -> Vec<H256> {
+		(0..count).map(|_| self.block().push()).collect()
+	}
+}
+
+/// Builder for a single block appended to a `TestChain`.
+pub struct BlockBuilder<'a> {
+	chain: &'a TestChain,
+	transactions: Vec<SignedTransaction>,
+	nonces: HashMap<Address, U256>,
+}
+
+impl<'a> BlockBuilder<'a> {
+	/// Include `transaction` sent by the `sender`th prefunded account. Its nonce is
+	/// replaced with the sender's next one, taking earlier transactions in this block into account.
+	pub fn transaction(mut self, sender: usize, mut transaction: Transaction) -> Self {
+		let chain = self.chain;
+		let keypair = &chain.accounts[sender];
+		let nonce = self.nonces.entry(keypair.address())
+			.or_insert_with(|| chain.client.latest_nonce(&keypair.address()));
+
+		transaction.nonce = *nonce;
+		*nonce += U256::one();
+		self.transactions.push(transaction.sign(keypair.secret(), Some(chain.spec.chain_id())));
+		self
+	}
+
+	/// Include a zero gas price transfer of `value` from the `sender`th prefunded account to `to`.
+	pub fn transfer<V: Into<U256>>(self, sender: usize, to: Address, value: V) -> Self {
+		self.transaction(sender, Transaction {
+			nonce: U256::zero(),
+			gas_price: U256::zero(),
+			gas: 21000.into(),
+			action: Action::Call(to),
+			value: value.into(),
+			data: Vec::new(),
+		})
+	}
+
+	/// Seal the block, import it and return its hash.
+	pub fn push(self) -> H256 {
+		let chain = self.chain;
+		let gas_limit = *chain.spec.genesis_header().gas_limit();
+		let mut block = chain.client.prepare_open_block(Address::zero(), (gas_limit, gas_limit), Vec::new()).unwrap();
+		for transaction in self.transactions {
+			block.push_transaction(transaction).unwrap();
+		}
+		let block = block.close_and_lock().unwrap().seal(&*chain.spec.engine, Vec::new()).unwrap();
+		chain.client.import_sealed_block(block).unwrap()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use client_traits::{Balance, BlockChainClient, ChainInfo};
+	use types::ids::BlockId;
+
+	#[test]
+	fn defaults_match_null_spec() {
+		let spec = ChainBuilder::new().spec();
+
+		assert_eq!(spec.engine.name(), "NullEngine");
+		assert_eq!(spec.network_id(), 2);
+		assert_eq!(spec.params().account_start_nonce, U256::zero());
+		assert_eq!(*spec.genesis_header().gas_limit(), 0x2fefd8.into());
+		assert_eq!(spec.genesis_state.get().len(), 4);
+	}
+
+	#[test]
+	fn applies_overrides() {
+		let chain = ChainBuilder::new()
+			.engine(TestEngine::InstantSeal)
+			.prefunded_account(1_000)
+			.prefunded_account(U256::exp10(24))
+			.param("networkID", "0x11")
+			.param("maxCodeSize", 24576)
+			.transition("eip150", 5)
+			.gas_limit(8_000_000)
+			.build();
+
+		assert_eq!(chain.spec.engine.name(), "InstantSeal");
+		assert_eq!(chain.spec.network_id(), 0x11);
+		assert_eq!(chain.spec.params().max_code_size, 24576);
+		assert_eq!(chain.spec.params().eip150_transition, 5);
+		assert_eq!(*chain.spec.genesis_header().gas_limit(), 8_000_000.into());
+
+		assert_eq!(chain.client.chain_info().best_block_number, 0);
+		assert_eq!(chain.accounts.len(), 2);
+		assert_eq!(chain.client.latest_balance(&chain.accounts[0].address()), 1_000.into());
+		assert_eq!(chain.client.latest_balance(&chain.accounts[1].address()), U256::exp10(24));
+	}
+
+	#[test]
+	fn prefunded_accounts_are_deterministic() {
+		let first = ChainBuilder::new().prefunded_account(1).build();
+		let second = ChainBuilder::new().prefunded_account(1).build();
+
+		assert_eq!(first.accounts[0].address(), second.accounts[0].address());
+		assert_eq!(first.spec.genesis_header().hash(), second.spec.genesis_header().hash());
+	}
+
+	#[test]
+	fn block_builder_manages_nonces() {
+		let chain = ChainBuilder::new()
+			.prefunded_account(100)
+			.prefunded_account(100)
+			.build();
+		let recipient = Address::from_low_u64_be(0x1234);
+
+		let first = chain.block()
+			.transfer(0, recipient, 1)
+			.transfer(0, recipient, 2)
+			.transfer(1, recipient, 3)
+			.push();
+		chain.block().transfer(0, recipient, 4).push();
+
+		assert_eq!(chain.client.chain_info().best_block_number, 2);
+		assert_eq!(chain.client.block_body(BlockId::Hash(first)).unwrap().transactions_count(), 3);
+		assert_eq!(chain.client.latest_nonce(&chain.accounts[0].address()), 3.into());
+		assert_eq!(chain.client.latest_nonce(&chain.accounts[1].address()), 1.into());
+		assert_eq!(chain.client.latest_balance(&recipient), 10.into());
+	}
+
+	#[test]
+	fn pushes_empty_blocks() {
+		let chain = ChainBuilder::new().build();
+		let hashes = chain.push_empty_blocks(3);
+
+		assert_eq!(hashes.len(), 3);
+		assert_eq!(chain.client.chain_info().best_block_hash, hashes[2]);
+	}
+}
@@ -164,6 +164,7 @@ impl<'a> EvmTestClient<'a> {
 			vm: trie_vm_factories::VmFactory::new(5 * 1024),
 			trie: trie::TrieFactory::new(trie_spec, ethtrie::Layout),
 			accountdb: Default::default(),
+			preimages: None,
 		}
 	}
 
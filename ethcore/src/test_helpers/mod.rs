@@ -18,6 +18,7 @@
 
 extern crate tempfile;
 
+mod chain_builder;
 mod test_client;
 mod evm_test_client;
 
@@ -28,6 +29,8 @@ pub use trie::TrieSpec;
 /// Re-export for tests only
 pub use self::test_client::{TestBlockChainClient, EachBlockWith, TestState};
 /// Re-export for tests only
+pub use self::chain_builder::{ChainBuilder, TestChain, TestEngine, BlockBuilder, account_keypair};
+/// Re-export for tests only
 pub use self::evm_test_client::{EvmTestClient, EvmTestError, TransactErr, TransactSuccess};
 
 use std::path::Path;
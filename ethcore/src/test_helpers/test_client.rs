@@ -16,13 +16,14 @@
 
 //! Test client.
 
+use std::cmp;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrder};
 use std::sync::Arc;
 use std::collections::{HashMap, BTreeMap};
 use blockchain::BlockProvider;
 
-use blockchain::{TreeRoute, BlockReceipts};
+use blockchain::{TreeRoute, BlockReceipts, CacheReport};
 use bytes::Bytes;
 use db::{NUM_COLUMNS, COL_STATE};
 use ethcore_miner::pool::VerifiedTransaction;
@@ -39,7 +40,7 @@ use rustc_hex::FromHex;
 use types::{
 	BlockNumber,
 	encoded,
-	engines::epoch::Transition as EpochTransition,
+	engines::{epoch::Transition as EpochTransition, params::ForkActivation},
 	ids::{BlockId, TransactionId, UncleId, TraceId},
 	basic_account::BasicAccount,
 	errors::{EthcoreError as Error, EthcoreResult},
@@ -54,7 +55,7 @@ use types::{
 	view,
 	views::BlockView,
 	verification::Unverified,
-	client_types::{Mode, StateResult},
+	client_types::{ExecutionProof, Mode, ProveError, StateResult},
 	blockchain_info::BlockChainInfo,
 	block_status::BlockStatus,
 	verification::VerificationQueueInfo as BlockQueueInfo,
@@ -104,6 +105,8 @@ pub struct TestBlockChainClient {
 	pub storage: RwLock<HashMap<(Address, H256), H256>>,
 	/// Code.
 	pub code: RwLock<HashMap<Address, Bytes>>,
+	/// Preimages of hashed state keys.
+	pub preimages: RwLock<HashMap<H256, Bytes>>,
 	/// Execution result.
 	pub execution_result: RwLock<Option<Result<Executed, CallError>>>,
 	/// Transaction receipts.
@@ -185,6 +188,7 @@ impl TestBlockChainClient {
 			balances: RwLock::new(HashMap::new()),
 			nonces: RwLock::new(HashMap::new()),
 			storage: RwLock::new(HashMap::new()),
+			preimages: RwLock::new(HashMap::new()),
 			code: RwLock::new(HashMap::new()),
 			execution_result: RwLock::new(None),
 			receipts: RwLock::new(HashMap::new()),
@@ -623,6 +627,19 @@ impl Call for TestBlockChainClient {
 		Ok(res)
 	}
 
+	fn call_bundle(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header, stop_on_error: bool) -> Vec<Result<Executed, CallError>> {
+		let mut res = Vec::with_capacity(txs.len());
+		for &(ref tx, analytics) in txs {
+			let ret = self.call(tx, analytics, state, header);
+			let failed = ret.as_ref().map_or(true, |executed| executed.exception.is_some());
+			res.push(ret);
+			if failed && stop_on_error {
+				break;
+			}
+		}
+		res
+	}
+
 	fn estimate_gas(&self, _t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<U256, CallError> {
 		Ok(21000.into())
 	}
@@ -684,10 +701,12 @@ impl BlockChainClient for TestBlockChainClient {
 			max_queue_size: 0,
 			max_mem_use: 0,
 			mem_used: 0,
+			duplicates_rejected: 0,
 		}
 	}
 
 	fn replay_block_transactions(&self, _block: BlockId, _analytics: CallAnalytics) -> Result<Box<dyn Iterator<Item = (H256, Executed)>>, CallError> {
+		let executed = self.execution_result.read().clone().unwrap()?;
 		Ok(Box::new(
 			self.traces
 				.read()
@@ -695,7 +714,7 @@ impl BlockChainClient for TestBlockChainClient {
 				.unwrap()
 				.into_iter()
 				.map(|t| t.transaction_hash.unwrap_or_default())
-				.zip(self.execution_result.read().clone().unwrap().into_iter())
+				.zip(::std::iter::once(executed))
 		))
 	}
 
@@ -858,6 +877,24 @@ impl BlockChainClient for TestBlockChainClient {
 		None
 	}
 
+	fn preimage(&self, hash: &H256) -> Option<Bytes> {
+		self.preimages.read().get(hash).cloned()
+	}
+
+	fn blockchain_cache_report(&self) -> CacheReport {
+		CacheReport::default()
+	}
+
+	fn blocks_authored_by(&self, author: &Address, from: BlockNumber, to: BlockNumber, limit: usize) -> Option<Vec<(BlockNumber, H256)>> {
+		let to = cmp::min(to, self.numbers.read().len() as BlockNumber);
+		Some((from..=to)
+			.filter_map(|n| BlockChainClient::block_header(self, BlockId::Number(n)))
+			.filter(|header| header.author() == *author)
+			.map(|header| (header.number(), header.hash()))
+			.take(limit)
+			.collect())
+	}
+
 	fn block_receipts(&self, hash: &H256) -> Option<BlockReceipts> {
 		// starts with 'f' ?
 		if *hash > H256::from_str("f000000000000000000000000000000000000000000000000000000000000000").unwrap() {
@@ -901,6 +938,8 @@ impl BlockChainClient for TestBlockChainClient {
 
 	fn spec_name(&self) -> String { "foundation".into() }
 
+	fn fork_schedule(&self) -> Vec<ForkActivation> { self.spec.params().fork_schedule() }
+
 	fn set_spec_name(&self, _: String) -> Result<(), ()> { unimplemented!(); }
 
 	fn disable(&self) { self.disabled.store(true, AtomicOrder::Relaxed); }
@@ -964,6 +1003,10 @@ impl ProvingBlockChainClient for TestBlockChainClient {
 		None
 	}
 
+	fn prove_transaction_with_cap(&self, _: SignedTransaction, _: BlockId, _: usize) -> Result<ExecutionProof, ProveError> {
+		Err(ProveError::StateUnavailable)
+	}
+
 	fn epoch_signal(&self, _: H256) -> Option<Vec<u8>> {
 		None
 	}
@@ -18,36 +18,42 @@ use std::str::{FromStr, from_utf8};
 use std::sync::Arc;
 
 use account_state::state::StateInfo;
-use ethereum_types::{U256, Address};
+use ethereum_types::{H256, U256, Address};
 use parity_crypto::publickey::KeyPair;
 use hash::keccak;
 use io::IoChannel;
 use tempfile::TempDir;
 use types::{
+	call_analytics::CallAnalytics,
+	chain_notify::{ChainEvent, ChainEventsError},
+	errors::ExecutionError,
 	data_format::DataFormat,
 	ids::BlockId,
-	transaction::{PendingTransaction, Transaction, Action, Condition},
+	transaction::{PendingTransaction, Transaction, Action, CallError, Condition},
 	filter::Filter,
+	header::Header,
 	verification::Unverified,
 	view,
 	views::BlockView,
 };
 
-use client::{Client, ClientConfig, PrepareOpenBlock, ImportSealedBlock};
+use client::{Call, Client, ClientConfig, PrepareOpenBlock, ImportSealedBlock};
 use client_traits::{
 	BlockInfo, BlockChainClient, BlockChainReset, ChainInfo,
 	ImportExportBlocks, Tick, ImportBlock
 };
-use machine::executive::{Executive, TransactOptions};
+use machine::executive::{contract_address, Executive, TransactOptions};
 use miner::{Miner, PendingOrdering, MinerService};
 use account_state::{State, CleanupMode, backend};
 use test_helpers::{
 	self,
 	generate_dummy_client, push_blocks_to_client, get_test_client_with_blocks, get_good_dummy_block_seq,
-	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block
+	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block, ChainBuilder,
+	CreateContractAddress,
 };
-use rustc_hex::ToHex;
+use rustc_hex::{FromHex, ToHex};
 use registrar::RegistrarClient;
+use spec::Spec;
 
 fn into_u256_vec<'a, T, I>(iter: I) -> Vec<U256>
 where
@@ -325,31 +331,28 @@ fn does_not_propagate_delayed_transactions() {
 		value: 0.into(),
 		data: Vec::new(),
 	}.sign(secret, None), None);
-	let client = generate_dummy_client(1);
+	let chain = ChainBuilder::new().build();
+	chain.push_empty_blocks(1);
+	let client = &chain.client;
 
-	client.miner().import_own_transaction(&*client, tx0).unwrap();
-	client.miner().import_own_transaction(&*client, tx1).unwrap();
+	client.miner().import_own_transaction(&**client, tx0).unwrap();
+	client.miner().import_own_transaction(&**client, tx1).unwrap();
 	assert_eq!(0, client.transactions_to_propagate().len());
-	assert_eq!(0, client.miner().ready_transactions(&*client, 10, PendingOrdering::Priority).len());
-	push_blocks_to_client(&client, 53, 2, 2);
-	client.flush_queue();
+	assert_eq!(0, client.miner().ready_transactions(&**client, 10, PendingOrdering::Priority).len());
+	chain.push_empty_blocks(2);
 	assert_eq!(2, client.transactions_to_propagate().len());
-	assert_eq!(2, client.miner().ready_transactions(&*client, 10, PendingOrdering::Priority).len());
+	assert_eq!(2, client.miner().ready_transactions(&**client, 10, PendingOrdering::Priority).len());
 }
 
 #[test]
 fn transaction_proof() {
 	use client_traits::ProvingBlockChainClient;
 
-	let client = generate_dummy_client(0);
+	let chain = ChainBuilder::new().prefunded_account(1_000).build();
+	let client = &chain.client;
 	let address = Address::random();
-	let test_spec = spec::new_test();
 	for _ in 0..20 {
-		let mut b = client.prepare_open_block(Address::zero(), (3141562.into(), 31415620.into()), vec![]).unwrap();
-		b.block_mut().state_mut().add_balance(&address, &5.into(), CleanupMode::NoEmpty).unwrap();
-		b.block_mut().state_mut().commit().unwrap();
-		let b = b.close_and_lock().unwrap().seal(&*test_spec.engine, vec![]).unwrap();
-		client.import_sealed_block(b).unwrap(); // account change is in the journal overlay
+		chain.block().transfer(0, address, 5).push(); // account change is in the journal overlay
 	}
 
 	let transaction = Transaction {
@@ -368,7 +371,7 @@ fn transaction_proof() {
 	factories.accountdb = ::account_db::Factory::Plain; // raw state values, no mangled keys.
 	let root = *client.best_block_header().state_root();
 
-	let machine = test_spec.engine.machine();
+	let machine = chain.spec.engine.machine();
 	let env_info = client.latest_env_info();
 	let schedule = machine.schedule(env_info.number);
 	let mut state = State::from_existing(backend, root, 0.into(), factories.clone()).unwrap();
@@ -379,6 +382,193 @@ fn transaction_proof() {
 	assert_eq!(state.balance(&address).unwrap(), 95.into());
 }
 
+#[test]
+fn records_state_key_preimages() {
+	let spec = spec::new_test();
+	for &record_preimages in &[true, false] {
+		let client = Client::new(
+			ClientConfig { record_preimages, ..Default::default() },
+			&spec,
+			test_helpers::new_db(),
+			Arc::new(Miner::new_for_tests(&spec, None)),
+			IoChannel::disconnected(),
+		).unwrap();
+
+		let address = Address::random();
+		let key = H256::from_low_u64_be(0x35);
+		let mut b = client.prepare_open_block(Address::zero(), (3141562.into(), 31415620.into()), vec![]).unwrap();
+		b.block_mut().state_mut().set_storage(&address, key, H256::from_low_u64_be(1)).unwrap();
+		b.block_mut().state_mut().commit().unwrap();
+		let b = b.close_and_lock().unwrap().seal(&*spec.engine, vec![]).unwrap();
+		client.import_sealed_block(b).unwrap();
+
+		let expected = |preimage: &[u8]| if record_preimages { Some(preimage.to_vec()) } else { None };
+		assert_eq!(client.preimage(&keccak(address)), expected(address.as_bytes()));
+		assert_eq!(client.preimage(&keccak(key)), expected(key.as_bytes()));
+		assert_eq!(client.preimage(&keccak(Address::random())), None);
+	}
+}
+
+fn authored_block_seq(spec: &Spec, parent: H256, first: u64, authors: &[Address], difficulty: u64) -> Vec<(H256, Unverified)> {
+	let mut parent = parent;
+	authors.iter().enumerate().map(|(i, author)| {
+		let number = first + i as u64;
+		let mut header = Header::new();
+		header.set_gas_limit(*spec.genesis_header().gas_limit());
+		header.set_difficulty(difficulty.into());
+		header.set_timestamp(number * 10);
+		header.set_number(number);
+		header.set_parent_hash(parent);
+		header.set_state_root(*spec.genesis_header().state_root());
+		header.set_author(*author);
+
+		parent = header.hash();
+		(parent, Unverified::from_rlp(test_helpers::create_test_block(&header)).unwrap())
+	}).collect()
+}
+
+fn import_authored_blocks(client: &Client, blocks: Vec<(H256, Unverified)>) -> Vec<H256> {
+	let hashes = blocks.into_iter().map(|(hash, block)| {
+		client.import_block(block).unwrap();
+		hash
+	}).collect();
+	client.flush_queue();
+	hashes
+}
+
+#[test]
+fn indexes_blocks_by_author() {
+	let spec = spec::new_test();
+	let client = Client::new(
+		ClientConfig { author_index: true, ..Default::default() },
+		&spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+	let (alice, bob, carol) = (Address::from_low_u64_be(1), Address::from_low_u64_be(2), Address::from_low_u64_be(3));
+
+	let main = import_authored_blocks(&client, authored_block_seq(&spec, spec.genesis_header().hash(), 1, &[alice, bob, alice, alice], 1));
+	assert_eq!(client.blocks_authored_by(&alice, 0, 10, 10), Some(vec![(1, main[0]), (3, main[2]), (4, main[3])]));
+	assert_eq!(client.blocks_authored_by(&alice, 2, 3, 10), Some(vec![(3, main[2])]));
+	assert_eq!(client.blocks_authored_by(&alice, 0, 10, 2), Some(vec![(1, main[0]), (3, main[2])]));
+	assert_eq!(client.blocks_authored_by(&carol, 0, 10, 10), Some(vec![]));
+
+	// a heavier fork on top of block 2 retracts blocks 3 and 4.
+	let fork = import_authored_blocks(&client, authored_block_seq(&spec, main[1], 3, &[carol, carol, bob], 2));
+	assert_eq!(client.chain_info().best_block_hash, fork[2]);
+	assert_eq!(client.blocks_authored_by(&alice, 0, 10, 10), Some(vec![(1, main[0])]));
+	assert_eq!(client.blocks_authored_by(&bob, 0, 10, 10), Some(vec![(2, main[1]), (5, fork[2])]));
+	assert_eq!(client.blocks_authored_by(&carol, 0, 10, 10), Some(vec![(3, fork[0]), (4, fork[1])]));
+}
+
+#[test]
+fn backfills_author_index() {
+	let spec = spec::new_test();
+	let db = test_helpers::new_db();
+	let author = Address::from_low_u64_be(1);
+	let new_client = |author_index| Client::new(
+		ClientConfig { author_index, ..Default::default() },
+		&spec,
+		db.clone(),
+		Arc::new(Miner::new_for_tests(&spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+
+	let blocks = {
+		let client = new_client(false);
+		let blocks = import_authored_blocks(&client, authored_block_seq(&spec, spec.genesis_header().hash(), 1, &[author; 3], 1));
+		assert_eq!(client.blocks_authored_by(&author, 0, 10, 10), None);
+		blocks
+	};
+
+	let client = new_client(true);
+	assert_eq!(client.blocks_authored_by(&author, 0, 10, 10), Some(vec![]));
+	client.tick(false);
+	assert_eq!(client.blocks_authored_by(&author, 0, 10, 10), Some(vec![(1, blocks[0]), (2, blocks[1]), (3, blocks[2])]));
+}
+
+#[test]
+fn replays_chain_events_after_restart() {
+	let spec = spec::new_test();
+	let db = test_helpers::new_db();
+	let new_client = |chain_events_depth| Client::new(
+		ClientConfig { chain_events_depth, ..Default::default() },
+		&spec,
+		db.clone(),
+		Arc::new(Miner::new_for_tests(&spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+	let author = Address::from_low_u64_be(1);
+
+	let (live, main, fork) = {
+		let client = new_client(16);
+		let mut live = Vec::new();
+		let mut pull = |client: &Client, live: &mut Vec<_>| {
+			let last = live.last().map_or(0, |event: &ChainEvent| event.sequence);
+			live.extend(client.chain_events_since(last, 100).unwrap());
+		};
+
+		let main = import_authored_blocks(&client, authored_block_seq(&spec, spec.genesis_header().hash(), 1, &[author; 4], 1));
+		pull(&client, &mut live);
+		// a heavier fork on top of block 2 retracts blocks 3 and 4.
+		let fork = import_authored_blocks(&client, authored_block_seq(&spec, main[1], 3, &[author; 3], 2));
+		pull(&client, &mut live);
+		assert_eq!(client.chain_info().best_block_hash, fork[2]);
+		(live, main, fork)
+	};
+
+	assert_eq!(live.iter().map(|event| event.sequence).collect::<Vec<_>>(), (1..=live.len() as u64).collect::<Vec<_>>());
+	let enacted: Vec<_> = live.iter().flat_map(|event| event.enacted.clone()).collect();
+	let retracted: Vec<_> = live.iter().flat_map(|event| event.retracted.clone()).collect();
+	assert_eq!(enacted, vec![main[0], main[1], main[2], main[3], fork[0], fork[1], fork[2]]);
+	assert_eq!(retracted, vec![main[3], main[2]]);
+
+	// a restarted consumer replays exactly what was seen live.
+	let client = new_client(16);
+	assert_eq!(client.chain_events_since(0, 100), Ok(live.clone()));
+	assert_eq!(client.chain_events_since(2, 2), Ok(live[2..4].to_vec()));
+
+	let next = import_authored_blocks(&client, authored_block_seq(&spec, fork[2], 6, &[author], 2));
+	let latest = live.last().unwrap().sequence;
+	let events = client.chain_events_since(latest, 100).unwrap();
+	assert_eq!(events.len(), 1);
+	assert_eq!(events[0].sequence, latest + 1);
+	assert_eq!(events[0].enacted, next);
+	drop(client);
+
+	// a consumer behind a shorter window has to resync.
+	let client = new_client(2);
+	assert_eq!(
+		client.chain_events_since(0, 100),
+		Err(ChainEventsError::GapTooLarge { requested: 0, oldest: latest, latest: latest + 1 }),
+	);
+	assert_eq!(client.chain_events_since(latest - 1, 100).unwrap().len(), 2);
+}
+
+#[test]
+fn reset_records_chain_event() {
+	let spec = spec::new_test();
+	let client = Client::new(
+		ClientConfig { chain_events_depth: 16, ..Default::default() },
+		&spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+	let author = Address::from_low_u64_be(1);
+	let main = import_authored_blocks(&client, authored_block_seq(&spec, spec.genesis_header().hash(), 1, &[author; 4], 1));
+	let latest = client.chain_events_since(0, 100).unwrap().last().unwrap().sequence;
+
+	client.reset(2).unwrap();
+
+	let events = client.chain_events_since(latest, 100).unwrap();
+	assert_eq!(events.len(), 1);
+	assert_eq!(events[0].sequence, latest + 1);
+	assert!(events[0].enacted.is_empty());
+	assert_eq!(events[0].retracted, vec![main[3], main[2]]);
+}
+
 #[test]
 fn reset_blockchain() {
 	let client = get_test_client_with_blocks(get_good_dummy_block_seq(19));
@@ -474,3 +664,45 @@ fn import_export_binary() {
 	assert!(client.block_header(BlockId::Number(17)).is_some());
 	assert!(client.block_header(BlockId::Number(16)).is_some());
 }
+
+#[test]
+fn call_bundle_shares_state_between_calls() {
+	let chain = ChainBuilder::new().build();
+	let client = &chain.client;
+	let sender = Address::from_low_u64_be(0x5e);
+	let contract = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+	let call = |action, gas: u64, data: &str| (Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::zero(),
+		gas: gas.into(),
+		action,
+		value: U256::zero(),
+		data: data.from_hex().unwrap(),
+	}.fake_sign(sender), CallAnalytics::default());
+
+	// deploys a contract which returns 42, fails to pay the intrinsic gas, then calls the contract.
+	let bundle = vec![
+		call(Action::Create, 100_000, "600a80600b6000396000f3602a60005260206000f3"),
+		call(Action::Call(contract), 1, ""),
+		call(Action::Call(contract), 100_000, ""),
+	];
+
+	let (mut state, header) = client.latest_state_and_header();
+	let results = client.call_bundle(&bundle, &mut state, &header, false);
+	assert_eq!(results.len(), 3);
+	assert!(results[0].as_ref().unwrap().exception.is_none());
+	assert_eq!(results[1], Err(CallError::Execution(ExecutionError::NotEnoughBaseGas {
+		required: 21_000.into(),
+		got: 1.into(),
+	})));
+	let executed = results[2].as_ref().unwrap();
+	assert!(executed.exception.is_none());
+	assert_eq!(executed.output, H256::from_low_u64_be(42).as_bytes().to_vec());
+	assert!(executed.gas_used > U256::zero());
+
+	let (mut state, header) = client.latest_state_and_header();
+	assert_eq!(state.code(&contract).unwrap(), None);
+	let results = client.call_bundle(&bundle, &mut state, &header, true);
+	assert_eq!(results.len(), 2);
+	assert!(results[1].is_err());
+}
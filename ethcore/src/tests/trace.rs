@@ -19,10 +19,12 @@
 use parity_crypto::publickey::KeyPair;
 use hash::keccak;
 use block::*;
-use ethereum_types::{U256, Address};
+use ethereum_types::{H256, U256, Address};
 use io::*;
+use machine::executive::contract_address;
+use rustc_hex::FromHex;
 use spec;
-use test_helpers::get_temp_state_db;
+use test_helpers::{get_temp_state_db, ChainBuilder, CreateContractAddress};
 use client::{Client, ClientConfig};
 use client_traits::{BlockChainClient, ImportBlock};
 use std::sync::Arc;
@@ -32,7 +34,7 @@ use trace::{RewardType, LocalizedTrace};
 use trace::trace::Action::Reward;
 use test_helpers;
 use types::{
-	ids::BlockId,
+	ids::{BlockId, TransactionId},
 	transaction::{Action, Transaction},
 	trace_filter::Filter as TraceFilter,
 	header::Header,
@@ -207,3 +209,57 @@ fn can_trace_block_and_uncle_reward() {
 	let traces = client.block_traces(BlockId::Number(3));
 	assert_eq!(traces.unwrap().len(), 3);
 }
+
+#[test]
+fn transaction_trace_pages_stitch_together() {
+	let mut client_config = ClientConfig::default();
+	client_config.tracing.enabled = true;
+	let chain = ChainBuilder::new().prefunded_account(0).client_config(client_config).build();
+	let client = &chain.client;
+
+	// Reads a depth from calldata and, unless it is zero, calls itself twice with depth - 1.
+	let runtime = "6000358015602b576001900360005260006000602060006000305af15060006000602060006000305af1505b00";
+	let init = format!("602d80600b6000396000f3{}", runtime);
+	let sender = chain.accounts[0].address();
+	let contract = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+
+	chain.block().transaction(0, Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::zero(),
+		gas: 1_000_000.into(),
+		action: Action::Create,
+		value: U256::zero(),
+		data: init.from_hex().unwrap(),
+	}).push();
+	let block = chain.block().transaction(0, Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::zero(),
+		gas: 2_000_000.into(),
+		action: Action::Call(contract),
+		value: U256::zero(),
+		data: H256::from_low_u64_be(5).as_bytes().to_vec(),
+	}).push();
+
+	// One top-level call plus a full binary tree of depth 5 below it.
+	let id = TransactionId::Location(BlockId::Hash(block), 0);
+	let all = client.transaction_traces(id.clone()).unwrap();
+	assert_eq!(all.len(), 63);
+
+	for &count in &[1, 7, 63, 100] {
+		let mut stitched = Vec::new();
+		loop {
+			let page = client.transaction_traces_page(id.clone(), stitched.len(), count).unwrap();
+			assert_eq!(page.total, 63);
+			assert!(page.traces.len() <= count);
+			if page.traces.is_empty() {
+				break;
+			}
+			stitched.extend(page.traces);
+		}
+		assert_eq!(stitched, all);
+	}
+
+	let past_end = client.transaction_traces_page(id, 100, 10).unwrap();
+	assert!(past_end.traces.is_empty());
+	assert_eq!(past_end.total, 63);
+}
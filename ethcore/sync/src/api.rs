@@ -26,6 +26,7 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use crate::sync_io::NetSyncIo;
 use crate::light_sync::{self, SyncInfo};
 use crate::private_tx::PrivateTxHandler;
+use crate::snapshot_serving::{SnapshotServingConfig, SnapshotServingStats};
 use crate::chain::{
 	fork_filter::ForkFilterApi,
 	sync_packet::SyncPacket::{PrivateTransactionPacket, SignedPrivateTransactionPacket},
@@ -54,7 +55,7 @@ use network::{
 	client_version::ClientVersion,
 	NetworkProtocolHandler, NetworkContext, PeerId, ProtocolId,
 	NetworkConfiguration as BasicNetworkConfiguration, NonReservedPeerMode, Error,
-	ConnectionFilter, IpFilter, NatType
+	ConnectionFilter, IpFilter, NatType, DiversityLimits, ClientIdFilter, ClientHistogram
 };
 use snapshot::SnapshotService;
 use parking_lot::{RwLock, Mutex};
@@ -129,6 +130,12 @@ pub struct SyncConfig {
 	pub warp_sync: WarpSync,
 	/// Enable light client server.
 	pub serve_light: bool,
+	/// Upper bound on the size of execution proofs served to light peers, in bytes.
+	pub light_max_execution_witness_bytes: usize,
+	/// Upper bound on the size of headers responses served to light peers, in bytes.
+	pub light_max_headers_response_bytes: usize,
+	/// Limits for serving snapshot chunks to other peers.
+	pub snapshot_serving: SnapshotServingConfig,
 }
 
 impl Default for SyncConfig {
@@ -142,6 +149,9 @@ impl Default for SyncConfig {
 			fork_block: None,
 			warp_sync: WarpSync::Disabled,
 			serve_light: false,
+			light_max_execution_witness_bytes: light_net::Config::default().max_execution_witness_bytes,
+			light_max_headers_response_bytes: light_net::Config::default().max_headers_response_bytes,
+			snapshot_serving: SnapshotServingConfig::default(),
 		}
 	}
 }
@@ -168,6 +178,9 @@ pub trait SyncProvider: Send + Sync {
 
 	/// are we in the middle of a major sync?
 	fn is_major_syncing(&self) -> bool;
+
+	/// Returns statistics of snapshot data served to other peers.
+	fn snapshot_serving_stats(&self) -> SnapshotServingStats;
 }
 
 /// Transaction stats
@@ -304,6 +317,8 @@ pub struct EthSync {
 fn light_params(
 	network_id: u64,
 	median_peers: f64,
+	max_execution_witness_bytes: usize,
+	max_headers_response_bytes: usize,
 	pruning_info: PruningInfo,
 	sample_store: Option<Box<dyn SampleStore>>,
 ) -> LightParams {
@@ -320,6 +335,8 @@ fn light_params(
 	};
 
 	light_params.config.median_peers = median_peers;
+	light_params.config.max_execution_witness_bytes = max_execution_witness_bytes;
+	light_params.config.max_headers_response_bytes = max_headers_response_bytes;
 	light_params
 }
 
@@ -340,6 +357,8 @@ impl EthSync {
 				let light_params = light_params(
 					params.config.network_id,
 					median_peers,
+					params.config.light_max_execution_witness_bytes,
+					params.config.light_max_headers_response_bytes,
 					pruning_info,
 					sample_store,
 				);
@@ -459,6 +478,10 @@ impl SyncProvider for EthSync {
 	fn is_major_syncing(&self) -> bool {
 		self.is_major_syncing.load(Ordering::SeqCst)
 	}
+
+	fn snapshot_serving_stats(&self) -> SnapshotServingStats {
+		self.eth_handler.sync.snapshot_serving_stats()
+	}
 }
 
 const PEERS_TIMER: TimerToken = 0;
@@ -467,6 +490,7 @@ const CONTINUE_SYNC_TIMER: TimerToken = 2;
 const TX_TIMER: TimerToken = 3;
 const PRIORITY_TIMER: TimerToken = 4;
 const DELAYED_PROCESSING_TIMER: TimerToken = 5;
+const SNAPSHOT_SERVING_TIMER: TimerToken = 6;
 
 pub(crate) const PRIORITY_TIMER_INTERVAL: Duration = Duration::from_millis(250);
 
@@ -491,6 +515,7 @@ impl NetworkProtocolHandler for SyncProtocolHandler {
 			io.register_timer(CONTINUE_SYNC_TIMER, Duration::from_millis(2500)).expect("Error registering sync timer");
 			io.register_timer(TX_TIMER, Duration::from_millis(1300)).expect("Error registering transactions timer");
 			io.register_timer(DELAYED_PROCESSING_TIMER, Duration::from_millis(2100)).expect("Error registering delayed processing timer");
+			io.register_timer(SNAPSHOT_SERVING_TIMER, Duration::from_millis(200)).expect("Error registering snapshot serving timer");
 
 			io.register_timer(PRIORITY_TIMER, PRIORITY_TIMER_INTERVAL).expect("Error registering peers timer");
 		}
@@ -542,6 +567,7 @@ impl NetworkProtocolHandler for SyncProtocolHandler {
 			TX_TIMER => self.sync.write().propagate_new_transactions(&mut io),
 			PRIORITY_TIMER => self.sync.process_priority_queue(&mut io),
 			DELAYED_PROCESSING_TIMER => self.sync.process_delayed_requests(&mut io),
+			SNAPSHOT_SERVING_TIMER => self.sync.process_deferred_snapshot_requests(&mut io),
 			_ => warn!("Unknown timer {} triggered.", timer),
 		}
 	}
@@ -686,6 +712,8 @@ pub trait ManageNetwork: Send + Sync {
 	fn num_peers_range(&self) -> RangeInclusive<u32>;
 	/// Get network context for protocol.
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut dyn FnMut(&dyn NetworkContext));
+	/// Returns connected and filtered-out peer counts per client name.
+	fn client_histogram(&self) -> ClientHistogram;
 }
 
 impl ManageNetwork for EthSync {
@@ -733,6 +761,10 @@ impl ManageNetwork for EthSync {
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut dyn FnMut(&dyn NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
+
+	fn client_histogram(&self) -> ClientHistogram {
+		self.network.client_histogram()
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -772,6 +804,10 @@ pub struct NetworkConfiguration {
 	pub allow_non_reserved: bool,
 	/// IP Filtering
 	pub ip_filter: IpFilter,
+	/// Peer diversity limits
+	pub diversity: DiversityLimits,
+	/// Client id allow/deny lists
+	pub client_id_filter: ClientIdFilter,
 	/// Client version string
 	pub client_version: String,
 }
@@ -811,6 +847,8 @@ impl NetworkConfiguration {
 			reserved_nodes: self.reserved_nodes,
 			ip_filter: self.ip_filter,
 			non_reserved_mode: if self.allow_non_reserved { NonReservedPeerMode::Accept } else { NonReservedPeerMode::Deny },
+			diversity: self.diversity,
+			client_id_filter: self.client_id_filter,
 			client_version: self.client_version,
 		})
 	}
@@ -836,6 +874,8 @@ impl From<BasicNetworkConfiguration> for NetworkConfiguration {
 			reserved_nodes: other.reserved_nodes,
 			ip_filter: other.ip_filter,
 			allow_non_reserved: match other.non_reserved_mode { NonReservedPeerMode::Accept => true, _ => false } ,
+			diversity: other.diversity,
+			client_id_filter: other.client_id_filter,
 			client_version: other.client_version,
 		}
 	}
@@ -1033,6 +1073,10 @@ impl ManageNetwork for LightSync {
 	fn with_proto_context(&self, proto: ProtocolId, f: &mut dyn FnMut(&dyn NetworkContext)) {
 		self.network.with_context_eval(proto, f);
 	}
+
+	fn client_histogram(&self) -> ClientHistogram {
+		self.network.client_histogram()
+	}
 }
 
 impl LightSyncProvider for LightSync {
@@ -110,6 +110,7 @@ impl SyncHandler {
 	pub fn on_peer_aborting(sync: &mut ChainSync, io: &mut dyn SyncIo, peer_id: PeerId) {
 		trace!(target: "sync", "== Disconnecting {}: {}", peer_id, io.peer_version(peer_id));
 		sync.handshaking_peers.remove(&peer_id);
+		sync.snapshot_serving.remove_peer(peer_id);
 		if sync.peers.contains_key(&peer_id) {
 			debug!(target: "sync", "Disconnected {}", peer_id);
 			sync.clear_peer_download(peer_id);
@@ -483,11 +484,8 @@ impl SyncHandler {
 		let manifest_rlp = r.at(0)?;
 		let manifest = ManifestData::from_rlp(manifest_rlp.as_raw())?;
 
-		let is_supported_version = io.snapshot_service().supported_versions()
-			.map_or(false, |(l, h)| manifest.version >= l && manifest.version <= h);
-
-		if !is_supported_version {
-			warn!(target: "snapshot_sync", "{}: Snapshot manifest version not supported: {}", peer_id, manifest.version);
+		if let Err(e) = io.snapshot_service().check_manifest(&manifest) {
+			warn!(target: "snapshot_sync", "{}: Unusable snapshot manifest: {}", peer_id, e);
 			return Err(DownloaderImportError::Invalid);
 		}
 		sync.snapshot.reset_to(&manifest, &keccak(manifest_rlp.as_raw()));
@@ -107,6 +107,7 @@ use crate::{
 	chain::fork_filter::ForkFilterApi,
 	sync_io::SyncIo,
 	snapshot_sync::Snapshot,
+	snapshot_serving::{SnapshotServing, SnapshotServingStats},
 	transactions_stats::{TransactionsStats, Stats as TransactionStats},
 	private_tx::PrivateTxHandler,
 };
@@ -496,6 +497,16 @@ impl ChainSyncApi {
 		}
 	}
 
+	/// Serve deferred snapshot chunk requests, as far as the serving limits allow.
+	pub fn process_deferred_snapshot_requests(&self, io: &mut dyn SyncIo) {
+		SyncSupplier::dispatch_deferred_snapshot_requests(&self.sync, io)
+	}
+
+	/// Returns statistics of snapshot data served to other peers.
+	pub fn snapshot_serving_stats(&self) -> SnapshotServingStats {
+		self.sync.read().snapshot_serving.stats()
+	}
+
 	/// Process a priority propagation queue.
 	/// This task is run from a timer and should be time constrained.
 	/// Hence we set up a deadline for the execution and cancel the task if the deadline is exceeded.
@@ -704,6 +715,9 @@ pub struct ChainSync {
 	delayed_requests: Vec<(PeerId, u8, Vec<u8>)>,
 	/// Ids of delayed requests, used for lookup, id is composed from peer id and packet id
 	delayed_requests_ids: HashSet<(PeerId, u8)>,
+	/// Admission control for snapshot chunks served to other peers.
+	#[ignore_malloc_size_of = "bounded by the number of peers"]
+	snapshot_serving: SnapshotServing,
 	/// Sync start timestamp. Measured when first peer is connected
 	sync_start_time: Option<Instant>,
 	/// Transactions propagation statistics
@@ -741,6 +755,7 @@ impl ChainSync {
 			active_peers: HashSet::new(),
 			delayed_requests: Vec::new(),
 			delayed_requests_ids: HashSet::new(),
+			snapshot_serving: SnapshotServing::new(config.snapshot_serving),
 			new_blocks: BlockDownloader::new(BlockSet::NewBlocks, &chain_info.best_block_hash, chain_info.best_block_number),
 			old_blocks: None,
 			last_sent_block_number: 0,
@@ -1620,7 +1635,8 @@ pub mod tests {
 			verifying_queue_size: 0,
 			max_queue_size: 1000,
 			max_mem_use: 1000,
-			mem_used: 500
+			mem_used: 500,
+			duplicates_rejected: 0,
 		}
 	}
 
@@ -17,6 +17,7 @@
 use std::cmp;
 use std::time::{Duration, Instant};
 
+use crate::snapshot_serving::Admission;
 use crate::sync_io::SyncIo;
 
 use bytes::Bytes;
@@ -97,12 +98,10 @@ impl SyncSupplier {
 				GetSnapshotManifestPacket => SyncSupplier::return_rlp(
 					io, &rlp, peer,
 					SyncSupplier::return_snapshot_manifest,
-					|e| format!("Error sending snapshot manifest: {:?}", e)),
+					|e| format!("Error sending snapshot manifest: {:?}", e))
+					.map(|()| sync.write().snapshot_serving.note_manifest_served()),
 
-				GetSnapshotDataPacket => SyncSupplier::return_rlp(
-					io, &rlp, peer,
-					SyncSupplier::return_snapshot_data,
-					|e| format!("Error sending snapshot data: {:?}", e)),
+				GetSnapshotDataPacket => SyncSupplier::return_limited_snapshot_data(sync, io, &rlp, peer),
 
 				GetPrivateStatePacket => SyncSupplier::return_rlp(
 					io, &rlp, peer,
@@ -387,23 +386,53 @@ impl SyncSupplier {
 		Ok(Some((SnapshotManifestPacket, rlp)))
 	}
 
-	/// Respond to GetSnapshotData request
-	fn return_snapshot_data(io: &dyn SyncIo, r: &Rlp, peer_id: PeerId) -> RlpResponseResult {
+	/// Respond to GetSnapshotData request, unless the serving limits require it to be deferred
+	fn return_limited_snapshot_data(sync: &RwLock<ChainSync>, io: &mut dyn SyncIo, r: &Rlp, peer_id: PeerId) -> Result<(), PacketProcessError> {
 		let hash: H256 = r.val_at(0)?;
 		trace!(target: "warp", "{} -> GetSnapshotData {:?}", peer_id, hash);
-		let rlp = match io.snapshot_service().chunk(hash) {
+		let admission = sync.write().snapshot_serving.request(peer_id, hash, Instant::now());
+		match admission {
+			Admission::Serve => {
+				let (rlp, size) = SyncSupplier::snapshot_data(io, hash, peer_id);
+				sync.write().snapshot_serving.note_chunk_served(peer_id, size);
+				io.respond(SnapshotDataPacket.id(), rlp.out()).unwrap_or_else(
+					|e| debug!(target: "sync", "Error sending snapshot data: {:?}", e));
+			},
+			Admission::Defer => trace!(target: "warp", "{}: Snapshot data request deferred", peer_id),
+			Admission::Drop => debug!(target: "warp", "{}: Too many deferred snapshot data requests, ignoring", peer_id),
+		}
+		Ok(())
+	}
+
+	/// Serve deferred GetSnapshotData requests, as far as the serving limits allow
+	pub fn dispatch_deferred_snapshot_requests(sync: &RwLock<ChainSync>, io: &mut dyn SyncIo) {
+		loop {
+			let next = sync.write().snapshot_serving.next_deferred(Instant::now());
+			let (peer_id, hash) = match next {
+				Some(request) => request,
+				None => break,
+			};
+			let (rlp, size) = SyncSupplier::snapshot_data(io, hash, peer_id);
+			sync.write().snapshot_serving.note_chunk_served(peer_id, size);
+			io.send(peer_id, SnapshotDataPacket, rlp.out()).unwrap_or_else(
+				|e| debug!(target: "sync", "Error sending snapshot data: {:?}", e));
+		}
+	}
+
+	/// Build a SnapshotData response, returning it along with the chunk size
+	fn snapshot_data(io: &dyn SyncIo, hash: H256, peer_id: PeerId) -> (RlpStream, usize) {
+		match io.snapshot_service().chunk(hash) {
 			Some(data) => {
 				let mut rlp = RlpStream::new_list(1);
 				trace!(target: "warp", "{} <- SnapshotData", peer_id);
 				rlp.append(&data);
-				rlp
+				(rlp, data.len())
 			},
 			None => {
 				trace!(target: "warp", "{}: No snapshot data to return", peer_id);
-				RlpStream::new_list(0)
+				(RlpStream::new_list(0), 0)
 			}
-		};
-		Ok(Some((SnapshotDataPacket, rlp)))
+		}
 	}
 
 	/// Respond to GetPrivateStatePacket
@@ -462,11 +491,15 @@ mod test {
 	use crate::{
 		blocks::SyncHeader,
 		chain::RlpResponseResult,
+		snapshot_serving::{SnapshotServing, SnapshotServingConfig},
 		tests::{helpers::TestIo, snapshot::TestSnapshotService}
 	};
 
 	use super::{
-		SyncPacket::{GetReceiptsPacket, GetNodeDataPacket},
+		SyncPacket::{
+			GetReceiptsPacket, GetNodeDataPacket, GetSnapshotDataPacket, GetSnapshotManifestPacket,
+			SnapshotDataPacket, SnapshotManifestPacket,
+		},
 		BlockNumber, BlockId, SyncSupplier, PacketInfo
 	};
 
@@ -478,6 +511,7 @@ mod test {
 	use ethereum_types::H256;
 	use parking_lot::RwLock;
 	use rlp::{Rlp, RlpStream};
+	use snapshot::SnapshotService;
 
 	#[test]
 	fn return_block_headers() {
@@ -654,4 +688,49 @@ mod test {
 		SyncSupplier::dispatch_packet(&RwLock::new(sync), &mut io, 0usize, GetReceiptsPacket.id(), &receipts_request);
 		assert_eq!(1, io.packets.len());
 	}
+
+	#[test]
+	fn snapshot_data_is_deferred_but_manifest_is_not() {
+		let mut client = TestBlockChainClient::new();
+		let queue = RwLock::new(VecDeque::new());
+		let mut sync = dummy_sync_with_peer(H256::zero(), &client);
+		sync.snapshot_serving = SnapshotServing::new(SnapshotServingConfig {
+			max_bytes_per_sec: None,
+			max_chunks_per_peer_per_sec: Some(1),
+			max_pending_per_peer: 4,
+		});
+		let sync = RwLock::new(sync);
+		let ss = TestSnapshotService::new_with_snapshot(4, H256::zero(), 0);
+		let manifest = ss.manifest().unwrap();
+		let mut io = TestIo::new(&mut client, &ss, &queue, Some(0), None);
+
+		let chunk_request = |hash: &H256| {
+			let mut rlp = RlpStream::new_list(1);
+			rlp.append(hash);
+			rlp.out()
+		};
+
+		SyncSupplier::dispatch_packet(&sync, &mut io, 0, GetSnapshotDataPacket.id(), &chunk_request(&manifest.state_hashes[0]));
+		assert_eq!(io.packets.len(), 1);
+		assert_eq!(io.packets[0].packet_id, SnapshotDataPacket.id());
+
+		// the peer's quota is used up, so the next chunk has to wait
+		SyncSupplier::dispatch_packet(&sync, &mut io, 0, GetSnapshotDataPacket.id(), &chunk_request(&manifest.block_hashes[0]));
+		assert_eq!(io.packets.len(), 1);
+
+		// manifest requests are never held back
+		SyncSupplier::dispatch_packet(&sync, &mut io, 0, GetSnapshotManifestPacket.id(), &RlpStream::new_list(0).out());
+		assert_eq!(io.packets.len(), 2);
+		assert_eq!(io.packets[1].packet_id, SnapshotManifestPacket.id());
+
+		SyncSupplier::dispatch_deferred_snapshot_requests(&sync, &mut io);
+		assert_eq!(io.packets.len(), 2);
+
+		let stats = sync.read().snapshot_serving.stats();
+		assert_eq!(stats.manifests_served, 1);
+		assert_eq!(stats.chunks_served, 1);
+		assert_eq!(stats.bytes_served, 32);
+		assert_eq!(stats.requests_deferred, 1);
+		assert_eq!(stats.pending, 1);
+	}
 }
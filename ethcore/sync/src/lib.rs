@@ -31,6 +31,7 @@ mod block_sync;
 mod sync_io;
 mod private_tx;
 mod snapshot_sync;
+mod snapshot_serving;
 mod transactions_stats;
 
 pub mod light_sync;
@@ -40,6 +41,7 @@ mod tests;
 
 pub use api::*;
 pub use chain::{SyncStatus, SyncState};
+pub use snapshot_serving::{SnapshotServingConfig, SnapshotServingStats};
 pub use devp2p::validate_node_url;
 pub use network::{NonReservedPeerMode, Error, ConnectionFilter, ConnectionDirection};
 pub use private_tx::{PrivateTxHandler, NoopPrivateTxHandler, SimplePrivateTxHandler};
@@ -531,6 +531,22 @@ impl<L: AsLightClient> LightSync<L> {
 				if sink.is_empty() { break }
 				trace!(target: "sync", "Drained {} headers to import", sink.len());
 
+				// drained headers are contiguous. once they follow an imported block and nothing
+				// is queued ahead of them, import them in one go instead of through the queue.
+				let known = sink.iter().take_while(|header| client.is_known(&header.hash())).count();
+				sink.drain(..known);
+				let batch_ready = sink.first().map_or(false, |first| client.is_known(first.parent_hash()))
+					&& client.queue_info().is_empty();
+				if batch_ready {
+					if let Err(e) = client.import_header_batch(sink.drain(..).collect()) {
+						debug!(target: "sync", "Found bad header in batch ({:?}). Reset to search state.", e);
+
+						self.begin_search(&mut state);
+						break 'a;
+					}
+					continue;
+				}
+
 				for header in sink.drain(..) {
 					match client.queue_header(header) {
 						Ok(_) => {}
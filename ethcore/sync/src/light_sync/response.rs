@@ -237,6 +237,32 @@ mod tests {
 		assert_eq!(verify(&headers, &request), Err(BasicError::TooManyHeaders(20, 25)));
 	}
 
+	#[test]
+	fn fewer_than_max() {
+		let request = HeadersRequest {
+			start: 10.into(),
+			max: 192,
+			skip: 0,
+			reverse: false,
+		};
+
+		let mut parent_hash = None;
+		let headers: Vec<_> = (0..100).map(|x| x + 10).map(|x| {
+			let mut header = Header::default();
+			header.set_number(x);
+
+			if let Some(parent_hash) = parent_hash {
+				header.set_parent_hash(parent_hash);
+			}
+
+			parent_hash = Some(header.hash());
+
+			encoded::Header::new(::rlp::encode(&header))
+		}).collect();
+
+		assert_eq!(verify(&headers, &request).map(|h| h.len()), Ok(100));
+	}
+
 	#[test]
 	fn wrong_skip() {
 		let request = HeadersRequest {
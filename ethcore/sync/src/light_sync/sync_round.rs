@@ -83,6 +83,35 @@ impl Ord for SubchainRequest {
 	}
 }
 
+impl SubchainRequest {
+	// add verified headers, in descending order, to the downloaded portion of the subchain
+	// and narrow the headers request down to the remainder.
+	// returns `false` if the headers don't form a chain.
+	fn extend_downloaded(&mut self, headers: Vec<Header>) -> bool {
+		let mut parent_hash = None;
+		for header in headers {
+			if let Some(hash) = parent_hash.as_ref() {
+				if *hash != header.hash() {
+					return false;
+				}
+			}
+			// incrementally update the frame request as we go so we can
+			// return at any time in the loop.
+			parent_hash = Some(*header.parent_hash());
+			self.headers_request.start = header.parent_hash().clone().into();
+			self.headers_request.max -= 1;
+			self.downloaded.push_front(header);
+		}
+
+		true
+	}
+
+	// whether the whole subchain has been downloaded.
+	fn is_complete(&self) -> bool {
+		self.headers_request.max == 0
+	}
+}
+
 /// Manages downloading of interior blocks of a sparse header chain.
 pub struct Fetcher {
 	sparse: VecDeque<Header>, // sparse header chain.
@@ -202,28 +231,18 @@ impl Fetcher {
 				SyncRound::Fetch(self)
 			}
 			Ok(headers) => {
-				let mut parent_hash = None;
-				for header in headers {
-					if let Some(hash) = parent_hash.as_ref() {
-						if *hash != header.hash() {
-							trace!(target: "sync", "Punishing peer {} for parent mismatch", ctx.responder());
-							ctx.punish_responder();
-							self.requests.push(request);
-							return SyncRound::Fetch(self);
-						}
-					}
-					// incrementally update the frame request as we go so we can
-					// return at any time in the loop.
-					parent_hash = Some(*header.parent_hash());
-					request.headers_request.start = header.parent_hash().clone().into();
-					request.headers_request.max -= 1;
-					request.downloaded.push_front(header);
+				if !request.extend_downloaded(headers) {
+					trace!(target: "sync", "Punishing peer {} for parent mismatch", ctx.responder());
+					ctx.punish_responder();
+					self.requests.push(request);
+					return SyncRound::Fetch(self);
 				}
 
 				let subchain_parent = request.subchain_parent.1;
 
 				// check if the subchain portion has been completely filled.
-				if request.headers_request.max == 0 {
+				if request.is_complete() {
+					let parent_hash = request.downloaded.front().map(|h| *h.parent_hash());
 					if parent_hash.map_or(true, |hash| hash != subchain_parent) {
 						let abort = AbortReason::BadScaffold(self.scaffold_contributors);
 						return SyncRound::abort(abort, self.ready);
@@ -231,6 +250,11 @@ impl Fetcher {
 
 					self.complete_requests.insert(subchain_parent, request);
 					self.collect_ready();
+				} else {
+					// the peer served only part of the subchain: request the remainder.
+					trace!(target: "sync", "Partial response for subchain ({} -> {}), {} headers remaining",
+						request.subchain_parent.0, request.subchain_end.0, request.headers_request.max);
+					self.requests.push(request);
 				}
 
 				// state transition not triggered until drain is finished.
@@ -540,7 +564,24 @@ impl fmt::Debug for SyncRound {
 
 #[cfg(test)]
 mod tests {
-	use super::scaffold_params;
+	use std::collections::VecDeque;
+
+	use common_types::header::Header;
+	use light::request::HashOrNumber;
+
+	use super::{scaffold_params, HeadersRequest, SubchainRequest};
+
+	// headers 1..=len, each linked to its predecessor.
+	fn chain(len: u64) -> Vec<Header> {
+		let mut parent_hash = Default::default();
+		(1..=len).map(|number| {
+			let mut header = Header::default();
+			header.set_number(number);
+			header.set_parent_hash(parent_hash);
+			parent_hash = header.hash();
+			header
+		}).collect()
+	}
 
 	#[test]
 	fn scaffold_config() {
@@ -554,4 +595,51 @@ mod tests {
 		assert_eq!(scaffold_params(1000), (255, 4));
 		assert_eq!(scaffold_params(1024), (255, 4));
 	}
+
+	#[test]
+	fn partial_response_requests_remainder() {
+		let headers = chain(10);
+		// subchain between sparse headers #1 and #10.
+		let mut request = SubchainRequest {
+			subchain_parent: (1, headers[0].hash()),
+			headers_request: HeadersRequest {
+				start: headers[9].parent_hash().clone().into(),
+				max: 8,
+				skip: 0,
+				reverse: true,
+			},
+			subchain_end: (9, *headers[9].parent_hash()),
+			downloaded: VecDeque::new(),
+		};
+
+		// peer serves only #9..#6
+		assert!(request.extend_downloaded(headers[5..9].iter().rev().cloned().collect()));
+		assert!(!request.is_complete());
+		assert_eq!(request.headers_request.start, HashOrNumber::Hash(*headers[5].parent_hash()));
+		assert_eq!(request.headers_request.max, 4);
+
+		// follow-up request fills in the rest.
+		assert!(request.extend_downloaded(headers[1..5].iter().rev().cloned().collect()));
+		assert!(request.is_complete());
+		assert_eq!(request.downloaded.iter().map(|h| h.number()).collect::<Vec<_>>(), (2..10).collect::<Vec<_>>());
+		assert_eq!(request.downloaded.front().map(|h| *h.parent_hash()), Some(request.subchain_parent.1));
+	}
+
+	#[test]
+	fn non_chained_response_is_rejected() {
+		let headers = chain(10);
+		let mut request = SubchainRequest {
+			subchain_parent: (1, headers[0].hash()),
+			headers_request: HeadersRequest {
+				start: headers[9].parent_hash().clone().into(),
+				max: 8,
+				skip: 0,
+				reverse: true,
+			},
+			subchain_end: (9, *headers[9].parent_hash()),
+			downloaded: VecDeque::new(),
+		};
+
+		assert!(!request.extend_downloaded(vec![headers[8].clone(), headers[6].clone()]));
+	}
 }
@@ -19,6 +19,8 @@ use crate::tests::helpers::TestNet;
 use ethcore::test_helpers::EachBlockWith;
 use client_traits::BlockInfo;
 use common_types::ids::BlockId;
+use common_types::transaction::{PendingTransaction, Transaction};
+use ethereum_types::Address;
 
 mod test_net;
 
@@ -63,3 +65,34 @@ fn fork_post_cht() {
 		);
 	}
 }
+
+#[test]
+fn relayed_transaction_confirmed_on_header_import() {
+	let mut net = TestNet::light(1, 1);
+	net.peer(1).chain().add_blocks(10, EachBlockWith::Nothing);
+
+	let tx: PendingTransaction = Transaction::default().fake_sign(Address::zero()).into();
+	let hash = tx.hash();
+	let txq = net.peer(0).transaction_queue();
+	txq.write().import(tx).unwrap();
+	txq.write().note_propagated(&[hash], 1, None, 0);
+
+	// a full peer reports the transaction to be included in block 10.
+	let block_hash = net.peer(1).chain().block_header(BlockId::Number(10)).unwrap().hash();
+	txq.write().note_included(&hash, 10, block_hash);
+
+	let confirm = |net: &TestNet<test_net::Peer>| {
+		let light_chain = net.peer(0).light_chain();
+		txq.write().confirm_included(|num| light_chain.block_hash(BlockId::Number(num)))
+	};
+
+	// the header isn't known yet.
+	assert!(confirm(&net).is_empty());
+	assert!(!txq.read().propagation_status(&hash).unwrap().is_confirmed());
+
+	net.sync();
+
+	assert_eq!(confirm(&net), vec![hash]);
+	assert_eq!(txq.read().propagation_status(&hash).unwrap().first_seen_in, Some((10, block_hash)));
+	assert!(txq.read().transactions_to_propagate(10, 0).is_empty());
+}
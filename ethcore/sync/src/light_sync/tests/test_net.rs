@@ -31,7 +31,8 @@ use light::{
 	cache::Cache,
 	client::fetch::{self, Unavailable},
 	net::{LightProtocol, IoContext, Capabilities, Params as LightParams},
-	provider::LightProvider
+	provider::LightProvider,
+	transaction_queue::TransactionQueue,
 };
 use network::{NodeId, PeerId};
 use parking_lot::{Mutex, RwLock};
@@ -47,12 +48,13 @@ struct TestIoContext<'a> {
 }
 
 impl<'a> IoContext for TestIoContext<'a> {
-	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>) {
+	fn send(&self, peer: PeerId, packet_id: u8, packet_body: Vec<u8>) -> bool {
 		self.queue.write().push_back(TestPacket {
 			data: packet_body,
 			packet_id,
 			recipient: peer,
-		})
+		});
+		true
 	}
 
 	fn respond(&self, packet_id: u8, packet_body: Vec<u8>) {
@@ -84,7 +86,7 @@ impl<'a> IoContext for TestIoContext<'a> {
 
 // peer-specific data.
 enum PeerData {
-	Light(Arc<LightSync<LightClient>>, Arc<LightClient>),
+	Light(Arc<LightSync<LightClient>>, Arc<LightClient>, Arc<RwLock<TransactionQueue>>),
 	Full(Arc<TestBlockChainClient>)
 }
 
@@ -135,13 +137,14 @@ impl Peer {
 			sample_store: None,
 		};
 
-		let provider = LightProvider::new(chain.clone(), Arc::new(RwLock::new(Default::default())));
+		let txq = Arc::new(RwLock::new(TransactionQueue::default()));
+		let provider = LightProvider::new(chain.clone(), txq.clone());
 		let mut proto = LightProtocol::new(Arc::new(provider), params);
 		proto.add_handler(sync.clone());
 		Peer {
 			proto: proto,
 			queue: RwLock::new(VecDeque::new()),
-			data: PeerData::Light(sync, chain),
+			data: PeerData::Light(sync, chain, txq),
 		}
 	}
 
@@ -156,11 +159,19 @@ impl Peer {
 	// get the light chain from the peer, asserting that it is a light node.
 	pub fn light_chain(&self) -> &LightClient {
 		match self.data {
-			PeerData::Light(_, ref chain) => &*chain,
+			PeerData::Light(_, ref chain, _) => &*chain,
 			_ => panic!("Attempted to access light chain on full peer."),
 		}
 	}
 
+	// get the transaction queue from the peer, asserting that it is a light node.
+	pub fn transaction_queue(&self) -> Arc<RwLock<TransactionQueue>> {
+		match self.data {
+			PeerData::Light(_, _, ref txq) => txq.clone(),
+			_ => panic!("Attempted to access transaction queue on full peer."),
+		}
+	}
+
 	// get a test Io context based on
 	fn io(&self, sender: Option<PeerId>) -> TestIoContext {
 		TestIoContext {
@@ -196,7 +207,7 @@ impl PeerLike for Peer {
 
 	fn is_done(&self) -> bool {
 		self.queue.read().is_empty() && match self.data {
-			PeerData::Light(_, ref client) => {
+			PeerData::Light(_, ref client, _) => {
 				// should create a test light client which just imports
 				// headers directly and doesn't have a queue to drain.
 				client.import_verified();
@@ -207,7 +218,7 @@ impl PeerLike for Peer {
 	}
 
 	fn sync_step(&self) {
-		if let PeerData::Light(_, ref client) = self.data {
+		if let PeerData::Light(_, ref client, _) = self.data {
 			client.flush_queue();
 
 			while !client.queue_info().is_empty() {
@@ -0,0 +1,387 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Serving-side limits for warp snapshot chunks.
+//!
+//! Chunk requests are admitted against a global bandwidth bucket and a per-peer rate.
+//! Requests which can't be served right away are deferred (in arrival order) and picked
+//! up later by a timer; a peer with too many deferred requests has further ones dropped.
+//! Manifest requests are cheap and always served immediately.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
+
+use ethereum_types::H256;
+use network::PeerId;
+
+/// Limits for serving snapshot chunks to peers. Unlimited by default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapshotServingConfig {
+	/// Global chunk serving bandwidth, in bytes per second. `None` disables the cap.
+	pub max_bytes_per_sec: Option<u64>,
+	/// Chunk requests served per peer per second. `None` disables the limit.
+	pub max_chunks_per_peer_per_sec: Option<u32>,
+	/// Deferred chunk requests kept per peer. Further requests from that peer are dropped.
+	pub max_pending_per_peer: usize,
+}
+
+impl Default for SnapshotServingConfig {
+	fn default() -> Self {
+		SnapshotServingConfig {
+			max_bytes_per_sec: None,
+			max_chunks_per_peer_per_sec: None,
+			max_pending_per_peer: 4,
+		}
+	}
+}
+
+/// Snapshot serving statistics.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SnapshotServingStats {
+	/// Manifest requests served.
+	pub manifests_served: u64,
+	/// Chunk requests served.
+	pub chunks_served: u64,
+	/// Total size of the served chunks, in bytes.
+	pub bytes_served: u64,
+	/// Chunk requests which had to be deferred.
+	pub requests_deferred: u64,
+	/// Chunk requests dropped because the peer had too many deferred ones.
+	pub requests_dropped: u64,
+	/// Chunk requests currently waiting to be served.
+	pub pending: usize,
+}
+
+/// What to do with an incoming chunk request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Admission {
+	/// Serve it now.
+	Serve,
+	/// It was queued and will be served later.
+	Defer,
+	/// Too many requests from this peer are queued already; ignore it.
+	Drop,
+}
+
+/// Token bucket refilled continuously at `rate` tokens per second, up to `capacity`.
+/// Consumption may leave it in debt, which has to be paid off before anything else is admitted.
+#[derive(Debug)]
+struct TokenBucket {
+	rate: f64,
+	capacity: f64,
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl TokenBucket {
+	fn new(rate: f64, now: Instant) -> Self {
+		TokenBucket {
+			rate,
+			capacity: rate,
+			tokens: rate,
+			last_refill: now,
+		}
+	}
+
+	fn refill(&mut self, now: Instant) {
+		if now > self.last_refill {
+			let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+			self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+			self.last_refill = now;
+		}
+	}
+
+	fn available(&mut self, now: Instant) -> bool {
+		self.refill(now);
+		self.tokens > 0.0
+	}
+
+	fn consume(&mut self, amount: f64) {
+		self.tokens -= amount;
+	}
+}
+
+#[derive(Debug, Default)]
+struct PeerQuota {
+	chunks: Option<TokenBucket>,
+	pending: usize,
+}
+
+/// Admission control for snapshot chunk requests.
+#[derive(Debug)]
+pub struct SnapshotServing {
+	config: SnapshotServingConfig,
+	bandwidth: Option<TokenBucket>,
+	peers: HashMap<PeerId, PeerQuota>,
+	deferred: VecDeque<(PeerId, H256)>,
+	stats: SnapshotServingStats,
+}
+
+impl SnapshotServing {
+	/// Create new admission control with the given limits.
+	pub fn new(config: SnapshotServingConfig) -> Self {
+		let bandwidth = config.max_bytes_per_sec.map(|rate| TokenBucket::new(rate as f64, Instant::now()));
+		SnapshotServing {
+			config,
+			bandwidth,
+			peers: HashMap::new(),
+			deferred: VecDeque::new(),
+			stats: SnapshotServingStats::default(),
+		}
+	}
+
+	/// Decide what to do with a chunk request. Deferred requests are queued internally
+	/// and handed out by `next_deferred` once limits allow.
+	pub fn request(&mut self, peer: PeerId, hash: H256, now: Instant) -> Admission {
+		// Requests already waiting go first, so a peer re-requesting as soon as it gets
+		// a response can't starve the others.
+		if self.can_serve(peer, now) && !self.has_servable_deferred(now) {
+			return Admission::Serve;
+		}
+
+		let max_pending = self.config.max_pending_per_peer;
+		let quota = self.peers.entry(peer).or_default();
+		if quota.pending >= max_pending {
+			self.stats.requests_dropped += 1;
+			return Admission::Drop;
+		}
+
+		quota.pending += 1;
+		self.deferred.push_back((peer, hash));
+		self.stats.requests_deferred += 1;
+		Admission::Defer
+	}
+
+	/// Take the oldest deferred request which may be served now, if any.
+	pub fn next_deferred(&mut self, now: Instant) -> Option<(PeerId, H256)> {
+		if !self.bandwidth_available(now) {
+			return None;
+		}
+
+		let position = {
+			let deferred = &self.deferred;
+			let peers = &mut self.peers;
+			let config = &self.config;
+			deferred.iter().position(|(peer, _)| Self::peer_available(peers, config, *peer, now))
+		}?;
+		let (peer, hash) = self.deferred.remove(position)?;
+		if let Some(quota) = self.peers.get_mut(&peer) {
+			quota.pending = quota.pending.saturating_sub(1);
+		}
+		Some((peer, hash))
+	}
+
+	/// Account for a chunk response of `size` bytes sent to `peer`.
+	pub fn note_chunk_served(&mut self, peer: PeerId, size: usize) {
+		if let Some(ref mut bandwidth) = self.bandwidth {
+			bandwidth.consume(size as f64);
+		}
+		if let Some(chunks) = self.peers.get_mut(&peer).and_then(|quota| quota.chunks.as_mut()) {
+			chunks.consume(1.0);
+		}
+		self.stats.chunks_served += 1;
+		self.stats.bytes_served += size as u64;
+	}
+
+	/// Account for a manifest response.
+	pub fn note_manifest_served(&mut self) {
+		self.stats.manifests_served += 1;
+	}
+
+	/// Forget about a disconnected peer and its deferred requests.
+	pub fn remove_peer(&mut self, peer: PeerId) {
+		self.peers.remove(&peer);
+		self.deferred.retain(|(p, _)| *p != peer);
+	}
+
+	/// Serving statistics.
+	pub fn stats(&self) -> SnapshotServingStats {
+		SnapshotServingStats {
+			pending: self.deferred.len(),
+			..self.stats
+		}
+	}
+
+	fn can_serve(&mut self, peer: PeerId, now: Instant) -> bool {
+		self.bandwidth_available(now) && Self::peer_available(&mut self.peers, &self.config, peer, now)
+	}
+
+	fn has_servable_deferred(&mut self, now: Instant) -> bool {
+		let peers = &mut self.peers;
+		let config = &self.config;
+		self.deferred.iter().any(|(peer, _)| Self::peer_available(peers, config, *peer, now))
+	}
+
+	fn bandwidth_available(&mut self, now: Instant) -> bool {
+		self.bandwidth.as_mut().map_or(true, |bandwidth| bandwidth.available(now))
+	}
+
+	fn peer_available(peers: &mut HashMap<PeerId, PeerQuota>, config: &SnapshotServingConfig, peer: PeerId, now: Instant) -> bool {
+		let quota = peers.entry(peer).or_insert_with(|| PeerQuota {
+			chunks: config.max_chunks_per_peer_per_sec.map(|rate| TokenBucket::new(rate as f64, now)),
+			pending: 0,
+		});
+		quota.chunks.as_mut().map_or(true, |chunks| chunks.available(now))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use std::time::{Duration, Instant};
+
+	use ethereum_types::H256;
+	use network::PeerId;
+
+	use super::{Admission, SnapshotServing, SnapshotServingConfig};
+
+	const CHUNK_SIZE: usize = 100 * 1024;
+
+	/// Simulates greedy peers which send a new chunk request as soon as the previous
+	/// one is answered, with deferred requests picked up every `tick`.
+	/// Returns bytes served per peer.
+	fn run_greedy_peers(config: SnapshotServingConfig, peers: &[PeerId], duration: Duration, tick: Duration) -> HashMap<PeerId, usize> {
+		let start = Instant::now();
+		let mut serving = SnapshotServing::new(config);
+		// Start with an empty global bucket, so the measured rate isn't skewed by the initial burst.
+		if let Some(ref mut bandwidth) = serving.bandwidth {
+			bandwidth.tokens = 0.0;
+			bandwidth.last_refill = start;
+		}
+
+		let mut served: HashMap<PeerId, usize> = peers.iter().map(|p| (*p, 0)).collect();
+		let mut waiting: HashMap<PeerId, bool> = peers.iter().map(|p| (*p, false)).collect();
+
+		let mut now = start;
+		while now < start + duration {
+			for peer in peers {
+				if waiting[peer] {
+					continue;
+				}
+				match serving.request(*peer, H256::zero(), now) {
+					Admission::Serve => {
+						serving.note_chunk_served(*peer, CHUNK_SIZE);
+						*served.get_mut(peer).unwrap() += CHUNK_SIZE;
+					}
+					Admission::Defer => { waiting.insert(*peer, true); }
+					Admission::Drop => panic!("greedy peers only keep one request in flight"),
+				}
+			}
+
+			while let Some((peer, _)) = serving.next_deferred(now) {
+				serving.note_chunk_served(peer, CHUNK_SIZE);
+				*served.get_mut(&peer).unwrap() += CHUNK_SIZE;
+				waiting.insert(peer, false);
+			}
+
+			now += tick;
+		}
+
+		served
+	}
+
+	#[test]
+	fn default_config_serves_everything_immediately() {
+		let mut serving = SnapshotServing::new(SnapshotServingConfig::default());
+		let now = Instant::now();
+		for i in 0..100 {
+			assert_eq!(serving.request(1, H256::from_low_u64_be(i), now), Admission::Serve);
+			serving.note_chunk_served(1, CHUNK_SIZE);
+		}
+		assert_eq!(serving.stats().requests_deferred, 0);
+	}
+
+	#[test]
+	fn bandwidth_cap_limits_serve_rate() {
+		let rate = 1024 * 1024;
+		let config = SnapshotServingConfig {
+			max_bytes_per_sec: Some(rate),
+			max_chunks_per_peer_per_sec: None,
+			max_pending_per_peer: 4,
+		};
+		let secs = 20;
+		let served = run_greedy_peers(config, &[1, 2], Duration::from_secs(secs), Duration::from_millis(10));
+
+		let total: usize = served.values().sum();
+		let expected = (rate * secs) as usize;
+		// A single chunk may overdraw the bucket.
+		assert!(total <= expected + CHUNK_SIZE, "served {} bytes, expected at most {}", total, expected + CHUNK_SIZE);
+		assert!(total >= expected - 2 * CHUNK_SIZE, "served {} bytes, expected at least {}", total, expected - 2 * CHUNK_SIZE);
+	}
+
+	#[test]
+	fn greedy_peers_are_served_fairly() {
+		let config = SnapshotServingConfig {
+			max_bytes_per_sec: Some(512 * 1024),
+			max_chunks_per_peer_per_sec: None,
+			max_pending_per_peer: 4,
+		};
+		let served = run_greedy_peers(config, &[1, 2], Duration::from_secs(20), Duration::from_millis(10));
+
+		let (a, b) = (served[&1] as f64, served[&2] as f64);
+		assert!(a > 0.0 && b > 0.0);
+		assert!((a - b).abs() / (a + b) < 0.05, "unfair split: {} vs {}", a, b);
+	}
+
+	#[test]
+	fn per_peer_rate_limits_each_peer() {
+		let config = SnapshotServingConfig {
+			max_bytes_per_sec: None,
+			max_chunks_per_peer_per_sec: Some(2),
+			max_pending_per_peer: 4,
+		};
+		let secs = 10;
+		let served = run_greedy_peers(config, &[1, 2], Duration::from_secs(secs), Duration::from_millis(10));
+
+		for bytes in served.values() {
+			let chunks = bytes / CHUNK_SIZE;
+			// Initial burst of one second worth of chunks, then the steady rate.
+			assert!(chunks <= 2 * secs as usize + 2, "served {} chunks", chunks);
+			assert!(chunks >= 2 * secs as usize - 1, "served {} chunks", chunks);
+		}
+	}
+
+	#[test]
+	fn excess_requests_are_dropped_not_served() {
+		let config = SnapshotServingConfig {
+			max_bytes_per_sec: None,
+			max_chunks_per_peer_per_sec: Some(1),
+			max_pending_per_peer: 2,
+		};
+		let now = Instant::now();
+		let mut serving = SnapshotServing::new(config);
+
+		assert_eq!(serving.request(1, H256::zero(), now), Admission::Serve);
+		serving.note_chunk_served(1, CHUNK_SIZE);
+		assert_eq!(serving.request(1, H256::zero(), now), Admission::Defer);
+		assert_eq!(serving.request(1, H256::zero(), now), Admission::Defer);
+		assert_eq!(serving.request(1, H256::zero(), now), Admission::Drop);
+		// other peers are unaffected
+		assert_eq!(serving.request(2, H256::zero(), now), Admission::Serve);
+
+		let stats = serving.stats();
+		assert_eq!(stats.chunks_served, 1);
+		assert_eq!(stats.requests_deferred, 2);
+		assert_eq!(stats.requests_dropped, 1);
+		assert_eq!(stats.pending, 2);
+
+		assert_eq!(serving.next_deferred(now), None);
+		assert_eq!(serving.next_deferred(now + Duration::from_secs(1)), Some((1, H256::zero())));
+
+		serving.remove_peer(1);
+		assert_eq!(serving.stats().pending, 0);
+	}
+}
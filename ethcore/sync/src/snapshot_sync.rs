@@ -227,6 +227,8 @@ mod test {
 			state_root: H256::zero(),
 			block_number: 42,
 			block_hash: H256::zero(),
+			engine: String::new(),
+			compression: Default::default(),
 		};
 		let mhash = keccak(manifest.clone().into_rlp());
 		(manifest, mhash, state_chunks, block_chunks)
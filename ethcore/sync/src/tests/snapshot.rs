@@ -59,6 +59,8 @@ impl TestSnapshotService {
 			state_root: H256::zero(),
 			block_number: block_number,
 			block_hash: block_hash,
+			engine: String::new(),
+			compression: Default::default(),
 		};
 		let mut chunks: HashMap<H256, Bytes> = state_chunks.into_iter().map(|data| (keccak(&data), data)).collect();
 		chunks.extend(block_chunks.into_iter().map(|data| (keccak(&data), data)));
@@ -81,6 +83,10 @@ impl SnapshotService for TestSnapshotService {
 		Some((1, 2))
 	}
 
+	fn engine_name(&self) -> String {
+		String::new()
+	}
+
 	fn completed_chunks(&self) -> Option<Vec<H256>> {
 		Some(vec![])
 	}
@@ -103,6 +109,10 @@ impl SnapshotService for TestSnapshotService {
 		}
 	}
 
+	fn restoration_target(&self) -> Option<u64> {
+		self.restoration_manifest.lock().as_ref().map(|m| m.block_number)
+	}
+
 	fn begin_restore(&self, manifest: ManifestData) {
 		let mut restoration_manifest = self.restoration_manifest.lock();
 
@@ -28,6 +28,7 @@ use ethereum_types::{H256, H264};
 use kvdb::DBTransaction;
 use parity_util_mem::MallocSizeOfExt;
 use parking_lot::RwLock;
+use rlp::Rlp;
 
 use crate::{
 	BlockNumber,
@@ -162,6 +163,27 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 		self.traces(block_hash).map(Into::into)
 	}
 
+	/// Returns traces of a single transaction in given block. Unless the block traces are
+	/// cached, only the entry of that transaction is decoded.
+	fn single_transaction_traces(&self, block_hash: &H256, tx_position: usize) -> Option<Vec<FlatTrace>> {
+		let cached = self.traces.read().get(block_hash).map(|traces| traces.0.get(tx_position).cloned());
+		if let Some(traces) = cached {
+			self.note_trace_used(*block_hash);
+			return traces.map(Into::into);
+		}
+
+		let key = <H256 as Key<FlatBlockTraces>>::key(block_hash);
+		let encoded = self.db.key_value().get(db::COL_TRACE, key.as_bytes())
+			.expect("Low level database error. Some issue with disk?")?;
+		let block_traces = Rlp::new(&encoded);
+		if tx_position >= block_traces.item_count().expect("decode error: the db is corrupted or the data structure has changed") {
+			return None;
+		}
+		let traces: FlatTransactionTraces = block_traces.val_at(tx_position)
+			.expect("decode error: the db is corrupted or the data structure has changed");
+		Some(traces.into())
+	}
+
 	fn matching_block_traces(
 		&self,
 		filter: &Filter,
@@ -291,28 +313,25 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 			)
 	}
 
-	fn transaction_traces(&self, block_number: BlockNumber, tx_position: usize) -> Option<Vec<LocalizedTrace>> {
+	fn transaction_traces(&self, block_number: BlockNumber, tx_position: usize) -> Option<Box<dyn ExactSizeIterator<Item = LocalizedTrace>>> {
 		self.extras.block_hash(block_number)
-			.and_then(|block_hash| self.transactions_traces(&block_hash)
-				.and_then(|traces| traces.into_iter().nth(tx_position))
-				.map(Into::<Vec<FlatTrace>>::into)
+			.and_then(|block_hash| self.single_transaction_traces(&block_hash, tx_position)
 				.map(|traces| {
 					let tx_hash = self.extras.transaction_hash(block_number, tx_position)
 						.expect("Expected to find transaction hash. Database is probably corrupted");
 
-					traces
+					Box::new(traces
 						.into_iter()
-						.map(|trace| LocalizedTrace {
+						.map(move |trace| LocalizedTrace {
 							action: trace.action,
 							result: trace.result,
 							subtraces: trace.subtraces,
 							trace_address: trace.trace_address.into_iter().collect(),
 							transaction_number: Some(tx_position),
-							transaction_hash: Some(tx_hash.clone()),
+							transaction_hash: Some(tx_hash),
 							block_number,
 							block_hash,
-						})
-						.collect()
+						})) as Box<dyn ExactSizeIterator<Item = LocalizedTrace>>
 				})
 			)
 	}
@@ -612,15 +631,15 @@ mod tests {
 
 		assert_eq!(None, tracedb.block_traces(3));
 
-		let traces = tracedb.transaction_traces(1, 0).unwrap();
+		let traces: Vec<_> = tracedb.transaction_traces(1, 0).unwrap().collect();
 		assert_eq!(traces.len(), 1);
 		assert_eq!(traces[0], create_simple_localized_trace(1, block_1.clone(), tx_1.clone()));
 
-		let traces = tracedb.transaction_traces(2, 0).unwrap();
+		let traces: Vec<_> = tracedb.transaction_traces(2, 0).unwrap().collect();
 		assert_eq!(traces.len(), 1);
 		assert_eq!(traces[0], create_simple_localized_trace(2, block_2.clone(), tx_2.clone()));
 
-		assert_eq!(None, tracedb.transaction_traces(2, 1));
+		assert!(tracedb.transaction_traces(2, 1).is_none());
 
 		assert_eq!(tracedb.trace(1, 0, vec![]).unwrap(), create_simple_localized_trace(1, block_1.clone(), tx_1.clone()));
 		assert_eq!(tracedb.trace(2, 0, vec![]).unwrap(), create_simple_localized_trace(2, block_2.clone(), tx_2.clone()));
@@ -655,7 +674,8 @@ mod tests {
 		{
 			let tracedb = TraceDB::new(config.clone(), db.clone(), Arc::new(extras));
 			let traces = tracedb.transaction_traces(1, 0);
-			assert_eq!(traces.unwrap(), vec![create_simple_localized_trace(1, block_0, tx_0)]);
+			assert_eq!(traces.unwrap().collect::<Vec<_>>(), vec![create_simple_localized_trace(1, block_0, tx_0)]);
+			assert!(tracedb.transaction_traces(1, 1).is_none());
 		}
 	}
 
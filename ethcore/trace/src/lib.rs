@@ -30,7 +30,7 @@ mod types;
 pub use crate::{
 	config::Config,
 	db::{TraceDB, DatabaseExtras},
-	localized::LocalizedTrace,
+	localized::{LocalizedTrace, TracePage},
 	executive_tracer::{ExecutiveTracer, ExecutiveVMTracer},
 	import::ImportRequest,
 	noop_tracer::{NoopTracer, NoopVMTracer},
@@ -119,8 +119,8 @@ pub trait Database {
 	/// Returns localized trace at given position.
 	fn trace(&self, block_number: BlockNumber, tx_position: usize, trace_position: Vec<usize>) -> Option<LocalizedTrace>;
 
-	/// Returns localized traces created by a single transaction.
-	fn transaction_traces(&self, block_number: BlockNumber, tx_position: usize) -> Option<Vec<LocalizedTrace>>;
+	/// Returns localized traces created by a single transaction, in execution order.
+	fn transaction_traces(&self, block_number: BlockNumber, tx_position: usize) -> Option<Box<dyn ExactSizeIterator<Item = LocalizedTrace>>>;
 
 	/// Returns localized traces created in given block.
 	fn block_traces(&self, block_number: BlockNumber) -> Option<Vec<LocalizedTrace>>;
@@ -53,6 +53,8 @@ pub enum Error {
 	OutOfBounds,
 	/// Execution has been reverted with REVERT instruction.
 	Reverted,
+	/// Contract creation code exceeded the init code size limit.
+	InitCodeTooLarge,
 }
 
 impl<'a> From<&'a VmError> for Error {
@@ -72,6 +74,7 @@ impl<'a> From<&'a VmError> for Error {
 			VmError::Reverted => Error::Reverted,
 			VmError::SubStackUnderflow { .. } => Error::SubStackUnderflow,
 			VmError::OutOfSubStack { .. } => Error::OutOfSubStack,
+			VmError::InitCodeTooLarge { .. } => Error::InitCodeTooLarge,
 		}
 	}
 }
@@ -100,6 +103,7 @@ impl fmt::Display for Error {
 			Reverted => "Reverted",
 			SubStackUnderflow => "Subroutine stack underflow",
 			OutOfSubStack => "Subroutine stack overflow",
+			InitCodeTooLarge => "Init code too large",
 		};
 		message.fmt(f)
 	}
@@ -123,6 +127,7 @@ impl Encodable for Error {
 			SubStackUnderflow => 11,
 			OutOfSubStack => 12,
 			InvalidSubEntry => 13,
+			InitCodeTooLarge => 14,
 		};
 
 		s.append_internal(&value);
@@ -148,6 +153,7 @@ impl Decodable for Error {
 			11 => Ok(SubStackUnderflow),
 			12 => Ok(OutOfSubStack),
 			13 => Ok(InvalidSubEntry),
+			14 => Ok(InitCodeTooLarge),
 			_ => Err(DecoderError::Custom("Invalid error type")),
 		}
 	}
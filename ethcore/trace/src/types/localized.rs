@@ -44,3 +44,23 @@ pub struct LocalizedTrace {
 	/// Block hash.
 	pub block_hash: H256,
 }
+
+/// A page of the traces created by a single transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TracePage {
+	/// Traces on this page, in the same order as in the full list.
+	pub traces: Vec<LocalizedTrace>,
+	/// Number of traces of the transaction across all pages.
+	pub total: usize,
+}
+
+impl TracePage {
+	/// Take up to `count` traces, skipping the first `after`.
+	pub fn new<I>(traces: I, after: usize, count: usize) -> Self where I: ExactSizeIterator<Item = LocalizedTrace> {
+		let total = traces.len();
+		TracePage {
+			traces: traces.skip(after).take(count).collect(),
+			total,
+		}
+	}
+}
@@ -14,15 +14,35 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::Arc;
+
 use trie_db::TrieFactory;
 use ethtrie::Layout;
-use account_db::Factory as AccountFactory;
+use account_db::{Factory as AccountFactory, PreimageRecorder};
 use evm::{Factory as EvmFactory};
 use vm::{Exec, ActionParams, VersionedSchedule, Schedule};
 use wasm::WasmInterpreter;
 
 const WASM_MAGIC_NUMBER: &'static [u8; 4] = b"\0asm";
 
+/// Kind of virtual machine used to execute code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VmKind {
+	/// Picked by code version, unversioned code is executed as WASM
+	/// only if it starts with the WASM magic number
+	Auto,
+	/// Always executed by the EVM
+	Evm,
+	/// Always executed by the WASM interpreter
+	Wasm,
+}
+
+impl Default for VmKind {
+	fn default() -> Self {
+		VmKind::Auto
+	}
+}
+
 /// Virtual machine factory
 #[derive(Default, Clone)]
 pub struct VmFactory {
@@ -31,27 +51,48 @@ pub struct VmFactory {
 
 impl VmFactory {
 	pub fn create(&self, params: ActionParams, schedule: &Schedule, depth: usize) -> Option<Box<dyn Exec>> {
-		if params.code_version.is_zero() {
-			Some(if schedule.wasm.is_some() && schedule.versions.is_empty() && params.code.as_ref().map_or(false, |code| code.len() > 4 && &code[0..4] == WASM_MAGIC_NUMBER) {
-				Box::new(WasmInterpreter::new(params))
-			} else {
-				self.evm.create(params, schedule, depth)
-			})
-		} else {
-			let version_config = schedule.versions.get(&params.code_version);
-
-			match version_config {
-				Some(VersionedSchedule::PWasm) => {
-					Some(Box::new(WasmInterpreter::new(params)))
-				},
+		self.create_with_kind(params, schedule, depth, VmKind::Auto)
+	}
+
+	/// Create a virtual machine of the given kind. Returns `None` if the code version is unknown
+	/// or if WASM is forced while the schedule has no WASM costs.
+	pub fn create_with_kind(&self, params: ActionParams, schedule: &Schedule, depth: usize, kind: VmKind) -> Option<Box<dyn Exec>> {
+		match Self::resolve_kind(&params, schedule, kind)? {
+			VmKind::Wasm => Some(Box::new(WasmInterpreter::new(params))),
+			_ => Some(self.evm.create(params, schedule, depth)),
+		}
+	}
+
+	/// Resolve the kind of virtual machine which should execute the code, never `VmKind::Auto`.
+	fn resolve_kind(params: &ActionParams, schedule: &Schedule, kind: VmKind) -> Option<VmKind> {
+		match kind {
+			VmKind::Evm => Some(VmKind::Evm),
+			VmKind::Wasm if schedule.wasm.is_some() => Some(VmKind::Wasm),
+			VmKind::Wasm => None,
+			VmKind::Auto if params.code_version.is_zero() => {
+				Some(if schedule.wasm.is_some() && schedule.versions.is_empty() && params.code.as_ref().map_or(false, |code| code.len() > 4 && &code[0..4] == WASM_MAGIC_NUMBER) {
+					VmKind::Wasm
+				} else {
+					VmKind::Evm
+				})
+			},
+			VmKind::Auto => match schedule.versions.get(&params.code_version) {
+				Some(VersionedSchedule::PWasm) => Some(VmKind::Wasm),
 				None => None,
-			}
+			},
 		}
 	}
 
 	pub fn new(cache_size: usize) -> Self {
 		VmFactory { evm: EvmFactory::new(cache_size) }
 	}
+
+	/// Create a factory whose EVM interpreters reuse stack and memory buffers of finished
+	/// executions, keeping at most `pool_size` of them. See `evm::Factory::with_pool`.
+	/// WASM interpreters are never pooled.
+	pub fn with_pool(cache_size: usize, pool_size: usize) -> Self {
+		VmFactory { evm: EvmFactory::with_pool(cache_size, pool_size) }
+	}
 }
 
 impl From<EvmFactory> for VmFactory {
@@ -69,4 +110,72 @@ pub struct Factories {
 	pub trie: TrieFactory<Layout>,
 	/// factory for account databases.
 	pub accountdb: AccountFactory,
+	/// recorder of address and storage key preimages, if enabled.
+	pub preimages: Option<Arc<PreimageRecorder>>,
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use vm::{ActionParams, Schedule, VersionedSchedule};
+	use super::{VmFactory, VmKind};
+
+	fn wasm_schedule() -> Schedule {
+		let mut schedule = Schedule::new_istanbul();
+		schedule.wasm = Some(Default::default());
+		schedule
+	}
+
+	fn params_with_code(code: &[u8]) -> ActionParams {
+		ActionParams {
+			code: Some(Arc::new(code.to_vec())),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn auto_kind_sniffs_wasm_magic_number() {
+		let schedule = wasm_schedule();
+		let wasm = params_with_code(b"\0asm\x01\0\0\0");
+		let evm = params_with_code(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+
+		assert_eq!(VmFactory::resolve_kind(&wasm, &schedule, VmKind::Auto), Some(VmKind::Wasm));
+		assert_eq!(VmFactory::resolve_kind(&evm, &schedule, VmKind::Auto), Some(VmKind::Evm));
+		assert_eq!(VmFactory::resolve_kind(&wasm, &Schedule::new_istanbul(), VmKind::Auto), Some(VmKind::Evm));
+	}
+
+	#[test]
+	fn auto_kind_follows_code_version() {
+		let mut schedule = wasm_schedule();
+		schedule.versions.insert(1.into(), VersionedSchedule::PWasm);
+		let mut params = params_with_code(&[0x60, 0x00]);
+
+		params.code_version = 1.into();
+		assert_eq!(VmFactory::resolve_kind(&params, &schedule, VmKind::Auto), Some(VmKind::Wasm));
+
+		params.code_version = 2.into();
+		assert_eq!(VmFactory::resolve_kind(&params, &schedule, VmKind::Auto), None);
+		assert!(VmFactory::default().create_with_kind(params, &schedule, 0, VmKind::Auto).is_none());
+	}
+
+	#[test]
+	fn evm_kind_overrides_wasm_magic_number() {
+		let schedule = wasm_schedule();
+		let params = params_with_code(b"\0asm\x01\0\0\0");
+
+		assert_eq!(VmFactory::resolve_kind(&params, &schedule, VmKind::Evm), Some(VmKind::Evm));
+		assert!(VmFactory::default().create_with_kind(params, &schedule, 0, VmKind::Evm).is_some());
+	}
+
+	#[test]
+	fn wasm_kind_overrides_missing_magic_number() {
+		let schedule = wasm_schedule();
+		let params = params_with_code(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+
+		assert_eq!(VmFactory::resolve_kind(&params, &schedule, VmKind::Wasm), Some(VmKind::Wasm));
+		assert!(VmFactory::default().create_with_kind(params.clone(), &schedule, 0, VmKind::Wasm).is_some());
+
+		// WASM can't be executed without WASM costs in the schedule
+		assert_eq!(VmFactory::resolve_kind(&params, &Schedule::new_istanbul(), VmKind::Wasm), None);
+	}
 }
@@ -161,3 +161,44 @@ impl NewBlocks {
 		}
 	}
 }
+
+/// A change of the canonical chain recorded by the client. Events are numbered in the
+/// order they were imported, see `BlockChainClient::chain_events_since`.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct ChainEvent {
+	/// Position of the event, increasing by one with every recorded event.
+	pub sequence: u64,
+	/// Blocks which became canonical.
+	pub enacted: Vec<H256>,
+	/// Blocks which stopped being canonical.
+	pub retracted: Vec<H256>,
+}
+
+/// Errors returned when replaying chain events.
+#[derive(Debug, Display, Clone, PartialEq, Eq)]
+pub enum ChainEventsError {
+	/// Some events following the requested one are no longer retained. The consumer has to
+	/// resync from the current chain and continue with the events after `latest`.
+	#[display(fmt = "Chain events after #{} were evicted (oldest retained: #{}), resync and continue after #{}", requested, oldest, latest)]
+	GapTooLarge {
+		/// Sequence number the consumer asked to continue after.
+		requested: u64,
+		/// Oldest retained sequence number.
+		oldest: u64,
+		/// Latest recorded sequence number.
+		latest: u64,
+	},
+	/// The requested sequence number was never recorded, e.g. because the database was
+	/// reset since. The consumer has to resync from the current chain and continue with
+	/// the events after `latest`.
+	#[display(fmt = "Chain event #{} was never recorded (latest: #{}), resync and continue after #{}", requested, latest, latest)]
+	AheadOfLatest {
+		/// Sequence number the consumer asked to continue after.
+		requested: u64,
+		/// Latest recorded sequence number.
+		latest: u64,
+	},
+	/// Chain events are not recorded, e.g. because their depth is configured to zero.
+	#[display(fmt = "Chain events are not recorded")]
+	Disabled,
+}
@@ -23,6 +23,7 @@ use std::{
 	time::Duration,
 };
 
+use bytes::Bytes;
 use ethereum_types::U256;
 
 /// Operating mode for the client.
@@ -64,6 +65,8 @@ pub struct ClientReport {
 	pub state_db_mem: usize,
 	/// I/O statistics for the state DB.
 	pub io_stats: IoStats,
+	/// Number of stored epoch transitions.
+	pub epoch_transitions: u64,
 }
 
 /// I/O statistics.
@@ -118,3 +121,38 @@ pub enum StateResult<T> {
 	/// State is some
 	Some(T),
 }
+
+/// Proof of execution of a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionProof {
+	/// Output of the call.
+	pub output: Bytes,
+	/// Database items necessary to reproduce the execution.
+	pub items: Vec<Bytes>,
+	/// Total size of `items` in bytes.
+	pub witness_size: usize,
+}
+
+/// Reasons an execution proof could not be produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProveError {
+	/// The state needed to execute the transaction is unavailable.
+	StateUnavailable,
+	/// Execution was aborted because the witness outgrew the cap.
+	WitnessTooLarge {
+		/// Witness bytes collected when execution was aborted.
+		collected: usize,
+		/// Maximum witness size, in bytes.
+		cap: usize,
+	},
+}
+
+impl Display for ProveError {
+	fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+		match *self {
+			ProveError::StateUnavailable => write!(f, "state unavailable"),
+			ProveError::WitnessTooLarge { collected, cap } =>
+				write!(f, "execution witness too large: collected {} bytes, cap is {} bytes", collected, cap),
+		}
+	}
+}
@@ -16,6 +16,8 @@
 
 //! Engine-specific parameter types.
 
+use std::time::Duration;
+
 use ethereum_types::{Address, U256, H256};
 use bytes::Bytes;
 use crate::{
@@ -25,6 +27,13 @@ use crate::{
 
 const MAX_TRANSACTION_SIZE: usize = 300 * 1024;
 
+/// Default allowed drift of header timestamps into the future, in seconds.
+pub const DEFAULT_TIMESTAMP_DRIFT_SECS: u64 = 15;
+
+/// Number of ancestors whose median timestamp a header has to exceed,
+/// if `enforce_median_past_time` is set.
+pub const MEDIAN_TIME_SPAN: usize = 11;
+
 /// Parameters common to ethereum-like blockchains.
 /// NOTE: when adding bugfix hard-fork parameters,
 /// add to `nonzero_bugfix_hard_fork`
@@ -106,6 +115,8 @@ pub struct CommonParams {
 	pub eip2200_advance_transition: BlockNumber,
 	/// Number of first block where EIP-2028 rules begin.
 	pub eip2315_transition: BlockNumber,
+	/// Number of first block where EIP-2929 warm/cold account access costs begin.
+	pub eip2929_transition: BlockNumber,
 	/// Number of first block where dust cleanup rules (EIP-168 and EIP169) begin.
 	pub dust_protection_transition: BlockNumber,
 	/// Nonce cap increase per block. Nonce cap is only checked if dust protection is enabled.
@@ -131,15 +142,30 @@ pub struct CommonParams {
 	pub max_code_size: u64,
 	/// Number of first block where max code size limit is active.
 	pub max_code_size_transition: BlockNumber,
+	/// Maximum size of contract initialization code, if limited.
+	pub max_init_code_size: Option<u64>,
+	/// Number of first block where max init code size limit is active.
+	pub max_init_code_size_transition: BlockNumber,
 	/// Transaction permission managing contract address.
 	pub transaction_permission_contract: Option<Address>,
 	/// Block at which the transaction permission contract should start being used.
 	pub transaction_permission_contract_transition: BlockNumber,
 	/// Maximum size of transaction's RLP payload
 	pub max_transaction_size: usize,
+	/// How far into the future header timestamps may be, in seconds.
+	/// `DEFAULT_TIMESTAMP_DRIFT_SECS` if not set.
+	pub timestamp_drift_secs: Option<u64>,
+	/// Require header timestamps to be greater than the median timestamp
+	/// of the last `MEDIAN_TIME_SPAN` ancestors.
+	pub enforce_median_past_time: bool,
 }
 
 impl CommonParams {
+	/// Allowed drift of header timestamps into the future.
+	pub fn timestamp_drift(&self) -> Duration {
+		Duration::from_secs(self.timestamp_drift_secs.unwrap_or(DEFAULT_TIMESTAMP_DRIFT_SECS))
+	}
+
 	/// Schedule for an EVM in the post-EIP-150-era of the Ethereum main net.
 	pub fn schedule(&self, block_number: u64) -> vm::Schedule {
 		if block_number < self.eip150_transition {
@@ -167,8 +193,18 @@ impl CommonParams {
 		}
 	}
 
+	/// Returns max init code size at given block, if limited.
+	pub fn max_init_code_size(&self, block_number: u64) -> Option<u64> {
+		if block_number >= self.max_init_code_size_transition {
+			self.max_init_code_size
+		} else {
+			None
+		}
+	}
+
 	/// Apply common spec config parameters to the schedule.
 	pub fn update_schedule(&self, block_number: u64, schedule: &mut vm::Schedule) {
+		schedule.max_init_code_size = self.max_init_code_size(block_number).map(|size| size as _);
 		schedule.have_create2 = block_number >= self.eip1014_transition;
 		schedule.have_revert = block_number >= self.eip140_transition;
 		schedule.have_static_call = block_number >= self.eip214_transition;
@@ -182,6 +218,7 @@ impl CommonParams {
 			 !(block_number >= self.eip1283_disable_transition)) ||
 			block_number >= self.eip1283_reenable_transition;
 		schedule.eip1706 = block_number >= self.eip1706_transition;
+		schedule.eip2929 = block_number >= self.eip2929_transition;
 
 		if block_number >= self.eip1884_transition {
 			schedule.have_selfbalance = true;
@@ -241,6 +278,78 @@ impl CommonParams {
 	}
 }
 
+/// Block at which a single fork transition of `CommonParams` activates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForkActivation {
+	/// Stable name of the transition, as used in chain specs.
+	pub name: &'static str,
+	/// First block where the transition is active.
+	pub block: BlockNumber,
+}
+
+impl ForkActivation {
+	/// Whether the transition is active at given block.
+	pub fn is_active(&self, block_number: BlockNumber) -> bool {
+		block_number >= self.block
+	}
+}
+
+/// Registers transition fields of `CommonParams` under their chain spec names.
+/// Every `*_transition` field must be listed here, this is checked in tests.
+macro_rules! fork_schedule {
+	($($field:ident => $name:expr,)*) => {
+		impl CommonParams {
+			/// Activation blocks of all fork transitions, in registration order.
+			/// Transitions which are never activated have `BlockNumber::max_value()` as block.
+			pub fn fork_schedule(&self) -> Vec<ForkActivation> {
+				vec![
+					$(ForkActivation { name: $name, block: self.$field },)*
+				]
+			}
+		}
+
+		#[cfg(test)]
+		const FORK_SCHEDULE_FIELDS: &[&str] = &[$(stringify!($field),)*];
+	}
+}
+
+fork_schedule! {
+	eip150_transition => "eip150Transition",
+	eip160_transition => "eip160Transition",
+	eip161abc_transition => "eip161abcTransition",
+	eip161d_transition => "eip161dTransition",
+	eip98_transition => "eip98Transition",
+	eip658_transition => "eip658Transition",
+	eip155_transition => "eip155Transition",
+	validate_receipts_transition => "validateReceiptsTransition",
+	validate_chain_id_transition => "validateChainIdTransition",
+	eip140_transition => "eip140Transition",
+	eip210_transition => "eip210Transition",
+	eip211_transition => "eip211Transition",
+	eip214_transition => "eip214Transition",
+	eip145_transition => "eip145Transition",
+	eip1052_transition => "eip1052Transition",
+	eip1283_transition => "eip1283Transition",
+	eip1283_disable_transition => "eip1283DisableTransition",
+	eip1283_reenable_transition => "eip1283ReenableTransition",
+	eip1014_transition => "eip1014Transition",
+	eip1706_transition => "eip1706Transition",
+	eip1344_transition => "eip1344Transition",
+	eip1884_transition => "eip1884Transition",
+	eip2028_transition => "eip2028Transition",
+	eip2046_transition => "eip2046Transition",
+	eip2200_advance_transition => "eip2200AdvanceTransition",
+	eip2315_transition => "eip2315Transition",
+	eip2929_transition => "eip2929Transition",
+	dust_protection_transition => "dustProtectionTransition",
+	wasm_activation_transition => "wasmActivationTransition",
+	kip4_transition => "kip4Transition",
+	kip6_transition => "kip6Transition",
+	max_code_size_transition => "maxCodeSizeTransition",
+	max_init_code_size_transition => "maxInitCodeSizeTransition",
+	transaction_permission_contract_transition => "transactionPermissionContractTransition",
+}
+
 impl From<ethjson::spec::Params> for CommonParams {
 	fn from(p: ethjson::spec::Params) -> Self {
 		CommonParams {
@@ -348,6 +457,10 @@ impl From<ethjson::spec::Params> for CommonParams {
 				BlockNumber::max_value,
 				Into::into,
 			),
+			eip2929_transition: p.eip2929_transition.map_or_else(
+				BlockNumber::max_value,
+				Into::into,
+			),
 			dust_protection_transition: p.dust_protection_transition.map_or_else(
 				BlockNumber::max_value,
 				Into::into,
@@ -360,6 +473,8 @@ impl From<ethjson::spec::Params> for CommonParams {
 			max_code_size: p.max_code_size.map_or(u64::max_value(), Into::into),
 			max_transaction_size: p.max_transaction_size.map_or(MAX_TRANSACTION_SIZE, Into::into),
 			max_code_size_transition: p.max_code_size_transition.map_or(0, Into::into),
+			max_init_code_size: p.max_init_code_size.map(Into::into),
+			max_init_code_size_transition: p.max_init_code_size_transition.map_or(0, Into::into),
 			transaction_permission_contract: p.transaction_permission_contract.map(Into::into),
 			transaction_permission_contract_transition:
 			p.transaction_permission_contract_transition.map_or(0, Into::into),
@@ -376,6 +491,166 @@ impl From<ethjson::spec::Params> for CommonParams {
 				BlockNumber::max_value,
 				Into::into
 			),
+			timestamp_drift_secs: p.timestamp_drift_secs.map(Into::into),
+			enforce_median_past_time: p.enforce_median_past_time.unwrap_or(false),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{CommonParams, FORK_SCHEDULE_FIELDS};
+
+	fn foundation_params() -> CommonParams {
+		let spec = ethjson::spec::Spec::load(&include_bytes!("../../../res/ethereum/foundation.json")[..])
+			.expect("foundation spec is valid");
+		spec.params.into()
+	}
+
+	/// Returns the names of the given transition fields, together with params where each of them
+	/// is set to a distinct block. The remaining fields are listed too: destructuring without `..`
+	/// stops compiling once a field is added to `CommonParams`, until it is classified here.
+	macro_rules! transition_fields {
+		($($transition:ident,)*) => {{
+			let CommonParams {
+				$($transition: _,)*
+				account_start_nonce: _,
+				maximum_extra_data_size: _,
+				network_id: _,
+				chain_id: _,
+				subprotocol_name: _,
+				min_gas_limit: _,
+				fork_block: _,
+				eip210_contract_address: _,
+				eip210_contract_code: _,
+				eip210_contract_gas: _,
+				nonce_cap_increment: _,
+				remove_dust_contracts: _,
+				wasm_version: _,
+				gas_limit_bound_divisor: _,
+				registrar: _,
+				node_permission_contract: _,
+				max_code_size: _,
+				max_init_code_size: _,
+				transaction_permission_contract: _,
+				max_transaction_size: _,
+				timestamp_drift_secs: _,
+				enforce_median_past_time: _,
+			} = CommonParams::default();
+
+			let mut params = CommonParams::default();
+			let mut block = 0;
+			$(
+				block += 1;
+				params.$transition = block;
+			)*
+			(vec![$(stringify!($transition),)*], params)
+		}}
+	}
+
+	#[test]
+	fn fork_schedule_registers_all_transitions() {
+		let (transitions, params) = transition_fields! {
+			eip150_transition,
+			eip160_transition,
+			eip161abc_transition,
+			eip161d_transition,
+			eip98_transition,
+			eip658_transition,
+			eip155_transition,
+			validate_receipts_transition,
+			validate_chain_id_transition,
+			eip140_transition,
+			eip210_transition,
+			eip211_transition,
+			eip214_transition,
+			eip145_transition,
+			eip1052_transition,
+			eip1283_transition,
+			eip1283_disable_transition,
+			eip1283_reenable_transition,
+			eip1014_transition,
+			eip1706_transition,
+			eip1344_transition,
+			eip1884_transition,
+			eip2028_transition,
+			eip2046_transition,
+			eip2200_advance_transition,
+			eip2315_transition,
+			eip2929_transition,
+			dust_protection_transition,
+			wasm_activation_transition,
+			kip4_transition,
+			kip6_transition,
+			max_code_size_transition,
+			max_init_code_size_transition,
+			transaction_permission_contract_transition,
+		};
+
+		for field in &transitions {
+			assert!(FORK_SCHEDULE_FIELDS.contains(field), "`{}` is missing from `fork_schedule!`", field);
+		}
+		assert_eq!(transitions.len(), FORK_SCHEDULE_FIELDS.len());
+
+		// every registered transition reads its own field.
+		let mut blocks: Vec<_> = params.fork_schedule().iter().map(|activation| activation.block).collect();
+		blocks.sort();
+		assert_eq!(blocks, (1..=transitions.len() as u64).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn fork_schedule_matches_foundation_spec() {
+		let schedule = foundation_params().fork_schedule();
+		let block = |name: &str| schedule.iter()
+			.find(|activation| activation.name == name)
+			.map(|activation| activation.block)
+			.unwrap_or_else(|| panic!("`{}` is not in the schedule", name));
+
+		// Homestead-era transitions default to genesis.
+		assert_eq!(block("validateReceiptsTransition"), 0);
+		// Tangerine Whistle
+		assert_eq!(block("eip150Transition"), 2_463_000);
+		// Spurious Dragon
+		assert_eq!(block("eip155Transition"), 2_675_000);
+		assert_eq!(block("eip160Transition"), 2_675_000);
+		assert_eq!(block("eip161abcTransition"), 2_675_000);
+		assert_eq!(block("eip161dTransition"), 2_675_000);
+		assert_eq!(block("maxCodeSizeTransition"), 2_675_000);
+		// Byzantium
+		assert_eq!(block("eip140Transition"), 4_370_000);
+		assert_eq!(block("eip211Transition"), 4_370_000);
+		assert_eq!(block("eip214Transition"), 4_370_000);
+		assert_eq!(block("eip658Transition"), 4_370_000);
+		// Constantinople
+		assert_eq!(block("eip145Transition"), 7_280_000);
+		assert_eq!(block("eip1014Transition"), 7_280_000);
+		assert_eq!(block("eip1052Transition"), 7_280_000);
+		assert_eq!(block("eip1283Transition"), 0);
+		assert_eq!(block("eip1283DisableTransition"), 0);
+		// Istanbul
+		assert_eq!(block("eip1283ReenableTransition"), 9_069_000);
+		assert_eq!(block("eip1344Transition"), 9_069_000);
+		assert_eq!(block("eip1706Transition"), 9_069_000);
+		assert_eq!(block("eip1884Transition"), 9_069_000);
+		assert_eq!(block("eip2028Transition"), 9_069_000);
+		// Never activated on the main net.
+		assert_eq!(block("eip98Transition"), u64::max_value());
+		assert_eq!(block("eip210Transition"), u64::max_value());
+		assert_eq!(block("eip2315Transition"), u64::max_value());
+		assert_eq!(block("eip2929Transition"), u64::max_value());
+		assert_eq!(block("wasmActivationTransition"), u64::max_value());
+	}
+
+	#[test]
+	fn fork_schedule_active_at_block() {
+		let active: Vec<_> = foundation_params().fork_schedule().into_iter()
+			.filter(|activation| activation.is_active(4_370_000))
+			.map(|activation| activation.name)
+			.collect();
+
+		assert!(active.contains(&"eip140Transition"));
+		assert!(active.contains(&"eip150Transition"));
+		assert!(!active.contains(&"eip145Transition"));
+		assert!(!active.contains(&"eip1884Transition"));
+	}
+}
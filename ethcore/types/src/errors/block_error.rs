@@ -95,6 +95,9 @@ pub enum BlockError {
 	/// Timestamp header field is too far in future.
 	#[display(fmt = "Future timestamp in header: {}", _0)]
 	TemporarilyInvalid(OutOfBoundsTime),
+	/// Timestamp header field isn't greater than the median timestamp of recent ancestors.
+	#[display(fmt = "Timestamp not above median of recent ancestors: {}", _0)]
+	TimestampBelowMedian(OutOfBounds<u64>),
 	/// Log bloom header field is invalid.
 	#[display(fmt = "Invalid log bloom in header: {}", _0)]
 	InvalidLogBloom(Box<Mismatch<Bloom>>),
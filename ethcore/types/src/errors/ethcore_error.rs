@@ -156,6 +156,13 @@ pub enum ExecutionError {
 	MutableCallInStaticContext,
 	/// Returned when transacting from a non-existing account with dust protection enabled.
 	SenderMustExist,
+	/// Returned when contract creation code exceeds the init code size limit.
+	InitCodeTooLarge {
+		/// Maximum allowed init code size.
+		limit: usize,
+		/// Init code size of the transaction.
+		got: usize
+	},
 	/// Returned when internal evm error occurs.
 	Internal(String),
 	/// Returned when generic transaction occurs
@@ -196,6 +203,8 @@ impl fmt::Display for ExecutionError {
 					but the sender only has {}", required, got),
 			MutableCallInStaticContext => "Mutable Call in static context".to_owned(),
 			SenderMustExist => "Transacting from an empty account".to_owned(),
+			InitCodeTooLarge { limit, got } =>
+				format!("Contract creation code is {} bytes, but the limit is {}", got, limit),
 			Internal(ref msg) => msg.clone(),
 			TransactionMalformed(ref err) => format!("Malformed transaction: {}", err),
 		};
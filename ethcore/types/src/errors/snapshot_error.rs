@@ -56,6 +56,10 @@ pub enum SnapshotError {
 	Io(::std::io::Error),
 	/// Snapshot version is not supported.
 	VersionNotSupported(u64),
+	/// Snapshot format version is not supported (found, minimal supported, maximal supported).
+	FormatNotSupported(u64, u64, u64),
+	/// Snapshot was created by a different consensus engine (found, expected).
+	WrongEngine(String, String),
 	/// Max chunk size is to small to fit basic account data.
 	ChunkTooSmall,
 	/// Oversized chunk
@@ -106,6 +110,12 @@ impl fmt::Display for SnapshotError {
 			Decoder(ref err) => err.fmt(f),
 			Trie(ref err) => err.fmt(f),
 			VersionNotSupported(ref ver) => write!(f, "Snapshot version {} is not supprted.", ver),
+			FormatNotSupported(ver, min, max) => match ver > max {
+				true => write!(f, "Snapshot format v{} unsupported (we support ≤{})", ver, max),
+				false => write!(f, "Snapshot format v{} unsupported (we support ≥{})", ver, min),
+			},
+			WrongEngine(ref found, ref expected) =>
+				write!(f, "Snapshot was created by the {} engine, expected {}", found, expected),
 			ChunkTooSmall => write!(f, "Chunk size is too small."),
 			ChunkTooLarge => write!(f, "Chunk size is too large."),
 			SnapshotsUnsupported => write!(f, "Snapshots unsupported by consensus engine."),
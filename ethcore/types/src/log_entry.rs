@@ -18,6 +18,8 @@
 
 use crate::BlockNumber;
 use std::ops::Deref;
+use ethabi::{ParamType, Token};
+use hash::keccak;
 use parity_util_mem::MallocSizeOf;
 use bytes::Bytes;
 use ethereum_types::{H256, Address, Bloom, BloomInput};
@@ -41,6 +43,24 @@ impl LogEntry {
 			b
 		})
 	}
+
+	/// Returns the topic at the given position, if any.
+	pub fn topic(&self, index: usize) -> Option<&H256> {
+		self.topics.get(index)
+	}
+
+	/// Checks whether this log was emitted by the (non-anonymous) event with the given
+	/// signature, e.g. `Transfer(address,address,uint256)`.
+	/// The signature is hashed on every call; when checking many logs compare `topic(0)`
+	/// with a precomputed hash instead.
+	pub fn matches_event(&self, signature: &str) -> bool {
+		self.topic(0).map_or(false, |topic| *topic == keccak(signature))
+	}
+
+	/// Decodes the non-indexed event parameters stored in the data field.
+	pub fn decode_data(&self, types: &[ParamType]) -> Result<Vec<Token>, ethabi::Error> {
+		ethabi::decode(types, &self.data)
+	}
 }
 
 impl From<ethjson::state::Log> for LogEntry {
@@ -82,8 +102,24 @@ impl Deref for LocalizedLogEntry {
 
 #[cfg(test)]
 mod tests {
-	use ethereum_types::{Bloom, Address};
-	use super::LogEntry;
+	use std::str::FromStr;
+	use ethabi::{ParamType, Token};
+	use ethereum_types::{Bloom, Address, H256, U256};
+	use rustc_hex::FromHex;
+	use super::{LogEntry, LocalizedLogEntry};
+
+	// Transfer of 1000 tokens from 0x7d57...9e6e to 0x82a9...42f1.
+	fn transfer_log() -> LogEntry {
+		LogEntry {
+			address: Address::from_low_u64_be(0x1234),
+			topics: vec![
+				H256::from_str("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").unwrap(),
+				H256::from_str("0000000000000000000000007d577a597b2742b498cb5cf0c26cdcd726d39e6e").unwrap(),
+				H256::from_str("00000000000000000000000082a978b3f5962a5b0957d9ee9eef472ee55b42f1").unwrap(),
+			],
+			data: "00000000000000000000000000000000000000000000000000000000000003e8".from_hex().unwrap(),
+		}
+	}
 
 	#[test]
 	fn test_empty_log_bloom() {
@@ -96,4 +132,56 @@ mod tests {
 		};
 		assert_eq!(log.bloom(), bloom);
 	}
+
+	#[test]
+	fn should_match_transfer_event() {
+		let log = transfer_log();
+		assert!(log.matches_event("Transfer(address,address,uint256)"));
+		assert!(!log.matches_event("Approval(address,address,uint256)"));
+		assert_eq!(log.topic(1), Some(&H256::from_str("0000000000000000000000007d577a597b2742b498cb5cf0c26cdcd726d39e6e").unwrap()));
+		assert_eq!(log.topic(3), None);
+
+		let decoded = log.decode_data(&[ParamType::Uint(256)]).unwrap();
+		assert_eq!(decoded, vec![Token::Uint(U256::from(1000))]);
+	}
+
+	#[test]
+	fn should_match_validator_set_event_through_localized_entry() {
+		// InitiateChange(bytes32 indexed _parent_hash, address[] _new_set)
+		let parent_hash = H256::from_low_u64_be(0xabcd);
+		let new_set = vec![
+			Address::from_str("7d577a597b2742b498cb5cf0c26cdcd726d39e6e").unwrap(),
+			Address::from_str("82a978b3f5962a5b0957d9ee9eef472ee55b42f1").unwrap(),
+		];
+		let data = "\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000002\
+			0000000000000000000000007d577a597b2742b498cb5cf0c26cdcd726d39e6e\
+			00000000000000000000000082a978b3f5962a5b0957d9ee9eef472ee55b42f1";
+		let log = LocalizedLogEntry {
+			entry: LogEntry {
+				address: Address::from_low_u64_be(0x1234),
+				topics: vec![
+					H256::from_str("55252fa6eee4741b4e24a74a70e9c11fd2c2281df8d6ea13126ff845f7825c89").unwrap(),
+					parent_hash,
+				],
+				data: data.from_hex().unwrap(),
+			},
+			..Default::default()
+		};
+
+		assert!(log.matches_event("InitiateChange(bytes32,address[])"));
+		assert!(!log.matches_event("Transfer(address,address,uint256)"));
+		assert_eq!(log.topic(1), Some(&parent_hash));
+
+		let decoded = log.decode_data(&[ParamType::Array(Box::new(ParamType::Address))]).unwrap();
+		assert_eq!(decoded, vec![Token::Array(new_set.into_iter().map(Token::Address).collect())]);
+	}
+
+	#[test]
+	fn should_not_match_anonymous_log() {
+		let log = LogEntry::default();
+		assert!(!log.matches_event("Transfer(address,address,uint256)"));
+		assert!(log.decode_data(&[ParamType::Uint(256)]).is_err());
+	}
 }
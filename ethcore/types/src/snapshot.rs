@@ -20,7 +20,7 @@ use std::time::Instant;
 
 use bytes::Bytes;
 use ethereum_types::H256;
-use rlp::{Rlp, RlpStream, DecoderError};
+use rlp::{Decodable, Encodable, Rlp, RlpStream, DecoderError};
 
 /// Modes of snapshotting
 pub enum Snapshotting {
@@ -107,6 +107,42 @@ impl Progress {
 	}
 }
 
+/// Compression algorithm applied to snapshot chunks. Chunk hashes are taken
+/// over the compressed bytes regardless of the algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkCompression {
+	/// Snappy, understood by all clients.
+	Snappy,
+	/// Zstandard, smaller chunks at a similar CPU cost.
+	Zstd,
+}
+
+impl Default for ChunkCompression {
+	fn default() -> Self {
+		ChunkCompression::Snappy
+	}
+}
+
+impl Encodable for ChunkCompression {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		let id: u8 = match *self {
+			ChunkCompression::Snappy => 0,
+			ChunkCompression::Zstd => 1,
+		};
+		s.append(&id);
+	}
+}
+
+impl Decodable for ChunkCompression {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		match rlp.as_val::<u8>()? {
+			0 => Ok(ChunkCompression::Snappy),
+			1 => Ok(ChunkCompression::Zstd),
+			_ => Err(DecoderError::Custom("Unknown snapshot chunk compression.")),
+		}
+	}
+}
+
 /// Manifest data.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ManifestData {
@@ -122,26 +158,42 @@ pub struct ManifestData {
 	pub block_number: u64,
 	/// Block hash this snapshot was taken at.
 	pub block_hash: H256,
+	/// Name of the consensus engine which created the snapshot, empty if unknown.
+	pub engine: String,
+	/// Compression of the state and block chunks.
+	pub compression: ChunkCompression,
 }
 
 impl ManifestData {
 	/// Encode the manifest data to rlp.
+	///
+	/// The compression field is only written for non-snappy manifests, so snappy
+	/// manifests stay byte-identical to those of older clients.
 	pub fn into_rlp(self) -> Bytes {
-		let mut stream = RlpStream::new_list(6);
+		let with_compression = self.compression != ChunkCompression::Snappy;
+		let mut stream = RlpStream::new_list(if with_compression { 8 } else { 7 });
 		stream.append(&self.version);
 		stream.append_list(&self.state_hashes);
 		stream.append_list(&self.block_hashes);
 		stream.append(&self.state_root);
 		stream.append(&self.block_number);
 		stream.append(&self.block_hash);
+		stream.append(&self.engine);
+		if with_compression {
+			stream.append(&self.compression);
+		}
 
 		stream.out()
 	}
 
 	/// Try to restore manifest data from raw bytes, interpreted as RLP.
+	///
+	/// Manifests without a version are version 1, the engine and the chunk compression
+	/// are trailing fields which manifests created by older versions lack.
 	pub fn from_rlp(raw: &[u8]) -> Result<Self, DecoderError> {
 		let decoder = Rlp::new(raw);
-		let (start, version) = if decoder.item_count()? == 5 {
+		let item_count = decoder.item_count()?;
+		let (start, version) = if item_count == 5 {
 			(0, 1)
 		} else {
 			(1, decoder.val_at(0)?)
@@ -152,6 +204,8 @@ impl ManifestData {
 		let state_root: H256 = decoder.val_at(start + 2)?;
 		let block_number: u64 = decoder.val_at(start + 3)?;
 		let block_hash: H256 = decoder.val_at(start + 4)?;
+		let engine: String = if item_count > start + 5 { decoder.val_at(start + 5)? } else { String::new() };
+		let compression = if item_count > start + 6 { decoder.val_at(start + 6)? } else { ChunkCompression::Snappy };
 
 		Ok(ManifestData {
 			version,
@@ -160,6 +214,8 @@ impl ManifestData {
 			state_root,
 			block_number,
 			block_hash,
+			engine,
+			compression,
 		})
 	}
 }
@@ -16,7 +16,7 @@
 
 use std::{fmt, error};
 
-use ethereum_types::U256;
+use ethereum_types::{Address, H256, U256};
 use parity_crypto::publickey::{Error as EthPublicKeyCryptoError};
 use unexpected::OutOfBounds;
 
@@ -38,6 +38,13 @@ pub enum Error {
 		/// Transaction gas price
 		got: U256,
 	},
+	/// Transaction's gas price is below the pool's configured floor.
+	GasPriceBelowFloor {
+		/// Minimal gas price accepted by the pool
+		floor: U256,
+		/// Transaction gas price
+		got: U256,
+	},
 	/// Transaction has too low fee
 	/// (there is already a transaction with the same sender-nonce but higher gas price)
 	TooCheapToReplace {
@@ -79,10 +86,19 @@ pub enum Error {
 	InvalidChainId,
 	/// Not enough permissions given by permission contract.
 	NotAllowed,
+	/// Sender is not permitted to submit transactions to this node.
+	SenderNotPermitted,
 	/// Signature error
 	InvalidSignature(String),
 	/// Transaction too big
 	TooBig,
+	/// Contract creation code exceeds the init code size limit.
+	InitCodeTooLarge {
+		/// Maximum allowed init code size
+		limit: usize,
+		/// Init code size of the transaction
+		got: usize,
+	},
 	/// Invalid RLP encoding
 	InvalidRlp(String),
 }
@@ -112,6 +128,8 @@ impl fmt::Display for Error {
 			LimitReached => "Transaction limit reached".into(),
 			InsufficientGasPrice { minimal, got } =>
 				format!("Insufficient gas price. Min={}, Given={}", minimal, got),
+			GasPriceBelowFloor { floor, got } =>
+				format!("Gas price below floor. Floor={}, Given={}", floor, got),
 			InsufficientGas { minimal, got } =>
 				format!("Insufficient gas. Min={}, Given={}", minimal, got),
 			InsufficientBalance { balance, cost } =>
@@ -126,7 +144,10 @@ impl fmt::Display for Error {
 			InvalidChainId => "Transaction of this chain ID is not allowed on this chain.".into(),
 			InvalidSignature(ref err) => format!("Transaction has invalid signature: {}.", err),
 			NotAllowed => "Sender does not have permissions to execute this type of transaction".into(),
+			SenderNotPermitted => "Sender is not permitted to submit transactions to this node".into(),
 			TooBig => "Transaction too big".into(),
+			InitCodeTooLarge { limit, got } =>
+				format!("Init code too large. Limit={}, Given={}", limit, got),
 			InvalidRlp(ref err) => format!("Transaction has invalid RLP structure: {}.", err),
 		};
 
@@ -140,6 +161,42 @@ impl error::Error for Error {
 	}
 }
 
+/// Errors concerning access list validation.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AccessListError {
+	/// Address is listed more than once.
+	DuplicateAddress(Address),
+	/// Storage key is listed more than once for the same address.
+	DuplicateStorageKey {
+		/// Address the key belongs to.
+		address: Address,
+		/// Repeated storage key.
+		key: H256,
+	},
+	/// Zero address is not allowed in an access list.
+	ZeroAddress,
+}
+
+impl fmt::Display for AccessListError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::AccessListError::*;
+		let msg = match *self {
+			DuplicateAddress(ref address) => format!("Duplicate address {:?}", address),
+			DuplicateStorageKey { ref address, ref key } =>
+				format!("Duplicate storage key {:?} for address {:?}", key, address),
+			ZeroAddress => "Zero address".into(),
+		};
+
+		f.write_fmt(format_args!("Invalid access list ({})", msg))
+	}
+}
+
+impl error::Error for AccessListError {
+	fn description(&self) -> &str {
+		"Invalid access list"
+	}
+}
+
 /// Result of executing the transaction.
 #[derive(PartialEq, Debug, Clone)]
 pub enum CallError {
@@ -19,5 +19,5 @@
 mod error;
 mod transaction;
 
-pub use self::error::{Error, CallError};
+pub use self::error::{AccessListError, Error, CallError};
 pub use self::transaction::*;
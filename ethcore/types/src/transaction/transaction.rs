@@ -16,6 +16,7 @@
 
 //! Transaction data structure.
 
+use std::collections::HashSet;
 use std::ops::Deref;
 
 use ethereum_types::{H256, H160, Address, U256, BigEndianHash};
@@ -74,6 +75,87 @@ impl rlp::Encodable for Action {
 	}
 }
 
+/// Single entry of an EIP-2930 access list: an address and storage keys accessed under it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, MallocSizeOf)]
+pub struct AccessListItem {
+	/// Accessed address.
+	pub address: Address,
+	/// Accessed storage keys.
+	pub storage_keys: Vec<H256>,
+}
+
+impl rlp::Decodable for AccessListItem {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		if rlp.item_count()? != 2 {
+			return Err(DecoderError::RlpIncorrectListLen);
+		}
+
+		Ok(AccessListItem {
+			address: rlp.val_at(0)?,
+			storage_keys: rlp.list_at(1)?,
+		})
+	}
+}
+
+impl rlp::Encodable for AccessListItem {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2);
+		s.append(&self.address);
+		s.append_list(&self.storage_keys);
+	}
+}
+
+/// EIP-2930 access list.
+#[derive(Debug, Default, Clone, PartialEq, Eq, MallocSizeOf)]
+pub struct AccessList(pub Vec<AccessListItem>);
+
+impl AccessList {
+	/// Check that addresses are non-zero and neither addresses nor
+	/// storage keys of the same address are repeated.
+	pub fn validate(&self) -> Result<(), error::AccessListError> {
+		let mut addresses = HashSet::with_capacity(self.0.len());
+		for item in &self.0 {
+			if item.address.is_zero() {
+				return Err(error::AccessListError::ZeroAddress);
+			}
+			if !addresses.insert(item.address) {
+				return Err(error::AccessListError::DuplicateAddress(item.address));
+			}
+
+			let mut keys = HashSet::with_capacity(item.storage_keys.len());
+			for key in &item.storage_keys {
+				if !keys.insert(key) {
+					return Err(error::AccessListError::DuplicateStorageKey {
+						address: item.address,
+						key: *key,
+					});
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+impl Deref for AccessList {
+	type Target = [AccessListItem];
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+impl rlp::Decodable for AccessList {
+	fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+		Ok(AccessList(rlp.as_list()?))
+	}
+}
+
+impl rlp::Encodable for AccessList {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.append_list(&self.0);
+	}
+}
+
 /// Transaction activation condition.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Condition {
@@ -533,6 +615,7 @@ mod tests {
 	use super::*;
 	use ethereum_types::{U256, Address};
 	use hash::keccak;
+	use crate::transaction::AccessListError;
 	use rustc_hex::FromHex;
 
 	#[test]
@@ -675,4 +758,42 @@ mod tests {
 		test_vector("f867088504a817c8088302e2489435353535353535353535353535353535353535358202008025a064b1702d9298fee62dfeccc57d322a463ad55ca201256d01f62b45b2e1c21c12a064b1702d9298fee62dfeccc57d322a463ad55ca201256d01f62b45b2e1c21c10", "0x9bddad43f934d313c2b79ca28a432dd2b7281029");
 		test_vector("f867098504a817c809830334509435353535353535353535353535353535353535358202d98025a052f8f61201b2b11a78d6e866abc9c3db2ae8631fa656bfe5cb53668255367afba052f8f61201b2b11a78d6e866abc9c3db2ae8631fa656bfe5cb53668255367afb", "0x3c24d7329e92f84f08556ceb6df1cdb0104ca49f");
 	}
+
+	fn access_list_item(address: u64, keys: &[u64]) -> AccessListItem {
+		AccessListItem {
+			address: Address::from_low_u64_be(address),
+			storage_keys: keys.iter().map(|k| H256::from_low_u64_be(*k)).collect(),
+		}
+	}
+
+	#[test]
+	fn should_validate_access_list() {
+		let list = AccessList(vec![access_list_item(1, &[1, 2]), access_list_item(2, &[1])]);
+		assert_eq!(list.validate(), Ok(()));
+		assert_eq!(AccessList::default().validate(), Ok(()));
+
+		let list = AccessList(vec![access_list_item(1, &[1]), access_list_item(1, &[2])]);
+		assert_eq!(list.validate(), Err(AccessListError::DuplicateAddress(Address::from_low_u64_be(1))));
+
+		let list = AccessList(vec![access_list_item(1, &[1, 2, 1])]);
+		assert_eq!(list.validate(), Err(AccessListError::DuplicateStorageKey {
+			address: Address::from_low_u64_be(1),
+			key: H256::from_low_u64_be(1),
+		}));
+
+		let list = AccessList(vec![access_list_item(0, &[])]);
+		assert_eq!(list.validate(), Err(AccessListError::ZeroAddress));
+	}
+
+	#[test]
+	fn should_encode_and_decode_access_list() {
+		let list = AccessList(vec![access_list_item(1, &[1, 2]), access_list_item(2, &[])]);
+		let encoded = rlp::encode(&list);
+		assert_eq!(rlp::decode::<AccessList>(&encoded), Ok(list));
+
+		// address must be 20 bytes long
+		let mut s = RlpStream::new_list(1);
+		s.begin_list(2).append(&vec![1u8; 19]).begin_list(0);
+		assert!(rlp::decode::<AccessList>(&s.out()).is_err());
+	}
 }
@@ -38,6 +38,8 @@ pub struct VerificationQueueInfo {
 	pub max_mem_use: usize,
 	/// Heap memory used in bytes
 	pub mem_used: usize,
+	/// Number of imports rejected early because the item was already queued
+	pub duplicates_rejected: usize,
 }
 
 impl VerificationQueueInfo {
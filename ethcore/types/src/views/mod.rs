@@ -31,11 +31,32 @@ pub use self::transaction::TransactionView;
 
 #[cfg(test)]
 mod tests {
-	use super::HeaderView;
+	use rlp::RlpStream;
+	use super::{HeaderView, ViewRlp};
 
 	#[test]
 	#[should_panic]
 	fn should_include_file_line_number_in_panic_for_invalid_rlp() {
 		let _ = view!(HeaderView, &[]).parent_hash();
 	}
+
+	#[test]
+	fn should_borrow_byte_string_payload() {
+		let encoded = rlp::encode(&"cat");
+		let rlp = ViewRlp::new(&encoded, file!(), line!());
+		assert_eq!(rlp.as_bytes(), Some(&b"cat"[..]));
+		assert_eq!(rlp.decode_str(), Ok("cat"));
+
+		let encoded = rlp::encode(&vec![0xffu8, 0xfe]);
+		let rlp = ViewRlp::new(&encoded, file!(), line!());
+		assert_eq!(rlp.as_bytes(), Some(&[0xffu8, 0xfe][..]));
+		assert!(rlp.decode_str().is_err());
+
+		let mut stream = RlpStream::new_list(2);
+		stream.append(&"cat").append(&"dog");
+		let encoded = stream.out();
+		let rlp = ViewRlp::new(&encoded, file!(), line!());
+		assert_eq!(rlp.as_bytes(), None);
+		assert!(rlp.decode_str().is_err());
+	}
 }
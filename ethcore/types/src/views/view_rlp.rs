@@ -16,6 +16,8 @@
 
 //! Wrapper for view rlp expected to be valid with debug info
 
+use std::str;
+
 use rlp::{Rlp, Decodable, DecoderError};
 
 /// Wrapper for trusted rlp, which is expected to be valid, for use in views
@@ -96,6 +98,17 @@ impl<'a, 'view> ViewRlp<'a> where 'a : 'view {
 	pub fn as_raw(&'view self) -> &'a [u8] {
 		self.rlp.as_raw()
 	}
+
+	/// Returns payload of the rlp byte string without copying, `None` if it is a list
+	pub fn as_bytes(&self) -> Option<&'a [u8]> {
+		self.rlp.data().ok()
+	}
+
+	/// Returns payload of the rlp byte string as UTF-8 string without copying
+	pub fn decode_str(&self) -> Result<&'a str, DecoderError> {
+		let bytes = self.rlp.data()?;
+		str::from_utf8(bytes).map_err(|_| DecoderError::Custom("Invalid UTF-8 string"))
+	}
 }
 
 /// Iterator over rlp-slice list elements.
@@ -199,7 +199,7 @@ pub mod headers {
 			check_seal: bool
 		) -> Result<Self::Unverified, (Error, Option<Self::Input>)> {
 			let res = verify_header_params(&input, engine, check_seal)
-				.and_then(|_| verify_header_time(&input));
+				.and_then(|_| verify_header_time(&input, engine));
 
 			match res {
 				Ok(_) => Ok(input),
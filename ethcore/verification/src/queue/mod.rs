@@ -146,6 +146,7 @@ pub struct VerificationQueue<K: Kind, C: 'static> {
 	ready_signal: Arc<QueueSignal<C>>,
 	empty: Arc<Condvar>,
 	processing: RwLock<HashMap<H256, (U256, H256)>>, // item's hash to difficulty and parent item hash
+	duplicates_rejected: AtomicUsize,
 	ticks_since_adjustment: AtomicUsize,
 	max_queue_size: usize,
 	max_mem_use: usize,
@@ -281,6 +282,7 @@ impl<K: Kind, C> VerificationQueue<K, C> {
 			verification,
 			deleting,
 			processing: RwLock::new(HashMap::new()),
+			duplicates_rejected: AtomicUsize::new(0),
 			empty,
 			ticks_since_adjustment: AtomicUsize::new(0),
 			max_queue_size: cmp::max(config.max_queue_size, MIN_QUEUE_LIMIT),
@@ -477,11 +479,13 @@ impl<K: Kind, C> VerificationQueue<K, C> {
 		let hash = input.hash();
 		let raw_hash = input.raw_hash();
 		{
-			if self.processing.read().contains_key(&hash) {
+			let mut bad = self.verification.bad.lock();
+			let mut processing = self.processing.write();
+			if processing.contains_key(&hash) {
+				self.duplicates_rejected.fetch_add(1, AtomicOrdering::Relaxed);
 				return Err((Error::Import(ImportError::AlreadyQueued), Some(input)));
 			}
 
-			let mut bad = self.verification.bad.lock();
 			if bad.contains(&hash) || bad.contains(&raw_hash)  {
 				return Err((Error::Import(ImportError::KnownBad), Some(input)));
 			}
@@ -490,13 +494,16 @@ impl<K: Kind, C> VerificationQueue<K, C> {
 				bad.insert(hash);
 				return Err((Error::Import(ImportError::KnownBad), Some(input)));
 			}
+
+			// Reserve the hash before verifying, so that copies of the same item
+			// arriving concurrently (e.g. from different peers) are rejected right away.
+			// The difficulty is accounted for once the item gets into the queue.
+			processing.insert(hash, (U256::zero(), input.parent_hash()));
 		}
 
 		match K::create(input, &*self.engine, self.verification.check_seal) {
 			Ok(item) => {
-				if self.processing.write().insert(hash, (item.difficulty(), item.parent_hash())).is_some() {
-					return Err((Error::Import(ImportError::AlreadyQueued), None));
-				}
+				self.processing.write().insert(hash, (item.difficulty(), item.parent_hash()));
 				self.verification.sizes.unverified.fetch_add(item.malloc_size_of(), AtomicOrdering::SeqCst);
 				{
 					let mut td = self.total_difficulty.write();
@@ -507,6 +514,7 @@ impl<K: Kind, C> VerificationQueue<K, C> {
 				Ok(hash)
 			},
 			Err((err, input)) => {
+				self.processing.write().remove(&hash);
 				match err {
 					// Don't mark future blocks as bad.
 					Error::Block(BlockError::TemporarilyInvalid(_)) => {},
@@ -652,7 +660,8 @@ impl<K: Kind, C> VerificationQueue<K, C> {
 			max_mem_use: self.max_mem_use,
 			mem_used: unverified_bytes
 					   + verifying_bytes
-					   + verified_bytes
+					   + verified_bytes,
+			duplicates_rejected: self.duplicates_rejected.load(AtomicOrdering::Relaxed),
 		}
 	}
 
@@ -871,6 +880,52 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn returns_ok_for_cleared_duplicates() {
+		let queue = get_test_queue(false);
+		queue.import(new_unverified(get_good_dummy_block()))
+			.expect("error importing block that is valid by definition");
+		queue.clear();
+
+		if let Err(e) = queue.import(new_unverified(get_good_dummy_block())) {
+			panic!("error importing block that has been cleared from the queue ({:?})", e);
+		}
+		assert_eq!(queue.queue_info().duplicates_rejected, 0);
+	}
+
+	#[test]
+	fn verifies_concurrent_duplicates_once() {
+		use std::sync::{Arc, Barrier};
+		use std::thread;
+
+		const THREADS: usize = 8;
+		let queue = Arc::new(get_test_queue(false));
+		let barrier = Arc::new(Barrier::new(THREADS));
+
+		let handles: Vec<_> = (0..THREADS).map(|_| {
+			let queue = queue.clone();
+			let barrier = barrier.clone();
+			thread::spawn(move || {
+				let block = new_unverified(get_good_dummy_block());
+				barrier.wait();
+				queue.import(block).map_err(|(e, _)| e)
+			})
+		}).collect();
+		let results: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+		queue.flush();
+
+		// every copy but one is rejected before verification
+		assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+		assert!(results.iter().filter_map(|r| r.as_ref().err()).all(|e| match e {
+			EthcoreError::Import(ImportError::AlreadyQueued) => true,
+			_ => false,
+		}));
+		let info = queue.queue_info();
+		assert_eq!(info.duplicates_rejected, THREADS - 1);
+		assert_eq!(info.total_queue_size(), 1);
+		assert_eq!(queue.total_difficulty(), 131072.into());
+	}
+
 	#[test]
 	fn returns_empty_once_finished() {
 		let queue = get_test_queue(false);
@@ -37,7 +37,7 @@ use common_types::{
 	BlockNumber,
 	header::Header,
 	errors::{EthcoreError as Error, BlockError},
-	engines::MAX_UNCLE_AGE,
+	engines::{MAX_UNCLE_AGE, params::MEDIAN_TIME_SPAN},
 	block::{BlockRlpRepresentation, PreverifiedBlock},
 	verification::Unverified,
 };
@@ -47,7 +47,7 @@ use time_utils::CheckedSystemTime;
 /// Phase 1 quick block verification. Only does checks that are cheap. Operates on a single block
 pub fn verify_block_basic(block: &Unverified, engine: &dyn Engine, check_seal: bool) -> Result<(), Error> {
 	verify_header_params(&block.header, engine, check_seal)?;
-	verify_header_time(&block.header)?;
+	verify_header_time(&block.header, engine)?;
 	verify_block_integrity(block)?;
 
 	if check_seal {
@@ -139,8 +139,10 @@ pub fn verify_block_family<C: BlockInfo + CallContract>(
 	engine: &dyn Engine,
 	params: FullFamilyParams<C>
 ) -> Result<(), Error> {
-	// TODO: verify timestamp
 	verify_parent(&header, &parent, engine)?;
+	if engine.params().enforce_median_past_time {
+		verify_median_past_time(&header, &parent, params.block_provider, engine)?;
+	}
 	engine.verify_block_family(&header, &parent)?;
 	verify_uncles(params.block, params.block_provider, engine)?;
 
@@ -243,6 +245,37 @@ fn verify_uncles(block: &PreverifiedBlock, bc: &dyn BlockProvider, engine: &dyn
 	Ok(())
 }
 
+/// Check that the header timestamp is greater than the median timestamp of the last
+/// `MEDIAN_TIME_SPAN` ancestors, so that a single sealer can't move the chain time back.
+fn verify_median_past_time(header: &Header, parent: &Header, bc: &dyn BlockProvider, engine: &dyn Engine) -> Result<(), Error> {
+	let mut timestamps = Vec::with_capacity(MEDIAN_TIME_SPAN);
+	timestamps.push(parent.timestamp());
+	let mut hash = *parent.parent_hash();
+	while timestamps.len() < MEDIAN_TIME_SPAN {
+		match bc.block_header_data(&hash) {
+			Some(ancestor) => {
+				timestamps.push(ancestor.timestamp());
+				hash = ancestor.parent_hash();
+			}
+			None => break
+		}
+	}
+
+	timestamps.sort();
+	let median = timestamps[timestamps.len() / 2];
+	if header.timestamp() <= median {
+		let max_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+			.checked_add(engine.params().timestamp_drift());
+		return Err(From::from(BlockError::TimestampBelowMedian(OutOfBounds {
+			min: Some(median.saturating_add(1)),
+			max: max_time.map(|max_time| max_time.as_secs()),
+			found: header.timestamp()
+		})));
+	}
+
+	Ok(())
+}
+
 /// Phase 4 verification. Check block information against transaction enactment results,
 pub fn verify_block_final(expected: &Header, got: &Header) -> Result<(), Error> {
 	if expected.state_root() != got.state_root() {
@@ -341,25 +374,28 @@ pub(crate) fn verify_header_params(header: &Header, engine: &dyn Engine, check_s
 }
 
 /// A header verification step that should be done for new block headers, but not for uncles.
-pub(crate) fn verify_header_time(header: &Header) -> Result<(), Error> {
-	const ACCEPTABLE_DRIFT: Duration = Duration::from_secs(15);
-	// this will resist overflow until `year 2037`
-	let max_time = SystemTime::now() + ACCEPTABLE_DRIFT;
-	let invalid_threshold = max_time + ACCEPTABLE_DRIFT * 9;
+pub(crate) fn verify_header_time(header: &Header, engine: &dyn Engine) -> Result<(), Error> {
+	let acceptable_drift = engine.params().timestamp_drift();
+	// the drift is configurable, so the bounds may not be representable:
+	// a bound which overflows can't be exceeded by any timestamp.
+	let max_time = CheckedSystemTime::checked_add(SystemTime::now(), acceptable_drift);
+	let invalid_threshold = max_time.and_then(|max_time| {
+		acceptable_drift.checked_mul(9).and_then(|extra| CheckedSystemTime::checked_add(max_time, extra))
+	});
 	let timestamp = CheckedSystemTime::checked_add(UNIX_EPOCH, Duration::from_secs(header.timestamp()))
 		.ok_or(BlockError::TimestampOverflow)?;
 
-	if timestamp > invalid_threshold {
+	if invalid_threshold.map_or(false, |threshold| timestamp > threshold) {
 		return Err(From::from(BlockError::InvalidTimestamp(OutOfBounds {
-			max: Some(max_time),
+			max: max_time,
 			min: None,
 			found: timestamp
 		}.into())))
 	}
 
-	if timestamp > max_time {
+	if max_time.map_or(false, |max_time| timestamp > max_time) {
 		return Err(From::from(BlockError::TemporarilyInvalid(OutOfBounds {
-			max: Some(max_time),
+			max: max_time,
 			min: None,
 			found: timestamp
 		}.into())))
@@ -778,4 +814,82 @@ mod tests {
 		check_fail(unordered_test(&create_test_block_with_data(&header, &bad_transactions, &[]), &engine), TooManyTransactions(keypair.address()));
 		unordered_test(&create_test_block_with_data(&header, &good_transactions, &[]), &engine).unwrap();
 	}
+
+	fn timestamp_test_engine(timestamp_drift_secs: Option<u64>, enforce_median_past_time: bool) -> NullEngine {
+		let mut params = CommonParams::default();
+		params.gas_limit_bound_divisor = 0x400.into();
+		params.timestamp_drift_secs = timestamp_drift_secs;
+		params.enforce_median_past_time = enforce_median_past_time;
+
+		let machine = Machine::regular(params, BTreeMap::new());
+		NullEngine::new(Default::default(), machine)
+	}
+
+	// inserts a chain of headers with given timestamps and returns a child of the last one
+	fn timestamp_test_chain(timestamps: &[u64], bc: &mut TestBlockChain) -> Header {
+		let mut header = Header::new();
+		header.set_gas_limit(0x10000.into());
+		for (number, timestamp) in timestamps.iter().enumerate() {
+			let parent_hash = header.hash();
+			header.set_number(number as BlockNumber);
+			header.set_timestamp(*timestamp);
+			if number != 0 {
+				header.set_parent_hash(parent_hash);
+			}
+			bc.insert(create_test_block(&header));
+		}
+
+		let mut child = header.clone();
+		child.set_number(header.number() + 1);
+		child.set_parent_hash(header.hash());
+		child
+	}
+
+	#[test]
+	fn configurable_timestamp_drift() {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+		let mut header = Header::new();
+		header.set_timestamp(now + 20);
+
+		check_fail_timestamp(verify_header_time(&header, &timestamp_test_engine(None, false)), true);
+		check_ok(verify_header_time(&header, &timestamp_test_engine(Some(60), false)));
+		check_fail_timestamp(verify_header_time(&header, &timestamp_test_engine(Some(1), false)), false);
+		// a drift too large to add to the current time doesn't bound the timestamp.
+		check_ok(verify_header_time(&header, &timestamp_test_engine(Some(u64::max_value()), false)));
+	}
+
+	#[test]
+	fn median_past_time() {
+		let mut bc = TestBlockChain::new();
+		// the last sealer moved the time back, below the timestamps of its ancestors
+		let mut timestamps: Vec<u64> = (100..110).collect();
+		timestamps.push(50);
+		let mut header = timestamp_test_chain(&timestamps, &mut bc);
+
+		header.set_timestamp(60);
+		check_ok(family_test(&create_test_block(&header), &timestamp_test_engine(None, false), &bc));
+		match family_test(&create_test_block(&header), &timestamp_test_engine(None, true), &bc) {
+			Err(Error::Block(TimestampBelowMedian(OutOfBounds { min: Some(105), max: Some(_), found: 60 }))) => (),
+			other => panic!("Expected TimestampBelowMedian, got {:?}", other),
+		}
+
+		header.set_timestamp(105);
+		check_ok(family_test(&create_test_block(&header), &timestamp_test_engine(None, true), &bc));
+	}
+
+	#[test]
+	fn median_past_time_short_chain() {
+		let mut bc = TestBlockChain::new();
+		let mut header = timestamp_test_chain(&[30, 40, 10], &mut bc);
+
+		// fewer ancestors than `MEDIAN_TIME_SPAN`, the median of 30, 40 and 10 is used
+		header.set_timestamp(20);
+		match family_test(&create_test_block(&header), &timestamp_test_engine(None, true), &bc) {
+			Err(Error::Block(TimestampBelowMedian(OutOfBounds { min: Some(31), max: Some(_), found: 20 }))) => (),
+			other => panic!("Expected TimestampBelowMedian, got {:?}", other),
+		}
+
+		header.set_timestamp(31);
+		check_ok(family_test(&create_test_block(&header), &timestamp_test_engine(None, true), &bc));
+	}
 }
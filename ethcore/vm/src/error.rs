@@ -99,6 +99,13 @@ pub enum Error {
 	OutOfBounds,
 	/// Execution has been reverted with REVERT.
 	Reverted,
+	/// `CREATE` was given initialization code exceeding the schedule's limit.
+	InitCodeTooLarge {
+		/// Maximum allowed initialization code size
+		limit: usize,
+		/// Size of the given initialization code
+		got: usize,
+	},
 }
 
 impl From<Box<ethtrie::TrieError>> for Error {
@@ -131,6 +138,7 @@ impl fmt::Display for Error {
 			Wasm(ref msg) => write!(f, "Internal error: {}", msg),
 			OutOfBounds => write!(f, "Out of bounds"),
 			Reverted => write!(f, "Reverted"),
+			InitCodeTooLarge { limit, got } => write!(f, "Init code too large {}/{}", got, limit),
 		}
 	}
 }
@@ -160,6 +160,12 @@ pub trait Ext {
 	/// Decrements sstore refunds counter.
 	fn sub_sstore_refund(&mut self, value: usize);
 
+	/// Check if the account has already been accessed in the current transaction (EIP-2929).
+	fn is_address_warm(&self, address: &Address) -> bool;
+
+	/// Mark the account as accessed in the current transaction (EIP-2929).
+	fn mark_address_warm(&mut self, address: Address);
+
 	/// Decide if any more operations should be traced. Passthrough for the VM trace.
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _current_gas: U256) -> bool { false }
 
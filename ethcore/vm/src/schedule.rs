@@ -75,6 +75,10 @@ pub struct Schedule {
 	pub call_gas: usize,
 	/// Gas price for staticcall to precompiles
 	pub staticcall_precompile_gas: usize,
+	/// Gas price for `*CALL*` opcodes targeting an account not yet accessed in the transaction (EIP-2929)
+	pub cold_account_access_cost: usize,
+	/// Gas price for `*CALL*` opcodes targeting an already accessed account (EIP-2929)
+	pub warm_storage_read_cost: usize,
 	/// Stipend for transfer for `CALL|CALLCODE` opcode when `value>0`
 	pub call_stipend: usize,
 	/// Additional gas required for value transfer (`CALL|CALLCODE`)
@@ -91,6 +95,8 @@ pub struct Schedule {
 	pub create_data_gas: usize,
 	/// Maximum code size when creating a contract.
 	pub create_data_limit: usize,
+	/// Maximum size of contract initialization code, if limited.
+	pub max_init_code_size: Option<usize>,
 	/// Transaction cost
 	pub tx_gas: usize,
 	/// `CREATE` transaction cost
@@ -140,6 +146,8 @@ pub struct Schedule {
 	pub eip1283: bool,
 	/// Enable EIP-1706 rules
 	pub eip1706: bool,
+	/// Enable EIP-2929 rules
+	pub eip2929: bool,
 	/// Latest VM version for contract creation transaction.
 	pub latest_version: U256,
 	/// All supported non-legacy VM versions.
@@ -256,6 +264,8 @@ impl Schedule {
 			create_gas: 32000,
 			call_gas: 700,
 			staticcall_precompile_gas: 700,
+			cold_account_access_cost: 2600,
+			warm_storage_read_cost: 100,
 			call_stipend: 2300,
 			call_value_transfer_gas: 9000,
 			call_new_account_gas: 25000,
@@ -264,6 +274,7 @@ impl Schedule {
 			quad_coeff_div: 512,
 			create_data_gas: 200,
 			create_data_limit: max_code_size,
+			max_init_code_size: None,
 			tx_gas: 21000,
 			tx_create_gas: 53000,
 			tx_data_zero_gas: 4,
@@ -283,6 +294,7 @@ impl Schedule {
 			kill_dust: CleanDustMode::Off,
 			eip1283: false,
 			eip1706: false,
+			eip2929: false,
 			latest_version: U256::zero(),
 			versions: HashMap::new(),
 			wasm: None,
@@ -357,6 +369,8 @@ impl Schedule {
 			create_gas: 32000,
 			call_gas: 40,
 			staticcall_precompile_gas: 40,
+			cold_account_access_cost: 2600,
+			warm_storage_read_cost: 100,
 			call_stipend: 2300,
 			call_value_transfer_gas: 9000,
 			call_new_account_gas: 25000,
@@ -365,6 +379,7 @@ impl Schedule {
 			quad_coeff_div: 512,
 			create_data_gas: 200,
 			create_data_limit: usize::max_value(),
+			max_init_code_size: None,
 			tx_gas: 21000,
 			tx_create_gas: tcg,
 			tx_data_zero_gas: 4,
@@ -384,6 +399,7 @@ impl Schedule {
 			kill_dust: CleanDustMode::Off,
 			eip1283: false,
 			eip1706: false,
+			eip2929: false,
 			latest_version: U256::zero(),
 			versions: HashMap::new(),
 			wasm: None,
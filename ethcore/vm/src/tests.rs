@@ -67,6 +67,7 @@ pub struct FakeExt {
 	pub balances: HashMap<Address, U256>,
 	pub tracing: bool,
 	pub is_static: bool,
+	pub accessed_addresses: HashSet<Address>,
 
 	chain_id: u64,
 }
@@ -266,6 +267,14 @@ impl Ext for FakeExt {
 		self.sstore_clears -= value as i128;
 	}
 
+	fn is_address_warm(&self, address: &Address) -> bool {
+		self.accessed_addresses.contains(address)
+	}
+
+	fn mark_address_warm(&mut self, address: Address) {
+		self.accessed_addresses.insert(address);
+	}
+
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8, _gas: U256) -> bool {
 		self.tracing
 	}
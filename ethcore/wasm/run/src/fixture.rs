@@ -19,6 +19,9 @@ use ethjson::uint::Uint;
 use ethjson::hash::{Address, H256};
 use ethjson::bytes::Bytes;
 
+/// Fixture schema version understood by this harness.
+pub const SCHEMA_VERSION: u32 = 1;
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 pub enum Source {
@@ -44,6 +47,8 @@ impl Source {
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Fixture {
+	/// Schema version the fixture was written against; unversioned fixtures are taken as current.
+	pub schema_version: Option<u32>,
 	pub caption: Cow<'static, String>,
 	pub source: Source,
 	pub address: Option<Address>,
@@ -41,8 +41,21 @@ fn main() {
 			.required(true)
 			.multiple(true)
 			.help("JSON fixture"))
+		.arg(Arg::with_name("strict")
+			.long("strict")
+			.help("Fail fixtures written against a different schema version instead of warning"))
+		.arg(Arg::with_name("deterministic")
+			.long("deterministic")
+			.help("Stub nondeterministic host functions and print the gas trace of every fixture"))
 		.get_matches();
 
+	let strict = matches.is_present("strict");
+	let mode = if matches.is_present("deterministic") {
+		runner::Mode::Deterministic
+	} else {
+		runner::Mode::Normal
+	};
+
 	let mut exit_code = 0;
 
 	for target in matches.values_of("target").expect("No target parameter") {
@@ -50,11 +63,23 @@ fn main() {
 		let fixtures: Vec<Fixture> = serde_json::from_reader(&mut f).expect("Failed to deserialize json");
 
 		for fixture in fixtures.into_iter() {
-			let fails = runner::run_fixture(&fixture);
-			for fail in fails.iter() {
+			if let Some(mismatch) = runner::check_schema_version(&fixture) {
+				if strict {
+					exit_code = 1;
+					println!("Failed test \"{}\" ('{}'): {}", fixture.caption.as_ref(), target, mismatch);
+					continue;
+				}
+				println!("Warning for test \"{}\" ('{}'): {}", fixture.caption.as_ref(), target, mismatch);
+			}
+
+			let outcome = runner::run_fixture(&fixture, mode);
+			for fail in outcome.fails.iter() {
 				exit_code = 1;
 				println!("Failed assert in test \"{}\" ('{}'): {}", fixture.caption.as_ref(), target, fail);
 			}
+			if let Some(gas_trace) = outcome.gas_trace {
+				println!("Gas trace of test \"{}\" ('{}'): {:?}", fixture.caption.as_ref(), target, gas_trace);
+			}
 		}
 	}
 
@@ -14,9 +14,9 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use fixture::{Fixture, Assert, CallLocator, Source};
-use wasm::WasmInterpreter;
-use vm::{self, Exec, GasLeft, ActionParams, ActionValue, ParamsType};
+use fixture::{Fixture, Assert, CallLocator, Source, SCHEMA_VERSION};
+use wasm::{GasTrace, WasmInterpreter};
+use vm::{self, Exec, EnvInfo, GasLeft, ActionParams, ActionValue, ParamsType};
 use vm::tests::FakeExt;
 use std::io::{self, Read};
 use std::{fs, path, fmt};
@@ -24,6 +24,27 @@ use std::sync::Arc;
 use ethereum_types::{U256, H256, H160, BigEndianHash};
 use rustc_hex::ToHex;
 
+/// Block number seen by contracts in deterministic mode.
+const DETERMINISTIC_BLOCK_NUMBER: u64 = 1_000_000;
+
+/// How host functions behave while running a fixture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+	/// Host functions backed by the default `FakeExt`.
+	Normal,
+	/// Block environment and block hashes are fixed and the full gas trace is recorded,
+	/// so that repeated runs of a fixture produce identical output.
+	Deterministic,
+}
+
+/// Result of running a fixture.
+pub struct Outcome {
+	/// Failed asserts.
+	pub fails: Vec<Fail>,
+	/// Gas counter (in wasm-adjusted units) after every change, recorded in deterministic mode.
+	pub gas_trace: Option<Vec<u64>>,
+}
+
 fn load_code<P: AsRef<path::Path>>(p: P) -> io::Result<Vec<u8>> {
 	let mut result = Vec::new();
 	let mut f = fs::File::open(p)?;
@@ -31,8 +52,32 @@ fn load_code<P: AsRef<path::Path>>(p: P) -> io::Result<Vec<u8>> {
 	Ok(result)
 }
 
-fn wasm_interpreter(params: ActionParams) -> Box<WasmInterpreter> {
-	Box::new(WasmInterpreter::new(params))
+fn wasm_interpreter(params: ActionParams, gas_trace: Option<&GasTrace>) -> Box<WasmInterpreter> {
+	let interpreter = WasmInterpreter::new(params);
+	Box::new(match gas_trace {
+		Some(trace) => interpreter.with_gas_trace(trace.clone()),
+		None => interpreter,
+	})
+}
+
+/// Replaces the block environment seen by the `timestamp`, `blocknumber`, `coinbase`,
+/// `difficulty`, `gaslimit` and `blockhash` host functions with fixed values.
+fn stub_host_environment(ext: &mut FakeExt) {
+	let hashes: Vec<(U256, H256)> = (1..=256)
+		.map(|i| DETERMINISTIC_BLOCK_NUMBER - i)
+		.map(|n| (U256::from(n), H256::from_low_u64_be(n)))
+		.collect();
+
+	ext.info = EnvInfo {
+		number: DETERMINISTIC_BLOCK_NUMBER,
+		author: H160::from_low_u64_be(0xc0ffee),
+		timestamp: 1_500_000_000,
+		difficulty: U256::from(131_072),
+		gas_limit: U256::from(8_000_000),
+		last_hashes: Arc::new(hashes.iter().map(|&(_, hash)| hash).collect()),
+		gas_used: U256::zero(),
+	};
+	ext.blockhashes = hashes.into_iter().collect();
 }
 
 #[derive(Debug)]
@@ -48,7 +93,8 @@ pub enum Fail {
 	Load(io::Error),
 	NoCall(CallLocator),
 	StorageMismatch { key: H256, expected: H256, actual: Option<H256> },
-	Nonconformity(SpecNonconformity)
+	Nonconformity(SpecNonconformity),
+	SchemaVersion { expected: u32, actual: u32 },
 }
 
 impl Fail {
@@ -110,6 +156,9 @@ impl fmt::Display for Fail {
 
 			Nonconformity(SpecNonconformity::Address) =>
 				write!(f, "Cannot use address when constructor is specified!"),
+
+			SchemaVersion { expected, actual } =>
+				write!(f, "Fixture schema version {} does not match the harness schema version {}", actual, expected),
 		}
 	}
 }
@@ -120,6 +169,7 @@ pub fn construct(
 	arguments: Vec<u8>,
 	sender: H160,
 	at: H160,
+	gas_trace: Option<&GasTrace>,
 ) -> Result<Vec<u8>, vm::Error> {
 
 	let mut params = ActionParams::default();
@@ -131,14 +181,39 @@ pub fn construct(
 	params.params_type = ParamsType::Separate;
 
 	Ok(
-		match wasm_interpreter(params).exec(ext).ok().expect("Wasm interpreter always calls with trap=false; trap never happens; qed")? {
+		match wasm_interpreter(params, gas_trace).exec(ext).ok().expect("Wasm interpreter always calls with trap=false; trap never happens; qed")? {
 			GasLeft::Known(_) => Vec::new(),
 			GasLeft::NeedsReturn { data, .. } => data.to_vec(),
 		}
 	)
 }
 
-pub fn run_fixture(fixture: &Fixture) -> Vec<Fail> {
+/// Checks that the fixture was written against the schema this harness understands.
+pub fn check_schema_version(fixture: &Fixture) -> Option<Fail> {
+	match fixture.schema_version {
+		Some(version) if version != SCHEMA_VERSION => Some(Fail::SchemaVersion { expected: SCHEMA_VERSION, actual: version }),
+		_ => None,
+	}
+}
+
+pub fn run_fixture(fixture: &Fixture, mode: Mode) -> Outcome {
+	let mut ext = FakeExt::new().with_wasm();
+	let gas_trace = match mode {
+		Mode::Normal => None,
+		Mode::Deterministic => {
+			stub_host_environment(&mut ext);
+			Some(GasTrace::default())
+		},
+	};
+
+	let fails = run_with_ext(fixture, &mut ext, gas_trace.as_ref());
+	Outcome {
+		fails,
+		gas_trace: gas_trace.map(|trace| trace.lock().expect("Trace is only locked while recording; qed").clone()),
+	}
+}
+
+fn run_with_ext(fixture: &Fixture, ext: &mut FakeExt, gas_trace: Option<&GasTrace>) -> Vec<Fail> {
 	let mut params = ActionParams::default();
 
 	let source = match load_code(fixture.source.as_ref()) {
@@ -146,10 +221,9 @@ pub fn run_fixture(fixture: &Fixture) -> Vec<Fail> {
 		Err(e) => { return Fail::load(e); },
 	};
 
-	let mut ext = FakeExt::new().with_wasm();
 	params.code = Some(Arc::new(
 		if let Source::Constructor { ref arguments, ref sender, ref at, .. } = fixture.source {
-			match construct(&mut ext, source, arguments.clone().into(), sender.clone().into(), at.clone().into()) {
+			match construct(ext, source, arguments.clone().into(), sender.clone().into(), at.clone().into(), gas_trace) {
 				Ok(code) => code,
 				Err(e) => { return Fail::runtime(e); }
 			}
@@ -192,9 +266,9 @@ pub fn run_fixture(fixture: &Fixture) -> Vec<Fail> {
 		}
 	}
 
-	let interpreter = wasm_interpreter(params);
+	let interpreter = wasm_interpreter(params, gas_trace);
 
-	let interpreter_return = match interpreter.exec(&mut ext).ok().expect("Wasm interpreter always calls with trap=false; trap never happens; qed") {
+	let interpreter_return = match interpreter.exec(ext).ok().expect("Wasm interpreter always calls with trap=false; trap never happens; qed") {
 		Ok(ret) => ret,
 		Err(e) => { return Fail::runtime(e); }
 	};
@@ -275,3 +349,60 @@ pub fn run_fixture(fixture: &Fixture) -> Vec<Fail> {
 	}
 	fails
 }
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use fixture::Fixture;
+	use serde_json;
+	use super::{check_schema_version, run_fixture, Fail, Mode};
+
+	fn load_fixtures(path: &str) -> Vec<Fixture> {
+		let f = fs::File::open(path).expect("Fixture file exists");
+		serde_json::from_reader(f).expect("Fixture file is valid")
+	}
+
+	fn sample_fixtures() -> Vec<Fixture> {
+		load_fixtures("res/sample-fixture.json")
+	}
+
+	#[test]
+	fn schema_version_mismatch_is_reported() {
+		let fixtures = load_fixtures("res/schema-mismatch-fixture.json");
+		assert_eq!(fixtures.len(), 1);
+
+		match check_schema_version(&fixtures[0]) {
+			Some(Fail::SchemaVersion { expected: 1, actual: 2 }) => {},
+			other => panic!("Unexpected schema check result: {:?}", other),
+		}
+	}
+
+	#[test]
+	fn unversioned_fixtures_pass_schema_check() {
+		for fixture in sample_fixtures() {
+			assert!(check_schema_version(&fixture).is_none());
+		}
+	}
+
+	#[test]
+	fn deterministic_runs_have_identical_gas_traces() {
+		for fixture in sample_fixtures() {
+			let first = run_fixture(&fixture, Mode::Deterministic);
+			let second = run_fixture(&fixture, Mode::Deterministic);
+
+			let first_trace = first.gas_trace.expect("Trace is recorded in deterministic mode");
+			assert!(!first_trace.is_empty(), "empty gas trace for {}", fixture.caption);
+			assert_eq!(Some(first_trace), second.gas_trace, "gas traces differ for {}", fixture.caption);
+
+			let fails = |fails: &[super::Fail]| fails.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+			assert_eq!(fails(&first.fails), fails(&second.fails));
+		}
+	}
+
+	#[test]
+	fn normal_mode_records_no_gas_trace() {
+		let fixture = sample_fixtures().remove(0);
+		let outcome = run_fixture(&fixture, Mode::Normal);
+		assert!(outcome.gas_trace.is_none());
+	}
+}
@@ -41,6 +41,7 @@ use vm::{GasLeft, ReturnData, ActionParams};
 use wasmi::{Error as InterpreterError, Trap};
 
 use runtime::{Runtime, RuntimeContext};
+pub use runtime::GasTrace;
 
 use ethereum_types::U256;
 
@@ -75,11 +76,19 @@ impl From<Error> for vm::Error {
 /// Wasm interpreter instance
 pub struct WasmInterpreter {
 	params: ActionParams,
+	gas_trace: Option<GasTrace>,
 }
 
 impl WasmInterpreter {
 	pub fn new(params: ActionParams) -> Self {
-		WasmInterpreter { params }
+		WasmInterpreter { params, gas_trace: None }
+	}
+
+	/// Record the gas counter (in wasm-adjusted units) into `trace` after every change
+	/// made during execution.
+	pub fn with_gas_trace(mut self, trace: GasTrace) -> Self {
+		self.gas_trace = Some(trace);
+		self
 	}
 }
 
@@ -135,6 +144,9 @@ impl WasmInterpreter {
 					value: self.params.value.value(),
 				},
 			);
+			if let Some(ref trace) = self.gas_trace {
+				runtime.trace_gas(trace.clone());
+			}
 
 			// cannot overflow if static_region < 2^16,
 			// initial_memory ∈ [0..2^32)
@@ -15,6 +15,7 @@
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp;
+use std::sync::{Arc, Mutex};
 use ethereum_types::{BigEndianHash, U256, H256, Address};
 use vm::{self, ActionType};
 use wasmi::{self, MemoryRef, RuntimeArgs, RuntimeValue, Error as InterpreterError, Trap, TrapKind};
@@ -29,6 +30,9 @@ pub struct RuntimeContext {
 	pub value: U256,
 }
 
+/// Record of the gas counter after every change made during execution.
+pub type GasTrace = Arc<Mutex<Vec<u64>>>;
+
 pub struct Runtime<'a> {
 	gas_counter: u64,
 	gas_limit: u64,
@@ -37,6 +41,7 @@ pub struct Runtime<'a> {
 	memory: MemoryRef,
 	args: Vec<u8>,
 	result: Vec<u8>,
+	gas_trace: Option<GasTrace>,
 }
 
 /// User trap in native code
@@ -161,6 +166,20 @@ impl<'a> Runtime<'a> {
 			context: context,
 			args: args,
 			result: Vec::new(),
+			gas_trace: None,
+		}
+	}
+
+	/// Record the gas counter into `trace` after every change
+	pub fn trace_gas(&mut self, trace: GasTrace) {
+		self.gas_trace = Some(trace);
+	}
+
+	fn note_gas(&self) {
+		if let Some(ref trace) = self.gas_trace {
+			if let Ok(mut trace) = trace.lock() {
+				trace.push(self.gas_counter);
+			}
 		}
 	}
 
@@ -200,6 +219,7 @@ impl<'a> Runtime<'a> {
 			Some(val) if val > self.gas_limit => false,
 			Some(_) => {
 				self.gas_counter = prev + amount;
+				self.note_gas();
 				true
 			}
 		}
@@ -463,6 +483,7 @@ impl<'a> Runtime<'a> {
 				self.gas_counter = self.gas_counter -
 					gas_left.low_u64() * self.ext.schedule().wasm().opcodes_div as u64
 						/ self.ext.schedule().wasm().opcodes_mul as u64;
+				self.note_gas();
 
 				self.memory.set(result_ptr, &result)?;
 				Ok(0i32.into())
@@ -475,6 +496,7 @@ impl<'a> Runtime<'a> {
 				self.gas_counter = self.gas_counter -
 					gas_left.low_u64() * self.ext.schedule().wasm().opcodes_div as u64
 						/ self.ext.schedule().wasm().opcodes_mul as u64;
+				self.note_gas();
 
 				self.memory.set(result_ptr, &result)?;
 				Ok((-1i32).into())
@@ -538,6 +560,7 @@ impl<'a> Runtime<'a> {
 					// and gas_left cannot be bigger
 					gas_left.low_u64() * self.ext.schedule().wasm().opcodes_div as u64
 						/ self.ext.schedule().wasm().opcodes_mul as u64;
+				self.note_gas();
 				trace!(target: "wasm", "runtime: create contract success (@{:?})", address);
 				Ok(0i32.into())
 			},
@@ -552,6 +575,7 @@ impl<'a> Runtime<'a> {
 					// and gas_left cannot be bigger
 					gas_left.low_u64() * self.ext.schedule().wasm().opcodes_div as u64
 						/ self.ext.schedule().wasm().opcodes_mul as u64;
+				self.note_gas();
 
 				Ok((-1i32).into())
 			},
@@ -0,0 +1,124 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Machine-readable summary of the benchmark results.
+//!
+//! When `EVMBIN_BENCH_OUTPUT` is set, timings collected while the benches run are written
+//! to the given path once all of them are done. Paths ending in `.json` get a JSON array,
+//! anything else gets CSV with an `id,mean_ns,throughput_per_sec` header.
+
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use criterion::{Bencher, black_box};
+use serde::Serialize;
+
+/// Environment variable holding the summary output path.
+pub const OUTPUT_ENV: &str = "EVMBIN_BENCH_OUTPUT";
+
+thread_local! {
+	static RECORDER: RefCell<Recorder> = RefCell::new(Recorder::default());
+}
+
+/// Summary of a single benchmark.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Summary {
+	/// Benchmark id.
+	pub id: String,
+	/// Mean time of a single iteration, in nanoseconds.
+	pub mean_ns: f64,
+	/// Iterations per second.
+	pub throughput_per_sec: f64,
+}
+
+/// Accumulates iteration counts and timings per benchmark, in the order they were first seen.
+#[derive(Default)]
+pub struct Recorder {
+	totals: Vec<(String, u64, Duration)>,
+}
+
+impl Recorder {
+	/// Records `iters` iterations of benchmark `id` taking `elapsed` in total.
+	pub fn record(&mut self, id: &str, iters: u64, elapsed: Duration) {
+		match self.totals.iter_mut().find(|(existing, _, _)| existing == id) {
+			Some((_, total_iters, total_elapsed)) => {
+				*total_iters += iters;
+				*total_elapsed += elapsed;
+			}
+			None => self.totals.push((id.to_owned(), iters, elapsed)),
+		}
+	}
+
+	/// Returns the summaries of all recorded benchmarks.
+	pub fn summaries(&self) -> Vec<Summary> {
+		self.totals.iter()
+			.filter(|(_, iters, _)| *iters > 0)
+			.map(|(id, iters, elapsed)| {
+				let mean_ns = elapsed.as_nanos() as f64 / *iters as f64;
+				Summary {
+					id: id.clone(),
+					mean_ns,
+					throughput_per_sec: if mean_ns > 0.0 { 1e9 / mean_ns } else { 0.0 },
+				}
+			})
+			.collect()
+	}
+}
+
+/// Like `Bencher::iter`, but also records the timings for the summary.
+/// Warm-up iterations are timed by criterion through the same routine, so they are included.
+pub fn iter_recorded<O, R>(b: &mut Bencher, id: &str, mut routine: R) where R: FnMut() -> O {
+	b.iter_custom(|iters| {
+		let start = Instant::now();
+		for _ in 0..iters {
+			black_box(routine());
+		}
+		let elapsed = start.elapsed();
+		RECORDER.with(|recorder| recorder.borrow_mut().record(id, iters, elapsed));
+		elapsed
+	})
+}
+
+/// Writes the summary of the benchmarks run so far to the path in `EVMBIN_BENCH_OUTPUT`, if set.
+pub fn export_from_env() -> io::Result<()> {
+	match env::var_os(OUTPUT_ENV) {
+		Some(path) => {
+			let summaries = RECORDER.with(|recorder| recorder.borrow().summaries());
+			write(Path::new(&path), &summaries)
+		}
+		None => Ok(()),
+	}
+}
+
+/// Writes `summaries` to `path`, as JSON if the extension is `.json` and as CSV otherwise.
+pub fn write(path: &Path, summaries: &[Summary]) -> io::Result<()> {
+	let contents = match path.extension().and_then(|ext| ext.to_str()) {
+		Some("json") => serde_json::to_string_pretty(summaries)
+			.map_err(|e| io::Error::new(io::ErrorKind::Other, e))?,
+		_ => {
+			let mut csv = String::from("id,mean_ns,throughput_per_sec\n");
+			for summary in summaries {
+				csv.push_str(&format!("{},{:.2},{:.2}\n", summary.id, summary.mean_ns, summary.throughput_per_sec));
+			}
+			csv
+		}
+	};
+	fs::write(path, contents)
+}
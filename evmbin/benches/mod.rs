@@ -19,6 +19,7 @@
 //! ```bash
 //! cargo bench
 //! ```
+//! Set `EVMBIN_BENCH_OUTPUT` to also write a CSV (or, for `.json` paths, JSON) summary.
 
 #[macro_use]
 extern crate criterion;
@@ -31,6 +32,9 @@ extern crate evm;
 extern crate ethereum_types;
 extern crate vm;
 
+mod export;
+
+use std::process;
 use std::sync::Arc;
 use criterion::{Criterion, black_box};
 
@@ -44,9 +48,22 @@ criterion_group!(
 	bench_simple_loop_usize,
 	bench_simple_loop_u256,
 	bench_rng_usize,
-	bench_rng_u256
+	bench_rng_u256,
+	bench_memory_heavy_usize,
+	bench_memory_heavy_u256,
+	bench_storage_heavy_usize,
+	bench_storage_heavy_u256
 );
-criterion_main!(evmbin);
+
+fn main() {
+	evmbin();
+	Criterion::default().configure_from_args().final_summary();
+
+	if let Err(e) = export::export_from_env() {
+		eprintln!("Failed to write benchmark summary: {}", e);
+		process::exit(1);
+	}
+}
 
 fn bench_simple_loop_usize(c: &mut Criterion) {
 	simple_loop(U256::from(::std::usize::MAX), c, "simple_loop_usize")
@@ -62,7 +79,7 @@ fn simple_loop(gas: U256, c: &mut Criterion, bench_id: &str) {
 	);
 
 	c.bench_function(bench_id, move |b| {
-		b.iter(|| {
+		export::iter_recorded(b, bench_id, || {
 			let mut params = ActionParams::default();
 			params.gas = gas;
 			params.code = Some(Arc::new(code.clone()));
@@ -88,7 +105,63 @@ fn rng(gas: U256, c: &mut Criterion, bench_id: &str) {
 	);
 
 	c.bench_function(bench_id, move |b| {
-		b.iter(|| {
+		export::iter_recorded(b, bench_id, || {
+			let mut params = ActionParams::default();
+			params.gas = gas;
+			params.code = Some(Arc::new(code.clone()));
+
+			let mut ext = FakeExt::new();
+			let evm = Factory::default().create(params, ext.schedule(), ext.depth());
+			let _ = evm.exec(&mut ext);
+		})
+	});
+}
+
+fn bench_memory_heavy_usize(c: &mut Criterion) {
+	memory_heavy(U256::from(::std::usize::MAX), c, "memory_heavy_usize")
+}
+
+fn bench_memory_heavy_u256(c: &mut Criterion) {
+	memory_heavy(!U256::zero(), c, "memory_heavy_u256")
+}
+
+/// Writes 64 KiB of contiguous memory, one word at a time:
+/// `for (i = 0; i < 0x10000; i += 32) mstore(i, i)`
+fn memory_heavy(gas: U256, c: &mut Criterion, bench_id: &str) {
+	let code = black_box(
+		hex!("60005b80805260200180620100001160025700").to_vec()
+	);
+
+	c.bench_function(bench_id, move |b| {
+		export::iter_recorded(b, bench_id, || {
+			let mut params = ActionParams::default();
+			params.gas = gas;
+			params.code = Some(Arc::new(code.clone()));
+
+			let mut ext = FakeExt::new();
+			let evm = Factory::default().create(params, ext.schedule(), ext.depth());
+			let _ = evm.exec(&mut ext);
+		})
+	});
+}
+
+fn bench_storage_heavy_usize(c: &mut Criterion) {
+	storage_heavy(U256::from(::std::usize::MAX), c, "storage_heavy_usize")
+}
+
+fn bench_storage_heavy_u256(c: &mut Criterion) {
+	storage_heavy(!U256::zero(), c, "storage_heavy_u256")
+}
+
+/// Writes 1024 distinct storage slots, tracked by `FakeExt`:
+/// `for (i = 0; i < 0x400; i++) sstore(i, i + 1)`
+fn storage_heavy(gas: U256, c: &mut Criterion, bench_id: &str) {
+	let code = black_box(
+		hex!("60005b806001018155600101806104001160025700").to_vec()
+	);
+
+	c.bench_function(bench_id, move |b| {
+		export::iter_recorded(b, bench_id, || {
 			let mut params = ActionParams::default();
 			params.gas = gas;
 			params.code = Some(Arc::new(code.clone()));
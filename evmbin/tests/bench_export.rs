@@ -0,0 +1,77 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Smoke tests for the benchmark summary exporter.
+
+#[path = "../benches/export.rs"]
+mod export;
+
+use std::env;
+use std::fs;
+use std::time::Duration;
+
+use criterion::Criterion;
+use export::{Recorder, Summary};
+
+#[test]
+fn bench_run_produces_csv_summary() {
+	let dir = tempfile::tempdir().unwrap();
+	let path = dir.path().join("summary.csv");
+
+	Criterion::default()
+		.sample_size(10)
+		.warm_up_time(Duration::from_millis(10))
+		.measurement_time(Duration::from_millis(50))
+		.without_plots()
+		.bench_function("smoke", |b| export::iter_recorded(b, "smoke", || (0..100u64).sum::<u64>()));
+
+	env::set_var(export::OUTPUT_ENV, &path);
+	export::export_from_env().unwrap();
+
+	let contents = fs::read_to_string(&path).unwrap();
+	let mut lines = contents.lines();
+	assert_eq!(lines.next(), Some("id,mean_ns,throughput_per_sec"));
+
+	let row: Vec<_> = lines.next().unwrap().split(',').collect();
+	assert_eq!(row.len(), 3);
+	assert_eq!(row[0], "smoke");
+	assert!(row[1].parse::<f64>().unwrap() > 0.0);
+	assert!(row[2].parse::<f64>().unwrap() > 0.0);
+	assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn recorder_summary_written_as_json() {
+	let mut recorder = Recorder::default();
+	recorder.record("a", 10, Duration::from_nanos(500));
+	recorder.record("b", 4, Duration::from_micros(4));
+	recorder.record("a", 10, Duration::from_nanos(1500));
+
+	let summaries = recorder.summaries();
+	assert_eq!(summaries, vec![
+		Summary { id: "a".into(), mean_ns: 100.0, throughput_per_sec: 1e7 },
+		Summary { id: "b".into(), mean_ns: 1000.0, throughput_per_sec: 1e6 },
+	]);
+
+	let dir = tempfile::tempdir().unwrap();
+	let path = dir.path().join("summary.json");
+	export::write(&path, &summaries).unwrap();
+
+	let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+	assert_eq!(json[0]["id"], "a");
+	assert_eq!(json[0]["mean_ns"], 100.0);
+	assert_eq!(json[1]["throughput_per_sec"], 1e6);
+}
@@ -16,7 +16,7 @@
 
 //! Null engine params deserialization.
 
-use crate::uint::Uint;
+use crate::{hash::Address, uint::Uint};
 use serde::Deserialize;
 
 /// Authority params deserialization.
@@ -27,7 +27,11 @@ pub struct NullEngineParams {
 	/// Block reward.
 	pub block_reward: Option<Uint>,
 	/// Immediate finalization.
-	pub immediate_finalization: Option<bool>
+	pub immediate_finalization: Option<bool>,
+	/// Address receiving the author reward instead of the block author.
+	pub reward_beneficiary: Option<Address>,
+	/// How far (in seconds) block timestamps may be ahead of the local clock.
+	pub max_future_timestamp_secs: Option<u64>,
 }
 
 /// Null engine descriptor
@@ -41,7 +45,8 @@ pub struct NullEngine {
 #[cfg(test)]
 mod tests {
 	use super::{NullEngine, Uint};
-	use ethereum_types::U256;
+	use crate::hash::Address;
+	use ethereum_types::{H160, U256};
 
 	#[test]
 	fn null_engine_deserialization() {
@@ -53,5 +58,20 @@ mod tests {
 
 		let deserialized: NullEngine = serde_json::from_str(s).unwrap();
 		assert_eq!(deserialized.params.block_reward, Some(Uint(U256::from(0x0d))));
+		assert_eq!(deserialized.params.reward_beneficiary, None);
+		assert_eq!(deserialized.params.max_future_timestamp_secs, None);
+	}
+
+	#[test]
+	fn null_engine_deserialization_with_reward_beneficiary() {
+		let s = r#"{
+			"params": {
+				"blockReward": "0x0d",
+				"rewardBeneficiary": "0x0000000000000000000000000000000000000042"
+			}
+		}"#;
+
+		let deserialized: NullEngine = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.params.reward_beneficiary, Some(Address(H160::from_low_u64_be(0x42))));
 	}
 }
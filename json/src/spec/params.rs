@@ -113,6 +113,8 @@ pub struct Params {
 	/// See `CommonParams` docs.
 	pub eip2315_transition: Option<Uint>,
 	/// See `CommonParams` docs.
+	pub eip2929_transition: Option<Uint>,
+	/// See `CommonParams` docs.
 	pub dust_protection_transition: Option<Uint>,
 	/// See `CommonParams` docs.
 	pub nonce_cap_increment: Option<Uint>,
@@ -133,6 +135,10 @@ pub struct Params {
 	pub max_transaction_size: Option<Uint>,
 	/// See main EthashParams docs.
 	pub max_code_size_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub max_init_code_size: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub max_init_code_size_transition: Option<Uint>,
 	/// Transaction permission contract address.
 	pub transaction_permission_contract: Option<Address>,
 	/// Block at which the transaction permission contract should start being used.
@@ -145,6 +151,10 @@ pub struct Params {
 	pub kip4_transition: Option<Uint>,
 	/// KIP6 activiation block height.
 	pub kip6_transition: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub timestamp_drift_secs: Option<Uint>,
+	/// See `CommonParams` docs.
+	pub enforce_median_past_time: Option<bool>,
 }
 
 #[cfg(test)]
@@ -163,6 +173,7 @@ mod tests {
 			"accountStartNonce": "0x01",
 			"gasLimitBoundDivisor": "0x20",
 			"maxCodeSize": "0x1000",
+			"maxInitCodeSize": "0x2000",
 			"wasmActivationTransition": "0x1010"
 		}"#;
 
@@ -175,6 +186,8 @@ mod tests {
 		assert_eq!(deserialized.account_start_nonce, Some(Uint(U256::from(0x01))));
 		assert_eq!(deserialized.gas_limit_bound_divisor, Uint(U256::from(0x20)));
 		assert_eq!(deserialized.max_code_size, Some(Uint(U256::from(0x1000))));
+		assert_eq!(deserialized.max_init_code_size, Some(Uint(U256::from(0x2000))));
+		assert_eq!(deserialized.max_init_code_size_transition, None);
 		assert_eq!(deserialized.wasm_activation_transition, Some(Uint(U256::from(0x1010))));
 	}
 
@@ -19,95 +19,265 @@
 //! A simple client to get the current ETH price using an external API.
 
 use std::{cmp, fmt, io, str};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use fetch::{Client as FetchClient, Fetch};
-use futures::{Future, Stream};
+use futures::{future, Future, Stream};
 use log::warn;
 use parity_runtime::Executor;
 use serde_json::Value;
 
 pub use fetch;
 
+/// Endpoint of the CoinGecko simple price API for ETH in USD.
+pub const COINGECKO_ETH_PRICE_ENDPOINT: &str = "https://api.coingecko.com/api/v3/simple/price?ids=ethereum&vs_currencies=usd";
+
+/// Default time a single price source is given to respond before falling back to the next one.
+pub const DEFAULT_SOURCE_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Current ETH price information.
 #[derive(Debug)]
 pub struct PriceInfo {
 	/// Current ETH price in USD.
 	pub ethusd: f32,
+	/// Name of the source the price was fetched from.
+	pub source: String,
 }
 
-/// A client to get the current ETH price using an external API.
-pub struct Client<F = FetchClient> {
-	pool: Executor,
-	api_endpoint: String,
+/// Future resolving to the current ETH price in USD.
+pub type PriceFuture = Box<dyn Future<Item = f32, Error = fetch::Error> + Send>;
+
+/// A source of the current ETH price.
+pub trait PriceSource: Send + Sync {
+	/// Name of the source, used for logging and reporting.
+	fn name(&self) -> &str;
+
+	/// Fetch the current ETH price in USD. The request should give up once `abort` fires.
+	fn fetch_price(&self, abort: fetch::Abort) -> PriceFuture;
+}
+
+/// Price source reading `result.ethusd` from an Etherscan-compatible API.
+pub struct EtherscanSource<F = FetchClient> {
 	fetch: F,
+	endpoint: String,
+}
+
+impl<F: Fetch> EtherscanSource<F> {
+	/// Creates a new source querying the given endpoint.
+	pub fn new(fetch: F, endpoint: String) -> Self {
+		EtherscanSource { fetch, endpoint }
+	}
+}
+
+impl<F: Fetch> PriceSource for EtherscanSource<F> {
+	fn name(&self) -> &str {
+		&self.endpoint
+	}
+
+	fn fetch_price(&self, abort: fetch::Abort) -> PriceFuture {
+		fetch_price(&self.fetch, self.endpoint.clone(), abort, |value| {
+			value.pointer("/result/ethusd")
+				.and_then(|obj| obj.as_str())
+				.and_then(|s| s.parse().ok())
+		})
+	}
+}
+
+/// Price source reading `ethereum.usd` from a CoinGecko-compatible API.
+pub struct CoinGeckoSource<F = FetchClient> {
+	fetch: F,
+	endpoint: String,
+}
+
+impl<F: Fetch> CoinGeckoSource<F> {
+	/// Creates a new source querying the given endpoint.
+	pub fn new(fetch: F, endpoint: String) -> Self {
+		CoinGeckoSource { fetch, endpoint }
+	}
+}
+
+impl<F: Fetch> PriceSource for CoinGeckoSource<F> {
+	fn name(&self) -> &str {
+		&self.endpoint
+	}
+
+	fn fetch_price(&self, abort: fetch::Abort) -> PriceFuture {
+		fetch_price(&self.fetch, self.endpoint.clone(), abort, |value| {
+			value.pointer("/ethereum/usd")
+				.and_then(|obj| obj.as_f64())
+				.map(|usd| usd as f32)
+		})
+	}
+}
+
+/// Health of a single price source.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SourceHealth {
+	/// Number of failed requests since the last successful one.
+	pub consecutive_failures: u32,
+	/// Time of the last successful request.
+	pub last_success: Option<Instant>,
+	/// Last error returned by the source.
+	pub last_error: Option<String>,
+}
+
+/// A client to get the current ETH price from a prioritized list of sources.
+pub struct Client {
+	pool: Executor,
+	sources: Vec<Arc<dyn PriceSource>>,
+	health: Arc<Mutex<Vec<SourceHealth>>>,
+	timeout: Duration,
+}
+
+impl Client {
+	fn source_names(&self) -> Vec<&str> {
+		self.sources.iter().map(|source| source.name()).collect()
+	}
 }
 
-impl<F> fmt::Debug for Client<F> {
+impl fmt::Debug for Client {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		fmt.debug_struct("price_info::Client")
-		   .field("api_endpoint", &self.api_endpoint)
+		   .field("sources", &self.source_names())
+		   .field("timeout", &self.timeout)
 		   .finish()
 	}
 }
 
-impl<F> cmp::PartialEq for Client<F> {
-	fn eq(&self, other: &Client<F>) -> bool {
-		self.api_endpoint == other.api_endpoint
+impl cmp::PartialEq for Client {
+	fn eq(&self, other: &Client) -> bool {
+		self.source_names() == other.source_names() && self.timeout == other.timeout
 	}
 }
 
-impl<F: Fetch> Client<F> {
+type PriceInfoFuture = Box<dyn Future<Item = PriceInfo, Error = fetch::Error> + Send>;
+
+impl Client {
 	/// Creates a new instance of the `Client` given a `fetch::Client`.
-	pub fn new(fetch: F, pool: Executor, api_endpoint: String) -> Client<F> {
-		Client { pool, api_endpoint, fetch }
+	pub fn new<F: Fetch>(fetch: F, pool: Executor, api_endpoint: String) -> Client {
+		Client::with_endpoints(fetch, pool, vec![api_endpoint])
+	}
+
+	/// Creates a new instance of the `Client` querying the given Etherscan-compatible
+	/// endpoints in order, falling back to the next one if a request fails.
+	pub fn with_endpoints<F: Fetch>(fetch: F, pool: Executor, api_endpoints: Vec<String>) -> Client {
+		let sources = api_endpoints.into_iter()
+			.map(|endpoint| Arc::new(EtherscanSource::new(fetch.clone(), endpoint)) as Arc<dyn PriceSource>)
+			.collect();
+		Client::with_sources(pool, sources)
+	}
+
+	/// Creates a new instance of the `Client` querying the given sources in order,
+	/// falling back to the next one if a request fails.
+	pub fn with_sources(pool: Executor, sources: Vec<Arc<dyn PriceSource>>) -> Client {
+		let health = Arc::new(Mutex::new(vec![SourceHealth::default(); sources.len()]));
+		Client { pool, sources, health, timeout: DEFAULT_SOURCE_TIMEOUT }
+	}
+
+	/// Sets the time each source is given to respond.
+	pub fn with_timeout(mut self, timeout: Duration) -> Client {
+		self.timeout = timeout;
+		self
+	}
+
+	/// Returns the name and health of every source, in priority order.
+	pub fn health(&self) -> Vec<(String, SourceHealth)> {
+		let health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+		self.sources.iter()
+			.map(|source| source.name().to_owned())
+			.zip(health.iter().cloned())
+			.collect()
 	}
 
 	/// Gets the current ETH price and calls `set_price` with the result.
 	pub fn get<G: FnOnce(PriceInfo) + Sync + Send + 'static>(&self, set_price: G) {
-		let future = self.fetch.get(&self.api_endpoint, fetch::Abort::default())
-			.and_then(|response| response.concat2())
-			.and_then(move |body| {
-				let body_str = str::from_utf8(&body).ok();
-				let value: Option<Value> = body_str.and_then(|s| serde_json::from_str(s).ok());
-
-				let ethusd = value
-					.as_ref()
-					.and_then(|value| value.pointer("/result/ethusd"))
-					.and_then(|obj| obj.as_str())
-					.and_then(|s| s.parse().ok());
-
-				match ethusd {
-					Some(ethusd) => {
-						set_price(PriceInfo { ethusd });
-						Ok(())
-					},
-					None => {
-						let msg = format!("Unexpected response: {}", body_str.unwrap_or_default());
-						let err = io::Error::new(io::ErrorKind::Other, msg);
-						Err(fetch::Error::Io(err))
-					}
-				}
-			})
+		let mut sources = self.sources.iter().enumerate();
+		let mut future = match sources.next() {
+			Some((index, source)) => self.fetch_from(index, source.clone()),
+			None => {
+				warn!("No ETH price sources configured");
+				return;
+			}
+		};
+
+		for (index, source) in sources {
+			let next = self.fetch_from(index, source.clone());
+			let name = source.name().to_owned();
+			future = Box::new(future.or_else(move |err| {
+				warn!("Failed to fetch ETH price: {:?}. Trying {}", err, name);
+				next
+			}));
+		}
+
+		let future = future
+			.map(move |price| set_price(price))
 			.map_err(|err| {
 				warn!("Failed to auto-update latest ETH price: {:?}", err);
 			});
 		self.pool.spawn(future)
 	}
+
+	/// Lazily fetches the price from a single source, recording the outcome in its health.
+	fn fetch_from(&self, index: usize, source: Arc<dyn PriceSource>) -> PriceInfoFuture {
+		let health = self.health.clone();
+		let abort = fetch::Abort::default().with_max_duration(self.timeout);
+		let future = future::lazy(move || source.fetch_price(abort).then(move |result| {
+			let mut health = health.lock().unwrap_or_else(|e| e.into_inner());
+			let health = &mut health[index];
+			match result {
+				Ok(ethusd) => {
+					health.consecutive_failures = 0;
+					health.last_success = Some(Instant::now());
+					Ok(PriceInfo { ethusd, source: source.name().to_owned() })
+				},
+				Err(err) => {
+					health.consecutive_failures += 1;
+					health.last_error = Some(format!("{:?}", err));
+					Err(err)
+				},
+			}
+		}));
+		Box::new(future)
+	}
+}
+
+fn fetch_price<F, P>(fetch: &F, endpoint: String, abort: fetch::Abort, parse: P) -> PriceFuture where
+	F: Fetch,
+	P: FnOnce(&Value) -> Option<f32> + Send + 'static,
+{
+	let future = fetch.get(&endpoint, abort)
+		.and_then(|response| response.concat2())
+		.and_then(move |body| {
+			let body_str = str::from_utf8(&body).ok();
+			let value: Option<Value> = body_str.and_then(|s| serde_json::from_str(s).ok());
+
+			match value.as_ref().and_then(parse) {
+				Some(ethusd) => future::ok(ethusd),
+				None => {
+					let msg = format!("Unexpected response from {}: {}", endpoint, body_str.unwrap_or_default());
+					let err = io::Error::new(io::ErrorKind::Other, msg);
+					future::err(fetch::Error::Io(err))
+				}
+			}
+		});
+	Box::new(future)
 }
 
 #[cfg(test)]
 mod test {
 	use std::sync::{
-		Arc, atomic::{AtomicBool, Ordering}
+		Arc, mpsc, atomic::{AtomicBool, Ordering}
 	};
+	use std::time::Duration;
 	use fake_fetch::FakeFetch;
 	use parity_runtime::{Runtime, Executor};
-	use super::Client;
+	use super::{Client, CoinGeckoSource, EtherscanSource, PriceSource};
 
-	fn price_info_ok(response: &str, executor: Executor) -> Client<FakeFetch<String>> {
+	fn price_info_ok(response: &str, executor: Executor) -> Client {
 		Client::new(FakeFetch::new(Some(response.to_owned())), executor, "fake_endpoint".to_owned())
 	}
 
-	fn price_info_not_found(executor: Executor) -> Client<FakeFetch<String>> {
+	fn price_info_not_found(executor: Executor) -> Client {
 		Client::new(FakeFetch::new(None::<String>), executor, "fake_endpoint".to_owned())
 	}
 
@@ -174,4 +344,93 @@ mod test {
 		// then
 		assert_eq!(b.load(Ordering::Relaxed), false);
 	}
+
+	#[test]
+	fn should_fall_back_to_next_endpoint() {
+		let runtime = Runtime::with_thread_count(1);
+
+		// given
+		let response = r#"{
+			"status": "1",
+			"message": "OK",
+			"result": {
+				"ethusd": "180.25"
+			}
+		}"#;
+		let fetch = FakeFetch::new(None::<String>)
+			.with_response("https://second.example/price", response);
+		let price_info = Client::with_endpoints(fetch, runtime.executor(), vec![
+			"https://first.example/price".to_owned(),
+			"https://second.example/price".to_owned(),
+		]);
+		let (tx, rx) = mpsc::channel();
+
+		// when
+		price_info.get(move |price| {
+			tx.send(price.ethusd).unwrap();
+		});
+
+		// then
+		assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(180.25));
+	}
+
+	#[test]
+	fn should_fall_back_to_secondary_source() {
+		let runtime = Runtime::with_thread_count(1);
+
+		// given
+		let response = r#"{"ethereum":{"usd":195.5}}"#;
+		let fetch = FakeFetch::new(None::<String>)
+			.with_response("https://secondary.example/price", response);
+		let price_info = Client::with_sources(runtime.executor(), vec![
+			Arc::new(EtherscanSource::new(fetch.clone(), "https://primary.example/price".to_owned())) as Arc<dyn PriceSource>,
+			Arc::new(CoinGeckoSource::new(fetch.clone(), "https://secondary.example/price".to_owned())),
+			Arc::new(EtherscanSource::new(fetch, "https://tertiary.example/price".to_owned())),
+		]);
+		let (tx, rx) = mpsc::channel();
+
+		// when
+		price_info.get(move |price| {
+			tx.send((price.source, price.ethusd)).unwrap();
+		});
+
+		// then
+		assert_eq!(
+			rx.recv_timeout(Duration::from_secs(5)),
+			Ok(("https://secondary.example/price".to_owned(), 195.5))
+		);
+		let health = price_info.health();
+		assert_eq!(health[0].1.consecutive_failures, 1);
+		assert!(health[0].1.last_error.is_some());
+		assert_eq!(health[1].1.consecutive_failures, 0);
+		assert!(health[1].1.last_success.is_some());
+		// sources after the successful one are never queried
+		assert_eq!(health[2].1, Default::default());
+	}
+
+	#[test]
+	fn should_track_failures_of_all_sources() {
+		let runtime = Runtime::with_thread_count(1);
+
+		// given
+		let fetch = FakeFetch::new(None::<String>);
+		let price_info = Client::with_endpoints(fetch, runtime.executor(), vec![
+			"https://first.example/price".to_owned(),
+			"https://second.example/price".to_owned(),
+		]);
+
+		// when
+		for _ in 0..2 {
+			let (tx, rx) = mpsc::channel();
+			price_info.get(move |price| tx.send(price.ethusd).unwrap());
+			// the callback is dropped without being called once every source failed
+			assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Err(mpsc::RecvTimeoutError::Disconnected));
+		}
+
+		// then
+		let health = price_info.health();
+		assert_eq!(health[0].1.consecutive_failures, 2);
+		assert_eq!(health[1].1.consecutive_failures, 2);
+		assert!(health.iter().all(|(_, h)| h.last_success.is_none()));
+	}
 }
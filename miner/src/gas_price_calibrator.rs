@@ -16,14 +16,21 @@
 
 //! Auto-updates minimal gas price requirement from a price-info source.
 
-use std::time::{Instant, Duration};
+use std::sync::Arc;
+use std::time::{Instant, Duration, SystemTime};
 
 use ansi_term::Colour;
 use ethereum_types::U256;
+use parking_lot::Mutex;
 use parity_runtime::Executor;
-use price_info::{Client as PriceInfoClient, PriceInfo};
+use price_info::{
+	Client as PriceInfoClient, PriceInfo, PriceSource, EtherscanSource, CoinGeckoSource,
+	COINGECKO_ETH_PRICE_ENDPOINT,
+};
 use price_info::fetch::Client as FetchClient;
 
+use gas_pricer::{CalibrationInfo, CalibrationStatus, PriceSourceStatus};
+
 /// Options for the dynamic gas price recalibrator.
 #[derive(Debug, PartialEq)]
 pub struct GasPriceCalibratorOptions {
@@ -31,30 +38,108 @@ pub struct GasPriceCalibratorOptions {
 	pub usd_per_tx: f32,
 	/// How frequently we should recalibrate.
 	pub recalibration_period: Duration,
+	/// How long calibration may keep failing before reverting to `fallback_gas_price`.
+	/// `None` keeps the last calibrated price indefinitely.
+	pub stale_after: Option<Duration>,
+	/// Gas price used once no price source succeeded for `stale_after`.
+	pub fallback_gas_price: U256,
+}
+
+#[derive(Debug)]
+struct CalibrationState {
+	last_calibration: Option<CalibrationInfo>,
+	/// Time of the last successful calibration, or of creation if there was none yet.
+	last_success: Instant,
+	/// Whether the fallback gas price is in use since the last successful calibration.
+	reverted: bool,
 }
 
 /// The gas price validator variant for a `GasPricer`.
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct GasPriceCalibrator {
 	options: GasPriceCalibratorOptions,
 	next_calibration: Instant,
 	price_info: PriceInfoClient,
+	state: Arc<Mutex<CalibrationState>>,
+}
+
+impl PartialEq for GasPriceCalibrator {
+	fn eq(&self, other: &GasPriceCalibrator) -> bool {
+		self.options == other.options && self.price_info == other.price_info
+	}
 }
 
 impl GasPriceCalibrator {
-	/// Create a new gas price calibrator.
+	/// Create a new gas price calibrator querying the Etherscan-compatible `api_endpoint`.
 	pub fn new(options: GasPriceCalibratorOptions, fetch: FetchClient, p: Executor, api_endpoint: String) -> GasPriceCalibrator {
+		GasPriceCalibrator::with_client(options, PriceInfoClient::new(fetch, p, api_endpoint))
+	}
+
+	/// Create a new gas price calibrator querying the Etherscan-compatible `api_endpoints` in order,
+	/// falling back to CoinGecko if all of them fail.
+	pub fn with_coingecko_fallback(options: GasPriceCalibratorOptions, fetch: FetchClient, p: Executor, api_endpoints: Vec<String>) -> GasPriceCalibrator {
+		let mut sources: Vec<Arc<dyn PriceSource>> = api_endpoints.into_iter()
+			.map(|endpoint| Arc::new(EtherscanSource::new(fetch.clone(), endpoint)) as Arc<dyn PriceSource>)
+			.collect();
+		sources.push(Arc::new(CoinGeckoSource::new(fetch, COINGECKO_ETH_PRICE_ENDPOINT.to_owned())));
+		GasPriceCalibrator::with_client(options, PriceInfoClient::with_sources(p, sources))
+	}
+
+	/// Create a new gas price calibrator querying price-info sources in order,
+	/// falling back to the next one if a source fails.
+	pub fn with_endpoints(options: GasPriceCalibratorOptions, fetch: FetchClient, p: Executor, api_endpoints: Vec<String>) -> GasPriceCalibrator {
+		GasPriceCalibrator::with_client(options, PriceInfoClient::with_endpoints(fetch, p, api_endpoints))
+	}
+
+	/// Create a new gas price calibrator using the given price-info client.
+	pub fn with_client(options: GasPriceCalibratorOptions, price_info: PriceInfoClient) -> GasPriceCalibrator {
 		GasPriceCalibrator {
 			options: options,
 			next_calibration: Instant::now(),
-			price_info: PriceInfoClient::new(fetch, p, api_endpoint),
+			price_info: price_info,
+			state: Arc::new(Mutex::new(CalibrationState {
+				last_calibration: None,
+				last_success: Instant::now(),
+				reverted: false,
+			})),
+		}
+	}
+
+	/// Metadata of the last successful calibration.
+	pub fn last_calibration(&self) -> Option<CalibrationInfo> {
+		self.state.lock().last_calibration.clone()
+	}
+
+	/// Last successful calibration and health of every price source, in priority order.
+	pub fn status(&self) -> CalibrationStatus {
+		CalibrationStatus {
+			last_calibration: self.last_calibration(),
+			sources: self.price_info.health().into_iter().map(|(name, health)| PriceSourceStatus {
+				name,
+				consecutive_failures: health.consecutive_failures,
+				last_success: health.last_success.and_then(|at| SystemTime::now().checked_sub(at.elapsed())),
+				last_error: health.last_error,
+			}).collect(),
 		}
 	}
 
 	pub(crate) fn recalibrate<F: FnOnce(U256) + Sync + Send + 'static>(&mut self, set_price: F) {
 		trace!(target: "miner", "Recalibrating {:?} versus {:?}", Instant::now(), self.next_calibration);
 		if Instant::now() >= self.next_calibration {
+			{
+				let mut state = self.state.lock();
+				let stale = self.options.stale_after.map_or(false, |after| state.last_success.elapsed() >= after);
+				if !state.reverted && stale {
+					state.reverted = true;
+					warn!(target: "miner", "No ETH price update for {:?}, reverting to the fallback gas price of {} wei/gas", state.last_success.elapsed(), self.options.fallback_gas_price);
+					set_price(self.options.fallback_gas_price);
+					// Leave `next_calibration` as is, so the next recalibration fetches the price again.
+					return;
+				}
+			}
+
 			let usd_per_tx = self.options.usd_per_tx;
+			let state = self.state.clone();
 			trace!(target: "miner", "Getting price info");
 
 			self.price_info.get(move |price: PriceInfo| {
@@ -64,10 +149,121 @@ impl GasPriceCalibrator {
 				let gas_per_tx: f32 = 21000.0;
 				let wei_per_gas: f32 = wei_per_usd * usd_per_tx / gas_per_tx;
 				info!(target: "miner", "Updated conversion rate to Ξ1 = {} ({} wei/gas)", Colour::White.bold().paint(format!("US${:.2}", usd_per_eth)), Colour::Yellow.bold().paint(format!("{}", wei_per_gas)));
-				set_price(U256::from(wei_per_gas as u64));
+				let gas_price = U256::from(wei_per_gas as u64);
+				{
+					let mut state = state.lock();
+					state.last_success = Instant::now();
+					state.reverted = false;
+					state.last_calibration = Some(CalibrationInfo {
+						source: price.source,
+						timestamp: SystemTime::now(),
+						ethusd: usd_per_eth,
+						gas_price,
+					});
+				}
+				set_price(gas_price);
 			});
 
 			self.next_calibration = Instant::now() + self.options.recalibration_period;
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::time::Duration;
+
+	use ethereum_types::U256;
+	use futures::future;
+	use parity_runtime::Executor;
+	use parking_lot::Mutex;
+	use price_info::{Client as PriceInfoClient, PriceFuture, PriceSource};
+	use price_info::fetch;
+
+	use super::{GasPriceCalibrator, GasPriceCalibratorOptions};
+
+	struct FakeSource {
+		name: &'static str,
+		price: Arc<Mutex<Option<f32>>>,
+	}
+
+	impl PriceSource for FakeSource {
+		fn name(&self) -> &str {
+			self.name
+		}
+
+		fn fetch_price(&self, _abort: fetch::Abort) -> PriceFuture {
+			match *self.price.lock() {
+				Some(price) => Box::new(future::ok(price)),
+				None => Box::new(future::err(fetch::Error::Timeout)),
+			}
+		}
+	}
+
+	fn calibrator(sources: Vec<FakeSource>, stale_after: Option<Duration>) -> GasPriceCalibrator {
+		let sources = sources.into_iter().map(|s| Arc::new(s) as Arc<dyn PriceSource>).collect();
+		GasPriceCalibrator::with_client(
+			GasPriceCalibratorOptions {
+				usd_per_tx: 0.0021,
+				recalibration_period: Duration::from_secs(0),
+				stale_after,
+				fallback_gas_price: 42.into(),
+			},
+			PriceInfoClient::with_sources(Executor::new_sync(), sources),
+		)
+	}
+
+	fn current_price(calibrator: &mut GasPriceCalibrator) -> Option<U256> {
+		let price = Arc::new(Mutex::new(None));
+		let price2 = price.clone();
+		calibrator.recalibrate(move |p| *price2.lock() = Some(p));
+		let p = *price.lock();
+		p
+	}
+
+	#[test]
+	fn should_use_first_available_source() {
+		let failing = Arc::new(Mutex::new(None));
+		let working = Arc::new(Mutex::new(Some(100.0)));
+		let mut calibrator = calibrator(vec![
+			FakeSource { name: "first", price: failing },
+			FakeSource { name: "second", price: working },
+		], None);
+
+		let price = current_price(&mut calibrator).unwrap();
+		let info = calibrator.last_calibration().unwrap();
+		assert_eq!(info.source, "second");
+		assert_eq!(info.ethusd, 100.0);
+		assert_eq!(info.gas_price, price);
+
+		let status = calibrator.status();
+		assert_eq!(status.last_calibration, Some(info));
+		assert_eq!(status.sources.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["first", "second"]);
+		assert_eq!(status.sources[0].consecutive_failures, 1);
+		assert!(status.sources[0].last_success.is_none());
+		assert!(status.sources[0].last_error.is_some());
+		assert_eq!(status.sources[1].consecutive_failures, 0);
+		assert!(status.sources[1].last_success.is_some());
+	}
+
+	#[test]
+	fn should_revert_to_fallback_price_once_stale() {
+		let price = Arc::new(Mutex::new(None));
+		let mut calibrator = calibrator(vec![
+			FakeSource { name: "only", price: price.clone() },
+		], Some(Duration::from_secs(0)));
+
+		// no source succeeded yet, revert to the fallback price
+		assert_eq!(current_price(&mut calibrator), Some(42.into()));
+		// reverted only once; the next recalibration tries the sources again
+		assert_eq!(current_price(&mut calibrator), None);
+		assert!(calibrator.last_calibration().is_none());
+
+		// a successful calibration takes over again
+		*price.lock() = Some(100.0);
+		let calibrated = current_price(&mut calibrator).unwrap();
+		assert_ne!(calibrated, 42.into());
+		assert_eq!(calibrator.last_calibration().unwrap().gas_price, calibrated);
+	}
+}
@@ -16,10 +16,49 @@
 
 //! Auto-updates minimal gas price requirement.
 
-use ethereum_types::U256;
+use std::cmp;
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+use ethereum_types::{H256, U256};
 #[cfg(feature = "price-info")]
 use gas_price_calibrator::GasPriceCalibrator;
 
+/// Metadata of the last successful calibration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationInfo {
+	/// Name of the price source used.
+	pub source: String,
+	/// Time the price was fetched.
+	pub timestamp: SystemTime,
+	/// Fetched ETH price in USD.
+	pub ethusd: f32,
+	/// Resulting gas price in wei.
+	pub gas_price: U256,
+}
+
+/// Health of a price source used for calibration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceSourceStatus {
+	/// Name of the source.
+	pub name: String,
+	/// Number of failed requests since the last successful one.
+	pub consecutive_failures: u32,
+	/// Time of the last successful request.
+	pub last_success: Option<SystemTime>,
+	/// Last error returned by the source.
+	pub last_error: Option<String>,
+}
+
+/// Status of a calibrated `GasPricer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalibrationStatus {
+	/// Metadata of the last successful calibration, if any.
+	pub last_calibration: Option<CalibrationInfo>,
+	/// Health of every price source, in priority order.
+	pub sources: Vec<PriceSourceStatus>,
+}
+
 /// Struct to look after updating the acceptable gas price of a miner.
 #[derive(Debug, PartialEq)]
 pub enum GasPricer {
@@ -28,6 +67,41 @@ pub enum GasPricer {
 	/// Gas price is calibrated according to a fixed amount of USD.
 	#[cfg(feature = "price-info")]
 	Calibrated(GasPriceCalibrator),
+	/// Gas price follows the median transaction gas price of the last `window` blocks.
+	MedianOfRecent {
+		/// Number of recent blocks taken into account.
+		window: usize,
+		/// Lowest gas price, used while there are no transactions to look at.
+		floor: U256,
+		/// Hash and transaction gas prices of each recent block, oldest first.
+		blocks: VecDeque<(H256, Vec<U256>)>,
+	},
+	/// Gas price is raised when recent blocks are full and lowered when they are not.
+	DynamicMinGasPrice {
+		/// Adjustment parameters.
+		options: DynamicMinGasPriceOptions,
+		/// Fullness of each recent block in percent, oldest first.
+		fullness: VecDeque<u64>,
+		/// Current minimal gas price.
+		current: U256,
+	},
+}
+
+/// Parameters of the `DynamicMinGasPrice` pricer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicMinGasPriceOptions {
+	/// Number of recent blocks whose average fullness is taken into account.
+	pub window: usize,
+	/// Average fullness in percent above which the price is raised.
+	pub high_watermark: u64,
+	/// Average fullness in percent below which the price is lowered.
+	pub low_watermark: u64,
+	/// Multiplicative step in percent by which the price is raised or lowered.
+	pub step: u64,
+	/// Lowest minimal gas price, also the initial one.
+	pub floor: U256,
+	/// Highest minimal gas price.
+	pub ceiling: U256,
 }
 
 impl GasPricer {
@@ -42,12 +116,250 @@ impl GasPricer {
 		GasPricer::Fixed(gas_price)
 	}
 
+	/// Create a new `GasPricer` following the median gas price of the last `window` blocks,
+	/// never going below `floor`.
+	pub fn new_median_of_recent(window: usize, floor: U256) -> GasPricer {
+		GasPricer::MedianOfRecent {
+			window,
+			floor,
+			blocks: VecDeque::with_capacity(window),
+		}
+	}
+
+	/// Create a new `GasPricer` adjusting the gas price to the fullness of recent blocks.
+	pub fn new_dynamic(options: DynamicMinGasPriceOptions) -> GasPricer {
+		GasPricer::DynamicMinGasPrice {
+			current: options.floor,
+			fullness: VecDeque::with_capacity(options.window),
+			options,
+		}
+	}
+
+	/// Note gas prices of transactions in a newly imported block.
+	///
+	/// Only affects `MedianOfRecent` pricer.
+	pub fn note_block(&mut self, hash: H256, gas_prices: Vec<U256>) {
+		if let GasPricer::MedianOfRecent { window, ref mut blocks, .. } = *self {
+			blocks.push_back((hash, gas_prices));
+			while blocks.len() > window {
+				blocks.pop_front();
+			}
+		}
+	}
+
+	/// Forget gas prices of a block retracted from the canonical chain.
+	///
+	/// Only affects `MedianOfRecent` pricer.
+	pub fn note_retracted(&mut self, hash: &H256) {
+		if let GasPricer::MedianOfRecent { ref mut blocks, .. } = *self {
+			blocks.retain(|(h, _)| h != hash);
+		}
+	}
+
+	/// Note gas used and gas limit of a newly imported block.
+	///
+	/// Only affects `DynamicMinGasPrice` pricer, returns the new gas price if it was adjusted.
+	pub fn note_block_fullness(&mut self, gas_used: U256, gas_limit: U256) -> Option<U256> {
+		if let GasPricer::DynamicMinGasPrice { ref options, ref mut fullness, ref mut current } = *self {
+			let percent = if gas_limit.is_zero() {
+				0
+			} else {
+				(gas_used.saturating_mul(100.into()) / gas_limit).low_u64()
+			};
+			fullness.push_back(percent);
+			while fullness.len() > options.window {
+				fullness.pop_front();
+			}
+
+			let average = fullness.iter().sum::<u64>() / fullness.len() as u64;
+			let adjusted = if average > options.high_watermark {
+				let raise = cmp::max(current.saturating_mul(options.step.into()) / 100, 1.into());
+				cmp::min(current.saturating_add(raise), options.ceiling)
+			} else if average < options.low_watermark {
+				cmp::max(current.saturating_mul(100.into()) / options.step.saturating_add(100), options.floor)
+			} else {
+				*current
+			};
+
+			if adjusted != *current {
+				*current = adjusted;
+				return Some(adjusted);
+			}
+		}
+		None
+	}
+
+	/// Current minimal gas price of the `DynamicMinGasPrice` pricer, `None` for other pricers.
+	pub fn dynamic_min_gas_price(&self) -> Option<U256> {
+		match *self {
+			GasPricer::DynamicMinGasPrice { current, .. } => Some(current),
+			_ => None,
+		}
+	}
+
+	/// Last calibration and price source health if the gas price is calibrated, `None` for other pricers.
+	pub fn calibration_status(&self) -> Option<CalibrationStatus> {
+		match *self {
+			#[cfg(feature = "price-info")]
+			GasPricer::Calibrated(ref cal) => Some(cal.status()),
+			GasPricer::Fixed(_) | GasPricer::MedianOfRecent { .. } | GasPricer::DynamicMinGasPrice { .. } => None,
+		}
+	}
+
 	/// Recalibrate current gas price.
 	pub fn recalibrate<F: FnOnce(U256) + Sync + Send + 'static>(&mut self, set_price: F) {
 		match *self {
 			GasPricer::Fixed(ref curr) => set_price(curr.clone()),
 			#[cfg(feature = "price-info")]
 			GasPricer::Calibrated(ref mut cal) => cal.recalibrate(set_price),
+			GasPricer::MedianOfRecent { ref blocks, floor, .. } => {
+				// Fall back to the floor until there are some transactions to look at.
+				let median = median(blocks.iter().flat_map(|(_, b)| b.iter().cloned()).collect());
+				set_price(median.map_or(floor, |median| cmp::max(median, floor)))
+			},
+			GasPricer::DynamicMinGasPrice { ref current, .. } => set_price(*current),
+		}
+	}
+}
+
+fn median(mut prices: Vec<U256>) -> Option<U256> {
+	if prices.is_empty() {
+		return None;
+	}
+
+	prices.sort();
+	let mid = prices.len() / 2;
+	if prices.len() % 2 == 0 {
+		Some((prices[mid - 1] + prices[mid]) / 2)
+	} else {
+		Some(prices[mid])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::{Arc, Mutex};
+
+	use super::*;
+
+	fn current_price(pricer: &mut GasPricer) -> Option<U256> {
+		let price = Arc::new(Mutex::new(None));
+		let price2 = price.clone();
+		pricer.recalibrate(move |p| *price2.lock().unwrap() = Some(p));
+		let p = *price.lock().unwrap();
+		p
+	}
+
+	fn dynamic_options(floor: U256, ceiling: U256) -> DynamicMinGasPriceOptions {
+		DynamicMinGasPriceOptions {
+			window: 20,
+			high_watermark: 90,
+			low_watermark: 50,
+			step: 10,
+			floor,
+			ceiling,
+		}
+	}
+
+	#[test]
+	fn median_of_recent_should_follow_last_blocks() {
+		let mut pricer = GasPricer::new_median_of_recent(2, 0.into());
+		assert_eq!(current_price(&mut pricer), Some(0.into()));
+
+		pricer.note_block(H256::from_low_u64_be(1), vec![1.into(), 2.into(), 3.into()]);
+		assert_eq!(current_price(&mut pricer), Some(2.into()));
+
+		pricer.note_block(H256::from_low_u64_be(2), vec![10.into(), 20.into(), 30.into()]);
+		assert_eq!(current_price(&mut pricer), Some(6.into()));
+
+		// first block falls out of the window
+		pricer.note_block(H256::from_low_u64_be(3), vec![40.into()]);
+		assert_eq!(current_price(&mut pricer), Some(25.into()));
+
+		// empty blocks don't affect the median
+		pricer.note_block(H256::from_low_u64_be(4), vec![]);
+		assert_eq!(current_price(&mut pricer), Some(40.into()));
+	}
+
+	#[test]
+	fn median_of_recent_should_respect_floor_and_retractions() {
+		let mut pricer = GasPricer::new_median_of_recent(3, 5.into());
+		assert_eq!(current_price(&mut pricer), Some(5.into()));
+
+		pricer.note_block(H256::from_low_u64_be(1), vec![1.into()]);
+		assert_eq!(current_price(&mut pricer), Some(5.into()));
+
+		pricer.note_block(H256::from_low_u64_be(2), vec![100.into()]);
+		assert_eq!(current_price(&mut pricer), Some(50.into()));
+
+		// a retracted block no longer counts.
+		pricer.note_retracted(&H256::from_low_u64_be(2));
+		assert_eq!(current_price(&mut pricer), Some(5.into()));
+
+		pricer.note_retracted(&H256::from_low_u64_be(1));
+		assert_eq!(current_price(&mut pricer), Some(5.into()));
+	}
+
+	#[test]
+	fn fixed_should_ignore_blocks() {
+		let mut pricer = GasPricer::new_fixed(5.into());
+		pricer.note_block(H256::zero(), vec![100.into()]);
+		assert_eq!(pricer.note_block_fullness(100.into(), 100.into()), None);
+		assert_eq!(current_price(&mut pricer), Some(5.into()));
+		assert_eq!(pricer.dynamic_min_gas_price(), None);
+	}
+
+	#[test]
+	fn dynamic_should_follow_block_fullness() {
+		let mut pricer = GasPricer::new_dynamic(DynamicMinGasPriceOptions {
+			window: 2,
+			..dynamic_options(1000.into(), 1300.into())
+		});
+		assert_eq!(current_price(&mut pricer), Some(1000.into()));
+
+		let trajectory: Vec<_> = [100u64, 95, 70, 50, 0, 0, 0]
+			.iter()
+			.map(|&percent| {
+				pricer.note_block_fullness((percent * 100).into(), 10_000.into());
+				current_price(&mut pricer).unwrap().low_u64()
+			})
+			.collect();
+
+		// averages: 100, 97, 82, 60, 25, 0, 0
+		assert_eq!(trajectory, vec![1100, 1210, 1210, 1210, 1100, 1000, 1000]);
+	}
+
+	#[test]
+	fn dynamic_should_stay_within_bounds() {
+		let mut pricer = GasPricer::new_dynamic(dynamic_options(1.into(), 3.into()));
+
+		// a small price still moves by at least 1 wei.
+		assert_eq!(pricer.note_block_fullness(100.into(), 100.into()), Some(2.into()));
+		assert_eq!(pricer.note_block_fullness(100.into(), 100.into()), Some(3.into()));
+		assert_eq!(pricer.note_block_fullness(100.into(), 100.into()), None);
+		assert_eq!(current_price(&mut pricer), Some(3.into()));
+		assert_eq!(pricer.dynamic_min_gas_price(), Some(3.into()));
+
+		for _ in 0..40 {
+			pricer.note_block_fullness(0.into(), 100.into());
+		}
+		assert_eq!(current_price(&mut pricer), Some(1.into()));
+
+		// blocks with no gas limit count as empty.
+		assert_eq!(pricer.note_block_fullness(0.into(), 0.into()), None);
+	}
+
+	#[test]
+	fn dynamic_should_not_overflow_near_max_price() {
+		let max = U256::max_value();
+		let mut pricer = GasPricer::new_dynamic(dynamic_options(max - 1, max));
+
+		assert_eq!(pricer.note_block_fullness(100.into(), 100.into()), Some(max));
+		assert_eq!(pricer.note_block_fullness(100.into(), 100.into()), None);
+
+		for _ in 0..40 {
+			pricer.note_block_fullness(0.into(), 100.into());
 		}
+		assert_eq!(current_price(&mut pricer), Some(max - 1));
 	}
 }
@@ -61,5 +61,6 @@ pub mod gas_pricer;
 pub mod local_accounts;
 pub mod pool;
 pub mod service_transaction_checker;
+pub mod transaction_permissioning;
 #[cfg(feature = "work-notify")]
 pub mod work_notify;
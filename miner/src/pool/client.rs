@@ -63,6 +63,13 @@ pub trait Client: fmt::Debug + Sync {
 	fn verify_transaction(&self, tx: transaction::UnverifiedTransaction)
 		-> Result<transaction::SignedTransaction, transaction::Error>;
 
+	/// Check if the sender of given transaction is permitted to submit transactions to the pool.
+	///
+	/// This method is used for both local and external transactions.
+	fn verify_sender_permitted(&self, _tx: &transaction::SignedTransaction) -> Result<(), transaction::Error> {
+		Ok(())
+	}
+
 	/// Estimate minimal gas requirurement for given transaction.
 	fn required_gas(&self, tx: &transaction::Transaction) -> U256;
 
@@ -81,4 +88,10 @@ pub trait Client: fmt::Debug + Sync {
 pub trait NonceClient: fmt::Debug + Sync {
 	/// Fetch only account nonce for given sender.
 	fn account_nonce(&self, address: &Address) -> U256;
+
+	/// Nonce following the transactions of given sender which are already sealed in a pending
+	/// block, if there are any. Nonces between the state nonce and this one are taken.
+	fn sealed_nonce(&self, _address: &Address) -> Option<U256> {
+		None
+	}
 }
@@ -16,6 +16,7 @@
 
 //! Notifier for new transaction hashes.
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
 
@@ -24,7 +25,7 @@ use futures::sync::mpsc;
 use txpool::{self, VerifiedTransaction};
 
 use pool::VerifiedTransaction as Transaction;
-use pool::TxStatus;
+use pool::{DropReason, EvictReason, ScoredTransaction, TxStatus};
 
 /// Transaction pool logger.
 #[derive(Default, Debug)]
@@ -80,6 +81,7 @@ pub struct TransactionsPoolNotifier {
 	full_listeners: Vec<mpsc::UnboundedSender<Arc<Vec<(H256, TxStatus)>>>>,
 	pending_listeners: Vec<mpsc::UnboundedSender<Arc<Vec<H256>>>>,
 	tx_statuses: Vec<(H256, TxStatus)>,
+	dropping: Option<DropReason>,
 }
 
 impl TransactionsPoolNotifier {
@@ -93,6 +95,11 @@ impl TransactionsPoolNotifier {
 		self.pending_listeners.push(f);
 	}
 
+	/// Report transactions removed from the pool as dropped (instead of canceled).
+	pub fn set_dropping(&mut self, reason: Option<DropReason>) {
+		self.dropping = reason;
+	}
+
 	/// Notify listeners about all currently transactions.
 	pub fn notify(&mut self) {
 		if self.tx_statuses.is_empty() {
@@ -146,7 +153,11 @@ impl txpool::Listener<Transaction> for TransactionsPoolNotifier {
 	}
 
 	fn canceled(&mut self, tx: &Arc<Transaction>) {
-		self.tx_statuses.push((tx.hash.clone(), TxStatus::Canceled));
+		let status = match self.dropping {
+			Some(_) => TxStatus::Dropped,
+			None => TxStatus::Canceled,
+		};
+		self.tx_statuses.push((tx.hash.clone(), status));
 	}
 
 	fn culled(&mut self, tx: &Arc<Transaction>) {
@@ -154,6 +165,62 @@ impl txpool::Listener<Transaction> for TransactionsPoolNotifier {
 	}
 }
 
+/// Counts transactions evicted from the pool by reason.
+#[derive(Default, Debug)]
+pub struct EvictionStats {
+	stats: BTreeMap<EvictReason, u64>,
+	dropping: Option<DropReason>,
+}
+
+impl EvictionStats {
+	/// Number of evicted transactions, grouped by eviction reason.
+	pub fn stats(&self) -> &BTreeMap<EvictReason, u64> {
+		&self.stats
+	}
+
+	/// Count transactions removed from the pool as dropped for given reason (instead of canceled).
+	pub fn set_dropping(&mut self, reason: Option<DropReason>) {
+		self.dropping = reason;
+	}
+
+	fn record(&mut self, reason: EvictReason) {
+		*self.stats.entry(reason).or_insert(0) += 1;
+	}
+}
+
+impl txpool::Listener<Transaction> for EvictionStats {
+	fn added(&mut self, tx: &Arc<Transaction>, old: Option<&Arc<Transaction>>) {
+		if let Some(old) = old {
+			if old.sender() == tx.sender() && old.nonce() == tx.nonce() {
+				self.record(EvictReason::Replaced);
+			} else {
+				self.record(EvictReason::SenderLimit);
+			}
+		}
+	}
+
+	fn dropped(&mut self, _tx: &Arc<Transaction>, new: Option<&Transaction>) {
+		// the pool only drops transactions without a replacement when it is cleared.
+		match new {
+			Some(_) => self.record(DropReason::Limit.into()),
+			None => self.record(EvictReason::Cleared),
+		}
+	}
+
+	fn invalid(&mut self, _tx: &Arc<Transaction>) {
+		self.record(EvictReason::Invalid);
+	}
+
+	fn canceled(&mut self, _tx: &Arc<Transaction>) {
+		let reason = self.dropping.map_or(EvictReason::Canceled, Into::into);
+		self.record(reason);
+	}
+
+	fn culled(&mut self, _tx: &Arc<Transaction>) {
+		self.record(EvictReason::Stale);
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -190,6 +257,60 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_report_dropped_instead_of_canceled() {
+		// given
+		let (full_sender, full_receiver) = mpsc::unbounded();
+		let mut tx_listener = TransactionsPoolNotifier::default();
+		tx_listener.add_full_listener(full_sender);
+		let mut stats = EvictionStats::default();
+
+		// when
+		let tx = new_tx();
+		tx_listener.set_dropping(Some(DropReason::RepeatedlyFailing));
+		tx_listener.canceled(&tx);
+		tx_listener.set_dropping(None);
+		tx_listener.canceled(&tx);
+		stats.set_dropping(Some(DropReason::RepeatedlyFailing));
+		stats.canceled(&tx);
+
+		// then
+		tx_listener.notify();
+		let (full_res, _full_receiver) = full_receiver.into_future().wait().unwrap();
+		assert_eq!(
+			full_res,
+			Some(Arc::new(vec![
+				(tx.hash, TxStatus::Dropped),
+				(tx.hash, TxStatus::Canceled),
+			]))
+		);
+		assert_eq!(stats.stats().get(&EvictReason::RepeatedlyFailing), Some(&1));
+		assert_eq!(stats.stats().get(&EvictReason::Canceled), None);
+	}
+
+	#[test]
+	fn should_keep_drop_reason_out_of_notifications() {
+		// given
+		let (full_sender, full_receiver) = mpsc::unbounded();
+		let mut tx_listener = TransactionsPoolNotifier::default();
+		tx_listener.add_full_listener(full_sender);
+		let mut stats = EvictionStats::default();
+
+		// when
+		let tx = new_tx();
+		tx_listener.dropped(&tx, None);
+		stats.dropped(&tx, Some(&tx));
+		stats.dropped(&tx, None);
+
+		// then
+		tx_listener.notify();
+		let (full_res, _full_receiver) = full_receiver.into_future().wait().unwrap();
+		assert_eq!(full_res, Some(Arc::new(vec![(tx.hash, TxStatus::Dropped)])));
+		assert_eq!(serde_json::to_string(&TxStatus::Dropped).unwrap(), r#""dropped""#);
+		assert_eq!(stats.stats().get(&EvictReason::PoolFull), Some(&1));
+		assert_eq!(stats.stats().get(&EvictReason::Cleared), Some(&1));
+	}
+
 	fn new_tx() -> Arc<Transaction> {
 		let signed = transaction::Transaction {
 			action: transaction::Action::Create,
@@ -20,7 +20,7 @@ use std::{fmt, sync::Arc};
 
 use ethereum_types::H256;
 use linked_hash_map::LinkedHashMap;
-use pool::{VerifiedTransaction as Transaction, ScoredTransaction};
+use pool::{DropReason, VerifiedTransaction as Transaction, ScoredTransaction};
 use txpool::{self, VerifiedTransaction};
 
 /// Status of local transaction.
@@ -67,6 +67,7 @@ pub struct LocalTransactionsList {
 	transactions: LinkedHashMap<H256, Status>,
 	pending: usize,
 	in_chain: Option<Box<dyn Fn(&H256) -> bool + Send + Sync>>,
+	dropping: Option<DropReason>,
 }
 
 impl fmt::Debug for LocalTransactionsList {
@@ -76,6 +77,7 @@ impl fmt::Debug for LocalTransactionsList {
 			.field("transactions", &self.transactions)
 			.field("pending", &self.pending)
 			.field("in_chain", &self.in_chain.is_some())
+			.field("dropping", &self.dropping)
 			.finish()
 	}
 }
@@ -94,9 +96,15 @@ impl LocalTransactionsList {
 			transactions: Default::default(),
 			pending: 0,
 			in_chain: None,
+			dropping: None,
 		}
 	}
 
+	/// Mark transactions removed from the pool as dropped for given reason (instead of canceled).
+	pub fn set_dropping(&mut self, reason: Option<DropReason>) {
+		self.dropping = reason;
+	}
+
 	/// Set blockchain checker.
 	///
 	/// The function should return true if transaction is included in chain.
@@ -209,8 +217,13 @@ impl txpool::Listener<Transaction> for LocalTransactionsList {
 			return;
 		}
 
-		warn!(target: "own_tx", "Transaction canceled (hash {:?})", tx.hash());
-		self.insert(*tx.hash(), Status::Canceled(tx.clone()));
+		if let Some(reason) = self.dropping {
+			warn!(target: "own_tx", "Transaction dropped (hash {:?}, reason: {:?})", tx.hash(), reason);
+			self.insert(*tx.hash(), Status::Dropped(tx.clone()));
+		} else {
+			warn!(target: "own_tx", "Transaction canceled (hash {:?})", tx.hash());
+			self.insert(*tx.hash(), Status::Canceled(tx.clone()));
+		}
 		self.clear_old();
 	}
 
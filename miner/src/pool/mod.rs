@@ -22,6 +22,7 @@ use types::transaction;
 use txpool;
 
 mod listener;
+mod nonce_cap;
 mod queue;
 mod ready;
 
@@ -34,7 +35,8 @@ pub mod verifier;
 #[cfg(test)]
 mod tests;
 
-pub use self::queue::{TransactionQueue, Status as QueueStatus};
+pub use self::nonce_cap::{NonceCap, NonceCapClient};
+pub use self::queue::{TransactionQueue, Status as QueueStatus, RejectedTransaction, SenderStatus};
 pub use self::txpool::{VerifiedTransaction as PoolVerifiedTransaction, Options};
 
 /// How to prioritize transactions in the pool
@@ -217,3 +219,45 @@ pub enum TxStatus {
 	/// Culled transaction
 	Culled,
 }
+
+/// Reason for a transaction being dropped from the pool.
+///
+/// Listeners are only notified that the transaction was dropped, the reason is
+/// recorded in the eviction stats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+	/// Pushed out by a better transaction because the pool was full.
+	Limit,
+	/// Failed execution in too many consecutive pending blocks.
+	RepeatedlyFailing,
+}
+
+/// Reason for a transaction being evicted from the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EvictReason {
+	/// Pushed out by a better transaction because the pool was full.
+	PoolFull,
+	/// Replaced by a transaction with the same sender and nonce paying a higher fee.
+	Replaced,
+	/// Pushed out by a better transaction of the same sender because of the per-sender limit.
+	SenderLimit,
+	/// Culled because the nonce is too old (usually mined).
+	Stale,
+	/// Marked as invalid by the executor.
+	Invalid,
+	/// Canceled by the user.
+	Canceled,
+	/// Dropped by the miner after failing execution in too many consecutive pending blocks.
+	RepeatedlyFailing,
+	/// Removed when the whole pool was cleared.
+	Cleared,
+}
+
+impl From<DropReason> for EvictReason {
+	fn from(reason: DropReason) -> Self {
+		match reason {
+			DropReason::Limit => EvictReason::PoolFull,
+			DropReason::RepeatedlyFailing => EvictReason::RepeatedlyFailing,
+		}
+	}
+}
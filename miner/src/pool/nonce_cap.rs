@@ -0,0 +1,108 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Nonces of transactions included in the currently sealed pending block.
+//!
+//! Transactions which made it into the pending block are not yet reflected
+//! in the latest state, and may already be gone from the pool (e.g. removed
+//! or culled). `NonceCap` remembers the next nonce of every sender of
+//! the pending block, so that nonce advice never goes below it and queued
+//! transactions following the sealed ones are ready.
+
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+
+use ethereum_types::{U256, Address};
+
+use super::client::NonceClient;
+
+/// Snapshot of per-sender next nonces derived from a pending block.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct NonceCap {
+	block_number: u64,
+	nonces: HashMap<Address, U256>,
+}
+
+impl NonceCap {
+	/// Create a snapshot for pending block `block_number`
+	/// given `(sender, nonce)` of all its transactions.
+	pub fn new<I>(block_number: u64, transactions: I) -> Self where
+		I: IntoIterator<Item = (Address, U256)>,
+	{
+		let mut nonces = HashMap::new();
+		for (sender, nonce) in transactions {
+			let next = nonce.saturating_add(U256::from(1));
+			let entry = nonces.entry(sender).or_insert(next);
+			*entry = cmp::max(*entry, next);
+		}
+
+		NonceCap {
+			block_number,
+			nonces,
+		}
+	}
+
+	/// Number of the pending block this snapshot was taken from.
+	pub fn block_number(&self) -> u64 {
+		self.block_number
+	}
+
+	/// Whether the pending block has no transactions.
+	pub fn is_empty(&self) -> bool {
+		self.nonces.is_empty()
+	}
+
+	/// Next nonce of `sender` after the pending block, if it has any transactions there.
+	pub fn next_nonce(&self, sender: &Address) -> Option<U256> {
+		self.nonces.get(sender).cloned()
+	}
+
+	/// Wrap given nonce client so that returned nonces account for the pending block.
+	pub fn client<C>(&self, client: C) -> NonceCapClient<C> {
+		NonceCapClient {
+			client,
+			cap: self,
+		}
+	}
+}
+
+/// Nonce client reporting the nonces taken by the pending block as sealed.
+#[derive(Clone)]
+pub struct NonceCapClient<'a, C> {
+	client: C,
+	cap: &'a NonceCap,
+}
+
+impl<'a, C: fmt::Debug> fmt::Debug for NonceCapClient<'a, C> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		fmt.debug_struct("NonceCapClient")
+			.field("client", &self.client)
+			.field("block_number", &self.cap.block_number)
+			.finish()
+	}
+}
+
+impl<'a, C: NonceClient> NonceClient for NonceCapClient<'a, C> {
+	fn account_nonce(&self, address: &Address) -> U256 {
+		self.client.account_nonce(address)
+	}
+
+	fn sealed_nonce(&self, address: &Address) -> Option<U256> {
+		let nonce = self.client.account_nonce(address);
+		self.cap.next_nonce(address).filter(|sealed| *sealed > nonce)
+	}
+}
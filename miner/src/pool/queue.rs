@@ -19,21 +19,22 @@
 use std::{cmp, fmt};
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicUsize};
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use ethereum_types::{H256, U256, Address};
 use futures::sync::mpsc;
 use parking_lot::RwLock;
-use txpool::{self, Verifier};
+use txpool;
 use types::transaction;
 
 use pool::{
 	self, replace, scoring, verifier, client, ready, listener,
-	PrioritizationStrategy, PendingOrdering, PendingSettings, TxStatus
+	PrioritizationStrategy, PendingOrdering, PendingSettings, ScoredTransaction, TxStatus, EvictReason, DropReason,
 };
 use pool::local_transactions::LocalTransactionsList;
 
-type Listener = (LocalTransactionsList, (listener::TransactionsPoolNotifier, listener::Logger));
+type Listener = (LocalTransactionsList, (listener::TransactionsPoolNotifier, (listener::Logger, listener::EvictionStats)));
 type Pool = txpool::Pool<pool::VerifiedTransaction, scoring::NonceAndGasPrice, Listener>;
 
 /// Max cache time in milliseconds for pending transactions.
@@ -191,6 +192,73 @@ impl RecentlyRejected {
 /// Minimal size of rejection cache, by default it's equal to queue size.
 const MIN_REJECTED_CACHE_SIZE: usize = 2048;
 
+/// How many rejected transactions are kept in the rejection history.
+const REJECTION_HISTORY_SIZE: usize = 1024;
+
+/// Transaction refused by the queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RejectedTransaction {
+	/// Transaction hash.
+	pub hash: H256,
+	/// Transaction sender, if it was recovered before the transaction got rejected.
+	pub sender: Option<Address>,
+	/// Reason of the rejection.
+	pub reason: transaction::Error,
+	/// Unix timestamp (in seconds) of the rejection.
+	pub timestamp: u64,
+}
+
+/// Most recent rejections, oldest first.
+#[derive(Debug)]
+struct RejectionHistory {
+	inner: RwLock<VecDeque<RejectedTransaction>>,
+	limit: usize,
+}
+
+impl RejectionHistory {
+	fn new(limit: usize) -> Self {
+		RejectionHistory {
+			inner: RwLock::new(VecDeque::with_capacity(limit)),
+			limit,
+		}
+	}
+
+	fn all(&self) -> Vec<RejectedTransaction> {
+		self.inner.read().iter().cloned().collect()
+	}
+
+	fn insert(&self, hash: H256, rejection: verifier::Rejection) {
+		let timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|d| d.as_secs())
+			.unwrap_or(0);
+
+		let mut inner = self.inner.write();
+		if inner.len() == self.limit {
+			inner.pop_front();
+		}
+		inner.push_back(RejectedTransaction {
+			hash,
+			sender: rejection.sender,
+			reason: rejection.error,
+			timestamp,
+		});
+	}
+}
+
+/// Queued transactions of a single sender.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SenderStatus {
+	/// Transaction sender.
+	pub sender: Address,
+	/// Current state nonce of the sender.
+	pub nonce: U256,
+	/// Queued transactions, ordered by nonce.
+	pub transactions: Vec<Arc<pool::VerifiedTransaction>>,
+	/// First nonce missing in the queue, which prevents later transactions from being mined.
+	pub first_gap: Option<U256>,
+}
+
 /// Ethereum Transaction Queue
 ///
 /// Responsible for:
@@ -202,8 +270,10 @@ pub struct TransactionQueue {
 	insertion_id: Arc<AtomicUsize>,
 	pool: RwLock<Pool>,
 	options: RwLock<verifier::Options>,
+	minimum_gas_price: RwLock<U256>,
 	cached_pending: RwLock<CachedPending>,
 	recently_rejected: RecentlyRejected,
+	rejection_history: RejectionHistory,
 }
 
 impl TransactionQueue {
@@ -218,8 +288,10 @@ impl TransactionQueue {
 			insertion_id: Default::default(),
 			pool: RwLock::new(txpool::Pool::new(Default::default(), scoring::NonceAndGasPrice(strategy), limits)),
 			options: RwLock::new(verification_options),
+			minimum_gas_price: RwLock::new(U256::zero()),
 			cached_pending: RwLock::new(CachedPending::none()),
 			recently_rejected: RecentlyRejected::new(cmp::max(MIN_REJECTED_CACHE_SIZE, max_count / 4)),
+			rejection_history: RejectionHistory::new(REJECTION_HISTORY_SIZE),
 		}
 	}
 
@@ -230,6 +302,19 @@ impl TransactionQueue {
 		*self.options.write() = options;
 	}
 
+	/// Set the gas price floor enforced at import.
+	///
+	/// Unlike the minimal gas price of the verifier options (which follows the gas pricer)
+	/// this is an operator-set floor. Local and service transactions bypass it.
+	pub fn set_minimum_gas_price(&self, minimum_gas_price: U256) {
+		*self.minimum_gas_price.write() = minimum_gas_price;
+	}
+
+	/// Returns the gas price floor enforced at import.
+	pub fn minimum_gas_price(&self) -> U256 {
+		*self.minimum_gas_price.read()
+	}
+
 	/// Sets the in-chain transaction checker for pool listener.
 	pub fn set_in_chain_checker<F>(&self, f: F) where
 		F: Fn(&H256) -> bool + Send + Sync + 'static
@@ -268,7 +353,7 @@ impl TransactionQueue {
 			options,
 			self.insertion_id.clone(),
 			transaction_to_replace,
-		);
+		).with_minimum_gas_price(self.minimum_gas_price());
 
 		let mut replace = replace::ReplaceByScoreAndReadiness::new(self.pool.read().scoring().clone(), client);
 
@@ -287,15 +372,21 @@ impl TransactionQueue {
 				}
 
 				let imported = verifier
-					.verify_transaction(transaction)
+					.verify(transaction)
 					.and_then(|verified| {
-						self.pool.write().import(verified, &mut replace).map_err(convert_error)
+						let sender = verified.sender;
+						self.pool.write().import(verified, &mut replace).map_err(|err| verifier::Rejection {
+							error: convert_error(err),
+							sender: Some(sender),
+						})
 					});
 
 				match imported {
 					Ok(_) => Ok(()),
-					Err(err) => {
+					Err(rejection) => {
+						let err = rejection.error.clone();
 						self.recently_rejected.insert(hash, &err);
+						self.rejection_history.insert(hash, rejection);
 						Err(err)
 					},
 				}
@@ -318,6 +409,101 @@ impl TransactionQueue {
 		self.pool.read().unordered_pending(ready).collect()
 	}
 
+	/// Returns all transactions in the queue, so they can be persisted across restarts.
+	pub fn export_pending(&self) -> Vec<transaction::UnverifiedTransaction> {
+		self.all_transactions()
+			.into_iter()
+			.map(|tx| tx.signed().clone().into())
+			.collect()
+	}
+
+	/// Returns all transactions in the queue together with their local status and condition,
+	/// so local transactions keep their priority and tracking once imported back.
+	pub fn export_pending_with_status(&self) -> Vec<(transaction::UnverifiedTransaction, bool, Option<transaction::Condition>)> {
+		self.all_transactions()
+			.into_iter()
+			.map(|tx| {
+				let is_local = tx.priority().is_local();
+				let condition = if is_local { tx.pending().condition.clone() } else { None };
+				(tx.signed().clone().into(), is_local, condition)
+			})
+			.collect()
+	}
+
+	/// Imports previously exported transactions.
+	///
+	/// Transactions are fully re-verified against the current state,
+	/// so the ones which became invalid in the meantime are rejected.
+	pub fn import_pending<C: client::Client + client::NonceClient + Clone>(
+		&self,
+		client: C,
+		transactions: Vec<transaction::UnverifiedTransaction>,
+	) -> Vec<Result<(), transaction::Error>> {
+		self.import(client, transactions.into_iter().map(verifier::Transaction::Unverified))
+	}
+
+	/// Imports transactions exported with `export_pending_with_status`.
+	///
+	/// The sender of a local transaction is recovered from its signature again before
+	/// it is imported as local, the ones with an invalid signature are rejected.
+	pub fn import_pending_with_status<C: client::Client + client::NonceClient + Clone>(
+		&self,
+		client: C,
+		transactions: Vec<(transaction::UnverifiedTransaction, bool, Option<transaction::Condition>)>,
+	) -> Vec<Result<(), transaction::Error>> {
+		let mut to_import = Vec::with_capacity(transactions.len());
+		let recovered = transactions.into_iter()
+			.map(|(tx, is_local, condition)| {
+				let tx = if is_local {
+					let signed = transaction::SignedTransaction::new(tx)?;
+					verifier::Transaction::Local(transaction::PendingTransaction::new(signed, condition))
+				} else {
+					verifier::Transaction::Unverified(tx)
+				};
+				to_import.push(tx);
+				Ok(())
+			})
+			.collect::<Vec<Result<(), transaction::Error>>>();
+
+		let mut imported = self.import(client, to_import).into_iter();
+		recovered.into_iter()
+			.map(|res| res.and_then(|_| imported.next().expect("one result is returned for every imported transaction; qed")))
+			.collect()
+	}
+
+	/// Returns queued transactions grouped by sender, ordered by sender address.
+	pub fn queue_status_grouped<C: client::NonceClient>(&self, client: C) -> Vec<SenderStatus> {
+		let mut by_sender = BTreeMap::<Address, Vec<_>>::new();
+		for tx in self.all_transactions() {
+			by_sender.entry(tx.sender).or_default().push(tx);
+		}
+
+		by_sender.into_iter().map(|(sender, mut transactions)| {
+			transactions.sort_by_key(|tx| tx.signed().nonce);
+			let nonce = client.account_nonce(&sender);
+
+			let sealed = client.sealed_nonce(&sender);
+			let mut expected = nonce;
+			let mut first_gap = None;
+			for tx in &transactions {
+				let tx_nonce = tx.signed().nonce;
+				// nonces sealed in the pending block are no gap.
+				if tx_nonce > expected && !sealed.map_or(false, |sealed| tx_nonce <= sealed) {
+					first_gap = Some(expected);
+					break;
+				}
+				expected = cmp::max(expected, tx_nonce.saturating_add(U256::from(1)));
+			}
+
+			SenderStatus { sender, nonce, transactions, first_gap }
+		}).collect()
+	}
+
+	/// Returns the most recently rejected transactions, oldest first.
+	pub fn rejected_transactions(&self) -> Vec<RejectedTransaction> {
+		self.rejection_history.all()
+	}
+
 	/// Returns all transaction hashes in the queue without explicit ordering.
 	pub fn all_transaction_hashes(&self) -> Vec<H256> {
 		let ready = |_tx: &pool::VerifiedTransaction| txpool::Readiness::Ready;
@@ -462,7 +648,7 @@ impl TransactionQueue {
 	}
 
 	/// Returns next valid nonce for given sender
-	/// or `None` if there are no pending transactions from that sender.
+	/// or `None` if there are no pending or sealed transactions from that sender.
 	pub fn next_nonce<C: client::NonceClient>(
 		&self,
 		client: C,
@@ -473,11 +659,17 @@ impl TransactionQueue {
 		// Also we ignore stale transactions in the queue.
 		let stale_id = None;
 
+		let sealed = client.sealed_nonce(address);
 		let state_readiness = ready::State::new(client, stale_id, nonce_cap);
 
-		self.pool.read().pending_from_sender(state_readiness, address)
+		let pending = self.pool.read().pending_from_sender(state_readiness, address)
 			.last()
-			.map(|tx| tx.signed().nonce.saturating_add(U256::from(1)))
+			.map(|tx| tx.signed().nonce.saturating_add(U256::from(1)));
+
+		match (pending, sealed) {
+			(Some(pending), Some(sealed)) => Some(cmp::max(pending, sealed)),
+			(pending, sealed) => pending.or(sealed),
+		}
 	}
 
 	/// Retrieve a transaction from the pool.
@@ -518,6 +710,36 @@ impl TransactionQueue {
 		results
 	}
 
+	/// Drop a set of transactions from the pool for given reason.
+	///
+	/// Works like `remove`, but listeners are notified about the transactions
+	/// being dropped (with the reason) rather than canceled.
+	pub fn drop_transactions<'a, T: IntoIterator<Item=&'a H256>>(
+		&self,
+		hashes: T,
+		reason: DropReason,
+	) -> Vec<Option<Arc<pool::VerifiedTransaction>>> {
+		let results = {
+			let mut pool = self.pool.write();
+
+			set_dropping(pool.listener_mut(), Some(reason));
+			let results = hashes
+				.into_iter()
+				.map(|hash| pool.remove(hash, false))
+				.collect::<Vec<_>>();
+			set_dropping(pool.listener_mut(), None);
+			(pool.listener_mut().1).0.notify();
+
+			results
+		};
+
+		if results.iter().any(Option::is_some) {
+			self.cached_pending.write().clear();
+		}
+
+		results
+	}
+
 	/// Clear the entire pool.
 	pub fn clear(&self) {
 		self.pool.write().clear();
@@ -569,6 +791,11 @@ impl TransactionQueue {
 		self.pool.read().listener().0.all_transactions().iter().map(|(a, b)| (*a, b.clone())).collect()
 	}
 
+	/// Returns the number of transactions evicted from the pool so far, grouped by reason.
+	pub fn eviction_stats(&self) -> BTreeMap<EvictReason, u64> {
+		((self.pool.read().listener().1).1).1.stats().clone()
+	}
+
 	/// Add a listener to be notified about all transactions the pool
 	pub fn add_pending_listener(&self, f: mpsc::UnboundedSender<Arc<Vec<H256>>>) {
 		let mut pool = self.pool.write();
@@ -588,6 +815,12 @@ impl TransactionQueue {
 	}
 }
 
+fn set_dropping(listener: &mut Listener, reason: Option<DropReason>) {
+	listener.0.set_dropping(reason);
+	(listener.1).0.set_dropping(reason);
+	((listener.1).1).1.set_dropping(reason);
+}
+
 fn convert_error<H: fmt::Debug + fmt::LowerHex>(err: txpool::Error<H>) -> transaction::Error {
 	use self::txpool::Error;
 
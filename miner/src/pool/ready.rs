@@ -34,6 +34,9 @@
 //! - The transaction is ready (current; state nonce == transaction nonce)
 //! - The transaction is not ready yet (future; state nonce < transaction nonce)
 //!
+//! Transactions already sealed in a pending block (see `NonceClient::sealed_nonce`) may be gone
+//! from the queue. A gap they leave doesn't make the following transactions future.
+//!
 //! NOTE The transactions are always checked for readines in order they are stored within the queue.
 //! First `Readiness::Future` response also causes all subsequent transactions from the same sender
 //! to be marked as `Future`.
@@ -88,6 +91,11 @@ impl<C: NonceClient> txpool::Ready<VerifiedTransaction> for State<C> {
 		let state_nonce = || state.account_nonce(sender);
 		let nonce = self.nonces.entry(*sender).or_insert_with(state_nonce);
 		match tx.transaction.nonce.cmp(nonce) {
+			// The gap is covered by transactions sealed in the pending block.
+			cmp::Ordering::Greater if state.sealed_nonce(sender).map_or(false, |sealed| tx.transaction.nonce <= sealed) => {
+				*nonce = tx.transaction.nonce.saturating_add(U256::from(1));
+				txpool::Readiness::Ready
+			},
 			// Before marking as future check for stale ids
 			cmp::Ordering::Greater => match self.stale_id {
 				Some(id) if tx.insertion_id() < id => txpool::Readiness::Stale,
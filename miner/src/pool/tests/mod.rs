@@ -14,11 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::U256;
+use ethereum_types::{Address, U256};
 use types::transaction::{self, PendingTransaction};
 use txpool;
 
-use pool::{verifier, TransactionQueue, PrioritizationStrategy, PendingSettings, PendingOrdering};
+use pool::{verifier, DropReason, EvictReason, NonceCap, TransactionQueue, PrioritizationStrategy, PendingSettings, PendingOrdering, Priority, ScoredTransaction};
+use pool::local_transactions::Status;
 
 pub mod tx;
 pub mod client;
@@ -255,6 +256,26 @@ fn should_import_transaction_below_min_gas_price_threshold_if_local() {
 	assert_eq!(txq.status().status.transaction_count, 1);
 }
 
+#[test]
+fn should_reject_external_transaction_below_gas_price_floor() {
+	// given
+	let txq = new_queue();
+	txq.set_minimum_gas_price(3.into());
+	let (tx1, tx2) = Tx::default().signed_pair();
+
+	// when
+	let res = txq.import(TestClient::new(), vec![tx1.unverified()]);
+	let res2 = txq.import(TestClient::new(), vec![tx2.local()]);
+
+	// then
+	assert_eq!(res, vec![Err(transaction::Error::GasPriceBelowFloor {
+		floor: U256::from(3),
+		got: U256::from(1),
+	})]);
+	assert_eq!(res2, vec![Ok(())]);
+	assert_eq!(txq.status().status.transaction_count, 1);
+}
+
 #[test]
 fn should_import_txs_from_same_sender() {
 	// given
@@ -591,6 +612,46 @@ fn should_not_replace_same_transaction_if_the_fee_is_less_than_minimal_bump() {
 	assert_eq!(txq.pending(client.clone(), PendingSettings::all_prioritized(0, 0))[1].signed().gas_price, U256::from(2));
 }
 
+#[test]
+fn should_count_replaced_transactions_in_eviction_stats() {
+	// given
+	let txq = new_queue();
+	let (tx, tx2) = Tx::gas_price(1).signed_replacement();
+	let client = TestClient::new().with_balance(1_000_000);
+	let res = txq.import(client.clone(), vec![tx].local());
+	assert_eq!(res, vec![Ok(())]);
+	assert!(txq.eviction_stats().is_empty());
+
+	// when
+	let res = txq.import(client.clone(), vec![tx2].local());
+
+	// then
+	assert_eq!(res, vec![Ok(())]);
+	assert_eq!(txq.status().status.transaction_count, 1);
+	assert_eq!(txq.eviction_stats().get(&EvictReason::Replaced), Some(&1));
+	assert_eq!(txq.eviction_stats().len(), 1);
+}
+
+#[test]
+fn should_count_dropped_transactions_by_reason() {
+	// given
+	let txq = new_queue();
+	let tx = Tx::default().signed();
+	let hash = tx.hash();
+	let client = TestClient::new().with_balance(1_000_000);
+	let res = txq.import(client.clone(), vec![tx.local()]);
+	assert_eq!(res, vec![Ok(())]);
+
+	// when
+	let res = txq.drop_transactions(vec![&hash], DropReason::RepeatedlyFailing);
+
+	// then
+	assert!(res[0].is_some());
+	assert_eq!(txq.status().status.transaction_count, 0);
+	assert_eq!(txq.eviction_stats().get(&EvictReason::RepeatedlyFailing), Some(&1));
+	assert_eq!(txq.eviction_stats().get(&EvictReason::Canceled), None);
+}
+
 #[test]
 fn should_return_none_when_transaction_from_given_address_does_not_exist() {
 	// given
@@ -1074,3 +1135,238 @@ fn should_not_reject_early_in_case_gas_price_is_less_than_min_effective() {
 	assert_eq!(txq.status().status.transaction_count, 2);
 	assert!(client.was_verification_triggered());
 }
+
+#[test]
+fn should_not_advise_nonces_already_used_in_pending_block() {
+	// given
+	let txq = new_queue();
+	let (tx0, tx1, tx2) = Tx::default().signed_triple();
+	let sender = tx0.sender();
+	let hash0 = tx0.hash();
+	let res = txq.import(TestClient::new(), vec![tx0.clone(), tx1.clone(), tx2].local());
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+
+	// first two transactions are sealed in the pending block,
+	// then the first one is removed from the pool.
+	let cap = NonceCap::new(1, vec![(tx0.sender(), tx0.nonce), (tx1.sender(), tx1.nonce)]);
+	txq.remove(vec![&hash0], false);
+
+	// without the pending block the gap makes the rest of the queue future,
+	// and the state nonce would be advised again.
+	assert_eq!(txq.next_nonce(TestClient::new(), &sender), None);
+
+	// when
+	let next = txq.next_nonce(cap.client(TestClient::new()), &sender);
+
+	// then
+	assert_eq!(next, Some(126.into()));
+	assert_eq!(cap.next_nonce(&sender), Some(125.into()));
+	assert_eq!(cap.next_nonce(&Default::default()), None);
+}
+
+#[test]
+fn should_restore_exported_transactions() {
+	// given
+	let txq = new_queue();
+	let (tx0, tx1, tx2) = Tx::default().signed_triple();
+	let res = txq.import(TestClient::new(), vec![tx0, tx1, tx2].unverified());
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+	let mut hashes = txq.all_transaction_hashes();
+	hashes.sort();
+
+	// when
+	let exported = txq.export_pending();
+	txq.clear();
+	assert_eq!(txq.status().status.transaction_count, 0);
+	let res = txq.import_pending(TestClient::new(), exported);
+
+	// then
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+	let mut restored = txq.all_transaction_hashes();
+	restored.sort();
+	assert_eq!(restored, hashes);
+	assert_eq!(txq.pending(TestClient::new(), PendingSettings::all_prioritized(0, 0)).len(), 3);
+}
+
+#[test]
+fn should_reverify_imported_transactions() {
+	// given
+	let txq = new_queue();
+	let tx = Tx::default().signed();
+	let res = txq.import(TestClient::new(), vec![tx].unverified());
+	assert_eq!(res, vec![Ok(())]);
+	let exported = txq.export_pending();
+	txq.clear();
+
+	// when
+	let client = TestClient::new().with_nonce(125);
+	let res = txq.import_pending(client, exported);
+
+	// then
+	assert_eq!(res, vec![Err(transaction::Error::Old)]);
+	assert_eq!(txq.status().status.transaction_count, 0);
+}
+
+#[test]
+fn should_group_queued_transactions_by_sender() {
+	// given
+	let txq = new_queue();
+	let (tx0, _tx1, tx2) = Tx::default().signed_triple();
+	let tx3 = Tx::gas_price(2).signed();
+	let (sender1, sender2) = (tx0.sender(), tx3.sender());
+	let res = txq.import(TestClient::new(), vec![tx2.clone(), tx0.clone(), tx3.clone()].unverified());
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+
+	// when
+	let grouped = txq.queue_status_grouped(TestClient::new());
+
+	// then
+	assert_eq!(grouped.len(), 2);
+	let first = grouped.iter().find(|s| s.sender == sender1).unwrap();
+	assert_eq!(first.nonce, 123.into());
+	assert_eq!(first.transactions.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![tx0.hash(), tx2.hash()]);
+	assert_eq!(first.first_gap, Some(124.into()));
+
+	let second = grouped.iter().find(|s| s.sender == sender2).unwrap();
+	assert_eq!(second.nonce, 123.into());
+	assert_eq!(second.transactions.len(), 1);
+	assert_eq!(second.transactions[0].signed().gas_price, 2.into());
+	assert_eq!(second.first_gap, None);
+}
+
+#[test]
+fn should_group_transactions_with_max_nonce() {
+	use parity_crypto::publickey::{Generator, Random};
+
+	// given
+	let txq = new_queue();
+	let keypair = Random.generate();
+	let sign = |nonce: U256| {
+		let mut tx = Tx::default().unsigned();
+		tx.nonce = nonce;
+		tx.sign(keypair.secret(), None)
+	};
+	let tx0 = sign(U256::max_value() - 1);
+	let tx1 = sign(U256::max_value());
+	let client = || TestClient::new().with_nonce(U256::max_value() - 1);
+	let res = txq.import(client(), vec![tx0, tx1].unverified());
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+
+	// when
+	let grouped = txq.queue_status_grouped(client());
+
+	// then
+	assert_eq!(grouped.len(), 1);
+	assert_eq!(grouped[0].transactions.len(), 2);
+	assert_eq!(grouped[0].first_gap, None);
+}
+
+#[test]
+fn should_keep_history_of_rejected_transactions() {
+	// given
+	let txq = new_queue();
+	let (tx, replacement) = Tx::gas_price(20).signed_replacement();
+	let low_gas = Tx { gas: 20_000, ..Default::default() }.signed();
+	let no_balance = Tx::default().signed();
+	let client = TestClient::new().with_balance(1_000_000);
+	let res = txq.import(client.clone(), vec![tx].unverified());
+	assert_eq!(res, vec![Ok(())]);
+	assert!(txq.rejected_transactions().is_empty());
+
+	// when
+	let res = txq.import(client, vec![replacement.clone(), low_gas.clone()].unverified());
+	assert_eq!(res.len(), 2);
+	let res = txq.import(TestClient::new().with_balance(1), vec![no_balance.clone()].unverified());
+	assert_eq!(res.len(), 1);
+	// rejections served from the cache are not recorded again
+	let res = txq.import(TestClient::new().with_balance(1), vec![no_balance.clone()].unverified());
+	assert_eq!(res.len(), 1);
+
+	// then
+	let history = txq.rejected_transactions();
+	assert!(history.iter().all(|rejected| rejected.timestamp > 0));
+	assert_eq!(history.into_iter().map(|r| (r.hash, r.sender, r.reason)).collect::<Vec<_>>(), vec![
+		(replacement.hash(), Some(replacement.sender()), transaction::Error::TooCheapToReplace { prev: None, new: None }),
+		(low_gas.hash(), None, transaction::Error::InsufficientGas { minimal: 21_000.into(), got: 20_000.into() }),
+		(no_balance.hash(), Some(no_balance.sender()), transaction::Error::InsufficientBalance { cost: 21_100.into(), balance: 1.into() }),
+	]);
+}
+
+#[test]
+fn should_treat_transactions_after_pending_block_as_ready() {
+	// given
+	let txq = new_queue();
+	let (tx0, tx1, tx2) = Tx::default().signed_triple();
+	let sender = tx0.sender();
+	let hash0 = tx0.hash();
+	let res = txq.import(TestClient::new(), vec![tx0.clone(), tx1.clone(), tx2].local());
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+	let cap = NonceCap::new(1, vec![(tx0.sender(), tx0.nonce), (tx1.sender(), tx1.nonce)]);
+
+	// transactions still in the pool are ready while they're sealed.
+	let pending: Vec<_> = txq.collect_pending(cap.client(TestClient::new()), 0, 0, None, |i| i.collect());
+	assert_eq!(pending.len(), 3);
+
+	// when
+	txq.remove(vec![&hash0], false);
+
+	// then
+	let pending: Vec<_> = txq.collect_pending(TestClient::new(), 0, 0, None, |i| i.collect());
+	assert_eq!(pending.len(), 0);
+	let pending: Vec<_> = txq.collect_pending(cap.client(TestClient::new()), 0, 0, None, |i| i.collect());
+	assert_eq!(pending.len(), 2);
+
+	let status = txq.queue_status_grouped(TestClient::new());
+	assert_eq!(status[0].first_gap, Some(tx0.nonce));
+	let status = txq.queue_status_grouped(cap.client(TestClient::new()));
+	assert_eq!((status[0].sender, status[0].first_gap), (sender, None));
+}
+
+#[test]
+fn should_keep_local_transactions_local_after_restore() {
+	// given
+	let txq = new_queue();
+	let local = Tx::default().signed();
+	let external = Tx::gas_price(2).signed();
+	let local_hash = local.hash();
+	let res = txq.import(TestClient::new(), vec![local].local());
+	assert_eq!(res, vec![Ok(())]);
+	let res = txq.import(TestClient::new(), vec![external].unverified());
+	assert_eq!(res, vec![Ok(())]);
+	let exported = txq.export_pending_with_status();
+	txq.clear();
+	assert!(!txq.has_local_pending_transactions());
+
+	// when
+	let res = txq.import_pending_with_status(TestClient::new(), exported);
+
+	// then
+	assert_eq!(res, vec![Ok(()), Ok(())]);
+	assert!(txq.has_local_pending_transactions());
+	let local_transactions = txq.local_transactions();
+	assert_eq!(local_transactions.len(), 1);
+	match local_transactions.get(&local_hash) {
+		Some(Status::Pending(tx)) => assert_eq!(tx.priority(), Priority::Local),
+		other => panic!("unexpected status: {:?}", other),
+	}
+}
+
+#[test]
+fn should_recover_sender_of_restored_local_transactions() {
+	// given
+	let txq = new_queue();
+	let sender = Address::from_low_u64_be(1);
+	let fake = Tx::default().unsigned().fake_sign(sender);
+	let res = txq.import(TestClient::new(), vec![verifier::Transaction::Local(fake.into())]);
+	assert_eq!(res, vec![Ok(())]);
+	let exported = txq.export_pending_with_status();
+	assert_eq!(exported.len(), 1);
+	assert!(exported[0].1);
+	txq.clear();
+
+	// when
+	txq.import_pending_with_status(TestClient::new(), exported);
+
+	// then
+	assert!(txq.all_transactions().iter().all(|tx| tx.sender != sender));
+}
@@ -26,7 +26,7 @@ use std::cmp;
 use std::sync::Arc;
 use std::sync::atomic::{self, AtomicUsize};
 
-use ethereum_types::{U256, H256};
+use ethereum_types::{U256, H256, Address};
 use rlp::Encodable;
 use txpool;
 use types::transaction;
@@ -137,6 +137,7 @@ pub struct Verifier<C, S, V> {
 	options: Options,
 	id: Arc<AtomicUsize>,
 	transaction_to_replace: Option<(S, Arc<V>)>,
+	minimum_gas_price: U256,
 }
 
 impl<C, S, V> Verifier<C, S, V> {
@@ -152,8 +153,36 @@ impl<C, S, V> Verifier<C, S, V> {
 			options,
 			id,
 			transaction_to_replace,
+			minimum_gas_price: U256::zero(),
 		}
 	}
+
+	/// Enforce a gas price floor on non-local and non-service transactions.
+	pub fn with_minimum_gas_price(mut self, minimum_gas_price: U256) -> Self {
+		self.minimum_gas_price = minimum_gas_price;
+		self
+	}
+}
+
+/// Reason a transaction was refused by the verifier.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rejection {
+	/// Verification error.
+	pub error: transaction::Error,
+	/// Transaction sender, if it was known before the transaction got rejected.
+	pub sender: Option<Address>,
+}
+
+impl Rejection {
+	fn new(error: transaction::Error, sender: Address) -> Self {
+		Rejection { error, sender: Some(sender) }
+	}
+}
+
+impl From<transaction::Error> for Rejection {
+	fn from(error: transaction::Error) -> Self {
+		Rejection { error, sender: None }
+	}
 }
 
 impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::NonceAndGasPrice, VerifiedTransaction> {
@@ -161,6 +190,14 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 	type VerifiedTransaction = VerifiedTransaction;
 
 	fn verify_transaction(&self, tx: Transaction) -> Result<Self::VerifiedTransaction, Self::Error> {
+		self.verify(tx).map_err(|rejection| rejection.error)
+	}
+}
+
+impl<C: Client> Verifier<C, ::pool::scoring::NonceAndGasPrice, VerifiedTransaction> {
+	/// Verify the transaction, keeping the sender along with the error
+	/// in case it was recovered before the transaction got rejected.
+	pub fn verify(&self, tx: Transaction) -> Result<VerifiedTransaction, Rejection> {
 		// The checks here should be ordered by cost/complexity.
 		// Cheap checks should be done as early as possible to discard unneeded transactions early.
 
@@ -168,7 +205,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 
 		if self.client.transaction_already_included(&hash) {
 			trace!(target: "txqueue", "[{:?}] Rejected tx already in the blockchain", hash);
-			return Err(transaction::Error::AlreadyImported)
+			return Err(transaction::Error::AlreadyImported.into())
 		}
 
 		let gas_limit = cmp::min(self.options.tx_gas_limit, self.options.block_gas_limit);
@@ -184,7 +221,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 			return Err(transaction::Error::GasLimitExceeded {
 				limit: gas_limit,
 				got: *tx.gas(),
-			});
+			}.into());
 		}
 
 		let minimal_gas = self.client.required_gas(tx.transaction());
@@ -199,7 +236,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 			return Err(transaction::Error::InsufficientGas {
 				minimal: minimal_gas,
 				got: *tx.gas(),
-			})
+			}.into())
 		}
 
 		let is_own = tx.is_local();
@@ -219,7 +256,21 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 				return Err(transaction::Error::InsufficientGasPrice {
 					minimal: self.options.minimal_gas_price,
 					got: *tx.gas_price(),
-				});
+				}.into());
+			}
+
+			if tx.gas_price() < &self.minimum_gas_price {
+				trace!(
+					target: "txqueue",
+					"[{:?}] Rejected tx below gas price floor: {} < {}",
+					hash,
+					tx.gas_price(),
+					self.minimum_gas_price,
+				);
+				return Err(transaction::Error::GasPriceBelowFloor {
+					floor: self.minimum_gas_price,
+					got: *tx.gas_price(),
+				}.into());
 			}
 
 			if let Some((ref scoring, ref vtx)) = self.transaction_to_replace {
@@ -234,7 +285,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 					return Err(transaction::Error::TooCheapToReplace {
 						prev: Some(vtx.transaction.gas_price),
 						new: Some(*tx.gas_price()),
-					});
+					}.into());
 				}
 			}
 		}
@@ -247,33 +298,50 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 				Ok(signed) => signed.into(),
 				Err(err) => {
 					debug!(target: "txqueue", "[{:?}] Rejected tx {:?}", hash, err);
-					return Err(err)
+					return Err(err.into())
 				},
 			},
 			Transaction::Local(tx) => match self.client.verify_transaction_basic(&**tx) {
 				Ok(()) => tx,
 				Err(err) => {
 					warn!(target: "txqueue", "[{:?}] Rejected local tx {:?}", hash, err);
-					return Err(err)
+					return Err(Rejection::new(err, tx.sender()))
 				}
 			},
 		};
+		let sender = transaction.sender();
+
+		if let Err(err) = self.client.verify_sender_permitted(&transaction) {
+			debug!(target: "txqueue", "[{:?}] Rejected tx from sender {:?}: {:?}", hash, sender, err);
+			return Err(Rejection::new(err, sender))
+		}
 
 		// Verify RLP payload
 		if let Err(err) = self.client.decode_transaction(&transaction.rlp_bytes()) {
 			debug!(target: "txqueue", "[{:?}] Rejected transaction's rlp payload", err);
-			return Err(err)
+			return Err(Rejection::new(err, sender))
 		}
 
-		let sender = transaction.sender();
 		let account_details = self.client.account_details(&sender);
 
-		if transaction.gas_price < self.options.minimal_gas_price {
+		if transaction.gas_price < self.options.minimal_gas_price || transaction.gas_price < self.minimum_gas_price {
 			let transaction_type = self.client.transaction_type(&transaction);
 			if let TransactionType::Service = transaction_type {
 				debug!(target: "txqueue", "Service tx {:?} below minimal gas price accepted", hash);
 			} else if is_own || account_details.is_local {
 				info!(target: "own_tx", "Local tx {:?} below minimal gas price accepted", hash);
+			} else if transaction.gas_price < self.minimum_gas_price {
+				trace!(
+					target: "txqueue",
+					"[{:?}] Rejected tx below gas price floor: {} < {}",
+					hash,
+					transaction.gas_price,
+					self.minimum_gas_price,
+				);
+				return Err(Rejection::new(transaction::Error::GasPriceBelowFloor {
+					floor: self.minimum_gas_price,
+					got: transaction.gas_price,
+				}, sender));
 			} else {
 				trace!(
 					target: "txqueue",
@@ -282,10 +350,10 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 					transaction.gas_price,
 					self.options.minimal_gas_price,
 				);
-				return Err(transaction::Error::InsufficientGasPrice {
+				return Err(Rejection::new(transaction::Error::InsufficientGasPrice {
 					minimal: self.options.minimal_gas_price,
 					got: transaction.gas_price,
-				});
+				}, sender));
 			}
 		}
 
@@ -297,10 +365,10 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 				"[{:?}] Rejected tx, price overflow",
 				hash
 			);
-			return Err(transaction::Error::InsufficientBalance {
+			return Err(Rejection::new(transaction::Error::InsufficientBalance {
 				cost: U256::max_value(),
 				balance: account_details.balance,
-			});
+			}, sender));
 		}
 		if account_details.balance < cost {
 			debug!(
@@ -310,10 +378,10 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 				account_details.balance,
 				cost,
 			);
-			return Err(transaction::Error::InsufficientBalance {
+			return Err(Rejection::new(transaction::Error::InsufficientBalance {
 				cost: cost,
 				balance: account_details.balance,
-			});
+			}, sender));
 		}
 
 		if transaction.nonce < account_details.nonce {
@@ -324,7 +392,7 @@ impl<C: Client> txpool::Verifier<Transaction> for Verifier<C, ::pool::scoring::N
 				transaction.nonce,
 				account_details.nonce,
 			);
-			return Err(transaction::Error::Old);
+			return Err(Rejection::new(transaction::Error::Old, sender));
 		}
 
 		let priority = match (is_own || account_details.is_local, is_retracted) {
@@ -0,0 +1,174 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Node-level permissioning of transactions entering the pool.
+
+use std::collections::HashMap;
+
+use call_contract::CallContract;
+use ethabi::FunctionOutputDecoder;
+use ethereum_types::{Address, H256};
+use parking_lot::RwLock;
+use types::header::Header;
+use types::ids::BlockId;
+use types::transaction::SignedTransaction;
+
+use_contract!(sender_permissioning, "res/contracts/sender_permissioning.json");
+
+/// Decides whether transactions are accepted into the pool.
+///
+/// Unlike the chain spec transaction permission contract this is a local policy,
+/// it does not affect the validity of blocks.
+pub trait TransactionPermissioning: Send + Sync {
+	/// Returns true if `sender` is permitted to submit `tx` on top of `best_block`.
+	fn is_permitted(
+		&self,
+		client: &dyn CallContract,
+		tx: &SignedTransaction,
+		sender: &Address,
+		best_block: &Header,
+	) -> bool;
+}
+
+/// Permits all transactions.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AllowAll;
+
+impl TransactionPermissioning for AllowAll {
+	fn is_permitted(&self, _: &dyn CallContract, _: &SignedTransaction, _: &Address, _: &Header) -> bool {
+		true
+	}
+}
+
+/// Permits senders allowed by a contract.
+///
+/// The contract is queried at the best block, answers are cached until the best block changes.
+#[derive(Debug)]
+pub struct ContractPermissioning {
+	contract_address: Address,
+	cache: RwLock<(H256, HashMap<Address, bool>)>,
+}
+
+impl ContractPermissioning {
+	/// Create new permissioning backed by the contract at given address.
+	pub fn new(contract_address: Address) -> Self {
+		ContractPermissioning {
+			contract_address,
+			cache: RwLock::new((H256::zero(), HashMap::new())),
+		}
+	}
+
+	fn call_contract(&self, client: &dyn CallContract, block_hash: H256, sender: Address) -> Result<bool, String> {
+		let (data, decoder) = sender_permissioning::functions::is_sender_allowed::call(sender);
+		let value = client.call_contract(BlockId::Hash(block_hash), self.contract_address, data)?;
+		decoder.decode(&value).map_err(|e| e.to_string())
+	}
+}
+
+impl TransactionPermissioning for ContractPermissioning {
+	fn is_permitted(
+		&self,
+		client: &dyn CallContract,
+		_tx: &SignedTransaction,
+		sender: &Address,
+		best_block: &Header,
+	) -> bool {
+		let block_hash = best_block.hash();
+		{
+			let cache = self.cache.read();
+			if cache.0 == block_hash {
+				if let Some(permitted) = cache.1.get(sender) {
+					return *permitted;
+				}
+			}
+		}
+
+		let permitted = match self.call_contract(client, block_hash, *sender) {
+			Ok(permitted) => permitted,
+			Err(e) => {
+				error!(target: "txqueue", "Error calling sender permissioning contract: {:?}", e);
+				return false;
+			}
+		};
+		trace!(target: "txqueue", "Sender {:?} permitted at block {:?}: {}", sender, block_hash, permitted);
+
+		let mut cache = self.cache.write();
+		if cache.0 != block_hash {
+			*cache = (block_hash, HashMap::new());
+		}
+		cache.1.insert(*sender, permitted);
+		permitted
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::Cell;
+
+	use call_contract::CallContract;
+	use ethereum_types::{Address, H256};
+	use parity_crypto::publickey::{Generator, Random};
+	use types::header::Header;
+	use types::ids::BlockId;
+	use types::transaction::{SignedTransaction, Transaction};
+
+	use super::{ContractPermissioning, TransactionPermissioning};
+
+	/// Contract answering with `allowed` and counting calls.
+	struct MockContract {
+		allowed: Cell<bool>,
+		calls: Cell<usize>,
+	}
+
+	impl CallContract for MockContract {
+		fn call_contract(&self, _: BlockId, _: Address, _: Vec<u8>) -> Result<Vec<u8>, String> {
+			self.calls.set(self.calls.get() + 1);
+			let mut output = vec![0u8; 32];
+			output[31] = self.allowed.get() as u8;
+			Ok(output)
+		}
+	}
+
+	fn header(number: u64) -> Header {
+		let mut header = Header::new();
+		header.set_number(number);
+		header.set_parent_hash(H256::from_low_u64_be(number));
+		header
+	}
+
+	fn transaction() -> SignedTransaction {
+		let keypair = Random.generate();
+		Transaction::default().sign(keypair.secret(), None)
+	}
+
+	#[test]
+	fn should_cache_answers_until_best_block_changes() {
+		let contract = MockContract { allowed: Cell::new(true), calls: Cell::new(0) };
+		let permissioning = ContractPermissioning::new(Address::from_low_u64_be(5));
+		let tx = transaction();
+		let sender = tx.sender();
+
+		let block1 = header(1);
+		assert!(permissioning.is_permitted(&contract, &tx, &sender, &block1));
+		contract.allowed.set(false);
+		assert!(permissioning.is_permitted(&contract, &tx, &sender, &block1));
+		assert_eq!(contract.calls.get(), 1);
+
+		let block2 = header(2);
+		assert!(!permissioning.is_permitted(&contract, &tx, &sender, &block2));
+		assert_eq!(contract.calls.get(), 2);
+	}
+}
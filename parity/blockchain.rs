@@ -399,6 +399,7 @@ fn execute_import(cmd: ImportBlockchain) -> Result<(), String> {
 			client: client.clone(),
 			sync: None,
 			net: None,
+			sync_state: None,
 		},
 		None,
 		None,
@@ -16,6 +16,8 @@
 
 use std::cmp::max;
 
+use ethcore_blockchain::{CacheLimit, CacheLimits};
+
 const MIN_BC_CACHE_MB: u32 = 4;
 const MIN_DB_CACHE_MB: u32 = 8;
 const MIN_BLOCK_QUEUE_SIZE_LIMIT_MB: u32 = 16;
@@ -25,6 +27,24 @@ const DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB: u32 = 40;
 const DEFAULT_TRACE_CACHE_SIZE: u32 = 20;
 const DEFAULT_STATE_CACHE_SIZE: u32 = 25;
 
+/// Sizes of the individual blockchain caches, overriding their share of the blockchain cache.
+/// All values are represented in MB.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ChainCacheSizes {
+	/// Size of the block headers cache.
+	pub headers: Option<u32>,
+	/// Size of the block bodies cache.
+	pub bodies: Option<u32>,
+	/// Size of the block details cache.
+	pub details: Option<u32>,
+	/// Size of the block hashes cache.
+	pub hashes: Option<u32>,
+	/// Size of the transaction addresses cache.
+	pub tx_addresses: Option<u32>,
+	/// Size of the block receipts cache.
+	pub receipts: Option<u32>,
+}
+
 /// Configuration for application cache sizes.
 /// All	values are represented in MB.
 #[derive(Debug, PartialEq)]
@@ -39,6 +59,8 @@ pub struct CacheConfig {
 	traces: u32,
 	/// Size of the state cache.
 	state: u32,
+	/// Sizes of the individual blockchain caches.
+	chain_caches: ChainCacheSizes,
 }
 
 impl Default for CacheConfig {
@@ -62,6 +84,7 @@ impl CacheConfig {
 			queue: DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB,
 			traces: DEFAULT_TRACE_CACHE_SIZE,
 			state: total * 2 / 10,
+			chain_caches: ChainCacheSizes::default(),
 		}
 	}
 
@@ -73,9 +96,16 @@ impl CacheConfig {
 			queue,
 			traces: DEFAULT_TRACE_CACHE_SIZE,
 			state,
+			chain_caches: ChainCacheSizes::default(),
 		}
 	}
 
+	/// Overrides the sizes of the individual blockchain caches.
+	pub fn with_chain_caches(mut self, chain_caches: ChainCacheSizes) -> Self {
+		self.chain_caches = chain_caches;
+		self
+	}
+
 	/// Size of db cache.
 	pub fn db_cache_size(&self) -> u32 {
 		max(MIN_DB_CACHE_MB, self.db)
@@ -91,6 +121,31 @@ impl CacheConfig {
 		max(self.blockchain, MIN_BC_CACHE_MB)
 	}
 
+	/// Limits of the individual blockchain caches, in bytes. Caches without an explicit size keep
+	/// their share of the blockchain cache. `None` if no cache was sized explicitly.
+	pub fn blockchain_cache_limits(&self) -> Option<CacheLimits> {
+		let sizes = &self.chain_caches;
+		if *sizes == ChainCacheSizes::default() {
+			return None;
+		}
+
+		let mb = 1024 * 1024;
+		let blockchain = self.blockchain() as usize;
+		let mut limits = CacheLimits::from_total(blockchain * 3 / 4 * mb, blockchain * mb);
+		let set = |limit: &mut CacheLimit, size: Option<u32>| if let Some(size) = size {
+			let size = size as usize;
+			*limit = CacheLimit { pref: size * 3 / 4 * mb, max: size * mb };
+		};
+		set(&mut limits.block_headers, sizes.headers);
+		set(&mut limits.block_bodies, sizes.bodies);
+		set(&mut limits.block_details, sizes.details);
+		set(&mut limits.block_hashes, sizes.hashes);
+		set(&mut limits.transaction_addresses, sizes.tx_addresses);
+		set(&mut limits.block_receipts, sizes.receipts);
+
+		Some(limits)
+	}
+
 	/// Size of the traces cache.
 	pub fn traces(&self) -> u32 {
 		self.traces
@@ -109,7 +164,7 @@ impl CacheConfig {
 
 #[cfg(test)]
 mod tests {
-	use super::CacheConfig;
+	use super::{CacheConfig, CacheLimits, ChainCacheSizes};
 
 	#[test]
 	fn test_cache_config_constructor() {
@@ -137,4 +192,25 @@ mod tests {
 					   super::DEFAULT_BLOCK_QUEUE_SIZE_LIMIT_MB,
 					   super::DEFAULT_STATE_CACHE_SIZE));
 	}
+
+	#[test]
+	fn test_cache_config_chain_caches() {
+		let mb = 1024 * 1024;
+		let config = CacheConfig::default();
+		assert_eq!(config.blockchain_cache_limits(), None);
+
+		let config = config.with_chain_caches(ChainCacheSizes {
+			receipts: Some(64),
+			hashes: Some(3),
+			..Default::default()
+		});
+		let limits = config.blockchain_cache_limits().unwrap();
+		let shared = CacheLimits::from_total(6 * mb, 8 * mb);
+		assert_eq!(limits.block_receipts.max, 64 * mb);
+		assert_eq!(limits.block_receipts.pref, 48 * mb);
+		assert_eq!(limits.block_hashes.max, 3 * mb);
+		assert_eq!(limits.block_hashes.pref, 2 * mb);
+		assert_eq!(limits.block_headers, shared.block_headers);
+		assert_eq!(limits.block_bodies, shared.block_bodies);
+	}
 }
@@ -154,8 +154,12 @@ usage! {
 				"Generate a new signer-authentication token for the given --chain (default: mainnet)",
 			}
 
-			CMD cmd_signer_list {
+			CMD cmd_signer_list
+			{
 				"List the signer-authentication tokens from given --chain (default: mainnet)",
+				ARG arg_signer_list_timeout: (u64) = 30u64,
+				"--timeout=[SECONDS]",
+				"Time to wait for each response from the node.",
 			}
 
 			CMD cmd_signer_sign
@@ -165,6 +169,10 @@ usage! {
 				ARG arg_signer_sign_id: (Option<usize>) = None,
 				"[ID]",
 				"ID",
+
+				ARG arg_signer_sign_timeout: (u64) = 30u64,
+				"--timeout=[SECONDS]",
+				"Time to wait for each response from the node.",
 			}
 
 			CMD cmd_signer_reject
@@ -174,6 +182,36 @@ usage! {
 				ARG arg_signer_reject_id: (Option<usize>) = None,
 				"<ID>",
 				"ID",
+
+				ARG arg_signer_reject_timeout: (u64) = 30u64,
+				"--timeout=[SECONDS]",
+				"Time to wait for each response from the node.",
+			}
+
+			CMD cmd_signer_show
+			{
+				"Show the details of a request in the signing queue, with its estimated cost",
+
+				ARG arg_signer_show_id: (Option<usize>) = None,
+				"<ID>",
+				"ID",
+
+				ARG arg_signer_show_timeout: (u64) = 30u64,
+				"--timeout=[SECONDS]",
+				"Time to wait for each response from the node.",
+			}
+
+			CMD cmd_signer_watch
+			{
+				"Watch the signing queue, rejecting requests which violate the policy and printing the rest for manual approval",
+
+				ARG arg_signer_watch_policy: (Option<String>) = None,
+				"<POLICY>",
+				"Path to the TOML or JSON policy file, reloaded when it changes",
+
+				ARG arg_signer_watch_timeout: (u64) = 30u64,
+				"--timeout=[SECONDS]",
+				"Time to wait for each response from the node.",
 			}
 		}
 
@@ -419,6 +457,22 @@ usage! {
 			"--no-serve-light",
 			"Disable serving of light peers.",
 
+			ARG arg_light_max_execution_witness: (usize) = 4_194_304usize, or |c: &Config| c.network.as_ref()?.light_max_execution_witness.clone(),
+			"--light-max-execution-witness=[BYTES]",
+			"Largest proof of transaction execution served to light peers, in bytes. Requests needing a larger proof are refused.",
+
+			ARG arg_light_max_headers_response: (usize) = 262_144usize, or |c: &Config| c.network.as_ref()?.light_max_headers_response.clone(),
+			"--light-max-headers-response=[BYTES]",
+			"Largest headers response served to light peers, in bytes. Requests for more headers are truncated.",
+
+			ARG arg_snapshot_serving_max_bandwidth: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.snapshot_serving_max_bandwidth.clone(),
+			"--snapshot-serving-max-bandwidth=[BYTES]",
+			"Largest bandwidth used to serve snapshot chunks to peers, in bytes per second. Unlimited by default.",
+
+			ARG arg_snapshot_serving_max_chunks_per_peer: (Option<u32>) = None, or |c: &Config| c.network.as_ref()?.snapshot_serving_max_chunks_per_peer.clone(),
+			"--snapshot-serving-max-chunks-per-peer=[NUM]",
+			"Largest number of snapshot chunks served to a single peer per second. Further requests are deferred. Unlimited by default.",
+
 			ARG arg_warp_barrier: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.warp_barrier.clone(),
 			"--warp-barrier=[NUM]",
 			"When warp enabled never attempt regular sync before warping to block NUM.",
@@ -451,10 +505,26 @@ usage! {
 			"--allow-ips=[FILTER]",
 			"Filter outbound connections. Must be one of: private - connect to private network IP addresses only; public - connect to public network IP addresses only; all - connect to any IP address.",
 
+			ARG arg_allow_client_ids: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.allow_client_ids.clone(),
+			"--allow-client-ids=[REGEXES]",
+			"Only keep peers whose client id matches one of these whitespace-separated regular expressions. Checked after the hello exchange. Empty means allow all.",
+
+			ARG arg_deny_client_ids: (Option<String>) = None, or |c: &Config| c.network.as_ref()?.deny_client_ids.clone(),
+			"--deny-client-ids=[REGEXES]",
+			"Disconnect peers whose client id matches one of these whitespace-separated regular expressions. Takes precedence over --allow-client-ids.",
+
 			ARG arg_max_pending_peers: (u16) = 64u16, or |c: &Config| c.network.as_ref()?.max_pending_peers.clone(),
 			"--max-pending-peers=[NUM]",
 			"Allow up to NUM pending connections.",
 
+			ARG arg_max_peers_per_subnet: (Option<usize>) = None, or |c: &Config| c.network.as_ref()?.max_peers_per_subnet.clone(),
+			"--max-peers-per-subnet=[NUM]",
+			"Keep at most NUM non-reserved peers from the same IPv4 /24 or IPv6 /48 subnet, making it harder to eclipse the node. No limit by default.",
+
+			ARG arg_max_peers_per_id_bucket: (Option<usize>) = None, or |c: &Config| c.network.as_ref()?.max_peers_per_id_bucket.clone(),
+			"--max-peers-per-id-bucket=[NUM]",
+			"Keep at most NUM non-reserved peers from the same node id bucket. No limit by default.",
+
 			ARG arg_network_id: (Option<u64>) = None, or |c: &Config| c.network.as_ref()?.id.clone(),
 			"--network-id=[INDEX]",
 			"Override the network identifier from the chain we are on.",
@@ -601,6 +671,10 @@ usage! {
 			"--on-demand-consecutive-failures=[TIMES]",
 			"Specify light client the number of failures for a request until it gets exponentially backed off",
 
+			ARG arg_on_demand_max_in_flight: (Option<usize>) = None, or |c: &Config| c.light.as_ref()?.on_demand_max_in_flight,
+			"--on-demand-max-in-flight=[NUM]",
+			"Specify light client maximum number of requests awaiting a response at once; further requests are queued",
+
 		["Secret Store Options"]
 			FLAG flag_no_secretstore: (bool) = false, or |c: &Config| c.secretstore.as_ref()?.disable.clone(),
 			"--no-secretstore",
@@ -743,12 +817,24 @@ usage! {
 
 			ARG arg_usd_per_eth: (String) = "auto", or |c: &Config| c.mining.as_ref()?.usd_per_eth.clone(),
 			"--usd-per-eth=[SOURCE]",
-			"USD value of a single ETH. SOURCE may be either an amount in USD, a web service or 'auto' to use each web service in turn and fallback on the last known good value.",
+			"USD value of a single ETH. SOURCE may be either an amount in USD, a comma-delimited list of web services queried in order until one succeeds, or 'auto' to use each web service in turn and fallback on the last known good value.",
 
 			ARG arg_price_update_period: (String) = "hourly", or |c: &Config| c.mining.as_ref()?.price_update_period.clone(),
 			"--price-update-period=[T]",
 			"T will be allowed to pass between each gas price update. T may be daily, hourly, a number of seconds, or a time string of the form \"2 days\", \"30 minutes\" etc..",
 
+			FLAG flag_price_coingecko_fallback: (bool) = false, or |c: &Config| c.mining.as_ref()?.price_coingecko_fallback.clone(),
+			"--price-coingecko-fallback",
+			"Query CoinGecko for the USD value of ETH when the --usd-per-eth web service fails.",
+
+			ARG arg_price_stale_after: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.price_stale_after.clone(),
+			"--price-stale-after=[T]",
+			"Use --price-fallback-gas-price once no gas price update succeeded for T. T takes the same form as for --price-update-period. By default the last known good price is kept.",
+
+			ARG arg_price_fallback_gas_price: (Option<u64>) = None, or |c: &Config| c.mining.as_ref()?.price_fallback_gas_price.clone(),
+			"--price-fallback-gas-price=[WEI]",
+			"Minimum gas price, in wei, to use once the USD value of ETH is older than --price-stale-after.",
+
 			ARG arg_gas_floor_target: (String) = "8000000", or |c: &Config| c.mining.as_ref()?.gas_floor_target.clone(),
 			"--gas-floor-target=[GAS]",
 			"Amount of gas per block to target when sealing a new block.",
@@ -777,6 +863,10 @@ usage! {
 			"--tx-queue-strategy=[S]",
 			"Prioritization strategy used to order transactions in the queue. S may be: gas_price - Prioritize txs with high gas price",
 
+			ARG arg_tx_queue_gas_price_floor: (Option<u64>) = None, or |c: &Config| c.mining.as_ref()?.tx_queue_gas_price_floor.clone(),
+			"--tx-queue-gas-price-floor=[WEI]",
+			"Reject external transactions with a gas price below WEI when importing them to the queue. Local and service transactions are accepted regardless.",
+
 			ARG arg_stratum_interface: (String) = "local", or |c: &Config| c.stratum.as_ref()?.interface.clone(),
 			"--stratum-interface=[IP]",
 			"Interface address for Stratum server.",
@@ -789,6 +879,30 @@ usage! {
 			"--min-gas-price=[STRING]",
 			"Minimum amount of Wei per GAS to be paid for a transaction to be accepted for mining. Overrides --usd-per-tx.",
 
+			ARG arg_dynamic_min_gas_price_ceiling: (Option<u64>) = None, or |c: &Config| c.mining.as_ref()?.dynamic_min_gas_price_ceiling.clone(),
+			"--dynamic-min-gas-price-ceiling=[WEI]",
+			"Raise the minimum gas price when recent blocks are consistently full, up to WEI per GAS, and lower it back to --min-gas-price when they are not.",
+
+			ARG arg_dynamic_min_gas_price_window: (usize) = 20usize, or |c: &Config| c.mining.as_ref()?.dynamic_min_gas_price_window,
+			"--dynamic-min-gas-price-window=[BLOCKS]",
+			"Number of recent blocks whose average fullness drives --dynamic-min-gas-price-ceiling.",
+
+			ARG arg_dynamic_min_gas_price_high_watermark: (u64) = 90u64, or |c: &Config| c.mining.as_ref()?.dynamic_min_gas_price_high_watermark,
+			"--dynamic-min-gas-price-high-watermark=[PCT]",
+			"Raise the dynamic minimum gas price when recent blocks are on average more than PCT percent full.",
+
+			ARG arg_dynamic_min_gas_price_low_watermark: (u64) = 50u64, or |c: &Config| c.mining.as_ref()?.dynamic_min_gas_price_low_watermark,
+			"--dynamic-min-gas-price-low-watermark=[PCT]",
+			"Lower the dynamic minimum gas price when recent blocks are on average less than PCT percent full.",
+
+			ARG arg_dynamic_min_gas_price_step: (u64) = 10u64, or |c: &Config| c.mining.as_ref()?.dynamic_min_gas_price_step,
+			"--dynamic-min-gas-price-step=[PCT]",
+			"Raise or lower the dynamic minimum gas price by PCT percent at a time.",
+
+			ARG arg_median_gas_price_window: (Option<usize>) = None, or |c: &Config| c.mining.as_ref()?.median_gas_price_window.clone(),
+			"--median-gas-price-window=[BLOCKS]",
+			"Set the minimum gas price to the median gas price of transactions in the last BLOCKS blocks, never going below --min-gas-price.",
+
 			ARG arg_gas_price_percentile: (usize) = 50usize, or |c: &Config| c.mining.as_ref()?.gas_price_percentile,
 			"--gas-price-percentile=[PCT]",
 			"Set PCT percentile gas price value from last 100 blocks as default gas price when sending transactions.",
@@ -805,6 +919,10 @@ usage! {
 			"--tx-gas-limit=[GAS]",
 			"Apply a limit of GAS as the maximum amount of gas a single transaction may have for it to be mined.",
 
+			ARG arg_sender_permission_contract: (Option<String>) = None, or |c: &Config| c.mining.as_ref()?.sender_permission_contract.clone(),
+			"--sender-permission-contract=[ADDRESS]",
+			"Only accept transactions into the queue from senders permitted by the contract at ADDRESS (function isSenderAllowed(address) returns (bool)).",
+
 			ARG arg_tx_time_limit: (Option<u64>) = None, or |c: &Config| c.mining.as_ref()?.tx_time_limit.clone(),
 			"--tx-time-limit=[MS]",
 			"Maximal time for processing single transaction. If enabled senders of transactions offending the limit will get other transactions penalized.",
@@ -880,6 +998,30 @@ usage! {
 			"--cache-size-blocks=[MB]",
 			"Specify the preferred size of the blockchain cache in megabytes.",
 
+			ARG arg_cache_size_headers: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.cache_size_headers.clone(),
+			"--cache-size-headers=[MB]",
+			"Specify the maximum size of the block headers cache in megabytes. Defaults to a share of --cache-size-blocks.",
+
+			ARG arg_cache_size_bodies: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.cache_size_bodies.clone(),
+			"--cache-size-bodies=[MB]",
+			"Specify the maximum size of the block bodies cache in megabytes. Defaults to a share of --cache-size-blocks.",
+
+			ARG arg_cache_size_details: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.cache_size_details.clone(),
+			"--cache-size-details=[MB]",
+			"Specify the maximum size of the block details cache in megabytes. Defaults to a share of --cache-size-blocks.",
+
+			ARG arg_cache_size_hashes: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.cache_size_hashes.clone(),
+			"--cache-size-hashes=[MB]",
+			"Specify the maximum size of the block hashes cache in megabytes. Defaults to a share of --cache-size-blocks.",
+
+			ARG arg_cache_size_tx_addresses: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.cache_size_tx_addresses.clone(),
+			"--cache-size-tx-addresses=[MB]",
+			"Specify the maximum size of the transaction addresses cache in megabytes. Defaults to a share of --cache-size-blocks.",
+
+			ARG arg_cache_size_receipts: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.cache_size_receipts.clone(),
+			"--cache-size-receipts=[MB]",
+			"Specify the maximum size of the block receipts cache in megabytes. Defaults to a share of --cache-size-blocks.",
+
 			ARG arg_cache_size_queue: (u32) = 40u32, or |c: &Config| c.footprint.as_ref()?.cache_size_queue.clone(),
 			"--cache-size-queue=[MB]",
 			"Specify the maximum size of memory to use for block queue.",
@@ -896,6 +1038,18 @@ usage! {
 			"--fat-db=[BOOL]",
 			"Build appropriate information to allow enumeration of all accounts and storage keys. Doubles the size of the state database. BOOL may be one of on, off or auto.",
 
+			FLAG flag_record_preimages: (bool) = false, or |c: &Config| c.footprint.as_ref()?.record_preimages.clone(),
+			"--record-preimages",
+			"Record preimages of hashed addresses and storage keys of modified state, so they can be looked up with parity_lookupPreimage. Meant for debugging.",
+
+			FLAG flag_author_index: (bool) = false, or |c: &Config| c.footprint.as_ref()?.author_index.clone(),
+			"--author-index",
+			"Index canonical blocks by author, so they can be listed with parity_blocksByAuthor. Blocks imported before the index was enabled are indexed in the background.",
+
+			ARG arg_chain_events_depth: (usize) = 0usize, or |c: &Config| c.footprint.as_ref()?.chain_events_depth.clone(),
+			"--chain-events-depth=[NUM]",
+			"Number of canonical chain changes retained in the database, so that consumers can replay the changes they missed across restarts. 0 disables recording.",
+
 			ARG arg_cache_size: (Option<u32>) = None, or |c: &Config| c.footprint.as_ref()?.cache_size.clone(),
 			"--cache-size=[MB]",
 			"Set total amount of discretionary memory to use for the entire system, overrides other cache and queue options.",
@@ -918,6 +1072,14 @@ usage! {
 			"--snapshot-threads=[NUM]",
 			"Enables multiple threads for snapshots creation.",
 
+			ARG arg_snapshot_compression: (String) = "snappy", or |c: &Config| c.snapshots.as_ref()?.compression.clone(),
+			"--snapshot-compression=[ALGORITHM]",
+			"Compression of the chunks of created snapshots. Must be one of: snappy, zstd. Clients which don't support zstd can't warp sync from zstd snapshots.",
+
+			ARG arg_snapshot_zstd_level: (i32) = 3i32, or |c: &Config| c.snapshots.as_ref()?.zstd_level,
+			"--snapshot-zstd-level=[LEVEL]",
+			"Compression level used for zstd snapshot chunks.",
+
 		["Whisper Options"]
 			FLAG flag_whisper: (bool) = false, or |c: &Config| c.whisper.as_ref()?.enabled,
 			"--whisper",
@@ -1233,8 +1395,12 @@ struct Network {
 	max_peers: Option<u16>,
 	snapshot_peers: Option<u16>,
 	max_pending_peers: Option<u16>,
+	max_peers_per_subnet: Option<usize>,
+	max_peers_per_id_bucket: Option<usize>,
 	nat: Option<String>,
 	allow_ips: Option<String>,
+	allow_client_ids: Option<String>,
+	deny_client_ids: Option<String>,
 	id: Option<u64>,
 	bootnodes: Option<Vec<String>>,
 	discovery: Option<bool>,
@@ -1242,6 +1408,10 @@ struct Network {
 	reserved_peers: Option<String>,
 	reserved_only: Option<bool>,
 	no_serve_light: Option<bool>,
+	light_max_execution_witness: Option<usize>,
+	light_max_headers_response: Option<usize>,
+	snapshot_serving_max_bandwidth: Option<u64>,
+	snapshot_serving_max_chunks_per_peer: Option<u32>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1342,10 +1512,19 @@ struct Mining {
 	tx_time_limit: Option<u64>,
 	relay_set: Option<String>,
 	min_gas_price: Option<u64>,
+	dynamic_min_gas_price_ceiling: Option<u64>,
+	dynamic_min_gas_price_window: Option<usize>,
+	dynamic_min_gas_price_high_watermark: Option<u64>,
+	dynamic_min_gas_price_low_watermark: Option<u64>,
+	dynamic_min_gas_price_step: Option<u64>,
+	median_gas_price_window: Option<usize>,
 	gas_price_percentile: Option<usize>,
 	usd_per_tx: Option<String>,
 	usd_per_eth: Option<String>,
 	price_update_period: Option<String>,
+	price_coingecko_fallback: Option<bool>,
+	price_stale_after: Option<String>,
+	price_fallback_gas_price: Option<u64>,
 	gas_floor_target: Option<String>,
 	gas_cap: Option<String>,
 	extra_data: Option<String>,
@@ -1354,6 +1533,7 @@ struct Mining {
 	tx_queue_mem_limit: Option<u32>,
 	tx_queue_locals: Option<HashSet<String>>,
 	tx_queue_strategy: Option<String>,
+	tx_queue_gas_price_floor: Option<u64>,
 	tx_queue_ban_count: Option<u16>,
 	tx_queue_ban_time: Option<u16>,
 	tx_queue_no_unfamiliar_locals: Option<bool>,
@@ -1361,6 +1541,7 @@ struct Mining {
 	remove_solved: Option<bool>,
 	notify_work: Option<Vec<String>>,
 	refuse_service_transactions: Option<bool>,
+	sender_permission_contract: Option<String>,
 	infinite_pending_block: Option<bool>,
 	max_round_blocks_to_import: Option<usize>,
 }
@@ -1385,10 +1566,19 @@ struct Footprint {
 	cache_size: Option<u32>,
 	cache_size_db: Option<u32>,
 	cache_size_blocks: Option<u32>,
+	cache_size_headers: Option<u32>,
+	cache_size_bodies: Option<u32>,
+	cache_size_details: Option<u32>,
+	cache_size_hashes: Option<u32>,
+	cache_size_tx_addresses: Option<u32>,
+	cache_size_receipts: Option<u32>,
 	cache_size_queue: Option<u32>,
 	cache_size_state: Option<u32>,
 	db_compaction: Option<String>,
 	fat_db: Option<String>,
+	record_preimages: Option<bool>,
+	author_index: Option<bool>,
+	chain_events_depth: Option<usize>,
 	scale_verifiers: Option<bool>,
 	num_verifiers: Option<usize>,
 }
@@ -1398,6 +1588,8 @@ struct Footprint {
 struct Snapshots {
 	enable: Option<bool>,
 	processing_threads: Option<usize>,
+	compression: Option<String>,
+	zstd_level: Option<i32>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -1425,6 +1617,7 @@ struct Light {
 	on_demand_request_backoff_max: Option<u64>,
 	on_demand_request_backoff_rounds_max: Option<usize>,
 	on_demand_request_consecutive_failures: Option<usize>,
+	on_demand_max_in_flight: Option<usize>,
 }
 
 #[cfg(test)]
@@ -1690,6 +1883,8 @@ mod tests {
 			cmd_signer_sign: false,
 			cmd_signer_reject: false,
 			cmd_signer_new_token: false,
+			cmd_signer_show: false,
+			cmd_signer_watch: false,
 			cmd_snapshot: false,
 			cmd_restore: false,
 			cmd_tools: false,
@@ -1713,7 +1908,14 @@ mod tests {
 
 			arg_enable_signing_queue: false,
 			arg_signer_sign_id: None,
+			arg_signer_sign_timeout: 30u64,
 			arg_signer_reject_id: None,
+			arg_signer_reject_timeout: 30u64,
+			arg_signer_list_timeout: 30u64,
+			arg_signer_show_id: None,
+			arg_signer_show_timeout: 30u64,
+			arg_signer_watch_policy: None,
+			arg_signer_watch_timeout: 30u64,
 			arg_dapp_path: None,
 			arg_account_import_path: None,
 			arg_wallet_import_path: None,
@@ -1778,8 +1980,12 @@ mod tests {
 			arg_min_peers: Some(25u16),
 			arg_max_peers: Some(50u16),
 			arg_max_pending_peers: 64u16,
+			arg_max_peers_per_subnet: Some(4),
+			arg_max_peers_per_id_bucket: Some(8),
 			arg_snapshot_peers: 0u16,
 			arg_allow_ips: "all".into(),
+			arg_allow_client_ids: Some("".into()),
+			arg_deny_client_ids: Some("".into()),
 			arg_nat: "any".into(),
 			arg_network_id: Some(1),
 			arg_bootnodes: Some("".into()),
@@ -1789,6 +1995,10 @@ mod tests {
 			flag_reserved_only: false,
 			flag_no_ancient_blocks: false,
 			flag_no_serve_light: false,
+			arg_light_max_execution_witness: 4_194_304usize,
+			arg_light_max_headers_response: 262_144usize,
+			arg_snapshot_serving_max_bandwidth: None,
+			arg_snapshot_serving_max_chunks_per_peer: None,
 
 			// -- API and Console Options
 			// RPC
@@ -1858,10 +2068,19 @@ mod tests {
 			arg_tx_time_limit: Some(100u64),
 			arg_relay_set: "cheap".into(),
 			arg_min_gas_price: Some(0u64),
+			arg_dynamic_min_gas_price_ceiling: None,
+			arg_dynamic_min_gas_price_window: 20usize,
+			arg_dynamic_min_gas_price_high_watermark: 90u64,
+			arg_dynamic_min_gas_price_low_watermark: 50u64,
+			arg_dynamic_min_gas_price_step: 10u64,
+			arg_median_gas_price_window: None,
 			arg_usd_per_tx: "0.0001".into(),
 			arg_gas_price_percentile: 50usize,
 			arg_usd_per_eth: "auto".into(),
 			arg_price_update_period: "hourly".into(),
+			flag_price_coingecko_fallback: false,
+			arg_price_stale_after: None,
+			arg_price_fallback_gas_price: None,
 			arg_gas_floor_target: "8000000".into(),
 			arg_gas_cap: "10000000".into(),
 			arg_extra_data: Some("Parity".into()),
@@ -1872,11 +2091,13 @@ mod tests {
 			arg_tx_queue_mem_limit: 4u32,
 			arg_tx_queue_locals: Some("0xdeadbeefcafe0000000000000000000000000000".into()),
 			arg_tx_queue_strategy: "gas_factor".into(),
+			arg_tx_queue_gas_price_floor: None,
 			arg_tx_queue_ban_count: Some(1u16),
 			arg_tx_queue_ban_time: Some(180u16),
 			flag_remove_solved: false,
 			arg_notify_work: Some("http://localhost:3001".into()),
 			flag_refuse_service_transactions: false,
+			arg_sender_permission_contract: None,
 			flag_infinite_pending_block: false,
 			arg_max_round_blocks_to_import: 12usize,
 
@@ -1892,12 +2113,21 @@ mod tests {
 			arg_pruning_memory: 500usize,
 			arg_cache_size_db: 64u32,
 			arg_cache_size_blocks: 8u32,
+			arg_cache_size_headers: None,
+			arg_cache_size_bodies: None,
+			arg_cache_size_details: None,
+			arg_cache_size_hashes: None,
+			arg_cache_size_tx_addresses: None,
+			arg_cache_size_receipts: None,
 			arg_cache_size_queue: 50u32,
 			arg_cache_size_state: 25u32,
 			arg_cache_size: Some(128),
 			flag_fast_and_loose: false,
 			arg_db_compaction: "ssd".into(),
 			arg_fat_db: "auto".into(),
+			flag_record_preimages: false,
+			flag_author_index: false,
+			arg_chain_events_depth: 0,
 			flag_scale_verifiers: true,
 			arg_num_verifiers: Some(6),
 
@@ -1915,6 +2145,8 @@ mod tests {
 			arg_snapshot_at: "latest".into(),
 			flag_enable_snapshotting: false,
 			arg_snapshot_threads: None,
+			arg_snapshot_compression: "zstd".into(),
+			arg_snapshot_zstd_level: 5i32,
 
 			// -- Light options.
 			arg_on_demand_response_time_window: Some(2),
@@ -1922,6 +2154,7 @@ mod tests {
 			arg_on_demand_request_backoff_max: Some(15),
 			arg_on_demand_request_backoff_rounds_max: Some(100),
 			arg_on_demand_request_consecutive_failures: Some(1),
+			arg_on_demand_max_in_flight: Some(16),
 
 			// -- Whisper options.
 			flag_whisper: false,
@@ -2047,8 +2280,12 @@ mod tests {
 				min_peers: Some(10),
 				max_peers: Some(20),
 				max_pending_peers: Some(30),
+				max_peers_per_subnet: None,
+				max_peers_per_id_bucket: None,
 				snapshot_peers: Some(40),
 				allow_ips: Some("public".into()),
+				allow_client_ids: None,
+				deny_client_ids: None,
 				nat: Some("any".into()),
 				id: None,
 				bootnodes: None,
@@ -2057,6 +2294,10 @@ mod tests {
 				reserved_peers: Some("./path/to/reserved_peers".into()),
 				reserved_only: Some(true),
 				no_serve_light: None,
+				light_max_execution_witness: None,
+				light_max_headers_response: None,
+				snapshot_serving_max_bandwidth: None,
+				snapshot_serving_max_chunks_per_peer: None,
 			}),
 			websockets: Some(Ws {
 				disable: Some(true),
@@ -2130,10 +2371,19 @@ mod tests {
 				work_queue_size: None,
 				relay_set: None,
 				min_gas_price: None,
+				dynamic_min_gas_price_ceiling: None,
+				dynamic_min_gas_price_window: None,
+				dynamic_min_gas_price_high_watermark: None,
+				dynamic_min_gas_price_low_watermark: None,
+				dynamic_min_gas_price_step: None,
+				median_gas_price_window: None,
 				gas_price_percentile: None,
 				usd_per_tx: None,
 				usd_per_eth: None,
 				price_update_period: Some("hourly".into()),
+				price_coingecko_fallback: None,
+				price_stale_after: None,
+				price_fallback_gas_price: None,
 				gas_floor_target: None,
 				gas_cap: None,
 				tx_queue_size: Some(8192),
@@ -2141,6 +2391,7 @@ mod tests {
 				tx_queue_mem_limit: None,
 				tx_queue_locals: None,
 				tx_queue_strategy: None,
+				tx_queue_gas_price_floor: None,
 				tx_queue_ban_count: None,
 				tx_queue_ban_time: None,
 				tx_queue_no_unfamiliar_locals: None,
@@ -2151,6 +2402,7 @@ mod tests {
 				remove_solved: None,
 				notify_work: None,
 				refuse_service_transactions: None,
+				sender_permission_contract: None,
 				infinite_pending_block: None,
 				max_round_blocks_to_import: None,
 			}),
@@ -2163,10 +2415,19 @@ mod tests {
 				cache_size: None,
 				cache_size_db: Some(256),
 				cache_size_blocks: Some(16),
+				cache_size_headers: None,
+				cache_size_bodies: None,
+				cache_size_details: None,
+				cache_size_hashes: None,
+				cache_size_tx_addresses: None,
+				cache_size_receipts: None,
 				cache_size_queue: Some(100),
 				cache_size_state: Some(25),
 				db_compaction: Some("ssd".into()),
 				fat_db: Some("off".into()),
+				record_preimages: None,
+				author_index: None,
+				chain_events_depth: None,
 				scale_verifiers: Some(false),
 				num_verifiers: None,
 			}),
@@ -2176,10 +2437,13 @@ mod tests {
 				on_demand_request_backoff_max: Some(15),
 				on_demand_request_backoff_rounds_max: Some(10),
 				on_demand_request_consecutive_failures: Some(1),
+				on_demand_max_in_flight: None,
 			}),
 			snapshots: Some(Snapshots {
 				enable: Some(false),
 				processing_threads: None,
+				compression: None,
+				zstd_level: None,
 			}),
 			misc: Some(Misc {
 				logging: Some("own_tx=trace".into()),
@@ -32,11 +32,12 @@ use parity_crypto::publickey::{Secret, Public};
 use ethcore::miner::{stratum, MinerOptions};
 use snapshot::SnapshotConfiguration;
 use miner::pool;
+use miner::gas_pricer::DynamicMinGasPriceOptions;
 use verification::queue::VerifierSettings;
 
 use rpc::{IpcConfiguration, HttpConfiguration, WsConfiguration};
 use parity_rpc::NetworkSettings;
-use cache::CacheConfig;
+use cache::{CacheConfig, ChainCacheSizes};
 use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_queue_strategy, to_queue_penalization};
 use dir::helpers::{replace_home, replace_home_and_local};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, SpecType};
@@ -47,12 +48,13 @@ use secretstore::{NodeSecretKey, Configuration as SecretStoreConfiguration, Cont
 use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 use run::RunCmd;
 use types::data_format::DataFormat;
+use types::snapshot::ChunkCompression;
 use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, ResetBlockchain};
 use export_hardcoded_sync::ExportHsyncCmd;
 use presale::ImportWallet;
 use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts};
 use snapshot_cmd::{self, SnapshotCommand};
-use network::{IpFilter, NatType};
+use network::{ClientIdFilter, DiversityLimits, IpFilter, NatType};
 
 const DEFAULT_MAX_PEERS: u16 = 50;
 const DEFAULT_MIN_PEERS: u16 = 25;
@@ -71,15 +73,30 @@ pub enum Cmd {
 		pwfile: Option<PathBuf>,
 		port: u16,
 		authfile: PathBuf,
+		timeout: Duration,
 	},
 	SignerList {
 		port: u16,
-		authfile: PathBuf
+		authfile: PathBuf,
+		timeout: Duration,
 	},
 	SignerReject {
 		id: Option<usize>,
 		port: u16,
-		authfile: PathBuf
+		authfile: PathBuf,
+		timeout: Duration,
+	},
+	SignerShow {
+		id: Option<usize>,
+		port: u16,
+		authfile: PathBuf,
+		timeout: Duration,
+	},
+	SignerWatch {
+		policy: PathBuf,
+		port: u16,
+		authfile: PathBuf,
+		timeout: Duration,
 	},
 	Snapshot(SnapshotCommand),
 	Hash(Option<String>),
@@ -162,17 +179,35 @@ impl Configuration {
 					pwfile: pwfile,
 					port: ws_conf.port,
 					authfile: authfile,
+					timeout: Duration::from_secs(self.args.arg_signer_sign_timeout),
 				}
 			} else if self.args.cmd_signer_reject {
 				Cmd::SignerReject {
 					id: self.args.arg_signer_reject_id,
 					port: ws_conf.port,
 					authfile: authfile,
+					timeout: Duration::from_secs(self.args.arg_signer_reject_timeout),
 				}
 			} else if self.args.cmd_signer_list {
 				Cmd::SignerList {
 					port: ws_conf.port,
 					authfile: authfile,
+					timeout: Duration::from_secs(self.args.arg_signer_list_timeout),
+				}
+			} else if self.args.cmd_signer_show {
+				Cmd::SignerShow {
+					id: self.args.arg_signer_show_id,
+					port: ws_conf.port,
+					authfile: authfile,
+					timeout: Duration::from_secs(self.args.arg_signer_show_timeout),
+				}
+			} else if self.args.cmd_signer_watch {
+				Cmd::SignerWatch {
+					policy: self.args.arg_signer_watch_policy.clone().map(PathBuf::from)
+						.ok_or_else(|| "policy file required for signer watch".to_owned())?,
+					port: ws_conf.port,
+					authfile: authfile,
+					timeout: Duration::from_secs(self.args.arg_signer_watch_timeout),
 				}
 			} else {
 				unreachable!();
@@ -404,6 +439,10 @@ impl Configuration {
 				download_old_blocks: !self.args.flag_no_ancient_blocks,
 				verifier_settings,
 				serve_light: !self.args.flag_no_serve_light,
+				light_max_execution_witness: self.args.arg_light_max_execution_witness,
+				light_max_headers_response: self.args.arg_light_max_headers_response,
+				snapshot_serving_max_bandwidth: self.args.arg_snapshot_serving_max_bandwidth,
+				snapshot_serving_max_chunks_per_peer: self.args.arg_snapshot_serving_max_chunks_per_peer,
 				light: self.args.flag_light,
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
 				no_hardcoded_sync: self.args.flag_no_hardcoded_sync,
@@ -413,7 +452,11 @@ impl Configuration {
 				on_demand_request_backoff_max: self.args.arg_on_demand_request_backoff_max,
 				on_demand_request_backoff_rounds_max: self.args.arg_on_demand_request_backoff_rounds_max,
 				on_demand_request_consecutive_failures: self.args.arg_on_demand_request_consecutive_failures,
+				on_demand_max_in_flight: self.args.arg_on_demand_max_in_flight,
 				sync_until: self.args.arg_sync_until,
+				record_preimages: self.args.flag_record_preimages,
+				author_index: self.args.flag_author_index,
+				chain_events_depth: self.args.arg_chain_events_depth,
 			};
 			Cmd::Run(run_cmd)
 		};
@@ -457,7 +500,7 @@ impl Configuration {
 	}
 
 	fn cache_config(&self) -> CacheConfig {
-		match self.args.arg_cache_size.or(self.args.arg_cache) {
+		let cache_config = match self.args.arg_cache_size.or(self.args.arg_cache) {
 			Some(size) => CacheConfig::new_with_total_cache_size(size),
 			None => CacheConfig::new(
 				self.args.arg_cache_size_db,
@@ -465,7 +508,16 @@ impl Configuration {
 				self.args.arg_cache_size_queue,
 				self.args.arg_cache_size_state,
 			),
-		}
+		};
+
+		cache_config.with_chain_caches(ChainCacheSizes {
+			headers: self.args.arg_cache_size_headers,
+			bodies: self.args.arg_cache_size_bodies,
+			details: self.args.arg_cache_size_details,
+			hashes: self.args.arg_cache_size_hashes,
+			tx_addresses: self.args.arg_cache_size_tx_addresses,
+			receipts: self.args.arg_cache_size_receipts,
+		})
 	}
 
 	/// returns logger config
@@ -504,6 +556,14 @@ impl Configuration {
 		}
 	}
 
+	fn client_id_filter(&self) -> Result<ClientIdFilter, String> {
+		let split = |arg: &Option<String>| arg.as_ref()
+			.map(|s| s.split_whitespace().collect::<Vec<_>>())
+			.unwrap_or_default();
+		ClientIdFilter::new(&split(&self.args.arg_allow_client_ids), &split(&self.args.arg_deny_client_ids))
+			.map_err(|e| format!("Invalid client id filter: {}", e))
+	}
+
 	fn min_peers(&self) -> u32 {
 		self.args.arg_min_peers
 			.or(cmp::min(self.args.arg_max_peers, Some(DEFAULT_MIN_PEERS)))
@@ -570,7 +630,11 @@ impl Configuration {
 			tx_queue_penalization: to_queue_penalization(self.args.arg_tx_time_limit)?,
 			tx_queue_strategy: to_queue_strategy(&self.args.arg_tx_queue_strategy)?,
 			tx_queue_no_unfamiliar_locals: self.args.flag_tx_queue_no_unfamiliar_locals,
+			tx_queue_gas_price_floor: self.args.arg_tx_queue_gas_price_floor.map_or_else(U256::zero, Into::into),
 			refuse_service_transactions: self.args.flag_refuse_service_transactions,
+			sender_permission_contract: self.args.arg_sender_permission_contract.clone()
+				.map(|address| to_address(Some(address)))
+				.transpose()?,
 
 			pool_limits: self.pool_limits()?,
 			pool_verification_options: self.pool_verification_options()?,
@@ -638,6 +702,51 @@ impl Configuration {
 			U256::from_dec_str(&format!("{:.0}", wei_per_gas)).unwrap()
 		}
 
+		if let Some(ceiling) = self.args.arg_dynamic_min_gas_price_ceiling {
+			if self.args.arg_median_gas_price_window.is_some() {
+				return Err("--median-gas-price-window can't be used with --dynamic-min-gas-price-ceiling.".into());
+			}
+			if self.args.arg_gasprice.is_some() {
+				return Err("--gasprice can't be used with --dynamic-min-gas-price-ceiling, use --min-gas-price to set the floor.".into());
+			}
+			let floor = self.args.arg_min_gas_price.unwrap_or(0);
+			if floor > ceiling {
+				return Err(format!("--dynamic-min-gas-price-ceiling {} is below --min-gas-price {}", ceiling, floor));
+			}
+			let (high_watermark, low_watermark) = (
+				self.args.arg_dynamic_min_gas_price_high_watermark,
+				self.args.arg_dynamic_min_gas_price_low_watermark,
+			);
+			if low_watermark > high_watermark || high_watermark > 100 {
+				return Err(format!(
+					"Invalid dynamic minimum gas price watermarks: low {}% must not exceed high {}%, which must not exceed 100%.",
+					low_watermark, high_watermark,
+				));
+			}
+			if self.args.arg_dynamic_min_gas_price_window == 0 || self.args.arg_dynamic_min_gas_price_step == 0 {
+				return Err("--dynamic-min-gas-price-window and --dynamic-min-gas-price-step must be greater than 0.".into());
+			}
+			return Ok(GasPricerConfig::Dynamic(DynamicMinGasPriceOptions {
+				window: self.args.arg_dynamic_min_gas_price_window,
+				high_watermark,
+				low_watermark,
+				step: self.args.arg_dynamic_min_gas_price_step,
+				floor: floor.into(),
+				ceiling: ceiling.into(),
+			}));
+		}
+
+		if let Some(window) = self.args.arg_median_gas_price_window {
+			if self.args.arg_gasprice.is_some() {
+				return Err("--median-gas-price-window can't be used with --gasprice, use --min-gas-price to set the floor.".into());
+			}
+			if window == 0 {
+				return Err("--median-gas-price-window must be greater than 0.".into());
+			}
+			let floor = self.args.arg_min_gas_price.unwrap_or(0);
+			return Ok(GasPricerConfig::MedianOfRecent(window, floor.into()));
+		}
+
 		if let Some(dec) = self.args.arg_gasprice.as_ref() {
 			return Ok(GasPricerConfig::Fixed(to_u256(dec)?));
 		} else if let Some(dec) = self.args.arg_min_gas_price {
@@ -647,12 +756,25 @@ impl Configuration {
 		}
 
 		let usd_per_tx = to_price(&self.args.arg_usd_per_tx)?;
+		let stale_after = match self.args.arg_price_stale_after {
+			Some(ref after) => Some(to_duration(after)?),
+			None => None,
+		};
+		let fallback_gas_price = match (stale_after, self.args.arg_price_fallback_gas_price) {
+			(Some(_), Some(price)) => price.into(),
+			(None, None) => U256::zero(),
+			(Some(_), None) => return Err("--price-stale-after requires --price-fallback-gas-price".into()),
+			(None, Some(_)) => return Err("--price-fallback-gas-price requires --price-stale-after".into()),
+		};
 
 		if "auto" == self.args.arg_usd_per_eth {
 			Ok(GasPricerConfig::Calibrated {
 				usd_per_tx: usd_per_tx,
 				recalibration_period: to_duration(self.args.arg_price_update_period.as_str())?,
-				api_endpoint: ETHERSCAN_ETH_PRICE_ENDPOINT.to_string(),
+				api_endpoints: vec![ETHERSCAN_ETH_PRICE_ENDPOINT.to_string()],
+				coingecko_fallback: self.args.flag_price_coingecko_fallback,
+				stale_after,
+				fallback_gas_price,
 			})
 		} else if let Ok(usd_per_eth_parsed) = to_price(&self.args.arg_usd_per_eth) {
 			let wei_per_gas = wei_per_gas(usd_per_tx, usd_per_eth_parsed);
@@ -668,7 +790,10 @@ impl Configuration {
 			Ok(GasPricerConfig::Calibrated {
 				usd_per_tx: usd_per_tx,
 				recalibration_period: to_duration(self.args.arg_price_update_period.as_str())?,
-				api_endpoint: self.args.arg_usd_per_eth.clone(),
+				api_endpoints: self.args.arg_usd_per_eth.split(',').map(str::to_owned).collect(),
+				coingecko_fallback: self.args.flag_price_coingecko_fallback,
+				stale_after,
+				fallback_gas_price,
 			})
 		}
 	}
@@ -754,7 +879,13 @@ impl Configuration {
 		ret.min_peers = self.min_peers();
 		ret.snapshot_peers = self.snapshot_peers();
 		ret.ip_filter = self.ip_filter()?;
+		ret.client_id_filter = self.client_id_filter()?;
 		ret.max_pending_peers = self.max_pending_peers();
+		ret.diversity = DiversityLimits {
+			max_per_ipv4_subnet: self.args.arg_max_peers_per_subnet,
+			max_per_ipv6_subnet: self.args.arg_max_peers_per_subnet,
+			max_per_id_bucket: self.args.arg_max_peers_per_id_bucket,
+		};
 		let mut net_path = PathBuf::from(self.directories().base);
 		net_path.push("network");
 		ret.config_path = Some(net_path.to_str().unwrap().to_owned());
@@ -931,6 +1062,12 @@ impl Configuration {
 				conf.processing_threads = threads;
 			}
 		}
+		conf.compression.algorithm = match self.args.arg_snapshot_compression.as_str() {
+			"snappy" => ChunkCompression::Snappy,
+			"zstd" => ChunkCompression::Zstd,
+			other => return Err(format!("Invalid snapshot compression: {}. Must be one of: snappy, zstd", other)),
+		};
+		conf.compression.zstd_level = self.args.arg_snapshot_zstd_level;
 
 		Ok(conf)
 	}
@@ -1378,6 +1515,45 @@ mod tests {
 		} ));
 	}
 
+	#[test]
+	fn test_command_signer_reject_timeout() {
+		let args = vec!["parity", "signer", "reject", "1", "--timeout", "5"];
+		let conf = parse(&args);
+		let authfile = ::signer::codes_path(::std::path::Path::new(&Directories::default().signer));
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::SignerReject {
+			id: Some(1),
+			port: 8546,
+			authfile,
+			timeout: Duration::from_secs(5),
+		});
+	}
+
+	#[test]
+	fn test_command_signer_show() {
+		let args = vec!["parity", "signer", "show", "1"];
+		let conf = parse(&args);
+		let authfile = ::signer::codes_path(::std::path::Path::new(&Directories::default().signer));
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::SignerShow {
+			id: Some(1),
+			port: 8546,
+			authfile,
+			timeout: Duration::from_secs(30),
+		});
+	}
+
+	#[test]
+	fn test_command_signer_watch() {
+		let args = vec!["parity", "signer", "watch", "policy.toml"];
+		let conf = parse(&args);
+		let authfile = ::signer::codes_path(::std::path::Path::new(&Directories::default().signer));
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::SignerWatch {
+			policy: "policy.toml".into(),
+			port: 8546,
+			authfile,
+			timeout: Duration::from_secs(30),
+		});
+	}
+
 	#[test]
 	fn test_ws_max_connections() {
 		let args = vec!["parity", "--ws-max-connections", "1"];
@@ -1445,6 +1621,10 @@ mod tests {
 			download_old_blocks: true,
 			verifier_settings: Default::default(),
 			serve_light: true,
+			light_max_execution_witness: 4 * 1024 * 1024,
+			light_max_headers_response: 256 * 1024,
+			snapshot_serving_max_bandwidth: None,
+			snapshot_serving_max_chunks_per_peer: None,
 			light: false,
 			no_hardcoded_sync: false,
 			no_persistent_txqueue: false,
@@ -1454,7 +1634,11 @@ mod tests {
 			on_demand_request_backoff_max: None,
 			on_demand_request_backoff_rounds_max: None,
 			on_demand_request_consecutive_failures: None,
+			on_demand_max_in_flight: None,
 			sync_until: None,
+			record_preimages: false,
+			author_index: false,
+			chain_events_depth: 0,
 		};
 		expected.secretstore_conf.enabled = cfg!(feature = "secretstore");
 		expected.secretstore_conf.http_enabled = cfg!(feature = "secretstore");
@@ -1469,11 +1653,14 @@ mod tests {
 		// when
 		let conf0 = parse(&["parity"]);
 		let conf2 = parse(&["parity", "--tx-queue-strategy", "gas_price"]);
+		let conf3 = parse(&["parity", "--tx-queue-strategy", "gas_price", "--tx-queue-gas-price-floor", "1000"]);
 
 		// then
 		assert_eq!(conf0.miner_options().unwrap(), mining_options);
 		mining_options.tx_queue_strategy = PrioritizationStrategy::GasPriceOnly;
 		assert_eq!(conf2.miner_options().unwrap(), mining_options);
+		mining_options.tx_queue_gas_price_floor = 1000.into();
+		assert_eq!(conf3.miner_options().unwrap(), mining_options);
 	}
 
 	#[test]
@@ -1642,6 +1829,117 @@ mod tests {
 		assert_eq!(reserved_nodes.unwrap().len(), 1);
 	}
 
+	#[test]
+	fn should_parse_dynamic_min_gas_price() {
+		let args = vec!["parity", "--min-gas-price", "1000", "--dynamic-min-gas-price-ceiling", "5000"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::Dynamic(DynamicMinGasPriceOptions {
+			window: 20,
+			high_watermark: 90,
+			low_watermark: 50,
+			step: 10,
+			floor: 1000.into(),
+			ceiling: 5000.into(),
+		})));
+
+		let args = vec![
+			"parity", "--dynamic-min-gas-price-ceiling", "5000", "--dynamic-min-gas-price-window", "5",
+			"--dynamic-min-gas-price-high-watermark", "80", "--dynamic-min-gas-price-low-watermark", "20",
+			"--dynamic-min-gas-price-step", "25",
+		];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::Dynamic(DynamicMinGasPriceOptions {
+			window: 5,
+			high_watermark: 80,
+			low_watermark: 20,
+			step: 25,
+			floor: 0.into(),
+			ceiling: 5000.into(),
+		})));
+
+		let args = vec!["parity", "--min-gas-price", "1000", "--dynamic-min-gas-price-ceiling", "500"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+
+		let args = vec!["parity", "--gasprice", "1000", "--dynamic-min-gas-price-ceiling", "5000"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+
+		let args = vec![
+			"parity", "--dynamic-min-gas-price-ceiling", "5000",
+			"--dynamic-min-gas-price-high-watermark", "40", "--dynamic-min-gas-price-low-watermark", "60",
+		];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+	}
+
+	#[test]
+	fn should_parse_median_gas_pricer() {
+		let args = vec!["parity", "--median-gas-price-window", "30"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::MedianOfRecent(30, 0.into())));
+
+		let args = vec!["parity", "--median-gas-price-window", "0"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+
+		let args = vec!["parity", "--median-gas-price-window", "30", "--min-gas-price", "1000"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::MedianOfRecent(30, 1000.into())));
+
+		let args = vec!["parity", "--median-gas-price-window", "30", "--gasprice", "1000"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+
+		let args = vec!["parity", "--median-gas-price-window", "30", "--dynamic-min-gas-price-ceiling", "5000"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+	}
+
+	#[test]
+	fn should_parse_chain_cache_sizes() {
+		let args = vec!["parity", "--cache-size-receipts", "64", "--cache-size-headers", "16"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		let expected = CacheConfig::default().with_chain_caches(ChainCacheSizes {
+			headers: Some(16),
+			receipts: Some(64),
+			..Default::default()
+		});
+		assert_eq!(conf.cache_config(), expected);
+	}
+
+	#[test]
+	fn should_parse_multiple_price_endpoints() {
+		let args = vec!["parity", "--usd-per-eth", "https://first.example/price,https://second.example/price"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::Calibrated {
+			usd_per_tx: 0.0001,
+			recalibration_period: Duration::from_secs(3600),
+			api_endpoints: vec!["https://first.example/price".into(), "https://second.example/price".into()],
+			coingecko_fallback: false,
+			stale_after: None,
+			fallback_gas_price: 0.into(),
+		}));
+	}
+
+	#[test]
+	fn should_parse_calibrated_gas_price_fallback() {
+		let args = vec!["parity", "--price-coingecko-fallback", "--price-stale-after", "1 day", "--price-fallback-gas-price", "1000"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert_eq!(conf.gas_pricer_config(), Ok(GasPricerConfig::Calibrated {
+			usd_per_tx: 0.0001,
+			recalibration_period: Duration::from_secs(3600),
+			api_endpoints: vec![ETHERSCAN_ETH_PRICE_ENDPOINT.to_string()],
+			coingecko_fallback: true,
+			stale_after: Some(Duration::from_secs(24 * 60 * 60)),
+			fallback_gas_price: 1000.into(),
+		}));
+
+		let args = vec!["parity", "--price-stale-after", "1 day"];
+		let conf = Configuration::parse_cli(&args).unwrap();
+		assert!(conf.gas_pricer_config().is_err());
+	}
+
 	#[test]
 	fn test_dev_preset() {
 		let args = vec!["parity", "--config", "dev"];
@@ -1828,6 +2126,45 @@ mod tests {
 		assert_eq!(&conf0.secretstore_config().unwrap().http_interface, "0.0.0.0");
 	}
 
+	#[test]
+	fn snapshot_compression() {
+		let default = parse(&["parity"]);
+		let zstd = parse(&["parity", "--snapshot-compression", "zstd", "--snapshot-zstd-level", "7"]);
+		let invalid = parse(&["parity", "--snapshot-compression", "lz4"]);
+
+		assert_eq!(default.snapshot_config().unwrap().compression.algorithm, ChunkCompression::Snappy);
+		let conf = zstd.snapshot_config().unwrap();
+		assert_eq!(conf.compression.algorithm, ChunkCompression::Zstd);
+		assert_eq!(conf.compression.zstd_level, 7);
+		assert!(invalid.snapshot_config().is_err());
+	}
+
+	#[test]
+	fn client_id_filter() {
+		let none = parse(&["parity"]);
+		let both = parse(&["parity", "--allow-client-ids", "^OpenEthereum/ ^Geth/", "--deny-client-ids", "^Geth/v1\\.8"]);
+		let invalid = parse(&["parity", "--deny-client-ids", "("]);
+
+		assert_eq!(none.client_id_filter().unwrap(), ClientIdFilter::default());
+		assert_eq!(both.client_id_filter().unwrap(), ClientIdFilter::new(&["^OpenEthereum/", "^Geth/"], &["^Geth/v1\\.8"]).unwrap());
+		assert_eq!(both.net_config().unwrap().client_id_filter, both.client_id_filter().unwrap());
+		assert!(invalid.client_id_filter().is_err());
+		assert!(invalid.net_config().is_err());
+	}
+
+	#[test]
+	fn diversity_limits() {
+		let none = parse(&["parity"]);
+		let both = parse(&["parity", "--max-peers-per-subnet", "4", "--max-peers-per-id-bucket", "8"]);
+
+		assert_eq!(none.net_config().unwrap().diversity, DiversityLimits::unlimited());
+		assert_eq!(both.net_config().unwrap().diversity, DiversityLimits {
+			max_per_ipv4_subnet: Some(4),
+			max_per_ipv6_subnet: Some(4),
+			max_per_id_bucket: Some(8),
+		});
+	}
+
 	#[test]
 	fn allow_ips() {
 		let all = parse(&["parity", "--allow-ips", "all"]);
@@ -18,7 +18,10 @@ use std::fs;
 use std::io::{Read, Write, Error as IoError, ErrorKind};
 use std::path::{Path, PathBuf};
 use std::fmt::{Display, Formatter, Error as FmtError};
-use super::migration_rocksdb::{Manager as MigrationManager, Config as MigrationConfig, ChangeColumns, VacuumAccountsBloom};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use parking_lot::Mutex;
+use super::migration_rocksdb::{Manager as MigrationManager, Config as MigrationConfig, ChangeColumns, VacuumAccountsBloom, MigrationError};
 use super::kvdb_rocksdb::{CompactionProfile};
 use ethcore::client::DatabaseCompactionProfile;
 use ethcore_db::NUM_COLUMNS;
@@ -67,6 +70,23 @@ const BATCH_SIZE: usize = 1024;
 /// Version file name.
 const VERSION_FILE_NAME: &'static str = "db_version";
 
+lazy_static! {
+	/// Cancellation flag of the running migration, if any.
+	static ref RUNNING_MIGRATION: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+}
+
+/// Cancels the running database migration, leaving the database at its current version.
+/// Returns `false` if no migration is running.
+pub fn cancel_migration() -> bool {
+	match *RUNNING_MIGRATION.lock() {
+		Some(ref cancel) => {
+			cancel.store(true, Ordering::SeqCst);
+			true
+		},
+		None => false,
+	}
+}
+
 /// Migration related erorrs.
 #[derive(Debug)]
 pub enum Error {
@@ -76,6 +96,8 @@ pub enum Error {
 	FutureDBVersion,
 	/// Migration is not possible.
 	MigrationImpossible,
+	/// Migration was cancelled, the database is left at given version.
+	Cancelled(u32),
 	/// Migration was completed succesfully,
 	/// but there was a problem with io.
 	Io(IoError),
@@ -87,6 +109,7 @@ impl Display for Error {
 			Error::UnknownDatabaseVersion => "Current database version cannot be read".into(),
 			Error::FutureDBVersion => "Database was created with newer client version. Upgrade your client or delete DB and resync.".into(),
 			Error::MigrationImpossible => format!("Database migration to version {} is not possible.", CURRENT_VERSION),
+			Error::Cancelled(version) => format!("Database migration was cancelled, the database is left at version {}.", version),
 			Error::Io(ref err) => format!("Unexpected io error on DB migration: {}.", err),
 		};
 
@@ -150,6 +173,9 @@ pub fn default_migration_settings(compaction_profile: &CompactionProfile) -> Mig
 	MigrationConfig {
 		batch_size: BATCH_SIZE,
 		compaction_profile: *compaction_profile,
+		// a migration changing the database in place can't be cancelled, Ctrl-C exits as usual.
+		on_uncancellable: Some(Arc::new(|| *RUNNING_MIGRATION.lock() = None)),
+		..Default::default()
 	}
 }
 
@@ -175,7 +201,16 @@ fn migrate_database(version: u32, db_path: &Path, mut migrations: MigrationManag
 	let _ = fs::remove_dir_all(&backup_path);
 
 	// migrate old database to the new one
-	let temp_path = migrations.execute(&db_path, version)?;
+	let cancel = Arc::new(AtomicBool::new(false));
+	*RUNNING_MIGRATION.lock() = Some(cancel.clone());
+	let result = migrations.execute_cancellable(&db_path, version, cancel);
+	*RUNNING_MIGRATION.lock() = None;
+	let (temp_path, report) = match result {
+		Err(ref err) if MigrationError::from_io(err) == Some(MigrationError::Cancelled) => return Err(Error::Cancelled(version)),
+		result => result?,
+	};
+	info!(target: "migration", "Applied migrations {:?}, database version {} -> {}", report.applied, report.from_version, report.to_version);
+	trace!(target: "migration", "Keys migrated per column: {:?}", report.keys_migrated_per_column);
 
 	// completely in-place migration leads to the paths being equal.
 	// in that case, no need to shuffle directories.
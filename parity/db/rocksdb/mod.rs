@@ -36,7 +36,7 @@ use cache::CacheConfig;
 mod migration;
 mod helpers;
 
-pub use self::migration::migrate;
+pub use self::migration::{migrate, cancel_migration};
 
 struct AppDB {
 	key_value: Arc<dyn KeyValueDB>,
@@ -106,9 +106,43 @@ pub fn open_database(client_path: &str, config: &DatabaseConfig) -> io::Result<A
 
 	let db = AppDB {
 		key_value: Arc::new(Database::open(&config, client_path)?),
-		blooms: blooms_db::Database::open(blooms_path)?,
-		trace_blooms: blooms_db::Database::open(trace_blooms_path)?,
+		blooms: blooms_db::Database::open_with_migration(blooms_path)?,
+		trace_blooms: blooms_db::Database::open_with_migration(trace_blooms_path)?,
 	};
 
 	Ok(Arc::new(db))
 }
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::io::Write;
+	use ethereum_types::Bloom;
+	use super::tempfile::TempDir;
+	use super::{open_database, BlockChainDB, DatabaseConfig, NUM_COLUMNS};
+
+	#[test]
+	fn legacy_blooms_are_migrated_in_background() {
+		let tempdir = TempDir::new().unwrap();
+		let blooms_path = tempdir.path().join("blooms");
+		fs::create_dir_all(&blooms_path).unwrap();
+
+		// a single headerless bloom at index 0 on every level.
+		let bloom = Bloom::from_low_u64_be(0x42);
+		for name in &["top.bdb", "mid.bdb", "bot.bdb"] {
+			let mut file = fs::File::create(blooms_path.join(name)).unwrap();
+			file.write_all(bloom.as_bytes()).unwrap();
+		}
+
+		let config = DatabaseConfig::with_columns(NUM_COLUMNS);
+		let db = open_database(&tempdir.path().to_string_lossy(), &config).unwrap();
+
+		// legacy files serve reads while the migration is running.
+		assert_eq!(db.blooms().filter(0, 0, Some(&bloom)).unwrap(), vec![0]);
+
+		// writes wait for the migration, which prepends a 32 byte header.
+		db.blooms().insert_blooms(1, Some(&bloom).into_iter()).unwrap();
+		assert_eq!(fs::metadata(blooms_path.join("bot.bdb")).unwrap().len(), 32 + 2 * 256);
+		assert_eq!(db.blooms().filter(0, 1, Some(&bloom)).unwrap(), vec![0, 1]);
+	}
+}
@@ -223,6 +223,8 @@ pub fn default_network_config() -> ::sync::NetworkConfiguration {
 		ip_filter: IpFilter::default(),
 		reserved_nodes: Vec::new(),
 		allow_non_reserved: true,
+		diversity: Default::default(),
+		client_id_filter: Default::default(),
 		client_version: ::parity_version::version(),
 	}
 }
@@ -249,6 +251,8 @@ pub fn to_client_config(
 	client_config.blockchain.max_cache_size = cache_config.blockchain() as usize * mb;
 	// in bytes
 	client_config.blockchain.pref_cache_size = cache_config.blockchain() as usize * 3 / 4 * mb;
+	// in bytes, `None` keeps each cache at its share of the above
+	client_config.blockchain.cache_limits = cache_config.blockchain_cache_limits();
 	// db cache size, in megabytes
 	client_config.db_cache_size = Some(cache_config.db_cache_size() as usize);
 	// db queue cache size, in bytes
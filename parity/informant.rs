@@ -22,6 +22,8 @@ use ansi_term::Colour::{White, Yellow, Green, Cyan, Blue};
 use ansi_term::{Colour, Style};
 use atty;
 use ethcore::client::Client;
+use ethcore::miner::MinerService;
+use ethcore_blockchain::CacheReport as ChainCacheReport;
 use client_traits::{BlockInfo, ChainInfo, BlockChainClient, ChainNotify};
 use types::{
 	BlockNumber,
@@ -35,14 +37,15 @@ use types::{
 };
 use snapshot::SnapshotService as SS;
 use snapshot::service::Service as SnapshotService;
-use sync::{LightSyncProvider, LightSync, SyncProvider, ManageNetwork};
+use sync::{LightSyncProvider, LightSync, SyncProvider, ManageNetwork, SnapshotServingStats};
 use io::{TimerToken, IoContext, IoHandler};
 use light::Cache as LightDataCache;
 use light::client::{LightChainClient, LightChainNotify};
+use miner::gas_pricer::CalibrationStatus;
 use number_prefix::{binary_prefix, Standalone, Prefixed};
-use parity_rpc::is_major_importing_or_waiting;
+use parity_rpc::{is_major_importing_or_waiting, SyncStateTracker};
 use parity_rpc::informant::RpcStats;
-use ethereum_types::H256;
+use ethereum_types::{H256, U256};
 use parking_lot::{RwLock, Mutex};
 
 /// Format byte counts to standard denominations.
@@ -53,6 +56,28 @@ pub fn format_bytes(b: u64) -> String {
 	}
 }
 
+/// Format used and maximum bytes, and the hit rate, of each blockchain cache.
+fn format_chain_caches(report: &ChainCacheReport) -> String {
+	let caches = [
+		("headers", &report.block_headers),
+		("bodies", &report.block_bodies),
+		("details", &report.block_details),
+		("hashes", &report.block_hashes),
+		("tx addresses", &report.transaction_addresses),
+		("receipts", &report.block_receipts),
+	];
+
+	caches.iter()
+		.map(|(name, usage)| format!("{} {}/{} ({:.1}% hits)",
+			name,
+			format_bytes(usage.used as u64),
+			format_bytes(usage.max as u64),
+			usage.hit_rate() * 100.0,
+		))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
 #[derive(Default)]
 struct CacheSizes {
 	sizes: ::std::collections::BTreeMap<&'static str, usize>,
@@ -84,6 +109,7 @@ pub struct SyncInfo {
 	num_peers: usize,
 	max_peers: u32,
 	snapshot_sync: bool,
+	snapshot_serving: SnapshotServingStats,
 }
 
 pub struct Report {
@@ -92,7 +118,10 @@ pub struct Report {
 	client_report: ClientReport,
 	queue_info: BlockQueueInfo,
 	cache_sizes: CacheSizes,
+	chain_cache_report: Option<ChainCacheReport>,
 	sync_info: Option<SyncInfo>,
+	min_gas_price: Option<U256>,
+	gas_price_calibration: Option<CalibrationStatus>,
 }
 
 /// Something which can provide data to the informant.
@@ -112,6 +141,7 @@ pub struct FullNodeInformantData {
 	pub client: Arc<Client>,
 	pub sync: Option<Arc<dyn SyncProvider>>,
 	pub net: Option<Arc<dyn ManageNetwork>>,
+	pub sync_state: Option<Arc<SyncStateTracker>>,
 }
 
 impl InformantData for FullNodeInformantData {
@@ -123,15 +153,20 @@ impl InformantData for FullNodeInformantData {
 	}
 
 	fn report(&self) -> Report {
-		let (client_report, queue_info, blockchain_cache_info) =
-			(self.client.report(), self.client.queue_info(), self.client.blockchain_cache_info());
+		let (client_report, queue_info, blockchain_cache_report) =
+			(self.client.report(), self.client.queue_info(), self.client.blockchain_cache_report());
 
 		let chain_info = self.client.chain_info();
 
 		let mut cache_sizes = CacheSizes::default();
 		cache_sizes.insert("db", client_report.state_db_mem);
 		cache_sizes.insert("queue", queue_info.mem_used);
-		cache_sizes.insert("chain", blockchain_cache_info.total());
+		cache_sizes.insert("chain", blockchain_cache_report.total());
+
+		// recorded on every tick, so phase start times don't depend on `parity_syncState` polling.
+		if let Some(ref sync_state) = self.sync_state {
+			sync_state.update();
+		}
 
 		let importing = self.is_major_importing();
 		let sync_info = match (self.sync.as_ref(), self.net.as_ref()) {
@@ -148,6 +183,7 @@ impl InformantData for FullNodeInformantData {
 					num_peers: status.num_peers,
 					max_peers: status.current_max_peers(*num_peers_range.start(), *num_peers_range.end()),
 					snapshot_sync: status.is_snapshot_syncing(),
+					snapshot_serving: sync.snapshot_serving_stats(),
 				})
 			}
 			_ => None
@@ -159,7 +195,10 @@ impl InformantData for FullNodeInformantData {
 			client_report,
 			queue_info,
 			cache_sizes,
+			chain_cache_report: Some(blockchain_cache_report),
 			sync_info,
+			min_gas_price: self.client.miner().dynamic_min_gas_price(),
+			gas_price_calibration: self.client.miner().gas_price_calibration(),
 		}
 	}
 }
@@ -193,6 +232,7 @@ impl InformantData for LightNodeInformantData {
 			num_peers: peer_numbers.connected,
 			max_peers: peer_numbers.max as u32,
 			snapshot_sync: false,
+			snapshot_serving: SnapshotServingStats::default(),
 		});
 
 		Report {
@@ -201,7 +241,10 @@ impl InformantData for LightNodeInformantData {
 			client_report,
 			queue_info,
 			cache_sizes,
+			chain_cache_report: None,
 			sync_info,
+			min_gas_price: None,
+			gas_price_calibration: None,
 		}
 	}
 }
@@ -265,12 +308,22 @@ impl<T: InformantData> Informant<T> {
 			client_report.io_stats.transactions,
 		);
 
+		if full_report.client_report.epoch_transitions > 0 {
+			debug!(target: "informant", "{} epoch transitions stored", full_report.client_report.epoch_transitions);
+		}
+
+		if let Some(ref chain_caches) = full_report.chain_cache_report {
+			debug!(target: "informant", "Chain caches: {}", format_chain_caches(chain_caches));
+		}
+
 		let Report {
 			importing,
 			chain_info,
 			queue_info,
 			cache_sizes,
 			sync_info,
+			min_gas_price,
+			gas_price_calibration,
 			..
 		} = full_report;
 
@@ -293,7 +346,7 @@ impl<T: InformantData> Informant<T> {
 			false => t,
 		};
 
-		info!(target: "import", "{}  {}  {}  {}",
+		info!(target: "import", "{}  {}  {}  {}{}{}",
 			match importing {
 				true => match snapshot_sync {
 					false => format!("Syncing {} {}  {}  {}+{} Qed",
@@ -337,7 +390,7 @@ impl<T: InformantData> Informant<T> {
 				false => String::new(),
 			},
 			match sync_info.as_ref() {
-				Some(ref sync_info) => format!("{}{}/{} peers",
+				Some(ref sync_info) => format!("{}{}/{} peers{}",
 					match importing {
 						true => format!("{}",
 							if self.target.executes_transactions() {
@@ -353,6 +406,14 @@ impl<T: InformantData> Informant<T> {
 					},
 					paint(Cyan.bold(), format!("{:2}", sync_info.num_peers)),
 					paint(Cyan.bold(), format!("{:2}", sync_info.max_peers)),
+					match sync_info.snapshot_serving {
+						SnapshotServingStats { chunks_served: 0, .. } => String::new(),
+						ref serving => format!("  {} snapshot chunks served ({}), {} pending",
+							paint(Cyan.bold(), format!("{}", serving.chunks_served)),
+							paint(Cyan.bold(), format_bytes(serving.bytes_served)),
+							paint(Cyan.bold(), format!("{}", serving.pending)),
+						),
+					},
 				),
 				_ => String::new(),
 			},
@@ -366,6 +427,24 @@ impl<T: InformantData> Informant<T> {
 				),
 				_ => String::new(),
 			},
+			match min_gas_price {
+				Some(price) => format!("  min gas price {} wei", paint(Yellow.bold(), format!("{}", price))),
+				None => String::new(),
+			},
+			match gas_price_calibration {
+				Some(status) => format!("  {}, {}/{} price sources failing",
+					match status.last_calibration {
+						Some(info) => format!("gas price {} wei via {}",
+							paint(Yellow.bold(), format!("{}", info.gas_price)),
+							info.source,
+						),
+						None => "gas price not calibrated".to_owned(),
+					},
+					paint(Cyan.bold(), format!("{}", status.sources.iter().filter(|s| s.consecutive_failures > 0).count())),
+					status.sources.len(),
+				),
+				None => String::new(),
+			},
 		);
 	}
 }
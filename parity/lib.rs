@@ -46,6 +46,7 @@ extern crate client_traits;
 extern crate common_types as types;
 extern crate engine;
 extern crate ethcore;
+extern crate ethcore_blockchain;
 extern crate ethcore_db;
 extern crate ethcore_io as io;
 extern crate ethcore_light as light;
@@ -76,6 +77,8 @@ extern crate snapshot;
 extern crate spec;
 extern crate verification;
 
+#[macro_use]
+extern crate lazy_static;
 #[macro_use]
 extern crate log as rlog;
 
@@ -132,6 +135,7 @@ use deprecated::find_deprecated;
 use hash::keccak_buffer;
 
 pub use self::configuration::Configuration;
+pub use self::db::cancel_migration;
 pub use self::run::RunningClient;
 pub use parity_rpc::PubSubSession;
 pub use ethcore_logger::{Config as LoggerConfig, setup_log, RotatingLogger};
@@ -211,9 +215,11 @@ fn execute<Cr, Rr>(
 		Cmd::ImportPresaleWallet(presale_cmd) => presale::execute(presale_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Blockchain(blockchain_cmd) => blockchain::execute(blockchain_cmd).map(|_| ExecutionAction::Instant(None)),
 		Cmd::SignerToken(ws_conf, logger_config) => signer::execute(ws_conf, logger_config).map(|s| ExecutionAction::Instant(Some(s))),
-		Cmd::SignerSign { id, pwfile, port, authfile } => cli_signer::signer_sign(id, pwfile, port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
-		Cmd::SignerList { port, authfile } => cli_signer::signer_list(port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
-		Cmd::SignerReject { id, port, authfile } => cli_signer::signer_reject(id, port, authfile).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerSign { id, pwfile, port, authfile, timeout } => cli_signer::signer_sign(id, pwfile, port, authfile, timeout).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerList { port, authfile, timeout } => cli_signer::signer_list(port, authfile, timeout).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerReject { id, port, authfile, timeout } => cli_signer::signer_reject(id, port, authfile, timeout).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerShow { id, port, authfile, timeout } => cli_signer::signer_show(id, port, authfile, timeout).map(|s| ExecutionAction::Instant(Some(s))),
+		Cmd::SignerWatch { policy, port, authfile, timeout } => cli_signer::signer_watch(policy, port, authfile, timeout).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::Snapshot(snapshot_cmd) => snapshot_cmd::execute(snapshot_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 		Cmd::ExportHardcodedSync(export_hs_cmd) => export_hardcoded_sync::execute(export_hs_cmd).map(|s| ExecutionAction::Instant(Some(s))),
 	}
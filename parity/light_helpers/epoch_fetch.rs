@@ -28,7 +28,7 @@ use futures::{future, Future};
 use futures::future::Either;
 
 use light::client::fetch::ChainDataFetcher;
-use light::on_demand::{request, OnDemand, OnDemandRequester};
+use light::on_demand::{request, OnDemand, OnDemandRequester, Priority};
 
 use parking_lot::RwLock;
 use ethereum_types::H256;
@@ -52,8 +52,9 @@ impl EpochFetch {
 		Box::new(match self.sync.read().upgrade() {
 			Some(sync) => {
 				let on_demand = &self.on_demand;
+				// background work for header import, user queries go first.
 				let maybe_future = sync.with_context(move |ctx| {
-					on_demand.request(ctx, req).expect(ALL_VALID_BACKREFS)
+					on_demand.request_with_priority(ctx, req, Priority::Low).expect(ALL_VALID_BACKREFS)
 				});
 
 				match maybe_future {
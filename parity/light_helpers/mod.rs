@@ -17,5 +17,7 @@
 //! Utilities and helpers for the light client.
 
 mod epoch_fetch;
+mod tx_confirmation;
 
 pub use self::epoch_fetch::EpochFetch;
+pub use self::tx_confirmation::TransactionConfirmation;
@@ -0,0 +1,128 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use sync::{LightSync, LightNetworkDispatcher};
+use types::ids::BlockId;
+
+use futures::Future;
+
+use light::client::{LightChainClient, LightChainNotify};
+use light::on_demand::{request, OnDemand, OnDemandRequester, Priority};
+use light::TransactionQueue;
+
+use parity_runtime::Executor;
+use parking_lot::{Mutex, RwLock};
+use ethereum_types::H256;
+
+// minimal interval between on-demand inclusion checks.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+// maximal number of transactions checked on-demand at once.
+const MAX_CHECKS: usize = 16;
+
+/// Confirms inclusion of local transactions relayed by the light client.
+///
+/// Full peers are asked which block contains a relayed transaction at a low rate;
+/// the transaction is confirmed once a canonical header of that block is imported.
+pub struct TransactionConfirmation<C> {
+	client: Arc<C>,
+	sync: Arc<LightSync>,
+	on_demand: Arc<OnDemand>,
+	transaction_queue: Arc<RwLock<TransactionQueue>>,
+	executor: Executor,
+	last_check: Mutex<Option<Instant>>,
+}
+
+impl<C: LightChainClient + 'static> TransactionConfirmation<C> {
+	/// Create a new `TransactionConfirmation`.
+	pub fn new(
+		client: Arc<C>,
+		sync: Arc<LightSync>,
+		on_demand: Arc<OnDemand>,
+		transaction_queue: Arc<RwLock<TransactionQueue>>,
+		executor: Executor,
+	) -> Self {
+		TransactionConfirmation {
+			client,
+			sync,
+			on_demand,
+			transaction_queue,
+			executor,
+			last_check: Mutex::new(None),
+		}
+	}
+
+	fn confirm(&self) {
+		let client = &self.client;
+		let canonical_hash = |num| client.block_hash(BlockId::Number(num));
+		let mut transaction_queue = self.transaction_queue.write();
+
+		for hash in transaction_queue.revert_retracted(canonical_hash) {
+			debug!(target: "light_txqueue", "Local transaction {:?} retracted", hash);
+		}
+
+		for hash in transaction_queue.confirm_included(canonical_hash) {
+			debug!(target: "light_txqueue", "Local transaction {:?} confirmed", hash);
+		}
+	}
+
+	fn request_inclusion(&self) {
+		let now = Instant::now();
+		{
+			let mut last_check = self.last_check.lock();
+			if last_check.map_or(false, |last| now.duration_since(last) < CHECK_INTERVAL) {
+				return
+			}
+			*last_check = Some(now);
+		}
+
+		let awaiting = self.transaction_queue.read().awaiting_confirmation();
+		for hash in awaiting.into_iter().take(MAX_CHECKS) {
+			let on_demand = &self.on_demand;
+			let maybe_future = self.sync.with_context(move |ctx| {
+				on_demand.request_with_priority(ctx, request::TransactionIndex(hash.into()), Priority::Low)
+			});
+
+			let index = match maybe_future {
+				Some(Ok(index)) => index,
+				_ => {
+					trace!(target: "light_txqueue", "Unable to check inclusion of {:?}: network unavailable", hash);
+					return
+				}
+			};
+
+			let client = self.client.clone();
+			let transaction_queue = self.transaction_queue.clone();
+			self.executor.spawn(index.then(move |res| {
+				if let Ok(index) = res {
+					let mut transaction_queue = transaction_queue.write();
+					transaction_queue.note_included(&hash, index.num, index.hash);
+					transaction_queue.confirm_included(|num| client.block_hash(BlockId::Number(num)));
+				}
+				Ok(())
+			}));
+		}
+	}
+}
+
+impl<C: LightChainClient + 'static> LightChainNotify for TransactionConfirmation<C> {
+	fn new_headers(&self, _good: &[H256]) {
+		self.confirm();
+		self.request_inclusion();
+	}
+}
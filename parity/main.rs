@@ -234,6 +234,35 @@ fn main_direct(force_can_restart: bool) -> i32 {
 	// Double panic can happen. So when we lock `ExitStatus` after the main thread is notified, it cannot be locked
 	// again.
 	let exiting = Arc::new(AtomicBool::new(false));
+	// Set once the client is running and can be shut down gracefully.
+	let running = Arc::new(AtomicBool::new(false));
+
+	// A Ctrl-C handler can only be set once, so it's set before starting: it cancels a database
+	// migration run during the start up and shuts down the client once it's running.
+	ctrlc::set_handler({
+		let e = exit.clone();
+		let exiting = exiting.clone();
+		let running = running.clone();
+		move || {
+			if openethereum::cancel_migration() {
+				warn!("Cancelling database migration");
+				return;
+			}
+			if !running.load(Ordering::SeqCst) {
+				// nothing to shut down yet, exit as if there was no handler.
+				process::exit(130);
+			}
+			if !exiting.swap(true, Ordering::SeqCst) {
+				*e.0.lock() = ExitStatus {
+					panicking: false,
+					should_exit: true,
+					should_restart: false,
+					spec_name_override: None,
+				};
+				e.1.notify_all();
+			}
+		}
+	}).expect("Error setting Ctrl-C handler");
 
 	let exec = if can_restart {
 		start(
@@ -298,21 +327,7 @@ fn main_direct(force_can_restart: bool) -> i32 {
 					}
 				});
 
-				ctrlc::set_handler({
-					let e = exit.clone();
-					let exiting = exiting.clone();
-					move || {
-						if !exiting.swap(true, Ordering::SeqCst) {
-							*e.0.lock() = ExitStatus {
-								panicking: false,
-								should_exit: true,
-								should_restart: false,
-								spec_name_override: None,
-							};
-							e.1.notify_all();
-						}
-					}
-				}).expect("Error setting Ctrl-C handler");
+				running.store(true, Ordering::SeqCst);
 
 				// so the client has started successfully
 				// if this is a daemon, detach from the parent process
@@ -23,7 +23,7 @@ use ethereum_types::{U256, Address};
 use parity_runtime::Executor;
 use hash_fetch::fetch::Client as FetchClient;
 use journaldb::Algorithm;
-use miner::gas_pricer::GasPricer;
+use miner::gas_pricer::{GasPricer, DynamicMinGasPriceOptions};
 use miner::gas_price_calibrator::{GasPriceCalibratorOptions, GasPriceCalibrator};
 use parity_version::version_data;
 use user_defaults::UserDefaults;
@@ -253,8 +253,13 @@ pub enum GasPricerConfig {
 	Calibrated {
 		usd_per_tx: f32,
 		recalibration_period: Duration,
-		api_endpoint: String
-	}
+		api_endpoints: Vec<String>,
+		coingecko_fallback: bool,
+		stale_after: Option<Duration>,
+		fallback_gas_price: U256,
+	},
+	Dynamic(DynamicMinGasPriceOptions),
+	MedianOfRecent(usize, U256),
 }
 
 impl Default for GasPricerConfig {
@@ -262,7 +267,10 @@ impl Default for GasPricerConfig {
 		GasPricerConfig::Calibrated {
 			usd_per_tx: 0.0001f32,
 			recalibration_period: Duration::from_secs(3600),
-			api_endpoint: configuration::ETHERSCAN_ETH_PRICE_ENDPOINT.to_string(),
+			api_endpoints: vec![configuration::ETHERSCAN_ETH_PRICE_ENDPOINT.to_string()],
+			coingecko_fallback: false,
+			stale_after: None,
+			fallback_gas_price: U256::zero(),
 		}
 	}
 }
@@ -271,19 +279,21 @@ impl GasPricerConfig {
 	pub fn to_gas_pricer(&self, fetch: FetchClient, p: Executor) -> GasPricer {
 		match *self {
 			GasPricerConfig::Fixed(u) => GasPricer::Fixed(u),
-			GasPricerConfig::Calibrated { usd_per_tx, recalibration_period, ref api_endpoint } => {
-				GasPricer::new_calibrated(
-					GasPriceCalibrator::new(
-						GasPriceCalibratorOptions {
-							usd_per_tx: usd_per_tx,
-							recalibration_period: recalibration_period,
-						},
-						fetch,
-						p,
-						api_endpoint.clone(),
-					)
-				)
-			}
+			GasPricerConfig::Calibrated { usd_per_tx, recalibration_period, ref api_endpoints, coingecko_fallback, stale_after, fallback_gas_price } => {
+				let options = GasPriceCalibratorOptions {
+					usd_per_tx: usd_per_tx,
+					recalibration_period: recalibration_period,
+					stale_after: stale_after,
+					fallback_gas_price: fallback_gas_price,
+				};
+				GasPricer::new_calibrated(if coingecko_fallback {
+					GasPriceCalibrator::with_coingecko_fallback(options, fetch, p, api_endpoints.clone())
+				} else {
+					GasPriceCalibrator::with_endpoints(options, fetch, p, api_endpoints.clone())
+				})
+			},
+			GasPricerConfig::Dynamic(ref options) => GasPricer::new_dynamic(options.clone()),
+			GasPricerConfig::MedianOfRecent(window, floor) => GasPricer::new_median_of_recent(window, floor),
 		}
 	}
 }
@@ -37,7 +37,7 @@ use light::{Cache as LightDataCache, TransactionQueue as LightTransactionQueue};
 use miner::external::ExternalMiner;
 use parity_rpc::dispatch::{FullDispatcher, LightDispatcher};
 use parity_rpc::informant::{ActivityNotifier, ClientNotifier};
-use parity_rpc::{Host, Metadata, NetworkSettings};
+use parity_rpc::{Host, Metadata, NetworkSettings, SyncStateTracker};
 use parity_rpc::v1::traits::TransactionsPool;
 use parity_runtime::Executor;
 use parking_lot::{Mutex, RwLock};
@@ -255,6 +255,7 @@ pub struct FullDependencies {
 	pub poll_lifetime: u32,
 	pub allow_missing_blocks: bool,
 	pub no_ancient_blocks: bool,
+	pub sync_state: Arc<SyncStateTracker>,
 }
 
 impl FullDependencies {
@@ -392,10 +393,11 @@ impl FullDependencies {
 							signer,
 							self.ws_address.clone(),
 							self.snapshot.clone().into(),
+							self.sync_state.clone(),
 						).to_delegate(),
 					);
 					#[cfg(feature = "accounts")]
-					handler.extend_with(ParityAccountsInfo::to_delegate(ParityAccountsClient::new(&self.accounts)));
+					handler.extend_with(ParityAccountsInfo::to_delegate(ParityAccountsClient::new(&self.accounts, self.geth_compatibility)));
 
 					if !for_generic_pubsub {
 						add_signing_methods!(ParitySigning, handler, self, (&dispatcher, &account_signer));
@@ -415,7 +417,7 @@ impl FullDependencies {
 				}
 				Api::ParityAccounts => {
 					#[cfg(feature = "accounts")]
-					handler.extend_with(ParityAccounts::to_delegate(ParityAccountsClient::new(&self.accounts)));
+					handler.extend_with(ParityAccounts::to_delegate(ParityAccountsClient::new(&self.accounts, self.geth_compatibility)));
 				}
 				Api::ParitySet => {
 					handler.extend_with(
@@ -629,7 +631,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 					);
 					#[cfg(feature = "accounts")]
 					handler.extend_with(
-						ParityAccountsInfo::to_delegate(ParityAccountsClient::new(&self.accounts))
+						ParityAccountsInfo::to_delegate(ParityAccountsClient::new(&self.accounts, self.geth_compatibility))
 					);
 
 					if !for_generic_pubsub {
@@ -650,7 +652,7 @@ impl<C: LightChainClient + 'static> LightDependencies<C> {
 				}
 				Api::ParityAccounts => {
 					#[cfg(feature = "accounts")]
-					handler.extend_with(ParityAccounts::to_delegate(ParityAccountsClient::new(&self.accounts)));
+					handler.extend_with(ParityAccounts::to_delegate(ParityAccountsClient::new(&self.accounts, self.geth_compatibility)));
 				}
 				Api::ParitySet => handler.extend_with(
 					light::ParitySetClient::new(self.client.clone(), self.sync.clone(), self.fetch.clone())
@@ -44,7 +44,7 @@ use types::{
 	snapshot::Snapshotting,
 };
 use parity_rpc::{
-	Origin, Metadata, NetworkSettings, informant, PubSubSession, FutureResult, FutureResponse, FutureOutput
+	Origin, Metadata, NetworkSettings, SyncStateTracker, informant, PubSubSession, FutureResult, FutureResponse, FutureOutput
 };
 use updater::{UpdateFilter, UpdatePolicy, Updater};
 use parity_version::version;
@@ -128,6 +128,10 @@ pub struct RunCmd {
 	pub download_old_blocks: bool,
 	pub verifier_settings: VerifierSettings,
 	pub serve_light: bool,
+	pub light_max_execution_witness: usize,
+	pub light_max_headers_response: usize,
+	pub snapshot_serving_max_bandwidth: Option<u64>,
+	pub snapshot_serving_max_chunks_per_peer: Option<u32>,
 	pub light: bool,
 	pub no_persistent_txqueue: bool,
 	pub no_hardcoded_sync: bool,
@@ -137,7 +141,11 @@ pub struct RunCmd {
 	pub on_demand_request_backoff_max: Option<u64>,
 	pub on_demand_request_backoff_rounds_max: Option<usize>,
 	pub on_demand_request_consecutive_failures: Option<usize>,
+	pub on_demand_max_in_flight: Option<usize>,
 	pub sync_until: Option<u64>,
+	pub record_preimages: bool,
+	pub author_index: bool,
+	pub chain_events_depth: usize,
 }
 
 // node info fetcher for the local store.
@@ -241,7 +249,8 @@ fn execute_light_impl<Cr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq
 			request_backoff_start,
 			request_backoff_max,
 			cmd.on_demand_request_backoff_rounds_max.unwrap_or(::light::on_demand::DEFAULT_MAX_REQUEST_BACKOFF_ROUNDS),
-			cmd.on_demand_request_consecutive_failures.unwrap_or(::light::on_demand::DEFAULT_NUM_CONSECUTIVE_FAILED_REQUESTS)
+			cmd.on_demand_request_consecutive_failures.unwrap_or(::light::on_demand::DEFAULT_NUM_CONSECUTIVE_FAILED_REQUESTS),
+			cmd.on_demand_max_in_flight.unwrap_or(::light::on_demand::DEFAULT_MAX_IN_FLIGHT_REQUESTS),
 		)
 	});
 
@@ -311,9 +320,9 @@ fn execute_light_impl<Cr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq
 		accounts: account_provider,
 		logger,
 		settings: Arc::new(cmd.net_settings),
-		on_demand,
+		on_demand: on_demand.clone(),
 		cache: cache.clone(),
-		transaction_queue: txq,
+		transaction_queue: txq.clone(),
 		ws_address: cmd.ws_conf.address(),
 		fetch,
 		geth_compatibility: cmd.geth_compatibility,
@@ -350,6 +359,16 @@ fn execute_light_impl<Cr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq
 	service.add_notify(informant.clone());
 	service.register_handler(informant.clone()).map_err(|_| "Unable to register informant handler".to_owned())?;
 
+	// confirmation of relayed local transactions.
+	let tx_confirmation = Arc::new(::light_helpers::TransactionConfirmation::new(
+		client.clone(),
+		light_sync.clone(),
+		on_demand,
+		txq,
+		runtime.executor(),
+	));
+	service.add_notify(tx_confirmation.clone());
+
 	client.set_exit_handler(on_client_rq);
 
 	Ok(RunningClient {
@@ -357,7 +376,7 @@ fn execute_light_impl<Cr>(cmd: RunCmd, logger: Arc<RotatingLogger>, on_client_rq
 			rpc: rpc_direct,
 			informant,
 			client,
-			keep_alive: Box::new((service, ws_server, http_server, ipc_server, runtime)),
+			keep_alive: Box::new((service, tx_confirmation, ws_server, http_server, ipc_server, runtime)),
 		}
 	})
 }
@@ -477,6 +496,10 @@ fn execute_impl<Cr, Rr>(
 	};
 	sync_config.download_old_blocks = cmd.download_old_blocks;
 	sync_config.serve_light = cmd.serve_light;
+	sync_config.light_max_execution_witness_bytes = cmd.light_max_execution_witness;
+	sync_config.light_max_headers_response_bytes = cmd.light_max_headers_response;
+	sync_config.snapshot_serving.max_bytes_per_sec = cmd.snapshot_serving_max_bandwidth;
+	sync_config.snapshot_serving.max_chunks_per_peer_per_sec = cmd.snapshot_serving_max_chunks_per_peer;
 
 	let passwords = passwords_from_files(&cmd.acc_conf.password_files)?;
 
@@ -545,6 +568,9 @@ fn execute_impl<Cr, Rr>(
 	client_config.queue.verifier_settings = cmd.verifier_settings;
 	client_config.transaction_verification_queue_size = ::std::cmp::max(2048, txpool_size / 4);
 	client_config.snapshot = cmd.snapshot_conf.clone();
+	client_config.record_preimages = cmd.record_preimages;
+	client_config.author_index = cmd.author_index;
+	client_config.chain_events_depth = cmd.chain_events_depth;
 
 	// set up bootnodes
 	let mut net_conf = cmd.net_conf;
@@ -722,6 +748,8 @@ fn execute_impl<Cr, Rr>(
 	let secret_store = account_provider.clone();
 	let signer_service = Arc::new(signer::new_service(&cmd.ws_conf, &cmd.logger_config));
 
+	let sync_state = Arc::new(SyncStateTracker::new(sync_provider.clone(), Some(snapshot_service.clone()), client.clone()));
+
 	let deps_for_rpc_apis = Arc::new(rpc_apis::FullDependencies {
 		signer_service: signer_service,
 		snapshot: snapshot_service.clone(),
@@ -745,6 +773,7 @@ fn execute_impl<Cr, Rr>(
 		poll_lifetime: cmd.poll_lifetime,
 		allow_missing_blocks: cmd.allow_missing_blocks,
 		no_ancient_blocks: !cmd.download_old_blocks,
+		sync_state: sync_state.clone(),
 	});
 
 	let dependencies = rpc::Dependencies {
@@ -775,6 +804,7 @@ fn execute_impl<Cr, Rr>(
 			client: service.client(),
 			sync: Some(sync_provider.clone()),
 			net: Some(manage_network.clone()),
+			sync_state: Some(sync_state.clone()),
 		},
 		Some(snapshot_service.clone()),
 		Some(rpc_stats.clone()),
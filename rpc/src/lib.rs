@@ -63,6 +63,7 @@ extern crate client_traits;
 extern crate common_types as types;
 extern crate ethash;
 extern crate ethcore;
+extern crate ethcore_blockchain as blockchain;
 extern crate fastmap;
 extern crate parity_bytes as bytes;
 extern crate parity_crypto as crypto;
@@ -153,7 +154,7 @@ pub use http::{
 	AccessControlAllowOrigin, Host, DomainsValidation, cors::AccessControlAllowHeaders
 };
 
-pub use v1::{NetworkSettings, Metadata, Origin, informant, dispatch, signer};
+pub use v1::{NetworkSettings, SyncStateTracker, Metadata, Origin, informant, dispatch, signer};
 pub use v1::block_import::{is_major_importing_or_waiting};
 pub use v1::PubSubSyncStatus;
 pub use v1::extractors::{RpcExtractor, WsExtractor, WsStats, WsDispatcher};
@@ -21,14 +21,18 @@ use std::sync::Arc;
 use jsonrpc_core::MetaIoHandler;
 use ws;
 
-use v1::{extractors, informant};
+use v1::{extractors, informant, Metadata};
 use tests::helpers::{GuardedAuthCodes, Server};
 use tests::http_client;
 
 /// Setup a mock signer for tests
 pub fn serve() -> (Server<ws::Server>, usize, GuardedAuthCodes) {
+	serve_with_io(MetaIoHandler::default())
+}
+
+/// Setup a mock signer for tests answering calls (single or batched) with given handler
+pub fn serve_with_io(io: MetaIoHandler<Metadata>) -> (Server<ws::Server>, usize, GuardedAuthCodes) {
 	let address = "127.0.0.1:0".parse().unwrap();
-	let io = MetaIoHandler::default();
 	let authcodes = GuardedAuthCodes::default();
 	let stats = Arc::new(informant::RpcStats::default());
 
@@ -20,7 +20,7 @@ use ethereum_types::{H256, Address, U256};
 use light::TransactionQueue as LightTransactionQueue;
 use light::cache::Cache as LightDataCache;
 use light::client::LightChainClient;
-use light::on_demand::{request, OnDemandRequester};
+use light::on_demand::{request, OnDemandRequester, Priority};
 use parking_lot::{Mutex, RwLock};
 use stats::Corpus;
 use sync::{LightSyncProvider, LightNetworkDispatcher, ManageNetwork};
@@ -100,10 +100,10 @@ where
 	/// Get an account's state
 	fn account(&self, addr: Address) -> BoxFuture<Option<BasicAccount>> {
 		let best_header = self.client.best_block_header();
-		let account_future = self.sync.with_context(|ctx| self.on_demand.request(ctx, request::Account {
+		let account_future = self.sync.with_context(|ctx| self.on_demand.request_with_priority(ctx, request::Account {
 			header: best_header.into(),
 			address: addr,
-		}).expect("no back-references; therefore all back-references valid; qed"));
+		}, Priority::High).expect("no back-references; therefore all back-references valid; qed"));
 
 		match account_future {
 			Some(response) => Box::new(response.map_err(|_| errors::no_light_peers())),
@@ -273,7 +273,7 @@ where
 			.collect::<Vec<_>>();
 
 		// when the blocks come in, collect gas prices into a vector
-		on_demand.request(ctx, block_requests)
+		on_demand.request_with_priority(ctx, block_requests, Priority::High)
 			.expect("no back-references; therefore all back-references are valid; qed")
 			.map(|bodies| {
 				bodies.into_iter().fold(Vec::new(), |mut v, block| {
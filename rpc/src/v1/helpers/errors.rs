@@ -438,6 +438,9 @@ pub fn transaction_message(error: &TransactionError) -> String {
 		InsufficientGasPrice { minimal, got } => {
 			format!("Transaction gas price is too low. It does not satisfy your node's minimal gas price (minimal: {}, got: {}). Try increasing the gas price.", minimal, got)
 		}
+		GasPriceBelowFloor { floor, got } => {
+			format!("Transaction gas price is below the floor enforced by your node's transaction pool (floor: {}, got: {}). Try increasing the gas price.", floor, got)
+		}
 		InsufficientBalance { balance, cost } => {
 			format!("Insufficient funds. The account you tried to send transaction from does not have enough funds. Required {} and got: {}.", cost, balance)
 		}
@@ -451,7 +454,11 @@ pub fn transaction_message(error: &TransactionError) -> String {
 		RecipientBanned => "Recipient is banned in local queue.".into(),
 		CodeBanned => "Code is banned in local queue.".into(),
 		NotAllowed => "Transaction is not permitted.".into(),
+		SenderNotPermitted => "Transaction sender is not permitted by this node.".into(),
 		TooBig => "Transaction is too big, see chain specification for the limit.".into(),
+		InitCodeTooLarge { limit, got } => {
+			format!("Contract creation code is too large (limit: {}, got: {}), see chain specification for the limit.", limit, got)
+		}
 		InvalidRlp(ref descr) => format!("Invalid RLP data: {}", descr),
 	}
 }
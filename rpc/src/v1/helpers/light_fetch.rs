@@ -38,7 +38,7 @@ use light::cache::Cache;
 use light::client::LightChainClient;
 use light::{cht, MAX_HEADERS_PER_REQUEST};
 use light::on_demand::{
-	request, OnDemandRequester, HeaderRef, Request as OnDemandRequest,
+	request, OnDemandRequester, HeaderRef, Priority, Request as OnDemandRequest,
 	Response as OnDemandResponse,
 };
 use light::on_demand::error::Error as OnDemandError;
@@ -138,7 +138,8 @@ fn extract_header(res: &[OnDemandResponse], header: HeaderRef) -> Option<encoded
 	match header {
 		HeaderRef::Stored(hdr) => Some(hdr),
 		HeaderRef::Unresolved(idx, _) => match res.get(idx) {
-			Some(&OnDemandResponse::HeaderByHash(ref hdr)) => Some(hdr.clone()),
+			Some(&OnDemandResponse::HeaderByHash(ref hdr)) |
+			Some(&OnDemandResponse::HeaderDescendant(ref hdr)) => Some(hdr.clone()),
 			_ => None,
 		},
 	}
@@ -160,7 +161,17 @@ where
 			BlockId::Number(n) => {
 				let cht_root = cht::block_to_cht_number(n).and_then(|cn| self.client.cht_root(cn as usize));
 				match cht_root {
-					None => Err(errors::unknown_block()),
+					None => {
+						// not covered by a CHT yet: fetch the headers linking it to our best header.
+						let best = self.client.best_block_header();
+						let req = request::HeaderDescendant::new(n, best.hash().into(), best.number(), self.client.engine().clone())
+							.ok_or_else(errors::unknown_block)?;
+
+						let idx = reqs.len();
+						reqs.push(req.into());
+
+						Ok(HeaderRef::Unresolved(idx, Field::back_ref(idx, 0)))
+					}
 					Some(root) => {
 						let req = request::HeaderProof::new(n, root)
 							.expect("only fails for 0; client always stores genesis; client already queried; qed");
@@ -393,7 +404,7 @@ where
 							bit_combos.iter().any(|bloom| hdr_bloom.contains_bloom(bloom))
 						})
 						.map(|hdr| (hdr.number(), hdr.hash(), request::BlockReceipts(hdr.into())))
-						.map(|(num, hash, req)| on_demand.request(ctx, req).expect(NO_INVALID_BACK_REFS_PROOF).map(move |x| (num, hash, x)))
+						.map(|(num, hash, req)| on_demand.request_with_priority(ctx, req, Priority::High).expect(NO_INVALID_BACK_REFS_PROOF).map(move |x| (num, hash, x)))
 						.collect();
 
 					// as the receipts come in, find logs within them which match the filter.
@@ -478,7 +489,7 @@ where
 		Box::new(future::loop_fn(params, move |(sync, on_demand)| {
 			let maybe_future = sync.with_context(|ctx| {
 				let req = request::TransactionIndex(tx_hash.into());
-				on_demand.request(ctx, req)
+				on_demand.request_with_priority(ctx, req, Priority::High)
 			});
 
 			let eventual_index = match maybe_future {
@@ -545,7 +556,7 @@ where
 				.collect::<Vec<_>>();
 
 			// when they come in, update each sender to the new nonce.
-			on_demand.request(ctx, nonce_reqs)
+			on_demand.request_with_priority(ctx, nonce_reqs, Priority::High)
 				.expect(NO_INVALID_BACK_REFS_PROOF)
 				.map(move |accs| {
 					let mut txq = txq.write();
@@ -571,7 +582,7 @@ where
 		T: Send + 'static,
 	{
 		let maybe_future = self.sync.with_context(move |ctx| {
-			Box::new(self.on_demand.request_raw(ctx, reqs)
+			Box::new(self.on_demand.request_raw_with_priority(ctx, reqs, Priority::High)
 					 .expect(NO_INVALID_BACK_REFS_PROOF)
 					 .map_err(errors::on_demand_cancel)
 					 .and_then(|responses| {
@@ -821,7 +832,7 @@ where
 		let on_demand = params.on_demand;
 		let proved_future = params.sync.with_context(move |ctx| {
 			on_demand
-				.request(ctx, request)
+				.request_with_priority(ctx, request, Priority::High)
 				.expect("no back-references; therefore all back-refs valid; qed")
 				.map_err(errors::on_demand_error)
 		});
@@ -37,6 +37,7 @@ mod poll_manager;
 mod requests;
 mod subscribers;
 mod subscription_manager;
+mod sync_state;
 mod work;
 mod signature;
 
@@ -50,6 +51,7 @@ pub use self::requests::{
 };
 pub use self::subscribers::Subscribers;
 pub use self::subscription_manager::GenericPollManager;
+pub use self::sync_state::SyncStateTracker;
 pub use self::work::submit_work_detail;
 
 pub fn to_url(address: &Option<::Host>) -> Option<String> {
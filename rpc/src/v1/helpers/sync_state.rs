@@ -0,0 +1,139 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sync phase reporting, combining the sync, snapshot restoration and chain status.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use client_traits::ChainInfo;
+use ethereum_types::U256;
+use parking_lot::Mutex;
+use snapshot::SnapshotService;
+use sync::{SyncProvider, SyncState, SyncStatus};
+use types::{
+	blockchain_info::BlockChainInfo,
+	snapshot::RestorationStatus,
+};
+use v1::types::{SyncPhase, SyncStateInfo};
+
+/// Work out the phase the node's synchronization is in.
+pub fn sync_phase(
+	status: &SyncStatus,
+	is_major_syncing: bool,
+	restoration: &RestorationStatus,
+	chain_info: &BlockChainInfo,
+) -> SyncPhase {
+	match *restoration {
+		RestorationStatus::Ongoing { state_chunks, block_chunks, state_chunks_done, block_chunks_done } => {
+			return SyncPhase::WarpChunks {
+				state_chunks_done: state_chunks_done.into(),
+				state_chunks: state_chunks.into(),
+				block_chunks_done: block_chunks_done.into(),
+				block_chunks: block_chunks.into(),
+			};
+		}
+		// previously downloaded chunks are being re-imported, progress is not split per kind yet.
+		RestorationStatus::Initializing { state_chunks, block_chunks, .. } => {
+			return SyncPhase::WarpChunks {
+				state_chunks_done: U256::zero(),
+				state_chunks: state_chunks.into(),
+				block_chunks_done: U256::zero(),
+				block_chunks: block_chunks.into(),
+			};
+		}
+		RestorationStatus::Finalizing => return SyncPhase::WarpFinalizing,
+		RestorationStatus::Inactive | RestorationStatus::Failed => {}
+	}
+
+	if status.is_snapshot_syncing() && status.state != SyncState::SnapshotWaiting {
+		return SyncPhase::WarpManifest;
+	}
+
+	if is_major_syncing {
+		return SyncPhase::FullSync {
+			current_block: chain_info.best_block_number.into(),
+			highest_block: status.highest_block_number.unwrap_or(status.start_block_number).into(),
+			starting_block: status.start_block_number.into(),
+		};
+	}
+
+	match (chain_info.ancient_block_number, chain_info.first_block_number) {
+		(Some(ancient), Some(first)) => SyncPhase::AncientBackfill {
+			remaining: first.saturating_sub(ancient + 1).into(),
+		},
+		_ => SyncPhase::Idle,
+	}
+}
+
+/// Keeps track of when the node entered each sync phase. A single tracker is shared by
+/// all RPC handlers and updated periodically by the informant, so phases are recorded
+/// whether or not anyone polls `parity_syncState`.
+pub struct SyncStateTracker {
+	sync: Arc<dyn SyncProvider>,
+	snapshot: Option<Arc<dyn SnapshotService>>,
+	chain: Arc<dyn ChainInfo>,
+	inner: Mutex<TrackerState>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+	current: Option<&'static str>,
+	started: BTreeMap<String, u64>,
+}
+
+impl SyncStateTracker {
+	/// Create a new tracker watching the given sync, snapshot restoration and chain.
+	pub fn new(sync: Arc<dyn SyncProvider>, snapshot: Option<Arc<dyn SnapshotService>>, chain: Arc<dyn ChainInfo>) -> Self {
+		SyncStateTracker {
+			sync,
+			snapshot,
+			chain,
+			inner: Mutex::new(TrackerState::default()),
+		}
+	}
+
+	/// Work out the current phase, record it and return the full sync state.
+	pub fn update(&self) -> SyncStateInfo {
+		let restoration = self.snapshot.as_ref().map_or(RestorationStatus::Inactive, |s| s.status());
+		let warp_target = self.snapshot.as_ref().and_then(|s| s.restoration_target());
+		let phase = sync_phase(
+			&self.sync.status(),
+			self.sync.is_major_syncing(),
+			&restoration,
+			&self.chain.chain_info(),
+		);
+
+		self.observe(phase, warp_target)
+	}
+
+	fn observe(&self, phase: SyncPhase, warp_target: Option<u64>) -> SyncStateInfo {
+		let mut inner = self.inner.lock();
+		let name = phase.name();
+		if inner.current != Some(name) {
+			let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+			inner.current = Some(name);
+			inner.started.insert(name.into(), now);
+		}
+
+		SyncStateInfo {
+			phase,
+			warp_target: warp_target.map(Into::into),
+			phases_started: inner.started.clone(),
+		}
+	}
+}
@@ -561,8 +561,15 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 
 		if warping || self.sync.is_major_syncing() {
 			let chain_info = client.chain_info();
-			let current_block = U256::from(chain_info.best_block_number);
-			let highest_block = U256::from(status.highest_block_number.unwrap_or(status.start_block_number));
+			let mut current_block = U256::from(chain_info.best_block_number);
+			let mut highest_block = U256::from(status.highest_block_number.unwrap_or(status.start_block_number));
+
+			// while warping the best block stays at genesis until the restored database is
+			// put in place, so report the block the restoration is bringing the chain to.
+			if let Some(target) = self.snapshot.restoration_target().filter(|_| warping) {
+				current_block = U256::from(target);
+				highest_block = ::std::cmp::max(highest_block, current_block);
+			}
 
 			let info = SyncInfo {
 				starting_block: status.start_block_number.into(),
@@ -26,7 +26,7 @@ use jsonrpc_core::futures::future::Either;
 use light::cache::Cache as LightDataCache;
 use light::client::LightChainClient;
 use light::{cht, TransactionQueue};
-use light::on_demand::{request, OnDemandRequester};
+use light::on_demand::{request, OnDemandRequester, Priority};
 
 use ethereum_types::{Address, H64, H160, H256, U64, U256};
 use hash::{KECCAK_NULL_RLP, KECCAK_EMPTY_LIST_RLP};
@@ -199,7 +199,7 @@ where
 					//   - network is down.
 					//   - we get a score, but our hash is non-canonical.
 					//   - we get a score, and our hash is canonical.
-					let maybe_fut = sync.with_context(move |ctx| on_demand.request(ctx, req).expect(NO_INVALID_BACK_REFS));
+					let maybe_fut = sync.with_context(move |ctx| on_demand.request_with_priority(ctx, req, Priority::High).expect(NO_INVALID_BACK_REFS));
 					match maybe_fut {
 						Some(fut) => Either::B(fut
 							.map(move |(hash, score)| {
@@ -316,7 +316,7 @@ where
 			if hdr.transactions_root() == KECCAK_NULL_RLP {
 				Either::A(future::ok(Some(U256::from(0))))
 			} else {
-				sync.with_context(|ctx| on_demand.request(ctx, request::Body(hdr.into())))
+				sync.with_context(|ctx| on_demand.request_with_priority(ctx, request::Body(hdr.into()), Priority::High))
 					.map(|x| x.expect(NO_INVALID_BACK_REFS))
 					.map(|x| x.map(|b| Some(U256::from(b.transactions_count()))))
 					.map(|x| Either::B(x.map_err(errors::on_demand_error)))
@@ -332,7 +332,7 @@ where
 			if hdr.transactions_root() == KECCAK_NULL_RLP {
 				Either::A(future::ok(Some(U256::from(0))))
 			} else {
-				sync.with_context(|ctx| on_demand.request(ctx, request::Body(hdr.into())))
+				sync.with_context(|ctx| on_demand.request_with_priority(ctx, request::Body(hdr.into()), Priority::High))
 					.map(|x| x.expect(NO_INVALID_BACK_REFS))
 					.map(|x| x.map(|b| Some(U256::from(b.transactions_count()))))
 					.map(|x| Either::B(x.map_err(errors::on_demand_error)))
@@ -348,7 +348,7 @@ where
 			if hdr.uncles_hash() == KECCAK_EMPTY_LIST_RLP {
 				Either::A(future::ok(Some(U256::from(0))))
 			} else {
-				sync.with_context(|ctx| on_demand.request(ctx, request::Body(hdr.into())))
+				sync.with_context(|ctx| on_demand.request_with_priority(ctx, request::Body(hdr.into()), Priority::High))
 					.map(|x| x.expect(NO_INVALID_BACK_REFS))
 					.map(|x| x.map(|b| Some(U256::from(b.uncles_count()))))
 					.map(|x| Either::B(x.map_err(errors::on_demand_error)))
@@ -364,7 +364,7 @@ where
 			if hdr.uncles_hash() == KECCAK_EMPTY_LIST_RLP {
 				Either::B(future::ok(Some(U256::from(0))))
 			} else {
-				sync.with_context(|ctx| on_demand.request(ctx, request::Body(hdr.into())))
+				sync.with_context(|ctx| on_demand.request_with_priority(ctx, request::Body(hdr.into()), Priority::High))
 					.map(|x| x.expect(NO_INVALID_BACK_REFS))
 					.map(|x| x.map(|b| Some(U256::from(b.uncles_count()))))
 					.map(|x| Either::A(x.map_err(errors::on_demand_error)))
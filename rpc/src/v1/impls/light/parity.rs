@@ -40,10 +40,10 @@ use v1::helpers::light_fetch::{LightFetch, light_all_transactions};
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
-	Bytes, CallRequest,
+	Bytes, CallRequest, CallManyOptions, CallManyResult,
 	Peers, Transaction, RpcSettings, Histogram,
-	TransactionStats, LocalTransactionStatus,
-	LightBlockNumber, ChainStatus, Receipt,
+	TransactionStats, LocalTransactionStatus, PoolStatus, GasPriceCalibration, CacheInfo,
+	LightBlockNumber, ChainStatus, SyncStateInfo, ForkSchedule, Receipt,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, Header, RichHeader, RecoveredAccount,
 	Log, Filter,
@@ -146,11 +146,14 @@ where
 	fn net_peers(&self) -> Result<Peers> {
 		let peers = self.light_dispatch.sync.peers().into_iter().map(Into::into).collect();
 		let peer_numbers = self.light_dispatch.sync.peer_numbers();
+		let clients = self.light_dispatch.sync.client_histogram();
 
 		Ok(Peers {
 			active: peer_numbers.active,
 			connected: peer_numbers.connected,
 			max: peer_numbers.max as u32,
+			clients: clients.connected,
+			rejected: clients.rejected,
 			peers,
 		})
 	}
@@ -254,11 +257,26 @@ where
 	}
 
 	fn pending_transactions_stats(&self) -> Result<BTreeMap<H256, TransactionStats>> {
-		let stats = self.light_dispatch.sync.transactions_stats();
-		Ok(stats.into_iter()
+		let mut stats: BTreeMap<H256, TransactionStats> = self.light_dispatch.sync.transactions_stats()
+			.into_iter()
 			.map(|(hash, stats)| (hash, stats.into()))
-			.collect()
-		)
+			.collect();
+
+		// local transactions relayed to full peers, including those already seen in a block.
+		let txq = self.light_dispatch.transaction_queue.read();
+		for (hash, status) in txq.propagation_statuses() {
+			let mut propagated_to = BTreeMap::new();
+			for node_id in status.peers.values().filter_map(|node_id| *node_id) {
+				*propagated_to.entry(node_id).or_insert(0) += 1;
+			}
+
+			stats.entry(hash).or_insert(TransactionStats {
+				first_seen: status.first_propagated,
+				propagated_to,
+			});
+		}
+
+		Ok(stats)
 	}
 
 	fn local_transactions(&self) -> Result<BTreeMap<H256, LocalTransactionStatus>> {
@@ -275,11 +293,30 @@ where
 			map.insert(future.hash(), LocalTransactionStatus::Future);
 		}
 
+		for (tx, block_number, block_hash) in txq.mined_transactions() {
+			let mut tx = Transaction::from_signed(tx);
+			tx.block_number = Some(block_number.into());
+			tx.block_hash = Some(block_hash);
+			map.insert(tx.hash, LocalTransactionStatus::Mined(tx));
+		}
+
 		// TODO: other types?
 
 		Ok(map)
 	}
 
+	fn pool_status(&self) -> Result<PoolStatus> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn gas_price_calibration(&self) -> Result<Option<GasPriceCalibration>> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn cache_info(&self) -> Result<CacheInfo> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn ws_url(&self) -> Result<String> {
 		helpers::to_url(&self.ws_address)
 			.ok_or_else(errors::ws_disabled)
@@ -324,6 +361,16 @@ where
 		})
 	}
 
+	fn sync_state(&self) -> Result<SyncStateInfo> {
+		Err(errors::light_unimplemented(None))
+	}
+
+	fn fork_schedule(&self) -> Result<ForkSchedule> {
+		let client = &self.light_dispatch.client;
+		let best_block = client.chain_info().best_block_number;
+		Ok(ForkSchedule::new(client.engine().params().fork_schedule(), best_block))
+	}
+
 	fn node_kind(&self) -> Result<::v1::types::NodeKind> {
 		use ::v1::types::{NodeKind, Availability, Capability};
 
@@ -376,6 +423,10 @@ where
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn call_many(&self, _requests: Vec<CallRequest>, _block: Option<BlockNumber>, _options: Option<CallManyOptions>) -> Result<Vec<CallManyResult>> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn submit_work_detail(&self, _nonce: H64, _pow_hash: H256, _mix_hash: H256) -> Result<H256> {
 		Err(errors::light_unimplemented(None))
 	}
@@ -414,4 +465,12 @@ where
 	fn submit_raw_block(&self, _block: Bytes) -> Result<H256> {
 		Err(light_unimplemented(None))
 	}
+
+	fn lookup_preimage(&self, _hash: H256) -> Result<Option<Bytes>> {
+		Err(light_unimplemented(None))
+	}
+
+	fn blocks_by_author(&self, _author: H160, _from: u64, _to: u64, _limit: u64) -> Result<Option<Vec<H256>>> {
+		Err(light_unimplemented(None))
+	}
 }
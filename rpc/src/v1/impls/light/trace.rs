@@ -22,7 +22,7 @@ use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::errors;
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults,
-	TraceResultsWithTransactionHash, TraceOptions};
+	TraceResultsWithTransactionHash, TraceOptions, TransactionTraces};
 
 /// Traces api implementation.
 // TODO: all calling APIs should be possible w. proved remote TX execution.
@@ -39,7 +39,7 @@ impl Traces for TracesClient {
 		Err(errors::light_unimplemented(None))
 	}
 
-	fn transaction_traces(&self, _transaction_hash: H256) -> Result<Option<Vec<LocalizedTrace>>> {
+	fn transaction_traces(&self, _transaction_hash: H256, _after: Option<usize>, _count: Option<usize>) -> Result<Option<TransactionTraces>> {
 		Err(errors::light_unimplemented(None))
 	}
 
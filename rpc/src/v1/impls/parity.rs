@@ -33,6 +33,7 @@ use jsonrpc_core::futures::future;
 use jsonrpc_core::{BoxFuture, Result};
 use sync::{SyncProvider, ManageNetwork};
 use types::{
+	header::Header,
 	ids::BlockId,
 	verification::Unverified,
 	snapshot::RestorationStatus,
@@ -40,21 +41,25 @@ use types::{
 use updater::{Service as UpdateService};
 use version::version_data;
 
-use v1::helpers::{self, errors, fake_sign, NetworkSettings, verify_signature};
+use v1::helpers::{self, errors, fake_sign, NetworkSettings, verify_signature, SyncStateTracker};
 use v1::helpers::external_signer::{SigningQueue, SignerService};
+use v1::impls::traces::to_call_analytics;
 use v1::metadata::Metadata;
 use v1::traits::Parity;
 use v1::types::{
-	Bytes, CallRequest,
+	Bytes, CallRequest, CallManyOptions, CallManyResult,
 	Peers, Transaction, RpcSettings, Histogram,
-	TransactionStats, LocalTransactionStatus,
+	TransactionStats, LocalTransactionStatus, PoolStatus, GasPriceCalibration, CacheInfo,
 	BlockNumber, ConsensusCapability, VersionInfo,
-	OperationsInfo, ChainStatus, Log, Filter,
+	OperationsInfo, ChainStatus, SyncStateInfo, ForkSchedule, Log, Filter,
 	RichHeader, Receipt, RecoveredAccount,
 	block_number_to_id
 };
 use Host;
 
+/// Maximal number of blocks which can be requested from `parity_blocksByAuthor` at once.
+const MAX_BLOCKS_BY_AUTHOR: u64 = 1024;
+
 /// Parity implementation.
 pub struct ParityClient<C, M, U> {
 	client: Arc<C>,
@@ -67,6 +72,7 @@ pub struct ParityClient<C, M, U> {
 	signer: Option<Arc<SignerService>>,
 	ws_address: Option<Host>,
 	snapshot: Option<Arc<dyn SnapshotService>>,
+	sync_state: Arc<SyncStateTracker>,
 }
 
 impl<C, M, U> ParityClient<C, M, U> where
@@ -84,6 +90,7 @@ impl<C, M, U> ParityClient<C, M, U> where
 		signer: Option<Arc<SignerService>>,
 		ws_address: Option<Host>,
 		snapshot: Option<Arc<dyn SnapshotService>>,
+		sync_state: Arc<SyncStateTracker>,
 	) -> Self {
 		ParityClient {
 			client,
@@ -96,6 +103,38 @@ impl<C, M, U> ParityClient<C, M, U> where
 			signer,
 			ws_address,
 			snapshot,
+			sync_state,
+		}
+	}
+}
+
+impl<C, M, U, S> ParityClient<C, M, U> where
+	S: StateInfo + 'static,
+	C: miner::BlockChainClient + BlockChainClient + StateClient<State=S> + Call<State=S> + 'static,
+	M: MinerService<State=S> + 'static,
+	U: UpdateService + 'static,
+{
+	/// State and header to execute calls against at block `num`.
+	fn call_state(&self, num: BlockNumber) -> Result<(S, Header)> {
+		if num == BlockNumber::Pending {
+			let info = self.client.chain_info();
+			let state = self.miner.pending_state(info.best_block_number).ok_or_else(errors::state_pruned)?;
+			let header = self.miner.pending_block_header(info.best_block_number).ok_or_else(errors::state_pruned)?;
+
+			Ok((state, header))
+		} else {
+			let id = match num {
+				BlockNumber::Hash { hash, .. } => BlockId::Hash(hash),
+				BlockNumber::Num(num) => BlockId::Number(num),
+				BlockNumber::Earliest => BlockId::Earliest,
+				BlockNumber::Latest => BlockId::Latest,
+				BlockNumber::Pending => unreachable!(), // Already covered
+			};
+
+			let state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
+			let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?.decode().map_err(errors::decode)?;
+
+			Ok((state, header))
 		}
 	}
 }
@@ -151,11 +190,14 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		let num_peers_range = self.net.num_peers_range();
 		debug_assert!(num_peers_range.end() >= num_peers_range.start());
 		let peers = self.sync.peers().into_iter().map(Into::into).collect();
+		let clients = self.net.client_histogram();
 
 		Ok(Peers {
 			active: sync_status.num_active_peers,
 			connected: sync_status.num_peers,
 			max: sync_status.current_max_peers(*num_peers_range.start(), *num_peers_range.end()),
+			clients: clients.connected,
+			rejected: clients.rejected,
 			peers,
 		})
 	}
@@ -294,6 +336,21 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		)
 	}
 
+	fn pool_status(&self) -> Result<PoolStatus> {
+		Ok(PoolStatus {
+			senders: self.miner.queue_status_grouped(&*self.client).into_iter().map(Into::into).collect(),
+			rejected: self.miner.rejected_transactions().into_iter().map(Into::into).collect(),
+		})
+	}
+
+	fn gas_price_calibration(&self) -> Result<Option<GasPriceCalibration>> {
+		Ok(self.miner.gas_price_calibration().map(Into::into))
+	}
+
+	fn cache_info(&self) -> Result<CacheInfo> {
+		Ok(self.client.blockchain_cache_report().into())
+	}
+
 	fn ws_url(&self) -> Result<String> {
 		helpers::to_url(&self.ws_address)
 			.ok_or_else(errors::ws_disabled)
@@ -334,6 +391,15 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		})
 	}
 
+	fn sync_state(&self) -> Result<SyncStateInfo> {
+		Ok(self.sync_state.update())
+	}
+
+	fn fork_schedule(&self) -> Result<ForkSchedule> {
+		let best_block = self.client.chain_info().best_block_number;
+		Ok(ForkSchedule::new(self.client.fork_schedule(), best_block))
+	}
+
 	fn node_kind(&self) -> Result<::v1::types::NodeKind> {
 		use ::v1::types::{NodeKind, Availability, Capability};
 
@@ -405,34 +471,35 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 			)))
 			.collect::<Result<Vec<_>>>()?;
 
-		let num = num.unwrap_or_default();
-
-		let (mut state, header) = if num == BlockNumber::Pending {
-			let info = self.client.chain_info();
-			let state = self.miner.pending_state(info.best_block_number).ok_or_else(errors::state_pruned)?;
-			let header = self.miner.pending_block_header(info.best_block_number).ok_or_else(errors::state_pruned)?;
-
-			(state, header)
-		} else {
-			let id = match num {
-				BlockNumber::Hash { hash, .. } => BlockId::Hash(hash),
-				BlockNumber::Num(num) => BlockId::Number(num),
-				BlockNumber::Earliest => BlockId::Earliest,
-				BlockNumber::Latest => BlockId::Latest,
-				BlockNumber::Pending => unreachable!(), // Already covered
-			};
-
-			let state = self.client.state_at(id).ok_or_else(errors::state_pruned)?;
-			let header = self.client.block_header(id).ok_or_else(errors::state_pruned)?.decode().map_err(errors::decode)?;
-
-			(state, header)
-		};
+		let (mut state, header) = self.call_state(num.unwrap_or_default())?;
 
 		self.client.call_many(&requests, &mut state, &header)
 				.map(|res| res.into_iter().map(|res| res.output.into()).collect())
 				.map_err(errors::call)
 	}
 
+	fn call_many(&self, requests: Vec<CallRequest>, num: Option<BlockNumber>, options: Option<CallManyOptions>) -> Result<Vec<CallManyResult>> {
+		let options = options.unwrap_or_default();
+		let analytics = to_call_analytics(options.trace);
+		let requests = requests
+			.into_iter()
+			.map(|request| Ok((fake_sign::sign_call(request.into())?, analytics)))
+			.collect::<Result<Vec<_>>>()?;
+
+		let (mut state, header) = self.call_state(num.unwrap_or_default())?;
+
+		Ok(self.client.call_bundle(&requests, &mut state, &header, options.stop_on_error)
+			.into_iter()
+			.map(|result| match result {
+				Ok(executed) => {
+					let error = executed.exception.as_ref().map(|e| errors::vm(e, &executed.output).message);
+					CallManyResult { error, ..executed.into() }
+				},
+				Err(e) => CallManyResult::failed(errors::call(e).message),
+			})
+			.collect())
+	}
+
 	fn submit_work_detail(&self, nonce: H64, pow_hash: H256, mix_hash: H256) -> Result<H256> {
 		helpers::submit_work_detail(&self.client, &self.miner, nonce, pow_hash, mix_hash)
 	}
@@ -480,4 +547,17 @@ impl<C, M, U, S> Parity for ParityClient<C, M, U> where
 		);
 		Ok(result.map_err(errors::cannot_submit_block)?)
 	}
+
+	fn lookup_preimage(&self, hash: H256) -> Result<Option<Bytes>> {
+		Ok(self.client.preimage(&hash).map(Into::into))
+	}
+
+	fn blocks_by_author(&self, author: H160, from: u64, to: u64, limit: u64) -> Result<Option<Vec<H256>>> {
+		if limit > MAX_BLOCKS_BY_AUTHOR {
+			return Err(errors::invalid_params("limit", format!("Expected at most {} blocks.", MAX_BLOCKS_BY_AUTHOR)));
+		}
+		Ok(self.client
+			.blocks_authored_by(&author, from, to, limit as usize)
+			.map(|blocks| blocks.into_iter().map(|(_, hash)| hash).collect()))
+	}
 }
@@ -16,12 +16,13 @@
 
 //! Account management (personal) rpc implementation
 use std::sync::Arc;
+use std::time::Duration;
 use std::collections::{
 	btree_map::{BTreeMap, Entry},
 	HashSet,
 };
 
-use ethereum_types::{Address, H160, H256, H520};
+use ethereum_types::{Address, H160, H256, H520, U64};
 use ethkey::{Brain, Password};
 use crypto::publickey::{Generator, Secret};
 use ethstore::KeyFile;
@@ -30,19 +31,21 @@ use jsonrpc_core::Result;
 use v1::helpers::deprecated::{self, DeprecationNotice};
 use v1::helpers::errors;
 use v1::traits::{ParityAccounts, ParityAccountsInfo};
-use v1::types::{Derive, DeriveHierarchical, DeriveHash, ExtAccountInfo, AccountInfo};
+use v1::types::{Derive, DeriveHierarchical, DeriveHash, ExtAccountInfo, AccountInfo, KeyFormatInfo};
 
 /// Account management (personal) rpc implementation.
 pub struct ParityAccountsClient {
 	accounts: Arc<AccountProvider>,
+	allow_perm_unlock: bool,
 	deprecation_notice: DeprecationNotice,
 }
 
 impl ParityAccountsClient {
 	/// Creates new PersonalClient
-	pub fn new(store: &Arc<AccountProvider>) -> Self {
+	pub fn new(store: &Arc<AccountProvider>, allow_perm_unlock: bool) -> Self {
 		ParityAccountsClient {
 			accounts: store.clone(),
+			allow_perm_unlock,
 			deprecation_notice: Default::default(),
 		}
 	}
@@ -52,6 +55,18 @@ impl ParityAccountsClient {
 	fn deprecation_notice(&self, method: &'static str) {
 		self.deprecation_notice.print(method, deprecated::msgs::ACCOUNTS);
 	}
+
+	/// Sets the name of an account, or of an address book entry if there is no such account.
+	fn apply_account_name(&self, addr: Address, name: String) {
+		self.accounts.set_account_name(addr.clone(), name.clone())
+			.unwrap_or_else(|_| self.accounts.set_address_name(addr, name));
+	}
+
+	/// Sets the metadata of an account, or of an address book entry if there is no such account.
+	fn apply_account_meta(&self, addr: Address, meta: String) {
+		self.accounts.set_account_meta(addr.clone(), meta.clone())
+			.unwrap_or_else(|_| self.accounts.set_address_meta(addr, meta));
+	}
 }
 
 impl ParityAccountsInfo for ParityAccountsClient {
@@ -159,6 +174,29 @@ impl ParityAccounts for ParityAccountsClient {
 			.map_err(|e| errors::account("Could not fetch account info.", e))
 	}
 
+	fn unlock_account_timed(&self, account: H160, password: Password, seconds: U64) -> Result<bool> {
+		self.deprecation_notice("parity_unlockAccountTimed");
+		if !self.allow_perm_unlock {
+			return Err(errors::unsupported(
+				"Time-unlocking is not supported when permanent unlock is disabled.",
+				Some("Use personal_sendTransaction or enable permanent unlocking, instead."),
+			));
+		}
+		// bounded like the duration of personal_unlockAccount.
+		let seconds = seconds.as_u64();
+		if seconds > u32::max_value() as u64 {
+			return Err(errors::invalid_params("seconds", "Unlock duration is too long."));
+		}
+		if seconds == 0 {
+			return Err(errors::invalid_params("seconds", "Unlock duration has to be greater than zero."));
+		}
+
+		self.accounts
+			.unlock_account_timed(account.into(), password, Duration::from_secs(seconds))
+			.map(|_| true)
+			.map_err(|e| errors::account("Unable to unlock the account.", e))
+	}
+
 	fn kill_account(&self, account: H160, password: Password) -> Result<bool> {
 		self.deprecation_notice("parity_killAccount");
 		let account: Address = account.into();
@@ -178,19 +216,29 @@ impl ParityAccounts for ParityAccountsClient {
 
 	fn set_account_name(&self, addr: H160, name: String) -> Result<bool> {
 		self.deprecation_notice("parity_setAccountName");
-		let addr: Address = addr.into();
-
-		self.accounts.set_account_name(addr.clone(), name.clone())
-			.unwrap_or_else(|_| self.accounts.set_address_name(addr, name));
+		self.apply_account_name(addr.into(), name);
 		Ok(true)
 	}
 
 	fn set_account_meta(&self, addr: H160, meta: String) -> Result<bool> {
 		self.deprecation_notice("parity_setAccountMeta");
-		let addr: Address = addr.into();
+		self.apply_account_meta(addr.into(), meta);
+		Ok(true)
+	}
+
+	fn set_accounts_metadata(&self, updates: Vec<(H160, Option<String>, Option<String>)>) -> Result<bool> {
+		self.deprecation_notice("parity_setAccountsMetadata");
+
+		for (addr, name, meta) in updates {
+			let addr: Address = addr.into();
+			if let Some(name) = name {
+				self.apply_account_name(addr, name);
+			}
+			if let Some(meta) = meta {
+				self.apply_account_meta(addr, meta);
+			}
+		}
 
-		self.accounts.set_account_meta(addr.clone(), meta.clone())
-			.unwrap_or_else(|_| self.accounts.set_address_meta(addr, meta));
 		Ok(true)
 	}
 
@@ -324,6 +372,19 @@ impl ParityAccounts for ParityAccountsClient {
 			.map_err(|e| errors::account("Could not export account.", e))
 	}
 
+	fn account_key_info(&self, addr: H160) -> Result<KeyFormatInfo> {
+		self.deprecation_notice("parity_accountKeyInfo");
+		self.accounts
+			.account_key_format(addr)
+			.map(|format| KeyFormatInfo {
+				version: format.version,
+				kdf: format.kdf,
+				iterations: format.iterations,
+				cipher: format.cipher,
+			})
+			.map_err(|e| errors::account("Could not fetch account key info.", e))
+	}
+
 	fn sign_message(&self, addr: H160, password: Password, message: H256) -> Result<H520> {
 		self.deprecation_notice("parity_signMessage");
 		self.accounts
@@ -34,9 +34,9 @@ use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::{errors, fake_sign};
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults,
-	TraceResultsWithTransactionHash, TraceOptions, block_number_to_id};
+	TraceResultsWithTransactionHash, TraceOptions, TransactionTraces, block_number_to_id};
 
-fn to_call_analytics(flags: TraceOptions) -> CallAnalytics {
+pub(crate) fn to_call_analytics(flags: TraceOptions) -> CallAnalytics {
 	CallAnalytics {
 		transaction_tracing: flags.contains(&("trace".to_owned())),
 		vm_tracing: flags.contains(&("vmTrace".to_owned())),
@@ -79,9 +79,15 @@ impl<C, S> Traces for TracesClient<C> where
 			.map(|traces| traces.into_iter().map(LocalizedTrace::from).collect()))
 	}
 
-	fn transaction_traces(&self, transaction_hash: H256) -> Result<Option<Vec<LocalizedTrace>>> {
-		Ok(self.client.transaction_traces(TransactionId::Hash(transaction_hash))
-			.map(|traces| traces.into_iter().map(LocalizedTrace::from).collect()))
+	fn transaction_traces(&self, transaction_hash: H256, after: Option<usize>, count: Option<usize>) -> Result<Option<TransactionTraces>> {
+		let id = TransactionId::Hash(transaction_hash);
+		if after.is_none() && count.is_none() {
+			return Ok(self.client.transaction_traces(id)
+				.map(|traces| TransactionTraces::All(traces.into_iter().map(LocalizedTrace::from).collect())));
+		}
+
+		Ok(self.client.transaction_traces_page(id, after.unwrap_or(0), count.unwrap_or(usize::max_value()))
+			.map(|page| TransactionTraces::Page(page.into())))
 	}
 
 	fn trace(&self, transaction_hash: H256, address: Vec<Index>) -> Result<Option<LocalizedTrace>> {
@@ -43,7 +43,7 @@ pub mod traits;
 
 pub use self::traits::{Debug, Eth, EthFilter, EthPubSub, EthSigning, Net, Parity, ParityAccountsInfo, ParityAccounts, ParitySet, ParitySetAccounts, ParitySigning, Personal, PubSub, Private, Rpc, SecretStore, Signer, Traces, Web3};
 pub use self::impls::*;
-pub use self::helpers::{NetworkSettings, block_import, dispatch};
+pub use self::helpers::{NetworkSettings, SyncStateTracker, block_import, dispatch};
 pub use self::metadata::Metadata;
 pub use self::types::Origin;
 pub use self::types::pubsub::PubSubSyncStatus;
@@ -54,5 +54,5 @@ pub mod signer {
 	#[cfg(any(test, feature = "accounts"))]
 	pub use super::helpers::engine_signer::EngineSigner;
 	pub use super::helpers::external_signer::{SignerService, ConfirmationsQueue};
-	pub use super::types::{ConfirmationRequest, TransactionModification, TransactionCondition};
+	pub use super::types::{ConfirmationRequest, ConfirmationPayload, TransactionRequest, TransactionModification, TransactionCondition, format_ether};
 }
@@ -27,8 +27,9 @@ use ethcore::client::{PrepareOpenBlock, EngineInfo};
 use ethcore::miner::{self, MinerService, AuthoringParams, FilterOptions};
 use ethcore::test_helpers::TestState;
 use ethereum_types::{H256, U256, Address};
+use miner::gas_pricer::CalibrationStatus;
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
-use miner::pool::{verifier, VerifiedTransaction, QueueStatus};
+use miner::pool::{verifier, VerifiedTransaction, QueueStatus, RejectedTransaction, SenderStatus};
 use parking_lot::{RwLock, Mutex};
 use txpool;
 use types::{
@@ -49,12 +50,18 @@ pub struct TestMinerService {
 	pub pending_transactions: Mutex<HashMap<H256, SignedTransaction>>,
 	/// Pre-existed local transactions
 	pub local_transactions: Mutex<BTreeMap<H256, LocalTransactionStatus>>,
+	/// Queued transactions grouped by sender
+	pub queued_senders: Mutex<Vec<SenderStatus>>,
+	/// Recently rejected transactions
+	pub rejected_transactions: Mutex<Vec<RejectedTransaction>>,
 	/// Pre-existed pending receipts
 	pub pending_receipts: Mutex<Vec<RichReceipt>>,
 	/// Next nonces.
 	pub next_nonces: RwLock<HashMap<Address, U256>>,
 	/// Minimum gas price
 	pub min_gas_price: RwLock<Option<U256>>,
+	/// Gas price calibration status
+	pub gas_price_calibration: RwLock<Option<CalibrationStatus>>,
 	/// Signer (if any)
 	pub signer: RwLock<Option<Box<dyn EngineSigner>>>,
 
@@ -67,9 +74,12 @@ impl Default for TestMinerService {
 			imported_transactions: Default::default(),
 			pending_transactions: Default::default(),
 			local_transactions: Default::default(),
+			queued_senders: Default::default(),
+			rejected_transactions: Default::default(),
 			pending_receipts: Default::default(),
 			next_nonces: Default::default(),
 			min_gas_price: RwLock::new(Some(0.into())),
+			gas_price_calibration: RwLock::new(None),
 			authoring_params: RwLock::new(AuthoringParams {
 				author: Address::zero(),
 				gas_range_target: (12345.into(), 54321.into()),
@@ -295,6 +305,14 @@ impl MinerService for TestMinerService {
 		}
 	}
 
+	fn queue_status_grouped<C: Nonce + Sync>(&self, _chain: &C) -> Vec<SenderStatus> {
+		self.queued_senders.lock().clone()
+	}
+
+	fn rejected_transactions(&self) -> Vec<RejectedTransaction> {
+		self.rejected_transactions.lock().clone()
+	}
+
 	/// Submit `seal` as a valid solution for the header of `pow_hash`.
 	/// Will check the seal, but not actually insert the block into the chain.
 	fn submit_seal(&self, _pow_hash: H256, _seal: Vec<Bytes>) -> Result<SealedBlock, Error> {
@@ -322,4 +340,8 @@ impl MinerService for TestMinerService {
 			},
 		}
 	}
+
+	fn gas_price_calibration(&self) -> Option<CalibrationStatus> {
+		self.gas_price_calibration.read().clone()
+	}
 }
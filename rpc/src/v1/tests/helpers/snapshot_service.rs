@@ -24,6 +24,7 @@ use types::snapshot::{ManifestData, RestorationStatus};
 /// Mocked snapshot service (used for sync info extensions).
 pub struct TestSnapshotService {
 	status: Mutex<RestorationStatus>,
+	restoration_target: Mutex<Option<u64>>,
 }
 
 impl TestSnapshotService {
@@ -32,6 +33,7 @@ impl TestSnapshotService {
 	pub fn new() -> Self {
 		TestSnapshotService {
 			status: Mutex::new(RestorationStatus::Inactive),
+			restoration_target: Mutex::new(None),
 		}
 	}
 
@@ -39,14 +41,21 @@ impl TestSnapshotService {
 	pub fn set_status(&self, status: RestorationStatus) {
 		*self.status.lock() = status;
 	}
+
+	/// Set the block number the restoration is targeting.
+	pub fn set_restoration_target(&self, target: Option<u64>) {
+		*self.restoration_target.lock() = target;
+	}
 }
 
 impl SnapshotService for TestSnapshotService {
 	fn manifest(&self) -> Option<ManifestData> { None }
 	fn supported_versions(&self) -> Option<(u64, u64)> { None }
+	fn engine_name(&self) -> String { String::new() }
 	fn completed_chunks(&self) -> Option<Vec<H256>> { Some(vec![]) }
 	fn chunk(&self, _hash: H256) -> Option<Bytes> { None }
 	fn status(&self) -> RestorationStatus { self.status.lock().clone() }
+	fn restoration_target(&self) -> Option<u64> { *self.restoration_target.lock() }
 	fn begin_restore(&self, _manifest: ManifestData) { }
 	fn abort_restore(&self) { }
 	fn restore_state_chunk(&self, _hash: H256, _chunk: Bytes) { }
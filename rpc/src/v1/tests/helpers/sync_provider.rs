@@ -21,7 +21,7 @@ use ethereum_types::{H256, H512};
 use parking_lot::RwLock;
 use network::client_version::ClientVersion;
 use futures::sync::mpsc;
-use sync::{SyncProvider, EthProtocolInfo, SyncStatus, PeerInfo, TransactionStats, SyncState};
+use sync::{SyncProvider, EthProtocolInfo, SyncStatus, PeerInfo, TransactionStats, SyncState, SnapshotServingStats};
 
 /// TestSyncProvider config.
 pub struct Config {
@@ -141,4 +141,8 @@ impl SyncProvider for TestSyncProvider {
 			_ => false
 		}
 	}
+
+	fn snapshot_serving_stats(&self) -> SnapshotServingStats {
+		SnapshotServingStats::default()
+	}
 }
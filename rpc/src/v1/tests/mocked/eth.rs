@@ -165,7 +165,13 @@ fn rpc_eth_syncing() {
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(snap_res.to_owned()));
 
+	// current block is the one the restoration is bringing the chain to.
+	tester.snapshot.set_restoration_target(Some(5000));
+	let snap_res = r#"{"jsonrpc":"2.0","result":{"currentBlock":"0x1388","highestBlock":"0x1388","startingBlock":"0x0","warpChunksAmount":"0x32","warpChunksProcessed":"0x18"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(snap_res.to_owned()));
+
 	tester.snapshot.set_status(RestorationStatus::Inactive);
+	tester.snapshot.set_restoration_target(None);
 
 	// finish "syncing"
 	tester.add_blocks(1500, EachBlockWith::Nothing);
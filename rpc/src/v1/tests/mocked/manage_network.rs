@@ -16,7 +16,7 @@
 
 use std::ops::RangeInclusive;
 use sync::ManageNetwork;
-use self::ethcore_network::{ProtocolId, NetworkContext};
+use self::ethcore_network::{ClientHistogram, ProtocolId, NetworkContext};
 
 extern crate ethcore_network;
 
@@ -32,4 +32,10 @@ impl ManageNetwork for TestManageNetwork {
 	fn stop_network(&self) {}
 	fn num_peers_range(&self) -> RangeInclusive<u32> { 25..=50 }
 	fn with_proto_context(&self, _: ProtocolId, _: &mut dyn FnMut(&dyn NetworkContext)) { }
+	fn client_histogram(&self) -> ClientHistogram {
+		ClientHistogram {
+			connected: vec![("OpenEthereum".to_owned(), 1), ("Parity-Ethereum".to_owned(), 1)].into_iter().collect(),
+			rejected: vec![("other".to_owned(), 2)].into_iter().collect(),
+		}
+	}
 }
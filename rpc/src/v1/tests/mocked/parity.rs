@@ -15,24 +15,25 @@
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
-use ethcore::test_helpers::TestBlockChainClient;
+use ethcore::test_helpers::{EachBlockWith, TestBlockChainClient};
 use ethcore_logger::RotatingLogger;
 use ethereum_types::{Address, U256, H256, BigEndianHash, Bloom};
 use crypto::publickey::{Generator, Random};
 use machine::executed::Executed;
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
-use sync::ManageNetwork;
+use sync::{ManageNetwork, SyncState};
 use types::{
 	ids::TransactionId,
 	receipt::{LocalizedReceipt, TransactionOutcome},
+	snapshot::RestorationStatus,
 };
 
 use jsonrpc_core::IoHandler;
 use v1::{Parity, ParityClient};
 use v1::metadata::Metadata;
-use v1::helpers::NetworkSettings;
+use v1::helpers::{NetworkSettings, SyncStateTracker};
 use v1::helpers::external_signer::SignerService;
-use v1::tests::helpers::{TestSyncProvider, Config, TestMinerService, TestUpdater};
+use v1::tests::helpers::{TestSyncProvider, Config, TestMinerService, TestUpdater, TestSnapshotService};
 use super::manage_network::TestManageNetwork;
 use Host;
 
@@ -47,6 +48,7 @@ pub struct Dependencies {
 	pub settings: Arc<NetworkSettings>,
 	pub network: Arc<dyn ManageNetwork>,
 	pub ws_address: Option<Host>,
+	pub snapshot: Arc<TestSnapshotService>,
 }
 
 impl Dependencies {
@@ -71,6 +73,7 @@ impl Dependencies {
 			}),
 			network: Arc::new(TestManageNetwork),
 			ws_address: Some("127.0.0.1:18546".into()),
+			snapshot: Arc::new(TestSnapshotService::new()),
 		}
 	}
 
@@ -85,7 +88,8 @@ impl Dependencies {
 			self.settings.clone(),
 			signer,
 			self.ws_address.clone(),
-			None,
+			Some(self.snapshot.clone()),
+			Arc::new(SyncStateTracker::new(self.sync.clone(), Some(self.snapshot.clone()), self.client.clone())),
 		)
 	}
 
@@ -260,6 +264,10 @@ fn rpc_parity_net_peers() {
 		"jsonrpc": "2.0",
 		"result": {
 			"active": 0,
+			"clients": {
+				"OpenEthereum": 1,
+				"Parity-Ethereum": 1
+			},
 			"connected": 120,
 			"max": 50,
 			"peers": [
@@ -321,7 +329,10 @@ fn rpc_parity_net_peers() {
 						"pip": null
 					}
 				}
-			]
+			],
+			"rejected": {
+				"other": 2
+			}
 		},
 		"id": 1
 	}"#.replace(char::is_whitespace, "");
@@ -513,6 +524,131 @@ fn rpc_parity_local_transactions() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_pool_status() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+	let tx = ::types::transaction::Transaction {
+		value: 5.into(),
+		gas: 3.into(),
+		gas_price: 2.into(),
+		action: ::types::transaction::Action::Create,
+		data: vec![1, 2, 3],
+		nonce: 2.into(),
+	}.fake_sign(Address::from_low_u64_be(3));
+	let hash = tx.hash();
+	let tx = Arc::new(::miner::pool::VerifiedTransaction::from_pending_block_transaction(tx));
+	deps.miner.queued_senders.lock().push(::miner::pool::SenderStatus {
+		sender: Address::from_low_u64_be(3),
+		nonce: 1.into(),
+		transactions: vec![tx],
+		first_gap: Some(1.into()),
+	});
+	deps.miner.rejected_transactions.lock().push(::miner::pool::RejectedTransaction {
+		hash: H256::from_low_u64_be(10),
+		sender: Some(Address::from_low_u64_be(4)),
+		reason: ::types::transaction::Error::InsufficientBalance { balance: 1.into(), cost: 2.into() },
+		timestamp: 1_000,
+	});
+	deps.miner.rejected_transactions.lock().push(::miner::pool::RejectedTransaction {
+		hash: H256::from_low_u64_be(15),
+		sender: None,
+		reason: ::types::transaction::Error::InsufficientGas { minimal: 21_000.into(), got: 3.into() },
+		timestamp: 1_001,
+	});
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_poolStatus", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"rejected":[{"hash":"0x000000000000000000000000000000000000000000000000000000000000000a","reason":"Insufficient funds. The account you tried to send transaction from does not have enough funds. Required 2 and got: 1.","sender":"0x0000000000000000000000000000000000000004","timestamp":1000},{"hash":"0x000000000000000000000000000000000000000000000000000000000000000f","reason":"Transaction gas is too low. There is not enough gas to cover minimal cost of the transaction (minimal: 21000, got: 3). Try increasing supplied gas.","sender":null,"timestamp":1001}],"senders":[{"firstGap":"0x1","nonce":"0x1","sender":"0x0000000000000000000000000000000000000003","transactions":[{"gasPrice":"0x2","hash":""#.to_owned()
+		+ &format!("0x{:x}", hash)
+		+ r#"","nonce":"0x2"}]}]},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_parity_gas_price_calibration() {
+	use std::time::{Duration, SystemTime};
+	use miner::gas_pricer::{CalibrationInfo, CalibrationStatus, PriceSourceStatus};
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_gasPriceCalibration", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+	*deps.miner.gas_price_calibration.write() = Some(CalibrationStatus {
+		last_calibration: Some(CalibrationInfo {
+			source: "second".into(),
+			timestamp: at,
+			ethusd: 100.0,
+			gas_price: 1_000.into(),
+		}),
+		sources: vec![
+			PriceSourceStatus {
+				name: "first".into(),
+				consecutive_failures: 2,
+				last_success: None,
+				last_error: Some("Timeout".into()),
+			},
+			PriceSourceStatus {
+				name: "second".into(),
+				consecutive_failures: 0,
+				last_success: Some(at),
+				last_error: None,
+			},
+		],
+	});
+
+	let response = r#"{"jsonrpc":"2.0","result":{"lastCalibration":{"ethusd":100.0,"gasPrice":"0x3e8","source":"second","timestamp":1000},"sources":[{"consecutiveFailures":2,"lastError":"Timeout","lastSuccess":null,"name":"first"},{"consecutiveFailures":0,"lastError":null,"lastSuccess":1000,"name":"second"}]},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_cache_info() {
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_cacheInfo", "params":[], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"blockBodies":{"hits":0,"max":0,"misses":0,"pref":0,"used":0},"blockDetails":{"hits":0,"max":0,"misses":0,"pref":0,"used":0},"blockHashes":{"hits":0,"max":0,"misses":0,"pref":0,"used":0},"blockHeaders":{"hits":0,"max":0,"misses":0,"pref":0,"used":0},"blockReceipts":{"hits":0,"max":0,"misses":0,"pref":0,"used":0},"transactionAddresses":{"hits":0,"max":0,"misses":0,"pref":0,"used":0}},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_fork_schedule() {
+	use serde_json::{self, Value};
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_forkSchedule", "params":[], "id": 1}"#;
+	let response: Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+	let result = &response["result"];
+
+	assert_eq!(result["bestBlock"], "0x0");
+	assert_eq!(result["transitions"][0]["name"], "eip150Transition");
+	assert_eq!(result["transitions"][0]["block"], "0x0");
+	assert_eq!(result["transitions"][4]["name"], "eip98Transition");
+	assert_eq!(result["transitions"][4]["block"], Value::Null);
+
+	let active: Vec<_> = result["active"].as_array().unwrap().iter()
+		.map(|name| name.as_str().unwrap())
+		.collect();
+	assert_eq!(active, vec![
+		"eip150Transition",
+		"eip160Transition",
+		"eip161abcTransition",
+		"eip161dTransition",
+		"eip155Transition",
+		"validateReceiptsTransition",
+		"validateChainIdTransition",
+		"maxCodeSizeTransition",
+		"maxInitCodeSizeTransition",
+		"transactionPermissionContractTransition",
+	]);
+}
+
 #[test]
 fn rpc_parity_chain_status() {
 	let deps = Dependencies::new();
@@ -527,6 +663,82 @@ fn rpc_parity_chain_status() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_sync_state() {
+	use serde_json::{self, json};
+
+	let deps = Dependencies::new();
+	let io = deps.default_client();
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_syncState", "params":[], "id": 1}"#;
+	let phase = |res: String| {
+		let res: serde_json::Value = serde_json::from_str(&res).unwrap();
+		(res["result"]["phase"].clone(), res["result"]["warpTarget"].clone(), res["result"]["phasesStarted"].clone())
+	};
+
+	let (current, target, started) = phase(io.handle_request_sync(request).unwrap());
+	assert_eq!(current, json!({"name": "idle"}));
+	assert_eq!(target, json!(null));
+	assert!(started["idle"].is_u64());
+
+	// looking for a manifest
+	deps.sync.status.write().state = SyncState::SnapshotManifest;
+	let (current, _, started) = phase(io.handle_request_sync(request).unwrap());
+	assert_eq!(current, json!({"name": "warpManifest"}));
+	assert!(started["warpManifest"].is_u64());
+
+	// restoring chunks
+	deps.sync.status.write().state = SyncState::SnapshotWaiting;
+	deps.snapshot.set_restoration_target(Some(5000));
+	deps.snapshot.set_status(RestorationStatus::Ongoing {
+		state_chunks: 40,
+		block_chunks: 10,
+		state_chunks_done: 18,
+		block_chunks_done: 6,
+	});
+	let (current, target, started) = phase(io.handle_request_sync(request).unwrap());
+	assert_eq!(current, json!({
+		"name": "warpChunks",
+		"stateChunksDone": "0x12",
+		"stateChunks": "0x28",
+		"blockChunksDone": "0x6",
+		"blockChunks": "0xa"
+	}));
+	assert_eq!(target, json!("0x1388"));
+	assert!(started["idle"].is_u64() && started["warpManifest"].is_u64() && started["warpChunks"].is_u64());
+
+	deps.snapshot.set_status(RestorationStatus::Finalizing);
+	let (current, _, _) = phase(io.handle_request_sync(request).unwrap());
+	assert_eq!(current, json!({"name": "warpFinalizing"}));
+
+	// catching up with the network after the restoration
+	deps.snapshot.set_status(RestorationStatus::Inactive);
+	deps.snapshot.set_restoration_target(None);
+	deps.client.add_blocks(5000, EachBlockWith::Nothing);
+	{
+		let mut status = deps.sync.status.write();
+		status.state = SyncState::Blocks;
+		status.start_block_number = 5000;
+		status.highest_block_number = Some(5100);
+	}
+	*deps.client.ancient_block.write() = Some((H256::zero(), 0));
+	*deps.client.first_block.write() = Some((H256::zero(), 5000));
+	let (current, target, _) = phase(io.handle_request_sync(request).unwrap());
+	assert_eq!(current, json!({
+		"name": "fullSync",
+		"currentBlock": "0x1388",
+		"highestBlock": "0x13ec",
+		"startingBlock": "0x1388"
+	}));
+	assert_eq!(target, json!(null));
+
+	// filling in the blocks preceding the snapshot
+	deps.sync.status.write().state = SyncState::Idle;
+	*deps.client.ancient_block.write() = Some((H256::zero(), 1000));
+	let (current, _, started) = phase(io.handle_request_sync(request).unwrap());
+	assert_eq!(current, json!({"name": "ancientBackfill", "remaining": "0xf9f"}));
+	assert!(started["fullSync"].is_u64() && started["ancientBackfill"].is_u64());
+}
+
 #[test]
 fn rpc_parity_node_kind() {
 	let deps = Dependencies::new();
@@ -575,6 +787,59 @@ fn rpc_parity_call() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_call_many() {
+	let deps = Dependencies::new();
+	let executed = Executed {
+		exception: None,
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::from(0x5),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![0x12, 0x34, 0xff],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+	};
+	deps.client.set_execution_result(Ok(executed.clone()));
+	let io = deps.default_client();
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_callMany",
+		"params": [[{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"data": "0xd46e8dd67c5d32be8d46e8dd67c5d32be8058bb8eb970870f072445675058bb8eb970870f072445675"
+		}, {
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"gas": "0x76c0"
+		}],
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"gasUsed":"0xff30","output":"0x1234ff"},{"gasUsed":"0xff30","output":"0x1234ff"}],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	deps.client.set_execution_result(Ok(Executed { exception: Some(::vm::Error::OutOfGas), ..executed }));
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "parity_callMany",
+		"params": [[{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155"
+		}, {
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155"
+		}],
+		"latest",
+		{"stopOnError": true}],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"error":"Out of gas","gasUsed":"0xff30","output":"0x1234ff"}],"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_parity_block_receipts() {
 	let deps = Dependencies::new();
@@ -678,3 +943,42 @@ fn rpc_parity_verify_signature() {
 
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_parity_lookup_preimage() {
+	let deps = Dependencies::new();
+	deps.client.preimages.write().insert(H256::from_low_u64_be(1), vec![0x35; 20]);
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_lookupPreimage", "params": ["0x0000000000000000000000000000000000000000000000000000000000000001"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x3535353535353535353535353535353535353535","id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_lookupPreimage", "params": ["0x0000000000000000000000000000000000000000000000000000000000000002"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_parity_blocks_by_author() {
+	let deps = Dependencies::new();
+	let author = Address::from_low_u64_be(0x35);
+	for n in 1..5 {
+		let block_author = if n % 2 == 1 { author } else { Address::zero() };
+		deps.client.add_block(EachBlockWith::Nothing, |mut header| { header.set_author(block_author); header });
+	}
+	let io = deps.default_client();
+	let (first, second) = (deps.client.numbers.read()[&1], deps.client.numbers.read()[&3]);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_blocksByAuthor", "params": ["0x0000000000000000000000000000000000000035", 0, 10, 5], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":["{:?}","{:?}"],"id":1}}"#, first, second);
+	assert_eq!(io.handle_request_sync(request), Some(response));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_blocksByAuthor", "params": ["0x0000000000000000000000000000000000000035", 2, 10, 1], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":["{:?}"],"id":1}}"#, second);
+	assert_eq!(io.handle_request_sync(request), Some(response));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_blocksByAuthor", "params": ["0x0000000000000000000000000000000000000035", 0, 10, 1025], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: limit","data":"\"Expected at most 1024 blocks.\""},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -16,6 +16,7 @@
 
 use std::sync::Arc;
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 use accounts::{AccountProvider, AccountProviderSettings};
 use ethereum_types::Address;
@@ -24,6 +25,7 @@ use ethstore::accounts_dir::RootDiskDirectory;
 use tempfile::TempDir;
 
 use jsonrpc_core::IoHandler;
+use parking_lot::Mutex;
 use v1::{ParityAccounts, ParityAccountsInfo, ParityAccountsClient};
 
 struct ParityAccountsTester {
@@ -42,9 +44,13 @@ fn accounts_provider_with_vaults_support(temp_path: &str) -> Arc<AccountProvider
 }
 
 fn setup_with_accounts_provider(accounts_provider: Arc<AccountProvider>) -> ParityAccountsTester {
+	setup_with_perm_unlock(accounts_provider, true)
+}
+
+fn setup_with_perm_unlock(accounts_provider: Arc<AccountProvider>, allow_perm_unlock: bool) -> ParityAccountsTester {
 	let opt_ap = accounts_provider.clone();
-	let parity_accounts = ParityAccountsClient::new(&opt_ap);
-	let parity_accounts2 = ParityAccountsClient::new(&opt_ap);
+	let parity_accounts = ParityAccountsClient::new(&opt_ap, allow_perm_unlock);
+	let parity_accounts2 = ParityAccountsClient::new(&opt_ap, allow_perm_unlock);
 	let mut io = IoHandler::default();
 	io.extend_with(ParityAccounts::to_delegate(parity_accounts));
 	io.extend_with(ParityAccountsInfo::to_delegate(parity_accounts2));
@@ -166,6 +172,82 @@ fn should_be_able_to_set_meta() {
 	assert_eq!(res, Some(response));
 }
 
+#[test]
+fn should_be_able_to_set_metadata_of_several_accounts() {
+	let tester = setup();
+	tester.accounts.new_account(&"".into()).unwrap();
+	tester.accounts.new_account(&"".into()).unwrap();
+	let accounts = tester.accounts.accounts().unwrap();
+	assert_eq!(accounts.len(), 2);
+	let (first, second) = (accounts[0], accounts[1]);
+
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "parity_setAccountsMetadata", "params": [[["0x{:x}", "First", null], ["0x{:x}", "Second", "{{foo: 69}}"]]], "id": 1}}"#,
+		first,
+		second,
+	);
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	let res = tester.io.handle_request_sync(&request);
+	assert_eq!(res, Some(response.into()));
+
+	let info = tester.accounts.accounts_info().unwrap();
+	assert_eq!(info[&first].name, "First");
+	assert_eq!(info[&first].meta, "{}");
+	assert_eq!(info[&second].name, "Second");
+	assert_eq!(info[&second].meta, "{foo: 69}");
+}
+
+#[test]
+fn should_be_able_to_unlock_account_timed() {
+	let now = Arc::new(Mutex::new(Instant::now()));
+	let clock = now.clone();
+	let tester = setup_with_accounts_provider(Arc::new(
+		AccountProvider::transient_provider().with_clock(move || *clock.lock())
+	));
+	tester.accounts.new_account(&"password".into()).unwrap();
+	let address = tester.accounts.accounts().unwrap()[0];
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_unlockAccountTimed", "params": ["0x{:x}", "wrong", "0x1"], "id": 1}}"#, address);
+	let res = tester.io.handle_request_sync(&request).unwrap();
+	assert!(res.contains("Unable to unlock the account."), "{}", res);
+	assert!(!tester.accounts.is_unlocked(&address));
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_unlockAccountTimed", "params": ["0x{:x}", "password", "0x0"], "id": 1}}"#, address);
+	let res = tester.io.handle_request_sync(&request).unwrap();
+	assert!(res.contains("Couldn't parse parameters: seconds"), "{}", res);
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_unlockAccountTimed", "params": ["0x{:x}", "password", "0x1"], "id": 1}}"#, address);
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
+	assert!(tester.accounts.is_unlocked(&address));
+
+	*now.lock() += Duration::from_secs(1);
+	assert!(tester.accounts.is_unlocked(&address));
+	*now.lock() += Duration::from_secs(1);
+	assert!(!tester.accounts.is_unlocked(&address));
+}
+
+#[test]
+fn should_bound_timed_unlock() {
+	let tester = setup();
+	tester.accounts.new_account(&"password".into()).unwrap();
+	let address = tester.accounts.accounts().unwrap()[0];
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_unlockAccountTimed", "params": ["0x{:x}", "password", "0x100000000"], "id": 1}}"#, address);
+	let res = tester.io.handle_request_sync(&request).unwrap();
+	assert!(res.contains("Unlock duration is too long."), "{}", res);
+	assert!(!tester.accounts.is_unlocked(&address));
+
+	let tester = setup_with_perm_unlock(accounts_provider(), false);
+	tester.accounts.new_account(&"password".into()).unwrap();
+	let address = tester.accounts.accounts().unwrap()[0];
+
+	let request = format!(r#"{{"jsonrpc": "2.0", "method": "parity_unlockAccountTimed", "params": ["0x{:x}", "password", "0x1"], "id": 1}}"#, address);
+	let res = tester.io.handle_request_sync(&request).unwrap();
+	assert!(res.contains("Time-unlocking is not supported"), "{}", res);
+	assert!(!tester.accounts.is_unlocked(&address));
+}
+
 #[test]
 fn should_be_able_to_kill_account() {
 	let tester = setup();
@@ -444,6 +526,26 @@ fn should_export_account() {
 	assert_eq!(result, Some(response.into()));
 }
 
+#[test]
+fn should_report_account_key_info() {
+	// given
+	let tester = setup();
+	let wallet = r#"{"id":"6a186c80-7797-cff2-bc2e-7c1d6a6cc76e","version":3,"crypto":{"cipher":"aes-128-ctr","cipherparams":{"iv":"a1c6ff99070f8032ca1c4e8add006373"},"ciphertext":"df27e3db64aa18d984b6439443f73660643c2d119a6f0fa2fa9a6456fc802d75","kdf":"pbkdf2","kdfparams":{"c":10240,"dklen":32,"prf":"hmac-sha256","salt":"ddc325335cda5567a1719313e73b4842511f3e4a837c9658eeb78e51ebe8c815"},"mac":"3dc888ae79cbb226ff9c455669f6cf2d79be72120f2298f6cb0d444fddc0aa3d"},"address":"0042e5d2a662eeaca8a7e828c174f98f35d8925b","name":"parity-export-test","meta":"{}"}"#;
+	tester.accounts.import_wallet(wallet.as_bytes(), &"parity-export-test".into(), false).unwrap();
+
+	// when
+	let request = r#"{"jsonrpc":"2.0","method":"parity_accountKeyInfo","params":["0x0042e5d2a662eeaca8a7e828c174f98f35d8925b"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"version":3,"kdf":"pbkdf2","iterations":10240,"cipher":"aes-128-ctr"},"id":1}"#;
+
+	// then
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
+
+	// unknown account
+	let request = r#"{"jsonrpc":"2.0","method":"parity_accountKeyInfo","params":["0x00000000000000000000000000000000000000ff"],"id":2}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32023,"message":"Could not fetch account key info.","data":"InvalidAccount"},"id":2}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
+}
+
 #[test]
 fn should_import_wallet() {
 	let tester = setup();
@@ -23,11 +23,11 @@ use ethcore::miner::FilterOptions;
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 use v1::types::{
-	Bytes, CallRequest,
+	Bytes, CallRequest, CallManyOptions, CallManyResult,
 	Peers, Transaction, RpcSettings, Histogram, RecoveredAccount,
-	TransactionStats, LocalTransactionStatus,
+	TransactionStats, LocalTransactionStatus, PoolStatus, GasPriceCalibration, CacheInfo,
 	BlockNumber, ConsensusCapability, VersionInfo,
-	OperationsInfo, ChainStatus, Log, Filter,
+	OperationsInfo, ChainStatus, SyncStateInfo, ForkSchedule, Log, Filter,
 	RichHeader, Receipt,
 };
 
@@ -156,6 +156,19 @@ pub trait Parity {
 	#[rpc(name = "parity_localTransactions")]
 	fn local_transactions(&self) -> Result<BTreeMap<H256, LocalTransactionStatus>>;
 
+	/// Returns transactions from the queue grouped by sender, along with recently rejected transactions.
+	#[rpc(name = "parity_poolStatus")]
+	fn pool_status(&self) -> Result<PoolStatus>;
+
+	/// Returns the last gas price calibration and the health of the price sources,
+	/// or `null` if the minimal gas price is not calibrated.
+	#[rpc(name = "parity_gasPriceCalibration")]
+	fn gas_price_calibration(&self) -> Result<Option<GasPriceCalibration>>;
+
+	/// Returns memory usage, limits and hit counts of each of the blockchain caches.
+	#[rpc(name = "parity_cacheInfo")]
+	fn cache_info(&self) -> Result<CacheInfo>;
+
 	/// Returns current WS Server interface and port or an error if ws server is disabled.
 	#[rpc(name = "parity_wsUrl")]
 	fn ws_url(&self) -> Result<String>;
@@ -192,6 +205,15 @@ pub trait Parity {
 	#[rpc(name = "parity_chainStatus")]
 	fn chain_status(&self) -> Result<ChainStatus>;
 
+	/// Get the current sync phase, including warp restoration progress, along with the
+	/// time each phase was entered.
+	#[rpc(name = "parity_syncState")]
+	fn sync_state(&self) -> Result<SyncStateInfo>;
+
+	/// Get activation blocks of all fork transitions and those active at the best block.
+	#[rpc(name = "parity_forkSchedule")]
+	fn fork_schedule(&self) -> Result<ForkSchedule>;
+
 	/// Get node kind info.
 	#[rpc(name = "parity_nodeKind")]
 	fn node_kind(&self) -> Result<::v1::types::NodeKind>;
@@ -211,6 +233,13 @@ pub trait Parity {
 	#[rpc(name = "parity_call")]
 	fn call(&self, _: Vec<CallRequest>, _: Option<BlockNumber>) -> Result<Vec<Bytes>>;
 
+	/// Execute calls one after another on top of the same temporary state, so that each call
+	/// sees the effects of the ones before it. Nothing is persisted. Returns the outcome of
+	/// every call; a call which fails doesn't prevent the following ones from running unless
+	/// `stopOnError` is set.
+	#[rpc(name = "parity_callMany")]
+	fn call_many(&self, _: Vec<CallRequest>, _: Option<BlockNumber>, _: Option<CallManyOptions>) -> Result<Vec<CallManyResult>>;
+
 	/// Used for submitting a proof-of-work solution (similar to `eth_submitWork`,
 	/// but returns block hash on success, and returns an explicit error message on failure).
 	#[rpc(name = "parity_submitWorkDetail")]
@@ -243,4 +272,15 @@ pub trait Parity {
 	/// Submit raw block to be published to the network
 	#[rpc(name = "parity_submitRawBlock")]
 	fn submit_raw_block(&self, _: Bytes) -> Result<H256>;
+
+	/// Returns the preimage of a hashed account address or storage key.
+	/// Only available if the node records preimages.
+	#[rpc(name = "parity_lookupPreimage")]
+	fn lookup_preimage(&self, _: H256) -> Result<Option<Bytes>>;
+
+	/// Returns hashes of up to the given number of canonical blocks in the (inclusive) range
+	/// authored by the given address, in ascending order. At most 1024 blocks can be requested.
+	/// Returns null if the node doesn't keep an author index.
+	#[rpc(name = "parity_blocksByAuthor")]
+	fn blocks_by_author(&self, _: H160, _: u64, _: u64, _: u64) -> Result<Option<Vec<H256>>>;
 }
@@ -19,10 +19,10 @@ use std::collections::BTreeMap;
 
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
-use ethereum_types::{H160, H256, H520};
+use ethereum_types::{H160, H256, H520, U64};
 use ethkey::Password;
 use ethstore::KeyFile;
-use v1::types::{DeriveHash, DeriveHierarchical, ExtAccountInfo};
+use v1::types::{DeriveHash, DeriveHierarchical, ExtAccountInfo, KeyFormatInfo};
 use v1::types::AccountInfo;
 
 /// Parity-specific read-only accounts rpc interface.
@@ -69,6 +69,11 @@ pub trait ParityAccounts {
 	#[rpc(name = "parity_changePassword")]
 	fn change_password(&self, _: H160, _: Password, _: Password) -> Result<bool>;
 
+	/// Unlocks an account for the given number of seconds.
+	/// Arguments: `account`, `password`, `seconds`.
+	#[rpc(name = "parity_unlockAccountTimed")]
+	fn unlock_account_timed(&self, _: H160, _: Password, _: U64) -> Result<bool>;
+
 	/// Permanently deletes an account.
 	/// Arguments: `account`, `password`.
 	#[rpc(name = "parity_killAccount")]
@@ -87,6 +92,11 @@ pub trait ParityAccounts {
 	#[rpc(name = "parity_setAccountMeta")]
 	fn set_account_meta(&self, _: H160, _: String) -> Result<bool>;
 
+	/// Set names and/or metadata strings of several accounts at once.
+	/// Arguments: list of `[address, name, meta]`, where `null` leaves the field unchanged.
+	#[rpc(name = "parity_setAccountsMetadata")]
+	fn set_accounts_metadata(&self, _: Vec<(H160, Option<String>, Option<String>)>) -> Result<bool>;
+
 	/// Imports a number of Geth accounts, with the list provided as the argument.
 	#[rpc(name = "parity_importGethAccounts")]
 	fn import_geth_accounts(&self, _: Vec<H160>) -> Result<Vec<H160>>;
@@ -146,6 +156,10 @@ pub trait ParityAccounts {
 	#[rpc(name = "parity_exportAccount")]
 	fn export_account(&self, _: H160, _: Password) -> Result<KeyFile>;
 
+	/// Returns key derivation function, its work factor and cipher of the account's key file.
+	#[rpc(name = "parity_accountKeyInfo")]
+	fn account_key_info(&self, _: H160) -> Result<KeyFormatInfo>;
+
 	/// Sign raw hash with the key corresponding to address and password.
 	#[rpc(name = "parity_signMessage")]
 	fn sign_message(&self, _: H160, _: Password, _: H256) -> Result<H520>;
@@ -20,7 +20,7 @@ use ethereum_types::H256;
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
 use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults,
-	TraceResultsWithTransactionHash, TraceOptions};
+	TraceResultsWithTransactionHash, TraceOptions, TransactionTraces};
 
 /// Traces specific rpc interface.
 #[rpc(server)]
@@ -37,8 +37,9 @@ pub trait Traces {
 	fn trace(&self, _: H256, _: Vec<Index>) -> Result<Option<LocalizedTrace>>;
 
 	/// Returns all traces of given transaction.
+	/// If `after` or `count` is given, returns only that page of the traces and their total number.
 	#[rpc(name = "trace_transaction")]
-	fn transaction_traces(&self, _: H256) -> Result<Option<Vec<LocalizedTrace>>>;
+	fn transaction_traces(&self, _: H256, _: Option<usize>, _: Option<usize>) -> Result<Option<TransactionTraces>>;
 
 	/// Returns all traces produced at given block.
 	#[rpc(name = "trace_block")]
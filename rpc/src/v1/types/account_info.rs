@@ -60,6 +60,19 @@ pub struct ExtAccountInfo {
 	pub uuid: Option<String>,
 }
 
+/// Key file format information (used by `parity_accountKeyInfo`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct KeyFormatInfo {
+	/// Key file version
+	pub version: u32,
+	/// Key derivation function (`pbkdf2` or `scrypt`)
+	pub kdf: String,
+	/// KDF work factor (round count for `pbkdf2`, `n` for `scrypt`)
+	pub iterations: u32,
+	/// Cipher used to encrypt the secret
+	pub cipher: String,
+}
+
 /// account derived from a signature
 /// as well as information that tells if it is valid for
 /// the current chain
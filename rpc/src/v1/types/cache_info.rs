@@ -0,0 +1,78 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Blockchain cache usage.
+
+use blockchain;
+
+/// Memory usage and hit counts of a single blockchain cache.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheUsage {
+	/// Bytes currently used by the cache.
+	pub used: usize,
+	/// Preferred cache size in bytes.
+	pub pref: usize,
+	/// Maximum cache size in bytes.
+	pub max: usize,
+	/// Number of reads served from the cache.
+	pub hits: usize,
+	/// Number of reads which had to go to the database.
+	pub misses: usize,
+}
+
+impl From<blockchain::CacheUsage> for CacheUsage {
+	fn from(usage: blockchain::CacheUsage) -> Self {
+		CacheUsage {
+			used: usage.used,
+			pref: usage.pref,
+			max: usage.max,
+			hits: usage.hits,
+			misses: usage.misses,
+		}
+	}
+}
+
+/// Usage of each of the blockchain caches.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheInfo {
+	/// Block headers cache.
+	pub block_headers: CacheUsage,
+	/// Block bodies cache.
+	pub block_bodies: CacheUsage,
+	/// Block details cache.
+	pub block_details: CacheUsage,
+	/// Block hashes cache.
+	pub block_hashes: CacheUsage,
+	/// Transaction addresses cache.
+	pub transaction_addresses: CacheUsage,
+	/// Block receipts cache.
+	pub block_receipts: CacheUsage,
+}
+
+impl From<blockchain::CacheReport> for CacheInfo {
+	fn from(report: blockchain::CacheReport) -> Self {
+		CacheInfo {
+			block_headers: report.block_headers.into(),
+			block_bodies: report.block_bodies.into(),
+			block_details: report.block_details.into(),
+			block_hashes: report.block_hashes.into(),
+			transaction_addresses: report.transaction_addresses.into(),
+			block_receipts: report.block_receipts.into(),
+		}
+	}
+}
@@ -0,0 +1,110 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Options and results of `parity_callMany`.
+
+use ethereum_types::U256;
+use machine::executed::Executed;
+
+use v1::types::{Bytes, TraceOptions};
+use super::trace::{StateDiff, Trace, VMTrace};
+
+/// Options of a bundle of calls.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct CallManyOptions {
+	/// Stop at the first call which fails or throws instead of executing the remaining ones.
+	#[serde(default)]
+	pub stop_on_error: bool,
+	/// Traces to produce for every call, as for `trace_call`.
+	#[serde(default)]
+	pub trace: TraceOptions,
+}
+
+/// Outcome of a single call of a bundle.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallManyResult {
+	/// The output of the call/create.
+	pub output: Bytes,
+	/// Gas used by the call.
+	pub gas_used: U256,
+	/// Why the call failed or threw.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// The transaction trace, if requested.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub trace: Vec<Trace>,
+	/// The VM trace, if requested.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub vm_trace: Option<VMTrace>,
+	/// The state diff, if requested.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub state_diff: Option<StateDiff>,
+}
+
+impl CallManyResult {
+	/// Result of a call which couldn't be executed at all.
+	pub fn failed(error: String) -> Self {
+		CallManyResult {
+			output: Bytes::default(),
+			gas_used: U256::zero(),
+			error: Some(error),
+			trace: Vec::new(),
+			vm_trace: None,
+			state_diff: None,
+		}
+	}
+}
+
+impl From<Executed> for CallManyResult {
+	fn from(t: Executed) -> Self {
+		CallManyResult {
+			output: t.output.into(),
+			gas_used: t.gas_used,
+			error: None,
+			trace: t.trace.into_iter().map(Into::into).collect(),
+			vm_trace: t.vm_trace.map(Into::into),
+			state_diff: t.state_diff.map(Into::into),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::{CallManyOptions, CallManyResult};
+
+	#[test]
+	fn call_many_options_deserialization() {
+		let s = r#"{"stopOnError":true,"trace":["trace","stateDiff"]}"#;
+		let deserialized: CallManyOptions = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, CallManyOptions {
+			stop_on_error: true,
+			trace: vec!["trace".into(), "stateDiff".into()],
+		});
+
+		let deserialized: CallManyOptions = serde_json::from_str("{}").unwrap();
+		assert_eq!(deserialized, CallManyOptions::default());
+	}
+
+	#[test]
+	fn call_many_result_serialization() {
+		let serialized = serde_json::to_string(&CallManyResult::failed("oops".into())).unwrap();
+		assert_eq!(serialized, r#"{"output":"0x","gasUsed":"0x0","error":"oops"}"#);
+	}
+}
@@ -0,0 +1,89 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fork activation schedule.
+
+use ethereum_types::U64;
+use types::{BlockNumber, engines::params::ForkActivation as EthForkActivation};
+
+/// Activation block of a single fork transition.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkActivation {
+	/// Name of the transition, as used in chain specs.
+	pub name: String,
+	/// First block where the transition is active, `None` if it's never activated.
+	pub block: Option<U64>,
+}
+
+impl From<EthForkActivation> for ForkActivation {
+	fn from(activation: EthForkActivation) -> Self {
+		ForkActivation {
+			name: activation.name.into(),
+			block: match activation.block {
+				block if block == BlockNumber::max_value() => None,
+				block => Some(block.into()),
+			},
+		}
+	}
+}
+
+/// Fork transitions of the chain together with those active at the best block.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkSchedule {
+	/// Best block number the active set was computed for.
+	pub best_block: U64,
+	/// All transitions of the chain spec.
+	pub transitions: Vec<ForkActivation>,
+	/// Names of transitions active at the best block.
+	pub active: Vec<String>,
+}
+
+impl ForkSchedule {
+	/// Build the schedule given all transitions and the best block number.
+	pub fn new(schedule: Vec<EthForkActivation>, best_block: BlockNumber) -> Self {
+		let active = schedule.iter()
+			.filter(|activation| activation.is_active(best_block))
+			.map(|activation| activation.name.into())
+			.collect();
+
+		ForkSchedule {
+			best_block: best_block.into(),
+			transitions: schedule.into_iter().map(Into::into).collect(),
+			active,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use types::engines::params::ForkActivation as EthForkActivation;
+	use super::ForkSchedule;
+
+	#[test]
+	fn should_serialize_fork_schedule() {
+		let schedule = ForkSchedule::new(vec![
+			EthForkActivation { name: "eip150Transition", block: 10 },
+			EthForkActivation { name: "eip155Transition", block: 20 },
+			EthForkActivation { name: "eip98Transition", block: u64::max_value() },
+		], 15);
+
+		let serialized = serde_json::to_string(&schedule).unwrap();
+		assert_eq!(serialized, r#"{"bestBlock":"0xf","transitions":[{"name":"eip150Transition","block":"0xa"},{"name":"eip155Transition","block":"0x14"},{"name":"eip98Transition","block":null}],"active":["eip150Transition"]}"#);
+	}
+}
@@ -0,0 +1,95 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Gas price calibration status.
+
+use std::time::SystemTime;
+
+use ethereum_types::U256;
+use miner::gas_pricer::{CalibrationInfo, CalibrationStatus, PriceSourceStatus};
+
+fn unix_seconds(time: SystemTime) -> u64 {
+	time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Last successful gas price calibration.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastCalibration {
+	/// Name of the price source used.
+	pub source: String,
+	/// Unix timestamp (in seconds) of the calibration.
+	pub timestamp: u64,
+	/// Fetched ETH price in USD.
+	pub ethusd: f32,
+	/// Resulting minimal gas price.
+	pub gas_price: U256,
+}
+
+impl From<CalibrationInfo> for LastCalibration {
+	fn from(info: CalibrationInfo) -> Self {
+		LastCalibration {
+			source: info.source,
+			timestamp: unix_seconds(info.timestamp),
+			ethusd: info.ethusd,
+			gas_price: info.gas_price,
+		}
+	}
+}
+
+/// Health of a price source.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceSourceHealth {
+	/// Name of the source.
+	pub name: String,
+	/// Number of failed requests since the last successful one.
+	pub consecutive_failures: u32,
+	/// Unix timestamp (in seconds) of the last successful request.
+	pub last_success: Option<u64>,
+	/// Last error returned by the source.
+	pub last_error: Option<String>,
+}
+
+impl From<PriceSourceStatus> for PriceSourceHealth {
+	fn from(status: PriceSourceStatus) -> Self {
+		PriceSourceHealth {
+			name: status.name,
+			consecutive_failures: status.consecutive_failures,
+			last_success: status.last_success.map(unix_seconds),
+			last_error: status.last_error,
+		}
+	}
+}
+
+/// Gas price calibration status: the last calibration and the health of every price source.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasPriceCalibration {
+	/// Last successful calibration, if any.
+	pub last_calibration: Option<LastCalibration>,
+	/// Price sources, in priority order.
+	pub sources: Vec<PriceSourceHealth>,
+}
+
+impl From<CalibrationStatus> for GasPriceCalibration {
+	fn from(status: CalibrationStatus) -> Self {
+		GasPriceCalibration {
+			last_calibration: status.last_calibration.map(Into::into),
+			sources: status.sources.into_iter().map(Into::into).collect(),
+		}
+	}
+}
@@ -23,15 +23,20 @@ mod account_info;
 mod block;
 mod block_number;
 mod bytes;
+mod cache_info;
+mod call_many;
 mod call_request;
 mod confirmations;
 mod consensus_status;
 mod derivation;
 mod filter;
+mod fork_schedule;
+mod gas_price_calibration;
 mod histogram;
 mod index;
 mod log;
 mod node_kind;
+mod pool_status;
 mod private_receipt;
 mod private_log;
 mod provenance;
@@ -50,10 +55,12 @@ mod eip191;
 pub mod pubsub;
 
 pub use self::eip191::{EIP191Version, PresignedTransaction};
-pub use self::account_info::{AccountInfo, ExtAccountInfo, EthAccount, StorageProof, RecoveredAccount};
+pub use self::account_info::{AccountInfo, ExtAccountInfo, EthAccount, StorageProof, RecoveredAccount, KeyFormatInfo};
 pub use self::bytes::Bytes;
+pub use self::cache_info::{CacheInfo, CacheUsage};
 pub use self::block::{RichBlock, Block, BlockTransactions, Header, RichHeader, Rich};
 pub use self::block_number::{BlockNumber, LightBlockNumber, block_number_to_id};
+pub use self::call_many::{CallManyOptions, CallManyResult};
 pub use self::call_request::CallRequest;
 pub use self::confirmations::{
 	ConfirmationPayload, ConfirmationRequest, ConfirmationResponse, ConfirmationResponseWithToken,
@@ -62,10 +69,13 @@ pub use self::confirmations::{
 pub use self::consensus_status::*;
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
 pub use self::filter::{Filter, FilterChanges};
+pub use self::fork_schedule::{ForkActivation, ForkSchedule};
+pub use self::gas_price_calibration::{GasPriceCalibration, LastCalibration, PriceSourceHealth};
 pub use self::histogram::Histogram;
 pub use self::index::Index;
 pub use self::log::Log;
 pub use self::node_kind::{NodeKind, Availability, Capability};
+pub use self::pool_status::{PoolStatus, SenderQueue, QueuedTransaction, RejectedTransaction};
 pub use self::private_receipt::{PrivateTransactionReceipt, PrivateTransactionReceiptAndTransaction};
 pub use self::private_log::PrivateTransactionLog;
 pub use self::provenance::Origin;
@@ -75,11 +85,12 @@ pub use self::secretstore::EncryptedDocumentKey;
 pub use self::sync::{
 	SyncStatus, SyncInfo, Peers, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
 	TransactionStats, ChainStatus, EthProtocolInfo, PipProtocolInfo,
+	SyncPhase, SyncStateInfo,
 };
-pub use self::trace::{LocalizedTrace, TraceResults, TraceResultsWithTransactionHash};
+pub use self::trace::{LocalizedTrace, TraceResults, TraceResultsWithTransactionHash, TransactionTraces};
 pub use self::trace_filter::TraceFilter;
 pub use self::transaction::{Transaction, RichRawTransaction, LocalTransactionStatus};
-pub use self::transaction_request::TransactionRequest;
+pub use self::transaction_request::{TransactionRequest, format_ether};
 pub use self::transaction_condition::TransactionCondition;
 pub use self::work::Work;
 
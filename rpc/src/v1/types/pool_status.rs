@@ -0,0 +1,97 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool status.
+
+use ethereum_types::{H160, H256, U256};
+use miner;
+use v1::helpers::errors;
+
+/// Transaction queued in the pool.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedTransaction {
+	/// Transaction hash.
+	pub hash: H256,
+	/// Transaction nonce.
+	pub nonce: U256,
+	/// Transaction gas price.
+	pub gas_price: U256,
+}
+
+/// Queued transactions of a single sender.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SenderQueue {
+	/// Transaction sender.
+	pub sender: H160,
+	/// Current state nonce of the sender.
+	pub nonce: U256,
+	/// Queued transactions, ordered by nonce.
+	pub transactions: Vec<QueuedTransaction>,
+	/// First nonce missing in the queue, if any.
+	pub first_gap: Option<U256>,
+}
+
+impl From<miner::pool::SenderStatus> for SenderQueue {
+	fn from(status: miner::pool::SenderStatus) -> Self {
+		SenderQueue {
+			sender: status.sender,
+			nonce: status.nonce,
+			transactions: status.transactions.iter().map(|tx| QueuedTransaction {
+				hash: tx.signed().hash(),
+				nonce: tx.signed().nonce,
+				gas_price: tx.signed().gas_price,
+			}).collect(),
+			first_gap: status.first_gap,
+		}
+	}
+}
+
+/// Transaction recently rejected by the pool.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedTransaction {
+	/// Transaction hash.
+	pub hash: H256,
+	/// Transaction sender, if it was recovered before the rejection.
+	pub sender: Option<H160>,
+	/// Reason of the rejection.
+	pub reason: String,
+	/// Unix timestamp (in seconds) of the rejection.
+	pub timestamp: u64,
+}
+
+impl From<miner::pool::RejectedTransaction> for RejectedTransaction {
+	fn from(rejected: miner::pool::RejectedTransaction) -> Self {
+		RejectedTransaction {
+			hash: rejected.hash,
+			sender: rejected.sender,
+			reason: errors::transaction_message(&rejected.reason),
+			timestamp: rejected.timestamp,
+		}
+	}
+}
+
+/// Transaction pool status: queued transactions grouped by sender and recent rejections.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolStatus {
+	/// Queued transactions grouped by sender.
+	pub senders: Vec<SenderQueue>,
+	/// Recently rejected transactions, oldest first.
+	pub rejected: Vec<RejectedTransaction>,
+}
@@ -46,6 +46,10 @@ pub struct Peers {
 	pub connected: usize,
 	/// Max number of peers
 	pub max: u32,
+	/// Number of connected peers per client name
+	pub clients: BTreeMap<String, usize>,
+	/// Number of peers rejected by the client id filter per client name
+	pub rejected: BTreeMap<String, usize>,
 	/// Detailed information on peers
 	pub peers: Vec<PeerInfo>,
 }
@@ -193,9 +197,74 @@ pub struct ChainStatus {
 	pub block_gap: Option<(U256, U256)>,
 }
 
+/// Phase the node's synchronization is currently in.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "name", rename_all = "camelCase")]
+pub enum SyncPhase {
+	/// Not syncing: the chain is up to date and complete.
+	Idle,
+	/// Looking for a snapshot manifest to warp to.
+	WarpManifest,
+	/// Restoring snapshot chunks.
+	#[serde(rename_all = "camelCase")]
+	WarpChunks {
+		/// Number of state chunks restored.
+		state_chunks_done: U256,
+		/// Total number of state chunks.
+		state_chunks: U256,
+		/// Number of block chunks restored.
+		block_chunks_done: U256,
+		/// Total number of block chunks.
+		block_chunks: U256,
+	},
+	/// All chunks are restored; the restored database is being put in place.
+	WarpFinalizing,
+	/// Downloading and importing new blocks.
+	#[serde(rename_all = "camelCase")]
+	FullSync {
+		/// Best block imported so far.
+		current_block: U256,
+		/// Highest block seen so far.
+		highest_block: U256,
+		/// Best block at the moment the sync started.
+		starting_block: U256,
+	},
+	/// Filling in the blocks preceding a warp-restored snapshot.
+	AncientBackfill {
+		/// Number of ancient blocks left to import.
+		remaining: U256,
+	},
+}
+
+impl SyncPhase {
+	/// Name of the phase, as serialized.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			SyncPhase::Idle => "idle",
+			SyncPhase::WarpManifest => "warpManifest",
+			SyncPhase::WarpChunks { .. } => "warpChunks",
+			SyncPhase::WarpFinalizing => "warpFinalizing",
+			SyncPhase::FullSync { .. } => "fullSync",
+			SyncPhase::AncientBackfill { .. } => "ancientBackfill",
+		}
+	}
+}
+
+/// Sync state (used by `parity_syncState`).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStateInfo {
+	/// Current phase.
+	pub phase: SyncPhase,
+	/// Block number the ongoing warp restoration is bringing the chain to.
+	pub warp_target: Option<U256>,
+	/// UNIX timestamp (in seconds) the node last entered each of the phases it went through.
+	pub phases_started: BTreeMap<String, u64>,
+}
+
 #[cfg(test)]
 mod tests {
-	use super::{SyncInfo, SyncStatus, Peers, TransactionStats, ChainStatus, H512};
+	use super::{SyncInfo, SyncStatus, Peers, TransactionStats, ChainStatus, SyncPhase, SyncStateInfo, H512};
 
 	#[test]
 	fn test_serialize_sync_info() {
@@ -222,6 +291,27 @@ mod tests {
 		assert_eq!(serialized, r#"{"startingBlock":"0x0","currentBlock":"0x0","highestBlock":"0x0","warpChunksAmount":null,"warpChunksProcessed":null}"#);
 	}
 
+	#[test]
+	fn test_serialize_sync_state() {
+		let mut t = SyncStateInfo {
+			phase: SyncPhase::WarpChunks {
+				state_chunks_done: 18.into(),
+				state_chunks: 40.into(),
+				block_chunks_done: 6.into(),
+				block_chunks: 10.into(),
+			},
+			warp_target: Some(5000.into()),
+			phases_started: Default::default(),
+		};
+		t.phases_started.insert("warpChunks".into(), 1_500_000_000);
+		let serialized = serde_json::to_string(&t).unwrap();
+		assert_eq!(serialized, r#"{"phase":{"name":"warpChunks","stateChunksDone":"0x12","stateChunks":"0x28","blockChunksDone":"0x6","blockChunks":"0xa"},"warpTarget":"0x1388","phasesStarted":{"warpChunks":1500000000}}"#);
+
+		let t = SyncStateInfo { phase: SyncPhase::Idle, warp_target: None, phases_started: Default::default() };
+		let serialized = serde_json::to_string(&t).unwrap();
+		assert_eq!(serialized, r#"{"phase":{"name":"idle"},"warpTarget":null,"phasesStarted":{}}"#);
+	}
+
 	#[test]
 	fn test_serialize_block_gap() {
 		let mut t = ChainStatus::default();
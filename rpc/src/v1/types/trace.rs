@@ -18,7 +18,7 @@ use std::collections::BTreeMap;
 
 use machine::executed::Executed;
 use trace as et;
-use trace::{FlatTrace, LocalizedTrace as EthLocalizedTrace, trace, TraceError};
+use trace::{FlatTrace, LocalizedTrace as EthLocalizedTrace, TracePage as EthTracePage, trace, TraceError};
 use ethereum_types::{H160, H256, U256};
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
@@ -556,6 +556,34 @@ impl From<EthLocalizedTrace> for LocalizedTrace {
 	}
 }
 
+/// Traces of a single transaction.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TransactionTraces {
+	/// All traces, returned if no page was requested.
+	All(Vec<LocalizedTrace>),
+	/// A single page of the traces.
+	Page(TracePage),
+}
+
+/// Page of the traces of a single transaction.
+#[derive(Debug, Serialize)]
+pub struct TracePage {
+	/// Traces on this page.
+	traces: Vec<LocalizedTrace>,
+	/// Number of traces of the transaction across all pages.
+	total: usize,
+}
+
+impl From<EthTracePage> for TracePage {
+	fn from(page: EthTracePage) -> Self {
+		TracePage {
+			traces: page.traces.into_iter().map(Into::into).collect(),
+			total: page.total,
+		}
+	}
+}
+
 /// Trace
 #[derive(Debug)]
 pub struct Trace {
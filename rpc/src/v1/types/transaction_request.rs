@@ -46,6 +46,7 @@ pub struct TransactionRequest {
 	pub condition: Option<TransactionCondition>,
 }
 
+/// Format an amount of wei as ether, without trailing zeros.
 pub fn format_ether(i: U256) -> String {
 	let mut string = format!("{}", i);
 	let idx = string.len() as isize - 18;
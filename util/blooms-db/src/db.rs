@@ -14,10 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{error, io, fmt};
+use std::{error, io, fmt, thread};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc;
 use ethbloom;
-use crate::file::{File, FileIterator};
+use crate::file::{File, FileIterator, is_legacy, write_migration, finish_migration};
+use crate::Pattern;
+
+/// Names of the database files, from the top level to the bottom one.
+const FILE_NAMES: [&str; 3] = ["top.bdb", "mid.bdb", "bot.bdb"];
 
 fn other_io_err<E>(e: E) -> io::Error where E: Into<Box<dyn error::Error + Send + Sync>> {
 	io::Error::new(io::ErrorKind::Other, e)
@@ -73,6 +78,24 @@ impl DatabaseFiles {
 		})
 	}
 
+	/// Open the blooms db files, legacy files are opened for reading without migrating them.
+	fn open_legacy(path: &Path) -> io::Result<DatabaseFiles> {
+		let open = |name: &str| -> io::Result<File> {
+			let path = path.join(name);
+			if is_legacy(&path)? {
+				File::open_legacy(path)
+			} else {
+				File::open(path)
+			}
+		};
+
+		Ok(DatabaseFiles {
+			top: open("top.bdb")?,
+			mid: open("mid.bdb")?,
+			bot: open("bot.bdb")?,
+		})
+	}
+
 	pub fn accrue_bloom(&mut self, pos: Positions, bloom: ethbloom::BloomRef) -> io::Result<()> {
 		self.top.accrue_bloom::<ethbloom::BloomRef>(pos.top, bloom)?;
 		self.mid.accrue_bloom::<ethbloom::BloomRef>(pos.mid, bloom)?;
@@ -94,6 +117,13 @@ impl DatabaseFiles {
 		self.bot.flush()?;
 		Ok(())
 	}
+
+	fn sync(&mut self) -> io::Result<()> {
+		self.top.sync()?;
+		self.mid.sync()?;
+		self.bot.sync()?;
+		Ok(())
+	}
 }
 
 impl Drop for DatabaseFiles {
@@ -103,12 +133,22 @@ impl Drop for DatabaseFiles {
 	}
 }
 
+/// Background migration of legacy database files.
+struct Migration {
+	/// Paths of the migrated files
+	paths: Vec<PathBuf>,
+	/// Receives the result once all files are rewritten
+	result: mpsc::Receiver<io::Result<()>>,
+}
+
 /// Blooms database.
 pub struct Database {
 	/// Database files
 	db_files: Option<DatabaseFiles>,
 	/// Database path
 	path: PathBuf,
+	/// Pending migration of legacy files
+	migration: Option<Migration>,
 }
 
 impl Database {
@@ -118,19 +158,101 @@ impl Database {
 		let database = Database {
 			db_files: Some(DatabaseFiles::open(&path)?),
 			path: path,
+			migration: None,
 		};
 
 		Ok(database)
 	}
 
+	/// Opens blooms database, migrating legacy files in a background thread.
+	///
+	/// Reads are served from the legacy files until the migration completes,
+	/// writes wait for the migration to complete.
+	pub fn open_with_migration<P>(path: P) -> io::Result<Database> where P: AsRef<Path> {
+		let path: PathBuf = path.as_ref().to_path_buf();
+		let mut legacy_paths = Vec::new();
+		for name in &FILE_NAMES {
+			let file_path = path.join(name);
+			if is_legacy(&file_path)? {
+				legacy_paths.push(file_path);
+			}
+		}
+
+		if legacy_paths.is_empty() {
+			return Database::open(path);
+		}
+
+		let db_files = DatabaseFiles::open_legacy(&path)?;
+		let (sender, receiver) = mpsc::channel();
+		let paths = legacy_paths.clone();
+		thread::Builder::new()
+			.name("blooms-migration".into())
+			.spawn(move || {
+				let result = paths.iter().try_for_each(|path| write_migration(path));
+				let _ = sender.send(result);
+			})?;
+
+		let database = Database {
+			db_files: Some(db_files),
+			path,
+			migration: Some(Migration {
+				paths: legacy_paths,
+				result: receiver,
+			}),
+		};
+
+		Ok(database)
+	}
+
+	/// Replaces legacy files with the migrated ones once the background migration is done.
+	///
+	/// Returns immediately if the migration is still running and `wait` is false.
+	fn complete_migration(&mut self, wait: bool) -> io::Result<()> {
+		let result = match self.migration {
+			None => return Ok(()),
+			Some(ref migration) if wait => migration.result.recv()
+				.unwrap_or_else(|_| Err(other_io_err("Blooms migration thread panicked"))),
+			Some(ref migration) => match migration.result.try_recv() {
+				Ok(result) => result,
+				Err(mpsc::TryRecvError::Empty) => return Ok(()),
+				Err(mpsc::TryRecvError::Disconnected) => Err(other_io_err("Blooms migration thread panicked")),
+			},
+		};
+
+		let migration = self.migration.take().expect("migration is some, checked above; qed");
+		let reopen = self.db_files.is_some();
+		// legacy files are closed before they are replaced, and stay closed if the migration failed.
+		// reopening the database migrates them synchronously.
+		self.db_files = None;
+		result?;
+
+		for path in &migration.paths {
+			finish_migration(path)?;
+		}
+
+		if reopen {
+			self.db_files = Some(DatabaseFiles::open(&self.path)?);
+		}
+		Ok(())
+	}
+
 	/// Close the inner-files
 	pub fn close(&mut self) -> io::Result<()> {
 		self.db_files = None;
 		Ok(())
 	}
 
+	/// Syncs the inner files to the disk, keeping them open.
+	pub fn flush(&mut self) -> io::Result<()> {
+		match self.db_files {
+			Some(ref mut db_files) => db_files.sync(),
+			None => Err(other_io_err("Database is closed")),
+		}
+	}
+
 	/// Reopens the database at the same location.
 	pub fn reopen(&mut self) -> io::Result<()> {
+		self.complete_migration(true)?;
 		self.db_files = Some(DatabaseFiles::open(&self.path)?);
 		Ok(())
 	}
@@ -138,6 +260,7 @@ impl Database {
 	/// Insert consecutive blooms into database starting at the given positon.
 	pub fn insert_blooms<'a, I, B>(&mut self, from: u64, blooms: I) -> io::Result<()>
 	where ethbloom::BloomRef<'a>: From<B>, I: Iterator<Item = B> {
+		self.complete_migration(true)?;
 		match self.db_files {
 			Some(ref mut db_files) => {
 				for (index, bloom) in (from..).into_iter().zip(blooms.map(Into::into)) {
@@ -155,9 +278,18 @@ impl Database {
 		}
 	}
 
+	/// Returns the highest index with a stored bloom, `None` if the database is empty.
+	pub fn tip(&self) -> io::Result<Option<u64>> {
+		match self.db_files {
+			Some(ref db_files) => Ok(db_files.bot.bloom_count().checked_sub(1)),
+			None => Err(other_io_err("Database is closed")),
+		}
+	}
+
 	/// Returns an iterator yielding all indexes containing given bloom.
 	pub fn iterate_matching<'a, 'b, B, I, II>(&'a mut self, from: u64, to: u64, blooms: II) -> io::Result<DatabaseIterator<'a, II>>
 	where ethbloom::BloomRef<'b>: From<B>, 'b: 'a, II: IntoIterator<Item = B, IntoIter = I> + Copy, I: Iterator<Item = B> {
+		self.complete_migration(false)?;
 		match self.db_files {
 			Some(ref mut db_files) => {
 				let index = from / 256 * 256;
@@ -180,6 +312,81 @@ impl Database {
 			None => Err(other_io_err("Database is closed")),
 		}
 	}
+
+	/// Returns indexes matching each of the given patterns, scanning the range only once.
+	pub fn filter_multi(&mut self, from: u64, to: u64, patterns: &[Pattern]) -> io::Result<Vec<Vec<u64>>> {
+		self.complete_migration(false)?;
+		let db_files = match self.db_files {
+			Some(ref mut db_files) => db_files,
+			None => return Err(other_io_err("Database is closed")),
+		};
+
+		let mut result = vec![Vec::new(); patterns.len()];
+		if patterns.is_empty() {
+			return Ok(result);
+		}
+
+		macro_rules! next_bloom {
+			($iter: expr) => {
+				match $iter.next() {
+					Some(bloom) => bloom?,
+					None => return Ok(result),
+				}
+			}
+		}
+
+		// indexes of the patterns contained in the bloom, out of the given candidates.
+		let matching = |bloom: ethbloom::Bloom, candidates: &[usize]| -> Vec<usize> {
+			candidates.iter()
+				.cloned()
+				.filter(|&i| contains_any(bloom, patterns[i].iter()))
+				.collect()
+		};
+
+		let all_patterns: Vec<usize> = (0..patterns.len()).collect();
+		let mut index = from / 256 * 256;
+		let mut files = db_files.iterator_from(Positions::from_index(index))?;
+
+		while index <= to {
+			let top_matches = matching(next_bloom!(files.top), &all_patterns);
+			if top_matches.is_empty() {
+				index += 256;
+				files.mid.advance(16)?;
+				files.bot.advance(256)?;
+				continue;
+			}
+
+			for _ in 0..16 {
+				if index > to {
+					break;
+				}
+
+				let mid = next_bloom!(files.mid);
+				let mid_matches = if index + 16 >= from { matching(mid, &top_matches) } else { Vec::new() };
+				if mid_matches.is_empty() {
+					index += 16;
+					files.bot.advance(16)?;
+					continue;
+				}
+
+				for _ in 0..16 {
+					if index > to {
+						break;
+					}
+
+					let bot = next_bloom!(files.bot);
+					if index >= from {
+						for i in matching(bot, &mid_matches) {
+							result[i].push(index);
+						}
+					}
+					index += 1;
+				}
+			}
+		}
+
+		Ok(result)
+	}
 }
 
 fn contains_any<'a, I, B>(bloom: ethbloom::Bloom, mut iterator: I) -> bool
@@ -293,9 +500,11 @@ where ethbloom::BloomRef<'b>: From<B>, 'b: 'a, II: IntoIterator<Item = B, IntoIt
 
 #[cfg(test)]
 mod tests {
+	use std::fs;
 	use ethbloom::Bloom;
 	use tempfile::TempDir;
-	use super::Database;
+	use crate::file::{is_legacy, HEADER_SIZE};
+	use super::{Database, FILE_NAMES};
 
 	#[test]
 	fn test_database() {
@@ -360,6 +569,56 @@ mod tests {
 		assert_eq!(matches, vec![256, 257]);
 	}
 
+	#[test]
+	fn test_tip() {
+		let tempdir = TempDir::new().unwrap();
+		let mut database = Database::open(tempdir.path()).unwrap();
+		assert_eq!(database.tip().unwrap(), None);
+
+		database.insert_blooms(254, vec![Bloom::from_low_u64_be(0x01); 4].iter()).unwrap();
+		assert_eq!(database.tip().unwrap(), Some(257));
+
+		// blooms inserted below the tip don't move it.
+		database.insert_blooms(10, Some(Bloom::from_low_u64_be(0x10)).iter()).unwrap();
+		assert_eq!(database.tip().unwrap(), Some(257));
+
+		database.reopen().unwrap();
+		assert_eq!(database.tip().unwrap(), Some(257));
+
+		database.close().unwrap();
+		assert!(database.tip().is_err());
+	}
+
+	#[test]
+	fn test_filter_multi() {
+		let tempdir = TempDir::new().unwrap();
+		let mut database = Database::open(tempdir.path()).unwrap();
+		database.insert_blooms(254, vec![
+			Bloom::from_low_u64_be(0x100),
+			Bloom::from_low_u64_be(0x01),
+			Bloom::from_low_u64_be(0x10),
+			Bloom::from_low_u64_be(0x11),
+		].iter()).unwrap();
+
+		let patterns = vec![
+			vec![Bloom::from_low_u64_be(0x01)],
+			vec![Bloom::from_low_u64_be(0x100), Bloom::from_low_u64_be(0x10)],
+		];
+
+		let matches = database.filter_multi(0, 257, &patterns).unwrap();
+		assert_eq!(matches, vec![vec![255, 257], vec![254, 256, 257]]);
+
+		for (pattern, pattern_matches) in patterns.iter().zip(&matches) {
+			let single = database.iterate_matching(0, 257, pattern).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+			assert_eq!(&single, pattern_matches);
+		}
+
+		let matches = database.filter_multi(255, 256, &patterns).unwrap();
+		assert_eq!(matches, vec![vec![255], vec![256]]);
+
+		assert_eq!(database.filter_multi(0, 257, &[]).unwrap(), Vec::<Vec<u64>>::new());
+	}
+
 	#[test]
 	fn test_db_close() {
 		let tempdir = TempDir::new().unwrap();
@@ -379,4 +638,68 @@ mod tests {
 		database.reopen().unwrap();
 		assert!(database.insert_blooms(254, blooms.iter()).is_ok());
 	}
+
+	#[test]
+	fn test_db_flush() {
+		let tempdir = TempDir::new().unwrap();
+		let blooms = vec![
+			Bloom::from_low_u64_be(0x100),
+			Bloom::from_low_u64_be(0x01),
+			Bloom::from_low_u64_be(0x10),
+			Bloom::from_low_u64_be(0x11),
+		];
+		let mut database = Database::open(tempdir.path()).unwrap();
+		database.insert_blooms(254, blooms.iter()).unwrap();
+		database.flush().unwrap();
+
+		// Flushed data is visible to other handles while the database stays open
+		let mut other = Database::open(tempdir.path()).unwrap();
+		let matches = other.iterate_matching(0, 257, Some(&Bloom::from_low_u64_be(0x01))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(matches, vec![255, 257]);
+
+		database.close().unwrap();
+		assert!(database.flush().is_err());
+
+		database.reopen().unwrap();
+		let matches = database.iterate_matching(0, 258, Some(&Bloom::from_low_u64_be(0x100))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(matches, vec![254]);
+	}
+
+	#[test]
+	fn test_open_with_migration() {
+		let tempdir = TempDir::new().unwrap();
+		{
+			let mut database = Database::open(tempdir.path()).unwrap();
+			database.insert_blooms(0, vec![
+				Bloom::from_low_u64_be(0),
+				Bloom::from_low_u64_be(0x01),
+				Bloom::from_low_u64_be(0x10),
+				Bloom::from_low_u64_be(0x11),
+			].iter()).unwrap();
+		}
+
+		// strip the headers to get files in the legacy format
+		for name in &FILE_NAMES {
+			let path = tempdir.path().join(name);
+			let contents = fs::read(&path).unwrap();
+			fs::write(&path, &contents[HEADER_SIZE as usize..]).unwrap();
+			assert!(is_legacy(&path).unwrap());
+		}
+
+		let mut database = Database::open_with_migration(tempdir.path()).unwrap();
+		let matches = database.iterate_matching(0, 3, Some(&Bloom::from_low_u64_be(0x01))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(matches, vec![1, 3]);
+
+		// writes wait for the migration to complete
+		database.insert_blooms(4, vec![Bloom::from_low_u64_be(0x100)].iter()).unwrap();
+		assert!(database.migration.is_none());
+		for name in &FILE_NAMES {
+			assert!(!is_legacy(&tempdir.path().join(name)).unwrap());
+		}
+
+		let matches = database.iterate_matching(0, 4, Some(&Bloom::from_low_u64_be(0x10))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(matches, vec![2, 3]);
+		let matches = database.iterate_matching(0, 4, Some(&Bloom::from_low_u64_be(0x100))).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(matches, vec![4]);
+	}
 }
@@ -14,22 +14,159 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::ffi::OsString;
 use std::io::{Seek, SeekFrom, Write, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{io, fs};
 use ethbloom;
 
+/// Magic bytes at the beginning of every blooms file.
+const MAGIC: [u8; 8] = *b"BLOOMSDB";
+/// Current version of the file format.
+const VERSION: u32 = 1;
+/// Size of a single bloom in bytes.
+const BLOOM_SIZE: u64 = 256;
+/// Size of the file header in bytes, blooms are stored right after it.
+pub const HEADER_SIZE: u64 = 32;
+
+/// Blooms file header.
+///
+/// Layout (little endian): magic (8 bytes), version (4 bytes), bloom size (4 bytes),
+/// creation timestamp in seconds (8 bytes), reserved (8 bytes).
+#[derive(Debug, Clone, PartialEq)]
+struct Header {
+	/// Version of the file format.
+	version: u32,
+	/// Size of a single bloom in bytes.
+	bloom_size: u32,
+	/// Unix timestamp of file creation.
+	created_at: u64,
+}
+
+impl Header {
+	fn new() -> Self {
+		Header {
+			version: VERSION,
+			bloom_size: BLOOM_SIZE as u32,
+			created_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+		}
+	}
+
+	fn to_bytes(&self) -> [u8; HEADER_SIZE as usize] {
+		let mut bytes = [0u8; HEADER_SIZE as usize];
+		bytes[0..8].copy_from_slice(&MAGIC);
+		bytes[8..12].copy_from_slice(&self.version.to_le_bytes());
+		bytes[12..16].copy_from_slice(&self.bloom_size.to_le_bytes());
+		bytes[16..24].copy_from_slice(&self.created_at.to_le_bytes());
+		bytes
+	}
+
+	/// Decodes the header, returns `None` if `bytes` don't start with the magic.
+	fn from_bytes(bytes: &[u8; HEADER_SIZE as usize]) -> Option<Self> {
+		if bytes[0..8] != MAGIC {
+			return None;
+		}
+
+		let mut version = [0u8; 4];
+		version.copy_from_slice(&bytes[8..12]);
+		let mut bloom_size = [0u8; 4];
+		bloom_size.copy_from_slice(&bytes[12..16]);
+		let mut created_at = [0u8; 8];
+		created_at.copy_from_slice(&bytes[16..24]);
+
+		Some(Header {
+			version: u32::from_le_bytes(version),
+			bloom_size: u32::from_le_bytes(bloom_size),
+			created_at: u64::from_le_bytes(created_at),
+		})
+	}
+}
+
+/// Path of the temporary file used while migrating the file at `path`.
+fn migration_path(path: &Path) -> PathBuf {
+	let mut temp: OsString = path.as_os_str().to_owned();
+	temp.push(".migrating");
+	temp.into()
+}
+
+/// Returns true if the file at `path` exists, is not empty and has no header.
+pub fn is_legacy(path: &Path) -> io::Result<bool> {
+	let mut file = match fs::File::open(path) {
+		Ok(file) => file,
+		Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+		Err(err) => return Err(err),
+	};
+
+	let mut magic = [0u8; 8];
+	match file.read_exact(&mut magic) {
+		Ok(_) => Ok(magic != MAGIC),
+		// files shorter than the magic can only be legacy files if they're not empty.
+		Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(file.metadata()?.len() > 0),
+		Err(err) => Err(err),
+	}
+}
+
+/// Removes the temporary file of an interrupted migration of the file at `path`, if any.
+fn remove_migration_file(path: &Path) -> io::Result<()> {
+	match fs::remove_file(migration_path(path)) {
+		Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+		result => result,
+	}
+}
+
+/// Writes contents of legacy headerless file at `path` into a temporary file with header.
+///
+/// The original file is left intact, `finish_migration` replaces it with the temporary file.
+pub fn write_migration(path: &Path) -> io::Result<()> {
+	remove_migration_file(path)?;
+
+	let mut source = fs::File::open(path)?;
+	let mut temp = fs::OpenOptions::new()
+		.write(true)
+		.create_new(true)
+		.open(migration_path(path))?;
+	temp.write_all(&Header::new().to_bytes())?;
+	io::copy(&mut source, &mut temp)?;
+	temp.sync_all()
+}
+
+/// Atomically replaces legacy file at `path` with the file written by `write_migration`.
+pub fn finish_migration(path: &Path) -> io::Result<()> {
+	fs::rename(migration_path(path), path)?;
+	// persist the rename, directories can't be opened on some platforms.
+	if let Some(dir) = path.parent().and_then(|dir| fs::File::open(dir).ok()) {
+		let _ = dir.sync_all();
+	}
+	Ok(())
+}
+
 /// Autoresizable file containing blooms.
 pub struct File {
 	/// Backing file.
 	file: fs::File,
 	/// Current file len.
 	len: u64,
+	/// Offset of the first bloom, zero for legacy files.
+	data_offset: u64,
 }
 
 impl File {
 	/// Opens database file. Creates new file if database file does not exist.
+	///
+	/// Legacy files without header are migrated in place.
 	pub fn open<P>(path: P) -> io::Result<File> where P: AsRef<Path> {
+		let path = path.as_ref();
+
+		// a temporary file is left behind only if a migration was interrupted before the rename,
+		// the original file is still intact in that case.
+		remove_migration_file(path)?;
+
+		if is_legacy(path)? {
+			write_migration(path)?;
+			finish_migration(path)?;
+		}
+
 		let file = fs::OpenOptions::new()
 			.read(true)
 			.write(true)
@@ -37,21 +174,58 @@ impl File {
 			// appending is done manually by calling `ensure_space_for_write`
 			.append(false)
 			.open(path)?;
+		let mut len = file.metadata()?.len();
+
+		if len == 0 {
+			let header = Header::new();
+			(&file).write_all(&header.to_bytes())?;
+			file.sync_all()?;
+			len = HEADER_SIZE;
+		} else {
+			let mut bytes = [0u8; HEADER_SIZE as usize];
+			(&file).read_exact(&mut bytes)?;
+			let header = Header::from_bytes(&bytes)
+				.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Invalid blooms file header"))?;
+			if header.version != VERSION || header.bloom_size as u64 != BLOOM_SIZE {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("Unsupported blooms file version {} with bloom size {}", header.version, header.bloom_size),
+				));
+			}
+		}
+
+		let file = File {
+			file,
+			len,
+			data_offset: HEADER_SIZE,
+		};
+
+		Ok(file)
+	}
+
+	/// Opens legacy headerless file for reading, without migrating it.
+	pub fn open_legacy<P>(path: P) -> io::Result<File> where P: AsRef<Path> {
+		let file = fs::File::open(path)?;
 		let len = file.metadata()?.len();
 
 		let file = File {
 			file,
 			len,
+			data_offset: 0,
 		};
 
 		Ok(file)
+	}
 
+	/// Offset of the bloom at given position.
+	fn offset(&self, pos: u64) -> u64 {
+		self.data_offset + pos * BLOOM_SIZE
 	}
 
 	/// Resizes the file if there is not enough space to write bloom at given position.
 	fn ensure_space_for_write(&mut self, pos: u64) -> io::Result<()> {
 		// position to write + 256 bytes
-		let required_space = (pos + 1) * 256;
+		let required_space = self.offset(pos + 1);
 		if required_space > self.len {
 			self.file.set_len(required_space)?;
 			self.len = required_space;
@@ -59,10 +233,15 @@ impl File {
 		Ok(())
 	}
 
+	/// Number of blooms stored in the file.
+	pub fn bloom_count(&self) -> u64 {
+		self.len.saturating_sub(self.data_offset) / BLOOM_SIZE
+	}
+
 	/// Read bloom at given position.
 	pub fn read_bloom(&self, pos: u64) -> io::Result<ethbloom::Bloom> {
 		let mut file_ref = &self.file;
-		file_ref.seek(SeekFrom::Start(pos * 256))?;
+		file_ref.seek(SeekFrom::Start(self.offset(pos)))?;
 		let mut bloom = ethbloom::Bloom::default();
 		file_ref.read_exact(bloom.as_bytes_mut())?;
 		Ok(bloom)
@@ -74,7 +253,7 @@ impl File {
 		let mut old_bloom: ethbloom::Bloom = self.read_bloom(pos)?;
 		old_bloom.accrue_bloom(bloom);
 		let mut file_ref = &self.file;
-		file_ref.seek(SeekFrom::Start(pos * 256))?;
+		file_ref.seek(SeekFrom::Start(self.offset(pos)))?;
 		file_ref.write_all(old_bloom.as_bytes())
 	}
 
@@ -82,7 +261,7 @@ impl File {
 	pub fn replace_bloom<'a, B>(&mut self, pos: u64, bloom: B) -> io::Result<()> where ethbloom::BloomRef<'a>: From<B> {
 		self.ensure_space_for_write(pos)?;
 		let mut file_ref = &self.file;
-		file_ref.seek(SeekFrom::Start(pos * 256))?;
+		file_ref.seek(SeekFrom::Start(self.offset(pos)))?;
 		file_ref.write_all(ethbloom::BloomRef::from(bloom).data())
 	}
 
@@ -91,7 +270,7 @@ impl File {
 	/// This function needs to be mutable `fs::File` is just a shared reference a system file handle.
 	/// https://users.rust-lang.org/t/how-to-handle-match-with-irrelevant-ok--/6291/15
 	pub fn iterator_from(&mut self, pos: u64) -> io::Result<FileIterator> {
-		let start = std::cmp::min(self.len, pos * 256);
+		let start = std::cmp::min(self.len, self.offset(pos));
 		let mut buf_reader = io::BufReader::new(&self.file);
 		buf_reader.seek(SeekFrom::Start(start))?;
 
@@ -106,6 +285,12 @@ impl File {
 	pub fn flush(&mut self) -> io::Result<()> {
 		self.file.flush()
 	}
+
+	/// Flush outstanding modifications and wait until they reach the disk.
+	pub fn sync(&mut self) -> io::Result<()> {
+		self.file.flush()?;
+		self.file.sync_all()
+	}
 }
 
 /// Iterator over blooms of a single file.
@@ -117,7 +302,7 @@ pub struct FileIterator<'a> {
 impl<'a> FileIterator<'a> {
 	/// Advance file by n blooms
 	pub fn advance(&mut self, n: u64) -> io::Result<()> {
-		self.file.seek(SeekFrom::Current(n as i64 * 256))?;
+		self.file.seek(SeekFrom::Current((n * BLOOM_SIZE) as i64))?;
 		Ok(())
 	}
 }
@@ -137,9 +322,24 @@ impl<'a> Iterator for FileIterator<'a> {
 
 #[cfg(test)]
 mod tests {
+	use std::fs;
+	use std::io::Write;
 	use ethbloom::Bloom;
 	use tempfile::TempDir;
-	use super::File;
+	use super::{File, Header, HEADER_SIZE, VERSION, migration_path};
+
+	fn read_header(path: &std::path::Path) -> Header {
+		let mut bytes = [0u8; HEADER_SIZE as usize];
+		bytes.copy_from_slice(&fs::read(path).unwrap()[..HEADER_SIZE as usize]);
+		Header::from_bytes(&bytes).unwrap()
+	}
+
+	fn write_legacy(path: &std::path::Path, blooms: &[Bloom]) {
+		let mut file = fs::File::create(path).unwrap();
+		for bloom in blooms {
+			file.write_all(bloom.as_bytes()).unwrap();
+		}
+	}
 
 	#[test]
 	fn test_file() {
@@ -150,4 +350,80 @@ mod tests {
 		assert_eq!(file.read_bloom(0).unwrap(), Bloom::from_low_u64_be(1));
 
 	}
+
+	#[test]
+	fn new_file_has_header() {
+		let tempdir = TempDir::new().unwrap();
+		let path = tempdir.path().join("file");
+		{
+			let mut file = File::open(&path).unwrap();
+			let header = read_header(&path);
+			assert_eq!(header.version, VERSION);
+			assert_eq!(header.bloom_size, 256);
+			file.replace_bloom(1, &Bloom::from_low_u64_be(2)).unwrap();
+			file.flush().unwrap();
+		}
+		assert_eq!(fs::metadata(&path).unwrap().len(), HEADER_SIZE + 2 * 256);
+
+		let mut file = File::open(&path).unwrap();
+		assert_eq!(file.read_bloom(0).unwrap(), Bloom::zero());
+		assert_eq!(file.read_bloom(1).unwrap(), Bloom::from_low_u64_be(2));
+		let blooms = file.iterator_from(0).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+		assert_eq!(blooms, vec![Bloom::zero(), Bloom::from_low_u64_be(2)]);
+	}
+
+	#[test]
+	fn legacy_file_is_migrated() {
+		let tempdir = TempDir::new().unwrap();
+		let path = tempdir.path().join("file");
+		let blooms = vec![Bloom::from_low_u64_be(1), Bloom::from_low_u64_be(2), Bloom::from_low_u64_be(3)];
+		write_legacy(&path, &blooms);
+
+		{
+			let mut file = File::open(&path).unwrap();
+			assert_eq!(read_header(&path).version, VERSION);
+			for (pos, bloom) in blooms.iter().enumerate() {
+				assert_eq!(&file.read_bloom(pos as u64).unwrap(), bloom);
+			}
+			let migrated = file.iterator_from(1).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+			assert_eq!(migrated, blooms[1..].to_vec());
+		}
+		assert_eq!(fs::metadata(&path).unwrap().len(), HEADER_SIZE + 3 * 256);
+		assert!(!migration_path(&path).exists());
+
+		// reopening a migrated file doesn't migrate it again
+		let file = File::open(&path).unwrap();
+		assert_eq!(fs::metadata(&path).unwrap().len(), HEADER_SIZE + 3 * 256);
+		assert_eq!(file.read_bloom(2).unwrap(), Bloom::from_low_u64_be(3));
+	}
+
+	#[test]
+	fn interrupted_migration_is_restarted() {
+		let tempdir = TempDir::new().unwrap();
+		let path = tempdir.path().join("file");
+		let blooms = vec![Bloom::from_low_u64_be(1), Bloom::from_low_u64_be(2)];
+		write_legacy(&path, &blooms);
+
+		// simulate a crash after the temporary file was partially written
+		let mut partial = Header::new().to_bytes().to_vec();
+		partial.extend_from_slice(&blooms[0].as_bytes()[..100]);
+		fs::write(migration_path(&path), &partial).unwrap();
+
+		let file = File::open(&path).unwrap();
+		assert!(!migration_path(&path).exists());
+		assert_eq!(file.read_bloom(0).unwrap(), blooms[0]);
+		assert_eq!(file.read_bloom(1).unwrap(), blooms[1]);
+		assert_eq!(fs::metadata(&path).unwrap().len(), HEADER_SIZE + 2 * 256);
+	}
+
+	#[test]
+	fn unsupported_version_is_rejected() {
+		let tempdir = TempDir::new().unwrap();
+		let path = tempdir.path().join("file");
+		let mut header = Header::new();
+		header.version = VERSION + 1;
+		fs::write(&path, &header.to_bytes()[..]).unwrap();
+
+		assert!(File::open(&path).is_err());
+	}
 }
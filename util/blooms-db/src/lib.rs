@@ -24,6 +24,9 @@ use std::path::Path;
 use ethbloom;
 use parking_lot::Mutex;
 
+/// A set of blooms, matched if any of them is contained in a stored bloom.
+pub type Pattern = Vec<ethbloom::Bloom>;
+
 /// Threadsafe API for blooms database.
 ///
 /// # Warning
@@ -47,16 +50,43 @@ impl Database {
 		Ok(result)
 	}
 
+	/// Creates new database handle, migrating legacy files in a background thread.
+	///
+	/// Legacy files serve reads until the migration completes.
+	///
+	/// # Arguments
+	///
+	/// * `path` - database directory
+	pub fn open_with_migration<P>(path: P) -> io::Result<Database> where P: AsRef<Path> {
+		let result = Database {
+			database: Mutex::new(db::Database::open_with_migration(path)?),
+		};
+
+		Ok(result)
+	}
+
 	/// Closes the inner database
 	pub fn close(&self) -> io::Result<()> {
 		self.database.lock().close()
 	}
 
+	/// Syncs the inner database to the disk without closing it.
+	pub fn flush(&self) -> io::Result<()> {
+		self.database.lock().flush()
+	}
+
 	/// Reopens database at the same location.
 	pub fn reopen(&self) -> io::Result<()> {
 		self.database.lock().reopen()
 	}
 
+	/// Returns the highest index with a stored bloom, or `None` if the database is empty.
+	///
+	/// Insertion can be resumed from the next index with `insert_blooms`.
+	pub fn tip(&self) -> io::Result<Option<u64>> {
+		self.database.lock().tip()
+	}
+
 	/// Inserts one or more blooms into database.
 	///
 	/// # Arguments
@@ -81,4 +111,17 @@ impl Database {
 			.iterate_matching(from, to, blooms)?
 			.collect::<Result<Vec<u64>, _>>()
 	}
+
+	/// Returns indexes of all headers matching each of the given patterns in a specified range.
+	///
+	/// The range is scanned once for all the patterns.
+	///
+	/// # Arguments
+	///
+	/// * `from` - index of the first bloom that needs to be checked
+	/// * `to` - index of the last bloom that needs to be checked (inclusive range)
+	/// * `patterns` - searched patterns, matches are returned in the same order
+	pub fn filter_multi(&self, from: u64, to: u64, patterns: &[Pattern]) -> io::Result<Vec<Vec<u64>>> {
+		self.database.lock().filter_multi(from, to, patterns)
+	}
 }
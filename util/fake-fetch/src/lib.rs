@@ -18,6 +18,8 @@ extern crate fetch;
 extern crate hyper;
 extern crate futures;
 
+use std::collections::HashMap;
+
 use hyper::{StatusCode, Body};
 use futures::{future, future::FutureResult};
 use fetch::{Fetch, Url, Request};
@@ -25,11 +27,18 @@ use fetch::{Fetch, Url, Request};
 #[derive(Clone, Default)]
 pub struct FakeFetch<T> where T: Clone + Send + Sync {
 	val: Option<T>,
+	responses: HashMap<String, String>,
 }
 
 impl<T> FakeFetch<T> where T: Clone + Send + Sync {
 	pub fn new(t: Option<T>) -> Self {
-		FakeFetch { val : t }
+		FakeFetch { val : t, responses: HashMap::new() }
+	}
+
+	/// Respond to requests for `url` with the given body.
+	pub fn with_response(mut self, url: &str, body: &str) -> Self {
+		self.responses.insert(url.to_owned(), body.to_owned());
+		self
 	}
 }
 
@@ -38,6 +47,11 @@ impl<T: 'static> Fetch for FakeFetch<T> where T: Clone + Send+ Sync {
 
 	fn fetch(&self, request: Request, abort: fetch::Abort) -> Self::Result {
 		let u = request.url().clone();
+		if let Some(body) = self.responses.get(u.as_str()) {
+			let r = hyper::Response::new(body.clone().into());
+			return future::ok(fetch::client::Response::new(u, r, abort));
+		}
+
 		future::ok(if self.val.is_some() {
 			let r = hyper::Response::new("Some content".into());
 			fetch::client::Response::new(u, r, abort)
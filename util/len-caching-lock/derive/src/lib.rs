@@ -0,0 +1,92 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `#[derive(Len)]` for [`len_caching_lock::Len`](../len_caching_lock/trait.Len.html).
+//!
+//! The length of the struct is the length of the single field marked with `#[len]`:
+//!
+//! ```rust
+//! use len_caching_lock::{Len, LenCachingMutex};
+//!
+//! #[derive(Default, Len)]
+//! struct Queue {
+//!     #[len]
+//!     items: Vec<u64>,
+//!     max_seen: u64,
+//! }
+//!
+//! let queue = LenCachingMutex::new(Queue::default());
+//! queue.lock().items.push(1);
+//! assert_eq!(queue.load_len(), 1);
+//! ```
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Index, Member};
+
+/// Implement `Len` by delegating to the field marked with `#[len]`.
+#[proc_macro_derive(Len, attributes(len))]
+pub fn derive_len(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	match impl_len(&input) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
+fn impl_len(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+	let fields = match input.data {
+		Data::Struct(ref data) => &data.fields,
+		_ => return Err(Error::new(Span::call_site(), "#[derive(Len)] is only supported on structs")),
+	};
+	let field = len_field(fields)?.ok_or_else(|| {
+		Error::new(Span::call_site(), "#[derive(Len)] requires a field marked with #[len]")
+	})?;
+
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	Ok(quote! {
+		impl #impl_generics ::len_caching_lock::Len for #name #ty_generics #where_clause {
+			fn len(&self) -> usize {
+				::len_caching_lock::Len::len(&self.#field)
+			}
+		}
+	})
+}
+
+fn len_field(fields: &Fields) -> Result<Option<Member>, Error> {
+	let mut found = None;
+	for (index, field) in fields.iter().enumerate() {
+		let attr = match field.attrs.iter().find(|attr| attr.path.is_ident("len")) {
+			Some(attr) => attr,
+			None => continue,
+		};
+		if !attr.tokens.is_empty() {
+			return Err(Error::new_spanned(attr, "#[len] does not take arguments"));
+		}
+		if found.is_some() {
+			return Err(Error::new_spanned(attr, "only one field can be marked with #[len]"));
+		}
+		found = Some(match field.ident {
+			Some(ref ident) => Member::Named(ident.clone()),
+			None => Member::Unnamed(Index::from(index)),
+		});
+	}
+	Ok(found)
+}
@@ -0,0 +1,77 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::collections::{HashMap, VecDeque};
+
+use len_caching_lock::{Len, LenCachingMutex, LenCachingRwLock};
+
+#[derive(Default, Len)]
+struct Pending {
+	#[len]
+	transactions: Vec<u64>,
+	total_gas: u64,
+}
+
+#[derive(Default, Len)]
+struct Peers {
+	best: Option<u32>,
+	#[len]
+	peers: HashMap<u32, String>,
+}
+
+#[derive(Default, Len)]
+struct Generic<T: Clone> {
+	#[len]
+	queue: VecDeque<T>,
+}
+
+#[derive(Default, Len)]
+struct Tuple(u8, #[len] Pending);
+
+#[test]
+fn delegates_to_vec_field() {
+	let pending = Pending { transactions: vec![1, 2, 3], total_gas: 100 };
+	assert_eq!(pending.len(), 3);
+	assert_eq!(pending.total_gas, 100);
+}
+
+#[test]
+fn delegates_to_map_field() {
+	let mut peers = Peers::default();
+	peers.peers.insert(1, "one".into());
+	peers.best = Some(1);
+	assert_eq!(peers.len(), 1);
+	assert_eq!(peers.best, Some(1));
+}
+
+#[test]
+fn delegates_to_generic_field() {
+	let lcl = LenCachingRwLock::new(Generic::<String>::default());
+	lcl.write().queue.push_back("first".into());
+	assert_eq!(lcl.load_len(), 1);
+}
+
+#[test]
+fn delegates_to_derived_tuple_field() {
+	let lcm = LenCachingMutex::new(Tuple::default());
+	{
+		let mut guard = lcm.lock();
+		guard.0 = 7;
+		guard.1.transactions.extend(&[1, 2]);
+	}
+	assert_eq!(lcm.load_len(), 2);
+	assert_eq!(lcm.lock().0, 7);
+}
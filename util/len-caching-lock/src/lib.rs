@@ -20,7 +20,15 @@
 //! and [`RwLock`](../lock_api/struct.RwLock.html) for most common use-cases.
 //!
 //! This crate implements `Len` for the following types: 
-//! `std::collections::{VecDeque, LinkedList, HashMap, BTreeMap, HashSet, BTreeSet, BinaryHeap}`
+//! `std::collections::{VecDeque, LinkedList, HashMap, BTreeMap, HashSet, BTreeSet, BinaryHeap}`,
+//! and `smallvec::SmallVec` with the `smallvec` feature enabled.
+//!
+//! With the `derive` feature enabled, `#[derive(Len)]` implements `Len` for a struct by
+//! delegating to the field marked with `#[len]`. The caching guards can be projected onto
+//! such a field with `map_len`, or onto any other part of `T` with `map`.
+//!
+//! [`SummaryCachingMutex`](summary/struct.SummaryCachingMutex.html) generalizes this to caching
+//! any summary of `T` computed by a closure, e.g. the total gas of pending transactions.
 //!
 //! ## Example
 //!
@@ -39,9 +47,14 @@ use std::hash::Hash;
 
 pub mod mutex;
 pub mod rwlock;
+pub mod summary;
 
 pub use mutex::LenCachingMutex;
 pub use rwlock::LenCachingRwLock;
+pub use summary::{SummaryCache, SummaryCachingMutex};
+
+#[cfg(feature = "derive")]
+pub use len_caching_lock_derive::Len;
 
 /// Implement to allow a type with a len() method to be used
 /// with [`LenCachingMutex`](mutex/struct.LenCachingMutex.html)
@@ -81,3 +94,8 @@ impl<T> Len for BTreeSet<T> {
 impl<T: Ord> Len for BinaryHeap<T> {
 	fn len(&self) -> usize { BinaryHeap::len(self) }
 }
+
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array> Len for smallvec::SmallVec<A> {
+	fn len(&self) -> usize { smallvec::SmallVec::len(self) }
+}
@@ -14,22 +14,34 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::fmt;
 use std::ops::{Deref, DerefMut};
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
-use parking_lot::{Mutex, MutexGuard};
+use parking_lot::{MappedMutexGuard, MutexGuard};
 
 use crate::Len;
+use crate::summary::{CachingSummaryGuard, SummaryCachingMutex};
 
 /// Can be used in place of a [`Mutex`](../../lock_api/struct.Mutex.html) where reading `T`'s `len()` without 
 /// needing to lock, is advantageous. 
 /// When the Guard is released, `T`'s `len()` will be cached.
 /// The cached `len()` may be at most 1 lock behind current state.
-#[derive(Debug)]
+///
+/// This is a [`SummaryCachingMutex`](../summary/struct.SummaryCachingMutex.html) summarizing `T`
+/// by its `len()`, cached in an `AtomicUsize`.
 pub struct LenCachingMutex<T: ?Sized> {
-	len: AtomicUsize,
-	data: Mutex<T>,
+	inner: SummaryCachingMutex<T, usize, fn(&T) -> usize, AtomicUsize>,
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for LenCachingMutex<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("LenCachingMutex")
+			.field("len", self.inner.cache())
+			.field("data", &self.inner.data())
+			.finish()
+	}
 }
 
 impl<T: Len + Default> Default for LenCachingMutex<T> {
@@ -47,9 +59,9 @@ impl<T: Len> From<T> for LenCachingMutex<T> {
 impl<T: Len> LenCachingMutex<T> {
 	/// Constructs a new LenCachingMutex
 	pub fn new(data: T) -> Self {
+		let len: fn(&T) -> usize = <T as Len>::len;
 		LenCachingMutex {
-			len: AtomicUsize::new(data.len()),
-			data: Mutex::new(data),
+			inner: SummaryCachingMutex::with_cache(data, len),
 		}
 	}
 }
@@ -57,64 +69,84 @@ impl<T: Len> LenCachingMutex<T> {
 impl<T: Len + ?Sized> LenCachingMutex<T> {
 	/// Load the cached value that was returned from your `T`'s `len()`
 	/// subsequent to the most recent lock being released.
+	///
+	/// The length is only cached when a guard is dropped, so while one is held
+	/// this returns the length from before the write, even if `T` was already modified.
 	pub fn load_len(&self) -> usize {
-		self.len.load(Ordering::SeqCst)
+		self.inner.cache().load(Ordering::SeqCst)
+	}
+
+	/// Same as [`load_len`](#method.load_len), but with `Relaxed` ordering.
+	/// Suitable for statistics, the value isn't ordered with any other memory access.
+	pub fn load_len_relaxed(&self) -> usize {
+		self.inner.cache().load(Ordering::Relaxed)
+	}
+
+	/// Same as [`load_len`](#method.load_len), but with `Acquire` ordering.
+	/// Writes made before the guard caching the length was dropped are visible after this returns.
+	pub fn load_len_acquire(&self) -> usize {
+		self.inner.cache().load(Ordering::Acquire)
 	}
 
 	/// Delegates to `parking_lot::Mutex`
 	/// [`lock()`](../../lock_api/struct.Mutex.html#method.lock).
 	pub fn lock(&self) -> CachingMutexGuard<T> {
-		CachingMutexGuard {
-			mutex_guard: self.data.lock(),
-			len: &self.len,
-		}
+		self.inner.lock()
 	}
 
 	/// Delegates to `parking_lot::Mutex`
 	/// [`try_lock()`](../../lock_api/struct.Mutex.html#method.try_lock).
 	pub fn try_lock(&self) -> Option<CachingMutexGuard<T>> {
-		Some(CachingMutexGuard {
-			mutex_guard: self.data.try_lock()?,
-			len: &self.len,
-		})
+		self.inner.try_lock()
 	}
 }
 
-/// Guard comprising `MutexGuard` and `AtomicUsize` for cache
-pub struct CachingMutexGuard<'a, T: Len + 'a + ?Sized> {
-	mutex_guard: MutexGuard<'a, T>,
-	len: &'a AtomicUsize,
-}
-
-impl<'a, T: Len + ?Sized> CachingMutexGuard<'a, T> {
-	/// Returns a mutable reference to the contained
-	/// [`MutexGuard`](../../parking_lot/mutex/type.MutexGuard.html)
-	pub fn inner_mut(&mut self) -> &mut MutexGuard<'a, T> {
-		&mut self.mutex_guard
+/// Guard comprising `MutexGuard` and `AtomicUsize` for cache.
+///
+/// Use [`map`](../summary/struct.CachingSummaryGuard.html#method.map) to project it onto a part
+/// of `T` which doesn't affect `T`'s `len()`, and [`map_len`](#method.map_len) to project it onto
+/// the collection itself.
+pub type CachingMutexGuard<'a, T> = CachingSummaryGuard<'a, T, usize, fn(&T) -> usize, AtomicUsize>;
+
+impl<'a, T: Len + ?Sized> CachingSummaryGuard<'a, T, usize, fn(&T) -> usize, AtomicUsize> {
+	/// Project the guard onto the collection which determines `T`'s `len()`,
+	/// e.g. the field marked with `#[len]` when deriving `Len`.
+	///
+	/// The returned guard caches the collection's `len()` when dropped, which must be the
+	/// same as `T`'s `len()` for the cache to stay accurate.
+	pub fn map_len<U: Len + ?Sized, F>(this: Self, f: F) -> MappedCachingMutexGuard<'a, U>
+		where F: FnOnce(&mut T) -> &mut U
+	{
+		let (mutex_guard, len, _) = Self::into_parts(this);
+		MappedCachingMutexGuard {
+			mutex_guard: MutexGuard::map(mutex_guard, f),
+			len,
+		}
 	}
+}
 
-	/// Returns a non-mutable reference to the contained
-	/// [`MutexGuard`](../../parking_lot/mutex/type.MutexGuard.html)
-	pub fn inner(&self) -> &MutexGuard<'a, T> {
-		&self.mutex_guard
-	}
+/// Guard projected onto the collection which determines the cached `len()`,
+/// see [`CachingMutexGuard::map_len`](struct.CachingMutexGuard.html#method.map_len).
+pub struct MappedCachingMutexGuard<'a, U: Len + 'a + ?Sized> {
+	mutex_guard: MappedMutexGuard<'a, U>,
+	len: &'a AtomicUsize,
 }
 
-impl<'a, T: Len + ?Sized> Drop for CachingMutexGuard<'a, T> {
+impl<'a, U: Len + ?Sized> Drop for MappedCachingMutexGuard<'a, U> {
 	fn drop(&mut self) {
 		self.len.store(self.mutex_guard.len(), Ordering::SeqCst);
 	}
 }
 
-impl<'a, T: Len + ?Sized> Deref for CachingMutexGuard<'a, T> {
-	type Target = T;
-	fn deref(&self)	-> &T {
+impl<'a, U: Len + ?Sized> Deref for MappedCachingMutexGuard<'a, U> {
+	type Target = U;
+	fn deref(&self)	-> &U {
 		self.mutex_guard.deref()
 	}
 }
 
-impl<'a, T: Len + ?Sized> DerefMut for CachingMutexGuard<'a, T> {
-	fn deref_mut(&mut self)	-> &mut T {
+impl<'a, U: Len + ?Sized> DerefMut for MappedCachingMutexGuard<'a, U> {
+	fn deref_mut(&mut self)	-> &mut U {
 		self.mutex_guard.deref_mut()
 	}
 }
@@ -133,6 +165,35 @@ mod tests {
 		assert_eq!(lcm.load_len(), 4);
 	}
 
+	#[cfg(feature = "smallvec")]
+	#[test]
+	fn caches_smallvec_len() {
+		let v: smallvec::SmallVec<[u8; 2]> = smallvec::smallvec![1, 2];
+		let lcm = LenCachingMutex::new(v);
+		assert_eq!(lcm.load_len(), 2);
+		// spills to the heap.
+		lcm.lock().push(3);
+		assert_eq!(lcm.load_len(), 3);
+		assert!(lcm.lock().spilled());
+		lcm.lock().clear();
+		assert_eq!(lcm.load_len(), 0);
+	}
+
+	#[test]
+	fn caches_len_on_guard_drop() {
+		let lcm = LenCachingMutex::new(vec![1, 2, 3]);
+		let mut guard = lcm.lock();
+		guard.push(4);
+		guard.push(5);
+		assert_eq!(lcm.load_len(), 3);
+		assert_eq!(lcm.load_len_relaxed(), 3);
+		assert_eq!(lcm.load_len_acquire(), 3);
+		drop(guard);
+		assert_eq!(lcm.load_len(), 5);
+		assert_eq!(lcm.load_len_relaxed(), 5);
+		assert_eq!(lcm.load_len_acquire(), 5);
+	}
+
 	#[test]
 	fn works_with_vec() {
 		let v: Vec<i32> = Vec::new();
@@ -147,4 +208,48 @@ mod tests {
 		lcm.lock().push_front(4);
 		assert_eq!(lcm.load_len(), 1);
 	}
+
+	struct Pending {
+		transactions: Vec<u64>,
+		total_gas: u64,
+	}
+
+	impl Len for Pending {
+		fn len(&self) -> usize { self.transactions.len() }
+	}
+
+	#[test]
+	fn mapped_guard_caches_len_when_mapping() {
+		let lcm = LenCachingMutex::new(Pending { transactions: vec![1], total_gas: 0 });
+		let mut guard = lcm.lock();
+		guard.transactions.push(2);
+		let mut total_gas = CachingMutexGuard::map(guard, |pending| &mut pending.total_gas);
+		assert_eq!(lcm.load_len(), 2);
+		*total_gas += 21_000;
+		drop(total_gas);
+		assert_eq!(lcm.lock().total_gas, 21_000);
+	}
+
+	#[test]
+	fn mapped_guard_does_not_track_len() {
+		let lcm = LenCachingMutex::new(Pending { transactions: vec![1], total_gas: 0 });
+		let mut transactions = CachingMutexGuard::map(lcm.lock(), |pending| &mut pending.transactions);
+		transactions.push(2);
+		drop(transactions);
+		// stale until the next lock is released.
+		assert_eq!(lcm.load_len(), 1);
+		drop(lcm.lock());
+		assert_eq!(lcm.load_len(), 2);
+	}
+
+	#[test]
+	fn mapped_len_guard_caches_len_on_drop() {
+		let lcm = LenCachingMutex::new(Pending { transactions: vec![1], total_gas: 0 });
+		let mut transactions = CachingMutexGuard::map_len(lcm.lock(), |pending| &mut pending.transactions);
+		transactions.push(2);
+		transactions.push(3);
+		assert_eq!(lcm.load_len(), 1);
+		drop(transactions);
+		assert_eq!(lcm.load_len(), 3);
+	}
 }
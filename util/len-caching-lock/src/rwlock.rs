@@ -14,11 +14,13 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::mem::ManuallyDrop;
 use std::ops::{Deref, DerefMut};
+use std::ptr;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
-use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use parking_lot::{MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use crate::Len;
 
@@ -56,10 +58,25 @@ impl<T: Len> LenCachingRwLock<T> {
 impl<T: Len + ?Sized> LenCachingRwLock<T> {
 	/// Load the cached value that was returned from your `T`'s `len()`
 	/// subsequent to the most recent lock being released.
+	///
+	/// The length is only cached when a write guard is dropped, so while one is held
+	/// this returns the length from before the write, even if `T` was already modified.
 	pub fn load_len(&self) -> usize {
 		self.len.load(Ordering::SeqCst)
 	}
 
+	/// Same as [`load_len`](#method.load_len), but with `Relaxed` ordering.
+	/// Suitable for statistics, the value isn't ordered with any other memory access.
+	pub fn load_len_relaxed(&self) -> usize {
+		self.len.load(Ordering::Relaxed)
+	}
+
+	/// Same as [`load_len`](#method.load_len), but with `Acquire` ordering.
+	/// Writes made before the write guard caching the length was dropped are visible after this returns.
+	pub fn load_len_acquire(&self) -> usize {
+		self.len.load(Ordering::Acquire)
+	}
+
 	/// Delegates to `parking_lot::RwLock`
 	/// [`write()`](../../lock_api/struct.RwLock.html#method.write).
 	pub fn write(&self) -> CachingRwLockWriteGuard<T> {
@@ -109,6 +126,41 @@ impl<'a, T: Len + ?Sized> CachingRwLockWriteGuard<'a, T> {
 	pub fn inner(&self) -> &RwLockWriteGuard<'a, T> {
 		&self.write_guard
 	}
+
+	/// Project the guard onto a part of `T` which doesn't affect `T`'s `len()`, e.g. a
+	/// bookkeeping field next to the collection.
+	///
+	/// `T`'s `len()` is cached when mapping and the returned guard doesn't update the cache,
+	/// so changes to the length made through it are only seen after the next write guard is released.
+	/// Use [`map_len`](#method.map_len) to project onto the collection itself.
+	pub fn map<U: ?Sized, F>(this: Self, f: F) -> MappedRwLockWriteGuard<'a, U>
+		where F: FnOnce(&mut T) -> &mut U
+	{
+		let (write_guard, len) = Self::into_parts(this);
+		len.store(write_guard.len(), Ordering::SeqCst);
+		RwLockWriteGuard::map(write_guard, f)
+	}
+
+	/// Project the guard onto the collection which determines `T`'s `len()`,
+	/// e.g. the field marked with `#[len]` when deriving `Len`.
+	///
+	/// The returned guard caches the collection's `len()` when dropped, which must be the
+	/// same as `T`'s `len()` for the cache to stay accurate.
+	pub fn map_len<U: Len + ?Sized, F>(this: Self, f: F) -> MappedCachingRwLockWriteGuard<'a, U>
+		where F: FnOnce(&mut T) -> &mut U
+	{
+		let (write_guard, len) = Self::into_parts(this);
+		MappedCachingRwLockWriteGuard {
+			write_guard: RwLockWriteGuard::map(write_guard, f),
+			len,
+		}
+	}
+
+	fn into_parts(this: Self) -> (RwLockWriteGuard<'a, T>, &'a AtomicUsize) {
+		let this = ManuallyDrop::new(this);
+		// SAFETY: `this` is never dropped, so the guard is moved out exactly once.
+		(unsafe { ptr::read(&this.write_guard) }, this.len)
+	}
 }
 
 impl<'a, T: Len + ?Sized> Drop for CachingRwLockWriteGuard<'a, T> {
@@ -130,6 +182,32 @@ impl<'a, T: Len + ?Sized> DerefMut for CachingRwLockWriteGuard<'a, T> {
 	}
 }
 
+/// Write guard projected onto the collection which determines the cached `len()`,
+/// see [`CachingRwLockWriteGuard::map_len`](struct.CachingRwLockWriteGuard.html#method.map_len).
+pub struct MappedCachingRwLockWriteGuard<'a, U: Len + 'a + ?Sized> {
+	write_guard: MappedRwLockWriteGuard<'a, U>,
+	len: &'a AtomicUsize,
+}
+
+impl<'a, U: Len + ?Sized> Drop for MappedCachingRwLockWriteGuard<'a, U> {
+	fn drop(&mut self) {
+		self.len.store(self.write_guard.len(), Ordering::SeqCst);
+	}
+}
+
+impl<'a, U: Len + ?Sized> Deref for MappedCachingRwLockWriteGuard<'a, U> {
+	type Target = U;
+	fn deref(&self)	-> &U {
+		self.write_guard.deref()
+	}
+}
+
+impl<'a, U: Len + ?Sized> DerefMut for MappedCachingRwLockWriteGuard<'a, U> {
+	fn deref_mut(&mut self)	-> &mut U {
+		self.write_guard.deref_mut()
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -144,6 +222,21 @@ mod tests {
 		assert_eq!(lcl.load_len(), 4);
 	}
 
+	#[test]
+	fn caches_len_on_guard_drop() {
+		let lcl = LenCachingRwLock::new(vec![1, 2, 3]);
+		let mut guard = lcl.write();
+		guard.push(4);
+		guard.push(5);
+		assert_eq!(lcl.load_len(), 3);
+		assert_eq!(lcl.load_len_relaxed(), 3);
+		assert_eq!(lcl.load_len_acquire(), 3);
+		drop(guard);
+		assert_eq!(lcl.load_len(), 5);
+		assert_eq!(lcl.load_len_relaxed(), 5);
+		assert_eq!(lcl.load_len_acquire(), 5);
+	}
+
 	#[test]
 	fn works_with_vec() {
 		let v: Vec<i32> = Vec::new();
@@ -165,4 +258,35 @@ mod tests {
 		let lcl = LenCachingRwLock::new(v);
 		assert_eq!(lcl.read().len(), 3);
 	}
+
+	struct Peers {
+		peers: Vec<u32>,
+		best: Option<u32>,
+	}
+
+	impl Len for Peers {
+		fn len(&self) -> usize { self.peers.len() }
+	}
+
+	#[test]
+	fn mapped_guard_caches_len_when_mapping() {
+		let lcl = LenCachingRwLock::new(Peers { peers: vec![1], best: None });
+		let mut guard = lcl.write();
+		guard.peers.push(2);
+		let mut best = CachingRwLockWriteGuard::map(guard, |peers| &mut peers.best);
+		assert_eq!(lcl.load_len(), 2);
+		*best = Some(2);
+		drop(best);
+		assert_eq!(lcl.read().best, Some(2));
+	}
+
+	#[test]
+	fn mapped_len_guard_caches_len_on_drop() {
+		let lcl = LenCachingRwLock::new(Peers { peers: vec![1], best: None });
+		let mut peers = CachingRwLockWriteGuard::map_len(lcl.write(), |peers| &mut peers.peers);
+		peers.clear();
+		assert_eq!(lcl.load_len(), 1);
+		drop(peers);
+		assert_eq!(lcl.load_len(), 0);
+	}
 }
@@ -0,0 +1,216 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use parking_lot::{MappedMutexGuard, Mutex, MutexGuard, RwLock};
+
+/// Storage for the summary cached by a [`SummaryCachingMutex`](struct.SummaryCachingMutex.html).
+pub trait SummaryCache<S> {
+	/// Create the cache holding `summary`.
+	fn new(summary: S) -> Self;
+
+	/// Replace the cached summary.
+	fn store(&self, summary: S);
+}
+
+impl<S> SummaryCache<S> for RwLock<S> {
+	fn new(summary: S) -> Self {
+		RwLock::new(summary)
+	}
+
+	fn store(&self, summary: S) {
+		*self.write() = summary;
+	}
+}
+
+impl SummaryCache<usize> for AtomicUsize {
+	fn new(summary: usize) -> Self {
+		AtomicUsize::new(summary)
+	}
+
+	fn store(&self, summary: usize) {
+		AtomicUsize::store(self, summary, Ordering::SeqCst);
+	}
+}
+
+/// Can be used in place of a [`Mutex`](../../lock_api/struct.Mutex.html) where reading a summary
+/// of `T`, computed by a user-provided closure, without locking `T` is advantageous.
+/// When the Guard is released, the summary is computed and cached in `C`.
+///
+/// [`LenCachingMutex`](../mutex/struct.LenCachingMutex.html) is the special case where the summary
+/// is `T`'s `len()`, kept in an atomic so it can be read without any lock at all.
+pub struct SummaryCachingMutex<T: ?Sized, S, F = fn(&T) -> S, C = RwLock<S>> {
+	summary: C,
+	summarize: F,
+	_summary: PhantomData<fn() -> S>,
+	data: Mutex<T>,
+}
+
+impl<T, S, F: Fn(&T) -> S> SummaryCachingMutex<T, S, F> {
+	/// Constructs a new SummaryCachingMutex, summarizing `data` with `summarize`.
+	pub fn new(data: T, summarize: F) -> Self {
+		SummaryCachingMutex::with_cache(data, summarize)
+	}
+}
+
+impl<T, S, F: Fn(&T) -> S, C: SummaryCache<S>> SummaryCachingMutex<T, S, F, C> {
+	/// Constructs a new SummaryCachingMutex, summarizing `data` with `summarize`
+	/// and caching the summary in `C`.
+	pub fn with_cache(data: T, summarize: F) -> Self {
+		SummaryCachingMutex {
+			summary: C::new(summarize(&data)),
+			summarize,
+			_summary: PhantomData,
+			data: Mutex::new(data),
+		}
+	}
+}
+
+impl<T: ?Sized, S, F: Fn(&T) -> S, C: SummaryCache<S>> SummaryCachingMutex<T, S, F, C> {
+	/// Delegates to `parking_lot::Mutex`
+	/// [`lock()`](../../lock_api/struct.Mutex.html#method.lock).
+	pub fn lock(&self) -> CachingSummaryGuard<T, S, F, C> {
+		CachingSummaryGuard {
+			mutex_guard: self.data.lock(),
+			summary: &self.summary,
+			summarize: &self.summarize,
+			_summary: PhantomData,
+		}
+	}
+
+	/// Delegates to `parking_lot::Mutex`
+	/// [`try_lock()`](../../lock_api/struct.Mutex.html#method.try_lock).
+	pub fn try_lock(&self) -> Option<CachingSummaryGuard<T, S, F, C>> {
+		Some(CachingSummaryGuard {
+			mutex_guard: self.data.try_lock()?,
+			summary: &self.summary,
+			summarize: &self.summarize,
+			_summary: PhantomData,
+		})
+	}
+}
+
+impl<T: ?Sized, S, F, C> SummaryCachingMutex<T, S, F, C> {
+	/// The cache holding the summary computed when the most recent lock was released.
+	pub fn cache(&self) -> &C {
+		&self.summary
+	}
+
+	pub(crate) fn data(&self) -> &Mutex<T> {
+		&self.data
+	}
+}
+
+impl<T: ?Sized, S: Clone, F> SummaryCachingMutex<T, S, F, RwLock<S>> {
+	/// Load the cached summary of `T` computed when the most recent lock was released.
+	///
+	/// The summary is only cached when a guard is dropped, so while one is held
+	/// this returns the summary from before the write.
+	pub fn load_summary(&self) -> S {
+		self.summary.read().clone()
+	}
+}
+
+/// Guard comprising `MutexGuard` and the summary cache
+pub struct CachingSummaryGuard<'a, T: 'a + ?Sized, S, F: 'a + Fn(&T) -> S, C: 'a + SummaryCache<S> = RwLock<S>> {
+	mutex_guard: MutexGuard<'a, T>,
+	summary: &'a C,
+	summarize: &'a F,
+	_summary: PhantomData<fn() -> S>,
+}
+
+impl<'a, T: ?Sized, S, F: Fn(&T) -> S, C: SummaryCache<S>> CachingSummaryGuard<'a, T, S, F, C> {
+	/// Returns a mutable reference to the contained
+	/// [`MutexGuard`](../../parking_lot/mutex/type.MutexGuard.html)
+	pub fn inner_mut(&mut self) -> &mut MutexGuard<'a, T> {
+		&mut self.mutex_guard
+	}
+
+	/// Returns a non-mutable reference to the contained
+	/// [`MutexGuard`](../../parking_lot/mutex/type.MutexGuard.html)
+	pub fn inner(&self) -> &MutexGuard<'a, T> {
+		&self.mutex_guard
+	}
+
+	/// Project the guard onto a part of `T` which doesn't affect the summary.
+	///
+	/// The summary is cached when mapping and the returned guard doesn't update the cache,
+	/// so changes made through it are only summarized after the next lock is released.
+	pub fn map<U: ?Sized, M>(this: Self, f: M) -> MappedMutexGuard<'a, U>
+		where M: FnOnce(&mut T) -> &mut U
+	{
+		let (mutex_guard, summary, summarize) = Self::into_parts(this);
+		summary.store(summarize(&mutex_guard));
+		MutexGuard::map(mutex_guard, f)
+	}
+
+	pub(crate) fn into_parts(this: Self) -> (MutexGuard<'a, T>, &'a C, &'a F) {
+		let this = ManuallyDrop::new(this);
+		// SAFETY: `this` is never dropped, so the guard is moved out exactly once.
+		(unsafe { ptr::read(&this.mutex_guard) }, this.summary, this.summarize)
+	}
+}
+
+impl<'a, T: ?Sized, S, F: Fn(&T) -> S, C: SummaryCache<S>> Drop for CachingSummaryGuard<'a, T, S, F, C> {
+	fn drop(&mut self) {
+		self.summary.store((self.summarize)(&self.mutex_guard));
+	}
+}
+
+impl<'a, T: ?Sized, S, F: Fn(&T) -> S, C: SummaryCache<S>> Deref for CachingSummaryGuard<'a, T, S, F, C> {
+	type Target = T;
+	fn deref(&self)	-> &T {
+		self.mutex_guard.deref()
+	}
+}
+
+impl<'a, T: ?Sized, S, F: Fn(&T) -> S, C: SummaryCache<S>> DerefMut for CachingSummaryGuard<'a, T, S, F, C> {
+	fn deref_mut(&mut self)	-> &mut T {
+		self.mutex_guard.deref_mut()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn caches_summary() {
+		let scm = SummaryCachingMutex::new(vec![1u32, 2, 3], |v: &Vec<u32>| v.iter().map(|x| *x as u64).sum::<u64>());
+		assert_eq!(scm.load_summary(), 6);
+		scm.lock().push(4);
+		assert_eq!(scm.load_summary(), 10);
+
+		let mut guard = scm.lock();
+		guard.clear();
+		assert_eq!(scm.load_summary(), 10);
+		drop(guard);
+		assert_eq!(scm.load_summary(), 0);
+	}
+
+	#[test]
+	fn works_with_fn_pointers() {
+		fn total(v: &Vec<u32>) -> u32 { v.iter().sum() }
+		let scm: SummaryCachingMutex<Vec<u32>, u32> = SummaryCachingMutex::new(vec![5, 5], total);
+		scm.try_lock().unwrap().push(5);
+		assert_eq!(scm.load_summary(), 15);
+	}
+}
@@ -19,7 +19,8 @@
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::{fs, io, error};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::{cmp, error, fmt, fs, io, mem};
 
 use log::{info, trace, warn};
 use kvdb::DBTransaction;
@@ -29,6 +30,36 @@ fn other_io_err<E>(e: E) -> io::Error where E: Into<Box<dyn error::Error + Send
 	io::Error::new(io::ErrorKind::Other, e)
 }
 
+/// Errors specific to migrations, reported wrapped into `io::Error`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MigrationError {
+	/// Migration was cancelled through the cancellation flag.
+	Cancelled,
+}
+
+impl fmt::Display for MigrationError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			MigrationError::Cancelled => write!(f, "Migration cancelled"),
+		}
+	}
+}
+
+impl error::Error for MigrationError {}
+
+impl MigrationError {
+	/// Returns the migration error wrapped into `err`, if any.
+	pub fn from_io(err: &io::Error) -> Option<MigrationError> {
+		err.get_ref()
+			.and_then(|inner| inner.downcast_ref::<MigrationError>())
+			.cloned()
+	}
+}
+
+fn cancelled_err() -> io::Error {
+	io::Error::new(io::ErrorKind::Interrupted, MigrationError::Cancelled)
+}
+
 /// Migration config.
 #[derive(Clone)]
 pub struct Config {
@@ -36,6 +67,14 @@ pub struct Config {
 	pub batch_size: usize,
 	/// Database compaction profile.
 	pub compaction_profile: CompactionProfile,
+	/// Maximum number of files kept open by migrated databases.
+	pub max_open_files: i32,
+	/// Log peak memory held by pending batches for every migrated column.
+	pub log_memory_usage: bool,
+	/// Migration is aborted with `MigrationError::Cancelled` once this flag is set.
+	pub cancel: Option<Arc<AtomicBool>>,
+	/// Called once the migration starts changing the source database and stops checking `cancel`.
+	pub on_uncancellable: Option<Arc<dyn Fn() + Send + Sync>>,
 }
 
 impl Default for Config {
@@ -43,6 +82,39 @@ impl Default for Config {
 		Config {
 			batch_size: 1024,
 			compaction_profile: Default::default(),
+			max_open_files: 64,
+			log_memory_usage: false,
+			cancel: None,
+			on_uncancellable: None,
+		}
+	}
+}
+
+impl Config {
+	/// Returns true if the migration has been cancelled.
+	pub fn is_cancelled(&self) -> bool {
+		self.cancel.as_ref().map_or(false, |cancel| cancel.load(Ordering::SeqCst))
+	}
+
+	/// Stops checking the cancellation flag, calling `on_uncancellable` if the flag was set.
+	/// Returns false if the migration wasn't cancellable.
+	fn stop_cancellation(&mut self) -> bool {
+		if self.cancel.take().is_none() {
+			return false;
+		}
+		if let Some(ref on_uncancellable) = self.on_uncancellable {
+			on_uncancellable();
+		}
+		true
+	}
+
+	/// Configuration of databases opened during migration.
+	pub fn database_config(&self, columns: u32) -> DatabaseConfig {
+		DatabaseConfig {
+			max_open_files: self.max_open_files,
+			compaction: self.compaction_profile,
+			columns,
+			..Default::default()
 		}
 	}
 }
@@ -52,6 +124,9 @@ pub struct Batch {
 	inner: BTreeMap<Vec<u8>, Vec<u8>>,
 	batch_size: usize,
 	column: u32,
+	pending_bytes: usize,
+	peak_pending_bytes: usize,
+	cancel: Option<Arc<AtomicBool>>,
 }
 
 impl Batch {
@@ -61,12 +136,32 @@ impl Batch {
 			inner: BTreeMap::new(),
 			batch_size: config.batch_size,
 			column,
+			pending_bytes: 0,
+			peak_pending_bytes: 0,
+			cancel: config.cancel.clone(),
 		}
 	}
 
+	/// Total length of keys and values currently buffered in the batch.
+	pub fn pending_bytes(&self) -> usize {
+		self.pending_bytes
+	}
+
+	/// Highest value of `pending_bytes` since the batch was created.
+	pub fn peak_pending_bytes(&self) -> usize {
+		self.peak_pending_bytes
+	}
+
 	/// Insert a value into the batch, committing if necessary.
 	pub fn insert(&mut self, key: Vec<u8>, value: Vec<u8>, dest: &mut Database) -> io::Result<()> {
-		self.inner.insert(key, value);
+		let key_len = key.len();
+		let value_len = value.len();
+		match self.inner.insert(key, value) {
+			Some(old_value) => self.pending_bytes = self.pending_bytes - old_value.len() + value_len,
+			None => self.pending_bytes += key_len + value_len,
+		}
+		self.peak_pending_bytes = ::std::cmp::max(self.peak_pending_bytes, self.pending_bytes);
+
 		if self.inner.len() == self.batch_size {
 			self.commit(dest)?;
 		}
@@ -74,7 +169,13 @@ impl Batch {
 	}
 
 	/// Commit all the items in the batch to the given database.
+	///
+	/// Fails with `MigrationError::Cancelled` if the migration has been cancelled.
 	pub fn commit(&mut self, dest: &mut Database) -> io::Result<()> {
+		if self.cancel.as_ref().map_or(false, |cancel| cancel.load(Ordering::SeqCst)) {
+			return Err(cancelled_err());
+		}
+
 		if self.inner.is_empty() { return Ok(()) }
 
 		let mut transaction = DBTransaction::new();
@@ -84,6 +185,7 @@ impl Batch {
 		}
 
 		self.inner.clear();
+		self.pending_bytes = 0;
 		dest.write(transaction)
 	}
 }
@@ -99,6 +201,9 @@ pub trait Migration {
 	fn alters_existing(&self) -> bool { true }
 	/// Whether this migration deletes data in any of the existing columns.
 	fn deletes_existing(&self) -> bool { false }
+	/// Existing columns altered by this migration, if known.
+	/// When `Some`, only these columns are migrated and all other columns are left in place.
+	fn modified_columns(&self) -> Option<Vec<u32>> { None }
 	/// Version of the database after the migration.
 	fn version(&self) -> u32;
 	/// Migrate a source to a destination.
@@ -113,6 +218,10 @@ pub trait SimpleMigration {
 	fn version(&self) -> u32;
 	/// Index of column which should be migrated.
 	fn migrated_column_index(&self) -> u32;
+	/// Columns modified by the migration, `None` if unknown.
+	/// Return `Some(vec![self.migrated_column_index()])` to let the other columns be kept in place
+	/// instead of being copied into a new database.
+	fn modified_columns(&self) -> Option<Vec<u32>> { None }
 	/// Should migrate existing object to new database.
 	/// Returns `None` if the object does not exist in new version of database.
 	fn simple_migrate(&mut self, key: Vec<u8>, value: Vec<u8>) -> Option<(Vec<u8>, Vec<u8>)>;
@@ -123,6 +232,8 @@ impl<T: SimpleMigration> Migration for T {
 
 	fn alters_existing(&self) -> bool { true }
 
+	fn modified_columns(&self) -> Option<Vec<u32>> { SimpleMigration::modified_columns(self) }
+
 	fn version(&self) -> u32 { SimpleMigration::version(self) }
 
 	fn migrate(&mut self, source: Arc<Database>, config: &Config, dest: Option<&mut Database>, col: u32) -> io::Result<()> {
@@ -146,7 +257,11 @@ impl<T: SimpleMigration> Migration for T {
 			}
 		}
 
-		batch.commit(dest)
+		batch.commit(dest)?;
+		if config.log_memory_usage {
+			info!(target: "migration", "Column {}: peak pending batch size {} bytes", col, batch.peak_pending_bytes());
+		}
+		Ok(())
 	}
 }
 
@@ -206,6 +321,40 @@ impl Migration for VacuumAccountsBloom {
 	}
 }
 
+/// Replace all the data in column `col` of `db` with the data of the same column of `source`,
+/// writing at most `batch_size` operations at once.
+fn replace_column(db: &Database, source: &Database, col: u32, batch_size: usize) -> io::Result<()> {
+	let mut transaction = DBTransaction::with_capacity(batch_size);
+	for (key, _) in db.iter(col) {
+		transaction.delete(col, &key);
+		if transaction.ops.len() >= batch_size {
+			db.write(mem::replace(&mut transaction, DBTransaction::with_capacity(batch_size)))?;
+		}
+	}
+
+	for (key, value) in source.iter(col) {
+		transaction.put(col, &key, &value);
+		if transaction.ops.len() >= batch_size {
+			db.write(mem::replace(&mut transaction, DBTransaction::with_capacity(batch_size)))?;
+		}
+	}
+
+	db.write(transaction)
+}
+
+/// Add or remove column families of `db` until it has `goal_columns` columns.
+fn change_columns(db: &Database, goal_columns: u32) -> io::Result<()> {
+	while db.num_columns() < goal_columns {
+		db.add_column().map_err(other_io_err)?;
+	}
+
+	while db.num_columns() > goal_columns {
+		db.remove_last_column().map_err(other_io_err)?;
+	}
+
+	Ok(())
+}
+
 /// Get the path where all databases reside.
 fn database_path(path: &Path) -> PathBuf {
 	let mut temp_path = path.to_owned();
@@ -239,6 +388,27 @@ impl TempIndex {
 	}
 }
 
+/// Summary of an executed sequence of migrations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationReport {
+	/// Version of the database before the migrations.
+	pub from_version: u32,
+	/// Version of the database after the migrations.
+	pub to_version: u32,
+	/// Versions of the applied migrations, in order of execution.
+	pub applied: Vec<u32>,
+	/// Estimated number of keys passed through migrations, per column.
+	pub keys_migrated_per_column: BTreeMap<u32, u64>,
+}
+
+impl MigrationReport {
+	fn note_keys(&mut self, db: &Database, col: u32) -> io::Result<()> {
+		let keys = db.num_keys(col)?;
+		*self.keys_migrated_per_column.entry(col).or_insert(0) += keys;
+		Ok(())
+	}
+}
+
 /// Manages database migration.
 pub struct Manager {
 	config: Config,
@@ -270,7 +440,44 @@ impl Manager {
 	/// Performs migration in order, starting with a source path, migrating between two temporary databases,
 	/// and producing a path where the final migration lives.
 	pub fn execute(&mut self, old_path: &Path, version: u32) -> io::Result<PathBuf> {
+		self.execute_with_report(old_path, version).map(|(path, _)| path)
+	}
+
+	/// Same as `execute`, additionally returning a report of the applied migrations.
+	pub fn execute_with_report(&mut self, old_path: &Path, version: u32) -> io::Result<(PathBuf, MigrationReport)> {
 		let config = self.config.clone();
+		self.run(old_path, version, config)
+	}
+
+	/// Same as `execute_with_report`, aborting the migration once `cancel` is set.
+	///
+	/// The flag is checked between batches and columns. On cancellation temporary databases
+	/// are removed and an error wrapping `MigrationError::Cancelled` is returned.
+	///
+	/// Migrations which only add or remove columns, delete data, or report their modified
+	/// columns run in place. Once such a migration has changed the source database the flag
+	/// is ignored and the remaining migrations run to completion, so a cancelled migration
+	/// always leaves the source database as it was.
+	pub fn execute_cancellable(&mut self, old_path: &Path, version: u32, cancel: Arc<AtomicBool>) -> io::Result<(PathBuf, MigrationReport)> {
+		let config = Config { cancel: Some(cancel), ..self.config.clone() };
+		self.run(old_path, version, config)
+	}
+
+	fn run(&mut self, old_path: &Path, version: u32, config: Config) -> io::Result<(PathBuf, MigrationReport)> {
+		let result = self.migrate_all(old_path, version, &config);
+		if let Err(ref err) = result {
+			if MigrationError::from_io(err) == Some(MigrationError::Cancelled) {
+				info!(target: "migration", "Migration cancelled, removing temporary databases");
+				let db_root = database_path(old_path);
+				let _ = fs::remove_dir_all(TempIndex::One.path(&db_root));
+				let _ = fs::remove_dir_all(TempIndex::Two.path(&db_root));
+			}
+		}
+		result
+	}
+
+	fn migrate_all(&mut self, old_path: &Path, version: u32, config: &Config) -> io::Result<(PathBuf, MigrationReport)> {
+		let mut config = config.clone();
 		let migrations = self.migrations_from(version);
 		trace!(target: "migration", "Total migrations to execute for version {}: {}", version, migrations.len());
 		if migrations.is_empty() {
@@ -279,12 +486,7 @@ impl Manager {
 
 		let columns = migrations.first().expect("checked empty above; qed").pre_columns();
 		trace!(target: "migration", "Expecting database to contain {} columns", columns);
-		let mut db_config = DatabaseConfig {
-			max_open_files: 64,
-			compaction: config.compaction_profile,
-			columns,
-			..Default::default()
-		};
+		let mut db_config = config.database_config(columns);
 
 		let db_root = database_path(old_path);
 		let mut temp_idx = TempIndex::One;
@@ -294,14 +496,64 @@ impl Manager {
 		let old_path_str = old_path.to_str().ok_or_else(|| other_io_err("Migration impossible."))?;
 		let mut cur_db = Arc::new(Database::open(&db_config, old_path_str)?);
 
+		let mut report = MigrationReport {
+			from_version: version,
+			to_version: version,
+			..Default::default()
+		};
+
 		for migration in migrations {
+			if config.is_cancelled() {
+				return Err(cancelled_err());
+			}
+
 			trace!(target: "migration", "starting migration to version {}", migration.version());
 			// Change number of columns in new db
 			let current_columns = db_config.columns;
 			db_config.columns = migration.columns();
 
-			// slow migrations: alter existing data.
-			if migration.alters_existing() {
+			let modified_columns = match migration.alters_existing() {
+				true => migration.modified_columns(),
+				false => None,
+			};
+
+			if let Some(modified_columns) = modified_columns {
+				// migrations altering known columns: only these are migrated into a scratch database,
+				// all other columns stay untouched in the current database.
+				let scratch_path = temp_idx.path(&db_root);
+				let scratch_path_str = scratch_path.to_str().ok_or_else(|| other_io_err("Migration impossible."))?;
+				let scratch_config = DatabaseConfig { columns: current_columns, ..db_config.clone() };
+				let modified_columns: Vec<_> = modified_columns.into_iter().filter(|col| *col < current_columns).collect();
+
+				{
+					let mut scratch_db = Database::open(&scratch_config, scratch_path_str)?;
+					for &col in &modified_columns {
+						if config.is_cancelled() {
+							return Err(cancelled_err());
+						}
+						report.note_keys(&cur_db, col)?;
+						migration.migrate(cur_db.clone(), &config, Some(&mut scratch_db), col)?;
+					}
+
+					if config.is_cancelled() {
+						return Err(cancelled_err());
+					}
+
+					// the migrated columns are written back in batches, a migration can't be
+					// cancelled anymore once the current database is being written to.
+					if temp_path.as_path() == old_path && config.stop_cancellation() {
+						info!(target: "migration", "Migrating the database in place, the migration can no longer be cancelled");
+					}
+					for &col in &modified_columns {
+						replace_column(&cur_db, &scratch_db, col, cmp::max(config.batch_size, 1))?;
+					}
+				}
+
+				let _ = fs::remove_dir_all(&scratch_path);
+				change_columns(&cur_db, migration.columns())?;
+			} else if migration.alters_existing() {
+				// slow migrations: alter existing data.
+				// the old database is never written to, so an interrupted migration can be restarted.
 				temp_path = temp_idx.path(&db_root);
 
 				// open the target temporary database.
@@ -309,6 +561,10 @@ impl Manager {
 				let mut new_db = Database::open(&db_config, temp_path_str)?;
 
 				for col in 0..current_columns {
+					if config.is_cancelled() {
+						return Err(cancelled_err());
+					}
+					report.note_keys(&cur_db, col)?;
 					migration.migrate(cur_db.clone(), &config, Some(&mut new_db), col)?
 				}
 
@@ -318,27 +574,31 @@ impl Manager {
 
 				// remove the other temporary migration database.
 				let _ = fs::remove_dir_all(temp_idx.path(&db_root));
-			} else if migration.deletes_existing() {
-				// Migration deletes data in an existing column.
-				for col in 0..db_config.columns {
-					migration.migrate(cur_db.clone(), &config, None, col)?
-				}
 			} else {
-				// migrations which simply add or remove column families.
-				// we can do this in-place.
-				let goal_columns = migration.columns();
-				while cur_db.num_columns() < goal_columns {
-					cur_db.add_column().map_err(other_io_err)?;
+				// the remaining migrations run in place, cancelling after they touched
+				// the source database would leave it half-migrated.
+				if temp_path.as_path() == old_path && config.stop_cancellation() {
+					info!(target: "migration", "Migrating the database in place, the migration can no longer be cancelled");
 				}
 
-				while cur_db.num_columns() > goal_columns {
-					cur_db.remove_last_column().map_err(other_io_err)?;
+				if migration.deletes_existing() {
+					// Migration deletes data in an existing column.
+					for col in 0..db_config.columns {
+						migration.migrate(cur_db.clone(), &config, None, col)?
+					}
+				} else {
+					// migrations which simply add or remove column families.
+					// we can do this in-place.
+					change_columns(&cur_db, migration.columns())?;
 				}
 			}
+
+			report.applied.push(migration.version());
+			report.to_version = migration.version();
 		}
 		// If `temp_path` is different from `old_path` we will shuffle database
 		// directories and delete the old paths.
-		Ok(temp_path)
+		Ok((temp_path, report))
 	}
 
 	/// Returns true if migration is needed.
@@ -21,11 +21,12 @@
 use std::collections::BTreeMap;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use kvdb_rocksdb::{Database, DatabaseConfig};
 use maplit::btreemap;
-use migration_rocksdb::{Batch, Config, SimpleMigration, Migration, Manager, ChangeColumns};
+use migration_rocksdb::{Batch, Config, SimpleMigration, Migration, MigrationError, Manager, ChangeColumns};
 use tempfile::TempDir;
 
 #[inline]
@@ -84,6 +85,49 @@ impl SimpleMigration for Migration1 {
 	}
 }
 
+struct MigrationInPlace;
+
+impl SimpleMigration for MigrationInPlace {
+	fn columns(&self) -> u32 { 2 }
+	fn version(&self) -> u32 { 1 }
+	fn migrated_column_index(&self) -> u32 { 1 }
+	fn modified_columns(&self) -> Option<Vec<u32>> { Some(vec![1]) }
+	fn simple_migrate(&mut self, mut key: Vec<u8>, mut value: Vec<u8>) -> Option<(Vec<u8>, Vec<u8>)> {
+		key.push(0x11);
+		value.push(0x22);
+
+		Some((key, value))
+	}
+}
+
+struct KnownColumnsMigration {
+	migrated_columns: Arc<Mutex<Vec<u32>>>,
+}
+
+impl Migration for KnownColumnsMigration {
+	fn columns(&self) -> u32 { 2 }
+	fn version(&self) -> u32 { 1 }
+	fn modified_columns(&self) -> Option<Vec<u32>> { Some(vec![1]) }
+	fn migrate(&mut self, source: Arc<Database>, config: &Config, dest: Option<&mut Database>, col: u32) -> io::Result<()> {
+		self.migrated_columns.lock().unwrap().push(col);
+		MigrationInPlace.migrate(source, config, dest, col)
+	}
+}
+
+struct CancellingMigration {
+	cancel: Arc<AtomicBool>,
+}
+
+impl SimpleMigration for CancellingMigration {
+	fn columns(&self) -> u32 { 1 }
+	fn version(&self) -> u32 { 1 }
+	fn migrated_column_index(&self) -> u32 { 0 }
+	fn simple_migrate(&mut self, key: Vec<u8>, value: Vec<u8>) -> Option<(Vec<u8>, Vec<u8>)> {
+		self.cancel.store(true, Ordering::SeqCst);
+		Some((key, value))
+	}
+}
+
 struct AddsColumn;
 
 impl Migration for AddsColumn {
@@ -159,6 +203,23 @@ fn multiple_migrations() {
 	verify_migration(&end_path, expected);
 }
 
+#[test]
+fn multiple_migrations_report() {
+	let tempdir = TempDir::new().unwrap();
+	let db_path = db_path(tempdir.path());
+	let mut manager = Manager::new(Config::default());
+	make_db(&db_path, btreemap![vec![] => vec![], vec![1] => vec![1]]);
+
+	manager.add_migration(Migration0).unwrap();
+	manager.add_migration(Migration1).unwrap();
+	let (_, report) = manager.execute_with_report(&db_path, 0).unwrap();
+
+	assert_eq!(report.from_version, 0);
+	assert_eq!(report.to_version, 2);
+	assert_eq!(report.applied, vec![1, 2]);
+	assert_eq!(report.keys_migrated_per_column.keys().collect::<Vec<_>>(), vec![&0]);
+}
+
 #[test]
 fn second_migration() {
 	let tempdir = TempDir::new().unwrap();
@@ -248,3 +309,138 @@ fn change_columns() {
 	let db = Database::open(&config, new_path.to_str().unwrap()).unwrap();
 	assert_eq!(db.num_columns(), 4);
 }
+
+#[test]
+fn untouched_columns_are_not_rewritten() {
+	let tempdir = TempDir::new().unwrap();
+	let db_path = db_path(tempdir.path());
+	let config = DatabaseConfig::with_columns(2);
+	{
+		let db = Database::open(&config, db_path.to_str().unwrap()).unwrap();
+		let mut transaction = db.transaction();
+		transaction.put(0, &[1], &[1]);
+		transaction.put(1, &[2], &[2]);
+		db.write(transaction).unwrap();
+	}
+
+	let migrated_columns = Arc::new(Mutex::new(Vec::new()));
+	let mut manager = Manager::new(Config::default());
+	manager.add_migration(KnownColumnsMigration { migrated_columns: migrated_columns.clone() }).unwrap();
+	let end_path = manager.execute(&db_path, 0).unwrap();
+
+	assert_eq!(db_path, end_path, "Migrating known columns is an in-place migration.");
+	assert!(!tempdir.path().join("temp_migration_1").exists());
+	assert!(!tempdir.path().join("temp_migration_2").exists());
+	assert_eq!(*migrated_columns.lock().unwrap(), vec![1], "Only the modified column is read by the migration.");
+
+	let db = Database::open(&config, end_path.to_str().unwrap()).unwrap();
+	assert_eq!(db.get(0, &[1]).unwrap().unwrap(), vec![1]);
+	assert_eq!(db.get(1, &[2]).unwrap(), None);
+	assert_eq!(db.get(1, &[2, 0x11]).unwrap().unwrap(), vec![2, 0x22]);
+	assert_eq!(db.num_keys(0).unwrap(), 1);
+}
+
+#[test]
+fn modified_columns_are_written_back_in_batches() {
+	let tempdir = TempDir::new().unwrap();
+	let db_path = db_path(tempdir.path());
+	let config = DatabaseConfig::with_columns(2);
+	{
+		let db = Database::open(&config, db_path.to_str().unwrap()).unwrap();
+		let mut transaction = db.transaction();
+		for i in 0..5u8 {
+			transaction.put(1, &[i], &[i]);
+		}
+		db.write(transaction).unwrap();
+	}
+
+	let mut manager = Manager::new(Config { batch_size: 2, ..Default::default() });
+	manager.add_migration(MigrationInPlace).unwrap();
+	let end_path = manager.execute(&db_path, 0).unwrap();
+
+	let db = Database::open(&config, end_path.to_str().unwrap()).unwrap();
+	assert_eq!(db.num_keys(1).unwrap(), 5);
+	for i in 0..5u8 {
+		assert_eq!(db.get(1, &[i]).unwrap(), None);
+		assert_eq!(db.get(1, &[i, 0x11]).unwrap().unwrap(), vec![i, 0x22]);
+	}
+}
+
+#[test]
+fn max_open_files_reaches_database_config() {
+	let config = Config {
+		max_open_files: 16,
+		..Default::default()
+	};
+
+	assert_eq!(Config::default().database_config(1).max_open_files, 64);
+	let db_config = config.database_config(3);
+	assert_eq!(db_config.max_open_files, 16);
+	assert_eq!(db_config.columns, 3);
+}
+
+#[test]
+fn batch_pending_bytes() {
+	let tempdir = TempDir::new().unwrap();
+	let mut db = Database::open(&DatabaseConfig::default(), tempdir.path().to_str().unwrap()).unwrap();
+	let mut batch = Batch::new(&Config::default(), 0);
+	assert_eq!(batch.pending_bytes(), 0);
+
+	batch.insert(vec![1], vec![1, 2, 3], &mut db).unwrap();
+	batch.insert(vec![2, 2], vec![4, 5], &mut db).unwrap();
+	assert_eq!(batch.pending_bytes(), 4 + 4);
+
+	// overwriting a key only replaces the value
+	batch.insert(vec![1], vec![6], &mut db).unwrap();
+	assert_eq!(batch.pending_bytes(), 2 + 4);
+	assert_eq!(batch.peak_pending_bytes(), 8);
+
+	batch.commit(&mut db).unwrap();
+	assert_eq!(batch.pending_bytes(), 0);
+	assert_eq!(batch.peak_pending_bytes(), 8);
+	assert_eq!(db.get(0, &[1]).unwrap().unwrap(), vec![6]);
+	assert_eq!(db.num_keys(0).unwrap(), 2);
+}
+
+#[test]
+fn cancelled_migration() {
+	let tempdir = TempDir::new().unwrap();
+	let db_path = db_path(tempdir.path());
+	let cancel = Arc::new(AtomicBool::new(false));
+	let mut manager = Manager::new(Config { batch_size: 1, ..Default::default() });
+	let pairs = btreemap![vec![1] => vec![1], vec![2] => vec![2], vec![3] => vec![3]];
+	make_db(&db_path, pairs.clone());
+
+	manager.add_migration(CancellingMigration { cancel: cancel.clone() }).unwrap();
+	let err = manager.execute_cancellable(&db_path, 0, cancel).unwrap_err();
+
+	assert_eq!(MigrationError::from_io(&err), Some(MigrationError::Cancelled));
+	assert!(!tempdir.path().join("temp_migration_1").exists());
+	assert!(!tempdir.path().join("temp_migration_2").exists());
+	verify_migration(&db_path, pairs);
+}
+
+#[test]
+fn in_place_migration_is_not_cancellable() {
+	let tempdir = TempDir::new().unwrap();
+	let db_path = db_path(tempdir.path());
+	let cancel = Arc::new(AtomicBool::new(false));
+	let uncancellable = Arc::new(AtomicBool::new(false));
+	let mut manager = Manager::new(Config {
+		on_uncancellable: Some(Arc::new({
+			let uncancellable = uncancellable.clone();
+			move || uncancellable.store(true, Ordering::SeqCst)
+		})),
+		..Default::default()
+	});
+	manager.add_migration(ChangeColumns {
+		pre_columns: 1,
+		post_columns: 4,
+		version: 1,
+	}).unwrap();
+
+	let (new_path, _) = manager.execute_cancellable(&db_path, 0, cancel).unwrap();
+
+	assert_eq!(db_path, new_path);
+	assert!(uncancellable.load(Ordering::SeqCst));
+}
@@ -0,0 +1,275 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Peer diversity accounting, limiting how many non-reserved peers may share
+//! an IP subnet or a node id bucket.
+
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+use std::time::Duration;
+
+use ethcore_io::StreamToken;
+use network::DiversityLimits;
+
+use crate::ip_utils::SocketAddrExt;
+use crate::node_table::NodeId;
+
+/// Network neighbourhood of a globally routable address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Subnet {
+	/// IPv4 /24 subnet.
+	V4([u8; 3]),
+	/// IPv6 /48 subnet.
+	V6([u16; 3]),
+}
+
+impl Subnet {
+	/// Subnet of the address, `None` if the address is not globally routable.
+	fn of(ip: &IpAddr) -> Option<Subnet> {
+		if !ip.is_global_s() {
+			return None;
+		}
+
+		Some(match *ip {
+			IpAddr::V4(ref ip) => {
+				let octets = ip.octets();
+				Subnet::V4([octets[0], octets[1], octets[2]])
+			},
+			IpAddr::V6(ref ip) => {
+				let segments = ip.segments();
+				Subnet::V6([segments[0], segments[1], segments[2]])
+			},
+		})
+	}
+}
+
+/// Group of peers competing for the same diversity limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Group {
+	Subnet(Subnet),
+	Bucket(u8),
+}
+
+/// Node id bucket, node ids are public keys so their leading bits are uniformly distributed.
+fn id_bucket(id: &NodeId) -> u8 {
+	id.as_bytes()[0] >> 4
+}
+
+/// Groups the peer with given id and address belongs to.
+fn groups(id: &NodeId, ip: Option<IpAddr>) -> impl Iterator<Item = Group> {
+	ip.and_then(|ip| Subnet::of(&ip))
+		.map(Group::Subnet)
+		.into_iter()
+		.chain(Some(Group::Bucket(id_bucket(id))))
+}
+
+/// Connected, or being connected, peer as seen by diversity accounting.
+#[derive(Debug, Clone)]
+pub struct Peer {
+	/// Session token.
+	pub token: StreamToken,
+	/// Peer node id.
+	pub id: NodeId,
+	/// Remote address, if known.
+	pub ip: Option<IpAddr>,
+	/// Reserved peers are neither counted nor evicted.
+	pub reserved: bool,
+	/// Whether the peer may be disconnected to make room for a more diverse one.
+	pub evictable: bool,
+	/// How long the session has been ready.
+	pub uptime: Duration,
+}
+
+/// Number of non-reserved peers in each subnet and node id bucket.
+#[derive(Debug, Clone)]
+pub struct Diversity {
+	limits: DiversityLimits,
+	counted: HashSet<NodeId>,
+	groups: HashMap<Group, usize>,
+}
+
+impl Diversity {
+	/// Create accounting with no peers.
+	pub fn new(limits: DiversityLimits) -> Self {
+		Diversity {
+			limits,
+			counted: HashSet::new(),
+			groups: HashMap::new(),
+		}
+	}
+
+	/// Create accounting of the given peers, reserved ones are skipped.
+	pub fn from_peers(limits: DiversityLimits, peers: &[Peer]) -> Self {
+		let mut diversity = Diversity::new(limits);
+		for peer in peers.iter().filter(|p| !p.reserved) {
+			diversity.add(&peer.id, peer.ip);
+		}
+		diversity
+	}
+
+	/// Account a peer, a peer already accounted for is not counted twice.
+	pub fn add(&mut self, id: &NodeId, ip: Option<IpAddr>) {
+		if !self.counted.insert(*id) {
+			return;
+		}
+		for group in groups(id, ip) {
+			*self.groups.entry(group).or_insert(0) += 1;
+		}
+	}
+
+	/// Whether a peer with given id and address fits within the limits.
+	pub fn allows(&self, id: &NodeId, ip: Option<IpAddr>) -> bool {
+		self.counted.contains(id) || groups(id, ip).all(|group| {
+			let count = self.groups.get(&group).cloned().unwrap_or(0);
+			self.limit(&group).map_or(true, |limit| count < limit)
+		})
+	}
+
+	fn limit(&self, group: &Group) -> Option<usize> {
+		match *group {
+			Group::Subnet(Subnet::V4(_)) => self.limits.max_per_ipv4_subnet,
+			Group::Subnet(Subnet::V6(_)) => self.limits.max_per_ipv6_subnet,
+			Group::Bucket(_) => self.limits.max_per_id_bucket,
+		}
+	}
+
+	/// Pick the session to disconnect to make room for a new peer with given id and address
+	/// when all the slots are taken. The youngest evictable peer of the most represented group
+	/// is picked, as long as the new peer doesn't belong to that group; `None` if evicting
+	/// wouldn't make the peer set more diverse.
+	pub fn eviction_candidate(&self, peers: &[Peer], id: &NodeId, ip: Option<IpAddr>) -> Option<StreamToken> {
+		let own_groups: HashSet<Group> = groups(id, ip).collect();
+		let (group, count) = self.groups.iter()
+			.filter(|&(group, _)| !own_groups.contains(group))
+			// prefer subnets over buckets on a tie, they are harder to spread over
+			.max_by_key(|&(group, count)| (*count, match *group { Group::Subnet(_) => 1, Group::Bucket(_) => 0 }))?;
+
+		let own_count = own_groups.iter()
+			.filter(|g| ::std::mem::discriminant(*g) == ::std::mem::discriminant(group))
+			.map(|g| self.groups.get(g).cloned().unwrap_or(0))
+			.max()
+			.unwrap_or(0);
+
+		// the new peer's group would grow to `own_count + 1`, evict only if that's still smaller
+		if *count <= own_count + 1 {
+			return None;
+		}
+
+		peers.iter()
+			.filter(|p| !p.reserved && p.evictable && groups(&p.id, p.ip).any(|g| g == *group))
+			.min_by_key(|p| p.uptime)
+			.map(|p| p.token)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::net::{IpAddr, Ipv4Addr};
+	use std::time::Duration;
+
+	use ethereum_types::H512;
+	use network::DiversityLimits;
+
+	use super::{Diversity, Peer};
+
+	fn node_id(bucket: u8, n: u8) -> H512 {
+		let mut id = H512::zero();
+		id.as_bytes_mut()[0] = bucket << 4;
+		id.as_bytes_mut()[63] = n;
+		id
+	}
+
+	fn ip(a: u8, b: u8, c: u8, d: u8) -> Option<IpAddr> {
+		Some(IpAddr::V4(Ipv4Addr::new(a, b, c, d)))
+	}
+
+	fn peer(token: usize, id: H512, ip: Option<IpAddr>, uptime: u64) -> Peer {
+		Peer { token, id, ip, reserved: false, evictable: true, uptime: Duration::from_secs(uptime) }
+	}
+
+	fn limits() -> DiversityLimits {
+		DiversityLimits {
+			max_per_ipv4_subnet: Some(2),
+			max_per_ipv6_subnet: Some(2),
+			max_per_id_bucket: Some(3),
+		}
+	}
+
+	#[test]
+	fn respects_subnet_and_bucket_limits() {
+		let peers = vec![
+			peer(0, node_id(0, 0), ip(8, 8, 8, 1), 10),
+			peer(1, node_id(1, 0), ip(8, 8, 8, 2), 10),
+			peer(2, node_id(2, 0), ip(9, 9, 9, 1), 10),
+			peer(3, node_id(2, 1), ip(10, 0, 0, 1), 10),
+			peer(4, node_id(2, 2), ip(127, 0, 0, 1), 10),
+		];
+		let diversity = Diversity::from_peers(limits(), &peers);
+
+		// subnet 8.8.8.0/24 is full
+		assert!(!diversity.allows(&node_id(3, 0), ip(8, 8, 8, 3)));
+		// bucket 2 is full
+		assert!(!diversity.allows(&node_id(2, 3), ip(1, 1, 1, 1)));
+		// non-global addresses are not subject to subnet limits
+		assert!(diversity.allows(&node_id(3, 0), ip(10, 0, 0, 2)));
+		assert!(diversity.allows(&node_id(3, 0), ip(9, 9, 9, 2)));
+		// already accounted peers always fit
+		assert!(diversity.allows(&node_id(0, 0), ip(8, 8, 8, 1)));
+		// no limits, no restrictions
+		assert!(Diversity::from_peers(DiversityLimits::unlimited(), &peers).allows(&node_id(2, 3), ip(8, 8, 8, 3)));
+	}
+
+	#[test]
+	fn reserved_peers_are_not_counted() {
+		let mut peers = vec![
+			peer(0, node_id(0, 0), ip(8, 8, 8, 1), 10),
+			peer(1, node_id(1, 0), ip(8, 8, 8, 2), 10),
+		];
+		peers[0].reserved = true;
+		let diversity = Diversity::from_peers(limits(), &peers);
+
+		assert!(diversity.allows(&node_id(3, 0), ip(8, 8, 8, 3)));
+	}
+
+	#[test]
+	fn evicts_youngest_peer_of_over_represented_group() {
+		let mut peers = vec![
+			peer(0, node_id(0, 0), ip(8, 8, 8, 1), 30),
+			peer(1, node_id(1, 0), ip(8, 8, 8, 2), 5),
+			peer(2, node_id(2, 0), ip(9, 9, 9, 1), 1),
+			peer(3, node_id(3, 0), ip(8, 8, 8, 3), 20),
+		];
+		let limits = DiversityLimits { max_per_ipv4_subnet: Some(3), ..limits() };
+		let diversity = Diversity::from_peers(limits, &peers);
+
+		// the youngest peer of 8.8.8.0/24 makes room for a peer from a new subnet
+		assert_eq!(diversity.eviction_candidate(&peers, &node_id(4, 0), ip(7, 7, 7, 7)), Some(1));
+		// no improvement when the new peer joins the over-represented subnet
+		assert_eq!(diversity.eviction_candidate(&peers, &node_id(4, 0), ip(8, 8, 8, 4)), None);
+		// evicting still shrinks the largest subnet when the new peer's one grows
+		assert_eq!(diversity.eviction_candidate(&peers, &node_id(4, 0), ip(9, 9, 9, 2)), Some(1));
+
+		// reserved and non-evictable peers are skipped
+		peers[1].reserved = true;
+		peers[3].evictable = false;
+		let diversity = Diversity::from_peers(limits, &peers);
+		assert_eq!(diversity.eviction_candidate(&peers, &node_id(4, 0), ip(7, 7, 7, 7)), Some(0));
+
+		// nothing to evict when no peer of the group may be disconnected
+		peers[0].evictable = false;
+		assert_eq!(diversity.eviction_candidate(&peers, &node_id(4, 0), ip(7, 7, 7, 7)), None);
+	}
+}
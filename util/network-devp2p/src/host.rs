@@ -15,7 +15,7 @@
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::ops::*;
@@ -40,14 +40,15 @@ use rlp::{Encodable, RlpStream};
 use ethcore_io::{IoContext, IoHandler, IoManager, StreamToken, TimerToken};
 use parity_crypto::publickey::{Generator, KeyPair, Random, Secret};
 use network::{
-	client_version::ClientVersion, ConnectionDirection, ConnectionFilter, DisconnectReason, Error,
-	NetworkConfiguration, NetworkContext as NetworkContextTrait, NetworkIoMessage, NetworkProtocolHandler,
-	NonReservedPeerMode, PacketId, PeerId, ProtocolId, SessionInfo
+	client_name, client_version::ClientVersion, ClientHistogram, ClientIdFilter, ConnectionDirection, ConnectionFilter,
+	DisconnectReason, Error, NetworkConfiguration, NetworkContext as NetworkContextTrait, NetworkIoMessage, NetworkProtocolHandler,
+	NonReservedPeerMode, PacketId, PeerId, ProtocolId, ProtocolStats, SessionInfo, note_rejected_client
 };
 
 use crate::{
 	connection::PAYLOAD_SOFT_LIMIT,
 	discovery::{Discovery, MAX_DATAGRAM_SIZE, NodeEntry, TableUpdates},
+	diversity::{Diversity, Peer as DiversityPeer},
 	ip_utils::{map_external_address, select_public_address},
 	node_record::*,
 	node_table::*,
@@ -211,6 +212,10 @@ impl<'s> NetworkContextTrait for NetworkContext<'s> {
 	fn payload_soft_limit(&self) -> usize {
 		PAYLOAD_SOFT_LIMIT
 	}
+
+	fn protocol_stats(&self, peer: PeerId) -> Option<ProtocolStats> {
+		self.resolve_session(peer).map(|s| s.lock().protocol_stats(self.protocol))
+	}
 }
 
 /// Shared host information
@@ -231,6 +236,8 @@ pub struct HostInfo {
 	pub local_endpoint: NodeEndpoint,
 	/// Public address + discovery port
 	pub public_endpoint: Option<NodeEndpoint>,
+	/// Peers rejected by the client id filter, keyed by client name
+	rejected_clients: Mutex<BTreeMap<String, usize>>,
 }
 
 impl HostInfo {
@@ -243,6 +250,14 @@ impl HostInfo {
 		&self.config.client_version
 	}
 
+	pub(crate) fn client_id_filter(&self) -> &ClientIdFilter {
+		&self.config.client_id_filter
+	}
+
+	pub(crate) fn note_rejected_client(&self, client_id: &str) {
+		note_rejected_client(&mut self.rejected_clients.lock(), client_id);
+	}
+
 	pub(crate) fn secret(&self) -> &Secret {
 		self.keys.secret()
 	}
@@ -334,6 +349,7 @@ impl Host {
 				capabilities: Vec::new(),
 				public_endpoint: None,
 				local_endpoint,
+				rejected_clients: Mutex::new(BTreeMap::new()),
 			}),
 			discovery: Mutex::new(None),
 			udp_socket: Mutex::new(None),
@@ -464,6 +480,31 @@ impl Host {
 		peers
 	}
 
+	/// Packet statistics of `protocol` summed over all current sessions.
+	pub fn protocol_stats(&self, protocol: ProtocolId) -> ProtocolStats {
+		let mut stats = ProtocolStats::default();
+		for (_, session) in self.sessions.read().iter() {
+			stats.accumulate(&session.lock().protocol_stats(protocol));
+		}
+		stats
+	}
+
+	/// Connected peers and peers rejected by the client id filter, counted per client name.
+	pub fn client_histogram(&self) -> ClientHistogram {
+		let mut connected = BTreeMap::new();
+		for (_, session) in self.sessions.read().iter() {
+			let s = session.lock();
+			if s.is_ready() {
+				let client_id = s.info.client_version.to_string();
+				*connected.entry(client_name(&client_id).to_owned()).or_insert(0) += 1;
+			}
+		}
+		ClientHistogram {
+			connected,
+			rejected: self.info.read().rejected_clients.lock().clone(),
+		}
+	}
+
 	fn init_public_interface(&self, io: &IoContext<NetworkIoMessage>) -> Result<(), Error> {
 		if self.info.read().public_endpoint.is_some() {
 			return Ok(());
@@ -550,6 +591,31 @@ impl Host {
 		(handshakes, egress, ingress)
 	}
 
+	/// Peers connected or being connected to, with known ids. Must not be called while holding
+	/// a session lock.
+	///
+	/// Without diversity limits no peer is ever rejected or evicted for diversity, so no sessions
+	/// are locked and no peers are returned.
+	fn diversity_peers(&self, reserved: &HashSet<NodeId>) -> Vec<DiversityPeer> {
+		if self.info.read().config.diversity.is_unlimited() {
+			return Vec::new();
+		}
+
+		let sessions: Vec<_> = self.sessions.read().iter().map(|(token, s)| (token, s.clone())).collect();
+		sessions.into_iter().filter_map(|(token, s)| {
+			let s = s.lock();
+			let id = *s.id()?;
+			Some(DiversityPeer {
+				token,
+				id,
+				ip: s.remote_addr().ok().map(|address| address.ip()),
+				reserved: reserved.contains(&id),
+				evictable: s.is_ready() && !s.info.originated,
+				uptime: s.quality().uptime,
+			})
+		}).collect()
+	}
+
 	fn connecting_to(&self, id: &NodeId) -> bool {
 		self.sessions.read().iter().any(|(_, e)| e.lock().id() == Some(id))
 	}
@@ -582,14 +648,14 @@ impl Host {
 	}
 
 	fn connect_peers(&self, io: &IoContext<NetworkIoMessage>) {
-		let (min_peers, mut pin, max_handshakes, allow_ips, self_id) = {
+		let (min_peers, mut pin, max_handshakes, allow_ips, diversity_limits, self_id) = {
 			let info = self.info.read();
 			if info.capabilities.is_empty() {
 				return;
 			}
 			let config = &info.config;
 
-			(config.min_peers, config.non_reserved_mode == NonReservedPeerMode::Deny, config.max_handshakes as usize, config.ip_filter.clone(), *info.id())
+			(config.min_peers, config.non_reserved_mode == NonReservedPeerMode::Deny, config.max_handshakes as usize, config.ip_filter.clone(), config.diversity, *info.id())
 		};
 
 		let (handshake_count, egress_count, ingress_count) = self.session_count();
@@ -611,8 +677,10 @@ impl Host {
 
 		// iterate over all nodes, reserved ones coming first.
 		// if we are pinned to only reserved nodes, ignore all others.
+		// non-reserved nodes which would exceed the diversity limits are skipped.
 		let nodes = reserved_nodes.iter().cloned().chain(if !pin {
-			self.nodes.read().nodes(&allow_ips)
+			let mut diversity = Diversity::from_peers(diversity_limits, &self.diversity_peers(&reserved_nodes));
+			self.nodes.read().diverse_nodes(&allow_ips, &mut diversity)
 		} else {
 			Vec::new()
 		});
@@ -733,6 +801,7 @@ impl Host {
 		let mut kill = false;
 		let session = self.sessions.read().get(token).cloned();
 		let mut ready_id = None;
+		let mut evict = None;
 		if let Some(session) = session.clone() {
 			{
 				loop {
@@ -740,7 +809,7 @@ impl Host {
 					match session_result {
 						Err(e) => {
 							let reserved_nodes = self.reserved_nodes.read();
-							let s = session.lock();
+							let mut s = session.lock();
 							trace!(target: "network", "Session read error: {}:{:?} ({:?}) {:?}", token, s.id(), s.remote_addr(), e);
 							match e {
 								Error::Disconnect(DisconnectReason::IncompatibleProtocol) | Error::Disconnect(DisconnectReason::UselessPeer) => {
@@ -751,6 +820,7 @@ impl Host {
 											nodes.mark_as_useless(id);
 										}
 									}
+									s.set_misbehaved();
 								},
 								_ => {},
 							}
@@ -760,8 +830,10 @@ impl Host {
 						Ok(SessionData::Ready) => {
 							let (_, egress_count, ingress_count) = self.session_count();
 							let reserved_nodes = self.reserved_nodes.read();
+							let mut diversity_peers = self.diversity_peers(&reserved_nodes);
+							diversity_peers.retain(|p| p.token != token);
 							let mut s = session.lock();
-							let (min_peers, mut max_peers, reserved_only, self_id) = {
+							let (min_peers, mut max_peers, reserved_only, diversity_limits, self_id) = {
 								let info = self.info.read();
 								let mut max_peers = info.config.max_peers;
 								for cap in &s.info.capabilities {
@@ -770,12 +842,22 @@ impl Host {
 										break;
 									}
 								}
-								(info.config.min_peers as usize, max_peers as usize, info.config.non_reserved_mode == NonReservedPeerMode::Deny, *info.id())
+								(info.config.min_peers as usize, max_peers as usize, info.config.non_reserved_mode == NonReservedPeerMode::Deny, info.config.diversity, *info.id())
 							};
 
 							max_peers = max(max_peers, min_peers);
 
 							let id = *s.id().expect("Ready session always has id");
+							let ip = s.remote_addr().ok().map(|address| address.ip());
+
+							// Reserved peers bypass the diversity limits.
+							let diversity = Diversity::from_peers(diversity_limits, &diversity_peers);
+							if !reserved_nodes.contains(&id) && !diversity.allows(&id, ip) {
+								trace!(target: "network", "Disconnecting peer {:?} over diversity limits", id);
+								s.disconnect(io, DisconnectReason::TooManyPeers);
+								kill = true;
+								break;
+							}
 
 							// Check for the session limit.
 							// Outgoing connections are allowed as long as their count is <= min_peers
@@ -785,11 +867,21 @@ impl Host {
 								(s.info.originated && egress_count > min_peers) ||
 								(!s.info.originated && ingress_count > max_ingress) {
 								if !reserved_nodes.contains(&id) {
-									// only proceed if the connecting peer is reserved.
-									trace!(target: "network", "Disconnecting non-reserved peer {:?}", id);
-									s.disconnect(io, DisconnectReason::TooManyPeers);
-									kill = true;
-									break;
+									// an incoming peer improving diversity takes the slot of the youngest
+									// incoming peer of the most represented group.
+									evict = if reserved_only || s.info.originated {
+										None
+									} else {
+										diversity.eviction_candidate(&diversity_peers, &id, ip)
+									};
+
+									if evict.is_none() {
+										// only proceed if the connecting peer is reserved.
+										trace!(target: "network", "Disconnecting non-reserved peer {:?}", id);
+										s.disconnect(io, DisconnectReason::TooManyPeers);
+										kill = true;
+										break;
+									}
 								}
 							}
 
@@ -848,6 +940,15 @@ impl Host {
 				self.kill_connection(token, io, true);
 			}
 
+			if let Some(evicted) = evict.filter(|_| !kill) {
+				let evicted_session = self.sessions.read().get(evicted).cloned();
+				if let Some(evicted_session) = evicted_session {
+					trace!(target: "network", "Evicting session {} for a more diverse peer", evicted);
+					evicted_session.lock().disconnect(io, DisconnectReason::TooManyPeers);
+				}
+				self.kill_connection(evicted, io, false);
+			}
+
 			let handlers = self.handlers.read();
 			if !ready_data.is_empty() {
 				let duplicate = self.sessions.read().iter().any(|(_, e)| {
@@ -947,6 +1048,7 @@ impl Host {
 	fn kill_connection(&self, token: StreamToken, io: &IoContext<NetworkIoMessage>, remote: bool) {
 		let mut to_disconnect: Vec<ProtocolId> = Vec::new();
 		let mut failure_id = None;
+		let mut session_quality = None;
 		let mut deregister = false;
 		let mut expired_session = None;
 		if let FIRST_SESSION ..= LAST_SESSION = token {
@@ -964,13 +1066,22 @@ impl Host {
 					}
 					s.set_expired();
 					failure_id = s.id().cloned();
+					let quality = s.quality();
+					if s.is_ready() || quality.misbehaved {
+						session_quality = Some(quality);
+					}
 				}
 				deregister = remote || s.done();
 			}
 		}
 		if let Some(id) = failure_id {
+			let mut nodes = self.nodes.write();
 			if remote {
-				self.nodes.write().note_failure(&id);
+				nodes.note_failure(&id);
+			}
+			// local disconnects are only held against the peer if it misbehaved.
+			if let Some(quality) = session_quality.filter(|quality| remote || quality.misbehaved) {
+				nodes.note_disconnect(&id, quality);
 			}
 		}
 		for p in to_disconnect {
@@ -1147,7 +1258,17 @@ impl IoHandler<NetworkIoMessage> for Host {
 			NetworkIoMessage::Disconnect(ref peer) => {
 				let session = self.sessions.read().get(*peer).cloned();
 				if let Some(session) = session {
-					session.lock().disconnect(io, DisconnectReason::DisconnectRequested);
+					let mut s = session.lock();
+					s.disconnect(io, DisconnectReason::DisconnectRequested);
+					// a protocol asked to drop the peer (e.g. it timed out), it gets the reputation
+					// earned by the session like on a remote disconnect. Misbehaving peers are
+					// penalized by `kill_connection`.
+					let quality = s.quality();
+					if let Some(id) = s.id() {
+						if s.is_ready() && !s.expired() && !quality.misbehaved {
+							self.nodes.write().note_disconnect(id, quality);
+						}
+					}
 				}
 				trace!(target: "network", "Disconnect requested {}", peer);
 				self.kill_connection(*peer, io, false);
@@ -1156,6 +1277,8 @@ impl IoHandler<NetworkIoMessage> for Host {
 				let session = self.sessions.read().get(*peer).cloned();
 				if let Some(session) = session {
 					session.lock().disconnect(io, DisconnectReason::DisconnectRequested);
+					// penalized by `kill_connection`.
+					session.lock().set_misbehaved();
 					if let Some(id) = session.lock().id() {
 						let mut nodes = self.nodes.write();
 						nodes.note_failure(&id);
@@ -73,6 +73,7 @@ mod discovery;
 mod service;
 mod node_record;
 mod node_table;
+mod diversity;
 mod ip_utils;
 mod persistence;
 
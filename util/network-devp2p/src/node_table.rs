@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{fs, slice};
+use std::{cmp, fs, slice};
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::hash::{Hash, Hasher};
@@ -25,7 +25,7 @@ use std::str::FromStr;
 use std::time::{self, Duration, SystemTime};
 
 use ethereum_types::H512;
-use log::{debug, warn};
+use log::{debug, trace, warn};
 use rand::seq::SliceRandom;
 use rlp::{DecoderError, Rlp, RlpStream};
 use serde::{Deserialize, Serialize};
@@ -35,6 +35,7 @@ use network::{AllowIP, Error, IpFilter};
 
 use crate::{
 	discovery::{NodeEntry, TableUpdates},
+	diversity::Diversity,
 	ip_utils::*,
 };
 
@@ -187,12 +188,53 @@ impl NodeContact {
 	}
 }
 
+/// Highest reputation a node can reach.
+pub const MAX_REPUTATION: i32 = 100;
+/// Lowest reputation a node can reach.
+pub const MIN_REPUTATION: i32 = -100;
+/// Nodes loaded with a reputation below this value are quarantined.
+const QUARANTINE_REPUTATION: i32 = -50;
+
+/// Summary of a finished session, used to update the reputation of the remote node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SessionQuality {
+	/// Time elapsed since the session became ready.
+	pub uptime: Duration,
+	/// Number of protocol packets received over the session.
+	pub packets_received: u64,
+	/// Whether the peer was disconnected for violating the protocol.
+	pub misbehaved: bool,
+}
+
+impl SessionQuality {
+	/// Reputation change earned by the session.
+	fn reputation_delta(&self) -> i32 {
+		if self.misbehaved {
+			return -25;
+		}
+
+		if self.packets_received == 0 {
+			// too short to judge, e.g. dropped because of peer limits.
+			if self.uptime < Duration::from_secs(30) {
+				return 0;
+			}
+			return -5;
+		}
+
+		let uptime_bonus = cmp::min(self.uptime.as_secs() / 60, 10) as i32;
+		let packets_bonus = cmp::min(self.packets_received / 100, 10) as i32;
+		1 + uptime_bonus + packets_bonus
+	}
+}
+
 #[derive(Debug)]
 pub struct Node {
 	pub id: NodeId,
 	pub endpoint: NodeEndpoint,
 	pub peer_type: PeerType,
 	pub last_contact: Option<NodeContact>,
+	/// Reputation accumulated over previous sessions, between `MIN_REPUTATION` and `MAX_REPUTATION`.
+	pub reputation: i32,
 }
 
 impl Node {
@@ -202,6 +244,7 @@ impl Node {
 			endpoint,
 			peer_type: PeerType::Optional,
 			last_contact: None,
+			reputation: 0,
 		}
 	}
 }
@@ -232,6 +275,7 @@ impl FromStr for Node {
 			endpoint,
 			peer_type: PeerType::Optional,
 			last_contact: None,
+			reputation: 0,
 		})
 	}
 }
@@ -258,25 +302,44 @@ pub struct NodeTable {
 	nodes: HashMap<NodeId, Node>,
 	ordered_ids: Vec<NodeId>,
 	useless_nodes: HashSet<NodeId>,
+	quarantined_nodes: HashSet<NodeId>,
 	path: Option<String>,
 }
 
 impl NodeTable {
 	pub fn new(path: Option<String>) -> NodeTable {
-		let nodes = NodeTable::load(path.clone());
+		let mut nodes = NodeTable::load(path.clone());
 		let ordered_ids = NodeTable::make_ordered_entries(&nodes).iter().map(|m| m.id).collect();
+		let quarantined_nodes = NodeTable::quarantine(&mut nodes);
 		NodeTable {
 			path,
 			nodes,
 			useless_nodes: HashSet::new(),
+			quarantined_nodes,
 			ordered_ids
 		}
 	}
 
+	/// Collect nodes with a bad reputation, which are not contacted until the next restart.
+	/// Their reputation is halved so they get another chance afterwards.
+	fn quarantine(nodes: &mut HashMap<NodeId, Node>) -> HashSet<NodeId> {
+		nodes.values_mut()
+			.filter(|n| n.reputation < QUARANTINE_REPUTATION)
+			.map(|n| {
+				debug!(target: "network", "Quarantining node {} with reputation {}", n.id, n.reputation);
+				n.reputation /= 2;
+				n.id
+			})
+			.collect()
+	}
+
 	/// Add a node to table
 	pub fn add_node(&mut self, mut node: Node) {
-		// preserve node last_contact
-		node.last_contact = self.nodes.get(&node.id).and_then(|n| n.last_contact);
+		// preserve node last_contact and reputation
+		if let Some(existing) = self.nodes.get(&node.id) {
+			node.last_contact = existing.last_contact;
+			node.reputation = existing.reputation;
+		}
 		let id = node.id;
 		if self.ordered_ids.len() == MAX_NODES_IN_TABLE {
 			self.nodes.remove(&self.ordered_ids.pop().expect("ordered_ids is not empty; qed"));
@@ -325,7 +388,7 @@ impl NodeTable {
 		Vec::from_iter(
 			self.ordered_ids
 				.iter()
-				.filter(|id| !self.useless_nodes.contains(&id))
+				.filter(|id| !self.useless_nodes.contains(&id) && !self.quarantined_nodes.contains(&id))
 				.map(|id| self.nodes.get(&id).expect("nodes and ordered_ids do not get out of sync; qed"))
 		)
 	}
@@ -390,6 +453,24 @@ impl NodeTable {
 			.collect()
 	}
 
+	/// Same as `nodes`, skipping nodes which would exceed the diversity limits. Returned nodes
+	/// are accounted in `diversity` in order, so they fit within the limits all together.
+	pub fn diverse_nodes(&self, filter: &IpFilter, diversity: &mut Diversity) -> Vec<NodeId> {
+		self.ordered().iter()
+			.filter(|n| n.endpoint.is_allowed(&filter))
+			.filter(|n| {
+				let ip = Some(n.endpoint.address.ip());
+				if diversity.allows(&n.id, ip) {
+					diversity.add(&n.id, ip);
+					true
+				} else {
+					false
+				}
+			})
+			.map(|n| n.id)
+			.collect()
+	}
+
 	/// Ordered list of all entries by failure percentage, for nodes with the same failure percentage the absolute
 	/// number of failures is considered.
 	pub fn entries(&self) -> Vec<NodeEntry> {
@@ -457,6 +538,20 @@ impl NodeTable {
 		self.update_ordered_ids(id, Some(NodeContact::success()));
 	}
 
+	/// Update the reputation of a node after a session with it ended.
+	pub fn note_disconnect(&mut self, id: &NodeId, quality: SessionQuality) {
+		if let Some(node) = self.nodes.get_mut(id) {
+			let reputation = node.reputation.saturating_add(quality.reputation_delta());
+			node.reputation = cmp::max(MIN_REPUTATION, cmp::min(MAX_REPUTATION, reputation));
+			trace!(target: "network", "Node {} reputation {} after {:?}", id, node.reputation, quality);
+		}
+	}
+
+	/// Check if a node has been quarantined because of its bad reputation.
+	pub fn is_quarantined(&self, id: &NodeId) -> bool {
+		self.quarantined_nodes.contains(id)
+	}
+
 	/// Mark as useless, no further attempts to connect until next call to `clear_useless`.
 	pub fn mark_as_useless(&mut self, id: &NodeId) {
 		self.useless_nodes.insert(id.clone());
@@ -478,8 +573,10 @@ impl NodeTable {
 			return;
 		}
 		path.push(NODES_FILE);
-		let node_ids = self.nodes(&IpFilter::default());
-		let nodes = node_ids.into_iter()
+		// quarantined nodes are kept so that their reputation survives restarts.
+		let node_ids = self.nodes(&IpFilter::default()).into_iter()
+			.chain(self.quarantined_nodes.iter().cloned());
+		let nodes = node_ids
 			.map(|id| self.nodes.get(&id).expect("self.nodes() only returns node IDs from self.nodes"))
 			.take(MAX_NODES_IN_FILE)
 			.map(Into::into)
@@ -574,6 +671,8 @@ mod json {
 	pub struct Node {
 		pub url: String,
 		pub last_contact: Option<NodeContact>,
+		#[serde(default)]
+		pub reputation: i32,
 	}
 
 	impl Node {
@@ -581,6 +680,7 @@ mod json {
 			match super::Node::from_str(&self.url) {
 				Ok(mut node) => {
 					node.last_contact = self.last_contact.map(|c| c.into_node_contact());
+					node.reputation = cmp::max(MIN_REPUTATION, cmp::min(MAX_REPUTATION, self.reputation));
 					Some(node)
 				},
 				_ => None,
@@ -601,7 +701,8 @@ mod json {
 
 			Node {
 				url: format!("{}", node),
-				last_contact
+				last_contact,
+				reputation: node.reputation,
 			}
 		}
 	}
@@ -683,6 +784,51 @@ mod tests {
 		assert_matches!(node.unwrap_err(), Error::AddressParse);
 	}
 
+	#[test]
+	fn table_diverse_nodes_respect_limits() {
+		use network::DiversityLimits;
+
+		let node = |bucket: u8, n: u8, ip: [u8; 4]| {
+			let mut id = H512::zero();
+			id.as_bytes_mut()[0] = bucket << 4;
+			id.as_bytes_mut()[63] = n;
+			let address = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::new(ip[0], ip[1], ip[2], ip[3]), 30303));
+			Node::new(id, NodeEndpoint { address, udp_port: 30303 })
+		};
+
+		let mut table = NodeTable::new(None);
+		let connected = node(0, 0, [8, 8, 8, 1]);
+		let (connected_id, connected_ip) = (connected.id, connected.endpoint.address.ip());
+		// 8.8.8.0/24 is crowded
+		for n in 2..7 {
+			table.add_node(node(n, n, [8, 8, 8, n]));
+		}
+		// bucket 9 is crowded
+		for n in 0..4 {
+			table.add_node(node(9, n, [20 + n, 1, 1, 1]));
+		}
+		table.add_node(node(10, 0, [9, 9, 9, 1]));
+		table.add_node(connected);
+
+		let limits = DiversityLimits { max_per_ipv4_subnet: Some(2), max_per_ipv6_subnet: Some(2), max_per_id_bucket: Some(2) };
+		let mut diversity = Diversity::new(limits);
+		diversity.add(&connected_id, Some(connected_ip));
+
+		let selected = table.diverse_nodes(&IpFilter::default(), &mut diversity);
+		let in_subnet = selected.iter()
+			.filter(|id| match table.get(id).unwrap().endpoint.address { SocketAddr::V4(a) => a.ip().octets()[..3] == [8, 8, 8], _ => false })
+			.count();
+		let in_bucket = selected.iter().filter(|id| id.as_bytes()[0] >> 4 == 9).count();
+
+		// the connected peer takes one of the 8.8.8.0/24 slots
+		assert_eq!(in_subnet, 2);
+		assert_eq!(in_bucket, 2);
+		assert!(selected.contains(&connected_id));
+		assert!(selected.iter().any(|id| id.as_bytes()[0] >> 4 == 10));
+		assert_eq!(selected.len(), 5);
+		assert_eq!(table.nodes(&IpFilter::default()).len(), 11);
+	}
+
 	#[test]
 	fn table_last_contact_order() {
 		let node1 = Node::from_str("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
@@ -809,6 +955,45 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn table_reputation_quarantine() {
+		let tempdir = TempDir::new().unwrap();
+		let node1 = Node::from_str("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
+		let node2 = Node::from_str("enode://b979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
+		let id1 = H512::from_str("a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c").unwrap();
+		let id2 = H512::from_str("b979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c").unwrap();
+		let good = SessionQuality { uptime: Duration::from_secs(3600), packets_received: 5000, misbehaved: false };
+		let bad = SessionQuality { uptime: Duration::from_secs(5), packets_received: 0, misbehaved: true };
+
+		{
+			let mut table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			table.add_node(node1);
+			table.add_node(node2);
+
+			for _ in 0..10 {
+				table.note_disconnect(&id1, good);
+				table.note_disconnect(&id2, bad);
+			}
+			assert_eq!(table.nodes[&id1].reputation, MAX_REPUTATION);
+			assert_eq!(table.nodes[&id2].reputation, MIN_REPUTATION);
+			// quarantine only applies after a restart
+			assert_eq!(table.nodes(&IpFilter::default()).len(), 2);
+		}
+
+		{
+			let table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			assert_eq!(table.nodes[&id1].reputation, MAX_REPUTATION);
+			assert!(table.is_quarantined(&id2));
+			assert_eq!(table.nodes(&IpFilter::default()), vec![id1]);
+		}
+
+		{
+			let table = NodeTable::new(Some(tempdir.path().to_str().unwrap().to_owned()));
+			assert!(!table.is_quarantined(&id2));
+			assert_eq!(table.nodes(&IpFilter::default()).len(), 2);
+		}
+	}
+
 	#[test]
 	fn custom_allow() {
 		let filter = IpFilter {
@@ -24,8 +24,8 @@ use parking_lot::RwLock;
 
 use ethcore_io::{IoContext, IoHandler, IoService};
 use network::{
-	ConnectionFilter, Error, NetworkConfiguration, NetworkContext,
-	NetworkIoMessage, NetworkProtocolHandler, NonReservedPeerMode, PeerId, ProtocolId,
+	ClientHistogram, ConnectionFilter, Error, NetworkConfiguration, NetworkContext,
+	NetworkIoMessage, NetworkProtocolHandler, NonReservedPeerMode, PeerId, ProtocolId, ProtocolStats,
 
 };
 
@@ -154,6 +154,16 @@ impl NetworkService {
 		self.host.read().as_ref().map(|h| h.connected_peers()).unwrap_or_else(Vec::new)
 	}
 
+	/// Get packet statistics of `protocol` summed over all connected peers.
+	pub fn protocol_stats(&self, protocol: ProtocolId) -> ProtocolStats {
+		self.host.read().as_ref().map(|h| h.protocol_stats(protocol)).unwrap_or_default()
+	}
+
+	/// Get connected and rejected peer counts per client name.
+	pub fn client_histogram(&self) -> ClientHistogram {
+		self.host.read().as_ref().map(|h| h.client_histogram()).unwrap_or_default()
+	}
+
 	/// Try to add a reserved peer.
 	pub fn add_reserved_peer(&self, peer: &str) -> Result<(), Error> {
 		let host = self.host.read();
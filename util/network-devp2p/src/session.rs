@@ -27,7 +27,7 @@ use mio::tcp::*;
 use rlp::{EMPTY_LIST_RLP, Rlp, RlpStream};
 
 use ethcore_io::{IoContext, StreamToken};
-use network::{DisconnectReason, Error, PeerCapabilityInfo, ProtocolId, SessionInfo};
+use network::{DisconnectReason, Error, PeerCapabilityInfo, ProtocolId, ProtocolStats, SessionInfo};
 use network::client_version::ClientVersion;
 use network::SessionCapabilityInfo;
 
@@ -35,7 +35,7 @@ use crate::{
 	connection::{Connection, EncryptedConnection, MAX_PAYLOAD_SIZE, Packet},
 	handshake::Handshake,
 	host::HostInfo,
-	node_table::NodeId,
+	node_table::{NodeId, SessionQuality},
 };
 
 // Timeout must be less than (interval - 1).
@@ -68,6 +68,14 @@ pub struct Session {
 	// Protocol states -- accumulates pending packets until signaled as ready.
 	protocol_states: HashMap<ProtocolId, ProtocolState>,
 	compression: bool,
+	/// Time the Hello packet exchange completed.
+	ready_time: Option<Instant>,
+	/// Number of protocol packets received from the peer.
+	packets_received: u64,
+	/// Per-protocol packet accounting.
+	protocol_stats: HashMap<ProtocolId, ProtocolStats>,
+	/// Set when the peer is disconnected for a protocol violation.
+	misbehaved: bool,
 }
 
 enum State {
@@ -131,6 +139,10 @@ impl Session {
 			expired: false,
 			protocol_states: HashMap::new(),
 			compression: false,
+			ready_time: None,
+			packets_received: 0,
+			protocol_stats: HashMap::new(),
+			misbehaved: false,
 		})
 	}
 
@@ -174,6 +186,25 @@ impl Session {
 		self.expired
 	}
 
+	/// Mark the remote peer as misbehaving.
+	pub fn set_misbehaved(&mut self) {
+		self.misbehaved = true;
+	}
+
+	/// Summarize the session for updating the peer reputation.
+	pub fn quality(&self) -> SessionQuality {
+		SessionQuality {
+			uptime: self.ready_time.map_or(Duration::from_secs(0), |t| t.elapsed()),
+			packets_received: self.packets_received,
+			misbehaved: self.misbehaved,
+		}
+	}
+
+	/// Packet statistics of `protocol` on this session.
+	pub fn protocol_stats(&self, protocol: ProtocolId) -> ProtocolStats {
+		self.protocol_stats.get(&protocol).cloned().unwrap_or_default()
+	}
+
 	/// Check if this session is over and there is nothing to be sent.
 	pub fn done(&self) -> bool {
 		self.expired() && !self.connection().is_sending()
@@ -291,7 +322,11 @@ impl Session {
 			payload = &compressed;
 		}
 		rlp.append_raw(payload, 1);
-		self.send(io, &rlp.drain())
+		self.send(io, &rlp.drain())?;
+		if let Some(protocol) = protocol {
+			self.protocol_stats.entry(protocol).or_default().note_out(data.len());
+		}
+		Ok(())
 	}
 
 	/// Keep this session alive. Returns false if ping timeout happened
@@ -389,6 +424,8 @@ impl Session {
 				// map to protocol
 				let protocol = self.info.capabilities[i].protocol;
 				let protocol_packet_id = packet_id - self.info.capabilities[i].id_offset;
+				self.packets_received += 1;
+				self.protocol_stats.entry(protocol).or_default().note_in(data.len());
 
 				match *self.protocol_states.entry(protocol).or_insert_with(|| ProtocolState::Pending(Vec::new())) {
 					ProtocolState::Connected => {
@@ -426,7 +463,7 @@ impl Session {
 	where Message: Send + Sync + Clone {
 		let protocol = rlp.val_at::<u32>(0)?;
 		let client_version_string = rlp.val_at::<String>(1)?;
-		let client_version = ClientVersion::from(client_version_string);
+		let client_version = ClientVersion::from(client_version_string.as_str());
 		let peer_caps: Vec<PeerCapabilityInfo> = rlp.list_at(2)?;
 		let id = rlp.val_at::<NodeId>(4)?;
 
@@ -470,6 +507,11 @@ impl Session {
 		self.info.client_version = client_version;
 		self.info.capabilities = caps;
 		self.info.peer_capabilities = peer_caps;
+		if !host.client_id_filter().allows(&client_version_string) {
+			trace!(target: "network", "Peer client id rejected by filter: {}", client_version_string);
+			host.note_rejected_client(&client_version_string);
+			return Err(self.disconnect(io, DisconnectReason::UselessPeer));
+		}
 		if self.info.capabilities.is_empty() {
 			trace!(target: "network", "No common capabilities with peer.");
 			return Err(self.disconnect(io, DisconnectReason::UselessPeer));
@@ -481,6 +523,7 @@ impl Session {
 		self.compression = protocol >= MIN_COMPRESSION_PROTOCOL_VERSION;
 		self.send_ping(io)?;
 		self.had_hello = true;
+		self.ready_time = Some(Instant::now());
 		Ok(())
 	}
 
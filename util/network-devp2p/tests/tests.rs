@@ -24,7 +24,7 @@ use std::time::Duration;
 use parity_bytes::Bytes;
 use parking_lot::Mutex;
 
-use network::{PeerId, NetworkContext, NetworkProtocolHandler, NetworkConfiguration};
+use network::{ClientIdFilter, PeerId, NetworkContext, NetworkProtocolHandler, NetworkConfiguration, ProtocolStats};
 use ethcore_network_devp2p::NetworkService;
 use parity_crypto::publickey::{Generator, Random};
 use ethcore_io::TimerToken;
@@ -95,6 +95,43 @@ impl NetworkProtocolHandler for TestProtocol {
 	}
 }
 
+/// Sends two packets to every peer on connection and counts the packets it receives.
+pub struct ChattyProtocol {
+	pub peers: Mutex<Vec<PeerId>>,
+	pub received: Mutex<usize>,
+}
+
+impl ChattyProtocol {
+	pub fn register(service: &mut NetworkService) -> Arc<ChattyProtocol> {
+		let handler = Arc::new(ChattyProtocol { peers: Mutex::new(Vec::new()), received: Mutex::new(0) });
+		service.register_protocol(handler.clone(), *b"cht", &[(1u8, 2u8)]).expect("Error registering chatty protocol handler");
+		handler
+	}
+
+	pub fn received(&self) -> usize {
+		*self.received.lock()
+	}
+
+	pub fn stats(&self, service: &NetworkService) -> Option<ProtocolStats> {
+		let peer = self.peers.lock()[0];
+		service.with_context_eval(*b"cht", |io| io.protocol_stats(peer)).flatten()
+	}
+}
+
+impl NetworkProtocolHandler for ChattyProtocol {
+	fn read(&self, _io: &dyn NetworkContext, _peer: &PeerId, _packet_id: u8, _data: &[u8]) {
+		*self.received.lock() += 1;
+	}
+
+	fn connected(&self, io: &dyn NetworkContext, peer: &PeerId) {
+		self.peers.lock().push(*peer);
+		io.send(*peer, 0, b"hello".to_vec()).unwrap();
+		io.send(*peer, 1, b"world!".to_vec()).unwrap();
+	}
+
+	fn disconnected(&self, _io: &dyn NetworkContext, _peer: &PeerId) {}
+}
+
 #[test]
 fn net_service() {
 	let service = NetworkService::new(NetworkConfiguration::new_local(), None).expect("Error creating network service");
@@ -142,3 +179,66 @@ fn net_timeout() {
 		thread::sleep(Duration::from_millis(50));
 	}
 }
+
+#[test]
+fn net_protocol_stats() {
+	let mut config1 = NetworkConfiguration::new_local();
+	config1.use_secret = Some(Random.generate().secret().clone());
+	let mut service1 = NetworkService::new(config1, None).unwrap();
+	service1.start().unwrap();
+	let handler1 = ChattyProtocol::register(&mut service1);
+	let mut config2 = NetworkConfiguration::new_local();
+	config2.boot_nodes = vec![ service1.local_url().unwrap() ];
+	let mut service2 = NetworkService::new(config2, None).unwrap();
+	service2.start().unwrap();
+	let handler2 = ChattyProtocol::register(&mut service2);
+	while !(handler1.received() == 2 && handler2.received() == 2) {
+		thread::sleep(Duration::from_millis(50));
+	}
+
+	for (handler, service) in &[(&handler1, &service1), (&handler2, &service2)] {
+		let stats = handler.stats(service).expect("peer is connected");
+		assert_eq!((stats.packets_in, stats.packets_out), (2, 2));
+		assert_eq!((stats.bytes_in, stats.bytes_out), (11, 11));
+		assert!(stats.last_in.is_some() && stats.last_out.is_some());
+		assert_eq!(service.protocol_stats(*b"cht"), stats);
+
+		let peer = handler.peers.lock()[0];
+		let other = service.with_context_eval(*b"oth", |io| io.protocol_stats(peer)).flatten();
+		assert_eq!(other, Some(ProtocolStats::default()));
+	}
+}
+
+#[test]
+fn net_client_id_filter() {
+	let mut config1 = NetworkConfiguration::new_local();
+	config1.use_secret = Some(Random.generate().secret().clone());
+	config1.client_id_filter = ClientIdFilter::new(&["^Good/", "^Spam/"], &["^Spam/"]).unwrap();
+	let mut service1 = NetworkService::new(config1, None).unwrap();
+	service1.start().unwrap();
+	let _handler1 = ChattyProtocol::register(&mut service1);
+
+	let _clients: Vec<_> = ["Spam/v1", "Other/v1", "Good/v1"].iter().map(|client_version| {
+		let mut config = NetworkConfiguration::new_local();
+		config.boot_nodes = vec![ service1.local_url().unwrap() ];
+		config.client_version = client_version.to_string();
+		let mut service = NetworkService::new(config, None).unwrap();
+		service.start().unwrap();
+		let handler = ChattyProtocol::register(&mut service);
+		(service, handler)
+	}).collect();
+
+	loop {
+		let histogram = service1.client_histogram();
+		if histogram.connected.get("Good") == Some(&1)
+			&& histogram.rejected.contains_key("Spam")
+			&& histogram.rejected.contains_key("Other") {
+			break;
+		}
+		thread::sleep(Duration::from_millis(50));
+	}
+
+	let histogram = service1.client_histogram();
+	assert_eq!(histogram.connected.len(), 1);
+	assert!(!histogram.rejected.contains_key("Good"));
+}
@@ -0,0 +1,157 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Open Ethereum.
+
+// Open Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Open Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Open Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Peer filtering by the client id announced in the hello packet.
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+/// Allow and deny lists of client id patterns, compiled once at config time.
+/// A client id matching any deny pattern is rejected. Otherwise it is accepted if the
+/// allow list is empty or any allow pattern matches.
+#[derive(Clone, Debug, Default)]
+pub struct ClientIdFilter {
+	allow: Vec<Regex>,
+	deny: Vec<Regex>,
+}
+
+impl ClientIdFilter {
+	/// Compile the given allow and deny patterns.
+	pub fn new<S: AsRef<str>>(allow: &[S], deny: &[S]) -> Result<Self, regex::Error> {
+		let compile = |patterns: &[S]| patterns.iter()
+			.map(|p| Regex::new(p.as_ref()))
+			.collect::<Result<Vec<_>, _>>();
+
+		Ok(ClientIdFilter {
+			allow: compile(allow)?,
+			deny: compile(deny)?,
+		})
+	}
+
+	/// Whether a peer announcing `client_id` should be kept.
+	pub fn allows(&self, client_id: &str) -> bool {
+		if self.deny.iter().any(|r| r.is_match(client_id)) {
+			return false;
+		}
+		self.allow.is_empty() || self.allow.iter().any(|r| r.is_match(client_id))
+	}
+}
+
+impl PartialEq for ClientIdFilter {
+	fn eq(&self, other: &Self) -> bool {
+		fn patterns(regexes: &[Regex]) -> Vec<&str> {
+			regexes.iter().map(Regex::as_str).collect()
+		}
+		patterns(&self.allow) == patterns(&other.allow) && patterns(&self.deny) == patterns(&other.deny)
+	}
+}
+
+impl Eq for ClientIdFilter {}
+
+/// Aggregated peer counts keyed by client name (the part of the client id before the first `/`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClientHistogram {
+	/// Currently connected peers.
+	pub connected: BTreeMap<String, usize>,
+	/// Peers disconnected by the client id filter since the service started.
+	pub rejected: BTreeMap<String, usize>,
+}
+
+/// Histogram key for a client id.
+pub fn client_name(client_id: &str) -> &str {
+	client_id.split('/').next().unwrap_or(client_id)
+}
+
+/// Maximum number of distinct client names kept in `ClientHistogram::rejected`.
+pub const MAX_REJECTED_CLIENT_NAMES: usize = 32;
+
+/// Histogram key counting rejected peers whose client name did not fit.
+pub const OTHER_CLIENTS: &str = "other";
+
+/// Count a peer rejected by the client id filter. Client ids are chosen by the remote
+/// peer, so once `MAX_REJECTED_CLIENT_NAMES` names are tracked, new ones are counted
+/// under `OTHER_CLIENTS`.
+pub fn note_rejected_client(rejected: &mut BTreeMap<String, usize>, client_id: &str) {
+	let name = client_name(client_id);
+	let key = if rejected.contains_key(name) || rejected.len() < MAX_REJECTED_CLIENT_NAMES - 1 {
+		name
+	} else {
+		OTHER_CLIENTS
+	};
+	*rejected.entry(key.to_owned()).or_insert(0) += 1;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty_filter_allows_everything() {
+		let filter = ClientIdFilter::default();
+		assert!(filter.allows("OpenEthereum/v3.0.0/x86_64-linux-gnu/rustc1.41.0"));
+		assert!(filter.allows(""));
+	}
+
+	#[test]
+	fn deny_wins_over_allow() {
+		let filter = ClientIdFilter::new(&["^Geth/", "^Spam/"], &["^Spam/"]).unwrap();
+		assert!(filter.allows("Geth/v1.9.0"));
+		assert!(!filter.allows("Spam/v1"));
+		assert!(!filter.allows("Other/v1"));
+	}
+
+	#[test]
+	fn deny_only_allows_the_rest() {
+		let filter = ClientIdFilter::new::<&str>(&[], &["(?i)spam"]).unwrap();
+		assert!(filter.allows("Geth/v1.9.0"));
+		assert!(!filter.allows("SPAM/v1"));
+	}
+
+	#[test]
+	fn invalid_pattern_is_an_error() {
+		assert!(ClientIdFilter::new(&["("], &[]).is_err());
+		assert!(ClientIdFilter::new(&[], &["["]).is_err());
+	}
+
+	#[test]
+	fn filters_compare_by_pattern() {
+		let a = ClientIdFilter::new(&["^Geth/"], &[]).unwrap();
+		let b = ClientIdFilter::new(&["^Geth/"], &[]).unwrap();
+		assert_eq!(a, b);
+		assert_ne!(a, ClientIdFilter::default());
+	}
+
+	#[test]
+	fn client_name_is_first_segment() {
+		assert_eq!(client_name("Geth/v1.9.0/linux"), "Geth");
+		assert_eq!(client_name("Parity-network"), "Parity-network");
+	}
+
+	#[test]
+	fn rejected_client_names_are_capped() {
+		let mut rejected = BTreeMap::new();
+		for i in 0..MAX_REJECTED_CLIENT_NAMES * 2 {
+			note_rejected_client(&mut rejected, &format!("Spam{}/v1", i));
+		}
+		note_rejected_client(&mut rejected, "Spam0/v2");
+
+		assert_eq!(rejected.len(), MAX_REJECTED_CLIENT_NAMES);
+		assert_eq!(rejected["Spam0"], 2);
+		assert_eq!(rejected[OTHER_CLIENTS], MAX_REJECTED_CLIENT_NAMES + 1);
+		assert_eq!(rejected.values().sum::<usize>(), MAX_REJECTED_CLIENT_NAMES * 2 + 1);
+	}
+}
@@ -22,6 +22,7 @@ extern crate ethereum_types;
 extern crate rlp;
 extern crate ipnetwork;
 extern crate libc;
+extern crate regex;
 extern crate semver;
 extern crate serde;
 extern crate snap;
@@ -38,9 +39,11 @@ extern crate lazy_static;
 
 pub mod client_version;
 
+mod client_filter;
 mod connection_filter;
 mod error;
 
+pub use client_filter::{ClientIdFilter, ClientHistogram, client_name, note_rejected_client};
 pub use connection_filter::{ConnectionFilter, ConnectionDirection};
 pub use io::TimerToken;
 pub use error::{Error, DisconnectReason};
@@ -51,7 +54,7 @@ use std::collections::HashMap;
 use std::net::{SocketAddr, SocketAddrV4, Ipv4Addr};
 use std::str::{self, FromStr};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use ipnetwork::{IpNetwork, IpNetworkError};
 use crypto::publickey::Secret;
 use ethereum_types::H512;
@@ -122,6 +125,49 @@ pub struct SessionInfo {
 	pub local_address: String,
 }
 
+/// Packet accounting of a single protocol, either for one session or summed over sessions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProtocolStats {
+	/// Number of packets received.
+	pub packets_in: u64,
+	/// Number of packets sent.
+	pub packets_out: u64,
+	/// Received payload bytes, after decompression.
+	pub bytes_in: u64,
+	/// Sent payload bytes, before compression.
+	pub bytes_out: u64,
+	/// Time the last packet was received.
+	pub last_in: Option<Instant>,
+	/// Time the last packet was sent.
+	pub last_out: Option<Instant>,
+}
+
+impl ProtocolStats {
+	/// Account for a received packet with a payload of `size` bytes.
+	pub fn note_in(&mut self, size: usize) {
+		self.packets_in += 1;
+		self.bytes_in += size as u64;
+		self.last_in = Some(Instant::now());
+	}
+
+	/// Account for a sent packet with a payload of `size` bytes.
+	pub fn note_out(&mut self, size: usize) {
+		self.packets_out += 1;
+		self.bytes_out += size as u64;
+		self.last_out = Some(Instant::now());
+	}
+
+	/// Add the counters of `other`, keeping the most recent packet times.
+	pub fn accumulate(&mut self, other: &ProtocolStats) {
+		self.packets_in += other.packets_in;
+		self.packets_out += other.packets_out;
+		self.bytes_in += other.bytes_in;
+		self.bytes_out += other.bytes_out;
+		self.last_in = self.last_in.max(other.last_in);
+		self.last_out = self.last_out.max(other.last_out);
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerCapabilityInfo {
 	pub protocol: ProtocolId,
@@ -220,6 +266,10 @@ pub struct NetworkConfiguration {
 	pub non_reserved_mode: NonReservedPeerMode,
 	/// IP filter
 	pub ip_filter: IpFilter,
+	/// Limits on peers sharing a subnet or node id bucket
+	pub diversity: DiversityLimits,
+	/// Allow/deny lists applied to the client id of connected peers
+	pub client_id_filter: ClientIdFilter,
 	/// Client identifier
 	pub client_version: String,
 }
@@ -251,6 +301,8 @@ impl NetworkConfiguration {
 			ip_filter: IpFilter::default(),
 			reserved_nodes: Vec::new(),
 			non_reserved_mode: NonReservedPeerMode::Accept,
+			diversity: DiversityLimits::default(),
+			client_id_filter: ClientIdFilter::default(),
 			client_version: "Parity-network".into(),
 		}
 	}
@@ -311,6 +363,9 @@ pub trait NetworkContext {
 
 	/// Returns the size the payload shouldn't exceed
 	fn payload_soft_limit(&self) -> usize;
+
+	/// Returns packet statistics of this object's subprotocol on the session with `peer`.
+	fn protocol_stats(&self, peer: PeerId) -> Option<ProtocolStats>;
 }
 
 impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
@@ -365,6 +420,10 @@ impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
 	fn payload_soft_limit(&self) -> usize {
 		(**self).payload_soft_limit()
 	}
+
+	fn protocol_stats(&self, peer: PeerId) -> Option<ProtocolStats> {
+		(**self).protocol_stats(peer)
+	}
 }
 
 /// Network IO protocol handler. This needs to be implemented for each new subprotocol.
@@ -403,6 +462,36 @@ impl NonReservedPeerMode {
 	}
 }
 
+/// Limits on the number of non-reserved peers sharing a network neighbourhood,
+/// making it harder to eclipse the node with peers controlled by a single party.
+/// Peers with non-global addresses are not subject to the subnet limits.
+/// No limits are applied by default.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub struct DiversityLimits {
+	/// Maximum number of peers in the same IPv4 /24 subnet. `None` means no limit.
+	pub max_per_ipv4_subnet: Option<usize>,
+	/// Maximum number of peers in the same IPv6 /48 subnet. `None` means no limit.
+	pub max_per_ipv6_subnet: Option<usize>,
+	/// Maximum number of peers in the same node id bucket. `None` means no limit.
+	pub max_per_id_bucket: Option<usize>,
+}
+
+impl DiversityLimits {
+	/// No limits at all.
+	pub fn unlimited() -> Self {
+		DiversityLimits {
+			max_per_ipv4_subnet: None,
+			max_per_ipv6_subnet: None,
+			max_per_id_bucket: None,
+		}
+	}
+
+	/// Whether no limit is set.
+	pub fn is_unlimited(&self) -> bool {
+		*self == DiversityLimits::unlimited()
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IpFilter {
 	pub predefined: AllowIP,
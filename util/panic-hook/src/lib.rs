@@ -17,8 +17,11 @@
 //! Custom panic hook with bug report link
 
 extern crate backtrace;
+#[macro_use]
+extern crate log;
 
 use std::panic::{self, PanicInfo};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::process;
 use backtrace::Backtrace;
@@ -31,6 +34,32 @@ pub fn set_abort() {
 	});
 }
 
+/// Set the panic hook to write to the log, with the `panic` target, and abort the process when
+/// a panic happens.
+pub fn set_log() {
+	set_with(|msg| {
+		log_panic(msg);
+		process::abort()
+	});
+}
+
+fn log_panic(msg: &str) {
+	error!(target: "panic", "{}", msg);
+}
+
+/// Set the panic hook to record panic messages in the returned buffer, for tests asserting on
+/// them. The panic then unwinds as usual, so it can be caught with `std::panic::catch_unwind`.
+///
+/// The hook is process-wide, tests using it must not run concurrently with other panicking tests.
+pub fn set_capture() -> Arc<Mutex<Vec<String>>> {
+	let captured = Arc::new(Mutex::new(Vec::new()));
+	let buffer = captured.clone();
+	set_with(move |msg| {
+		buffer.lock().unwrap_or_else(|e| e.into_inner()).push(msg.to_owned());
+	});
+	captured
+}
+
 /// Set the panic hook with a closure to be called. The closure receives the panic message.
 ///
 /// Depending on how Parity was compiled, after the closure has been executed, either the process
@@ -52,10 +81,31 @@ This is a bug. Please report it at:
     https://github.com/openethereum/openethereum/issues/new
 ";
 
+/// Derive the crate and module a source file belongs to from its path, e.g.
+/// `light::on_demand` for `ethcore/light/src/on_demand/mod.rs`.
+///
+/// The crate is named after its directory, which may differ from the crate name.
+fn module_of(file: &str) -> Option<String> {
+	let file = file.replace('\\', "/");
+	let src = file.rfind("/src/")?;
+	let krate = file[..src].rsplit('/').next().filter(|krate| !krate.is_empty())?;
+
+	let mut path = vec![krate];
+	path.extend(file[src + 5..].trim_end_matches(".rs").split('/'));
+	match path.last() {
+		Some(&"mod") => { path.pop(); }
+		Some(&"lib") | Some(&"main") if path.len() == 2 => { path.pop(); }
+		_ => {}
+	}
+
+	Some(path.join("::"))
+}
+
 fn gen_panic_msg(info: &PanicInfo) -> String {
 	let location = info.location();
 	let file = location.as_ref().map(|l| l.file()).unwrap_or("<unknown>");
 	let line = location.as_ref().map(|l| l.line()).unwrap_or(0);
+	let module = module_of(file).map(|module| format!("Module: {}\n", module)).unwrap_or_default();
 
 	let msg = match info.payload().downcast_ref::<&'static str>() {
 		Some(s) => *s,
@@ -77,6 +127,81 @@ fn gen_panic_msg(info: &PanicInfo) -> String {
 {backtrace:?}
 
 Thread '{name}' panicked at '{msg}', {file}:{line}
-{about}
-"#, backtrace = backtrace, name = name, msg = msg, file = file, line = line, about = ABOUT_PANIC)
+{module}{about}
+"#, backtrace = backtrace, name = name, msg = msg, file = file, line = line, module = module, about = ABOUT_PANIC)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::RefCell;
+	use std::panic;
+	use std::sync::Mutex;
+
+	use log::{self, Log, Metadata, Record};
+
+	thread_local! {
+		static LOGGED: RefCell<Vec<(String, String)>> = RefCell::new(Vec::new());
+	}
+
+	struct CaptureLogger;
+
+	impl Log for CaptureLogger {
+		fn enabled(&self, _: &Metadata) -> bool { true }
+
+		fn log(&self, record: &Record) {
+			LOGGED.with(|logged| logged.borrow_mut().push((record.target().to_owned(), record.args().to_string())));
+		}
+
+		fn flush(&self) {}
+	}
+
+	static LOGGER: CaptureLogger = CaptureLogger;
+
+	// the panic hook is global, tests replacing it must not overlap.
+	static HOOK: Mutex<()> = Mutex::new(());
+
+	#[test]
+	fn logs_panic_message() {
+		let _hook = HOOK.lock().unwrap();
+		log::set_logger(&LOGGER).unwrap();
+		log::set_max_level(log::LevelFilter::Error);
+
+		// like `set_log`, without aborting.
+		super::set_with(super::log_panic);
+		assert!(panic::catch_unwind(|| panic!("expected panic")).is_err());
+		let _ = panic::take_hook();
+
+		let logged = LOGGED.with(|logged| logged.borrow().clone());
+		assert_eq!(logged.len(), 1);
+		assert_eq!(logged[0].0, "panic");
+		assert!(logged[0].1.contains("panicked at 'expected panic'"));
+	}
+
+	#[test]
+	fn captures_panic_message() {
+		let _hook = HOOK.lock().unwrap();
+
+		let captured = super::set_capture();
+		assert!(panic::catch_unwind(|| panic!("captured panic")).is_err());
+		let _ = panic::take_hook();
+
+		let captured = captured.lock().unwrap();
+		assert_eq!(captured.len(), 1);
+		assert!(captured[0].contains("panicked at 'captured panic'"));
+	}
+	#[test]
+	fn extracts_module_from_location() {
+		use super::module_of;
+
+		assert_eq!(module_of("ethcore/light/src/on_demand/mod.rs"), Some("light::on_demand".into()));
+		assert_eq!(module_of("ethcore/light/src/on_demand/request.rs"), Some("light::on_demand::request".into()));
+		assert_eq!(module_of("util/panic-hook/src/lib.rs"), Some("panic-hook".into()));
+		assert_eq!(module_of("src/main.rs"), None);
+		assert_eq!(
+			module_of("/home/user/.cargo/registry/src/github.com-1ecc6299db9ec823/backtrace-0.3.43/src/capture.rs"),
+			Some("backtrace-0.3.43::capture".into())
+		);
+		assert_eq!(module_of("C:\\openethereum\\parity\\src\\main.rs"), Some("parity".into()));
+		assert_eq!(module_of("<unknown>"), None);
+	}
 }
@@ -13,7 +13,7 @@ extern crate rlp;
 
 mod common;
 
-use std::cmp;
+use std::{cmp, error, fmt};
 use std::collections::HashMap;
 use elastic_array::ElasticArray1024;
 use rlp::{Rlp, RlpStream};
@@ -49,6 +49,81 @@ pub fn compress(c: &[u8], swapper: &dyn Compressor) -> ElasticArray1024<u8> {
 	}
 }
 
+/// Error returned when compressing RLP which is not in canonical form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonCanonical {
+	/// Offset of the offending item in the input.
+	pub offset: usize,
+}
+
+impl fmt::Display for NonCanonical {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Non-canonical RLP item at offset {}", self.offset)
+	}
+}
+
+impl error::Error for NonCanonical {}
+
+/// Compress rlp, validating first that it's a single item in canonical form.
+///
+/// Unlike `compress`, which re-emits whatever it's given, this rejects input a strict
+/// decoder would reject: overlong or zero-prefixed lengths, single bytes below `0x80`
+/// encoded as strings, truncated items and trailing bytes.
+pub fn compress_canonical(c: &[u8], swapper: &dyn Compressor) -> Result<ElasticArray1024<u8>, NonCanonical> {
+	let len = canonical_item_len(c, 0)?;
+	if len != c.len() {
+		return Err(NonCanonical { offset: len });
+	}
+	Ok(compress(c, swapper))
+}
+
+/// Validates the item at the start of `bytes`, found at `offset` in the input, returning its length.
+fn canonical_item_len(bytes: &[u8], offset: usize) -> Result<usize, NonCanonical> {
+	let err = NonCanonical { offset };
+	let (prefix, rest) = match bytes.split_first() {
+		Some((&prefix, rest)) => (prefix, rest),
+		None => return Err(err),
+	};
+
+	let (header_len, payload_len, is_list) = match prefix {
+		0x00..=0x7f => return Ok(1),
+		0x80..=0xb7 => (1, (prefix - 0x80) as usize, false),
+		0xb8..=0xbf => (1 + (prefix - 0xb7) as usize, long_len(rest, (prefix - 0xb7) as usize).ok_or(err)?, false),
+		0xc0..=0xf7 => (1, (prefix - 0xc0) as usize, true),
+		0xf8..=0xff => (1 + (prefix - 0xf7) as usize, long_len(rest, (prefix - 0xf7) as usize).ok_or(err)?, true),
+	};
+
+	let item_len = header_len.checked_add(payload_len).ok_or(err)?;
+	if item_len > bytes.len() {
+		return Err(err);
+	}
+
+	let payload = &bytes[header_len..item_len];
+	if is_list {
+		let mut pos = 0;
+		while pos < payload.len() {
+			pos += canonical_item_len(&payload[pos..], offset + header_len + pos)?;
+		}
+	} else if payload_len == 1 && payload[0] < 0x80 {
+		return Err(err);
+	}
+
+	Ok(item_len)
+}
+
+/// Reads a length of a long item, which has to be minimal and above 55.
+fn long_len(bytes: &[u8], len_of_len: usize) -> Option<usize> {
+	if len_of_len > cmp::min(bytes.len(), ::std::mem::size_of::<usize>()) || bytes[0] == 0 {
+		return None;
+	}
+
+	let len = bytes[..len_of_len].iter().fold(0usize, |len, &b| (len << 8) | b as usize);
+	if len <= 55 {
+		return None;
+	}
+	Some(len)
+}
+
 /// Call this function to decompress rlp.
 pub fn decompress(c: &[u8], swapper: &dyn Decompressor) -> ElasticArray1024<u8> {
 	let rlp = Rlp::new(c);
@@ -96,6 +171,56 @@ impl<'a> Swapper<'a> {
 	}
 }
 
+/// Inconsistency found in a `Swapper` dictionary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwapperError {
+	/// The compressed form of `rlp` decompresses to a different rlp.
+	Asymmetric {
+		/// Rlp being swapped.
+		rlp: Vec<u8>,
+		/// Its compressed form.
+		compressed: Vec<u8>,
+	},
+	/// A compressed form is itself swappable, so it can't be told apart from the rlp it stands for.
+	Collision(Vec<u8>),
+}
+
+impl fmt::Display for SwapperError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			SwapperError::Asymmetric { ref rlp, ref compressed } =>
+				write!(f, "Compressed form {:?} of {:?} doesn't decompress back to it", compressed, rlp),
+			SwapperError::Collision(ref compressed) =>
+				write!(f, "Compressed form {:?} is also a swappable rlp", compressed),
+		}
+	}
+}
+
+impl error::Error for SwapperError {}
+
+impl<'a> Swapper<'a> {
+	/// Check that the dictionary is consistent: every compressed form decompresses back to
+	/// the rlp it was swapped for and vice versa, and no compressed form is swappable itself.
+	pub fn self_check(&self) -> Result<(), SwapperError> {
+		for (&rlp, &compressed) in &self.rlp_to_compressed {
+			if self.compressed_to_rlp.get(compressed) != Some(&rlp) {
+				return Err(SwapperError::Asymmetric { rlp: rlp.to_vec(), compressed: compressed.to_vec() });
+			}
+			if self.rlp_to_compressed.contains_key(compressed) {
+				return Err(SwapperError::Collision(compressed.to_vec()));
+			}
+		}
+
+		for (&compressed, &rlp) in &self.compressed_to_rlp {
+			if self.rlp_to_compressed.get(rlp) != Some(&compressed) {
+				return Err(SwapperError::Asymmetric { rlp: rlp.to_vec(), compressed: compressed.to_vec() });
+			}
+		}
+
+		Ok(())
+	}
+}
+
 impl<'a> Decompressor for Swapper<'a> {
 	fn decompressed(&self, compressed: &[u8]) -> Option<&[u8]> {
 		self.compressed_to_rlp.get(compressed).cloned()
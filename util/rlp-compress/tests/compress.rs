@@ -16,7 +16,7 @@
 
 extern crate rlp_compress;
 
-use rlp_compress::{compress, decompress, Swapper, snapshot_swapper, blocks_swapper, Compressor, Decompressor};
+use rlp_compress::{compress, compress_canonical, decompress, Swapper, snapshot_swapper, blocks_swapper, Compressor, Decompressor, NonCanonical, SwapperError};
 
 #[test]
 fn invalid_rlp_swapper() {
@@ -28,6 +28,24 @@ fn invalid_rlp_swapper() {
 	assert_eq!(Some(to_swap[1]), swapper.decompressed(invalid_rlp[1]));
 }
 
+#[test]
+fn swapper_self_check() {
+	assert_eq!(snapshot_swapper().self_check(), Ok(()));
+	assert_eq!(blocks_swapper().self_check(), Ok(()));
+
+	let to_swap: &[&[u8]] = &[&[0x83, b'c', b'a', b't'], &[0x83, b'd', b'o', b'g']];
+
+	// both rlps share a compressed form, which decompresses to the last one only.
+	let shared: &[&[u8]] = &[&[0x81, 0x00], &[0x81, 0x00]];
+	assert_eq!(Swapper::new(to_swap, shared).self_check(), Err(SwapperError::Asymmetric {
+		rlp: to_swap[0].to_vec(),
+		compressed: shared[0].to_vec(),
+	}));
+
+	let swappable: &[&[u8]] = &[&[0x83, b'd', b'o', b'g'], &[0x81, 0x00]];
+	assert_eq!(Swapper::new(to_swap, swappable).self_check(), Err(SwapperError::Collision(to_swap[1].to_vec())));
+}
+
 #[test]
 fn simple_compression() {
 	let basic_account_rlp = vec![248, 68, 4, 2, 160, 86, 232, 31, 23, 27, 204, 85, 166, 255, 131, 69, 230, 146, 192, 248, 110, 91, 72, 224, 27, 153, 108, 173, 192, 1, 98, 47, 181, 227, 99, 180, 33, 160, 197, 210, 70, 1, 134, 247, 35, 60, 146, 126, 125, 178, 220, 199, 3, 192, 229, 0, 182, 83, 202, 130, 39, 59, 123, 250, 216, 4, 93, 133, 164, 112];
@@ -64,3 +82,42 @@ fn large_block() {
 	let decompressed = decompress(&compressed, blocks_swapper());
 	assert_eq!(decompressed.into_vec(), block);
 }
+
+#[test]
+fn canonical_compression() {
+	let basic_account_rlp = vec![248, 68, 4, 2, 160, 86, 232, 31, 23, 27, 204, 85, 166, 255, 131, 69, 230, 146, 192, 248, 110, 91, 72, 224, 27, 153, 108, 173, 192, 1, 98, 47, 181, 227, 99, 180, 33, 160, 197, 210, 70, 1, 134, 247, 35, 60, 146, 126, 125, 178, 220, 199, 3, 192, 229, 0, 182, 83, 202, 130, 39, 59, 123, 250, 216, 4, 93, 133, 164, 112];
+	let compressed = compress_canonical(&basic_account_rlp, snapshot_swapper()).unwrap();
+	assert_eq!(compressed.to_vec(), compress(&basic_account_rlp, snapshot_swapper()).to_vec());
+
+	// the nonce `4` encoded as a one byte string.
+	let mut non_canonical = basic_account_rlp.clone();
+	non_canonical[2] = 0x81;
+	non_canonical.insert(3, 4);
+	non_canonical[1] += 1;
+	assert_eq!(compress_canonical(&non_canonical, snapshot_swapper()).err(), Some(NonCanonical { offset: 2 }));
+}
+
+#[test]
+fn non_canonical_rlp() {
+	let invalid: &[(&[u8], usize)] = &[
+		// empty input
+		(&[], 0),
+		// short string with a long length
+		(&[0xb8, 0x02, b'o', b'k'], 0),
+		// length with a leading zero
+		(&[0xb9, 0x00, 0x38], 0),
+		// truncated list item
+		(&[0xc2, 0x82, b'o'], 1),
+		// trailing bytes
+		(&[0x82, b'o', b'k', 0x00], 3),
+	];
+
+	for &(rlp, offset) in invalid {
+		assert_eq!(compress_canonical(rlp, blocks_swapper()).err(), Some(NonCanonical { offset }), "{:?}", rlp);
+	}
+
+	let valid: &[&[u8]] = &[&[0x00], &[0x81, 0x80], &[0xc0], &[0xc2, 0x80, 0x7f]];
+	for rlp in valid {
+		assert!(compress_canonical(rlp, blocks_swapper()).is_ok(), "{:?}", rlp);
+	}
+}
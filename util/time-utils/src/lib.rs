@@ -23,6 +23,14 @@ pub trait CheckedSystemTime {
 	fn checked_add(self, _d: Duration) -> Option<SystemTime>;
 	/// Returns `Some<SystemTime>` when the result is successful and `None` when it is not
 	fn checked_sub(self, _d: Duration) -> Option<SystemTime>;
+	/// Same as `checked_add` with the duration given in seconds
+	fn checked_add_secs(self, secs: u64) -> Option<SystemTime> where Self: Sized {
+		self.checked_add(Duration::from_secs(secs))
+	}
+	/// Same as `checked_sub` with the duration given in seconds
+	fn checked_sub_secs(self, secs: u64) -> Option<SystemTime> where Self: Sized {
+		self.checked_sub(Duration::from_secs(secs))
+	}
 }
 
 impl CheckedSystemTime for SystemTime {
@@ -63,4 +71,20 @@ mod tests {
 		assert!(CheckedSystemTime::checked_sub(UNIX_EPOCH, Duration::from_secs(120)).is_none());
 		assert!(CheckedSystemTime::checked_sub(SystemTime::now(), Duration::from_secs(1000)).is_some());
 	}
+
+	#[test]
+	fn checked_secs() {
+		use super::CheckedSystemTime;
+		use std::time::{Duration, UNIX_EPOCH};
+
+		assert_eq!(CheckedSystemTime::checked_add_secs(UNIX_EPOCH, 10), Some(UNIX_EPOCH + Duration::from_secs(10)));
+		assert!(CheckedSystemTime::checked_add_secs(UNIX_EPOCH, i32::max_value() as u64).is_some());
+		assert!(CheckedSystemTime::checked_add_secs(UNIX_EPOCH, i32::max_value() as u64 + 1).is_none());
+		assert!(CheckedSystemTime::checked_add_secs(UNIX_EPOCH, u64::max_value()).is_none());
+
+		let time = UNIX_EPOCH + Duration::from_secs(100);
+		assert_eq!(CheckedSystemTime::checked_sub_secs(time, 40), Some(UNIX_EPOCH + Duration::from_secs(60)));
+		assert!(CheckedSystemTime::checked_sub_secs(time, 101).is_none());
+		assert!(CheckedSystemTime::checked_sub_secs(time, u64::max_value()).is_none());
+	}
 }